@@ -7,6 +7,7 @@ use percent_encoding::utf8_percent_encode;
 use url::Url;
 
 use crate::addon_transport::http_transport::legacy::AddonLegacyTransport;
+use crate::addon_transport::http_transport::resource_cache::fetch_resource_cached;
 use crate::addon_transport::AddonTransport;
 use crate::constants::{
     ADDON_LEGACY_PATH, ADDON_MANIFEST_PATH, CINEMETA_URL, URI_COMPONENT_ENCODE_SET,
@@ -29,12 +30,15 @@ impl<E: Env> AddonHTTPTransport<E> {
     }
 }
 
-impl<E: Env> AddonTransport for AddonHTTPTransport<E> {
+impl<E: Env + 'static> AddonTransport for AddonHTTPTransport<E> {
     /// Request a resource from the addon.
     ///
     /// This will encode all components with [`utf8_percent_encode(.., URI_COMPONENT_ENCODE_SET)`](utf8_percent_encode)
     /// and the [`ResourcePath.extra`](ResourcePath::extra) properties if they are not empty
     /// with [`query_params_encode`].
+    ///
+    /// The response is cached in the storage following the addon's cache hints,
+    /// see [`ResourceResponseCache`](crate::types::addon::ResourceResponseCache).
     fn resource(&self, path: &ResourcePath) -> TryEnvFuture<ResourceResponse> {
         if self.transport_url.path().ends_with(ADDON_LEGACY_PATH) {
             return AddonLegacyTransport::<E>::new(&self.transport_url).resource(path);
@@ -93,8 +97,7 @@ impl<E: Env> AddonTransport for AddonHTTPTransport<E> {
             _ => {}
         }
 
-        let request = Request::get(url).body(()).expect("request builder failed");
-        E::fetch(request)
+        fetch_resource_cached::<E>(url)
     }
     fn manifest(&self) -> TryEnvFuture<Manifest> {
         if self.transport_url.path().ends_with(ADDON_LEGACY_PATH) {
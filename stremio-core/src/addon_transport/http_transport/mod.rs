@@ -1,4 +1,6 @@
 mod legacy;
 
+mod resource_cache;
+
 mod http_transport;
 pub use http_transport::*;
@@ -0,0 +1,124 @@
+use futures::{FutureExt, TryFutureExt};
+use http::Request;
+use once_cell::sync::Lazy;
+use sha2::{Digest, Sha256};
+
+use crate::constants::{RESOURCE_CACHE_INDEX_STORAGE_KEY, RESOURCE_CACHE_STORAGE_KEY};
+use crate::runtime::{Env, EnvError, EnvFutureExt, TryEnvFuture};
+use crate::types::addon::{
+    ResourceCacheEntry, ResourceCacheFreshness, ResourceCacheIndex, ResourceResponse,
+    ResourceResponseCache,
+};
+
+/// Held while the index is updated, as the requests update it concurrently.
+static RESOURCE_CACHE_INDEX_LOCK: Lazy<futures::lock::Mutex<()>> = Lazy::new(Default::default);
+
+/// Fetches the resource at the given url honoring the addon's cache hints.
+///
+/// - [`ResourceCacheFreshness::Fresh`] entries are served without a request.
+/// - [`ResourceCacheFreshness::StaleRevalidate`] entries are served and
+///   revalidated in the background, the new response is served on the next request.
+/// - [`ResourceCacheFreshness::StaleError`] entries are served only if the request fails.
+///
+/// Every response with cache hints is persisted in the storage under a key derived from the url.
+/// The persisted entries are bounded by the [`ResourceCacheIndex`].
+pub fn fetch_resource_cached<E: Env + 'static>(url: String) -> TryEnvFuture<ResourceResponse> {
+    let storage_key = storage_key(&url);
+    async move {
+        // an unreadable entry is treated the same as a missing one
+        let entry = E::get_storage::<ResourceCacheEntry>(&storage_key)
+            .await
+            .ok()
+            .flatten();
+        let freshness = entry
+            .as_ref()
+            .map(|entry| entry.freshness(E::now()))
+            .unwrap_or(ResourceCacheFreshness::Expired);
+        match (entry, freshness) {
+            (Some(entry), ResourceCacheFreshness::Fresh) => Ok(entry.response.resource),
+            (Some(entry), ResourceCacheFreshness::StaleRevalidate) => {
+                E::exec_concurrent(fetch_resource::<E>(url, storage_key, true).map(|_| ()));
+                Ok(entry.response.resource)
+            }
+            (entry, freshness) => {
+                match fetch_resource::<E>(url, storage_key, entry.is_some()).await {
+                    Ok(resource) => Ok(resource),
+                    Err(error) => match entry {
+                        Some(entry) if freshness == ResourceCacheFreshness::StaleError => {
+                            tracing::debug!(
+                                ?error,
+                                "Addon request failed, falling back to the stale cache entry"
+                            );
+                            Ok(entry.response.resource)
+                        }
+                        _ => Err(error),
+                    },
+                }
+            }
+        }
+    }
+    .boxed_env()
+}
+
+/// Fetches the resource and updates the storage cache entry with the response.
+///
+/// Failing to update the storage does not fail the request.
+fn fetch_resource<E: Env + 'static>(
+    url: String,
+    storage_key: String,
+    has_entry: bool,
+) -> TryEnvFuture<ResourceResponse> {
+    let request = Request::get(url).body(()).expect("request builder failed");
+    E::fetch::<_, ResourceResponseCache>(request)
+        .and_then(move |response| async move {
+            if response.is_cacheable() {
+                let entry = ResourceCacheEntry::new(response, E::now());
+                store_entry::<E>(&storage_key, Some(&entry)).await;
+                Ok(entry.response.resource)
+            } else {
+                if has_entry {
+                    store_entry::<E>(&storage_key, None).await;
+                }
+                Ok(response.resource)
+            }
+        })
+        .boxed_env()
+}
+
+async fn store_entry<E: Env>(storage_key: &str, entry: Option<&ResourceCacheEntry>) {
+    if let Err(error) = E::set_storage(storage_key, entry).await {
+        tracing::warn!(?error, "Failed to update the addon resource cache entry");
+    }
+    if let Err(error) = update_index::<E>(storage_key, entry).await {
+        tracing::warn!(?error, "Failed to update the addon resource cache index");
+    }
+}
+
+/// Records the entry in the index and removes the evicted entries from the storage.
+async fn update_index<E: Env>(
+    storage_key: &str,
+    entry: Option<&ResourceCacheEntry>,
+) -> Result<(), EnvError> {
+    let _lock = RESOURCE_CACHE_INDEX_LOCK.lock().await;
+    let mut index = E::get_storage::<ResourceCacheIndex>(RESOURCE_CACHE_INDEX_STORAGE_KEY)
+        .await?
+        .unwrap_or_default();
+    let evicted = match entry {
+        Some(entry) => index.insert(storage_key.to_owned(), entry.cached_at),
+        None => {
+            index.remove(storage_key);
+            vec![]
+        }
+    };
+    for storage_key in evicted {
+        E::set_storage::<()>(&storage_key, None).await?;
+    }
+    E::set_storage(RESOURCE_CACHE_INDEX_STORAGE_KEY, Some(&index)).await
+}
+
+fn storage_key(url: &str) -> String {
+    format!(
+        "{RESOURCE_CACHE_STORAGE_KEY}_{}",
+        hex::encode(Sha256::digest(url.as_bytes()))
+    )
+}
@@ -0,0 +1,139 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use thiserror::Error;
+
+use crate::addon_transport::LocalAddon;
+use crate::constants::{
+    ADDON_CATALOG_RESOURCE_NAME, CATALOG_RESOURCE_NAME, META_RESOURCE_NAME, STREAM_RESOURCE_NAME,
+    SUBTITLES_RESOURCE_NAME,
+};
+use crate::runtime::TryEnvFuture;
+use crate::types::addon::{Manifest, ResourcePath, ResourceResponse};
+
+/// Handles the [`ResourcePath`] requests of a single resource of a [`LocalAddon`].
+pub type ResourceHandler =
+    Arc<dyn Fn(&ResourcePath) -> TryEnvFuture<ResourceResponse> + Send + Sync>;
+
+#[derive(Error, Clone, PartialEq, Eq, Debug)]
+pub enum AddonBuilderError {
+    #[error("Manifest declares the `{0}` resource but no handler is defined for it")]
+    MissingHandler(String),
+    #[error("Handler is defined for the `{0}` resource but the manifest does not declare it")]
+    UndeclaredResource(String),
+}
+
+/// Builder of a [`LocalAddon`], the Rust equivalent of the `addonBuilder`
+/// of the [stremio-addon-sdk](https://github.com/Stremio/stremio-addon-sdk).
+///
+/// # Examples
+///
+/// ```
+/// use futures::future;
+/// use semver::Version;
+///
+/// use stremio_core::addon_transport::AddonBuilder;
+/// use stremio_core::runtime::EnvFutureExt;
+/// use stremio_core::types::addon::{Manifest, ResourceResponse};
+///
+/// let addon = AddonBuilder::new(Manifest {
+///     id: "org.example.streams".to_owned(),
+///     version: Version::new(1, 0, 0),
+///     name: "Example".to_owned(),
+///     contact_email: None,
+///     description: None,
+///     logo: None,
+///     background: None,
+///     types: vec!["movie".to_owned()],
+///     resources: vec!["stream".into()],
+///     id_prefixes: Some(vec!["tt".to_owned()]),
+///     catalogs: vec![],
+///     addon_catalogs: vec![],
+///     behavior_hints: Default::default(),
+/// })
+/// .define_stream_handler(|_path| future::ok(ResourceResponse::Streams { streams: vec![] }).boxed_env())
+/// .build()
+/// .expect("Every declared resource has a handler");
+///
+/// assert_eq!(addon.manifest().id, "org.example.streams");
+/// ```
+pub struct AddonBuilder {
+    manifest: Manifest,
+    handlers: HashMap<String, ResourceHandler>,
+}
+
+impl AddonBuilder {
+    pub fn new(manifest: Manifest) -> Self {
+        AddonBuilder {
+            manifest,
+            handlers: HashMap::new(),
+        }
+    }
+    /// Defines the handler for the given resource,
+    /// replacing any previously defined handler for it.
+    pub fn define_resource_handler<F>(mut self, resource: &str, handler: F) -> Self
+    where
+        F: Fn(&ResourcePath) -> TryEnvFuture<ResourceResponse> + Send + Sync + 'static,
+    {
+        self.handlers.insert(resource.to_owned(), Arc::new(handler));
+        self
+    }
+    pub fn define_catalog_handler<F>(self, handler: F) -> Self
+    where
+        F: Fn(&ResourcePath) -> TryEnvFuture<ResourceResponse> + Send + Sync + 'static,
+    {
+        self.define_resource_handler(CATALOG_RESOURCE_NAME, handler)
+    }
+    pub fn define_meta_handler<F>(self, handler: F) -> Self
+    where
+        F: Fn(&ResourcePath) -> TryEnvFuture<ResourceResponse> + Send + Sync + 'static,
+    {
+        self.define_resource_handler(META_RESOURCE_NAME, handler)
+    }
+    pub fn define_stream_handler<F>(self, handler: F) -> Self
+    where
+        F: Fn(&ResourcePath) -> TryEnvFuture<ResourceResponse> + Send + Sync + 'static,
+    {
+        self.define_resource_handler(STREAM_RESOURCE_NAME, handler)
+    }
+    pub fn define_subtitles_handler<F>(self, handler: F) -> Self
+    where
+        F: Fn(&ResourcePath) -> TryEnvFuture<ResourceResponse> + Send + Sync + 'static,
+    {
+        self.define_resource_handler(SUBTITLES_RESOURCE_NAME, handler)
+    }
+    /// Builds the addon, failing if the declared resources in the manifest
+    /// do not match the defined handlers.
+    ///
+    /// The `catalog` and `addon_catalog` resources are declared
+    /// either in the manifest resources or with a non-empty
+    /// [`Manifest::catalogs`] and [`Manifest::addon_catalogs`] respectively.
+    pub fn build(self) -> Result<LocalAddon, AddonBuilderError> {
+        let mut declared = self
+            .manifest
+            .resources
+            .iter()
+            .map(|resource| resource.name().to_owned())
+            .collect::<Vec<_>>();
+        if !self.manifest.catalogs.is_empty() {
+            declared.push(CATALOG_RESOURCE_NAME.to_owned());
+        }
+        if !self.manifest.addon_catalogs.is_empty() {
+            declared.push(ADDON_CATALOG_RESOURCE_NAME.to_owned());
+        }
+        if let Some(resource) = declared
+            .iter()
+            .find(|resource| !self.handlers.contains_key(*resource))
+        {
+            return Err(AddonBuilderError::MissingHandler(resource.to_owned()));
+        }
+        if let Some(resource) = self
+            .handlers
+            .keys()
+            .find(|resource| !declared.contains(resource))
+        {
+            return Err(AddonBuilderError::UndeclaredResource(resource.to_owned()));
+        }
+        Ok(LocalAddon::new(self.manifest, self.handlers))
+    }
+}
@@ -0,0 +1,82 @@
+use percent_encoding::percent_decode_str;
+use url::form_urlencoded;
+
+use crate::constants::ADDON_MANIFEST_PATH;
+use crate::types::addon::{ExtraValue, ResourcePath};
+
+/// A route of the standard addon HTTP interface.
+///
+/// The url paths look as follows:
+/// - `/manifest.json`
+/// - Without extra values: `/{resource}/{type}/{id}.json`
+/// - With extra values: `/{resource}/{type}/{id}/{extra}.json`
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum AddonRoute {
+    Manifest,
+    Resource(ResourcePath),
+}
+
+impl AddonRoute {
+    /// Parses the route from the path of a request url, the query string is ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stremio_core::addon_transport::AddonRoute;
+    /// use stremio_core::types::addon::{ExtraValue, ResourcePath};
+    ///
+    /// assert_eq!(AddonRoute::parse("/manifest.json"), Some(AddonRoute::Manifest));
+    /// assert_eq!(
+    ///     AddonRoute::parse("/catalog/movie/top/genre=Sci-Fi&skip=100.json"),
+    ///     Some(AddonRoute::Resource(ResourcePath::with_extra(
+    ///         "catalog",
+    ///         "movie",
+    ///         "top",
+    ///         &[
+    ///             ExtraValue { name: "genre".to_owned(), value: "Sci-Fi".to_owned() },
+    ///             ExtraValue { name: "skip".to_owned(), value: "100".to_owned() },
+    ///         ],
+    ///     ))),
+    /// );
+    /// assert_eq!(AddonRoute::parse("/catalog/movie.json"), None);
+    /// ```
+    pub fn parse(path: &str) -> Option<Self> {
+        let path = path.split(['?', '#']).next().unwrap_or_default();
+        if path == ADDON_MANIFEST_PATH {
+            return Some(AddonRoute::Manifest);
+        }
+        let segments = path
+            .strip_prefix('/')?
+            .strip_suffix(".json")?
+            .split('/')
+            .collect::<Vec<_>>();
+        let (resource, r#type, id, extra) = match segments.as_slice() {
+            [resource, r#type, id] => (resource, r#type, id, None),
+            [resource, r#type, id, extra] => (resource, r#type, id, Some(extra)),
+            _ => return None,
+        };
+        let decode = |segment: &str| {
+            percent_decode_str(segment)
+                .decode_utf8()
+                .ok()
+                .map(|segment| segment.into_owned())
+                .filter(|segment| !segment.is_empty())
+        };
+        let extra = extra
+            .map(|extra| {
+                form_urlencoded::parse(extra.as_bytes())
+                    .map(|(name, value)| ExtraValue {
+                        name: name.into_owned(),
+                        value: value.into_owned(),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        Some(AddonRoute::Resource(ResourcePath {
+            resource: decode(resource)?,
+            r#type: decode(r#type)?,
+            id: decode(id)?,
+            extra,
+        }))
+    }
+}
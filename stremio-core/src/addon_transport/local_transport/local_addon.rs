@@ -0,0 +1,76 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use futures::{future, TryFutureExt};
+
+use crate::addon_transport::{AddonRoute, AddonTransport, ResourceHandler};
+use crate::runtime::{EnvError, EnvFutureExt, TryEnvFuture};
+use crate::types::addon::{Manifest, ResourcePath, ResourceResponse};
+
+/// An addon running in-process, built with the [`AddonBuilder`](crate::addon_transport::AddonBuilder).
+///
+/// It answers the [`ResourcePath`] requests directly as an [`AddonTransport`],
+/// register it in the [`AddonTransportRegistry`](crate::addon_transport::AddonTransportRegistry)
+/// to use it in the app.
+/// The same addon can be served over HTTP using [`LocalAddon::serve`].
+#[derive(Clone)]
+pub struct LocalAddon {
+    manifest: Arc<Manifest>,
+    handlers: Arc<HashMap<String, ResourceHandler>>,
+}
+
+impl LocalAddon {
+    pub(crate) fn new(manifest: Manifest, handlers: HashMap<String, ResourceHandler>) -> Self {
+        LocalAddon {
+            manifest: Arc::new(manifest),
+            handlers: Arc::new(handlers),
+        }
+    }
+    pub fn manifest(&self) -> &Manifest {
+        &self.manifest
+    }
+    /// Handles a request to the standard addon HTTP routes, see [`AddonRoute`].
+    ///
+    /// Returns `None` if the route is unknown or the resource is not supported by the addon,
+    /// otherwise the JSON body of the response.
+    pub fn serve(&self, path: &str) -> Option<TryEnvFuture<serde_json::Value>> {
+        match AddonRoute::parse(path)? {
+            AddonRoute::Manifest => Some(
+                future::ready(serde_json::to_value(self.manifest()).map_err(EnvError::from))
+                    .boxed_env(),
+            ),
+            AddonRoute::Resource(path) => {
+                let handler = self.handler(&path)?;
+                Some(
+                    handler(&path)
+                        .and_then(|response| async move {
+                            serde_json::to_value(response).map_err(EnvError::from)
+                        })
+                        .boxed_env(),
+                )
+            }
+        }
+    }
+    fn handler(&self, path: &ResourcePath) -> Option<&ResourceHandler> {
+        if !self.manifest.is_resource_supported(path) {
+            return None;
+        }
+        self.handlers.get(&path.resource)
+    }
+}
+
+impl AddonTransport for LocalAddon {
+    fn resource(&self, path: &ResourcePath) -> TryEnvFuture<ResourceResponse> {
+        match self.handler(path) {
+            Some(handler) => handler(path),
+            None => future::err(EnvError::AddonTransport(format!(
+                "Resource not supported by {}: {}/{}/{}",
+                self.manifest.id, path.resource, path.r#type, path.id
+            )))
+            .boxed_env(),
+        }
+    }
+    fn manifest(&self) -> TryEnvFuture<Manifest> {
+        future::ok(self.manifest().to_owned()).boxed_env()
+    }
+}
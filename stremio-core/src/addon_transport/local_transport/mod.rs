@@ -0,0 +1,8 @@
+mod addon_builder;
+pub use addon_builder::*;
+
+mod addon_route;
+pub use addon_route::*;
+
+mod local_addon;
+pub use local_addon::*;
@@ -4,5 +4,14 @@ pub use http_transport::*;
 mod addon_transport;
 pub use addon_transport::*;
 
+mod local_transport;
+pub use local_transport::*;
+
 mod unsupported_transport;
 pub use unsupported_transport::*;
+
+mod transport_registry;
+pub use transport_registry::*;
+
+mod request_policy;
+pub use request_policy::*;
@@ -0,0 +1,76 @@
+use futures::future::{self, Either};
+use futures::FutureExt;
+use url::Url;
+
+use crate::constants::{
+    ADDON_REQUEST_TIMEOUT, ADDON_STREAM_REQUEST_TIMEOUT, CATALOG_RESOURCE_NAME,
+    STREAM_RESOURCE_NAME,
+};
+use crate::runtime::{Env, EnvError, EnvFutureExt, TryEnvFuture};
+use crate::types::addon::{ResourcePath, ResourceResponse};
+
+/// The limits of a resource request to an addon, see [`Env::addon_request_policy`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct AddonRequestPolicy {
+    /// The request fails with [`EnvError::Timeout`] if the addon does not respond in time.
+    pub timeout: Option<chrono::Duration>,
+    /// Another identical request is sent if the addon does not respond in time
+    /// and the first of the two responses is used.
+    ///
+    /// Applied only to the catalog requests as they are idempotent.
+    pub hedge_after: Option<chrono::Duration>,
+}
+
+impl AddonRequestPolicy {
+    /// The default policy for the resource, without hedging.
+    pub fn new(path: &ResourcePath) -> Self {
+        let timeout = match path.resource.as_str() {
+            STREAM_RESOURCE_NAME => ADDON_STREAM_REQUEST_TIMEOUT,
+            _ => ADDON_REQUEST_TIMEOUT,
+        };
+        Self {
+            timeout: Some(timeout),
+            hedge_after: None,
+        }
+    }
+}
+
+/// Requests the resource from the addon following the [`AddonRequestPolicy`] of the [`Env`].
+pub fn fetch_resource_with_policy<E: Env + 'static>(
+    transport_url: &Url,
+    path: &ResourcePath,
+) -> TryEnvFuture<ResourceResponse> {
+    let policy = E::addon_request_policy(path);
+    let request = E::addon_transport(transport_url).resource(path);
+    let request = match policy.hedge_after {
+        Some(hedge_after) if path.resource == CATALOG_RESOURCE_NAME => {
+            let transport_url = transport_url.to_owned();
+            let path = path.to_owned();
+            let hedged_request = E::sleep(hedge_after)
+                .then(move |result| match result {
+                    Ok(()) => E::addon_transport(&transport_url).resource(&path),
+                    // without timers the request is not hedged
+                    Err(_) => future::pending().boxed_env(),
+                })
+                .boxed_env();
+            future::select(request, hedged_request)
+                .map(|either| either.factor_first().0)
+                .boxed_env()
+        }
+        _ => request,
+    };
+    match policy.timeout {
+        Some(timeout) => future::select(request, E::sleep(timeout))
+            .then(move |either| match either {
+                Either::Left((result, _)) => future::ready(result).boxed_env(),
+                Either::Right((Ok(()), _)) => {
+                    future::err(EnvError::Timeout(timeout.num_milliseconds().max(0) as u64))
+                        .boxed_env()
+                }
+                // without timers the request never times out
+                Either::Right((Err(_), request)) => request,
+            })
+            .boxed_env(),
+        None => request,
+    }
+}
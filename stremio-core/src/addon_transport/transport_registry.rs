@@ -0,0 +1,65 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use url::Url;
+
+use crate::addon_transport::{AddonHTTPTransport, AddonTransport, UnsupportedTransport};
+use crate::runtime::Env;
+
+/// Creates the [`AddonTransport`] for a given transport url.
+pub type AddonTransportFactory = Arc<dyn Fn(&Url) -> Box<dyn AddonTransport> + Send + Sync>;
+
+/// The [`AddonTransport`]s supported by an [`Env`], registered by URL scheme.
+///
+/// Any transport url with a scheme which is not registered
+/// will be handled by the [`UnsupportedTransport`].
+///
+/// # Examples
+///
+/// ```
+/// use stremio_core::addon_transport::{AddonTransportRegistry, UnsupportedTransport};
+/// # use stremio_core::runtime::Env;
+///
+/// fn addon_transports<E: Env + 'static>() -> AddonTransportRegistry {
+///     AddonTransportRegistry::new::<E>()
+///         // the built-in addons compiled into the app
+///         .register("local", |transport_url| {
+///             Box::new(UnsupportedTransport::new(transport_url.to_owned()))
+///         })
+/// }
+/// ```
+#[derive(Clone, Default)]
+pub struct AddonTransportRegistry {
+    transports: HashMap<String, AddonTransportFactory>,
+}
+
+impl AddonTransportRegistry {
+    /// A registry with the [`AddonHTTPTransport`] for the `http` and `https` schemes.
+    pub fn new<E: Env + 'static>() -> Self {
+        Self::default()
+            .register("http", |transport_url| {
+                Box::new(AddonHTTPTransport::<E>::new(transport_url.to_owned()))
+            })
+            .register("https", |transport_url| {
+                Box::new(AddonHTTPTransport::<E>::new(transport_url.to_owned()))
+            })
+    }
+    /// Registers the transport for the given URL scheme,
+    /// replacing any previously registered transport for it.
+    pub fn register<F>(mut self, scheme: &str, factory: F) -> Self
+    where
+        F: Fn(&Url) -> Box<dyn AddonTransport> + Send + Sync + 'static,
+    {
+        self.transports.insert(scheme.to_owned(), Arc::new(factory));
+        self
+    }
+    pub fn is_supported(&self, transport_url: &Url) -> bool {
+        self.transports.contains_key(transport_url.scheme())
+    }
+    pub fn transport(&self, transport_url: &Url) -> Box<dyn AddonTransport> {
+        match self.transports.get(transport_url.scheme()) {
+            Some(factory) => factory(transport_url),
+            None => Box::new(UnsupportedTransport::new(transport_url.to_owned())),
+        }
+    }
+}
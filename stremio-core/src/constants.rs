@@ -10,19 +10,44 @@ pub const SCHEMA_VERSION_STORAGE_KEY: &str = "schema_version";
 pub const PROFILE_STORAGE_KEY: &str = "profile";
 pub const LIBRARY_STORAGE_KEY: &str = "library";
 pub const LIBRARY_RECENT_STORAGE_KEY: &str = "library_recent";
+pub const LIBRARY_SYNC_STORAGE_KEY: &str = "library_sync";
+pub const OUTBOX_STORAGE_KEY: &str = "outbox";
 pub const STREAMS_STORAGE_KEY: &str = "streams";
 pub const SEARCH_HISTORY_STORAGE_KEY: &str = "search_history";
 pub const STREAMING_SERVER_URLS_STORAGE_KEY: &str = "streaming_server_urls";
 pub const NOTIFICATIONS_STORAGE_KEY: &str = "notifications";
 pub const CALENDAR_STORAGE_KEY: &str = "calendar";
 pub const DISMISSED_EVENTS_STORAGE_KEY: &str = "dismissed_events";
+pub const LOCAL_PROFILES_STORAGE_KEY: &str = "local_profiles";
+pub const ADDON_HEALTH_STORAGE_KEY: &str = "addon_health";
+pub const TASTE_STORAGE_KEY: &str = "taste";
+pub const LISTS_STORAGE_KEY: &str = "lists";
+/// The storage keys of the buckets of each local profile, see
+/// [`local_profile_storage_key`](crate::types::local_profiles::local_profile_storage_key)
+pub const LOCAL_PROFILE_STORAGE_KEYS: [&str; 5] = [
+    LIBRARY_RECENT_STORAGE_KEY,
+    LIBRARY_STORAGE_KEY,
+    STREAMS_STORAGE_KEY,
+    SEARCH_HISTORY_STORAGE_KEY,
+    LISTS_STORAGE_KEY,
+];
+/// The storage keys of the default local profile are not namespaced
+pub const DEFAULT_LOCAL_PROFILE_ID: &str = "default";
+pub const DEFAULT_LOCAL_PROFILE_NAME: &str = "Default";
+/// Prefix of the storage keys of the cached addon resource responses
+pub const RESOURCE_CACHE_STORAGE_KEY: &str = "resource_cache";
+/// The index of the cached addon resource responses, see [`ResourceCacheIndex`](crate::types::addon::ResourceCacheIndex)
+pub const RESOURCE_CACHE_INDEX_STORAGE_KEY: &str = "resource_cache_index";
 pub const LIBRARY_COLLECTION_NAME: &str = "libraryItem";
+pub const LISTS_COLLECTION_NAME: &str = "userList";
 pub const SEARCH_EXTRA_NAME: &str = "search";
 /// `https://{ADDON_UR}/meta/...` resource
 pub const META_RESOURCE_NAME: &str = "meta";
 pub const STREAM_RESOURCE_NAME: &str = "stream";
 /// `https://{ADDON_URL}/catalog/...` resource
 pub const CATALOG_RESOURCE_NAME: &str = "catalog";
+/// `https://{ADDON_URL}/addon_catalog/...` resource
+pub const ADDON_CATALOG_RESOURCE_NAME: &str = "addon_catalog";
 pub const SUBTITLES_RESOURCE_NAME: &str = "subtitles";
 pub const ADDON_MANIFEST_PATH: &str = "/manifest.json";
 pub const ADDON_LEGACY_PATH: &str = "/stremio/v1";
@@ -30,11 +55,49 @@ pub const CATALOG_PAGE_SIZE: usize = 100;
 pub const CATALOG_PREVIEW_SIZE: usize = 100;
 pub const LIBRARY_RECENT_COUNT: usize = 200;
 pub const NOTIFICATION_ITEMS_COUNT: usize = 100;
-/// Maximum calendar items to fetch from `calendarIds` resource
+/// Maximum calendar items to fetch with a single request of the `calendarIds` resource,
+/// the library items are requested in pages of this size
 pub const CALENDAR_ITEMS_COUNT: usize = 100;
+/// Maximum days of a calendar view
+pub const CALENDAR_MAX_DAYS: i64 = 366;
+/// Maximum months of a calendar view, as many as fit in [`CALENDAR_MAX_DAYS`]
+pub const CALENDAR_MAX_MONTHS: u32 = 12;
+/// Maximum offset in milliseconds searched when aligning the subtitles to a reference track
+pub const SUBTITLES_SYNC_MAX_OFFSET: i64 = 60_000;
+/// The product identifier of the exported iCalendar files
+pub const ICALENDAR_PRODUCT_ID: &str = "-//Stremio//Calendar//EN";
+/// Maximum meta items of the loaded catalogs kept for the local search
+pub const LOCAL_SEARCH_CATALOG_RECORDS_COUNT: usize = 1000;
+/// Maximum watched or rated items kept for the recommendations
+pub const TASTE_ITEMS_COUNT: usize = 200;
+/// Maximum recommendations of the loaded catalogs
+pub const RECOMMENDATIONS_COUNT: usize = 20;
+/// Maximum most rewatched items of the watch statistics
+pub const WATCH_STATS_REWATCHED_COUNT: usize = 10;
+/// Maximum most recently watched series whose meta items are requested for the watch statistics
+pub const WATCH_STATS_SERIES_COUNT: usize = 20;
 
 /// Account age in days to be considered a new user
 pub const NEW_USER_DAYS: chrono::Duration = chrono::Duration::days(30);
+/// The delay before the first retry of a failed [`OutboxRequest`](crate::types::outbox::OutboxRequest),
+/// doubled after every failed attempt.
+pub const OUTBOX_RETRY_BASE_DELAY: chrono::Duration = chrono::Duration::seconds(30);
+pub const OUTBOX_RETRY_MAX_DELAY: chrono::Duration = chrono::Duration::hours(1);
+/// The failed outbox requests are retried on a timer up to this many attempts,
+/// later only when the outbox is flushed.
+pub const OUTBOX_RETRY_TIMER_ATTEMPTS: u32 = 8;
+/// Maximum cached addon resource responses, the oldest ones are evicted first
+pub const RESOURCE_CACHE_MAX_ENTRIES: usize = 200;
+/// Cached addon resource responses older than this are evicted
+pub const RESOURCE_CACHE_TTL: chrono::Duration = chrono::Duration::days(7);
+
+/// PBKDF2-HMAC-SHA256 rounds of the parental controls PIN hash
+pub const PARENTAL_CONTROLS_PIN_HASH_ROUNDS: u32 = 100_000;
+/// Failed parental controls PIN attempts allowed before the next attempts are delayed
+pub const PARENTAL_CONTROLS_PIN_ATTEMPTS: u32 = 5;
+/// The delay after too many failed PIN attempts, doubled after every further failed attempt.
+pub const PARENTAL_CONTROLS_PIN_BASE_DELAY: chrono::Duration = chrono::Duration::minutes(1);
+pub const PARENTAL_CONTROLS_PIN_MAX_DELAY: chrono::Duration = chrono::Duration::days(1);
 
 /// A `LibraryItem` is considered watched once we've watched more than the `duration * threshold`:
 ///
@@ -42,9 +105,12 @@ pub const NEW_USER_DAYS: chrono::Duration = chrono::Duration::days(30);
 pub const WATCHED_THRESHOLD_COEF: f64 = 0.7;
 pub const CREDITS_THRESHOLD_COEF: f64 = 0.9;
 /// The latest migration scheme version
-pub const SCHEMA_VERSION: u32 = 20;
+pub const SCHEMA_VERSION: u32 = 24;
+/// The version of the [`Backup`](crate::types::backup::Backup) format
+pub const BACKUP_VERSION: u32 = 1;
 pub const IMDB_LINK_CATEGORY: &str = "imdb";
 pub const GENRES_LINK_CATEGORY: &str = "Genres";
+pub const CAST_LINK_CATEGORY: &str = "Cast";
 pub const CINEMETA_TOP_CATALOG_ID: &str = "top";
 /// Only found in Cinemeta catalogs, i.e. [`CINEMETA_CATALOGS_URL`]
 pub const CINEMETA_FEED_CATALOG_ID: &str = "feed.json";
@@ -68,6 +134,41 @@ pub const USER_LIKES_SUPPORTED_TYPES: &[&str] = &["movie", "series"];
 /// In milliseconds
 pub const PLAYER_IGNORE_SEEK_AFTER: u64 = 600_000;
 
+/// The drift between the players of the watch party members which is not corrected, in milliseconds
+pub const WATCH_PARTY_DRIFT_TOLERANCE: u64 = 500;
+/// A larger drift is corrected by seeking instead of changing the playback rate, in milliseconds
+pub const WATCH_PARTY_SEEK_THRESHOLD: u64 = 3_000;
+pub const WATCH_PARTY_PLAYBACK_RATE_ADJUSTMENT: f64 = 0.05;
+/// The host sends its playback state to the other members at most once per interval
+pub const WATCH_PARTY_STATE_INTERVAL: chrono::Duration = chrono::Duration::seconds(5);
+
+/// How many of the best ranked streams are probed when the health check is enabled
+pub const STREAM_PROBE_COUNT: usize = 10;
+pub const STREAM_PROBE_TTL: chrono::Duration = chrono::Duration::minutes(15);
+/// Maximum probes kept in memory, the oldest ones are evicted first
+pub const STREAM_PROBES_MAX_COUNT: usize = 500;
+
+/// The default timeout of the resource requests to the addons
+pub const ADDON_REQUEST_TIMEOUT: chrono::Duration = chrono::Duration::seconds(15);
+/// The addons scraping for streams usually need more time to respond
+pub const ADDON_STREAM_REQUEST_TIMEOUT: chrono::Duration = chrono::Duration::seconds(30);
+
+/// How many of the most recent requests to every addon are kept for its health stats
+pub const ADDON_HEALTH_SAMPLES_COUNT: usize = 50;
+/// An addon responding slower on average is considered slow, in milliseconds
+pub const ADDON_SLOW_LATENCY: u64 = 3_000;
+/// An addon failing at least this share of the recent requests is considered failing
+pub const ADDON_FAILING_ERROR_RATE: f64 = 0.5;
+/// The addon health is written to the storage at most once per interval,
+/// unless the status of an addon changes
+pub const ADDON_HEALTH_STORAGE_INTERVAL: chrono::Duration = chrono::Duration::minutes(1);
+
+/// The extensions of the torrent files which can be played, in lowercase
+pub const VIDEO_FILE_EXTENSIONS: &[&str] = &[
+    "3gp", "avi", "flv", "m2ts", "m4v", "mkv", "mov", "mp4", "mpeg", "mpg", "ogm", "ogv", "ts",
+    "webm", "wmv",
+];
+
 pub static BASE64: base64::engine::general_purpose::GeneralPurpose =
     base64::engine::general_purpose::STANDARD;
 
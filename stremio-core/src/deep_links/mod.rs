@@ -1,3 +1,5 @@
+use std::iter;
+
 use percent_encoding::utf8_percent_encode;
 use regex::Regex;
 use serde::Serialize;
@@ -753,31 +755,19 @@ impl From<&String> for LibraryDeepLinks {
 
 impl From<(&String, &LibraryRequest)> for LibraryDeepLinks {
     fn from((root, request): (&String, &LibraryRequest)) -> Self {
+        let sort = serde_json::to_value(&request.sort).unwrap();
+        let query_params = iter::once(("sort", sort.as_str().unwrap()))
+            .chain(request.list.as_deref().map(|list| ("list", list)))
+            .collect::<Vec<_>>();
         LibraryDeepLinks {
             library: match &request.r#type {
                 Some(r#type) => format!(
                     "stremio:///{}/{}?{}",
                     root,
                     utf8_percent_encode(r#type, URI_COMPONENT_ENCODE_SET),
-                    query_params_encode(&[(
-                        "sort",
-                        serde_json::to_value(&request.sort)
-                            .unwrap()
-                            .as_str()
-                            .unwrap()
-                    )]),
-                ),
-                _ => format!(
-                    "stremio:///{}?{}",
-                    root,
-                    query_params_encode(&[(
-                        "sort",
-                        serde_json::to_value(&request.sort)
-                            .unwrap()
-                            .as_str()
-                            .unwrap()
-                    )]),
+                    query_params_encode(query_params),
                 ),
+                _ => format!("stremio:///{}?{}", root, query_params_encode(query_params)),
             },
         }
     }
@@ -0,0 +1,86 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use url::Url;
+
+use crate::models::common::eq_update;
+use crate::models::ctx::Ctx;
+use crate::runtime::msg::{Internal, Msg};
+use crate::runtime::{Effects, Env, UpdateWithCtx};
+use crate::types::addon_health::{AddonErrorKind, AddonHealthBucket, AddonHealthStatus};
+use crate::types::profile::Profile;
+
+#[derive(Clone, PartialEq, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AddonHealthItem {
+    pub transport_url: Url,
+    pub name: String,
+    /// `None` if there are no recent requests to the addon
+    pub status: Option<AddonHealthStatus>,
+    pub requests_count: usize,
+    /// The share of the recent requests which failed, from 0 to 1
+    pub error_rate: f64,
+    /// The average latency of the recent requests, in milliseconds
+    pub latency: Option<u64>,
+    pub last_success: Option<DateTime<Utc>>,
+    pub last_error: Option<DateTime<Utc>>,
+    pub error_kinds: HashMap<AddonErrorKind, usize>,
+}
+
+/// The health of the installed addons, the failing and slowest ones first.
+#[derive(Default, Clone, Serialize, Debug)]
+pub struct AddonHealth {
+    pub items: Vec<AddonHealthItem>,
+}
+
+impl AddonHealth {
+    pub fn new(profile: &Profile, addon_health: &AddonHealthBucket) -> (Self, Effects) {
+        let mut items = vec![];
+        let effects = items_update(&mut items, profile, addon_health);
+        (Self { items }, effects.unchanged())
+    }
+}
+
+impl<E: Env + 'static> UpdateWithCtx<E> for AddonHealth {
+    fn update(&mut self, msg: &Msg, ctx: &Ctx) -> Effects {
+        match msg {
+            Msg::Internal(Internal::ProfileChanged)
+            | Msg::Internal(Internal::AddonHealthChanged) => {
+                items_update(&mut self.items, &ctx.profile, &ctx.addon_health)
+            }
+            _ => Effects::none().unchanged(),
+        }
+    }
+}
+
+fn items_update(
+    items: &mut Vec<AddonHealthItem>,
+    profile: &Profile,
+    addon_health: &AddonHealthBucket,
+) -> Effects {
+    let mut addons = profile.addons.iter().collect::<Vec<_>>();
+    addons.sort_by(|a, b| addon_health.compare_health(&a.transport_url, &b.transport_url));
+    let next_items = addons
+        .into_iter()
+        .map(|addon| {
+            let stats = addon_health
+                .items
+                .get(&addon.transport_url)
+                .cloned()
+                .unwrap_or_default();
+            AddonHealthItem {
+                transport_url: addon.transport_url.to_owned(),
+                name: addon.manifest.name.to_owned(),
+                status: stats.status(),
+                requests_count: stats.samples.len(),
+                error_rate: stats.error_rate(),
+                latency: stats.latency(),
+                last_success: stats.last_success,
+                last_error: stats.last_error,
+                error_kinds: stats.error_kinds(),
+            }
+        })
+        .collect::<Vec<_>>();
+    eq_update(items, next_items)
+}
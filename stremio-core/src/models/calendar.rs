@@ -1,25 +1,32 @@
-use chrono::{DateTime, Datelike, Months, NaiveDate, Utc};
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Datelike, Duration, Months, NaiveDate, Utc};
 use derivative::Derivative;
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    constants::{CALENDAR_IDS_EXTRA_PROP, CALENDAR_ITEMS_COUNT},
+    constants::{
+        CALENDAR_IDS_EXTRA_PROP, CALENDAR_ITEMS_COUNT, CALENDAR_MAX_DAYS, CALENDAR_MAX_MONTHS,
+    },
+    deep_links::CalendarItemDeepLinks,
     runtime::{
-        msg::{Action, ActionLoad, Internal, Msg},
+        msg::{Action, ActionCalendar, ActionLoad, Internal, Msg},
         Effects, Env, UpdateWithCtx,
     },
     types::{
         addon::{AggrRequest, Descriptor, ExtraType},
+        icalendar::{ICalendar, ICalendarEvent},
         library::LibraryBucket,
+        profile::ParentalControls,
         resource::{MetaItem, Video},
     },
 };
 
 use crate::models::{
     common::{
-        eq_update, resources_update_with_vector_content, Loadable, ResourceLoadable,
-        ResourcesAction,
+        eq_update, resources_cancel_effects, resources_update_with_vector_content, Loadable,
+        ResourceLoadable, ResourcesAction,
     },
     ctx::Ctx,
 };
@@ -72,14 +79,138 @@ impl FullDate {
     pub fn new(year: Year, month: Month, day: Day) -> Self {
         Self { day, month, year }
     }
+
+    /// `None` if the date doesn't exist
+    pub fn naive_date(&self) -> Option<NaiveDate> {
+        NaiveDate::from_ymd_opt(self.year, self.month, self.day)
+    }
+}
+
+#[derive(Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Debug)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum CalendarView {
+    /// The days of the selected month
+    #[default]
+    Month,
+    /// The days of the week, from Monday, of the selected day
+    Week,
+    /// The given number of days from the selected day, only the days with releases
+    Agenda { days: u32 },
+    /// The days of the given number of months from the selected month,
+    /// up to [`CALENDAR_MAX_MONTHS`]
+    Months { count: u32 },
+}
+
+#[derive(Default, Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CalendarFilters {
+    /// Only the library items of this type
+    #[serde(default)]
+    pub r#type: Option<String>,
+    /// Skips the library items whose notifications are disabled
+    #[serde(default)]
+    pub notifications_only: bool,
+}
+
+#[derive(Default, Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Selected {
+    #[serde(flatten)]
+    pub date: YearMonthDate,
+    /// The selected day of the week and agenda views, the first of the month if not given
+    #[serde(default)]
+    pub day: Option<Day>,
+    #[serde(default)]
+    pub view: CalendarView,
+    #[serde(default)]
+    pub filters: CalendarFilters,
+}
+
+impl Selected {
+    /// The first and the last day of the view, `None` if the selected date doesn't exist.
+    ///
+    /// The view is cut to [`CALENDAR_MAX_DAYS`].
+    pub fn range(&self) -> Option<(NaiveDate, NaiveDate)> {
+        let first_of_month = NaiveDate::from_ymd_opt(self.date.year, self.date.month, 1)?;
+        let day = match self.day {
+            Some(day) => NaiveDate::from_ymd_opt(self.date.year, self.date.month, day)?,
+            None => first_of_month,
+        };
+        let (start, end) = match self.view {
+            CalendarView::Month => (
+                first_of_month,
+                first_of_month
+                    .checked_add_months(Months::new(1))?
+                    .pred_opt()?,
+            ),
+            CalendarView::Week => {
+                let start = day.checked_sub_signed(Duration::days(
+                    day.weekday().num_days_from_monday().into(),
+                ))?;
+                (start, start.checked_add_signed(Duration::days(6))?)
+            }
+            CalendarView::Agenda { days } => {
+                let days = i64::from(days).clamp(1, CALENDAR_MAX_DAYS);
+                (day, day.checked_add_signed(Duration::days(days - 1))?)
+            }
+            CalendarView::Months { count } => (
+                first_of_month,
+                first_of_month
+                    .checked_add_months(view_months(count))?
+                    .pred_opt()?,
+            ),
+        };
+
+        let max_end = start
+            .checked_add_signed(Duration::days(CALENDAR_MAX_DAYS - 1))
+            .unwrap_or(NaiveDate::MAX);
+        Some((start, end.min(max_end)))
+    }
+
+    /// The previous (`forward` is `false`) or the next page of the view
+    fn page(&self, forward: bool) -> Option<Selected> {
+        let (start, end) = self.range()?;
+        let (date, day) = match self.view {
+            CalendarView::Month | CalendarView::Months { .. } => {
+                let months = match self.view {
+                    CalendarView::Months { count } => view_months(count),
+                    _ => Months::new(1),
+                };
+                let date = if forward {
+                    start.checked_add_months(months)?
+                } else {
+                    start.checked_sub_months(months)?
+                };
+                (date, None)
+            }
+            CalendarView::Week | CalendarView::Agenda { .. } => {
+                let days = end - start + Duration::days(1);
+                let date = if forward {
+                    start.checked_add_signed(days)?
+                } else {
+                    start.checked_sub_signed(days)?
+                };
+                (date, Some(date.day()))
+            }
+        };
+
+        Some(Selected {
+            date: YearMonthDate::from(date),
+            day,
+            ..self.to_owned()
+        })
+    }
 }
 
-pub type Selected = YearMonthDate;
+/// The months of a [`CalendarView::Months`], so that the pages move by the whole view
+fn view_months(count: u32) -> Months {
+    Months::new(count.clamp(1, CALENDAR_MAX_MONTHS))
+}
 
 #[derive(Default, Clone, PartialEq, Eq, Serialize, Debug)]
 pub struct Selectable {
-    pub prev: YearMonthDate,
-    pub next: YearMonthDate,
+    pub prev: Selected,
+    pub next: Selected,
 }
 
 #[derive(Clone, PartialEq, Eq, Serialize, Debug)]
@@ -103,6 +234,15 @@ pub struct MonthInfo {
     pub first_weekday: u32,
 }
 
+#[derive(Clone, PartialEq, Eq, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ICalendarExport {
+    pub from: FullDate,
+    pub to: FullDate,
+    /// The iCalendar file, `None` while the meta items are loading
+    pub ics: Option<String>,
+}
+
 #[derive(Derivative, Clone, Serialize, Debug)]
 #[derivative(Default(bound = ""))]
 pub struct Calendar {
@@ -111,6 +251,8 @@ pub struct Calendar {
     pub meta_items: Vec<ResourceLoadable<Vec<MetaItem>>>,
     pub month_info: MonthInfo,
     pub items: Vec<Item>,
+    /// The upcoming episodes of the exported date range, independent of the selected month
+    pub icalendar: Option<ICalendarExport>,
 }
 
 impl<E: Env + 'static> UpdateWithCtx<E> for Calendar {
@@ -126,8 +268,9 @@ impl<E: Env + 'static> UpdateWithCtx<E> for Calendar {
                 let items_effects = items_update(
                     &mut self.items,
                     &self.selected,
-                    &self.month_info,
                     &self.meta_items,
+                    &ctx.library,
+                    &ctx.profile.parental_controls,
                 );
 
                 meta_items_effects
@@ -137,17 +280,32 @@ impl<E: Env + 'static> UpdateWithCtx<E> for Calendar {
                     .join(items_effects)
             }
             Msg::Action(Action::Unload) => {
-                let meta_items_effects = eq_update(&mut self.meta_items, Vec::new());
+                let meta_items_effects = resources_cancel_effects(&self.meta_items)
+                    .join(eq_update(&mut self.meta_items, Vec::new()));
                 let selected_effects = eq_update(&mut self.selected, None);
                 let month_info_effects = eq_update(&mut self.month_info, MonthInfo::default());
                 let selectable_effects = eq_update(&mut self.selectable, Selectable::default());
                 let items_effects = eq_update(&mut self.items, Vec::new());
+                let icalendar_effects = eq_update(&mut self.icalendar, None);
 
                 meta_items_effects
                     .join(selected_effects)
                     .join(month_info_effects)
                     .join(selectable_effects)
                     .join(items_effects)
+                    .join(icalendar_effects)
+            }
+            Msg::Action(Action::Calendar(ActionCalendar::ExportICalendar { from, to })) => {
+                let meta_items_effects =
+                    meta_items_update::<E>(&mut self.meta_items, &ctx.library, &ctx.profile.addons);
+                let icalendar_effects = icalendar_update::<E>(
+                    &mut self.icalendar,
+                    Some((from, to)),
+                    &self.meta_items,
+                    &ctx.profile.parental_controls,
+                );
+
+                meta_items_effects.join(icalendar_effects)
             }
             Msg::Internal(Internal::ResourceRequestResult(request, result)) => {
                 let meta_items_effects = resources_update_with_vector_content::<E, _>(
@@ -158,11 +316,44 @@ impl<E: Env + 'static> UpdateWithCtx<E> for Calendar {
                 let items_effects = items_update(
                     &mut self.items,
                     &self.selected,
-                    &self.month_info,
                     &self.meta_items,
+                    &ctx.library,
+                    &ctx.profile.parental_controls,
+                );
+                let icalendar_effects = icalendar_update::<E>(
+                    &mut self.icalendar,
+                    None,
+                    &self.meta_items,
+                    &ctx.profile.parental_controls,
                 );
 
-                meta_items_effects.join(items_effects)
+                meta_items_effects
+                    .join(items_effects)
+                    .join(icalendar_effects)
+            }
+            Msg::Internal(Internal::LibraryChanged(_)) => items_update(
+                &mut self.items,
+                &self.selected,
+                &self.meta_items,
+                &ctx.library,
+                &ctx.profile.parental_controls,
+            ),
+            Msg::Internal(Internal::ProfileChanged) => {
+                let items_effects = items_update(
+                    &mut self.items,
+                    &self.selected,
+                    &self.meta_items,
+                    &ctx.library,
+                    &ctx.profile.parental_controls,
+                );
+                let icalendar_effects = icalendar_update::<E>(
+                    &mut self.icalendar,
+                    None,
+                    &self.meta_items,
+                    &ctx.profile.parental_controls,
+                );
+
+                items_effects.join(icalendar_effects)
             }
             _ => Effects::none().unchanged(),
         }
@@ -178,7 +369,10 @@ fn selected_update<E: Env + 'static>(
     let updated_selected = next_selected
         .as_ref()
         .map(|next_selected| next_selected.to_owned())
-        .or(Some(YearMonthDate::from(current_date)));
+        .or(Some(Selected {
+            date: YearMonthDate::from(current_date),
+            ..Default::default()
+        }));
 
     eq_update(selected, updated_selected)
 }
@@ -189,29 +383,37 @@ fn month_info_update<E: Env + 'static>(
 ) -> Effects {
     let updated_month_info = selected
         .as_ref()
-        .map(|Selected { month, year, .. }| {
-            let current_date = E::now();
-
-            let today = if current_date.year() == *year && current_date.month() == *month {
-                Some(current_date.day())
-            } else {
-                None
-            };
-
-            let first_day_of_month = NaiveDate::from_ymd_opt(*year, *month, 1).unwrap_or_default();
-            let first_of_next_month = first_day_of_month + Months::new(1);
-            let last_day_of_month = first_of_next_month.pred_opt().unwrap_or_default();
-
-            let days = last_day_of_month.day();
-
-            let first_weekday = first_day_of_month.weekday().num_days_from_monday();
-
-            MonthInfo {
-                today,
-                days,
-                first_weekday,
-            }
-        })
+        .map(
+            |Selected {
+                 date: YearMonthDate { month, year },
+                 ..
+             }| {
+                let current_date = E::now();
+
+                let today = if current_date.year() == *year && current_date.month() == *month {
+                    Some(current_date.day())
+                } else {
+                    None
+                };
+
+                let first_day_of_month =
+                    NaiveDate::from_ymd_opt(*year, *month, 1).unwrap_or_default();
+                let last_day_of_month = first_day_of_month
+                    .checked_add_months(Months::new(1))
+                    .and_then(|first_of_next_month| first_of_next_month.pred_opt())
+                    .unwrap_or(NaiveDate::MAX);
+
+                let days = last_day_of_month.day();
+
+                let first_weekday = first_day_of_month.weekday().num_days_from_monday();
+
+                MonthInfo {
+                    today,
+                    days,
+                    first_weekday,
+                }
+            },
+        )
         .unwrap_or_default();
 
     eq_update(month_info, updated_month_info)
@@ -220,16 +422,11 @@ fn month_info_update<E: Env + 'static>(
 fn selectable_update(selectable: &mut Selectable, selected: &Option<Selected>) -> Effects {
     let updated_selectable = selected
         .as_ref()
-        .map(|Selected { month, year, .. }| {
-            let date = NaiveDate::from_ymd_opt(*year, *month, 1).unwrap_or_default();
-
-            let prev_date = date - Months::new(1);
-            let next_date = date + Months::new(1);
-
-            Selectable {
-                prev: YearMonthDate::from(prev_date),
-                next: YearMonthDate::from(next_date),
-            }
+        .and_then(|selected| {
+            Some(Selectable {
+                prev: selected.page(false)?,
+                next: selected.page(true)?,
+            })
         })
         .unwrap_or_default();
 
@@ -239,44 +436,51 @@ fn selectable_update(selectable: &mut Selectable, selected: &Option<Selected>) -
 fn items_update(
     items: &mut Vec<Item>,
     selected: &Option<Selected>,
-    month_info: &MonthInfo,
     meta_items: &[ResourceLoadable<Vec<MetaItem>>],
+    library: &LibraryBucket,
+    parental_controls: &ParentalControls,
 ) -> Effects {
     let updated_items = selected
         .as_ref()
-        .map(|Selected { month, year, .. }| {
-            (1..=month_info.days)
-                .map(|day| Item {
-                    date: FullDate::new(*year, *month, day),
-                    items: meta_items
-                        .iter()
-                        .flat_map(|ResourceLoadable { content, .. }| match content {
-                            Some(Loadable::Ready(content)) => content
-                                .iter()
-                                .flat_map(|meta_item| {
-                                    meta_item
-                                        .videos
-                                        .iter()
-                                        .filter(|video| {
-                                            video
-                                                .released
-                                                .map(|released| {
-                                                    released.day() == day
-                                                        && released.month() == *month
-                                                        && released.year() == *year
-                                                })
-                                                .unwrap_or(false)
-                                        })
-                                        .map(|video| ContentItem {
-                                            meta_item: meta_item.clone(),
-                                            video: video.clone(),
-                                        })
-                                        .collect_vec()
-                                })
-                                .collect_vec(),
-                            _ => vec![],
+        .and_then(|selected| Some((selected, selected.range()?)))
+        .map(|(selected, (start, end))| {
+            let mut content_items = meta_items
+                .iter()
+                .filter_map(|ResourceLoadable { content, .. }| match content {
+                    Some(Loadable::Ready(content)) => Some(content),
+                    _ => None,
+                })
+                .flatten()
+                .filter(|meta_item| {
+                    !parental_controls.is_blocked(*meta_item)
+                        && is_selected(meta_item, &selected.filters, library)
+                })
+                .flat_map(|meta_item| {
+                    meta_item.videos.iter().filter_map(move |video| {
+                        let date = video.released?.date_naive();
+                        (start..=end).contains(&date).then(|| {
+                            (
+                                date,
+                                ContentItem {
+                                    meta_item: meta_item.clone(),
+                                    video: video.clone(),
+                                },
+                            )
                         })
-                        .collect_vec(),
+                    })
+                })
+                .into_group_map()
+                .into_iter()
+                .collect::<BTreeMap<_, _>>();
+            start
+                .iter_days()
+                .take_while(|date| *date <= end)
+                .map(|date| Item {
+                    date: FullDate::new(date.year(), date.month(), date.day()),
+                    items: content_items.remove(&date).unwrap_or_default(),
+                })
+                .filter(|item| {
+                    !matches!(selected.view, CalendarView::Agenda { .. }) || !item.items.is_empty()
                 })
                 .collect_vec()
         })
@@ -285,6 +489,124 @@ fn items_update(
     eq_update(items, updated_items)
 }
 
+fn is_selected(meta_item: &MetaItem, filters: &CalendarFilters, library: &LibraryBucket) -> bool {
+    let library_item = library.items.get(&meta_item.preview.id);
+    let type_selected = filters.r#type.as_ref().map_or(true, |r#type| {
+        library_item
+            .map(|library_item| &library_item.r#type)
+            .unwrap_or(&meta_item.preview.r#type)
+            == r#type
+    });
+    let notifications_selected = !filters.notifications_only
+        || library_item.map_or(true, |library_item| !library_item.state.no_notif);
+    type_selected && notifications_selected
+}
+
+/// Builds the iCalendar of the videos released between `from` and `to`, both inclusive.
+///
+/// The events are sorted by date and their UIDs are derived from the meta item and video ids,
+/// so re-exporting the same range updates the events of a subscribed calendar
+/// instead of duplicating them.
+pub fn icalendar(
+    meta_items: &[ResourceLoadable<Vec<MetaItem>>],
+    from: &FullDate,
+    to: &FullDate,
+    parental_controls: &ParentalControls,
+    now: DateTime<Utc>,
+) -> ICalendar {
+    let range = from.naive_date().zip(to.naive_date());
+    let events = meta_items
+        .iter()
+        .filter_map(|ResourceLoadable { content, .. }| match content {
+            Some(Loadable::Ready(content)) => Some(content),
+            _ => None,
+        })
+        .flatten()
+        .filter(|meta_item| !parental_controls.is_blocked(*meta_item))
+        .flat_map(|meta_item| {
+            meta_item.videos.iter().filter_map(move |video| {
+                let (from, to) = range?;
+                let date = video.released?.date_naive();
+                (from..=to)
+                    .contains(&date)
+                    .then(|| icalendar_event(meta_item, video, date, now))
+            })
+        })
+        .unique_by(|event| event.uid.to_owned())
+        .sorted_by(|a, b| a.date.cmp(&b.date).then_with(|| a.summary.cmp(&b.summary)))
+        .collect();
+
+    ICalendar {
+        name: "Stremio".to_owned(),
+        events,
+    }
+}
+
+fn icalendar_event(
+    meta_item: &MetaItem,
+    video: &Video,
+    date: NaiveDate,
+    now: DateTime<Utc>,
+) -> ICalendarEvent {
+    let episode = video
+        .series_info
+        .as_ref()
+        .map(|series_info| format!("S{:02}E{:02}", series_info.season, series_info.episode));
+    let summary = [
+        Some(meta_item.preview.name.as_str()),
+        episode.as_deref(),
+        Some(video.title.as_str()),
+    ]
+    .into_iter()
+    .flatten()
+    .filter(|part| !part.is_empty())
+    .join(" - ");
+    let url = CalendarItemDeepLinks::from((meta_item, video)).meta_details_streams;
+    let description = video
+        .overview
+        .iter()
+        .map(String::as_str)
+        .chain([url.as_str()])
+        .join("\n\n");
+
+    ICalendarEvent {
+        uid: format!("{}/{}@stremio", meta_item.preview.id, video.id),
+        stamp: now,
+        date,
+        summary,
+        description: Some(description),
+        url: Some(url),
+    }
+}
+
+/// Regenerates the iCalendar of the exported date range,
+/// a new range is exported when `next_range` is given.
+fn icalendar_update<E: Env + 'static>(
+    icalendar_export: &mut Option<ICalendarExport>,
+    next_range: Option<(&FullDate, &FullDate)>,
+    meta_items: &[ResourceLoadable<Vec<MetaItem>>],
+    parental_controls: &ParentalControls,
+) -> Effects {
+    let range = next_range.or_else(|| {
+        icalendar_export
+            .as_ref()
+            .map(|ICalendarExport { from, to, .. }| (from, to))
+    });
+    let next_icalendar_export = range.map(|(from, to)| {
+        let is_loading = meta_items
+            .iter()
+            .any(|ResourceLoadable { content, .. }| matches!(content, Some(Loadable::Loading)));
+        ICalendarExport {
+            from: from.to_owned(),
+            to: to.to_owned(),
+            ics: (!is_loading)
+                .then(|| icalendar(meta_items, from, to, parental_controls, E::now()).to_string()),
+        }
+    });
+
+    eq_update(icalendar_export, next_icalendar_export)
+}
+
 fn meta_items_update<E: Env + 'static>(
     meta_items: &mut Vec<ResourceLoadable<Vec<MetaItem>>>,
     library: &LibraryBucket,
@@ -299,16 +621,19 @@ fn meta_items_update<E: Env + 'static>(
             .map(|library_item| (library_item.id.to_owned(), library_item.r#type.to_owned()))
             .collect_vec();
 
+        // a request for each page of the library items
+        let extra_types = id_types
+            .chunks(CALENDAR_ITEMS_COUNT)
+            .map(|id_types| ExtraType::Ids {
+                extra_name: CALENDAR_IDS_EXTRA_PROP.name.to_owned(),
+                id_types: id_types.to_vec(),
+                limit: Some(CALENDAR_ITEMS_COUNT),
+            })
+            .collect_vec();
+
         resources_update_with_vector_content::<E, _>(
             meta_items,
-            ResourcesAction::force_request(
-                &AggrRequest::CatalogsFiltered(vec![ExtraType::Ids {
-                    extra_name: CALENDAR_IDS_EXTRA_PROP.name.to_owned(),
-                    id_types,
-                    limit: Some(CALENDAR_ITEMS_COUNT),
-                }]),
-                addons,
-            ),
+            ResourcesAction::force_request(&AggrRequest::CatalogsFiltered(extra_types), addons),
         )
     } else {
         Effects::none().unchanged()
@@ -1,7 +1,7 @@
 use crate::constants::{SKIP_EXTRA_PROP, TYPE_PRIORITIES};
 use crate::models::common::{
-    compare_with_priorities, eq_update, resource_update_with_vector_content, ResourceAction,
-    ResourceLoadable,
+    compare_with_priorities, eq_update, resource_update_with_vector_content,
+    resources_cancel_effects, resources_filtered, ResourceAction, ResourceLoadable,
 };
 use crate::models::ctx::Ctx;
 use crate::runtime::msg::{Action, ActionCatalogWithFilters, ActionLoad, Internal, Msg};
@@ -10,7 +10,7 @@ use crate::types::addon::{
     Descriptor, DescriptorPreview, ExtraExt, Manifest, ManifestCatalog, ResourcePath,
     ResourceRequest, ResourceResponse,
 };
-use crate::types::profile::Profile;
+use crate::types::profile::{ParentalControls, Profile};
 use crate::types::resource::MetaItemPreview;
 use boolinator::Boolinator;
 use derivative::Derivative;
@@ -28,6 +28,7 @@ pub trait CatalogResourceAdapter {
     fn resource() -> &'static str;
     fn catalogs(manifest: &Manifest) -> &[ManifestCatalog];
     fn selectable_priority() -> SelectablePriority;
+    fn is_blocked(&self, parental_controls: &ParentalControls) -> bool;
 }
 
 impl CatalogResourceAdapter for MetaItemPreview {
@@ -40,6 +41,9 @@ impl CatalogResourceAdapter for MetaItemPreview {
     fn selectable_priority() -> SelectablePriority {
         SelectablePriority::Type
     }
+    fn is_blocked(&self, parental_controls: &ParentalControls) -> bool {
+        parental_controls.is_blocked(self)
+    }
 }
 
 impl CatalogResourceAdapter for DescriptorPreview {
@@ -52,6 +56,9 @@ impl CatalogResourceAdapter for DescriptorPreview {
     fn selectable_priority() -> SelectablePriority {
         SelectablePriority::Catalog
     }
+    fn is_blocked(&self, _parental_controls: &ParentalControls) -> bool {
+        false
+    }
 }
 
 impl CatalogResourceAdapter for Descriptor {
@@ -64,6 +71,9 @@ impl CatalogResourceAdapter for Descriptor {
     fn selectable_priority() -> SelectablePriority {
         SelectablePriority::Catalog
     }
+    fn is_blocked(&self, _parental_controls: &ParentalControls) -> bool {
+        false
+    }
 }
 
 #[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
@@ -129,7 +139,11 @@ pub type Catalog<T> = Vec<CatalogPage<T>>;
 pub struct CatalogWithFilters<T> {
     pub selected: Option<Selected>,
     pub selectable: Selectable,
+    /// The loaded `pages` without the items blocked by the parental controls
     pub catalog: Catalog<T>,
+    /// The pages as they were returned by the addon, used for requesting the next page
+    #[serde(skip)]
+    pub pages: Catalog<T>,
 }
 
 impl<T: CatalogResourceAdapter> CatalogWithFilters<T> {
@@ -138,7 +152,7 @@ impl<T: CatalogResourceAdapter> CatalogWithFilters<T> {
         let selectable_effects = selectable_update(
             &mut model.selectable,
             &model.selected,
-            &model.catalog,
+            &model.pages,
             profile,
         )
         .unchanged();
@@ -149,7 +163,7 @@ impl<T: CatalogResourceAdapter> CatalogWithFilters<T> {
 impl<E, T> UpdateWithCtx<E> for CatalogWithFilters<T>
 where
     E: Env + 'static,
-    T: CatalogResourceAdapter + PartialEq,
+    T: CatalogResourceAdapter + Clone + PartialEq,
     Vec<T>: TryFrom<ResourceResponse, Error = derive_more::TryIntoError<ResourceResponse>>,
 {
     fn update(&mut self, msg: &Msg, ctx: &Ctx) -> Effects {
@@ -157,18 +171,23 @@ where
             Msg::Action(Action::Load(ActionLoad::CatalogWithFilters(selected))) => {
                 let selected_effects =
                     selected_update::<T>(&mut self.selected, &self.selectable, selected);
-                let catalog_effects = match self.selected.as_ref() {
+                let pages_effects = match self.selected.as_ref() {
                     Some(selected) => catalog_update::<E, _>(
-                        &mut self.catalog,
+                        &mut self.pages,
                         CatalogPageRequest::First,
                         &selected.request,
                     ),
                     _ => Effects::none().unchanged(),
                 };
+                let catalog_effects = pages_effects.join(catalog_filter_update(
+                    &mut self.catalog,
+                    &self.pages,
+                    &ctx.profile.parental_controls,
+                ));
                 let selectable_effects = selectable_update(
                     &mut self.selectable,
                     &self.selected,
-                    &self.catalog,
+                    &self.pages,
                     &ctx.profile,
                 );
                 selected_effects
@@ -176,15 +195,18 @@ where
                     .join(selectable_effects)
             }
             Msg::Action(Action::Unload) => {
+                let cancel_effects = resources_cancel_effects(&self.pages);
                 let selected_effects = eq_update(&mut self.selected, None);
-                let catalog_effects = eq_update(&mut self.catalog, vec![]);
+                let catalog_effects =
+                    eq_update(&mut self.pages, vec![]).join(eq_update(&mut self.catalog, vec![]));
                 let selectable_effects = selectable_update(
                     &mut self.selectable,
                     &self.selected,
-                    &self.catalog,
+                    &self.pages,
                     &ctx.profile,
                 );
-                selected_effects
+                cancel_effects
+                    .join(selected_effects)
                     .join(catalog_effects)
                     .join(selectable_effects)
             }
@@ -192,14 +214,19 @@ where
                 match self.selectable.next_page.as_ref() {
                     Some(next_page) => {
                         let catalog_effects = catalog_update::<E, _>(
-                            &mut self.catalog,
+                            &mut self.pages,
                             CatalogPageRequest::Next,
                             &next_page.request,
-                        );
+                        )
+                        .join(catalog_filter_update(
+                            &mut self.catalog,
+                            &self.pages,
+                            &ctx.profile.parental_controls,
+                        ));
                         let selectable_effects = selectable_update(
                             &mut self.selectable,
                             &self.selected,
-                            &self.catalog,
+                            &self.pages,
                             &ctx.profile,
                         );
                         catalog_effects.join(selectable_effects)
@@ -208,7 +235,7 @@ where
                 }
             }
             Msg::Internal(Internal::ResourceRequestResult(request, result)) => self
-                .catalog
+                .pages
                 .iter_mut()
                 .find(|page| page.request == *request)
                 .map(|page| {
@@ -217,22 +244,36 @@ where
                         ResourceAction::ResourceRequestResult { request, result },
                     )
                 })
-                .map(|catalog_effects| {
+                .map(|pages_effects| {
+                    let catalog_effects = catalog_filter_update(
+                        &mut self.catalog,
+                        &self.pages,
+                        &ctx.profile.parental_controls,
+                    );
                     let selectable_effects = selectable_update(
                         &mut self.selectable,
                         &self.selected,
-                        &self.catalog,
+                        &self.pages,
                         &ctx.profile,
                     );
-                    catalog_effects.join(selectable_effects)
+                    pages_effects.join(catalog_effects).join(selectable_effects)
                 })
                 .unwrap_or_else(|| Effects::none().unchanged()),
-            Msg::Internal(Internal::ProfileChanged) => selectable_update(
-                &mut self.selectable,
-                &self.selected,
-                &self.catalog,
-                &ctx.profile,
-            ),
+            Msg::Internal(Internal::ProfileChanged) => {
+                // the parental controls might have been locked or unlocked
+                let catalog_effects = catalog_filter_update(
+                    &mut self.catalog,
+                    &self.pages,
+                    &ctx.profile.parental_controls,
+                );
+                let selectable_effects = selectable_update(
+                    &mut self.selectable,
+                    &self.selected,
+                    &self.pages,
+                    &ctx.profile,
+                );
+                catalog_effects.join(selectable_effects)
+            }
             Msg::Internal(Internal::LibraryChanged(_)) => Effects::none(),
             _ => Effects::none().unchanged(),
         }
@@ -281,6 +322,11 @@ where
     T: CatalogResourceAdapter + PartialEq,
     Vec<T>: TryFrom<ResourceResponse, Error = derive_more::TryIntoError<ResourceResponse>>,
 {
+    // the pages of the previous selection which are still loading are not needed anymore
+    let cancel_effects = match page_request {
+        CatalogPageRequest::First => resources_cancel_effects(&*catalog),
+        CatalogPageRequest::Next => Effects::none().unchanged(),
+    };
     let mut page = ResourceLoadable {
         request: request.to_owned(),
         content: None,
@@ -293,7 +339,16 @@ where
         CatalogPageRequest::First => *catalog = vec![page],
         CatalogPageRequest::Next => catalog.extend(vec![page]),
     };
-    effects
+    cancel_effects.join(effects)
+}
+
+fn catalog_filter_update<T: CatalogResourceAdapter + Clone + PartialEq>(
+    catalog: &mut Catalog<T>,
+    pages: &Catalog<T>,
+    parental_controls: &ParentalControls,
+) -> Effects {
+    let next_catalog = resources_filtered(pages, |item| !item.is_blocked(parental_controls));
+    eq_update(catalog, next_catalog)
 }
 
 fn selectable_update<T: CatalogResourceAdapter>(
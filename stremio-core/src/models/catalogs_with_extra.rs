@@ -1,15 +1,19 @@
-use crate::constants::SKIP_EXTRA_PROP;
+use crate::constants::{RECOMMENDATIONS_COUNT, SKIP_EXTRA_PROP};
 use crate::models::common::{
-    eq_update, resource_update_with_vector_content, Loadable, ResourceAction, ResourceLoadable,
+    eq_update, resource_request_effects, resource_update_with_vector_content,
+    resources_cancel_effects, resources_filtered, Loadable, ResourceAction, ResourceLoadable,
 };
 use crate::models::ctx::Ctx;
 use crate::runtime::msg::{Action, ActionCatalogsWithExtra, ActionLoad, Internal, Msg};
-use crate::runtime::{EffectFuture, Effects, Env, EnvFutureExt, UpdateWithCtx};
+use crate::runtime::{Effects, Env, UpdateWithCtx};
 use crate::types::addon::{AggrRequest, ExtraExt, ExtraValue, ResourcePath, ResourceRequest};
-use crate::types::profile::Profile;
+use crate::types::library::LibraryBucket;
+use crate::types::profile::{ParentalControls, Profile};
 use crate::types::resource::MetaItemPreview;
-use futures::FutureExt;
+use crate::types::taste::{Recommendation, TasteBucket, TasteProfile};
+use itertools::Itertools;
 use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
 use std::ops::Range;
 
 #[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
@@ -26,7 +30,15 @@ pub type Catalog<T> = Vec<CatalogPage<T>>;
 #[derive(Default, Clone, Serialize, Debug)]
 pub struct CatalogsWithExtra {
     pub selected: Option<Selected>,
+    /// The loaded `pages` without the items blocked by the parental controls
     pub catalogs: Vec<Catalog<MetaItemPreview>>,
+    /// The pages of each catalog as they were returned by the addons,
+    /// used for requesting the next page
+    #[serde(skip)]
+    pub pages: Vec<Catalog<MetaItemPreview>>,
+    /// The "For You" catalog, the items of the loaded catalogs which match the taste of the user,
+    /// computed only when there is no extra, e.g. for the board
+    pub recommendations: Vec<Recommendation>,
 }
 
 impl<E: Env + 'static> UpdateWithCtx<E> for CatalogsWithExtra {
@@ -35,7 +47,13 @@ impl<E: Env + 'static> UpdateWithCtx<E> for CatalogsWithExtra {
             Msg::Action(Action::Load(ActionLoad::CatalogsWithExtra(selected))) => {
                 let selected_effects = selected_update(&mut self.selected, selected);
                 let catalogs_effects =
-                    catalogs_update::<E>(&mut self.catalogs, &self.selected, None, &ctx.profile);
+                    catalogs_update::<E>(&mut self.pages, &self.selected, None, &ctx.profile).join(
+                        catalogs_filter_update(
+                            &mut self.catalogs,
+                            &self.pages,
+                            &ctx.profile.parental_controls,
+                        ),
+                    );
                 let search_effects = match &self.selected {
                     Some(Selected { extra, .. }) => match extra
                         .iter()
@@ -51,24 +69,40 @@ impl<E: Env + 'static> UpdateWithCtx<E> for CatalogsWithExtra {
                     },
                     None => Effects::none().unchanged(),
                 };
-                selected_effects.join(catalogs_effects).join(search_effects)
+                let recommendations_effects = recommendations_update(
+                    &mut self.recommendations,
+                    &self.selected,
+                    &self.catalogs,
+                    &ctx.taste,
+                    &ctx.library,
+                );
+                selected_effects
+                    .join(catalogs_effects)
+                    .join(recommendations_effects)
+                    .join(search_effects)
             }
             Msg::Action(Action::Unload) => {
+                let cancel_effects = resources_cancel_effects(self.pages.iter().flatten());
                 let selected_effects = eq_update(&mut self.selected, None);
-                let catalogs_effects = eq_update(&mut self.catalogs, vec![]);
-                selected_effects.join(catalogs_effects)
+                let catalogs_effects =
+                    eq_update(&mut self.pages, vec![]).join(eq_update(&mut self.catalogs, vec![]));
+                let recommendations_effects = eq_update(&mut self.recommendations, vec![]);
+                cancel_effects
+                    .join(selected_effects)
+                    .join(catalogs_effects)
+                    .join(recommendations_effects)
             }
             Msg::Action(Action::CatalogsWithExtra(ActionCatalogsWithExtra::LoadRange(range))) => {
-                catalogs_update::<E>(
-                    &mut self.catalogs,
-                    &self.selected,
-                    Some(range),
-                    &ctx.profile,
-                )
+                catalogs_update::<E>(&mut self.pages, &self.selected, Some(range), &ctx.profile)
+                    .join(catalogs_filter_update(
+                        &mut self.catalogs,
+                        &self.pages,
+                        &ctx.profile.parental_controls,
+                    ))
             }
             Msg::Action(Action::CatalogsWithExtra(ActionCatalogsWithExtra::LoadNextPage(
                 index,
-            ))) => match self.catalogs.get_mut(*index) {
+            ))) => match self.pages.get_mut(*index) {
                 Some(catalog) => match catalog.last() {
                     Some(ResourceLoadable {
                         content: Some(Loadable::Ready(items)),
@@ -117,19 +151,12 @@ impl<E: Env + 'static> UpdateWithCtx<E> for CatalogsWithExtra {
                             request: request.to_owned(),
                             content: Some(Loadable::Loading),
                         });
-                        Effects::one(
-                            EffectFuture::Concurrent(
-                                E::addon_transport(&request.base)
-                                    .resource(&request.path)
-                                    .map(|result| {
-                                        Msg::Internal(Internal::ResourceRequestResult(
-                                            request,
-                                            Box::new(result),
-                                        ))
-                                    })
-                                    .boxed_env(),
-                            )
-                            .into(),
+                        Effects::many(resource_request_effects::<E>(&request)).join(
+                            catalogs_filter_update(
+                                &mut self.catalogs,
+                                &self.pages,
+                                &ctx.profile.parental_controls,
+                            ),
                         )
                     }
                     _ => Effects::none().unchanged(),
@@ -137,7 +164,7 @@ impl<E: Env + 'static> UpdateWithCtx<E> for CatalogsWithExtra {
                 _ => Effects::none().unchanged(),
             },
             Msg::Internal(Internal::ResourceRequestResult(request, result)) => self
-                .catalogs
+                .pages
                 .iter_mut()
                 .find_map(|catalog| catalog.last_mut().filter(|page| page.request == *request))
                 .map(|page| {
@@ -146,11 +173,55 @@ impl<E: Env + 'static> UpdateWithCtx<E> for CatalogsWithExtra {
                         ResourceAction::ResourceRequestResult { request, result },
                     )
                 })
+                .map(|page_effects| {
+                    page_effects
+                        .join(catalogs_filter_update(
+                            &mut self.catalogs,
+                            &self.pages,
+                            &ctx.profile.parental_controls,
+                        ))
+                        .join(recommendations_update(
+                            &mut self.recommendations,
+                            &self.selected,
+                            &self.catalogs,
+                            &ctx.taste,
+                            &ctx.library,
+                        ))
+                })
                 .unwrap_or_else(|| Effects::none().unchanged()),
             Msg::Internal(Internal::ProfileChanged) => {
-                catalogs_update::<E>(&mut self.catalogs, &self.selected, None, &ctx.profile)
+                // the parental controls might have been locked or unlocked
+                let catalogs_effects =
+                    catalogs_update::<E>(&mut self.pages, &self.selected, None, &ctx.profile).join(
+                        catalogs_filter_update(
+                            &mut self.catalogs,
+                            &self.pages,
+                            &ctx.profile.parental_controls,
+                        ),
+                    );
+                let recommendations_effects = recommendations_update(
+                    &mut self.recommendations,
+                    &self.selected,
+                    &self.catalogs,
+                    &ctx.taste,
+                    &ctx.library,
+                );
+                catalogs_effects.join(recommendations_effects)
             }
-            Msg::Internal(Internal::LibraryChanged(_)) => Effects::none(),
+            Msg::Internal(Internal::LibraryChanged(_)) => recommendations_update(
+                &mut self.recommendations,
+                &self.selected,
+                &self.catalogs,
+                &ctx.taste,
+                &ctx.library,
+            ),
+            Msg::Internal(Internal::TasteChanged) => recommendations_update(
+                &mut self.recommendations,
+                &self.selected,
+                &self.catalogs,
+                &ctx.taste,
+                &ctx.library,
+            ),
             _ => Effects::none().unchanged(),
         }
     }
@@ -192,20 +263,7 @@ fn catalogs_update<E: Env + 'static>(
                                     request: request.to_owned(),
                                     content: Some(Loadable::Loading),
                                 }],
-                                Some(
-                                    EffectFuture::Concurrent(
-                                        E::addon_transport(&request.base)
-                                            .resource(&request.path)
-                                            .map(|result| {
-                                                Msg::Internal(Internal::ResourceRequestResult(
-                                                    request,
-                                                    Box::new(result),
-                                                ))
-                                            })
-                                            .boxed_env(),
-                                    )
-                                    .into(),
-                                ),
+                                Some(resource_request_effects::<E>(&request)),
                             ),
                             _ => (
                                 vec![ResourceLoadable {
@@ -220,7 +278,61 @@ fn catalogs_update<E: Env + 'static>(
         }
         _ => Default::default(),
     };
-    Effects::many(effects.into_iter().flatten().collect())
+    Effects::many(effects.into_iter().flatten().flatten().collect())
         .unchanged()
         .join(eq_update(catalogs, next_catalogs))
 }
+
+fn catalogs_filter_update(
+    catalogs: &mut Vec<Catalog<MetaItemPreview>>,
+    pages: &[Catalog<MetaItemPreview>],
+    parental_controls: &ParentalControls,
+) -> Effects {
+    let next_catalogs = pages
+        .iter()
+        .map(|pages| {
+            resources_filtered(pages, |meta_item| !parental_controls.is_blocked(meta_item))
+        })
+        .collect();
+    eq_update(catalogs, next_catalogs)
+}
+
+fn recommendations_update(
+    recommendations: &mut Vec<Recommendation>,
+    selected: &Option<Selected>,
+    catalogs: &[Catalog<MetaItemPreview>],
+    taste: &TasteBucket,
+    library: &LibraryBucket,
+) -> Effects {
+    let next_recommendations = match selected {
+        Some(selected) if selected.extra.is_empty() => {
+            let taste_profile = TasteProfile::new(taste);
+            catalogs
+                .iter()
+                .flatten()
+                .filter_map(|page| match &page.content {
+                    Some(Loadable::Ready(meta_items)) => Some((meta_items, &page.request)),
+                    _ => None,
+                })
+                .flat_map(|(meta_items, request)| {
+                    meta_items.iter().map(move |meta_item| (meta_item, request))
+                })
+                .unique_by(|(meta_item, _)| &meta_item.id)
+                // the items which the user already knows
+                .filter(|(meta_item, _)| {
+                    !taste.items.contains_key(&meta_item.id)
+                        && library
+                            .items
+                            .get(&meta_item.id)
+                            .map(|library_item| library_item.removed && !library_item.watched())
+                            .unwrap_or(true)
+                })
+                .filter_map(|(meta_item, request)| taste_profile.recommend(meta_item, request))
+                .sorted_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(Ordering::Equal))
+                .take(RECOMMENDATIONS_COUNT)
+                .collect()
+        }
+        _ => vec![],
+    };
+    eq_update(recommendations, next_recommendations)
+}
@@ -3,6 +3,7 @@ use crate::models::common::Loadable;
 use crate::runtime::msg::{Internal, Msg};
 use crate::runtime::{EffectFuture, Effects, Env, EnvError, EnvFutureExt};
 use crate::types::addon::{Descriptor, Manifest};
+use crate::types::addon_health::AddonRequest;
 use futures::FutureExt;
 use serde::Serialize;
 use url::Url;
@@ -46,14 +47,17 @@ pub fn descriptor_update<E: Env + 'static>(
                     transport_url: transport_url.to_owned(),
                     content: Loadable::Loading,
                 });
-                Effects::future(EffectFuture::Concurrent(
+                Effects::msg(Msg::Internal(Internal::AddonRequestStarted(
+                    AddonRequest::Manifest(transport_url.to_owned()),
+                )))
+                .join(Effects::future(EffectFuture::Concurrent(
                     E::addon_transport(&transport_url)
                         .manifest()
                         .map(move |result| {
                             Msg::Internal(Internal::ManifestRequestResult(transport_url, result))
                         })
                         .boxed_env(),
-                ))
+                )))
             } else {
                 Effects::none().unchanged()
             }
@@ -10,5 +10,11 @@ pub use eq_update::*;
 mod loadable;
 pub use loadable::*;
 
+mod rank_streams;
+pub use rank_streams::*;
+
+mod rank_subtitles;
+pub use rank_subtitles::*;
+
 mod resource_loadable;
 pub use resource_loadable::*;
@@ -0,0 +1,142 @@
+use std::cmp;
+
+use serde::Serialize;
+
+use crate::models::common::{Loadable, ResourceLoadable};
+use crate::types::addon::ResourceRequest;
+use crate::types::profile::Settings;
+use crate::types::resource::{Stream, StreamInfo};
+use crate::types::stream_probes::StreamHealth;
+
+const LANGUAGE_WEIGHT: i64 = 1000;
+const RESOLUTION_WEIGHT: i64 = 100;
+const HDR_WEIGHT: i64 = 150;
+const CODEC_WEIGHT: i64 = 50;
+const AUDIO_CHANNEL_WEIGHT: i64 = 10;
+const MAX_SEEDERS_SCORE: i64 = 50;
+const NO_SEEDERS_PENALTY: i64 = 500;
+const MAX_SIZE_PENALTY: i64 = 10_000;
+
+/// A [`Stream`] of any addon ranked against the [`StreamPreferences`].
+///
+/// [`StreamPreferences`]: crate::types::profile::StreamPreferences
+#[derive(Clone, PartialEq, Eq, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RankedStream {
+    pub stream: Stream,
+    /// The request of the addon which returned the stream
+    pub request: ResourceRequest,
+    pub info: StreamInfo,
+    pub score: i64,
+    /// Set only when the stream health check is enabled, see [`StreamProbesBucket`].
+    ///
+    /// [`StreamProbesBucket`]: crate::types::stream_probes::StreamProbesBucket
+    pub health: Option<StreamHealth>,
+}
+
+/// Merges the loaded streams of all addons in a single list, the best ranked stream first.
+///
+/// Streams with the same source are returned only once, with the best rank.
+/// Streams with equal scores keep the order of the addons.
+pub fn rank_streams(
+    streams: &[ResourceLoadable<Vec<Stream>>],
+    settings: &Settings,
+) -> Vec<RankedStream> {
+    let mut ranked_streams = streams
+        .iter()
+        .filter_map(|resource| match &resource.content {
+            Some(Loadable::Ready(streams)) => Some((&resource.request, streams)),
+            _ => None,
+        })
+        .flat_map(|(request, streams)| {
+            streams.iter().map(move |stream| {
+                let info = StreamInfo::from(stream);
+                RankedStream {
+                    score: score(&info, settings),
+                    stream: stream.to_owned(),
+                    request: request.to_owned(),
+                    info,
+                    health: None,
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    ranked_streams.sort_by(|a, b| b.score.cmp(&a.score));
+    let mut unique_streams: Vec<RankedStream> = Vec::with_capacity(ranked_streams.len());
+    for ranked_stream in ranked_streams {
+        if !unique_streams
+            .iter()
+            .any(|unique_stream| unique_stream.stream.source == ranked_stream.stream.source)
+        {
+            unique_streams.push(ranked_stream);
+        }
+    }
+    unique_streams
+}
+
+fn score(info: &StreamInfo, settings: &Settings) -> i64 {
+    let preferences = &settings.stream_preferences;
+    let preferred_languages = if preferences.preferred_languages.is_empty() {
+        [&settings.audio_language, &settings.secondary_audio_language]
+            .into_iter()
+            .flatten()
+            .cloned()
+            .collect()
+    } else {
+        preferences.preferred_languages.to_owned()
+    };
+    // streams without any language are expected to be in the original language
+    let language_score = if info.languages.is_empty() {
+        0
+    } else {
+        preferred_languages
+            .iter()
+            .position(|language| info.languages.contains(language))
+            .map(|position| LANGUAGE_WEIGHT * (preferred_languages.len() - position) as i64)
+            .unwrap_or(-LANGUAGE_WEIGHT)
+    };
+    let resolution_score = match (info.resolution, preferences.max_resolution) {
+        (Some(resolution), Some(max_resolution)) if resolution > max_resolution => {
+            -RESOLUTION_WEIGHT
+        }
+        (Some(resolution), _) => RESOLUTION_WEIGHT * (resolution as i64 + 1),
+        (None, _) => 0,
+    };
+    let hdr_score = if preferences.prefer_hdr && info.hdr {
+        HDR_WEIGHT
+    } else {
+        0
+    };
+    let codec_score = info
+        .codec
+        .and_then(|codec| {
+            preferences
+                .preferred_codecs
+                .iter()
+                .position(|preferred_codec| *preferred_codec == codec)
+        })
+        .map(|position| CODEC_WEIGHT * (preferences.preferred_codecs.len() - position) as i64)
+        .unwrap_or_default();
+    let audio_score = match info.audio_channels {
+        Some(audio_channels) if settings.surround_sound => {
+            AUDIO_CHANNEL_WEIGHT * i64::from(audio_channels)
+        }
+        _ => 0,
+    };
+    let seeders_score = match info.seeders {
+        Some(0) => -NO_SEEDERS_PENALTY,
+        Some(seeders) => cmp::min(i64::from(seeders) / 10, MAX_SEEDERS_SCORE),
+        None => 0,
+    };
+    let size_score = match (info.size, preferences.max_size) {
+        (Some(size), Some(max_size)) if size > max_size => -MAX_SIZE_PENALTY,
+        _ => 0,
+    };
+    language_score
+        + resolution_score
+        + hdr_score
+        + codec_score
+        + audio_score
+        + seeders_score
+        + size_score
+}
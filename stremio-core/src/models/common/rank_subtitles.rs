@@ -0,0 +1,244 @@
+use serde::Serialize;
+
+use crate::models::common::{Loadable, ResourceLoadable};
+use crate::types::addon::ResourceRequest;
+use crate::types::profile::Settings;
+use crate::types::resource::{language_code, Subtitles};
+use crate::types::streams::SubtitleTrack;
+
+const PREVIOUS_TRACK_WEIGHT: i64 = 100_000;
+const PREVIOUS_LANGUAGE_WEIGHT: i64 = 10_000;
+const LANGUAGE_WEIGHT: i64 = 1000;
+const FORCED_PENALTY: i64 = 500;
+const HEARING_IMPAIRED_PENALTY: i64 = 100;
+
+/// The ISO 639-1 and the regional codes of the subtitles languages and their ISO 639-2 codes
+const SUBTITLES_LANGUAGE_CODES: &[(&str, &str)] = &[
+    ("en", "eng"),
+    ("it", "ita"),
+    ("fr", "fre"),
+    ("es", "spa"),
+    ("ea", "spa"),
+    ("spl", "spa"),
+    ("de", "ger"),
+    ("pt", "por"),
+    ("pb", "por"),
+    ("pob", "por"),
+    ("pom", "por"),
+    ("ru", "rus"),
+    ("pl", "pol"),
+    ("nl", "dut"),
+    ("tr", "tur"),
+    ("bg", "bul"),
+    ("ar", "ara"),
+    ("hi", "hin"),
+    ("ja", "jpn"),
+    ("ko", "kor"),
+    ("zh", "chi"),
+    ("zht", "chi"),
+    ("zhe", "chi"),
+    ("ze", "chi"),
+];
+
+const FORCED_MARKERS: &[&str] = &["forced"];
+const HEARING_IMPAIRED_MARKERS: &[&str] = &["sdh", "hi", "cc", "hoh", "hearing"];
+
+/// Why the subtitles are ranked above the ones in other languages.
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Debug)]
+pub enum SubtitlesMatch {
+    /// The subtitles selected the last time, see [`StreamItemState::subtitle_track`]
+    ///
+    /// [`StreamItemState::subtitle_track`]: crate::types::streams::StreamItemState::subtitle_track
+    PreviousTrack,
+    /// In the language of the subtitles selected the last time, usually for another video
+    PreviousLanguage,
+    /// In the [`Settings::subtitles_language`]
+    PreferredLanguage,
+    /// In the [`Settings::secondary_subtitles_language`]
+    SecondaryLanguage,
+}
+
+/// [`Subtitles`] of any addon ranked against the subtitles languages in the [`Settings`].
+#[derive(Clone, PartialEq, Eq, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RankedSubtitles {
+    pub subtitles: Subtitles,
+    /// The request of the addon which returned the subtitles
+    pub request: ResourceRequest,
+    /// The ISO 639-2 code of the `lang` when it's recognized, otherwise the lowercase `lang`
+    pub language: String,
+    /// Only the foreign parts of the dialogue are subtitled
+    pub forced: bool,
+    /// Sounds and speakers are described (SDH)
+    pub hearing_impaired: bool,
+    pub subtitles_match: Option<SubtitlesMatch>,
+    pub score: i64,
+}
+
+/// The subtitles track which should be selected when the playback starts.
+#[derive(Clone, PartialEq, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DefaultSubtitles {
+    /// Either the previous embedded track or one of the [`RankedSubtitles`]
+    pub track: SubtitleTrack,
+    pub subtitles_match: SubtitlesMatch,
+}
+
+/// Merges the loaded subtitles of all addons in a single list, the best ranked subtitles first.
+///
+/// Subtitles with the same url are returned only once, with the best rank.
+/// Subtitles with equal scores keep the order of the addons.
+pub fn rank_subtitles(
+    subtitles: &[ResourceLoadable<Vec<Subtitles>>],
+    settings: &Settings,
+    previous_track: Option<&SubtitleTrack>,
+) -> Vec<RankedSubtitles> {
+    let previous_language = previous_track
+        .and_then(|track| track.language.as_deref())
+        .map(normalize_language);
+    let preferred_languages = [
+        (
+            &settings.subtitles_language,
+            SubtitlesMatch::PreferredLanguage,
+        ),
+        (
+            &settings.secondary_subtitles_language,
+            SubtitlesMatch::SecondaryLanguage,
+        ),
+    ]
+    .into_iter()
+    .filter_map(|(language, subtitles_match)| {
+        language
+            .as_deref()
+            .map(|language| (normalize_language(language), subtitles_match))
+    })
+    .collect::<Vec<_>>();
+    let mut ranked_subtitles = subtitles
+        .iter()
+        .filter_map(|resource| match &resource.content {
+            Some(Loadable::Ready(subtitles)) => Some((&resource.request, subtitles)),
+            _ => None,
+        })
+        .flat_map(|(request, subtitles)| {
+            subtitles.iter().map(|subtitles| {
+                let language = normalize_language(&subtitles.lang);
+                let markers = markers(subtitles);
+                let forced = FORCED_MARKERS
+                    .iter()
+                    .any(|marker| markers.iter().any(|word| word == marker));
+                let hearing_impaired = HEARING_IMPAIRED_MARKERS
+                    .iter()
+                    .any(|marker| markers.iter().any(|word| word == marker));
+                let previous =
+                    previous_track.is_some_and(|track| !track.embedded && track.id == subtitles.id);
+                let preferred_position = preferred_languages
+                    .iter()
+                    .position(|(preferred_language, _)| *preferred_language == language);
+                let subtitles_match = if previous {
+                    Some(SubtitlesMatch::PreviousTrack)
+                } else if previous_language.as_ref() == Some(&language) {
+                    Some(SubtitlesMatch::PreviousLanguage)
+                } else {
+                    preferred_position.map(|position| preferred_languages[position].1)
+                };
+                let language_score = preferred_position
+                    .map(|position| LANGUAGE_WEIGHT * (preferred_languages.len() - position) as i64)
+                    .unwrap_or_default();
+                let match_score = match subtitles_match {
+                    Some(SubtitlesMatch::PreviousTrack) => PREVIOUS_TRACK_WEIGHT,
+                    Some(SubtitlesMatch::PreviousLanguage) => PREVIOUS_LANGUAGE_WEIGHT,
+                    _ => 0,
+                };
+                let forced_score = if forced { -FORCED_PENALTY } else { 0 };
+                let hearing_impaired_score = if hearing_impaired {
+                    -HEARING_IMPAIRED_PENALTY
+                } else {
+                    0
+                };
+                let score = match_score + language_score + forced_score + hearing_impaired_score;
+                RankedSubtitles {
+                    subtitles: subtitles.to_owned(),
+                    request: request.to_owned(),
+                    language,
+                    forced,
+                    hearing_impaired,
+                    subtitles_match,
+                    score,
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    ranked_subtitles.sort_by(|a, b| b.score.cmp(&a.score));
+    let mut unique_subtitles: Vec<RankedSubtitles> = Vec::with_capacity(ranked_subtitles.len());
+    for ranked in ranked_subtitles {
+        if !unique_subtitles
+            .iter()
+            .any(|unique| unique.subtitles.url == ranked.subtitles.url)
+        {
+            unique_subtitles.push(ranked);
+        }
+    }
+    unique_subtitles
+}
+
+/// Chooses the previous embedded track or the best ranked subtitles with a [`SubtitlesMatch`].
+///
+/// Forced subtitles are chosen only when they were selected the last time,
+/// as they are meant for a dialogue in the language of the audio.
+/// Subtitles in other languages are never chosen.
+pub fn default_subtitles(
+    ranked_subtitles: &[RankedSubtitles],
+    previous_track: Option<&SubtitleTrack>,
+) -> Option<DefaultSubtitles> {
+    match previous_track {
+        Some(track) if track.embedded => Some(DefaultSubtitles {
+            track: track.to_owned(),
+            subtitles_match: SubtitlesMatch::PreviousTrack,
+        }),
+        _ => ranked_subtitles
+            .iter()
+            .find_map(|ranked| match ranked.subtitles_match {
+                Some(subtitles_match)
+                    if !ranked.forced || subtitles_match == SubtitlesMatch::PreviousTrack =>
+                {
+                    Some(DefaultSubtitles {
+                        track: SubtitleTrack {
+                            id: ranked.subtitles.id.to_owned(),
+                            embedded: false,
+                            language: Some(ranked.subtitles.lang.to_owned()),
+                        },
+                        subtitles_match,
+                    })
+                }
+                _ => None,
+            }),
+    }
+}
+
+/// Addons use ISO 639-2 codes, but some of them send `eng-forced`, `en`, `pt-BR`, `pob` or `English`
+fn normalize_language(lang: &str) -> String {
+    let lang = lang.trim().to_lowercase();
+    let base = lang
+        .split(|character: char| !character.is_alphanumeric())
+        .next()
+        .unwrap_or_default();
+    SUBTITLES_LANGUAGE_CODES
+        .iter()
+        .find(|(code, _)| *code == base)
+        .map(|(_, language)| *language)
+        .or_else(|| language_code(base))
+        .map(ToOwned::to_owned)
+        .unwrap_or_else(|| base.to_owned())
+}
+
+/// The lowercase words of the `id` and the url path of the subtitles.
+///
+/// The `lang` is not included, as the `hi` marker is also the ISO 639-1 code of Hindi.
+fn markers(subtitles: &Subtitles) -> Vec<String> {
+    [subtitles.id.as_str(), subtitles.url.path()]
+        .into_iter()
+        .flat_map(|text| text.split(|character: char| !character.is_alphanumeric()))
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_lowercase())
+        .collect()
+}
@@ -1,9 +1,11 @@
 use std::{convert::TryFrom, fmt, fmt::Debug};
 
+use crate::addon_transport::fetch_resource_with_policy;
 use crate::models::common::{eq_update, Loadable};
 use crate::runtime::msg::{Internal, Msg};
-use crate::runtime::{EffectFuture, Effects, Env, EnvError, EnvFutureExt};
+use crate::runtime::{Effect, EffectFuture, Effects, Env, EnvError, EnvFutureExt};
 use crate::types::addon::{AggrRequest, Descriptor, ResourceRequest, ResourceResponse};
+use crate::types::addon_health::AddonRequest;
 
 use enclose::enclose;
 use futures::FutureExt;
@@ -97,6 +99,47 @@ impl<T> ResourceLoadable<Vec<T>> {
     }
 }
 
+/// Requests the resource from the addon following the [`Env::addon_request_policy`].
+///
+/// The request is reported with [`Internal::AddonRequestStarted`] before it's sent
+/// for measuring the latency of the addon and it can be cancelled with [`resource_cancel_effects`].
+pub fn resource_request_effects<E: Env + 'static>(request: &ResourceRequest) -> Vec<Effect> {
+    vec![
+        Effect::Msg(Box::new(Msg::Internal(Internal::AddonRequestStarted(
+            AddonRequest::Resource(request.to_owned()),
+        )))),
+        EffectFuture::Cancellable(
+            AddonRequest::Resource(request.to_owned()),
+            fetch_resource_with_policy::<E>(&request.base, &request.path)
+                .map(enclose!((request) move |result| {
+                    Msg::Internal(Internal::ResourceRequestResult(request, Box::new(result)))
+                }))
+                .boxed_env(),
+        )
+        .into(),
+    ]
+}
+
+/// Cancels the request of the resource if it's still loading.
+pub fn resource_cancel_effects<T>(resource: &ResourceLoadable<T>) -> Effects {
+    match resource.content {
+        Some(Loadable::Loading) => Effects::one(Effect::Cancel(AddonRequest::Resource(
+            resource.request.to_owned(),
+        )))
+        .unchanged(),
+        _ => Effects::none().unchanged(),
+    }
+}
+
+pub fn resources_cancel_effects<'a, T: 'a>(
+    resources: impl IntoIterator<Item = &'a ResourceLoadable<T>>,
+) -> Effects {
+    resources
+        .into_iter()
+        .map(resource_cancel_effects)
+        .fold(Effects::none().unchanged(), Effects::join)
+}
+
 pub fn resource_update<E, T>(resource: &mut ResourceLoadable<T>, action: ResourceAction) -> Effects
 where
     E: Env + 'static,
@@ -106,16 +149,10 @@ where
         ResourceAction::ResourceRequested { request }
             if resource.request != *request || resource.content.is_none() =>
         {
+            let cancel_effects = resource_cancel_effects(resource);
             request.clone_into(&mut resource.request);
             resource.content = Some(Loadable::Loading);
-            Effects::future(EffectFuture::Concurrent(
-                E::addon_transport(&request.base)
-                    .resource(&request.path)
-                    .map(enclose!((request) move |result| {
-                        Msg::Internal(Internal::ResourceRequestResult(request, Box::new(result)))
-                    }))
-                    .boxed_env(),
-            ))
+            cancel_effects.join(Effects::many(resource_request_effects::<E>(request)))
         }
         ResourceAction::ResourceRequestResult {
             request, result, ..
@@ -149,6 +186,31 @@ where
     }
 }
 
+/// The loaded resources with only the items matching the predicate,
+/// e.g. the ones which are not blocked by the parental controls.
+pub fn resources_filtered<T: Clone>(
+    resources: &[ResourceLoadable<Vec<T>>],
+    predicate: impl Fn(&T) -> bool,
+) -> Vec<ResourceLoadable<Vec<T>>> {
+    resources
+        .iter()
+        .map(|resource| ResourceLoadable {
+            request: resource.request.to_owned(),
+            content: resource.content.as_ref().map(|content| match content {
+                Loadable::Ready(items) => Loadable::Ready(
+                    items
+                        .iter()
+                        .filter(|item| predicate(item))
+                        .cloned()
+                        .collect(),
+                ),
+                Loadable::Loading => Loadable::Loading,
+                Loadable::Err(error) => Loadable::Err(error.to_owned()),
+            }),
+        })
+        .collect()
+}
+
 pub fn resources_update<E, T>(
     resources: &mut Vec<ResourceLoadable<T>>,
     action: ResourcesAction,
@@ -183,26 +245,20 @@ where
                                     request: request.to_owned(),
                                     content: Some(Loadable::Loading),
                                 },
-                                Some(
-                                    EffectFuture::Concurrent(
-                                        E::addon_transport(&request.base)
-                                            .resource(&request.path)
-                                            .map(|result| {
-                                                Msg::Internal(Internal::ResourceRequestResult(
-                                                    request,
-                                                    Box::new(result),
-                                                ))
-                                            })
-                                            .boxed_env(),
-                                    )
-                                    .into(),
-                                ),
+                                Some(resource_request_effects::<E>(&request)),
                             )
                         })
                 })
                 .unzip::<_, _, Vec<_>, Vec<_>>();
-            Effects::many(effects.into_iter().flatten().collect())
-                .unchanged()
+            // the requests which are still loading and are not needed anymore
+            let cancel_effects = resources_cancel_effects(resources.iter().filter(|resource| {
+                force
+                    || !next_resources
+                        .iter()
+                        .any(|next_resource| next_resource.request == resource.request)
+            }));
+            cancel_effects
+                .join(Effects::many(effects.into_iter().flatten().flatten().collect()).unchanged())
                 .join(eq_update(resources, next_resources))
         }
         ResourcesAction::ResourceRequestResult {
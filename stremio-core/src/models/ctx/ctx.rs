@@ -3,9 +3,10 @@ use crate::{
     models::{
         common::{DescriptorLoadable, Loadable, ResourceLoadable},
         ctx::{
-            update_events, update_library, update_notifications, update_profile,
-            update_search_history, update_streaming_server_urls, update_streams,
-            update_trakt_addon, CtxError, OtherError,
+            update_addon_health, update_backup, update_events, update_library, update_lists,
+            update_local_profiles, update_notifications, update_outbox, update_profile,
+            update_search_history, update_stream_probes, update_streaming_server_urls,
+            update_streams, update_taste, update_trakt_addon, CtxError, OtherError,
         },
     },
     runtime::{
@@ -13,18 +14,24 @@ use crate::{
         Effect, EffectFuture, Effects, Env, EnvFutureExt, Update,
     },
     types::{
+        addon_health::AddonHealthBucket,
         api::{
             fetch_api, APIRequest, APIResult, AuthRequest, AuthResponse, CollectionResponse,
             DatastoreCommand, DatastoreRequest, LibraryItemsResponse, SuccessResponse,
         },
         events::{DismissedEventsBucket, Events},
-        library::LibraryBucket,
+        library::{LibraryBucket, LibrarySyncBucket},
+        lists::ListsBucket,
+        local_profiles::LocalProfilesBucket,
         notifications::NotificationsBucket,
+        outbox::OutboxBucket,
         profile::{Auth, AuthKey, Profile},
         resource::MetaItem,
         search_history::SearchHistoryBucket,
         server_urls::ServerUrlsBucket,
+        stream_probes::StreamProbesBucket,
         streams::StreamsBucket,
+        taste::TasteBucket,
     },
 };
 
@@ -52,6 +59,9 @@ pub struct Ctx {
     // TODO SearchesBucket
     #[serde(skip)]
     pub library: LibraryBucket,
+    /// The library as it was last synced with the API and the unresolved sync conflicts
+    #[serde(skip)]
+    pub library_sync: LibrarySyncBucket,
     pub notifications: NotificationsBucket,
     #[serde(skip)]
     pub streams: StreamsBucket,
@@ -61,6 +71,22 @@ pub struct Ctx {
     pub search_history: SearchHistoryBucket,
     #[serde(skip)]
     pub dismissed_events: DismissedEventsBucket,
+    /// The changes which are not yet pushed to the API
+    #[serde(skip)]
+    pub outbox: OutboxBucket,
+    pub local_profiles: LocalProfilesBucket,
+    /// The recent pre-flight requests of the stream URLs, kept only in memory
+    #[serde(skip)]
+    pub stream_probes: StreamProbesBucket,
+    /// The request stats of the installed addons
+    #[serde(skip)]
+    pub addon_health: AddonHealthBucket,
+    /// The watched and rated items used for the recommendations
+    #[serde(skip)]
+    pub taste: TasteBucket,
+    /// The lists curated by the user
+    #[serde(skip)]
+    pub lists: ListsBucket,
     #[serde(skip)]
     #[cfg_attr(test, derivative(Default(value = "CtxStatus::Ready")))]
     pub status: CtxStatus,
@@ -77,25 +103,54 @@ pub struct Ctx {
     pub events: Events,
 }
 
-impl Ctx {
-    pub fn new(
-        profile: Profile,
-        library: LibraryBucket,
-        streams: StreamsBucket,
-        streaming_server_urls: ServerUrlsBucket,
-        notifications: NotificationsBucket,
+/// The persisted buckets from which the [`Ctx`] is created
+#[derive(Clone, Debug)]
+pub struct CtxBuckets {
+    pub library: LibraryBucket,
+    pub streams: StreamsBucket,
+    pub streaming_server_urls: ServerUrlsBucket,
+    pub notifications: NotificationsBucket,
+    pub search_history: SearchHistoryBucket,
+    pub dismissed_events: DismissedEventsBucket,
+    pub library_sync: LibrarySyncBucket,
+    pub outbox: OutboxBucket,
+    pub local_profiles: LocalProfilesBucket,
+    pub addon_health: AddonHealthBucket,
+    pub taste: TasteBucket,
+    pub lists: ListsBucket,
+}
 
-        search_history: SearchHistoryBucket,
-        dismissed_events: DismissedEventsBucket,
-    ) -> Self {
+impl Ctx {
+    pub fn new(profile: Profile, buckets: CtxBuckets) -> Self {
+        let CtxBuckets {
+            library,
+            streams,
+            streaming_server_urls,
+            notifications,
+            search_history,
+            dismissed_events,
+            library_sync,
+            outbox,
+            local_profiles,
+            addon_health,
+            taste,
+            lists,
+        } = buckets;
         Self {
             profile,
             library,
+            library_sync,
             streams,
             streaming_server_urls,
             search_history,
             dismissed_events,
+            outbox,
+            local_profiles,
             notifications,
+            stream_probes: StreamProbesBucket::default(),
+            addon_health,
+            taste,
+            lists,
             trakt_addon: None,
             notification_catalogs: vec![],
             status: CtxStatus::Ready,
@@ -127,18 +182,47 @@ impl<E: Env + 'static> Update<E> for Ctx {
                 };
                 let profile_effects =
                     update_profile::<E>(&mut self.profile, &mut self.streams, &self.status, msg);
-                let library_effects =
-                    update_library::<E>(&mut self.library, &self.profile, &self.status, msg);
-                let streams_effects = update_streams::<E>(&mut self.streams, &self.status, msg);
+                let library_effects = update_library::<E>(
+                    &mut self.library,
+                    &mut self.library_sync,
+                    &self.profile,
+                    &self.local_profiles,
+                    &self.status,
+                    msg,
+                );
+                let streams_effects =
+                    update_streams::<E>(&mut self.streams, &self.local_profiles, &self.status, msg);
                 let server_urls_effects = update_streaming_server_urls::<E>(
                     &mut self.streaming_server_urls,
                     &self.status,
                     msg,
                 );
-                let search_history_effects =
-                    update_search_history::<E>(&mut self.search_history, &self.status, msg);
+                let search_history_effects = update_search_history::<E>(
+                    &mut self.search_history,
+                    &self.local_profiles,
+                    &self.status,
+                    msg,
+                );
+                let taste_effects =
+                    update_taste::<E>(&mut self.taste, &self.library, &self.status, msg);
+                let lists_effects = update_lists::<E>(
+                    &mut self.lists,
+                    &self.library,
+                    &self.profile,
+                    &self.local_profiles,
+                    &self.status,
+                    msg,
+                );
                 let events_effects =
                     update_events::<E>(&mut self.events, &mut self.dismissed_events, msg);
+                let outbox_effects =
+                    update_outbox::<E>(&mut self.outbox, &self.profile, &self.status, msg);
+                let local_profiles_effects = update_local_profiles::<E>(
+                    &mut self.local_profiles,
+                    &self.profile,
+                    &self.status,
+                    msg,
+                );
                 let trakt_addon_effects = update_trakt_addon::<E>(
                     &mut self.trakt_addon,
                     &self.profile,
@@ -163,14 +247,24 @@ impl<E: Env + 'static> Update<E> for Ctx {
                     .join(server_urls_effects)
                     .join(search_history_effects)
                     .join(events_effects)
+                    .join(outbox_effects)
+                    .join(local_profiles_effects)
                     .join(trakt_addon_effects)
                     .join(notifications_effects)
+                    .join(taste_effects)
+                    .join(lists_effects)
             }
             Msg::Internal(Internal::CtxAuthResult(auth_request, result)) => {
                 let profile_effects =
                     update_profile::<E>(&mut self.profile, &mut self.streams, &self.status, msg);
-                let library_effects =
-                    update_library::<E>(&mut self.library, &self.profile, &self.status, msg);
+                let library_effects = update_library::<E>(
+                    &mut self.library,
+                    &mut self.library_sync,
+                    &self.profile,
+                    &self.local_profiles,
+                    &self.status,
+                    msg,
+                );
                 let trakt_addon_effects = update_trakt_addon::<E>(
                     &mut self.trakt_addon,
                     &self.profile,
@@ -185,16 +279,39 @@ impl<E: Env + 'static> Update<E> for Ctx {
                     &self.status,
                     msg,
                 );
-                let streams_effects = update_streams::<E>(&mut self.streams, &self.status, msg);
+                let streams_effects =
+                    update_streams::<E>(&mut self.streams, &self.local_profiles, &self.status, msg);
                 let server_urls_effects = update_streaming_server_urls::<E>(
                     &mut self.streaming_server_urls,
                     &self.status,
                     msg,
                 );
-                let search_history_effects =
-                    update_search_history::<E>(&mut self.search_history, &self.status, msg);
+                let search_history_effects = update_search_history::<E>(
+                    &mut self.search_history,
+                    &self.local_profiles,
+                    &self.status,
+                    msg,
+                );
+                let taste_effects =
+                    update_taste::<E>(&mut self.taste, &self.library, &self.status, msg);
+                let lists_effects = update_lists::<E>(
+                    &mut self.lists,
+                    &self.library,
+                    &self.profile,
+                    &self.local_profiles,
+                    &self.status,
+                    msg,
+                );
                 let events_effects =
                     update_events::<E>(&mut self.events, &mut self.dismissed_events, msg);
+                let outbox_effects =
+                    update_outbox::<E>(&mut self.outbox, &self.profile, &self.status, msg);
+                let local_profiles_effects = update_local_profiles::<E>(
+                    &mut self.local_profiles,
+                    &self.profile,
+                    &self.status,
+                    msg,
+                );
                 let ctx_effects = match &self.status {
                     CtxStatus::Loading(loading_auth_request)
                         if loading_auth_request == auth_request =>
@@ -264,14 +381,25 @@ impl<E: Env + 'static> Update<E> for Ctx {
                     .join(notifications_effects)
                     .join(search_history_effects)
                     .join(events_effects)
+                    .join(outbox_effects)
+                    .join(local_profiles_effects)
+                    .join(taste_effects)
+                    .join(lists_effects)
                     .join(ctx_effects)
             }
             _ => {
                 let profile_effects =
                     update_profile::<E>(&mut self.profile, &mut self.streams, &self.status, msg);
-                let library_effects =
-                    update_library::<E>(&mut self.library, &self.profile, &self.status, msg);
-                let streams_effects = update_streams::<E>(&mut self.streams, &self.status, msg);
+                let library_effects = update_library::<E>(
+                    &mut self.library,
+                    &mut self.library_sync,
+                    &self.profile,
+                    &self.local_profiles,
+                    &self.status,
+                    msg,
+                );
+                let streams_effects =
+                    update_streams::<E>(&mut self.streams, &self.local_profiles, &self.status, msg);
                 let server_urls_effects = update_streaming_server_urls::<E>(
                     &mut self.streaming_server_urls,
                     &self.status,
@@ -291,10 +419,36 @@ impl<E: Env + 'static> Update<E> for Ctx {
                     &self.status,
                     msg,
                 );
-                let search_history_effects =
-                    update_search_history::<E>(&mut self.search_history, &self.status, msg);
+                let search_history_effects = update_search_history::<E>(
+                    &mut self.search_history,
+                    &self.local_profiles,
+                    &self.status,
+                    msg,
+                );
+                let taste_effects =
+                    update_taste::<E>(&mut self.taste, &self.library, &self.status, msg);
+                let lists_effects = update_lists::<E>(
+                    &mut self.lists,
+                    &self.library,
+                    &self.profile,
+                    &self.local_profiles,
+                    &self.status,
+                    msg,
+                );
                 let events_effects =
                     update_events::<E>(&mut self.events, &mut self.dismissed_events, msg);
+                let outbox_effects =
+                    update_outbox::<E>(&mut self.outbox, &self.profile, &self.status, msg);
+                let local_profiles_effects = update_local_profiles::<E>(
+                    &mut self.local_profiles,
+                    &self.profile,
+                    &self.status,
+                    msg,
+                );
+                let stream_probes_effects = update_stream_probes::<E>(&mut self.stream_probes, msg);
+                let addon_health_effects =
+                    update_addon_health::<E>(&mut self.addon_health, &self.profile, msg);
+                let backup_effects = update_backup::<E>(self, msg);
                 profile_effects
                     .join(library_effects)
                     .join(streams_effects)
@@ -303,6 +457,13 @@ impl<E: Env + 'static> Update<E> for Ctx {
                     .join(notifications_effects)
                     .join(search_history_effects)
                     .join(events_effects)
+                    .join(outbox_effects)
+                    .join(local_profiles_effects)
+                    .join(stream_probes_effects)
+                    .join(addon_health_effects)
+                    .join(taste_effects)
+                    .join(lists_effects)
+                    .join(backup_effects)
             }
         }
     }
@@ -40,6 +40,15 @@ pub enum OtherError {
     AddonConfigurationRequired,
     UserAddonsAreLocked,
     UserLibraryIsMissing,
+    InvalidParentalControlsPin,
+    ParentalControlsAreLocked,
+    LocalProfileNotFound,
+    LocalProfileCannotBeRemoved,
+    ListNotFound,
+    UnsupportedBackupVersion,
+    InvalidBackupChecksum,
+    TooManyParentalControlsPinAttempts,
+    LocalProfileNameIsEmpty,
 }
 
 impl OtherError {
@@ -53,6 +62,15 @@ impl OtherError {
             OtherError::AddonConfigurationRequired => "Addon requires configuration".to_owned(),
             OtherError::UserAddonsAreLocked => "Fetching Addons from the API failed and we have defaulted the addons to the officials ones until the request succeeds".to_owned(),
             OtherError::UserLibraryIsMissing => "Fetching Library from the API failed and we have defaulted to empty library until the request succeeds".to_owned(),
+            OtherError::InvalidParentalControlsPin => "Parental controls PIN is invalid".to_owned(),
+            OtherError::ParentalControlsAreLocked => "Parental controls must be unlocked first".to_owned(),
+            OtherError::LocalProfileNotFound => "Local profile is not found".to_owned(),
+            OtherError::LocalProfileCannotBeRemoved => "The default and the selected local profiles cannot be removed".to_owned(),
+            OtherError::ListNotFound => "List is not found".to_owned(),
+            OtherError::UnsupportedBackupVersion => "Backup was created by a newer version".to_owned(),
+            OtherError::InvalidBackupChecksum => "Backup is corrupted".to_owned(),
+            OtherError::TooManyParentalControlsPinAttempts => "Too many invalid parental controls PIN attempts, try again later".to_owned(),
+            OtherError::LocalProfileNameIsEmpty => "Local profile name is empty".to_owned(),
         }
     }
     pub fn code(&self) -> u64 {
@@ -65,6 +83,15 @@ impl OtherError {
             OtherError::AddonConfigurationRequired => 6,
             OtherError::UserAddonsAreLocked => 7,
             OtherError::UserLibraryIsMissing => 8,
+            OtherError::InvalidParentalControlsPin => 9,
+            OtherError::ParentalControlsAreLocked => 10,
+            OtherError::LocalProfileNotFound => 11,
+            OtherError::LocalProfileCannotBeRemoved => 12,
+            OtherError::ListNotFound => 13,
+            OtherError::UnsupportedBackupVersion => 14,
+            OtherError::InvalidBackupChecksum => 15,
+            OtherError::TooManyParentalControlsPinAttempts => 16,
+            OtherError::LocalProfileNameIsEmpty => 17,
         }
     }
 }
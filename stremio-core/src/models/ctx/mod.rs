@@ -1,21 +1,42 @@
+mod update_addon_health;
+use update_addon_health::*;
+
+mod update_backup;
+use update_backup::*;
+
 mod update_events;
 use update_events::*;
 
 mod update_library;
 use update_library::*;
 
+mod update_lists;
+use update_lists::*;
+
+mod update_local_profiles;
+use update_local_profiles::*;
+
 mod update_notifications;
 use update_notifications::*;
 
+mod update_outbox;
+use update_outbox::*;
+
 mod update_profile;
 use update_profile::*;
 
 mod update_streams;
 use update_streams::*;
 
+mod update_stream_probes;
+use update_stream_probes::*;
+
 mod update_search_history;
 use update_search_history::*;
 
+mod update_taste;
+use update_taste::*;
+
 mod update_trakt_addon;
 use update_trakt_addon::*;
 
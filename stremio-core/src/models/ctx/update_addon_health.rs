@@ -0,0 +1,112 @@
+use futures::FutureExt;
+use url::Url;
+
+use crate::constants::{ADDON_HEALTH_STORAGE_INTERVAL, ADDON_HEALTH_STORAGE_KEY};
+use crate::models::ctx::CtxError;
+use crate::runtime::msg::{Event, Internal, Msg};
+use crate::runtime::{Effect, EffectFuture, Effects, Env, EnvError, EnvFutureExt};
+use crate::types::addon_health::{AddonHealthBucket, AddonRequest};
+use crate::types::profile::Profile;
+
+pub fn update_addon_health<E: Env + 'static>(
+    addon_health: &mut AddonHealthBucket,
+    profile: &Profile,
+    msg: &Msg,
+) -> Effects {
+    match msg {
+        Msg::Internal(Internal::AddonRequestStarted(request))
+            if is_installed(profile, request.transport_url()) =>
+        {
+            addon_health.pending.push((request.to_owned(), E::now()));
+            Effects::none().unchanged()
+        }
+        Msg::Internal(Internal::AddonRequestCancelled(request)) => {
+            addon_health.cancel(request);
+            Effects::none().unchanged()
+        }
+        Msg::Internal(Internal::ResourceRequestResult(request, result)) => finish_request::<E>(
+            addon_health,
+            &AddonRequest::Resource(request.to_owned()),
+            result.as_ref().as_ref().err(),
+        ),
+        Msg::Internal(Internal::ManifestRequestResult(transport_url, result)) => {
+            finish_request::<E>(
+                addon_health,
+                &AddonRequest::Manifest(transport_url.to_owned()),
+                result.as_ref().err(),
+            )
+        }
+        Msg::Internal(Internal::ProfileChanged) => {
+            let items_len = addon_health.items.len();
+            addon_health
+                .items
+                .retain(|transport_url, _| is_installed(profile, transport_url));
+            addon_health
+                .pending
+                .retain(|(request, _)| is_installed(profile, request.transport_url()));
+            if addon_health.items.len() != items_len {
+                Effects::one(push_addon_health_to_storage::<E>(addon_health))
+                    .unchanged()
+                    .join(Effects::msg(Msg::Internal(Internal::AddonHealthChanged)))
+            } else {
+                Effects::none().unchanged()
+            }
+        }
+        _ => Effects::none().unchanged(),
+    }
+}
+
+fn is_installed(profile: &Profile, transport_url: &Url) -> bool {
+    profile
+        .addons
+        .iter()
+        .any(|addon| addon.transport_url == *transport_url)
+}
+
+/// Records the request in the stats, they are written to the storage
+/// when the status of the addon changes or once per [`ADDON_HEALTH_STORAGE_INTERVAL`].
+fn finish_request<E: Env + 'static>(
+    addon_health: &mut AddonHealthBucket,
+    request: &AddonRequest,
+    error: Option<&EnvError>,
+) -> Effects {
+    let now = E::now();
+    let status = |addon_health: &AddonHealthBucket| {
+        addon_health
+            .items
+            .get(request.transport_url())
+            .and_then(|stats| stats.status())
+    };
+    let prev_status = status(addon_health);
+    if !addon_health.finish(request, now, error) {
+        return Effects::none().unchanged();
+    }
+
+    let storage_effects = if status(addon_health) != prev_status
+        || addon_health.pushed_at.map_or(true, |pushed_at| {
+            now - pushed_at >= ADDON_HEALTH_STORAGE_INTERVAL
+        }) {
+        Effects::one(push_addon_health_to_storage::<E>(addon_health)).unchanged()
+    } else {
+        Effects::none().unchanged()
+    };
+    storage_effects.join(Effects::msg(Msg::Internal(Internal::AddonHealthChanged)))
+}
+
+fn push_addon_health_to_storage<E: Env + 'static>(addon_health: &mut AddonHealthBucket) -> Effect {
+    addon_health.pushed_at = Some(E::now());
+    let mut transport_urls = addon_health.items.keys().cloned().collect::<Vec<_>>();
+    transport_urls.sort();
+    EffectFuture::Sequential(
+        E::set_storage(ADDON_HEALTH_STORAGE_KEY, Some(addon_health))
+            .map(move |result| match result {
+                Ok(_) => Msg::Event(Event::AddonHealthPushedToStorage { transport_urls }),
+                Err(error) => Msg::Event(Event::Error {
+                    error: CtxError::from(error),
+                    source: Box::new(Event::AddonHealthPushedToStorage { transport_urls }),
+                }),
+            })
+            .boxed_env(),
+    )
+    .into()
+}
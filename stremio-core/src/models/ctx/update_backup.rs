@@ -0,0 +1,386 @@
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+use std::marker::PhantomData;
+
+use futures::{future, Future, FutureExt, TryFutureExt};
+use http::Request;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+
+use crate::constants::{
+    BACKUP_VERSION, DISMISSED_EVENTS_STORAGE_KEY, LIBRARY_RECENT_STORAGE_KEY, LIBRARY_STORAGE_KEY,
+    LISTS_STORAGE_KEY, LOCAL_PROFILES_STORAGE_KEY, LOCAL_PROFILE_STORAGE_KEYS,
+    NOTIFICATIONS_STORAGE_KEY, PROFILE_STORAGE_KEY, SCHEMA_VERSION, SCHEMA_VERSION_STORAGE_KEY,
+    SEARCH_HISTORY_STORAGE_KEY, STREAMING_SERVER_URLS_STORAGE_KEY, STREAMS_STORAGE_KEY,
+    TASTE_STORAGE_KEY,
+};
+use crate::models::ctx::{CtxError, OtherError};
+use crate::models::streaming_server::StreamingServer;
+use crate::runtime::msg::{Action, ActionCtx, BackupStorageResponse, Event, Internal, Msg};
+use crate::runtime::{
+    ConditionalSend, Effect, EffectFuture, Effects, Env, EnvError, EnvFuture, EnvFutureExt,
+    TryEnvFuture,
+};
+use crate::types::backup::{Backup, RestoreMode};
+use crate::types::library::LibraryBucket;
+use crate::types::local_profiles::{local_profile_storage_key, LocalProfilesBucket};
+
+use super::Ctx;
+
+/// The storage in which the buckets of the backup are migrated,
+/// so the migrations never touch the buckets of the current state.
+static BACKUP_STORAGE: Lazy<std::sync::Mutex<HashMap<String, serde_json::Value>>> =
+    Lazy::new(Default::default);
+/// Held for the whole migration, as the storage is shared by all the restores.
+static BACKUP_STORAGE_LOCK: Lazy<futures::lock::Mutex<()>> = Lazy::new(Default::default);
+
+pub fn update_backup<E: Env + 'static>(ctx: &Ctx, msg: &Msg) -> Effects {
+    match msg {
+        Msg::Action(Action::Ctx(ActionCtx::CreateBackup)) => {
+            Effects::one(create_backup::<E>(ctx)).unchanged()
+        }
+        Msg::Action(Action::Ctx(ActionCtx::RestoreBackup { backup, mode })) => {
+            let error = if backup.version > BACKUP_VERSION {
+                Some(CtxError::from(OtherError::UnsupportedBackupVersion))
+            } else if !backup.is_valid_checksum() {
+                Some(CtxError::from(OtherError::InvalidBackupChecksum))
+            } else if backup.schema_version > SCHEMA_VERSION {
+                Some(CtxError::from(EnvError::StorageSchemaVersionDowngrade(
+                    backup.schema_version,
+                    SCHEMA_VERSION,
+                )))
+            } else {
+                None
+            };
+            match error {
+                Some(error) => Effects::msg(Msg::Event(Event::Error {
+                    error,
+                    source: Box::new(Event::BackupRestored {
+                        schema_version: backup.schema_version,
+                    }),
+                }))
+                .unchanged(),
+                None => Effects::one(migrate_backup::<E>(backup, *mode, &ctx.local_profiles))
+                    .unchanged(),
+            }
+        }
+        Msg::Internal(Internal::BackupRestoreResult(_, result)) => match result {
+            Ok(response) => Effects::msg(Msg::Event(Event::BackupRestored {
+                schema_version: response.schema_version,
+            }))
+            .unchanged(),
+            Err(error) => Effects::msg(Msg::Event(Event::Error {
+                error: error.to_owned(),
+                source: Box::new(Event::BackupRestored {
+                    schema_version: SCHEMA_VERSION,
+                }),
+            }))
+            .unchanged(),
+        },
+        _ => Effects::none().unchanged(),
+    }
+}
+
+/// Restores the items of a bucket, returns whether the items have changed.
+///
+/// When merging, the restored item replaces the current one only if `is_newer`.
+pub fn restore_items<K: Eq + Hash, V: PartialEq>(
+    items: &mut HashMap<K, V>,
+    restored: HashMap<K, V>,
+    mode: RestoreMode,
+    is_newer: impl Fn(&V, &V) -> bool,
+) -> bool {
+    match mode {
+        RestoreMode::Replace if *items != restored => {
+            *items = restored;
+            true
+        }
+        RestoreMode::Replace => false,
+        RestoreMode::Merge => restored
+            .into_iter()
+            .fold(false, |changed, (key, item)| match items.get(&key) {
+                Some(current) if !is_newer(&item, current) || *current == item => changed,
+                _ => {
+                    items.insert(key, item);
+                    true
+                }
+            }),
+    }
+}
+
+impl Ctx {
+    /// A backup of the buckets which are persisted in the storage,
+    /// of the selected local profile only as the others are not loaded.
+    ///
+    /// The auth and the parental controls of the profile are left out.
+    pub fn backup<E: Env + 'static>(&self) -> Backup {
+        let mut profile = self.profile.to_owned();
+        profile.auth = None;
+        profile.parental_controls = Default::default();
+        let local_profile_key = |key| self.local_profiles.storage_key(key);
+        let buckets = [
+            (PROFILE_STORAGE_KEY.to_owned(), to_value(&profile)),
+            (
+                local_profile_key(LIBRARY_STORAGE_KEY),
+                to_value(&self.library),
+            ),
+            (
+                local_profile_key(STREAMS_STORAGE_KEY),
+                to_value(&self.streams),
+            ),
+            (
+                STREAMING_SERVER_URLS_STORAGE_KEY.to_owned(),
+                to_value(&self.streaming_server_urls),
+            ),
+            (
+                NOTIFICATIONS_STORAGE_KEY.to_owned(),
+                to_value(&self.notifications),
+            ),
+            (
+                local_profile_key(SEARCH_HISTORY_STORAGE_KEY),
+                to_value(&self.search_history),
+            ),
+            (
+                DISMISSED_EVENTS_STORAGE_KEY.to_owned(),
+                to_value(&self.dismissed_events),
+            ),
+            (TASTE_STORAGE_KEY.to_owned(), to_value(&self.taste)),
+            (local_profile_key(LISTS_STORAGE_KEY), to_value(&self.lists)),
+            (
+                LOCAL_PROFILES_STORAGE_KEY.to_owned(),
+                to_value(&self.local_profiles),
+            ),
+        ]
+        .into_iter()
+        .collect();
+        Backup::new(buckets, E::now())
+    }
+}
+
+fn to_value<T: Serialize>(bucket: &T) -> serde_json::Value {
+    serde_json::to_value(bucket).expect("Failed to serialize bucket")
+}
+
+/// Adds the buckets of the other local profiles to the backup of the [`Ctx`],
+/// as only the buckets of the selected local profile are loaded.
+fn create_backup<E: Env + 'static>(ctx: &Ctx) -> Effect {
+    let backup = ctx.backup::<E>();
+    let keys = ctx
+        .local_profiles
+        .profiles
+        .iter()
+        .filter(|local_profile| local_profile.id != ctx.local_profiles.selected)
+        .flat_map(|local_profile| {
+            LOCAL_PROFILE_STORAGE_KEYS
+                .into_iter()
+                .map(|key| local_profile_storage_key(key, &local_profile.id))
+        })
+        .collect::<Vec<_>>();
+    EffectFuture::Concurrent(
+        future::try_join_all(
+            keys.into_iter().map(|key| {
+                E::get_storage::<serde_json::Value>(&key).map_ok(|bucket| (key, bucket))
+            }),
+        )
+        .map(move |result| match result {
+            Ok(buckets) => {
+                let buckets = buckets
+                    .into_iter()
+                    .filter_map(|(key, bucket)| bucket.map(|bucket| (key, bucket)))
+                    .chain(backup.buckets)
+                    .collect();
+                Msg::Event(Event::BackupCreated {
+                    backup: Box::new(Backup::new(buckets, backup.created)),
+                })
+            }
+            Err(error) => Msg::Event(Event::Error {
+                error: CtxError::from(error),
+                source: Box::new(Event::BackupCreated {
+                    backup: Box::new(backup),
+                }),
+            }),
+        })
+        .boxed_env(),
+    )
+    .into()
+}
+
+/// The buckets of the selected local profile are restored in the [`Ctx`],
+/// the buckets of the other local profiles are restored in the storage:
+/// when merging, only the ones of the local profiles which are not on this device.
+fn migrate_backup<E: Env + 'static>(
+    backup: &Backup,
+    mode: RestoreMode,
+    local_profiles: &LocalProfilesBucket,
+) -> Effect {
+    let backup = backup.to_owned();
+    let selected = local_profiles.selected.to_owned();
+    let local_profile_ids = local_profiles
+        .profiles
+        .iter()
+        .map(|local_profile| local_profile.id.to_owned())
+        .collect::<HashSet<_>>();
+    EffectFuture::Sequential(
+        async move {
+            let _lock = BACKUP_STORAGE_LOCK.lock().await;
+            {
+                let mut storage = BACKUP_STORAGE.lock().expect("backup storage lock failed");
+                *storage = backup.buckets.into_iter().collect();
+                storage.insert(
+                    SCHEMA_VERSION_STORAGE_KEY.to_owned(),
+                    serde_json::Value::from(backup.schema_version),
+                );
+            }
+            let result = BackupEnv::<E>::migrate_storage_schema()
+                .await
+                .and_then(|_| {
+                    let mut storage = BACKUP_STORAGE.lock().expect("backup storage lock failed");
+                    let mut buckets = std::mem::take(&mut *storage);
+                    let local_profile_key = |key| local_profile_storage_key(key, &selected);
+                    let library = [LIBRARY_RECENT_STORAGE_KEY, LIBRARY_STORAGE_KEY]
+                        .into_iter()
+                        .filter_map(|key| {
+                            take_bucket::<LibraryBucket>(&mut buckets, &local_profile_key(key))
+                        })
+                        .reduce(|mut library, bucket| {
+                            library.merge_items(bucket.items.into_values().collect());
+                            library
+                        });
+                    let local_profiles = take_bucket::<LocalProfilesBucket>(
+                        &mut buckets,
+                        LOCAL_PROFILES_STORAGE_KEY,
+                    );
+                    let local_profile_buckets = local_profiles
+                        .iter()
+                        .flat_map(|local_profiles| &local_profiles.profiles)
+                        .filter(|local_profile| {
+                            local_profile.id != selected
+                                && (mode == RestoreMode::Replace
+                                    || !local_profile_ids.contains(&local_profile.id))
+                        })
+                        .flat_map(|local_profile| {
+                            LOCAL_PROFILE_STORAGE_KEYS
+                                .into_iter()
+                                .map(|key| local_profile_storage_key(key, &local_profile.id))
+                        })
+                        .filter_map(|key| buckets.remove(&key).map(|bucket| (key, bucket)))
+                        .collect::<Vec<_>>();
+                    let response = BackupStorageResponse {
+                        schema_version: backup.schema_version,
+                        profile: take_bucket(&mut buckets, PROFILE_STORAGE_KEY),
+                        library,
+                        streams: take_bucket(&mut buckets, &local_profile_key(STREAMS_STORAGE_KEY)),
+                        streaming_server_urls: take_bucket(
+                            &mut buckets,
+                            STREAMING_SERVER_URLS_STORAGE_KEY,
+                        ),
+                        notifications: take_bucket(&mut buckets, NOTIFICATIONS_STORAGE_KEY),
+                        search_history: take_bucket(
+                            &mut buckets,
+                            &local_profile_key(SEARCH_HISTORY_STORAGE_KEY),
+                        ),
+                        dismissed_events: take_bucket(&mut buckets, DISMISSED_EVENTS_STORAGE_KEY),
+                        taste: take_bucket(&mut buckets, TASTE_STORAGE_KEY),
+                        lists: take_bucket(&mut buckets, &local_profile_key(LISTS_STORAGE_KEY)),
+                        local_profiles,
+                    };
+                    Ok((response, local_profile_buckets))
+                });
+            BACKUP_STORAGE
+                .lock()
+                .expect("backup storage lock failed")
+                .clear();
+            let (response, local_profile_buckets) = result?;
+            future::try_join_all(
+                local_profile_buckets
+                    .iter()
+                    .map(|(key, bucket)| E::set_storage(key, Some(bucket))),
+            )
+            .await?;
+            Ok::<_, EnvError>(response)
+        }
+        .map(move |result| {
+            Msg::Internal(Internal::BackupRestoreResult(
+                mode,
+                result.map(Box::new).map_err(CtxError::from),
+            ))
+        })
+        .boxed_env(),
+    )
+    .into()
+}
+
+/// A bucket which fails to deserialize is not restored
+fn take_bucket<T: for<'de> Deserialize<'de>>(
+    buckets: &mut HashMap<String, serde_json::Value>,
+    key: &str,
+) -> Option<T> {
+    buckets
+        .remove(key)
+        .and_then(|value| serde_json::from_value(value).ok())
+}
+
+/// Runs the storage schema migrations against the [`BACKUP_STORAGE`],
+/// everything else is delegated to the `E` environment.
+struct BackupEnv<E>(PhantomData<E>);
+
+impl<E: Env + 'static> Env for BackupEnv<E> {
+    fn fetch<
+        IN: Serialize + ConditionalSend + 'static,
+        OUT: for<'de> Deserialize<'de> + ConditionalSend + 'static,
+    >(
+        request: Request<IN>,
+    ) -> TryEnvFuture<OUT> {
+        E::fetch(request)
+    }
+    fn get_storage<T: for<'de> Deserialize<'de> + ConditionalSend + 'static>(
+        key: &str,
+    ) -> TryEnvFuture<Option<T>> {
+        let result = BACKUP_STORAGE
+            .lock()
+            .expect("backup storage lock failed")
+            .get(key)
+            .map(|value| serde_json::from_value(value.to_owned()))
+            .transpose()
+            .map_err(EnvError::from);
+        future::ready(result).boxed_env()
+    }
+    fn set_storage<T: Serialize>(key: &str, value: Option<&T>) -> TryEnvFuture<()> {
+        let mut storage = BACKUP_STORAGE.lock().expect("backup storage lock failed");
+        let result = match value {
+            Some(value) => serde_json::to_value(value)
+                .map(|value| {
+                    storage.insert(key.to_owned(), value);
+                })
+                .map_err(EnvError::from),
+            None => {
+                storage.remove(key);
+                Ok(())
+            }
+        };
+        future::ready(result).boxed_env()
+    }
+    fn exec_concurrent<F: Future<Output = ()> + ConditionalSend + 'static>(future: F) {
+        E::exec_concurrent(future)
+    }
+    fn exec_sequential<F: Future<Output = ()> + ConditionalSend + 'static>(future: F) {
+        E::exec_sequential(future)
+    }
+    fn now() -> chrono::DateTime<chrono::Utc> {
+        E::now()
+    }
+    fn flush_analytics() -> EnvFuture<'static, ()> {
+        E::flush_analytics()
+    }
+    fn analytics_context(
+        ctx: &Ctx,
+        streaming_server: &StreamingServer,
+        path: &str,
+    ) -> serde_json::Value {
+        E::analytics_context(ctx, streaming_server, path)
+    }
+    #[cfg(debug_assertions)]
+    fn log(message: String) {
+        E::log(message)
+    }
+}
@@ -4,7 +4,7 @@ use futures::{future, FutureExt, TryFutureExt};
 
 use crate::constants::DISMISSED_EVENTS_STORAGE_KEY;
 use crate::models::common::{eq_update, Loadable};
-use crate::models::ctx::CtxError;
+use crate::models::ctx::{restore_items, CtxError};
 use crate::runtime::msg::{Action, ActionCtx, Event, Internal, Msg};
 use crate::runtime::{Effect, EffectFuture, Effects, Env, EnvFutureExt};
 use crate::types::api::{
@@ -23,6 +23,21 @@ pub fn update_events<E: Env + 'static>(
             *dismissed_events = next_dismissed_events;
             Effects::msg(Msg::Internal(Internal::DismissedEventsChanged))
         }
+        Msg::Internal(Internal::BackupRestoreResult(mode, Ok(response))) => {
+            match &response.dismissed_events {
+                Some(restored)
+                    if restore_items(
+                        &mut dismissed_events.items,
+                        restored.items.to_owned(),
+                        *mode,
+                        |restored, date| restored > date,
+                    ) =>
+                {
+                    Effects::msg(Msg::Internal(Internal::DismissedEventsChanged))
+                }
+                _ => Effects::none().unchanged(),
+            }
+        }
         Msg::Action(Action::Ctx(ActionCtx::GetEvents)) => {
             let modal_effects = eq_update(&mut events.modal, Loadable::Loading);
             let notification_effects = eq_update(&mut events.notification, Loadable::Loading);
@@ -1,46 +1,72 @@
-use std::{collections::HashMap, marker::PhantomData};
+use std::{
+    collections::{HashMap, HashSet},
+    marker::PhantomData,
+};
 
+use enclose::enclose;
 use futures::{
     future::{self, Either},
     FutureExt, TryFutureExt,
 };
+use itertools::Itertools;
 
 use crate::{
     constants::{
         LIBRARY_COLLECTION_NAME, LIBRARY_RECENT_COUNT, LIBRARY_RECENT_STORAGE_KEY,
-        LIBRARY_STORAGE_KEY,
+        LIBRARY_STORAGE_KEY, LIBRARY_SYNC_STORAGE_KEY,
     },
     models::ctx::{CtxError, CtxStatus, OtherError},
     runtime::{
-        msg::{Action, ActionCtx, CtxAuthResponse, Event, Internal, Msg},
+        msg::{
+            Action, ActionCtx, CtxAuthResponse, Event, Internal, LocalProfileStorageResponse, Msg,
+        },
         Effect, EffectFuture, Effects, Env, EnvFutureExt,
     },
     types::{
         api::{
             fetch_api, APIResult, DatastoreCommand, DatastoreRequest, LibraryItemModified,
-            LibraryItemsResponse, SuccessResponse,
+            LibraryItemsResponse,
+        },
+        backup::RestoreMode,
+        library::{
+            LibraryBucket, LibraryBucketRef, LibraryItem, LibraryItemMerge, LibrarySyncBucket,
+            LibrarySyncConflict,
         },
-        library::{LibraryBucket, LibraryBucketRef, LibraryItem},
+        local_profiles::LocalProfilesBucket,
+        outbox::OutboxRequest,
         profile::{AuthKey, Profile},
     },
 };
 
 pub fn update_library<E: Env + 'static>(
     library: &mut LibraryBucket,
+    library_sync: &mut LibrarySyncBucket,
     profile: &Profile,
+    local_profiles: &LocalProfilesBucket,
     status: &CtxStatus,
     msg: &Msg,
 ) -> Effects {
-    let auth_key = profile.auth_key();
+    // only the library of the default local profile is synced with the API
+    let auth_key = profile
+        .auth_key()
+        .filter(|_| local_profiles.is_default_selected());
     match msg {
         Msg::Internal(Internal::Logout(_)) => {
             let next_library = LibraryBucket::default();
-            if *library != next_library {
+            let library_effects = if *library != next_library {
                 *library = next_library;
                 Effects::msg(Msg::Internal(Internal::LibraryChanged(false)))
             } else {
                 Effects::none().unchanged()
-            }
+            };
+            let next_library_sync = LibrarySyncBucket::default();
+            let library_sync_effects = if *library_sync != next_library_sync {
+                *library_sync = next_library_sync;
+                Effects::msg(Msg::Internal(Internal::LibrarySyncChanged))
+            } else {
+                Effects::none().unchanged()
+            };
+            library_effects.join(library_sync_effects)
         }
         Msg::Action(Action::Ctx(ActionCtx::AddToLibrary(meta_preview))) => {
             let mut library_item = match library.items.get(&meta_preview.id) {
@@ -131,7 +157,11 @@ pub fn update_library<E: Env + 'static>(
             }
         }
         Msg::Action(Action::Ctx(ActionCtx::SyncLibraryWithAPI)) => match auth_key {
-            Some(auth_key) => Effects::one(plan_sync_with_api::<E>(library, auth_key)).unchanged(),
+            Some(auth_key) => {
+                Effects::one(plan_sync_with_api::<E>(library, library_sync, auth_key)).unchanged()
+            }
+            // the library of the selected local profile is kept only locally
+            _ if profile.auth_key().is_some() => Effects::none().unchanged(),
             _ => Effects::msg(Msg::Event(Event::Error {
                 error: CtxError::from(OtherError::UserNotLoggedIn),
                 source: Box::new(Event::LibrarySyncWithAPIPlanned {
@@ -141,6 +171,15 @@ pub fn update_library<E: Env + 'static>(
             }))
             .unchanged(),
         },
+        Msg::Action(Action::Ctx(ActionCtx::DismissLibraryItemConflicts(id))) => {
+            let conflicts_count = library_sync.conflicts.len();
+            library_sync.conflicts.retain(|conflict| conflict.id != *id);
+            if library_sync.conflicts.len() != conflicts_count {
+                Effects::msg(Msg::Internal(Internal::LibrarySyncChanged))
+            } else {
+                Effects::none().unchanged()
+            }
+        }
         Msg::Action(Action::Ctx(ActionCtx::LibraryItemMarkAsWatched { id, is_watched })) => {
             match library.items.get(id) {
                 Some(library_item) => {
@@ -163,16 +202,16 @@ pub fn update_library<E: Env + 'static>(
             library_item.mtime = E::now();
 
             let push_to_api_effects = match auth_key {
-                Some(auth_key) => Effects::one(push_items_to_api::<E>(
-                    vec![library_item.to_owned()],
-                    auth_key,
-                ))
-                .unchanged(),
+                Some(auth_key) => {
+                    Effects::one(push_items_to_api(vec![library_item.to_owned()], auth_key))
+                        .unchanged()
+                }
                 _ => Effects::none().unchanged(),
             };
 
             let push_to_storage_effects = Effects::one(update_and_push_items_to_storage::<E>(
                 library,
+                local_profiles,
                 vec![library_item],
             ));
 
@@ -181,7 +220,78 @@ pub fn update_library<E: Env + 'static>(
                 .join(Effects::msg(Msg::Internal(Internal::LibraryChanged(true))))
         }
         Msg::Internal(Internal::LibraryChanged(persisted)) if !persisted => {
-            Effects::one(push_library_to_storage::<E>(library)).unchanged()
+            Effects::one(push_library_to_storage::<E>(library, local_profiles)).unchanged()
+        }
+        Msg::Internal(Internal::LocalProfileStorageResult(
+            _,
+            Ok(LocalProfileStorageResponse {
+                library: next_library,
+                ..
+            }),
+        )) => {
+            next_library.clone_into(library);
+            Effects::msg(Msg::Internal(Internal::LibraryChanged(true)))
+        }
+        Msg::Internal(Internal::BackupRestoreResult(mode, Ok(response))) => {
+            match &response.library {
+                Some(restored) => {
+                    let now = E::now();
+                    let restored_items = match mode {
+                        RestoreMode::Merge => restored
+                            .items
+                            .values()
+                            .filter(|item| {
+                                library
+                                    .items
+                                    .get(&item.id)
+                                    .map_or(true, |library_item| item.mtime > library_item.mtime)
+                            })
+                            .cloned()
+                            .collect::<Vec<_>>(),
+                        // the restored items are modified now so they win the sync with the API
+                        RestoreMode::Replace => restored
+                            .items
+                            .values()
+                            .filter(|item| library.items.get(&item.id) != Some(item))
+                            .map(|item| LibraryItem {
+                                mtime: now,
+                                ..item.to_owned()
+                            })
+                            .chain(
+                                library
+                                    .items
+                                    .values()
+                                    .filter(|library_item| {
+                                        !library_item.removed
+                                            && !restored.items.contains_key(&library_item.id)
+                                    })
+                                    .map(|library_item| LibraryItem {
+                                        removed: true,
+                                        mtime: now,
+                                        ..library_item.to_owned()
+                                    }),
+                            )
+                            .collect::<Vec<_>>(),
+                    };
+                    if restored_items.is_empty() {
+                        return Effects::none().unchanged();
+                    }
+                    let push_to_api_effects = match auth_key {
+                        Some(auth_key) => {
+                            Effects::one(push_items_to_api(restored_items.to_owned(), auth_key))
+                                .unchanged()
+                        }
+                        _ => Effects::none().unchanged(),
+                    };
+                    library.merge_items(restored_items);
+                    push_to_api_effects
+                        .join(Effects::msg(Msg::Internal(Internal::LibraryChanged(false))))
+                }
+                None => Effects::none().unchanged(),
+            }
+        }
+        Msg::Internal(Internal::LibrarySyncChanged) => {
+            Effects::one(push_library_sync_to_storage::<E>(library_sync)).unchanged()
         }
         Msg::Internal(Internal::CtxAuthResult(auth_request, result)) => match (status, result) {
             (
@@ -192,16 +302,24 @@ pub fn update_library<E: Env + 'static>(
                     ..
                 }),
             ) if loading_auth_request == auth_request => {
-                let next_library = LibraryBucket::new(
-                    Some(auth.user.id.to_owned()),
-                    library_items_result.to_owned().unwrap_or_default(),
-                );
-                if *library != next_library {
+                let library_items = library_items_result.to_owned().unwrap_or_default();
+                let next_library =
+                    LibraryBucket::new(Some(auth.user.id.to_owned()), library_items.to_owned());
+                let library_effects = if *library != next_library {
                     *library = next_library;
                     Effects::msg(Msg::Internal(Internal::LibraryChanged(false)))
                 } else {
                     Effects::none().unchanged()
-                }
+                };
+                let next_library_sync =
+                    LibrarySyncBucket::new(Some(auth.user.id.to_owned()), library_items);
+                let library_sync_effects = if *library_sync != next_library_sync {
+                    *library_sync = next_library_sync;
+                    Effects::msg(Msg::Internal(Internal::LibrarySyncChanged))
+                } else {
+                    Effects::none().unchanged()
+                };
+                library_effects.join(library_sync_effects)
             }
             _ => Effects::none().unchanged(),
         },
@@ -223,7 +341,7 @@ pub fn update_library<E: Env + 'static>(
                 let push_items_to_api_effects = if push_items.is_empty() {
                     Effects::none().unchanged()
                 } else {
-                    Effects::one(push_items_to_api::<E>(push_items, loading_auth_key)).unchanged()
+                    Effects::one(push_items_to_api(push_items, loading_auth_key)).unchanged()
                 };
                 let pull_items_from_api_effects = if pull_ids.is_empty() {
                     Effects::none().unchanged()
@@ -266,15 +384,38 @@ pub fn update_library<E: Env + 'static>(
                 }))
                 .unchanged();
 
+                let (items, push_items, conflicts) =
+                    merge_pulled_items::<E>(library, library_sync, items.to_owned());
+                let push_items_to_api_effects = if push_items.is_empty() {
+                    Effects::none().unchanged()
+                } else {
+                    Effects::one(push_items_to_api(push_items, loading_auth_key)).unchanged()
+                };
+                let conflicts_effects = if conflicts.is_empty() {
+                    Effects::none().unchanged()
+                } else {
+                    let ids = conflicts
+                        .iter()
+                        .map(|conflict| conflict.id.to_owned())
+                        .unique()
+                        .collect();
+                    library_sync.conflicts.extend(conflicts);
+                    Effects::msg(Msg::Event(Event::LibraryItemsConflicted { ids })).unchanged()
+                };
+
                 library_missing_effects
                     .join(Effects::msg(Msg::Event(Event::LibraryItemsPulledFromAPI {
                         ids: ids.to_owned(),
                     })))
                     .join(Effects::one(update_and_push_items_to_storage::<E>(
                         library,
-                        items.to_owned(),
+                        local_profiles,
+                        items,
                     )))
                     .join(Effects::msg(Msg::Internal(Internal::LibraryChanged(true))))
+                    .join(Effects::msg(Msg::Internal(Internal::LibrarySyncChanged)).unchanged())
+                    .join(push_items_to_api_effects)
+                    .join(conflicts_effects)
             }
             Err(error) => Effects::msg(Msg::Event(Event::Error {
                 error: error.to_owned(),
@@ -284,14 +425,77 @@ pub fn update_library<E: Env + 'static>(
             }))
             .unchanged(),
         },
+        Msg::Internal(Internal::OutboxRequestResult(
+            OutboxRequest::LibraryItems {
+                auth_key: loading_auth_key,
+                items,
+            },
+            Ok(_),
+        )) if Some(loading_auth_key) == profile.auth_key() => {
+            library_sync.update_bases(items.to_owned());
+            Effects::msg(Msg::Internal(Internal::LibrarySyncChanged)).unchanged()
+        }
         _ => Effects::none().unchanged(),
     }
 }
 
+/// Merges the pulled items with the local changes made since the last sync.
+///
+/// Returns the items to be stored, the merged items which should be pushed to the API
+/// and the conflicts found while merging.
+fn merge_pulled_items<E: Env + 'static>(
+    library: &LibraryBucket,
+    library_sync: &mut LibrarySyncBucket,
+    remote_items: Vec<LibraryItem>,
+) -> (Vec<LibraryItem>, Vec<LibraryItem>, Vec<LibrarySyncConflict>) {
+    let mut items = vec![];
+    let mut push_items = vec![];
+    let mut conflicts = vec![];
+    for remote_item in remote_items {
+        let base = library_sync.bases.get(&remote_item.id);
+        match library.items.get(&remote_item.id) {
+            // without a base the newer item wins
+            Some(local_item) if base.is_some() && library_sync.is_changed(local_item) => {
+                let LibraryItemMerge {
+                    mut item,
+                    conflicts: item_conflicts,
+                } = LibraryItem::merge(base, local_item, &remote_item);
+                conflicts.extend(
+                    item_conflicts
+                        .into_iter()
+                        .map(|conflict| LibrarySyncConflict {
+                            id: remote_item.id.to_owned(),
+                            conflict,
+                            local: local_item.to_owned(),
+                            remote: remote_item.to_owned(),
+                            date: E::now(),
+                        }),
+                );
+                if item.eq_no_mtime(&remote_item) {
+                    library_sync.update_bases(vec![item.to_owned()]);
+                } else {
+                    library_sync.update_bases(vec![remote_item]);
+                    item.mtime = E::now();
+                    push_items.push(item.to_owned());
+                }
+                items.push(item);
+            }
+            _ => {
+                library_sync.update_bases(vec![remote_item.to_owned()]);
+                items.push(remote_item);
+            }
+        }
+    }
+    (items, push_items, conflicts)
+}
+
 fn update_and_push_items_to_storage<E: Env + 'static>(
     library: &mut LibraryBucket,
+    local_profiles: &LocalProfilesBucket,
     items: Vec<LibraryItem>,
 ) -> Effect {
+    let recent_storage_key = local_profiles.storage_key(LIBRARY_RECENT_STORAGE_KEY);
+    let storage_key = local_profiles.storage_key(LIBRARY_STORAGE_KEY);
     let ids = items
         .iter()
         .map(|item| &item.id)
@@ -302,8 +506,8 @@ fn update_and_push_items_to_storage<E: Env + 'static>(
     let push_to_storage_future = if library.items.len() <= LIBRARY_RECENT_COUNT {
         Either::Left(
             future::try_join_all(vec![
-                E::set_storage(LIBRARY_RECENT_STORAGE_KEY, Some(&library)),
-                E::set_storage::<()>(LIBRARY_STORAGE_KEY, None),
+                E::set_storage(&recent_storage_key, Some(&library)),
+                E::set_storage::<()>(&storage_key, None),
             ])
             .map_ok(|_| ()),
         )
@@ -311,18 +515,18 @@ fn update_and_push_items_to_storage<E: Env + 'static>(
         let (recent_items, other_items) = library.split_items_by_recent();
         if are_items_in_recent {
             Either::Right(Either::Left(E::set_storage(
-                LIBRARY_RECENT_STORAGE_KEY,
+                &recent_storage_key,
                 Some(&LibraryBucketRef::new(&library.uid, &recent_items)),
             )))
         } else {
             Either::Right(Either::Right(
                 future::try_join_all(vec![
                     E::set_storage(
-                        LIBRARY_RECENT_STORAGE_KEY,
+                        &recent_storage_key,
                         Some(&LibraryBucketRef::new(&library.uid, &recent_items)),
                     ),
                     E::set_storage(
-                        LIBRARY_STORAGE_KEY,
+                        &storage_key,
                         Some(&LibraryBucketRef::new(&library.uid, &other_items)),
                     ),
                 ])
@@ -344,17 +548,20 @@ fn update_and_push_items_to_storage<E: Env + 'static>(
     .into()
 }
 
-fn push_library_to_storage<E: Env + 'static>(library: &LibraryBucket) -> Effect {
+fn push_library_to_storage<E: Env + 'static>(
+    library: &LibraryBucket,
+    local_profiles: &LocalProfilesBucket,
+) -> Effect {
     let ids = library.items.keys().cloned().collect();
     let (recent_items, other_items) = library.split_items_by_recent();
     EffectFuture::Sequential(
         future::try_join_all(vec![
             E::set_storage(
-                LIBRARY_RECENT_STORAGE_KEY,
+                &local_profiles.storage_key(LIBRARY_RECENT_STORAGE_KEY),
                 Some(&LibraryBucketRef::new(&library.uid, &recent_items)),
             ),
             E::set_storage(
-                LIBRARY_STORAGE_KEY,
+                &local_profiles.storage_key(LIBRARY_STORAGE_KEY),
                 Some(&LibraryBucketRef::new(&library.uid, &other_items)),
             ),
         ])
@@ -370,31 +577,32 @@ fn push_library_to_storage<E: Env + 'static>(library: &LibraryBucket) -> Effect
     .into()
 }
 
-fn push_items_to_api<E: Env + 'static>(items: Vec<LibraryItem>, auth_key: &AuthKey) -> Effect {
-    let ids = items.iter().map(|item| &item.id).cloned().collect();
-    EffectFuture::Concurrent(
-        fetch_api::<E, _, _, SuccessResponse>(&DatastoreRequest {
-            auth_key: auth_key.to_owned(),
-            collection: LIBRARY_COLLECTION_NAME.to_owned(),
-            command: DatastoreCommand::Put { changes: items },
-        })
-        .map_err(CtxError::from)
-        .and_then(|result| match result {
-            APIResult::Ok(result) => future::ok(result),
-            APIResult::Err(error) => future::err(CtxError::from(error)),
-        })
-        .map(move |result| match result {
-            Ok(_) => Msg::Event(Event::LibraryItemsPushedToAPI { ids }),
-            Err(error) => Msg::Event(Event::Error {
-                error,
-                source: Box::new(Event::LibraryItemsPushedToAPI { ids }),
-            }),
-        })
-        .boxed_env(),
+fn push_library_sync_to_storage<E: Env + 'static>(library_sync: &LibrarySyncBucket) -> Effect {
+    EffectFuture::Sequential(
+        E::set_storage(LIBRARY_SYNC_STORAGE_KEY, Some(library_sync))
+            .map(
+                enclose!((library_sync.uid => uid) move |result| match result {
+                    Ok(_) => Msg::Event(Event::LibrarySyncPushedToStorage { uid }),
+                    Err(error) => Msg::Event(Event::Error {
+                        error: CtxError::from(error),
+                        source: Box::new(Event::LibrarySyncPushedToStorage { uid }),
+                    })
+                }),
+            )
+            .boxed_env(),
     )
     .into()
 }
 
+fn push_items_to_api(items: Vec<LibraryItem>, auth_key: &AuthKey) -> Effect {
+    Effect::Msg(Box::new(Msg::Internal(Internal::EnqueueOutboxRequest(
+        OutboxRequest::LibraryItems {
+            auth_key: auth_key.to_owned(),
+            items,
+        },
+    ))))
+}
+
 fn pull_items_from_api<E: Env + 'static>(ids: Vec<String>, auth_key: &AuthKey) -> Effect {
     let request = DatastoreRequest {
         auth_key: auth_key.to_owned(),
@@ -414,7 +622,17 @@ fn pull_items_from_api<E: Env + 'static>(ids: Vec<String>, auth_key: &AuthKey) -
     .into()
 }
 
-fn plan_sync_with_api<E: Env + 'static>(library: &LibraryBucket, auth_key: &AuthKey) -> Effect {
+/// Plans which items should be pulled from and pushed to the API.
+///
+/// Items with a base in the [`LibrarySyncBucket`] are pulled if they were changed remotely
+/// and pushed if they were changed only locally since they were last synced,
+/// items changed on both sides are merged once pulled.
+/// Any other item is pulled or pushed depending on which side is newer.
+fn plan_sync_with_api<E: Env + 'static>(
+    library: &LibraryBucket,
+    library_sync: &LibrarySyncBucket,
+    auth_key: &AuthKey,
+) -> Effect {
     let local_mtimes = library
         .items
         .iter()
@@ -425,6 +643,17 @@ fn plan_sync_with_api<E: Env + 'static>(library: &LibraryBucket, auth_key: &Auth
             )
         })
         .collect::<HashMap<_, _>>();
+    let base_mtimes = library_sync
+        .bases
+        .iter()
+        .map(|(id, item)| (id.to_owned(), item.mtime.to_owned()))
+        .collect::<HashMap<_, _>>();
+    let changed_ids = library
+        .items
+        .values()
+        .filter(|item| library_sync.is_changed(item))
+        .map(|item| item.id.to_owned())
+        .collect::<HashSet<_>>();
     let request = DatastoreRequest {
         auth_key: auth_key.to_owned(),
         collection: LIBRARY_COLLECTION_NAME.to_owned(),
@@ -447,20 +676,31 @@ fn plan_sync_with_api<E: Env + 'static>(library: &LibraryBucket, auth_key: &Auth
                 let pull_ids = remote_mtimes
                     .iter()
                     .filter(|(id, remote_mtime)| {
-                        local_mtimes.get(*id).map_or(true, |(local_mtime, _)| {
-                            local_mtime.timestamp() < remote_mtime.timestamp()
-                        })
+                        match (local_mtimes.get(*id), base_mtimes.get(*id)) {
+                            (Some(_), Some(base_mtime)) => {
+                                base_mtime.timestamp() < remote_mtime.timestamp()
+                            }
+                            (Some((local_mtime, _)), None) => {
+                                local_mtime.timestamp() < remote_mtime.timestamp()
+                            }
+                            (None, _) => true,
+                        }
                     })
                     .map(|(id, _)| id)
                     .cloned()
-                    .collect();
+                    .collect::<Vec<_>>();
                 let push_ids = local_mtimes
                     .iter()
                     .filter(|(id, (local_mtime, should_sync))| {
                         *should_sync
-                            && remote_mtimes.get(*id).map_or(true, |remote_mtime| {
-                                remote_mtime.timestamp() < local_mtime.timestamp()
-                            })
+                            && !pull_ids.contains(*id)
+                            && match (remote_mtimes.get(*id), base_mtimes.get(*id)) {
+                                (Some(_), Some(_)) => changed_ids.contains(*id),
+                                (Some(remote_mtime), None) => {
+                                    remote_mtime.timestamp() < local_mtime.timestamp()
+                                }
+                                (None, _) => true,
+                            }
                     })
                     .map(|(id, _)| id)
                     .cloned()
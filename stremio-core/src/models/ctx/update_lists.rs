@@ -0,0 +1,379 @@
+use std::marker::PhantomData;
+
+use enclose::enclose;
+use futures::{future, FutureExt, TryFutureExt};
+
+use crate::constants::{LISTS_COLLECTION_NAME, LISTS_STORAGE_KEY};
+use crate::models::ctx::{CtxError, CtxStatus, OtherError};
+use crate::runtime::msg::{
+    Action, ActionCtx, CtxAuthResponse, Event, Internal, LocalProfileStorageResponse, Msg,
+};
+use crate::runtime::{Effect, EffectFuture, Effects, Env, EnvFutureExt};
+use crate::types::api::{fetch_api, APIResult, DatastoreCommand, DatastoreRequest};
+use crate::types::backup::RestoreMode;
+use crate::types::library::{LibraryBucket, LibraryItem};
+use crate::types::lists::{List, ListId, ListItem, ListsBucket};
+use crate::types::local_profiles::LocalProfilesBucket;
+use crate::types::outbox::OutboxRequest;
+use crate::types::profile::{AuthKey, Profile};
+use crate::types::random_id;
+
+pub fn update_lists<E: Env + 'static>(
+    lists: &mut ListsBucket,
+    library: &LibraryBucket,
+    profile: &Profile,
+    local_profiles: &LocalProfilesBucket,
+    status: &CtxStatus,
+    msg: &Msg,
+) -> Effects {
+    // only the lists of the default local profile are synced with the API
+    let auth_key = profile
+        .auth_key()
+        .filter(|_| local_profiles.is_default_selected());
+    match msg {
+        Msg::Internal(Internal::Logout(_)) => {
+            let next_lists = ListsBucket::default();
+            if *lists != next_lists {
+                *lists = next_lists;
+                Effects::msg(Msg::Internal(Internal::ListsChanged))
+            } else {
+                Effects::none().unchanged()
+            }
+        }
+        Msg::Internal(Internal::CtxAuthResult(auth_request, result)) => match (status, result) {
+            (CtxStatus::Loading(loading_auth_request), Ok(CtxAuthResponse { auth, .. }))
+                if loading_auth_request == auth_request =>
+            {
+                let next_lists = ListsBucket::new(Some(auth.user.id.to_owned()));
+                let pull_from_api_effects =
+                    Effects::one(pull_lists_from_api::<E>(&auth.key)).unchanged();
+                if *lists != next_lists {
+                    *lists = next_lists;
+                    Effects::msg(Msg::Internal(Internal::ListsChanged)).join(pull_from_api_effects)
+                } else {
+                    pull_from_api_effects
+                }
+            }
+            _ => Effects::none().unchanged(),
+        },
+        Msg::Action(Action::Ctx(ActionCtx::PullListsFromAPI)) => match auth_key {
+            Some(auth_key) => Effects::one(pull_lists_from_api::<E>(auth_key)).unchanged(),
+            // the lists of the selected local profile are kept only locally
+            _ if profile.auth_key().is_some() => Effects::none().unchanged(),
+            _ => Effects::msg(Msg::Event(Event::Error {
+                error: CtxError::from(OtherError::UserNotLoggedIn),
+                source: Box::new(Event::ListsPulledFromAPI { ids: vec![] }),
+            }))
+            .unchanged(),
+        },
+        Msg::Action(Action::Ctx(ActionCtx::CreateList { name })) => {
+            let list = new_list::<E>(lists, name.to_owned(), vec![]);
+            Effects::msg(Msg::Event(Event::ListCreated {
+                id: list.id.to_owned(),
+            }))
+            .join(Effects::msg(Msg::Internal(Internal::UpdateList(list))))
+            .unchanged()
+        }
+        Msg::Action(Action::Ctx(ActionCtx::ImportList(shared_list))) => {
+            let list = new_list::<E>(
+                lists,
+                shared_list.name.to_owned(),
+                shared_list.items.to_owned(),
+            );
+            Effects::msg(Msg::Event(Event::ListCreated {
+                id: list.id.to_owned(),
+            }))
+            .join(add_to_library_effects::<E>(library, &shared_list.items))
+            .join(Effects::msg(Msg::Internal(Internal::UpdateList(list))))
+            .unchanged()
+        }
+        Msg::Action(Action::Ctx(ActionCtx::RenameList { id, name })) => {
+            list_update_effects(lists, id, |list| name.clone_into(&mut list.name))
+        }
+        Msg::Action(Action::Ctx(ActionCtx::RemoveList(id))) => {
+            list_update_effects(lists, id, |list| list.removed = true)
+        }
+        Msg::Action(Action::Ctx(ActionCtx::AddToList { id, meta_item })) => {
+            let list_item = ListItem::from(meta_item);
+            let library_effects = match lists.items.get(id) {
+                Some(list) if !list.removed => {
+                    add_to_library_effects::<E>(library, &[list_item.to_owned()])
+                }
+                _ => Effects::none().unchanged(),
+            };
+            library_effects.join(list_update_effects(lists, id, |list| {
+                if !list.contains(&list_item.id) {
+                    list.items.push(list_item);
+                }
+            }))
+        }
+        Msg::Action(Action::Ctx(ActionCtx::RemoveFromList { id, item_id })) => {
+            list_update_effects(lists, id, |list| {
+                list.items.retain(|item| item.id != *item_id)
+            })
+        }
+        Msg::Action(Action::Ctx(ActionCtx::MoveListItem {
+            id,
+            item_id,
+            position,
+        })) => list_update_effects(lists, id, |list| {
+            if let Some(index) = list.items.iter().position(|item| item.id == *item_id) {
+                let item = list.items.remove(index);
+                let position = (*position).min(list.items.len());
+                list.items.insert(position, item);
+            }
+        }),
+        Msg::Action(Action::Ctx(ActionCtx::MoveList { id, position })) => {
+            let mut ordered_lists = lists.ordered();
+            match ordered_lists.iter().position(|list| list.id == *id) {
+                Some(index) => {
+                    let list = ordered_lists.remove(index);
+                    let position = (*position).min(ordered_lists.len());
+                    ordered_lists.insert(position, list);
+                    ordered_lists
+                        .into_iter()
+                        .enumerate()
+                        .filter(|(position, list)| list.position != *position)
+                        .map(|(position, list)| List {
+                            position,
+                            ..list.to_owned()
+                        })
+                        .map(|list| Effects::msg(Msg::Internal(Internal::UpdateList(list))))
+                        .fold(Effects::none(), |effects, list_effects| {
+                            effects.join(list_effects)
+                        })
+                        .unchanged()
+                }
+                None => list_not_found_effects(id),
+            }
+        }
+        Msg::Internal(Internal::UpdateList(list)) if lists.items.get(&list.id) != Some(list) => {
+            let mut list = list.to_owned();
+            list.mtime = E::now();
+            let push_to_api_effects = match auth_key {
+                Some(auth_key) => {
+                    Effects::one(push_lists_to_api(vec![list.to_owned()], auth_key)).unchanged()
+                }
+                _ => Effects::none().unchanged(),
+            };
+            let id = list.id.to_owned();
+            lists.items.insert(id.to_owned(), list);
+            Effects::msg(Msg::Event(Event::ListUpdated { id }))
+                .join(push_to_api_effects)
+                .join(Effects::msg(Msg::Internal(Internal::ListsChanged)))
+        }
+        Msg::Internal(Internal::ListsPullResult(
+            DatastoreRequest {
+                auth_key: loading_auth_key,
+                ..
+            },
+            result,
+        )) if Some(loading_auth_key) == auth_key => match result {
+            Ok(remote_lists) => {
+                let ids = remote_lists.iter().map(|list| list.id.to_owned()).collect();
+                let mut next_lists = lists.to_owned();
+                let push_lists = next_lists.merge(remote_lists.to_owned());
+                let push_to_api_effects = if push_lists.is_empty() {
+                    Effects::none().unchanged()
+                } else {
+                    Effects::one(push_lists_to_api(push_lists, loading_auth_key)).unchanged()
+                };
+                let lists_effects = if *lists != next_lists {
+                    *lists = next_lists;
+                    Effects::msg(Msg::Internal(Internal::ListsChanged))
+                } else {
+                    Effects::none().unchanged()
+                };
+                Effects::msg(Msg::Event(Event::ListsPulledFromAPI { ids }))
+                    .unchanged()
+                    .join(push_to_api_effects)
+                    .join(lists_effects)
+            }
+            Err(error) => Effects::msg(Msg::Event(Event::Error {
+                error: error.to_owned(),
+                source: Box::new(Event::ListsPulledFromAPI { ids: vec![] }),
+            }))
+            .unchanged(),
+        },
+        Msg::Internal(Internal::BackupRestoreResult(mode, Ok(response))) => match &response.lists {
+            Some(restored) => {
+                let now = E::now();
+                let restored_lists = match mode {
+                    RestoreMode::Merge => restored
+                        .items
+                        .values()
+                        .filter(|list| {
+                            lists
+                                .items
+                                .get(&list.id)
+                                .map_or(true, |current| list.mtime > current.mtime)
+                        })
+                        .cloned()
+                        .collect::<Vec<_>>(),
+                    RestoreMode::Replace => restored
+                        .items
+                        .values()
+                        .filter(|list| lists.items.get(&list.id) != Some(list))
+                        .map(|list| List {
+                            mtime: now,
+                            ..list.to_owned()
+                        })
+                        .chain(
+                            lists
+                                .items
+                                .values()
+                                .filter(|list| {
+                                    !list.removed && !restored.items.contains_key(&list.id)
+                                })
+                                .map(|list| List {
+                                    removed: true,
+                                    mtime: now,
+                                    ..list.to_owned()
+                                }),
+                        )
+                        .collect::<Vec<_>>(),
+                };
+                if restored_lists.is_empty() {
+                    return Effects::none().unchanged();
+                }
+                let push_to_api_effects = match auth_key {
+                    Some(auth_key) => {
+                        Effects::one(push_lists_to_api(restored_lists.to_owned(), auth_key))
+                            .unchanged()
+                    }
+                    _ => Effects::none().unchanged(),
+                };
+                lists.items.extend(
+                    restored_lists
+                        .into_iter()
+                        .map(|list| (list.id.to_owned(), list)),
+                );
+                push_to_api_effects.join(Effects::msg(Msg::Internal(Internal::ListsChanged)))
+            }
+            None => Effects::none().unchanged(),
+        },
+        Msg::Internal(Internal::LocalProfileStorageResult(
+            _,
+            Ok(LocalProfileStorageResponse {
+                lists: next_lists, ..
+            }),
+        )) => {
+            next_lists.clone_into(lists);
+            Effects::msg(Msg::Internal(Internal::ListsChanged))
+        }
+        Msg::Internal(Internal::ListsChanged) => {
+            Effects::one(push_lists_to_storage::<E>(lists, local_profiles)).unchanged()
+        }
+        _ => Effects::none().unchanged(),
+    }
+}
+
+/// A new list placed after the other lists
+fn new_list<E: Env + 'static>(lists: &ListsBucket, name: String, items: Vec<ListItem>) -> List {
+    let now = E::now();
+    List {
+        id: random_id::<E>(),
+        name,
+        items,
+        position: lists.ordered().len(),
+        removed: false,
+        ctime: now,
+        mtime: now,
+    }
+}
+
+fn list_update_effects(
+    lists: &ListsBucket,
+    id: &ListId,
+    update: impl FnOnce(&mut List),
+) -> Effects {
+    match lists.items.get(id) {
+        Some(list) if !list.removed => {
+            let mut list = list.to_owned();
+            update(&mut list);
+            Effects::msg(Msg::Internal(Internal::UpdateList(list))).unchanged()
+        }
+        _ => list_not_found_effects(id),
+    }
+}
+
+fn list_not_found_effects(id: &ListId) -> Effects {
+    Effects::msg(Msg::Event(Event::Error {
+        error: CtxError::from(OtherError::ListNotFound),
+        source: Box::new(Event::ListUpdated { id: id.to_owned() }),
+    }))
+    .unchanged()
+}
+
+/// The items of the lists are filtered from the library,
+/// so the items which are missing or removed from it are added back.
+fn add_to_library_effects<E: Env + 'static>(
+    library: &LibraryBucket,
+    list_items: &[ListItem],
+) -> Effects {
+    list_items
+        .iter()
+        .filter_map(|list_item| match library.items.get(&list_item.id) {
+            Some(library_item) if !library_item.removed => None,
+            Some(library_item) => Some(library_item.to_owned()),
+            None => Some(LibraryItem::from((list_item, PhantomData::<E>))),
+        })
+        .map(|mut library_item| {
+            library_item.removed = false;
+            library_item.temp = false;
+            Effects::msg(Msg::Internal(Internal::UpdateLibraryItem(library_item)))
+        })
+        .fold(Effects::none(), |effects, library_effects| {
+            effects.join(library_effects)
+        })
+        .unchanged()
+}
+
+fn push_lists_to_api(lists: Vec<List>, auth_key: &AuthKey) -> Effect {
+    Effect::Msg(Box::new(Msg::Internal(Internal::EnqueueOutboxRequest(
+        OutboxRequest::Lists {
+            auth_key: auth_key.to_owned(),
+            lists,
+        },
+    ))))
+}
+
+fn pull_lists_from_api<E: Env + 'static>(auth_key: &AuthKey) -> Effect {
+    let request = DatastoreRequest {
+        auth_key: auth_key.to_owned(),
+        collection: LISTS_COLLECTION_NAME.to_owned(),
+        command: DatastoreCommand::Get {
+            ids: vec![],
+            all: true,
+        },
+    };
+    EffectFuture::Concurrent(
+        fetch_api::<E, _, _, Vec<List>>(&request)
+            .map_err(CtxError::from)
+            .and_then(|result| match result {
+                APIResult::Ok(result) => future::ok(result),
+                APIResult::Err(error) => future::err(CtxError::from(error)),
+            })
+            .map(move |result| Msg::Internal(Internal::ListsPullResult(request, result)))
+            .boxed_env(),
+    )
+    .into()
+}
+
+fn push_lists_to_storage<E: Env + 'static>(
+    lists: &ListsBucket,
+    local_profiles: &LocalProfilesBucket,
+) -> Effect {
+    EffectFuture::Sequential(
+        E::set_storage(&local_profiles.storage_key(LISTS_STORAGE_KEY), Some(&lists))
+            .map(enclose!((lists.uid => uid) move |result| match result {
+                Ok(_) => Msg::Event(Event::ListsPushedToStorage { uid }),
+                Err(error) => Msg::Event(Event::Error {
+                    error: CtxError::from(error),
+                    source: Box::new(Event::ListsPushedToStorage { uid }),
+                })
+            }))
+            .boxed_env(),
+    )
+    .into()
+}
@@ -0,0 +1,305 @@
+use enclose::enclose;
+use futures::{future, FutureExt, TryFutureExt};
+
+use crate::constants::{
+    LIBRARY_RECENT_STORAGE_KEY, LIBRARY_STORAGE_KEY, LISTS_STORAGE_KEY, LOCAL_PROFILES_STORAGE_KEY,
+    LOCAL_PROFILE_STORAGE_KEYS, SEARCH_HISTORY_STORAGE_KEY, STREAMS_STORAGE_KEY,
+};
+use crate::models::ctx::{CtxError, CtxStatus, OtherError};
+use crate::runtime::msg::{
+    Action, ActionCtx, CtxAuthResponse, Event, Internal, LocalProfileStorageResponse, Msg,
+};
+use crate::runtime::{Effect, EffectFuture, Effects, Env, EnvFutureExt};
+use crate::types::backup::RestoreMode;
+use crate::types::library::LibraryBucket;
+use crate::types::lists::ListsBucket;
+use crate::types::local_profiles::{
+    local_profile_storage_key, LocalProfile, LocalProfileId, LocalProfilesBucket,
+};
+use crate::types::profile::{Profile, Settings};
+use crate::types::random_id;
+use crate::types::search_history::SearchHistoryBucket;
+use crate::types::streams::StreamsBucket;
+
+pub fn update_local_profiles<E: Env + 'static>(
+    local_profiles: &mut LocalProfilesBucket,
+    profile: &Profile,
+    status: &CtxStatus,
+    msg: &Msg,
+) -> Effects {
+    match msg {
+        Msg::Internal(Internal::Logout(_)) => {
+            let remove_effects = remove_local_profiles_from_storage::<E>(local_profiles);
+            let next_local_profiles = LocalProfilesBucket::new(None, profile.settings.to_owned());
+            let local_profiles_effects = if *local_profiles != next_local_profiles {
+                *local_profiles = next_local_profiles;
+                Effects::msg(Msg::Internal(Internal::LocalProfilesChanged))
+            } else {
+                Effects::none().unchanged()
+            };
+            remove_effects.join(local_profiles_effects)
+        }
+        Msg::Internal(Internal::CtxAuthResult(auth_request, result)) => match (status, result) {
+            (CtxStatus::Loading(loading_auth_request), Ok(CtxAuthResponse { auth, .. }))
+                if loading_auth_request == auth_request =>
+            {
+                let remove_effects = remove_local_profiles_from_storage::<E>(local_profiles);
+                let next_local_profiles = LocalProfilesBucket::new(
+                    Some(auth.user.id.to_owned()),
+                    profile.settings.to_owned(),
+                );
+                let local_profiles_effects = if *local_profiles != next_local_profiles {
+                    *local_profiles = next_local_profiles;
+                    Effects::msg(Msg::Internal(Internal::LocalProfilesChanged))
+                } else {
+                    Effects::none().unchanged()
+                };
+                remove_effects.join(local_profiles_effects)
+            }
+            _ => Effects::none().unchanged(),
+        },
+        Msg::Action(Action::Ctx(ActionCtx::AddLocalProfile { name })) => {
+            let id = random_id::<E>();
+            let name = name.trim();
+            if name.is_empty() {
+                return Effects::msg(Msg::Event(Event::Error {
+                    error: CtxError::from(OtherError::LocalProfileNameIsEmpty),
+                    source: Box::new(Event::LocalProfileAdded { id }),
+                }))
+                .unchanged();
+            }
+
+            local_profiles.profiles.push(LocalProfile {
+                id: id.to_owned(),
+                name: name.to_owned(),
+                settings: Settings::default(),
+            });
+            Effects::msg(Msg::Event(Event::LocalProfileAdded { id }))
+                .join(Effects::msg(Msg::Internal(Internal::LocalProfilesChanged)))
+        }
+        Msg::Action(Action::Ctx(ActionCtx::RemoveLocalProfile(id))) => {
+            match local_profiles.get(id) {
+                Some(local_profile)
+                    if !local_profile.is_default()
+                        && local_profile.id != local_profiles.selected =>
+                {
+                    local_profiles.profiles.retain(|profile| profile.id != *id);
+                    Effects::one(remove_local_profile_from_storage::<E>(id))
+                        .join(Effects::msg(Msg::Internal(Internal::LocalProfilesChanged)))
+                }
+                Some(_) => Effects::msg(Msg::Event(Event::Error {
+                    error: CtxError::from(OtherError::LocalProfileCannotBeRemoved),
+                    source: Box::new(Event::LocalProfileRemoved { id: id.to_owned() }),
+                }))
+                .unchanged(),
+                None => Effects::msg(Msg::Event(Event::Error {
+                    error: CtxError::from(OtherError::LocalProfileNotFound),
+                    source: Box::new(Event::LocalProfileRemoved { id: id.to_owned() }),
+                }))
+                .unchanged(),
+            }
+        }
+        Msg::Action(Action::Ctx(ActionCtx::SwitchLocalProfile(id))) => {
+            match local_profiles.get(id) {
+                Some(local_profile) if local_profile.id == local_profiles.selected => {
+                    Effects::msg(Msg::Event(Event::LocalProfileSwitched {
+                        id: id.to_owned(),
+                    }))
+                    .unchanged()
+                }
+                Some(local_profile) => Effects::one(pull_local_profile_from_storage::<E>(
+                    local_profile,
+                    local_profiles,
+                ))
+                .unchanged(),
+                None => Effects::msg(Msg::Event(Event::Error {
+                    error: CtxError::from(OtherError::LocalProfileNotFound),
+                    source: Box::new(Event::LocalProfileSwitched { id: id.to_owned() }),
+                }))
+                .unchanged(),
+            }
+        }
+        Msg::Internal(Internal::LocalProfileStorageResult(id, result)) => match result {
+            Ok(_) => {
+                local_profiles.selected = id.to_owned();
+                Effects::msg(Msg::Event(Event::LocalProfileSwitched {
+                    id: id.to_owned(),
+                }))
+                .join(Effects::msg(Msg::Internal(Internal::LocalProfilesChanged)))
+            }
+            Err(error) => Effects::msg(Msg::Event(Event::Error {
+                error: error.to_owned(),
+                source: Box::new(Event::LocalProfileSwitched { id: id.to_owned() }),
+            }))
+            .unchanged(),
+        },
+        Msg::Internal(Internal::ProfileChanged) => {
+            let selected = local_profiles.selected.to_owned();
+            match local_profiles
+                .profiles
+                .iter_mut()
+                .find(|local_profile| local_profile.id == selected)
+            {
+                Some(local_profile) if local_profile.settings != profile.settings => {
+                    profile.settings.clone_into(&mut local_profile.settings);
+                    Effects::msg(Msg::Internal(Internal::LocalProfilesChanged))
+                }
+                _ => Effects::none().unchanged(),
+            }
+        }
+        Msg::Internal(Internal::BackupRestoreResult(mode, Ok(response))) => {
+            match &response.local_profiles {
+                Some(restored) => {
+                    let next_profiles = match mode {
+                        RestoreMode::Merge => local_profiles
+                            .profiles
+                            .iter()
+                            .chain(restored.profiles.iter().filter(|restored_profile| {
+                                local_profiles.get(&restored_profile.id).is_none()
+                            }))
+                            .cloned()
+                            .collect::<Vec<_>>(),
+                        RestoreMode::Replace => {
+                            let mut profiles = restored.profiles.to_owned();
+                            match local_profiles.get(&local_profiles.selected) {
+                                Some(selected) if restored.get(&selected.id).is_none() => {
+                                    profiles.push(selected.to_owned())
+                                }
+                                _ => {}
+                            };
+                            profiles
+                        }
+                    };
+                    let remove_effects = Effects::many(
+                        local_profiles
+                            .profiles
+                            .iter()
+                            .filter(|local_profile| {
+                                next_profiles
+                                    .iter()
+                                    .all(|next_profile| next_profile.id != local_profile.id)
+                            })
+                            .map(|local_profile| {
+                                remove_local_profile_from_storage::<E>(&local_profile.id)
+                            })
+                            .collect(),
+                    )
+                    .unchanged();
+                    if local_profiles.profiles != next_profiles {
+                        local_profiles.profiles = next_profiles;
+                        remove_effects
+                            .join(Effects::msg(Msg::Internal(Internal::LocalProfilesChanged)))
+                    } else {
+                        remove_effects
+                    }
+                }
+                None => Effects::none().unchanged(),
+            }
+        }
+        Msg::Internal(Internal::LocalProfilesChanged) => {
+            Effects::one(push_local_profiles_to_storage::<E>(local_profiles)).unchanged()
+        }
+        _ => Effects::none().unchanged(),
+    }
+}
+
+fn pull_local_profile_from_storage<E: Env + 'static>(
+    local_profile: &LocalProfile,
+    local_profiles: &LocalProfilesBucket,
+) -> Effect {
+    let id = local_profile.id.to_owned();
+    let settings = local_profile.settings.to_owned();
+    let uid = local_profiles.uid.to_owned();
+    EffectFuture::Sequential(
+        future::try_join5(
+            E::get_storage::<LibraryBucket>(&local_profile_storage_key(
+                LIBRARY_RECENT_STORAGE_KEY,
+                &id,
+            )),
+            E::get_storage::<LibraryBucket>(&local_profile_storage_key(LIBRARY_STORAGE_KEY, &id)),
+            E::get_storage::<StreamsBucket>(&local_profile_storage_key(STREAMS_STORAGE_KEY, &id)),
+            E::get_storage::<SearchHistoryBucket>(&local_profile_storage_key(
+                SEARCH_HISTORY_STORAGE_KEY,
+                &id,
+            )),
+            E::get_storage::<ListsBucket>(&local_profile_storage_key(LISTS_STORAGE_KEY, &id)),
+        )
+        .map_ok(
+            enclose!((uid) move |(recent_bucket, other_bucket, streams, search_history, lists)| {
+                let mut library = LibraryBucket::new(uid.to_owned(), vec![]);
+                if let Some(recent_bucket) = recent_bucket {
+                    library.merge_bucket(recent_bucket);
+                };
+                if let Some(other_bucket) = other_bucket {
+                    library.merge_bucket(other_bucket);
+                };
+                LocalProfileStorageResponse {
+                    settings,
+                    library,
+                    streams: streams.unwrap_or_else(|| StreamsBucket::new(uid.to_owned())),
+                    search_history: search_history
+                        .unwrap_or_else(|| SearchHistoryBucket::new(uid.to_owned())),
+                    lists: lists.unwrap_or_else(|| ListsBucket::new(uid.to_owned())),
+                }
+            }),
+        )
+        .map_err(CtxError::from)
+        .map(move |result| Msg::Internal(Internal::LocalProfileStorageResult(id, result)))
+        .boxed_env(),
+    )
+    .into()
+}
+
+fn remove_local_profile_from_storage<E: Env + 'static>(id: &LocalProfileId) -> Effect {
+    EffectFuture::Sequential(
+        future::try_join_all(
+            LOCAL_PROFILE_STORAGE_KEYS
+                .into_iter()
+                .map(|key| E::set_storage::<()>(&local_profile_storage_key(key, id), None)),
+        )
+        .map(enclose!((id) move |result| match result {
+            Ok(_) => Msg::Event(Event::LocalProfileRemoved { id }),
+            Err(error) => Msg::Event(Event::Error {
+                error: CtxError::from(error),
+                source: Box::new(Event::LocalProfileRemoved { id }),
+            }),
+        }))
+        .boxed_env(),
+    )
+    .into()
+}
+
+/// Removes the data of all the profiles except the default one,
+/// which is replaced in the storage by the new buckets.
+fn remove_local_profiles_from_storage<E: Env + 'static>(
+    local_profiles: &LocalProfilesBucket,
+) -> Effects {
+    Effects::many(
+        local_profiles
+            .profiles
+            .iter()
+            .filter(|local_profile| !local_profile.is_default())
+            .map(|local_profile| remove_local_profile_from_storage::<E>(&local_profile.id))
+            .collect(),
+    )
+    .unchanged()
+}
+
+fn push_local_profiles_to_storage<E: Env + 'static>(
+    local_profiles: &LocalProfilesBucket,
+) -> Effect {
+    EffectFuture::Sequential(
+        E::set_storage(LOCAL_PROFILES_STORAGE_KEY, Some(local_profiles))
+            .map(
+                enclose!((local_profiles.uid => uid) move |result| match result {
+                    Ok(_) => Msg::Event(Event::LocalProfilesPushedToStorage { uid }),
+                    Err(error) => Msg::Event(Event::Error {
+                        error: CtxError::from(error),
+                        source: Box::new(Event::LocalProfilesPushedToStorage { uid }),
+                    })
+                }),
+            )
+            .boxed_env(),
+    )
+    .into()
+}
@@ -13,7 +13,7 @@ use crate::{
             eq_update, resources_update_with_vector_content, Loadable, ResourceLoadable,
             ResourcesAction,
         },
-        ctx::{CtxError, CtxStatus},
+        ctx::{restore_items, CtxError, CtxStatus},
     },
     runtime::{
         msg::{Action, ActionCtx, Event, Internal, Msg},
@@ -21,6 +21,7 @@ use crate::{
     },
     types::{
         addon::{AggrRequest, ExtraType},
+        backup::RestoreMode,
         library::LibraryBucket,
         notifications::{NotificationItem, NotificationsBucket},
         profile::Profile,
@@ -184,6 +185,33 @@ pub fn update_notifications<E: Env + 'static>(
         Msg::Internal(Internal::DismissNotificationItem(id)) => {
             dismiss_notification_item::<E>(library, notifications, id)
         }
+        Msg::Internal(Internal::BackupRestoreResult(mode, Ok(response))) => {
+            let changed = match (&response.notifications, mode) {
+                (Some(restored), RestoreMode::Replace) => restore_items(
+                    &mut notifications.items,
+                    restored.items.to_owned(),
+                    *mode,
+                    |_, _| true,
+                ),
+                // the notifications of the same meta item are merged by their videos
+                (Some(restored), RestoreMode::Merge) => {
+                    restored
+                        .items
+                        .iter()
+                        .fold(false, |changed, (meta_id, restored_videos)| {
+                            let videos = notifications.items.entry(meta_id.to_owned()).or_default();
+                            restore_items(videos, restored_videos.to_owned(), *mode, |_, _| false)
+                                || changed
+                        })
+                }
+                (None, _) => false,
+            };
+            if changed {
+                Effects::msg(Msg::Internal(Internal::NotificationsChanged))
+            } else {
+                Effects::none().unchanged()
+            }
+        }
         Msg::Internal(Internal::NotificationsChanged) => {
             Effects::one(push_notifications_to_storage::<E>(notifications)).unchanged()
         }
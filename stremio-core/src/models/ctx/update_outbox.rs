@@ -0,0 +1,219 @@
+use enclose::enclose;
+use futures::{future, FutureExt, TryFutureExt};
+
+use crate::constants::{
+    LIBRARY_COLLECTION_NAME, LISTS_COLLECTION_NAME, OUTBOX_RETRY_TIMER_ATTEMPTS, OUTBOX_STORAGE_KEY,
+};
+use crate::models::ctx::{CtxError, CtxStatus};
+use crate::runtime::msg::{Action, ActionCtx, CtxAuthResponse, Event, Internal, Msg};
+use crate::runtime::{Effect, EffectFuture, Effects, Env, EnvError, EnvFutureExt};
+use crate::types::api::{
+    fetch_api, APIRequest, APIResult, DatastoreCommand, DatastoreRequest, SuccessResponse,
+};
+use crate::types::outbox::{OutboxBucket, OutboxItem, OutboxRequest};
+use crate::types::profile::Profile;
+
+pub fn update_outbox<E: Env + 'static>(
+    outbox: &mut OutboxBucket,
+    profile: &Profile,
+    status: &CtxStatus,
+    msg: &Msg,
+) -> Effects {
+    match msg {
+        Msg::Internal(Internal::Logout(_)) => {
+            let next_outbox = OutboxBucket::default();
+            if *outbox != next_outbox {
+                *outbox = next_outbox;
+                Effects::msg(Msg::Internal(Internal::OutboxChanged))
+            } else {
+                Effects::none().unchanged()
+            }
+        }
+        Msg::Internal(Internal::CtxAuthResult(auth_request, result)) => match (status, result) {
+            (CtxStatus::Loading(loading_auth_request), Ok(CtxAuthResponse { auth, .. }))
+                if loading_auth_request == auth_request =>
+            {
+                let next_outbox = OutboxBucket::new(Some(auth.user.id.to_owned()));
+                if *outbox != next_outbox {
+                    *outbox = next_outbox;
+                    Effects::msg(Msg::Internal(Internal::OutboxChanged))
+                } else {
+                    Effects::none().unchanged()
+                }
+            }
+            _ => Effects::none().unchanged(),
+        },
+        Msg::Action(Action::Ctx(ActionCtx::FlushOutbox)) => {
+            let flush_effects = flush_outbox::<E>(outbox, profile);
+            // the failed requests restored from the storage may not be due yet
+            let retry_effects = match outbox.next_retry() {
+                Some(item) => retry_timer::<E>(item),
+                None => Effects::none().unchanged(),
+            };
+            flush_effects.join(retry_effects)
+        }
+        Msg::Internal(Internal::OutboxRetryDue) => flush_outbox::<E>(outbox, profile),
+        Msg::Internal(Internal::EnqueueOutboxRequest(request)) => {
+            outbox.enqueue(request.to_owned(), E::now());
+            flush_outbox::<E>(outbox, profile)
+                .join(Effects::msg(Msg::Internal(Internal::OutboxChanged)))
+        }
+        Msg::Internal(Internal::OutboxRequestResult(request, result))
+            if Some(request.auth_key()) == profile.auth_key() =>
+        {
+            let result_effects = match result {
+                Ok(_) => {
+                    outbox.acknowledge(request);
+                    // the API is reachable again, retry the requests which failed before
+                    Effects::msg(Msg::Event(pushed_event(request)))
+                        .join(flush_outbox::<E>(outbox, profile))
+                }
+                Err(error) => {
+                    let retry_effects = match error {
+                        // the request is retried only if the API could not be reached
+                        CtxError::Env(EnvError::Fetch(_)) => {
+                            match outbox.retry_later(request, E::now()) {
+                                Some(item) => retry_timer::<E>(item),
+                                None => Effects::none().unchanged(),
+                            }
+                        }
+                        _ => {
+                            outbox.discard(request);
+                            Effects::none().unchanged()
+                        }
+                    };
+                    Effects::msg(Msg::Event(Event::Error {
+                        error: error.to_owned(),
+                        source: Box::new(pushed_event(request)),
+                    }))
+                    .join(retry_effects)
+                }
+            };
+            result_effects
+                .unchanged()
+                .join(Effects::msg(Msg::Internal(Internal::OutboxChanged)))
+        }
+        Msg::Internal(Internal::OutboxChanged) => {
+            Effects::msg(Msg::Event(Event::OutboxSizeChanged {
+                size: outbox.items.len(),
+            }))
+            .join(Effects::one(push_outbox_to_storage::<E>(outbox)))
+            .unchanged()
+        }
+        _ => Effects::none().unchanged(),
+    }
+}
+
+/// Pushes the requests which are due and not in flight already.
+fn flush_outbox<E: Env + 'static>(outbox: &mut OutboxBucket, profile: &Profile) -> Effects {
+    match profile.auth_key() {
+        Some(auth_key) => Effects::many(
+            outbox
+                .take_due_requests(E::now(), auth_key)
+                .into_iter()
+                .map(push_to_api::<E>)
+                .collect(),
+        )
+        .unchanged(),
+        None => Effects::none().unchanged(),
+    }
+}
+
+/// Schedules the retry of a failed request, unless it has failed too many times already.
+fn retry_timer<E: Env + 'static>(item: &OutboxItem) -> Effects {
+    if item.attempts > OUTBOX_RETRY_TIMER_ATTEMPTS {
+        return Effects::none().unchanged();
+    }
+    Effects::future(EffectFuture::Concurrent(
+        E::sleep(item.retry_at - E::now())
+            .then(|result| match result {
+                Ok(()) => future::ready(Msg::Internal(Internal::OutboxRetryDue)).boxed_env(),
+                // without timers the request is retried with the next push
+                Err(_) => future::pending().boxed_env(),
+            })
+            .boxed_env(),
+    ))
+    .unchanged()
+}
+
+fn pushed_event(request: &OutboxRequest) -> Event {
+    match request {
+        OutboxRequest::LibraryItems { items, .. } => Event::LibraryItemsPushedToAPI {
+            ids: items.iter().map(|item| item.id.to_owned()).collect(),
+        },
+        OutboxRequest::Lists { lists, .. } => Event::ListsPushedToAPI {
+            ids: lists.iter().map(|list| list.id.to_owned()).collect(),
+        },
+        OutboxRequest::Addons { addons, .. } => Event::AddonsPushedToAPI {
+            transport_urls: addons
+                .iter()
+                .map(|addon| &addon.transport_url)
+                .cloned()
+                .collect(),
+        },
+        OutboxRequest::User { user, .. } => Event::UserPushedToAPI {
+            uid: Some(user.id.to_owned()),
+        },
+    }
+}
+
+fn push_to_api<E: Env + 'static>(request: OutboxRequest) -> Effect {
+    let fetch = match &request {
+        OutboxRequest::LibraryItems { auth_key, items } => {
+            fetch_api::<E, _, _, SuccessResponse>(&DatastoreRequest {
+                auth_key: auth_key.to_owned(),
+                collection: LIBRARY_COLLECTION_NAME.to_owned(),
+                command: DatastoreCommand::Put {
+                    changes: items.to_owned(),
+                },
+            })
+        }
+        OutboxRequest::Lists { auth_key, lists } => {
+            fetch_api::<E, _, _, SuccessResponse>(&DatastoreRequest {
+                auth_key: auth_key.to_owned(),
+                collection: LISTS_COLLECTION_NAME.to_owned(),
+                command: DatastoreCommand::PutLists {
+                    changes: lists.to_owned(),
+                },
+            })
+        }
+        OutboxRequest::Addons { auth_key, addons } => {
+            fetch_api::<E, _, _, SuccessResponse>(&APIRequest::AddonCollectionSet {
+                auth_key: auth_key.to_owned(),
+                addons: addons.to_owned(),
+            })
+        }
+        OutboxRequest::User { auth_key, user } => {
+            fetch_api::<E, _, _, SuccessResponse>(&APIRequest::SaveUser {
+                auth_key: auth_key.to_owned(),
+                user: user.to_owned(),
+            })
+        }
+    };
+    EffectFuture::Concurrent(
+        fetch
+            .map_err(CtxError::from)
+            .and_then(|result| match result {
+                APIResult::Ok(_) => future::ok(()),
+                APIResult::Err(error) => future::err(CtxError::from(error)),
+            })
+            .map(move |result| Msg::Internal(Internal::OutboxRequestResult(request, result)))
+            .boxed_env(),
+    )
+    .into()
+}
+
+fn push_outbox_to_storage<E: Env + 'static>(outbox: &OutboxBucket) -> Effect {
+    EffectFuture::Sequential(
+        E::set_storage(OUTBOX_STORAGE_KEY, Some(outbox))
+            .map(enclose!((outbox.uid => uid) move |result| match result {
+                Ok(_) => Msg::Event(Event::OutboxPushedToStorage { uid }),
+                Err(error) => Msg::Event(Event::Error {
+                    error: CtxError::from(error),
+                    source: Box::new(Event::OutboxPushedToStorage { uid }),
+                })
+            }))
+            .boxed_env(),
+    )
+    .into()
+}
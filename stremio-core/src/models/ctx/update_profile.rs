@@ -5,13 +5,15 @@ use futures::{future, FutureExt, TryFutureExt};
 
 use crate::constants::{OFFICIAL_ADDONS, PROFILE_STORAGE_KEY};
 use crate::models::ctx::{CtxError, CtxStatus, OtherError};
-use crate::runtime::msg::{Action, ActionCtx, CtxAuthResponse, Event, Internal, Msg};
+use crate::runtime::msg::{
+    Action, ActionCtx, CtxAuthResponse, Event, Internal, LocalProfileStorageResponse, Msg,
+};
 use crate::runtime::{Effect, EffectFuture, Effects, Env, EnvFutureExt};
 use crate::types::addon::Descriptor;
-use crate::types::api::{
-    fetch_api, APIError, APIRequest, APIResult, CollectionResponse, SuccessResponse,
-};
-use crate::types::profile::{Auth, AuthKey, Password, Profile, Settings, User};
+use crate::types::api::{fetch_api, APIError, APIRequest, APIResult, CollectionResponse};
+use crate::types::backup::RestoreMode;
+use crate::types::outbox::OutboxRequest;
+use crate::types::profile::{Auth, AuthKey, ParentalControls, Password, Profile, Settings, User};
 use crate::types::streams::StreamsBucket;
 
 pub fn update_profile<E: Env + 'static>(
@@ -22,7 +24,10 @@ pub fn update_profile<E: Env + 'static>(
 ) -> Effects {
     match msg {
         Msg::Internal(Internal::Logout(_)) => {
-            let next_profile = Profile::default();
+            let next_profile = Profile {
+                parental_controls: profile.parental_controls.to_owned(),
+                ..Default::default()
+            };
             if *profile != next_profile {
                 *profile = next_profile;
                 Effects::msg(Msg::Internal(Internal::ProfileChanged))
@@ -40,7 +45,7 @@ pub fn update_profile<E: Env + 'static>(
         },
         Msg::Action(Action::Ctx(ActionCtx::PushUserToAPI)) => match &profile.auth {
             Some(Auth { key, user }) => {
-                Effects::one(push_user_to_api::<E>(user.to_owned(), key)).unchanged()
+                Effects::one(push_user_to_api(user.to_owned(), key)).unchanged()
             }
             _ => Effects::msg(Msg::Event(Event::Error {
                 error: CtxError::from(OtherError::UserNotLoggedIn),
@@ -67,8 +72,7 @@ pub fn update_profile<E: Env + 'static>(
         }
         Msg::Action(Action::Ctx(ActionCtx::PushAddonsToAPI)) => match profile.auth_key() {
             Some(auth_key) => {
-                Effects::one(push_addons_to_api::<E>(profile.addons.to_owned(), auth_key))
-                    .unchanged()
+                Effects::one(push_addons_to_api(profile.addons.to_owned(), auth_key)).unchanged()
             }
             _ => Effects::msg(Msg::Event(Event::Error {
                 error: CtxError::from(OtherError::UserNotLoggedIn),
@@ -163,7 +167,7 @@ pub fn update_profile<E: Env + 'static>(
             addon.clone_into(&mut profile.addons[addon_position]);
             let push_to_api_effects = match profile.auth_key() {
                 Some(auth_key) => {
-                    Effects::one(push_addons_to_api::<E>(profile.addons.to_owned(), auth_key))
+                    Effects::one(push_addons_to_api(profile.addons.to_owned(), auth_key))
                         .unchanged()
                 }
                 _ => Effects::none().unchanged(),
@@ -195,11 +199,10 @@ pub fn update_profile<E: Env + 'static>(
                         .retain(|_key, item| item.stream_transport_url != addon.transport_url);
 
                     let push_to_api_effects = match profile.auth_key() {
-                        Some(auth_key) => Effects::one(push_addons_to_api::<E>(
-                            profile.addons.to_owned(),
-                            auth_key,
-                        ))
-                        .unchanged(),
+                        Some(auth_key) => {
+                            Effects::one(push_addons_to_api(profile.addons.to_owned(), auth_key))
+                                .unchanged()
+                        }
                         _ => Effects::none().unchanged(),
                     };
                     Effects::msg(Msg::Event(Event::AddonUninstalled {
@@ -219,8 +222,7 @@ pub fn update_profile<E: Env + 'static>(
             Some(Auth { user, key }) => {
                 if user.trakt.is_some() {
                     user.trakt = None;
-                    let push_to_api_effects =
-                        Effects::one(push_user_to_api::<E>(user.to_owned(), key));
+                    let push_to_api_effects = Effects::one(push_user_to_api(user.to_owned(), key));
 
                     Effects::msg(Msg::Event(Event::TraktLoggedOut { uid: profile.uid() }))
                         .join(push_to_api_effects)
@@ -252,6 +254,43 @@ pub fn update_profile<E: Env + 'static>(
                 .unchanged()
             }
         }
+        Msg::Action(Action::Ctx(ActionCtx::SetParentalControls { pin, restrictions })) => {
+            if profile.parental_controls.is_active() {
+                return parental_controls_error_effects(
+                    &profile.parental_controls,
+                    OtherError::ParentalControlsAreLocked,
+                );
+            }
+
+            profile.parental_controls = ParentalControls::new::<E>(pin, restrictions.to_owned());
+            parental_controls_updated_effects(&profile.parental_controls)
+        }
+        Msg::Action(Action::Ctx(ActionCtx::UnlockParentalControls { pin })) => {
+            if let Err(effects) =
+                verify_parental_controls_pin::<E>(&mut profile.parental_controls, pin)
+            {
+                return effects;
+            }
+
+            profile.parental_controls.locked = false;
+            parental_controls_updated_effects(&profile.parental_controls)
+        }
+        Msg::Action(Action::Ctx(ActionCtx::LockParentalControls))
+            if profile.parental_controls.is_enabled() && !profile.parental_controls.locked =>
+        {
+            profile.parental_controls.locked = true;
+            parental_controls_updated_effects(&profile.parental_controls)
+        }
+        Msg::Action(Action::Ctx(ActionCtx::DisableParentalControls { pin })) => {
+            if let Err(effects) =
+                verify_parental_controls_pin::<E>(&mut profile.parental_controls, pin)
+            {
+                return effects;
+            }
+
+            profile.parental_controls = ParentalControls::default();
+            parental_controls_updated_effects(&profile.parental_controls)
+        }
         Msg::Internal(Internal::ProfileChanged) => {
             Effects::one(push_profile_to_storage::<E>(profile)).unchanged()
         }
@@ -273,11 +312,10 @@ pub fn update_profile<E: Env + 'static>(
                         profile.addons.push(addon.to_owned());
                     };
                     let push_to_api_effects = match profile.auth_key() {
-                        Some(auth_key) => Effects::one(push_addons_to_api::<E>(
-                            profile.addons.to_owned(),
-                            auth_key,
-                        ))
-                        .unchanged(),
+                        Some(auth_key) => {
+                            Effects::one(push_addons_to_api(profile.addons.to_owned(), auth_key))
+                                .unchanged()
+                        }
                         _ => Effects::none().unchanged(),
                     };
                     Effects::msg(Msg::Event(Event::AddonInstalled {
@@ -307,6 +345,7 @@ pub fn update_profile<E: Env + 'static>(
                     addons: addons_result.to_owned().unwrap_or(OFFICIAL_ADDONS.clone()),
                     addons_locked: addons_result.is_err(),
                     settings: Settings::default(),
+                    parental_controls: profile.parental_controls.to_owned(),
                 };
                 if *profile != next_profile {
                     *profile = next_profile;
@@ -422,37 +461,77 @@ pub fn update_profile<E: Env + 'static>(
             }))
             .unchanged(),
         },
+        Msg::Internal(Internal::LocalProfileStorageResult(
+            _,
+            Ok(LocalProfileStorageResponse { settings, .. }),
+        )) if profile.settings != *settings => {
+            settings.clone_into(&mut profile.settings);
+            Effects::msg(Msg::Internal(Internal::ProfileChanged))
+        }
+        Msg::Internal(Internal::BackupRestoreResult(mode, Ok(response))) => {
+            match &response.profile {
+                Some(restored) => {
+                    let mut next_profile = profile.to_owned();
+                    match mode {
+                        RestoreMode::Replace => {
+                            restored.settings.clone_into(&mut next_profile.settings);
+                            if !profile.addons_locked {
+                                // the protected addons are always kept installed
+                                next_profile.addons = profile
+                                    .addons
+                                    .iter()
+                                    .filter(|addon| {
+                                        addon.flags.protected
+                                            && !restored.addons.iter().any(|restored_addon| {
+                                                restored_addon.transport_url == addon.transport_url
+                                            })
+                                    })
+                                    .chain(restored.addons.iter())
+                                    .cloned()
+                                    .collect();
+                            }
+                        }
+                        RestoreMode::Merge => {
+                            if !profile.addons_locked {
+                                for addon in &restored.addons {
+                                    if !next_profile.addons.iter().any(|installed_addon| {
+                                        installed_addon.transport_url == addon.transport_url
+                                    }) {
+                                        next_profile.addons.push(addon.to_owned());
+                                    }
+                                }
+                            }
+                        }
+                    };
+                    let push_to_api_effects = match profile.auth_key() {
+                        Some(auth_key) if next_profile.addons != profile.addons => Effects::one(
+                            push_addons_to_api(next_profile.addons.to_owned(), auth_key),
+                        )
+                        .unchanged(),
+                        _ => Effects::none().unchanged(),
+                    };
+                    if *profile != next_profile {
+                        *profile = next_profile;
+                        push_to_api_effects
+                            .join(Effects::msg(Msg::Internal(Internal::ProfileChanged)))
+                    } else {
+                        Effects::none().unchanged()
+                    }
+                }
+                None => Effects::none().unchanged(),
+            }
+        }
         _ => Effects::none().unchanged(),
     }
 }
 
-fn push_addons_to_api<E: Env + 'static>(addons: Vec<Descriptor>, auth_key: &AuthKey) -> Effect {
-    let transport_urls = addons
-        .iter()
-        .map(|addon| &addon.transport_url)
-        .cloned()
-        .collect();
-    let request = APIRequest::AddonCollectionSet {
-        auth_key: auth_key.to_owned(),
-        addons,
-    };
-    EffectFuture::Concurrent(
-        fetch_api::<E, _, _, SuccessResponse>(&request)
-            .map_err(CtxError::from)
-            .and_then(|result| match result {
-                APIResult::Ok(result) => future::ok(result),
-                APIResult::Err(error) => future::err(CtxError::from(error)),
-            })
-            .map(move |result| match result {
-                Ok(_) => Msg::Event(Event::AddonsPushedToAPI { transport_urls }),
-                Err(error) => Msg::Event(Event::Error {
-                    error,
-                    source: Box::new(Event::AddonsPushedToAPI { transport_urls }),
-                }),
-            })
-            .boxed_env(),
-    )
-    .into()
+fn push_addons_to_api(addons: Vec<Descriptor>, auth_key: &AuthKey) -> Effect {
+    Effect::Msg(Box::new(Msg::Internal(Internal::EnqueueOutboxRequest(
+        OutboxRequest::Addons {
+            auth_key: auth_key.to_owned(),
+            addons,
+        },
+    ))))
 }
 
 fn pull_user_from_api<E: Env + 'static>(auth_key: &AuthKey, overwritten: bool) -> Effect {
@@ -478,29 +557,13 @@ fn pull_user_from_api<E: Env + 'static>(auth_key: &AuthKey, overwritten: bool) -
     .into()
 }
 
-fn push_user_to_api<E: Env + 'static>(user: User, auth_key: &AuthKey) -> Effect {
-    let uid = Some(user.id.to_owned());
-    let request = APIRequest::SaveUser {
-        auth_key: auth_key.to_owned(),
-        user,
-    };
-    EffectFuture::Concurrent(
-        fetch_api::<E, _, _, SuccessResponse>(&request)
-            .map_err(CtxError::from)
-            .and_then(|result| match result {
-                APIResult::Ok(result) => future::ok(result),
-                APIResult::Err(error) => future::err(CtxError::from(error)),
-            })
-            .map(move |result| match result {
-                Ok(_) => Msg::Event(Event::UserPushedToAPI { uid }),
-                Err(error) => Msg::Event(Event::Error {
-                    error,
-                    source: Box::new(Event::UserPushedToAPI { uid }),
-                }),
-            })
-            .boxed_env(),
-    )
-    .into()
+fn push_user_to_api(user: User, auth_key: &AuthKey) -> Effect {
+    Effect::Msg(Box::new(Msg::Internal(Internal::EnqueueOutboxRequest(
+        OutboxRequest::User {
+            auth_key: auth_key.to_owned(),
+            user,
+        },
+    ))))
 }
 
 fn pull_addons_from_api<E: Env + 'static>(auth_key: &AuthKey) -> Effect {
@@ -524,7 +587,7 @@ fn pull_addons_from_api<E: Env + 'static>(auth_key: &AuthKey) -> Effect {
 
 fn push_profile_to_storage<E: Env + 'static>(profile: &Profile) -> Effect {
     EffectFuture::Sequential(
-        E::set_storage(PROFILE_STORAGE_KEY, Some(profile))
+        E::set_storage(PROFILE_STORAGE_KEY, Some(&profile.to_storage_value()))
             .map(enclose!((profile.uid() => uid) move |result| match result {
                 Ok(_) => Msg::Event(Event::ProfilePushedToStorage { uid }),
                 Err(error) => Msg::Event(Event::Error {
@@ -592,3 +655,47 @@ fn addon_action_error_effects(error: OtherError, source: Event) -> Effects {
     }))
     .unchanged()
 }
+
+fn parental_controls_updated_effects(parental_controls: &ParentalControls) -> Effects {
+    Effects::msg(Msg::Event(Event::ParentalControlsUpdated {
+        parental_controls: parental_controls.to_owned(),
+    }))
+    .join(Effects::msg(Msg::Internal(Internal::ProfileChanged)))
+}
+
+/// Verifies the PIN, the failed attempts are counted and written to the storage.
+fn verify_parental_controls_pin<E: Env + 'static>(
+    parental_controls: &mut ParentalControls,
+    pin: &str,
+) -> Result<(), Effects> {
+    let now = E::now();
+    if parental_controls.is_pin_throttled(now) {
+        return Err(parental_controls_error_effects(
+            parental_controls,
+            OtherError::TooManyParentalControlsPinAttempts,
+        ));
+    }
+
+    if parental_controls.verify_pin(pin, now) {
+        Ok(())
+    } else {
+        Err(parental_controls_error_effects(
+            parental_controls,
+            OtherError::InvalidParentalControlsPin,
+        )
+        .join(Effects::msg(Msg::Internal(Internal::ProfileChanged))))
+    }
+}
+
+fn parental_controls_error_effects(
+    parental_controls: &ParentalControls,
+    error: OtherError,
+) -> Effects {
+    Effects::msg(Msg::Event(Event::Error {
+        error: CtxError::from(error),
+        source: Box::new(Event::ParentalControlsUpdated {
+            parental_controls: parental_controls.to_owned(),
+        }),
+    }))
+    .unchanged()
+}
@@ -2,13 +2,17 @@ use enclose::enclose;
 use futures::FutureExt;
 
 use crate::constants::SEARCH_HISTORY_STORAGE_KEY;
-use crate::models::ctx::{CtxError, CtxStatus};
-use crate::runtime::msg::{Action, ActionCtx, CtxAuthResponse, Event, Internal};
+use crate::models::ctx::{restore_items, CtxError, CtxStatus};
+use crate::runtime::msg::{
+    Action, ActionCtx, CtxAuthResponse, Event, Internal, LocalProfileStorageResponse,
+};
 use crate::runtime::{Effect, EffectFuture, Effects, Env, EnvFutureExt};
+use crate::types::local_profiles::LocalProfilesBucket;
 use crate::{runtime::msg::Msg, types::search_history::SearchHistoryBucket};
 
 pub fn update_search_history<E: Env + 'static>(
     search_history: &mut SearchHistoryBucket,
+    local_profiles: &LocalProfilesBucket,
     status: &CtxStatus,
     msg: &Msg,
 ) -> Effects {
@@ -36,28 +40,58 @@ pub fn update_search_history<E: Env + 'static>(
             }
             _ => Effects::none().unchanged(),
         },
-        Msg::Internal(Internal::SearchHistoryChanged) => {
-            Effects::one(push_search_history_to_storage::<E>(search_history)).unchanged()
+        Msg::Internal(Internal::LocalProfileStorageResult(
+            _,
+            Ok(LocalProfileStorageResponse {
+                search_history: next_search_history,
+                ..
+            }),
+        )) => {
+            next_search_history.clone_into(search_history);
+            Effects::msg(Msg::Internal(Internal::SearchHistoryChanged))
+        }
+        Msg::Internal(Internal::BackupRestoreResult(mode, Ok(response))) => {
+            match &response.search_history {
+                Some(restored)
+                    if restore_items(
+                        &mut search_history.items,
+                        restored.items.to_owned(),
+                        *mode,
+                        |restored, date| restored > date,
+                    ) =>
+                {
+                    Effects::msg(Msg::Internal(Internal::SearchHistoryChanged))
+                }
+                _ => Effects::none().unchanged(),
+            }
         }
+        Msg::Internal(Internal::SearchHistoryChanged) => Effects::one(
+            push_search_history_to_storage::<E>(search_history, local_profiles),
+        )
+        .unchanged(),
         _ => Effects::none().unchanged(),
     }
 }
 
 fn push_search_history_to_storage<E: Env + 'static>(
     search_history: &SearchHistoryBucket,
+    local_profiles: &LocalProfilesBucket,
 ) -> Effect {
     EffectFuture::Sequential(
-        E::set_storage(SEARCH_HISTORY_STORAGE_KEY, Some(&search_history))
-            .map(
-                enclose!((search_history.uid => uid) move |result| match result {
-                    Ok(_) => Msg::Event(Event::SearchHistoryPushedToStorage { uid }),
-                    Err(error) => Msg::Event(Event::Error {
-                        error: CtxError::from(error),
-                        source: Box::new(Event::SearchHistoryPushedToStorage { uid }),
-                    })
-                }),
-            )
-            .boxed_env(),
+        E::set_storage(
+            &local_profiles.storage_key(SEARCH_HISTORY_STORAGE_KEY),
+            Some(&search_history),
+        )
+        .map(
+            enclose!((search_history.uid => uid) move |result| match result {
+                Ok(_) => Msg::Event(Event::SearchHistoryPushedToStorage { uid }),
+                Err(error) => Msg::Event(Event::Error {
+                    error: CtxError::from(error),
+                    source: Box::new(Event::SearchHistoryPushedToStorage { uid }),
+                })
+            }),
+        )
+        .boxed_env(),
     )
     .into()
 }
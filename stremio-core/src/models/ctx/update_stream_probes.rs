@@ -0,0 +1,121 @@
+use std::collections::HashMap;
+
+use http::{header, request, Request};
+use url::Url;
+
+use crate::runtime::msg::{Internal, Msg};
+use crate::runtime::{Effect, EffectFuture, Effects, Env, EnvError, EnvFutureExt, ResponseHead};
+use crate::types::stream_probes::{StreamProbe, StreamProbesBucket};
+
+pub fn update_stream_probes<E: Env + 'static>(
+    stream_probes: &mut StreamProbesBucket,
+    msg: &Msg,
+) -> Effects {
+    match msg {
+        Msg::Internal(Internal::ProbeStreams(probes)) => {
+            let now = E::now();
+            let probes = probes
+                .iter()
+                .filter(|(url, _)| stream_probes.needs_probe(url, now))
+                .collect::<Vec<_>>();
+            if probes.is_empty() {
+                return Effects::none().unchanged();
+            }
+            stream_probes
+                .pending
+                .extend(probes.iter().map(|(url, _)| url.to_owned()));
+            Effects::many(
+                probes
+                    .into_iter()
+                    .map(|(url, headers)| probe_stream::<E>(url, headers))
+                    .collect(),
+            )
+            .join(Effects::msg(Msg::Internal(Internal::StreamProbesChanged)))
+        }
+        Msg::Internal(Internal::StreamProbeResult(url, result))
+            if stream_probes.pending.contains(url) =>
+        {
+            stream_probes.pending.remove(url);
+            let probe = match result {
+                Ok(head) => StreamProbe {
+                    status: Some(head.status),
+                    content_type: head
+                        .header(header::CONTENT_TYPE.as_str())
+                        .map(str::to_owned),
+                    content_length: content_length(head),
+                    error: None,
+                    checked_at: E::now(),
+                },
+                Err(error) => StreamProbe {
+                    status: None,
+                    content_type: None,
+                    content_length: None,
+                    error: Some(error.message()),
+                    checked_at: E::now(),
+                },
+            };
+            stream_probes.insert(url.to_owned(), probe);
+            Effects::msg(Msg::Internal(Internal::StreamProbesChanged))
+        }
+        _ => Effects::none().unchanged(),
+    }
+}
+
+/// Sends a `HEAD` request with the request headers of the stream and falls back
+/// to requesting the first byte when the server does not support `HEAD` requests.
+fn probe_stream<E: Env + 'static>(url: &Url, headers: &HashMap<String, String>) -> Effect {
+    let url = url.to_owned();
+    let headers = headers.to_owned();
+    EffectFuture::Concurrent(
+        async move {
+            let result = async {
+                let head_request = probe_request(Request::head(url.as_str()), &headers)?;
+                match E::fetch_head(head_request).await {
+                    Ok(ResponseHead { status, .. }) if status == 405 || status == 501 => {
+                        let range_request = probe_request(
+                            Request::get(url.as_str()).header(header::RANGE, "bytes=0-0"),
+                            &headers,
+                        )?;
+                        E::fetch_head(range_request).await
+                    }
+                    result => result,
+                }
+            }
+            .await;
+            Msg::Internal(Internal::StreamProbeResult(url, result))
+        }
+        .boxed_env(),
+    )
+    .into()
+}
+
+/// The addons provide the headers, so the invalid ones fail the probe instead of panicking.
+fn probe_request(
+    builder: request::Builder,
+    headers: &HashMap<String, String>,
+) -> Result<Request<()>, EnvError> {
+    headers
+        .iter()
+        .fold(builder, |builder, (name, value)| {
+            builder.header(name.as_str(), value.as_str())
+        })
+        .body(())
+        .map_err(|error| EnvError::Fetch(error.to_string()))
+}
+
+/// The total size from the `Content-Range` of a range response or the `Content-Length`.
+///
+/// The `Content-Length` of a range response is the length of the range,
+/// so the size is unknown when the `Content-Range` is not exposed to cross-origin requests.
+fn content_length(head: &ResponseHead) -> Option<u64> {
+    match head.header(header::CONTENT_RANGE.as_str()) {
+        Some(content_range) => content_range
+            .rsplit('/')
+            .next()
+            .and_then(|length| length.parse().ok()),
+        None if head.status == 206 => None,
+        None => head
+            .header(header::CONTENT_LENGTH.as_str())
+            .and_then(|length| length.parse().ok()),
+    }
+}
@@ -8,7 +8,7 @@ use crate::runtime::{
 };
 use crate::types::server_urls::ServerUrlsBucket;
 
-use super::{CtxError, CtxStatus};
+use super::{restore_items, CtxError, CtxStatus};
 
 pub fn update_streaming_server_urls<E: Env + 'static>(
     streaming_server_urls: &mut ServerUrlsBucket,
@@ -34,6 +34,21 @@ pub fn update_streaming_server_urls<E: Env + 'static>(
             }
             _ => Effects::none().unchanged(),
         },
+        Msg::Internal(Internal::BackupRestoreResult(mode, Ok(response))) => {
+            match &response.streaming_server_urls {
+                Some(restored)
+                    if restore_items(
+                        &mut streaming_server_urls.items,
+                        restored.items.to_owned(),
+                        *mode,
+                        |restored, date| restored > date,
+                    ) =>
+                {
+                    Effects::msg(Msg::Internal(Internal::StreamingServerUrlsBucketChanged))
+                }
+                _ => Effects::none().unchanged(),
+            }
+        }
         Msg::Internal(Internal::StreamingServerUrlsBucketChanged) => {
             Effects::one(push_server_urls_to_storage::<E>(streaming_server_urls)).unchanged()
         }
@@ -4,13 +4,15 @@ use std::collections::hash_map::Entry;
 
 use crate::constants::STREAMS_STORAGE_KEY;
 use crate::models::common::{Loadable, ResourceLoadable};
-use crate::models::ctx::{CtxError, CtxStatus};
-use crate::runtime::msg::{CtxAuthResponse, Event, Internal, Msg};
+use crate::models::ctx::{restore_items, CtxError, CtxStatus};
+use crate::runtime::msg::{CtxAuthResponse, Event, Internal, LocalProfileStorageResponse, Msg};
 use crate::runtime::{Effect, EffectFuture, Effects, Env, EnvFutureExt};
+use crate::types::local_profiles::LocalProfilesBucket;
 use crate::types::streams::{StreamsBucket, StreamsItem, StreamsItemKey};
 
 pub fn update_streams<E: Env + 'static>(
     streams: &mut StreamsBucket,
+    local_profiles: &LocalProfilesBucket,
     status: &CtxStatus,
     msg: &Msg,
 ) -> Effects {
@@ -82,7 +84,32 @@ pub fn update_streams<E: Env + 'static>(
             }
         }
         Msg::Internal(Internal::StreamsChanged(persisted)) if !persisted => {
-            Effects::one(push_streams_to_storage::<E>(streams)).unchanged()
+            Effects::one(push_streams_to_storage::<E>(streams, local_profiles)).unchanged()
+        }
+        Msg::Internal(Internal::LocalProfileStorageResult(
+            _,
+            Ok(LocalProfileStorageResponse {
+                streams: next_streams,
+                ..
+            }),
+        )) => {
+            next_streams.clone_into(streams);
+            Effects::msg(Msg::Internal(Internal::StreamsChanged(true)))
+        }
+        Msg::Internal(Internal::BackupRestoreResult(mode, Ok(response))) => {
+            match &response.streams {
+                Some(restored)
+                    if restore_items(
+                        &mut streams.items,
+                        restored.items.to_owned(),
+                        *mode,
+                        |restored, item| restored.mtime > item.mtime,
+                    ) =>
+                {
+                    Effects::msg(Msg::Internal(Internal::StreamsChanged(false)))
+                }
+                _ => Effects::none().unchanged(),
+            }
         }
         Msg::Internal(Internal::CtxAuthResult(auth_request, result)) => match (status, result) {
             (CtxStatus::Loading(loading_auth_request), Ok(CtxAuthResponse { auth, .. }))
@@ -102,17 +129,23 @@ pub fn update_streams<E: Env + 'static>(
     }
 }
 
-fn push_streams_to_storage<E: Env + 'static>(streams: &StreamsBucket) -> Effect {
+fn push_streams_to_storage<E: Env + 'static>(
+    streams: &StreamsBucket,
+    local_profiles: &LocalProfilesBucket,
+) -> Effect {
     EffectFuture::Sequential(
-        E::set_storage(STREAMS_STORAGE_KEY, Some(&streams))
-            .map(enclose!((streams.uid => uid) move |result| match result {
-                Ok(_) => Msg::Event(Event::StreamsPushedToStorage { uid }),
-                Err(error) => Msg::Event(Event::Error {
-                    error: CtxError::from(error),
-                    source: Box::new(Event::StreamsPushedToStorage { uid }),
-                })
-            }))
-            .boxed_env(),
+        E::set_storage(
+            &local_profiles.storage_key(STREAMS_STORAGE_KEY),
+            Some(&streams),
+        )
+        .map(enclose!((streams.uid => uid) move |result| match result {
+            Ok(_) => Msg::Event(Event::StreamsPushedToStorage { uid }),
+            Err(error) => Msg::Event(Event::Error {
+                error: CtxError::from(error),
+                source: Box::new(Event::StreamsPushedToStorage { uid }),
+            })
+        }))
+        .boxed_env(),
     )
     .into()
 }
@@ -0,0 +1,96 @@
+use enclose::enclose;
+use futures::FutureExt;
+
+use crate::constants::TASTE_STORAGE_KEY;
+use crate::models::common::Loadable;
+use crate::models::ctx::{restore_items, CtxError, CtxStatus};
+use crate::runtime::msg::{CtxAuthResponse, Event, Internal, Msg};
+use crate::runtime::{Effect, EffectFuture, Effects, Env, EnvFutureExt};
+use crate::types::library::LibraryBucket;
+use crate::types::rating::Rating;
+use crate::types::taste::TasteBucket;
+
+pub fn update_taste<E: Env + 'static>(
+    taste: &mut TasteBucket,
+    library: &LibraryBucket,
+    status: &CtxStatus,
+    msg: &Msg,
+) -> Effects {
+    match msg {
+        Msg::Internal(Internal::Logout(_)) => {
+            let next_taste = TasteBucket::default();
+            *taste = next_taste;
+            Effects::msg(Msg::Internal(Internal::TasteChanged))
+        }
+        Msg::Internal(Internal::CtxAuthResult(auth_request, result)) => match (status, result) {
+            (CtxStatus::Loading(loading_auth_request), Ok(CtxAuthResponse { auth, .. }))
+                if loading_auth_request == auth_request =>
+            {
+                let mut next_taste = TasteBucket::new(Some(auth.user.id.to_owned()));
+                next_taste.seed(library);
+                *taste = next_taste;
+                Effects::msg(Msg::Internal(Internal::TasteChanged))
+            }
+            _ => Effects::none().unchanged(),
+        },
+        // the taste of the users is seeded from their library once it's loaded
+        Msg::Internal(Internal::LibraryChanged(_))
+            if taste.items.is_empty() && taste.uid == library.uid && taste.seed(library) =>
+        {
+            Effects::msg(Msg::Internal(Internal::TasteChanged))
+        }
+        Msg::Internal(Internal::StreamLoaded { meta_item, .. }) => match &meta_item.content {
+            Some(Loadable::Ready(meta_item)) => {
+                if taste.update(&meta_item.preview, E::now(), |item| item.watched = true) {
+                    Effects::msg(Msg::Internal(Internal::TasteChanged))
+                } else {
+                    Effects::none().unchanged()
+                }
+            }
+            _ => Effects::none().unchanged(),
+        },
+        Msg::Internal(Internal::MetaItemRatingChanged { meta_item, status }) => {
+            if taste.update(meta_item, E::now(), |item| {
+                item.rating = status.to_owned();
+                // rating an item as watched counts as watching it
+                item.watched = item.watched || *status == Some(Rating::Watched);
+            }) {
+                Effects::msg(Msg::Internal(Internal::TasteChanged))
+            } else {
+                Effects::none().unchanged()
+            }
+        }
+        Msg::Internal(Internal::BackupRestoreResult(mode, Ok(response))) => match &response.taste {
+            Some(restored)
+                if restore_items(
+                    &mut taste.items,
+                    restored.items.to_owned(),
+                    *mode,
+                    |restored, item| restored.mtime > item.mtime,
+                ) =>
+            {
+                Effects::msg(Msg::Internal(Internal::TasteChanged))
+            }
+            _ => Effects::none().unchanged(),
+        },
+        Msg::Internal(Internal::TasteChanged) => {
+            Effects::one(push_taste_to_storage::<E>(taste)).unchanged()
+        }
+        _ => Effects::none().unchanged(),
+    }
+}
+
+fn push_taste_to_storage<E: Env + 'static>(taste: &TasteBucket) -> Effect {
+    EffectFuture::Sequential(
+        E::set_storage(TASTE_STORAGE_KEY, Some(&taste))
+            .map(enclose!((taste.uid => uid) move |result| match result {
+                Ok(_) => Msg::Event(Event::TastePushedToStorage { uid }),
+                Err(error) => Msg::Event(Event::Error {
+                    error: CtxError::from(error),
+                    source: Box::new(Event::TastePushedToStorage { uid }),
+                })
+            }))
+            .boxed_env(),
+    )
+    .into()
+}
@@ -4,14 +4,31 @@ use crate::models::ctx::Ctx;
 use crate::runtime::msg::{Action, ActionLoad, Internal, Msg};
 use crate::runtime::{Effects, Env, UpdateWithCtx};
 use crate::types::addon::Descriptor;
+use crate::types::addon_health::{AddonHealthBucket, AddonHealthStatus};
 use crate::types::profile::Profile;
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 use std::iter;
+use strum::{EnumIter, IntoEnumIterator};
+
+#[derive(Default, Clone, PartialEq, Eq, EnumIter, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum InstalledAddonsSort {
+    /// In the order of installation
+    #[default]
+    Installed,
+    /// The failing and slowest addons first
+    Health,
+}
 
 #[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
 pub struct InstalledAddonsRequest {
     pub r#type: Option<String>,
+    #[serde(default)]
+    pub sort: InstalledAddonsSort,
+    /// Only the addons with this health status
+    #[serde(default)]
+    pub health: Option<AddonHealthStatus>,
 }
 
 #[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
@@ -19,19 +36,27 @@ pub struct Selected {
     pub request: InstalledAddonsRequest,
 }
 
-#[derive(Clone, PartialEq, Eq, Serialize)]
+#[derive(Clone, PartialEq, Eq, Serialize, Debug)]
 pub struct SelectableType {
     pub r#type: Option<String>,
     pub selected: bool,
     pub request: InstalledAddonsRequest,
 }
 
-#[derive(Default, Clone, PartialEq, Eq, Serialize)]
+#[derive(Clone, PartialEq, Eq, Serialize, Debug)]
+pub struct SelectableSort {
+    pub sort: InstalledAddonsSort,
+    pub selected: bool,
+    pub request: InstalledAddonsRequest,
+}
+
+#[derive(Default, Clone, PartialEq, Eq, Serialize, Debug)]
 pub struct Selectable {
     pub types: Vec<SelectableType>,
+    pub sorts: Vec<SelectableSort>,
 }
 
-#[derive(Default, Clone, Serialize)]
+#[derive(Default, Clone, Serialize, Debug)]
 pub struct InstalledAddonsWithFilters {
     pub selected: Option<Selected>,
     pub selectable: Selectable,
@@ -61,8 +86,12 @@ impl<E: Env + 'static> UpdateWithCtx<E> for InstalledAddonsWithFilters {
                 let selected_effects = eq_update(&mut self.selected, Some(selected.to_owned()));
                 let selectable_effects =
                     selectable_update(&mut self.selectable, &self.selected, &ctx.profile);
-                let catalog_effects =
-                    catalog_update(&mut self.catalog, &self.selected, &ctx.profile);
+                let catalog_effects = catalog_update(
+                    &mut self.catalog,
+                    &self.selected,
+                    &ctx.profile,
+                    &ctx.addon_health,
+                );
                 selected_effects
                     .join(selectable_effects)
                     .join(catalog_effects)
@@ -71,8 +100,12 @@ impl<E: Env + 'static> UpdateWithCtx<E> for InstalledAddonsWithFilters {
                 let selected_effects = eq_update(&mut self.selected, None);
                 let selectable_effects =
                     selectable_update(&mut self.selectable, &self.selected, &ctx.profile);
-                let catalog_effects =
-                    catalog_update(&mut self.catalog, &self.selected, &ctx.profile);
+                let catalog_effects = catalog_update(
+                    &mut self.catalog,
+                    &self.selected,
+                    &ctx.profile,
+                    &ctx.addon_health,
+                );
                 selected_effects
                     .join(selectable_effects)
                     .join(catalog_effects)
@@ -80,10 +113,20 @@ impl<E: Env + 'static> UpdateWithCtx<E> for InstalledAddonsWithFilters {
             Msg::Internal(Internal::ProfileChanged) => {
                 let selectable_effects =
                     selectable_update(&mut self.selectable, &self.selected, &ctx.profile);
-                let catalog_effects =
-                    catalog_update(&mut self.catalog, &self.selected, &ctx.profile);
+                let catalog_effects = catalog_update(
+                    &mut self.catalog,
+                    &self.selected,
+                    &ctx.profile,
+                    &ctx.addon_health,
+                );
                 selectable_effects.join(catalog_effects)
             }
+            Msg::Internal(Internal::AddonHealthChanged) => catalog_update(
+                &mut self.catalog,
+                &self.selected,
+                &ctx.profile,
+                &ctx.addon_health,
+            ),
             _ => Effects::none().unchanged(),
         }
     }
@@ -94,6 +137,7 @@ fn selectable_update(
     selected: &Option<Selected>,
     profile: &Profile,
 ) -> Effects {
+    let selected_request = selected.as_ref().map(|selected| &selected.request);
     let selectable_types = profile
         .addons
         .iter()
@@ -104,27 +148,49 @@ fn selectable_update(
         .rev()
         .map(|r#type| SelectableType {
             r#type: Some(r#type.to_owned()),
-            selected: selected
-                .as_ref()
-                .and_then(|selected| selected.request.r#type.as_ref())
+            selected: selected_request
+                .and_then(|request| request.r#type.as_ref())
                 .map(|type_| *type_ == r#type)
                 .unwrap_or_default(),
             request: InstalledAddonsRequest {
                 r#type: Some(r#type),
+                sort: selected_request
+                    .map(|request| request.sort.to_owned())
+                    .unwrap_or_default(),
+                health: selected_request.and_then(|request| request.health),
             },
         });
     let selectable_types = iter::once(SelectableType {
         r#type: None,
-        request: InstalledAddonsRequest { r#type: None },
-        selected: selected
-            .as_ref()
-            .map(|selected| selected.request.r#type.is_none())
+        request: InstalledAddonsRequest {
+            r#type: None,
+            sort: selected_request
+                .map(|request| request.sort.to_owned())
+                .unwrap_or_default(),
+            health: selected_request.and_then(|request| request.health),
+        },
+        selected: selected_request
+            .map(|request| request.r#type.is_none())
             .unwrap_or_default(),
     })
     .chain(selectable_types)
     .collect::<Vec<_>>();
+    let selectable_sorts = InstalledAddonsSort::iter()
+        .map(|sort| SelectableSort {
+            sort: sort.to_owned(),
+            selected: selected_request
+                .map(|request| request.sort == sort)
+                .unwrap_or_default(),
+            request: InstalledAddonsRequest {
+                r#type: selected_request.and_then(|request| request.r#type.to_owned()),
+                sort,
+                health: selected_request.and_then(|request| request.health),
+            },
+        })
+        .collect::<Vec<_>>();
     let next_selectable = Selectable {
         types: selectable_types,
+        sorts: selectable_sorts,
     };
     eq_update(selectable, next_selectable)
 }
@@ -133,17 +199,37 @@ fn catalog_update(
     catalog: &mut Vec<Descriptor>,
     selected: &Option<Selected>,
     profile: &Profile,
+    addon_health: &AddonHealthBucket,
 ) -> Effects {
     let next_catalog = match selected {
-        Some(selected) => profile
-            .addons
-            .iter()
-            .filter(|addon| match &selected.request.r#type {
-                Some(r#type) => addon.manifest.types.contains(r#type),
-                None => true,
-            })
-            .cloned()
-            .collect::<Vec<_>>(),
+        Some(selected) => {
+            let mut next_catalog = profile
+                .addons
+                .iter()
+                .filter(|addon| match &selected.request.r#type {
+                    Some(r#type) => addon.manifest.types.contains(r#type),
+                    None => true,
+                })
+                .filter(|addon| match &selected.request.health {
+                    Some(health) => {
+                        addon_health
+                            .items
+                            .get(&addon.transport_url)
+                            .and_then(|stats| stats.status())
+                            .as_ref()
+                            == Some(health)
+                    }
+                    None => true,
+                })
+                .cloned()
+                .collect::<Vec<_>>();
+            if selected.request.sort == InstalledAddonsSort::Health {
+                next_catalog.sort_by(|a, b| {
+                    addon_health.compare_health(&a.transport_url, &b.transport_url)
+                });
+            }
+            next_catalog
+        }
         _ => vec![],
     };
     eq_update(catalog, next_catalog)
@@ -0,0 +1,280 @@
+use serde::Serialize;
+
+use crate::constants::META_RESOURCE_NAME;
+use crate::models::common::{
+    eq_update, resource_update, resources_cancel_effects, Loadable, ResourceAction,
+    ResourceLoadable,
+};
+use crate::models::ctx::Ctx;
+use crate::runtime::msg::{Action, ActionLibraryTransfer, Internal, Msg};
+use crate::runtime::{Effects, Env, UpdateWithCtx};
+use crate::types::addon::{AggrRequest, ResourcePath};
+use crate::types::library::{LibraryBucket, LibraryItemId};
+use crate::types::resource::{MetaItem, Video};
+use crate::types::taste::TasteBucket;
+use crate::types::tracker::{
+    TrackerEntry, TrackerError, TrackerFormat, TrackerImport, UnmatchedReason, UnmatchedRow,
+};
+
+#[derive(Clone, PartialEq, Eq, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportReport {
+    /// The library items which were marked as watched
+    pub imported: Vec<LibraryItemId>,
+    pub unmatched: Vec<UnmatchedRow>,
+}
+
+/// Exports the library and the watch history to the file of another tracker and imports it back,
+/// matching the titles by their IMDb ids.
+#[derive(Default, Clone, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryTransfer {
+    pub format: Option<TrackerFormat>,
+    /// The series whose videos are needed to convert their watched episodes
+    pub meta_items: Vec<ResourceLoadable<MetaItem>>,
+    /// The exported file, loading until the videos of the series are loaded
+    pub export: Option<Loadable<String, TrackerError>>,
+    pub import: Option<Loadable<ImportReport, TrackerError>>,
+    /// The imported titles which wait for the videos of the series to be applied
+    #[serde(skip)]
+    pending_import: Option<TrackerImport>,
+}
+
+impl<E: Env + 'static> UpdateWithCtx<E> for LibraryTransfer {
+    fn update(&mut self, msg: &Msg, ctx: &Ctx) -> Effects {
+        match msg {
+            Msg::Action(Action::LibraryTransfer(ActionLibraryTransfer::Export(format))) => {
+                let series_ids = ctx
+                    .library
+                    .items
+                    .values()
+                    .filter(|library_item| {
+                        library_item.r#type == "series" && library_item.state.watched.is_some()
+                    })
+                    .filter_map(|library_item| {
+                        TrackerEntry::from_library_item(library_item, &[], None)
+                            .map(|entry| entry.imdb_id)
+                    })
+                    .collect::<Vec<_>>();
+                let cancel_effects = resources_cancel_effects(&self.meta_items);
+                let format_effects = eq_update(&mut self.format, Some(*format));
+                let import_effects = eq_update(&mut self.import, None);
+                self.pending_import = None;
+                let export_effects = eq_update(&mut self.export, Some(Loadable::Loading));
+                let meta_items_effects =
+                    meta_items_update::<E>(&mut self.meta_items, &series_ids, ctx);
+                cancel_effects
+                    .join(format_effects)
+                    .join(import_effects)
+                    .join(export_effects)
+                    .join(meta_items_effects)
+                    .join(transfer_update::<E>(self, ctx))
+            }
+            Msg::Action(Action::LibraryTransfer(ActionLibraryTransfer::Import {
+                format,
+                data,
+            })) => {
+                let cancel_effects = resources_cancel_effects(&self.meta_items);
+                let format_effects = eq_update(&mut self.format, Some(*format));
+                let export_effects = eq_update(&mut self.export, None);
+                match format.import(data) {
+                    Ok(import) => {
+                        let series_ids = import
+                            .entries
+                            .iter()
+                            .filter(|entry| !entry.episodes.is_empty())
+                            .map(|entry| entry.imdb_id.to_owned())
+                            .collect::<Vec<_>>();
+                        self.pending_import = Some(import);
+                        let import_effects = eq_update(&mut self.import, Some(Loadable::Loading));
+                        let meta_items_effects =
+                            meta_items_update::<E>(&mut self.meta_items, &series_ids, ctx);
+                        cancel_effects
+                            .join(format_effects)
+                            .join(export_effects)
+                            .join(import_effects)
+                            .join(meta_items_effects)
+                            .join(transfer_update::<E>(self, ctx))
+                    }
+                    Err(error) => {
+                        self.pending_import = None;
+                        let import_effects =
+                            eq_update(&mut self.import, Some(Loadable::Err(error)));
+                        let meta_items_effects = eq_update(&mut self.meta_items, vec![]);
+                        cancel_effects
+                            .join(format_effects)
+                            .join(export_effects)
+                            .join(import_effects)
+                            .join(meta_items_effects)
+                    }
+                }
+            }
+            Msg::Action(Action::Unload) => {
+                let cancel_effects = resources_cancel_effects(&self.meta_items);
+                self.pending_import = None;
+                let format_effects = eq_update(&mut self.format, None);
+                let meta_items_effects = eq_update(&mut self.meta_items, vec![]);
+                let export_effects = eq_update(&mut self.export, None);
+                let import_effects = eq_update(&mut self.import, None);
+                cancel_effects
+                    .join(format_effects)
+                    .join(meta_items_effects)
+                    .join(export_effects)
+                    .join(import_effects)
+            }
+            Msg::Internal(Internal::ResourceRequestResult(request, result)) => {
+                match self
+                    .meta_items
+                    .iter_mut()
+                    .find(|meta_item| meta_item.request == *request)
+                {
+                    Some(meta_item) => resource_update::<E, _>(
+                        meta_item,
+                        ResourceAction::ResourceRequestResult { request, result },
+                    )
+                    .join(transfer_update::<E>(self, ctx)),
+                    None => Effects::none().unchanged(),
+                }
+            }
+            _ => Effects::none().unchanged(),
+        }
+    }
+}
+
+/// Requests the meta item of each series from the first addon which provides it
+fn meta_items_update<E: Env + 'static>(
+    meta_items: &mut Vec<ResourceLoadable<MetaItem>>,
+    series_ids: &[String],
+    ctx: &Ctx,
+) -> Effects {
+    let requests = series_ids
+        .iter()
+        .filter_map(|id| {
+            AggrRequest::AllOfResource(ResourcePath::without_extra(
+                META_RESOURCE_NAME,
+                "series",
+                id,
+            ))
+            .plan(&ctx.profile.addons)
+            .into_iter()
+            .map(|(_, request)| request)
+            .next()
+        })
+        .collect::<Vec<_>>();
+    let mut next_meta_items = requests
+        .iter()
+        .map(|request| ResourceLoadable {
+            request: request.to_owned(),
+            content: None,
+        })
+        .collect::<Vec<_>>();
+    let request_effects = next_meta_items
+        .iter_mut()
+        .map(|meta_item| {
+            let request = meta_item.request.to_owned();
+            resource_update::<E, _>(
+                meta_item,
+                ResourceAction::ResourceRequested { request: &request },
+            )
+        })
+        .fold(Effects::none().unchanged(), Effects::join)
+        .unchanged();
+    request_effects.join(eq_update(meta_items, next_meta_items))
+}
+
+/// Finishes the export or the import once the videos of all the series are loaded
+fn transfer_update<E: Env + 'static>(transfer: &mut LibraryTransfer, ctx: &Ctx) -> Effects {
+    if transfer
+        .meta_items
+        .iter()
+        .any(|meta_item| matches!(meta_item.content, Some(Loadable::Loading)))
+    {
+        return Effects::none().unchanged();
+    }
+    let format = match transfer.format {
+        Some(format) => format,
+        None => return Effects::none().unchanged(),
+    };
+    let export_effects = match transfer.export {
+        Some(Loadable::Loading) => {
+            let export = export(format, &ctx.library, &ctx.taste, &transfer.meta_items);
+            eq_update(&mut transfer.export, Some(Loadable::Ready(export)))
+        }
+        _ => Effects::none().unchanged(),
+    };
+    let import_effects = match transfer.pending_import.take() {
+        Some(import) => {
+            let mut report = ImportReport {
+                imported: vec![],
+                unmatched: import.unmatched,
+            };
+            let library_effects = import
+                .entries
+                .iter()
+                .enumerate()
+                .map(|(index, entry)| {
+                    let (library_item, unknown_episodes) = entry.to_library_item::<E>(
+                        ctx.library.items.get(&entry.imdb_id),
+                        videos(&transfer.meta_items, &entry.imdb_id),
+                    );
+                    if !unknown_episodes.is_empty() {
+                        report.unmatched.push(UnmatchedRow {
+                            row: index + 1,
+                            title: Some(entry.name.to_owned()),
+                            reason: UnmatchedReason::UnknownEpisodes(unknown_episodes),
+                        });
+                    }
+                    report.imported.push(library_item.id.to_owned());
+                    Effects::msg(Msg::Internal(Internal::UpdateLibraryItem(library_item)))
+                        .unchanged()
+                })
+                .fold(Effects::none().unchanged(), Effects::join);
+            library_effects.join(eq_update(
+                &mut transfer.import,
+                Some(Loadable::Ready(report)),
+            ))
+        }
+        None => Effects::none().unchanged(),
+    };
+    export_effects.join(import_effects)
+}
+
+fn export(
+    format: TrackerFormat,
+    library: &LibraryBucket,
+    taste: &TasteBucket,
+    meta_items: &[ResourceLoadable<MetaItem>],
+) -> String {
+    let mut entries = library
+        .items
+        .values()
+        .filter_map(|library_item| {
+            TrackerEntry::from_library_item(
+                library_item,
+                videos(meta_items, &library_item.id),
+                taste
+                    .items
+                    .get(&library_item.id)
+                    .and_then(|taste_item| taste_item.rating.as_ref()),
+            )
+        })
+        .collect::<Vec<_>>();
+    entries.sort_by(|a, b| {
+        b.watched_at
+            .cmp(&a.watched_at)
+            .then(a.imdb_id.cmp(&b.imdb_id))
+    });
+    format.export(&entries)
+}
+
+fn videos<'a>(meta_items: &'a [ResourceLoadable<MetaItem>], id: &str) -> &'a [Video] {
+    meta_items
+        .iter()
+        .find_map(|meta_item| match &meta_item.content {
+            Some(Loadable::Ready(meta_item)) if meta_item.preview.id == id => {
+                Some(meta_item.videos.as_slice())
+            }
+            _ => None,
+        })
+        .unwrap_or_default()
+}
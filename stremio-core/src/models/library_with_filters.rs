@@ -18,7 +18,9 @@ use crate::{
     },
     types::{
         library::{LibraryBucket, LibraryItem},
+        lists::{ListId, ListItem, ListsBucket},
         notifications::NotificationsBucket,
+        profile::ParentalControls,
     },
 };
 
@@ -95,6 +97,9 @@ impl Sort {
 #[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
 pub struct LibraryRequest {
     pub r#type: Option<String>,
+    /// Only the items of the list, in the order of the list
+    #[serde(default)]
+    pub list: Option<ListId>,
     #[serde(default)]
     pub sort: Sort,
     #[serde(default)]
@@ -122,6 +127,14 @@ pub struct SelectableType {
     pub request: LibraryRequest,
 }
 
+#[derive(Clone, PartialEq, Eq, Serialize, Debug)]
+pub struct SelectableList {
+    pub list: Option<ListId>,
+    pub name: Option<String>,
+    pub selected: bool,
+    pub request: LibraryRequest,
+}
+
 #[derive(Clone, PartialEq, Eq, Serialize, Debug)]
 pub struct SelectableSort {
     pub sort: Sort,
@@ -137,6 +150,8 @@ pub struct SelectablePage {
 #[derive(Default, Clone, PartialEq, Eq, Serialize, Debug)]
 pub struct Selectable {
     pub types: Vec<SelectableType>,
+    pub lists: Vec<SelectableList>,
+    /// Empty when a list is selected, as its items are in the order of the list
     pub sorts: Vec<SelectableSort>,
     pub next_page: Option<SelectablePage>,
 }
@@ -152,10 +167,22 @@ pub struct LibraryWithFilters<F> {
 }
 
 impl<F: LibraryFilter> LibraryWithFilters<F> {
-    pub fn new(library: &LibraryBucket, notifications: &NotificationsBucket) -> (Self, Effects) {
+    pub fn new(
+        library: &LibraryBucket,
+        lists: &ListsBucket,
+        notifications: &NotificationsBucket,
+        parental_controls: &ParentalControls,
+    ) -> (Self, Effects) {
         let selected = None;
         let mut selectable = Selectable::default();
-        let effects = selectable_update::<F>(&mut selectable, &selected, library, notifications);
+        let effects = selectable_update::<F>(
+            &mut selectable,
+            &selected,
+            library,
+            lists,
+            notifications,
+            parental_controls,
+        );
         (
             Self {
                 selectable,
@@ -176,13 +203,17 @@ impl<E: Env + 'static, F: LibraryFilter> UpdateWithCtx<E> for LibraryWithFilters
                     &mut self.selectable,
                     &self.selected,
                     &ctx.library,
+                    &ctx.lists,
                     &ctx.notifications,
+                    &ctx.profile.parental_controls,
                 );
                 let catalog_effects = catalog_update::<F>(
                     &mut self.catalog,
                     &self.selected,
                     &ctx.library,
+                    &ctx.lists,
                     &ctx.notifications,
+                    &ctx.profile.parental_controls,
                 );
                 selected_effects
                     .join(selectable_effects)
@@ -194,13 +225,17 @@ impl<E: Env + 'static, F: LibraryFilter> UpdateWithCtx<E> for LibraryWithFilters
                     &mut self.selectable,
                     &self.selected,
                     &ctx.library,
+                    &ctx.lists,
                     &ctx.notifications,
+                    &ctx.profile.parental_controls,
                 );
                 let catalog_effects = catalog_update::<F>(
                     &mut self.catalog,
                     &self.selected,
                     &ctx.library,
+                    &ctx.lists,
                     &ctx.notifications,
+                    &ctx.profile.parental_controls,
                 );
                 selected_effects
                     .join(selectable_effects)
@@ -217,13 +252,17 @@ impl<E: Env + 'static, F: LibraryFilter> UpdateWithCtx<E> for LibraryWithFilters
                             &mut self.selectable,
                             &self.selected,
                             &ctx.library,
+                            &ctx.lists,
                             &ctx.notifications,
+                            &ctx.profile.parental_controls,
                         );
                         let catalog_effects = catalog_update::<F>(
                             &mut self.catalog,
                             &self.selected,
                             &ctx.library,
+                            &ctx.lists,
                             &ctx.notifications,
+                            &ctx.profile.parental_controls,
                         );
                         selected_effects
                             .join(selectable_effects)
@@ -232,18 +271,24 @@ impl<E: Env + 'static, F: LibraryFilter> UpdateWithCtx<E> for LibraryWithFilters
                     _ => Effects::none().unchanged(),
                 }
             }
-            Msg::Internal(Internal::LibraryChanged(_)) => {
+            Msg::Internal(Internal::LibraryChanged(_))
+            | Msg::Internal(Internal::ListsChanged)
+            | Msg::Internal(Internal::ProfileChanged) => {
                 let selectable_effects = selectable_update::<F>(
                     &mut self.selectable,
                     &self.selected,
                     &ctx.library,
+                    &ctx.lists,
                     &ctx.notifications,
+                    &ctx.profile.parental_controls,
                 );
                 let catalog_effects = catalog_update::<F>(
                     &mut self.catalog,
                     &self.selected,
                     &ctx.library,
+                    &ctx.lists,
                     &ctx.notifications,
+                    &ctx.profile.parental_controls,
                 );
                 selectable_effects.join(catalog_effects)
             }
@@ -256,12 +301,24 @@ fn selectable_update<F: LibraryFilter>(
     selectable: &mut Selectable,
     selected: &Option<Selected>,
     library: &LibraryBucket,
+    lists: &ListsBucket,
     notifications: &NotificationsBucket,
+    parental_controls: &ParentalControls,
 ) -> Effects {
+    let list_items = selected_list_items(selected, lists);
+    let selected_sort = selected
+        .as_ref()
+        .map(|selected| selected.request.sort.to_owned())
+        .unwrap_or_default();
+    let selected_list = selected
+        .as_ref()
+        .and_then(|selected| selected.request.list.to_owned());
     let selectable_types = library
         .items
         .values()
         .filter(|library_item| F::predicate(library_item, notifications))
+        .filter(|library_item| !parental_controls.is_blocked(*library_item))
+        .filter(|library_item| is_in_list(library_item, list_items))
         .map(|library_item| &library_item.r#type)
         .unique()
         .sorted_by(|a, b| compare_with_priorities(a.as_str(), b.as_str(), &*TYPE_PRIORITIES))
@@ -272,10 +329,8 @@ fn selectable_update<F: LibraryFilter>(
             r#type: r#type.to_owned(),
             request: LibraryRequest {
                 r#type: r#type.to_owned(),
-                sort: selected
-                    .as_ref()
-                    .map(|selected| selected.request.sort.to_owned())
-                    .unwrap_or_default(),
+                list: selected_list.to_owned(),
+                sort: selected_sort.to_owned(),
                 page: LibraryRequestPage::default(),
             },
             selected: selected
@@ -287,10 +342,8 @@ fn selectable_update<F: LibraryFilter>(
         r#type: None,
         request: LibraryRequest {
             r#type: None,
-            sort: selected
-                .as_ref()
-                .map(|selected| selected.request.sort.to_owned())
-                .unwrap_or_default(),
+            list: selected_list.to_owned(),
+            sort: selected_sort.to_owned(),
             page: LibraryRequestPage::default(),
         },
         selected: selected
@@ -300,31 +353,59 @@ fn selectable_update<F: LibraryFilter>(
     })
     .chain(selectable_types)
     .collect::<Vec<_>>();
-    let selectable_sorts = Sort::iter()
-        .map(|sort| SelectableSort {
-            sort: sort.to_owned(),
+    let selectable_lists = iter::once((None, None))
+        .chain(
+            lists
+                .ordered()
+                .into_iter()
+                .map(|list| (Some(list.id.to_owned()), Some(list.name.to_owned()))),
+        )
+        .map(|(list, name)| SelectableList {
             request: LibraryRequest {
-                r#type: selected
-                    .as_ref()
-                    .and_then(|selected| selected.request.r#type.to_owned()),
-                sort: sort.to_owned(),
+                r#type: None,
+                list: list.to_owned(),
+                sort: selected_sort.to_owned(),
                 page: LibraryRequestPage::default(),
             },
             selected: selected
                 .as_ref()
-                .map(|selected| selected.request.sort == sort)
+                .map(|selected| selected.request.list == list)
                 .unwrap_or_default(),
+            list,
+            name,
         })
         .collect();
+    let selectable_sorts = match list_items {
+        Some(_) => vec![],
+        None => Sort::iter()
+            .map(|sort| SelectableSort {
+                sort: sort.to_owned(),
+                request: LibraryRequest {
+                    r#type: selected
+                        .as_ref()
+                        .and_then(|selected| selected.request.r#type.to_owned()),
+                    list: None,
+                    sort: sort.to_owned(),
+                    page: LibraryRequestPage::default(),
+                },
+                selected: selected
+                    .as_ref()
+                    .map(|selected| selected.request.sort == sort)
+                    .unwrap_or_default(),
+            })
+            .collect(),
+    };
     let next_page = match selected {
         Some(selected) => library
             .items
             .values()
             .filter(|library_item| F::predicate(library_item, notifications))
+            .filter(|library_item| !parental_controls.is_blocked(*library_item))
             .filter(|library_item| match &selected.request.r#type {
                 Some(r#type) => library_item.r#type == *r#type,
                 None => true,
             })
+            .filter(|library_item| is_in_list(library_item, list_items))
             .nth(selected.request.page.get() * CATALOG_PAGE_SIZE)
             .map(|_| SelectablePage {
                 request: LibraryRequest {
@@ -338,6 +419,7 @@ fn selectable_update<F: LibraryFilter>(
     };
     let next_selectable = Selectable {
         types: selectable_types,
+        lists: selectable_lists,
         sorts: selectable_sorts,
         next_page,
     };
@@ -348,18 +430,33 @@ fn catalog_update<F: LibraryFilter>(
     catalog: &mut Vec<LibraryItem>,
     selected: &Option<Selected>,
     library: &LibraryBucket,
+    lists: &ListsBucket,
     notifications: &NotificationsBucket,
+    parental_controls: &ParentalControls,
 ) -> Effects {
+    let list_items = selected_list_items(selected, lists);
     let next_catalog = match selected {
         Some(selected) => library
             .items
             .values()
             .filter(|library_item| F::predicate(library_item, notifications))
+            .filter(|library_item| !parental_controls.is_blocked(*library_item))
             .filter(|library_item| match &selected.request.r#type {
                 Some(r#type) => library_item.r#type == *r#type,
                 None => true,
             })
-            .sorted_by(|a, b| selected.request.sort.sort_items(a, b))
+            .filter(|library_item| is_in_list(library_item, list_items))
+            .sorted_by(|a, b| match list_items {
+                Some(list_items) => {
+                    let position = |library_item: &LibraryItem| {
+                        list_items
+                            .iter()
+                            .position(|list_item| list_item.id == library_item.id)
+                    };
+                    position(a).cmp(&position(b))
+                }
+                None => selected.request.sort.sort_items(a, b),
+            })
             .take(selected.request.page.get() * CATALOG_PAGE_SIZE)
             .cloned()
             .collect(),
@@ -368,6 +465,33 @@ fn catalog_update<F: LibraryFilter>(
     eq_update(catalog, next_catalog)
 }
 
+/// The items of the selected list, none of them if the list was removed
+fn selected_list_items<'a>(
+    selected: &Option<Selected>,
+    lists: &'a ListsBucket,
+) -> Option<&'a [ListItem]> {
+    selected
+        .as_ref()
+        .and_then(|selected| selected.request.list.as_ref())
+        .map(|id| {
+            lists
+                .items
+                .get(id)
+                .filter(|list| !list.removed)
+                .map(|list| list.items.as_slice())
+                .unwrap_or_default()
+        })
+}
+
+fn is_in_list(library_item: &LibraryItem, list_items: Option<&[ListItem]>) -> bool {
+    match list_items {
+        Some(list_items) => list_items
+            .iter()
+            .any(|list_item| list_item.id == library_item.id),
+        None => true,
+    }
+}
+
 #[cfg(test)]
 mod test {
     use chrono::{Duration, Utc};
@@ -1,8 +1,13 @@
-//! Local autocompletion search
+//! Local full-text search over the user's library, search history,
+//! the loaded catalogs and Cinemeta's feed, which works offline
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
 
 use enclose::enclose;
 use futures::FutureExt;
 use http::Request;
+use itertools::Itertools;
 use num::{rational::Ratio, ToPrimitive};
 use serde::{Deserialize, Serialize};
 use serde_with::{serde_as, DefaultOnError, NoneAsEmptyString};
@@ -11,7 +16,10 @@ use url::Url;
 use localsearch::{self, LocalSearch as Searcher, DEFAULT_SCORE_THRESHOLD};
 
 use crate::{
-    constants::{CINEMETA_CATALOGS_URL, CINEMETA_FEED_CATALOG_ID},
+    constants::{
+        CAST_LINK_CATEGORY, CATALOG_RESOURCE_NAME, CINEMETA_CATALOGS_URL, CINEMETA_FEED_CATALOG_ID,
+        GENRES_LINK_CATEGORY, IMDB_LINK_CATEGORY, LOCAL_SEARCH_CATALOG_RECORDS_COUNT,
+    },
     models::{
         common::{eq_update, Loadable},
         ctx::Ctx,
@@ -20,6 +28,13 @@ use crate::{
         msg::{Action, ActionLoad, ActionSearch, Internal, Msg},
         Effect, EffectFuture, Effects, Env, EnvError, EnvFutureExt, UpdateWithCtx,
     },
+    types::{
+        addon::ResourceResponse,
+        library::{LibraryBucket, LibraryItem},
+        profile::Restrictable,
+        resource::MetaItemPreview,
+        search_history::SearchHistoryBucket,
+    },
 };
 
 pub use imdb_rating::*;
@@ -42,6 +57,58 @@ pub struct IndexOptions {
     popularity_weight: f64,
 }
 
+/// The weights of the matching fields in the score of a search result
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldWeights {
+    pub name: f64,
+    pub cast: f64,
+    pub genre: f64,
+    pub year: f64,
+}
+
+impl Default for FieldWeights {
+    fn default() -> Self {
+        Self {
+            name: 1.0,
+            cast: 0.6,
+            genre: 0.3,
+            year: 0.3,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchOptions {
+    /// The minimum similarity of a match, the lower it is the more typos are tolerated
+    pub score_threshold: f64,
+    pub field_weights: FieldWeights,
+}
+
+impl Default for SearchOptions {
+    fn default() -> Self {
+        Self {
+            score_threshold: DEFAULT_SCORE_THRESHOLD,
+            field_weights: FieldWeights::default(),
+        }
+    }
+}
+
+/// Where the searchable item comes from, in the order of priority in the search results
+#[derive(
+    Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, Debug,
+)]
+#[serde(rename_all = "camelCase")]
+pub enum SearchableSource {
+    Library,
+    /// The id and the name of the item are the search query
+    SearchHistory,
+    Catalog,
+    #[default]
+    Feed,
+}
+
 /// A searchable item
 #[serde_as]
 #[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
@@ -62,19 +129,258 @@ pub struct Searchable {
     pub imdb_rating: Option<ImdbRating>,
     pub popularity: Option<u64>,
     pub release_info: Option<String>,
+    #[serde(default)]
+    pub cast: Vec<String>,
+    #[serde(default)]
+    pub genres: Vec<String>,
+    #[serde(default)]
+    pub source: SearchableSource,
+}
+
+impl Searchable {
+    /// The year in which the item was released, e.g. `2010` for `2010-2015`
+    pub fn year(&self) -> Option<&str> {
+        self.release_info
+            .as_deref()
+            .and_then(|release_info| release_info.get(0..4))
+            .filter(|year| year.chars().all(|char| char.is_ascii_digit()))
+    }
+
+    /// Fills the missing details with the ones of the same item from another source.
+    fn merge(&mut self, other: Searchable) {
+        self.poster = self.poster.take().or(other.poster);
+        self.imdb_rating = self.imdb_rating.or(other.imdb_rating);
+        self.popularity = self.popularity.or(other.popularity);
+        self.release_info = self.release_info.take().or(other.release_info);
+        if self.cast.is_empty() {
+            self.cast = other.cast;
+        }
+        if self.genres.is_empty() {
+            self.genres = other.genres;
+        }
+    }
+}
+
+impl From<&LibraryItem> for Searchable {
+    fn from(library_item: &LibraryItem) -> Self {
+        Self {
+            id: library_item.id.to_owned(),
+            name: library_item.name.to_owned(),
+            r#type: library_item.r#type.to_owned(),
+            poster: library_item.poster.to_owned(),
+            imdb_rating: None,
+            popularity: None,
+            release_info: None,
+            cast: vec![],
+            genres: vec![],
+            source: SearchableSource::Library,
+        }
+    }
+}
+
+impl From<&MetaItemPreview> for Searchable {
+    fn from(meta_item: &MetaItemPreview) -> Self {
+        let links = |category: &str| {
+            meta_item
+                .links
+                .iter()
+                .filter(|link| link.category == category)
+                .map(|link| link.name.to_owned())
+                .collect::<Vec<_>>()
+        };
+        Self {
+            id: meta_item.id.to_owned(),
+            name: meta_item.name.to_owned(),
+            r#type: meta_item.r#type.to_owned(),
+            poster: meta_item.poster.to_owned(),
+            imdb_rating: meta_item
+                .links
+                .iter()
+                .find(|link| link.category == IMDB_LINK_CATEGORY)
+                .and_then(|link| link.name.parse().ok()),
+            popularity: None,
+            release_info: meta_item.release_info.to_owned(),
+            cast: links(CAST_LINK_CATEGORY),
+            genres: links(GENRES_LINK_CATEGORY),
+            source: SearchableSource::Catalog,
+        }
+    }
+}
+
+/// The feed doesn't have the certification and the genres,
+/// so only the type restrictions apply to the search results.
+impl Restrictable for Searchable {
+    fn content_type(&self) -> &str {
+        &self.r#type
+    }
+}
+
+/// The text of a field of an indexed record
+#[derive(Clone, Debug)]
+struct IndexedField {
+    record: usize,
+    text: String,
+    boost: f64,
+}
+
+#[derive(Clone, Debug)]
+struct FieldSearcher {
+    weight: f64,
+    size: usize,
+    searcher: Searcher<IndexedField>,
+}
+
+/// Full-text index over the searchable records with a searcher for each of the weighted fields
+#[derive(Clone, Debug)]
+pub struct SearchIndex {
+    records: Vec<Searchable>,
+    searchers: Vec<FieldSearcher>,
+}
+
+impl SearchIndex {
+    pub fn new(
+        records: Vec<Searchable>,
+        index_options: IndexOptions,
+        search_options: SearchOptions,
+    ) -> Self {
+        let boosts = Self::boosts(&records, index_options);
+        let weights = search_options.field_weights;
+        let fields: [(f64, fn(&Searchable) -> Vec<&str>); 4] = [
+            (weights.name, |record| vec![record.name.as_str()]),
+            (weights.cast, |record| {
+                record.cast.iter().map(String::as_str).collect()
+            }),
+            (weights.genre, |record| {
+                record.genres.iter().map(String::as_str).collect()
+            }),
+            (weights.year, |record| record.year().into_iter().collect()),
+        ];
+        let searchers = fields
+            .into_iter()
+            .filter(|(weight, _)| *weight > 0.0)
+            .map(|(weight, texts)| {
+                let fields = records
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(index, record)| {
+                        let boost = boosts[index];
+                        texts(record).into_iter().map(move |text| IndexedField {
+                            record: index,
+                            text: text.to_owned(),
+                            boost,
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                FieldSearcher {
+                    weight,
+                    size: fields.len(),
+                    searcher: Searcher::builder(fields, |field| &field.text)
+                        .boost_computer(|field| field.boost)
+                        .score_threshold(search_options.score_threshold)
+                        .build(),
+                }
+            })
+            .collect();
+        Self { records, searchers }
+    }
+
+    /// The records matching the query, the ones from the library first.
+    pub fn search(
+        &self,
+        query: &str,
+        max_results: usize,
+        r#type: Option<&str>,
+    ) -> Vec<&Searchable> {
+        let mut scores = HashMap::<usize, f64>::new();
+        for field_searcher in &self.searchers {
+            // only the best matching text of the field counts, e.g. one of the cast
+            let field_scores = field_searcher
+                .searcher
+                .search(query, field_searcher.size)
+                .into_iter()
+                .into_grouping_map_by(|(field, _)| field.record)
+                .fold(0.0_f64, |score, _, (_, field_score)| score.max(field_score));
+            for (record, score) in field_scores {
+                *scores.entry(record).or_default() += field_searcher.weight * score;
+            }
+        }
+        scores
+            .into_iter()
+            .map(|(record, score)| (&self.records[record], score))
+            .filter(|(record, _)| match r#type {
+                Some(r#type) => record.r#type == r#type,
+                None => true,
+            })
+            .sorted_by(|(record_a, score_a), (record_b, score_b)| {
+                record_a
+                    .source
+                    .cmp(&record_b.source)
+                    .then(score_b.partial_cmp(score_a).unwrap_or(Ordering::Equal))
+            })
+            .take(max_results)
+            .map(|(record, _)| record)
+            .collect()
+    }
+
+    fn boosts(records: &[Searchable], index_options: IndexOptions) -> Vec<f64> {
+        let max_imdb_rating = records
+            .iter()
+            // it's ok to set rating to 0 for the max if no items are present
+            .map(|searchable| searchable.imdb_rating.unwrap_or_default())
+            .max_by(|rating_a, rating_b| rating_a.partial_cmp(rating_b).unwrap())
+            .unwrap_or_default();
+
+        let max_popularity = records
+            .iter()
+            .map(|searchable| searchable.popularity.unwrap_or_default())
+            // it's ok to set popularity to 0 for the max if no items are present
+            .max_by(|popularity_a, popularity_b| popularity_a.partial_cmp(popularity_b).unwrap())
+            .unwrap_or_default();
+
+        records
+            .iter()
+            .map(|searchable| {
+                let imdb_rating_boost = searchable
+                    .imdb_rating
+                    .map(|imdb_rating| {
+                        (imdb_rating.to_f64() / max_imdb_rating.to_f64()
+                            * index_options.imdb_rating_weight)
+                            .exp()
+                    })
+                    .unwrap_or(1.0);
+
+                let popularity_boost = searchable
+                    .popularity
+                    .and_then(|popularity| {
+                        // make sure we always have > 0, because ratio will panic if denom is 0!
+                        let popularity_percent =
+                            Ratio::new(popularity, max_popularity.max(1)).to_f64()?;
+
+                        Some((popularity_percent * index_options.popularity_weight).exp())
+                    })
+                    .unwrap_or(1.0);
+
+                imdb_rating_boost * popularity_boost
+            })
+            .collect()
+    }
 }
 
 /// Local search functionality for the search engine's suggestions when typing
 #[derive(Default, Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct LocalSearch {
-    /// The Searchable items that will be used for the local search.
+    /// The Searchable items of the feed that will be used for the local search.
     #[serde(skip)]
     pub current_records: Vec<Searchable>,
+    /// The meta items of the latest loaded catalogs, the most recent first.
+    #[serde(skip)]
+    pub catalog_records: Vec<Searchable>,
     /// The results of the search autocompletion
     pub search_results: Vec<Searchable>,
+    pub options: SearchOptions,
     #[serde(skip)]
-    pub searcher: Option<Searcher<Searchable>>,
+    pub index: Option<SearchIndex>,
     /// A loadable resource in order to be able to search for items while
     /// a new set of items is being loaded (i.e. refreshed)
     pub latest_records: Option<Loadable<Vec<Searchable>, EnvError>>,
@@ -85,8 +391,10 @@ impl LocalSearch {
         (
             Self {
                 current_records: vec![],
+                catalog_records: vec![],
                 search_results: vec![],
-                searcher: None,
+                options: SearchOptions::default(),
+                index: None,
                 latest_records: None,
             },
             Effects::none().unchanged(),
@@ -117,86 +425,113 @@ impl LocalSearch {
         .into()
     }
 
-    fn index(&self, index_options: IndexOptions, score_threshold: f64) -> Searcher<Searchable> {
-        let max_imdb_rating = self
-            .current_records
-            .iter()
-            // it's ok to set rating to 0 for the max if no items are present
-            .map(|searchable| searchable.imdb_rating.unwrap_or_default())
-            .max_by(|rating_a, rating_b| rating_a.partial_cmp(rating_b).unwrap())
-            .unwrap_or_default();
+    /// Rebuilds the index from all the sources, if the local search is loaded.
+    ///
+    /// Due to LocalSearch not implementing PartialEq, the index is always considered changed.
+    fn index(&mut self, library: &LibraryBucket, search_history: &SearchHistoryBucket) -> Effects {
+        if self.latest_records.is_none() {
+            return Effects::none().unchanged();
+        }
 
-        let max_popularity = self
-            .current_records
+        // the items in the library and the watched ones which were not added to it
+        let library_records = library
+            .items
+            .values()
+            .filter(|item| !item.removed || (item.temp && item.state.last_watched.is_some()))
+            .sorted_by(|a, b| b.mtime.cmp(&a.mtime))
+            .map(Searchable::from);
+        let search_history_records = search_history
+            .items
             .iter()
-            .map(|searchable| searchable.popularity.unwrap_or_default())
-            // it's ok to set popularity to 0 for the max if no items are present
-            .max_by(|popularity_a, popularity_b| popularity_a.partial_cmp(popularity_b).unwrap())
-            .unwrap_or_default();
-
-        let score_computer = move |searchable: &Searchable| {
-            let imdb_rating_boost = searchable
-                .imdb_rating
-                .map(|imdb_rating| {
-                    (imdb_rating.to_f64() / max_imdb_rating.to_f64()
-                        * index_options.imdb_rating_weight)
-                        .exp()
-                })
-                .unwrap_or(1.0);
-
-            let popularity_boost = searchable
-                .popularity
-                .and_then(|popularity| {
-                    // make sure we always have > 0, because ratio will panic if denom is 0!
-                    let popularity_percent =
-                        Ratio::new(popularity, max_popularity.max(1)).to_f64()?;
-
-                    Some((popularity_percent * index_options.popularity_weight).exp())
-                })
-                .unwrap_or(1.0);
-
-            imdb_rating_boost * popularity_boost
-        };
+            .sorted_by(|(_, date_a), (_, date_b)| date_b.cmp(date_a))
+            .map(|(query, _)| Searchable {
+                id: query.to_owned(),
+                name: query.to_owned(),
+                r#type: String::new(),
+                poster: None,
+                imdb_rating: None,
+                popularity: None,
+                release_info: None,
+                cast: vec![],
+                genres: vec![],
+                source: SearchableSource::SearchHistory,
+            });
+        // the same item from a source with a lower priority only completes the missing details
+        let mut records = Vec::<Searchable>::new();
+        let mut record_indices = HashMap::<String, usize>::new();
+        for searchable in library_records
+            .chain(search_history_records)
+            .chain(self.catalog_records.iter().cloned())
+            .chain(self.current_records.iter().cloned())
+            .filter(|searchable| !searchable.name.is_empty())
+        {
+            match record_indices.get(&searchable.id) {
+                Some(index) => records[*index].merge(searchable),
+                None => {
+                    record_indices.insert(searchable.id.to_owned(), records.len());
+                    records.push(searchable);
+                }
+            }
+        }
 
-        Searcher::builder(self.current_records.clone(), |item| &item.name)
-            .boost_computer(score_computer)
-            .score_threshold(score_threshold)
-            .build()
+        self.index = Some(SearchIndex::new(records, INDEX_OPTIONS, self.options));
+        Effects::none()
     }
 }
 
 impl<E: Env + 'static> UpdateWithCtx<E> for LocalSearch {
-    fn update(&mut self, msg: &Msg, _ctx: &Ctx) -> Effects {
+    fn update(&mut self, msg: &Msg, ctx: &Ctx) -> Effects {
         match msg {
             Msg::Action(Action::Load(ActionLoad::LocalSearch)) => {
                 let load_feed_effect = Self::get_searchable_items::<E>(&CINEMETA_CATALOGS_URL);
 
+                let is_loaded = self.latest_records.is_some();
                 let last_records_effects =
                     eq_update(&mut self.latest_records, Some(Loadable::Loading));
 
+                // the library and the search history can be searched while the feed is loading
+                let index_effects = if !is_loaded {
+                    self.index(&ctx.library, &ctx.search_history)
+                } else {
+                    Effects::none().unchanged()
+                };
+
                 Effects::one(load_feed_effect)
                     .unchanged()
                     .join(last_records_effects)
+                    .join(index_effects)
             }
             Msg::Action(Action::Search(ActionSearch::Search {
                 search_query,
                 max_results,
+                r#type,
             })) => {
-                match &self.searcher {
+                match &self.index {
                     // local search can be performed
-                    Some(searcher) => {
-                        let new_search_results = searcher
-                            .search(search_query, *max_results)
+                    Some(index) => {
+                        let new_search_results = index
+                            .search(search_query, *max_results, r#type.as_deref())
                             .into_iter()
-                            .map(|(searchable, _score)| searchable.to_owned())
+                            .filter(|searchable| {
+                                !ctx.profile.parental_controls.is_blocked(*searchable)
+                            })
+                            .cloned()
                             .collect();
 
                         eq_update(&mut self.search_results, new_search_results)
                     }
-                    // we first need to load the Searchable records from Cinemeta
+                    // we first need to load the local search
                     None => Effects::none().unchanged(),
                 }
             }
+            Msg::Action(Action::Search(ActionSearch::UpdateOptions(options))) => {
+                let options_effects = eq_update(&mut self.options, *options);
+                if options_effects.has_changed {
+                    options_effects.join(self.index(&ctx.library, &ctx.search_history))
+                } else {
+                    options_effects
+                }
+            }
             Msg::Internal(Internal::LoadLocalSearchResult(_url, result)) => {
                 match result {
                     Ok(searchable) => {
@@ -222,20 +557,15 @@ impl<E: Env + 'static> UpdateWithCtx<E> for LocalSearch {
                         let current_records_effects =
                             eq_update(&mut self.current_records, searchable);
 
-                        // Due to LocalSearch not implementing PartialEq, we handle the effects
-                        // based on the current records effects.
-                        let searcher_effects = if current_records_effects.has_changed {
-                            let searcher = self.index(INDEX_OPTIONS, DEFAULT_SCORE_THRESHOLD);
-
-                            self.searcher = Some(searcher);
-                            Effects::none()
+                        let index_effects = if current_records_effects.has_changed {
+                            self.index(&ctx.library, &ctx.search_history)
                         } else {
                             Effects::none().unchanged()
                         };
 
                         last_records_effects
                             .join(current_records_effects)
-                            .join(searcher_effects)
+                            .join(index_effects)
                     }
                     Err(error) => {
                         // update the latest records, but leave the current_records
@@ -247,6 +577,35 @@ impl<E: Env + 'static> UpdateWithCtx<E> for LocalSearch {
                     }
                 }
             }
+            Msg::Internal(Internal::ResourceRequestResult(request, result))
+                if request.path.resource == CATALOG_RESOURCE_NAME
+                    && self.latest_records.is_some() =>
+            {
+                match result.as_ref() {
+                    Ok(ResourceResponse::Metas { metas }) if !metas.is_empty() => {
+                        let next_catalog_records = metas
+                            .iter()
+                            .map(Searchable::from)
+                            .chain(self.catalog_records.iter().cloned())
+                            .unique_by(|searchable| searchable.id.to_owned())
+                            .take(LOCAL_SEARCH_CATALOG_RECORDS_COUNT)
+                            .collect();
+                        let catalog_records_effects =
+                            eq_update(&mut self.catalog_records, next_catalog_records);
+                        if catalog_records_effects.has_changed {
+                            catalog_records_effects
+                                .join(self.index(&ctx.library, &ctx.search_history))
+                        } else {
+                            catalog_records_effects
+                        }
+                    }
+                    _ => Effects::none().unchanged(),
+                }
+            }
+            Msg::Internal(Internal::LibraryChanged(_))
+            | Msg::Internal(Internal::SearchHistoryChanged) => {
+                self.index(&ctx.library, &ctx.search_history)
+            }
             _ => Effects::none().unchanged(),
         }
     }
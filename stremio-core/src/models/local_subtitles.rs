@@ -0,0 +1,115 @@
+use enclose::enclose;
+use futures::FutureExt;
+use http::Request;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::models::common::{eq_update, Loadable};
+use crate::models::ctx::Ctx;
+use crate::runtime::msg::{Action, ActionLoad, Internal, Msg};
+use crate::runtime::{Effect, EffectFuture, Effects, Env, EnvFutureExt, UpdateWithCtx};
+use crate::types::subtitles::{to_webvtt, FramerateCorrection, SubtitlesError, SubtitlesTrack};
+
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Selected {
+    /// The SRT, WebVTT or ASS file, e.g. the `url` of the addon subtitles
+    pub url: Url,
+    /// The ISO 639-2 language of the subtitles, a hint for detecting the legacy encodings
+    #[serde(default)]
+    pub lang: Option<String>,
+    /// In milliseconds, the `subtitle_delay` of the stream state
+    #[serde(default)]
+    pub delay: i64,
+    /// The `subtitle_framerate` of the stream state
+    #[serde(default)]
+    pub framerate: Option<FramerateCorrection>,
+}
+
+/// Fetches, decodes and parses a subtitles file, so the player gets normalized WebVTT cues
+/// with the delay and the framerate correction already applied.
+#[derive(Default, Clone, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalSubtitles {
+    pub selected: Option<Selected>,
+    /// The cues of the file as they were parsed
+    pub track: Option<Loadable<SubtitlesTrack, SubtitlesError>>,
+    /// The retimed cues serialized as a WebVTT file
+    pub webvtt: Option<String>,
+}
+
+impl<E: Env + 'static> UpdateWithCtx<E> for LocalSubtitles {
+    fn update(&mut self, msg: &Msg, _ctx: &Ctx) -> Effects {
+        match msg {
+            Msg::Action(Action::Load(ActionLoad::LocalSubtitles(selected))) => {
+                // only the timing changed, the file is not fetched again
+                let track_effects = match &self.selected {
+                    Some(current)
+                        if current.url == selected.url && current.lang == selected.lang =>
+                    {
+                        Effects::none().unchanged()
+                    }
+                    _ => Effects::one(fetch_subtitles::<E>(selected))
+                        .join(eq_update(&mut self.track, Some(Loadable::Loading))),
+                };
+                let selected_effects = eq_update(&mut self.selected, Some(selected.to_owned()));
+                let webvtt_effects = webvtt_update(&mut self.webvtt, &self.selected, &self.track);
+                track_effects.join(selected_effects).join(webvtt_effects)
+            }
+            Msg::Action(Action::Unload) => {
+                let selected_effects = eq_update(&mut self.selected, None);
+                let track_effects = eq_update(&mut self.track, None);
+                let webvtt_effects = eq_update(&mut self.webvtt, None);
+                selected_effects.join(track_effects).join(webvtt_effects)
+            }
+            Msg::Internal(Internal::LocalSubtitlesResult(url, result))
+                if self
+                    .selected
+                    .as_ref()
+                    .is_some_and(|selected| selected.url == *url)
+                    && self.track == Some(Loadable::Loading) =>
+            {
+                let track = match result {
+                    Ok(track) => Loadable::Ready(track.to_owned()),
+                    Err(error) => Loadable::Err(error.to_owned()),
+                };
+                let track_effects = eq_update(&mut self.track, Some(track));
+                let webvtt_effects = webvtt_update(&mut self.webvtt, &self.selected, &self.track);
+                track_effects.join(webvtt_effects)
+            }
+            _ => Effects::none().unchanged(),
+        }
+    }
+}
+
+fn webvtt_update(
+    webvtt: &mut Option<String>,
+    selected: &Option<Selected>,
+    track: &Option<Loadable<SubtitlesTrack, SubtitlesError>>,
+) -> Effects {
+    let next_webvtt = match (selected, track) {
+        (Some(selected), Some(Loadable::Ready(track))) => Some(to_webvtt(
+            &track.retimed(selected.delay, selected.framerate),
+        )),
+        _ => None,
+    };
+    eq_update(webvtt, next_webvtt)
+}
+
+fn fetch_subtitles<E: Env + 'static>(selected: &Selected) -> Effect {
+    let Selected { url, lang, .. } = selected.to_owned();
+    let request = Request::get(url.as_str())
+        .body(())
+        .expect("request builder failed");
+    EffectFuture::Concurrent(
+        E::fetch_bytes(request)
+            .map(enclose!((url) move |result| {
+                let result = result
+                    .map_err(SubtitlesError::from)
+                    .and_then(|bytes| SubtitlesTrack::parse(&bytes, lang.as_deref()));
+                Msg::Internal(Internal::LocalSubtitlesResult(url, result))
+            }))
+            .boxed_env(),
+    )
+    .into()
+}
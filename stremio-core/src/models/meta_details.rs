@@ -8,13 +8,14 @@ use stremio_watched_bitfield::WatchedBitField;
 
 use crate::{
     constants::{
-        LIBRARY_COLLECTION_NAME, META_RESOURCE_NAME, STREAM_RESOURCE_NAME,
+        LIBRARY_COLLECTION_NAME, META_RESOURCE_NAME, STREAM_PROBE_COUNT, STREAM_RESOURCE_NAME,
         USER_LIKES_SUPPORTED_ID_PREFIXES, USER_LIKES_SUPPORTED_TYPES,
     },
     models::{
         common::{
-            eq_update, resources_update, resources_update_with_vector_content, Loadable,
-            ResourceLoadable, ResourcesAction,
+            eq_update, rank_streams, resources_cancel_effects, resources_update,
+            resources_update_with_vector_content, Loadable, RankedStream, ResourceLoadable,
+            ResourcesAction,
         },
         ctx::{Ctx, CtxError},
     },
@@ -26,12 +27,13 @@ use crate::{
         addon::{AggrRequest, ResourcePath, ResourceRequest},
         api::{DatastoreCommand, DatastoreRequest},
         library::{LibraryBucket, LibraryItem},
-        profile::{AuthKey, Profile},
+        profile::{AuthKey, Profile, Settings},
         rating::{
             Rating, RatingGetStatusRequest, RatingGetStatusResponse, RatingInfo, RatingSendRequest,
             RatingSendResponse,
         },
         resource::{MetaItem, Stream},
+        stream_probes::{stream_probe_urls, StreamHealth, StreamProbesBucket},
         streams::StreamsBucket,
     },
 };
@@ -54,6 +56,10 @@ pub struct MetaDetails {
     pub meta_items: Vec<ResourceLoadable<MetaItem>>,
     pub meta_streams: Vec<ResourceLoadable<Vec<Stream>>>,
     pub streams: Vec<ResourceLoadable<Vec<Stream>>>,
+    /// The loaded `streams` of all addons ranked against the [`StreamPreferences`].
+    ///
+    /// [`StreamPreferences`]: crate::types::profile::StreamPreferences
+    pub ranked_streams: Vec<RankedStream>,
     /// A Stream from addon responses, based on your already watched streams on the device
     /// (i.e. [`StreamsBucket`]), which could be played if binge watching or continuing to watch.
     ///
@@ -78,6 +84,12 @@ impl<E: Env + 'static> UpdateWithCtx<E> for MetaDetails {
                     meta_streams_update(&mut self.meta_streams, &self.selected, &self.meta_items);
                 let streams_effects =
                     streams_update::<E>(&mut self.streams, &self.selected, &ctx.profile);
+                let ranked_streams_effects = ranked_streams_update(
+                    &mut self.ranked_streams,
+                    &self.streams,
+                    &ctx.profile.settings,
+                    &ctx.stream_probes,
+                );
                 let last_used_stream_effects = last_used_stream_update(
                     &mut self.last_used_stream,
                     &self.selected,
@@ -106,24 +118,31 @@ impl<E: Env + 'static> UpdateWithCtx<E> for MetaDetails {
                     .join(meta_streams_effects)
                     .join(rating_info_effects)
                     .join(streams_effects)
+                    .join(ranked_streams_effects)
                     .join(last_used_stream_effects)
                     .join(library_item_effects)
                     .join(watched_effects)
             }
             Msg::Action(Action::Unload) => {
+                let cancel_effects = resources_cancel_effects(&self.meta_items)
+                    .join(resources_cancel_effects(&self.meta_streams))
+                    .join(resources_cancel_effects(&self.streams));
                 let selected_effects = eq_update(&mut self.selected, None);
                 let meta_items_effects = eq_update(&mut self.meta_items, vec![]);
                 let meta_streams_effects = eq_update(&mut self.meta_streams, vec![]);
                 let streams_effects = eq_update(&mut self.streams, vec![]);
+                let ranked_streams_effects = eq_update(&mut self.ranked_streams, vec![]);
                 let library_item_effects = eq_update(&mut self.library_item, None);
                 let last_used_stream_effects = eq_update(&mut self.last_used_stream, None);
                 let watched_effects = eq_update(&mut self.watched, None);
                 let rating_info_effects = eq_update(&mut self.rating_info, None);
 
-                selected_effects
+                cancel_effects
+                    .join(selected_effects)
                     .join(meta_items_effects)
                     .join(meta_streams_effects)
                     .join(streams_effects)
+                    .join(ranked_streams_effects)
                     .join(last_used_stream_effects)
                     .join(library_item_effects)
                     .join(watched_effects)
@@ -213,6 +232,12 @@ impl<E: Env + 'static> UpdateWithCtx<E> for MetaDetails {
                 } else {
                     Effects::default()
                 };
+                let ranked_streams_effects = ranked_streams_update(
+                    &mut self.ranked_streams,
+                    &self.streams,
+                    &ctx.profile.settings,
+                    &ctx.stream_probes,
+                );
                 let meta_streams_effects =
                     meta_streams_update(&mut self.meta_streams, &self.selected, &self.meta_items);
                 let last_used_stream_effects = last_used_stream_update(
@@ -235,6 +260,7 @@ impl<E: Env + 'static> UpdateWithCtx<E> for MetaDetails {
                     .join(meta_items_effects)
                     .join(meta_streams_effects)
                     .join(streams_effects)
+                    .join(ranked_streams_effects)
                     .join(last_used_stream_effects)
                     .join(library_item_effects)
                     .join(watched_effects)
@@ -246,6 +272,12 @@ impl<E: Env + 'static> UpdateWithCtx<E> for MetaDetails {
                     &mut self.streams,
                     ResourcesAction::ResourceRequestResult { request, result },
                 );
+                let ranked_streams_effects = ranked_streams_update(
+                    &mut self.ranked_streams,
+                    &self.streams,
+                    &ctx.profile.settings,
+                    &ctx.stream_probes,
+                );
                 let last_used_stream_effects = last_used_stream_update(
                     &mut self.last_used_stream,
                     &self.selected,
@@ -254,7 +286,9 @@ impl<E: Env + 'static> UpdateWithCtx<E> for MetaDetails {
                     &self.streams,
                     &ctx.streams,
                 );
-                streams_effects.join(last_used_stream_effects)
+                streams_effects
+                    .join(ranked_streams_effects)
+                    .join(last_used_stream_effects)
             }
             Msg::Internal(Internal::RatingGetStatusResult(meta_id, result))
                 if self
@@ -262,6 +296,10 @@ impl<E: Env + 'static> UpdateWithCtx<E> for MetaDetails {
                     .as_ref()
                     .is_some_and(|selected| selected.meta_path.id == *meta_id) =>
             {
+                let rating_changed_effects = match result {
+                    Ok(rating) => rating_changed_effects(&self.meta_items, &rating.status),
+                    Err(_) => Effects::none().unchanged(),
+                };
                 let rating_info_loadable = match result {
                     Ok(rating) => Loadable::Ready(RatingInfo {
                         meta_id: meta_id.to_owned(),
@@ -271,6 +309,7 @@ impl<E: Env + 'static> UpdateWithCtx<E> for MetaDetails {
                 };
 
                 eq_update(&mut self.rating_info, Some(rating_info_loadable))
+                    .join(rating_changed_effects)
             }
             Msg::Internal(Internal::RatingSendResult(meta_id, result))
                 if self
@@ -298,6 +337,7 @@ impl<E: Env + 'static> UpdateWithCtx<E> for MetaDetails {
                         }))))
                         .unchanged()
                         .join(rating_info_effets)
+                        .join(rating_changed_effects(&self.meta_items, &status))
                     }
                     Err(error) => {
                         let rating_info_effets =
@@ -335,6 +375,13 @@ impl<E: Env + 'static> UpdateWithCtx<E> for MetaDetails {
                     meta_streams_update(&mut self.meta_streams, &self.selected, &self.meta_items);
                 let streams_effects =
                     streams_update::<E>(&mut self.streams, &self.selected, &ctx.profile);
+                // the stream preferences might have changed
+                let ranked_streams_effects = ranked_streams_update(
+                    &mut self.ranked_streams,
+                    &self.streams,
+                    &ctx.profile.settings,
+                    &ctx.stream_probes,
+                );
                 let last_used_stream_effects = last_used_stream_update(
                     &mut self.last_used_stream,
                     &self.selected,
@@ -357,11 +404,18 @@ impl<E: Env + 'static> UpdateWithCtx<E> for MetaDetails {
                 meta_items_effects
                     .join(meta_streams_effects)
                     .join(streams_effects)
+                    .join(ranked_streams_effects)
                     .join(last_used_stream_effects)
                     .join(library_item_effects)
                     .join(watched_effects)
                     .join(rating_info_effects)
             }
+            Msg::Internal(Internal::StreamProbesChanged) => ranked_streams_update(
+                &mut self.ranked_streams,
+                &self.streams,
+                &ctx.profile.settings,
+                &ctx.stream_probes,
+            ),
             _ => Effects::none().unchanged(),
         }
     }
@@ -474,6 +528,27 @@ fn meta_items_update<E: Env + 'static>(
     }
 }
 
+/// Reports the rating of the loaded meta item, used for the recommendations.
+fn rating_changed_effects(
+    meta_items: &[ResourceLoadable<MetaItem>],
+    status: &Option<Rating>,
+) -> Effects {
+    meta_items
+        .iter()
+        .find_map(|meta_item| match &meta_item.content {
+            Some(Loadable::Ready(meta_item)) => Some(meta_item),
+            _ => None,
+        })
+        .map(|meta_item| {
+            Effects::msg(Msg::Internal(Internal::MetaItemRatingChanged {
+                meta_item: meta_item.preview.to_owned(),
+                status: status.to_owned(),
+            }))
+            .unchanged()
+        })
+        .unwrap_or_else(|| Effects::none().unchanged())
+}
+
 fn supported_rating_id(id: &str) -> bool {
     USER_LIKES_SUPPORTED_ID_PREFIXES
         .iter()
@@ -639,6 +714,46 @@ fn streams_update<E: Env + 'static>(
     }
 }
 
+/// Ranks the streams of all addons and, with the stream health check, probes the best ones.
+fn ranked_streams_update(
+    ranked_streams: &mut Vec<RankedStream>,
+    streams: &[ResourceLoadable<Vec<Stream>>],
+    settings: &Settings,
+    stream_probes: &StreamProbesBucket,
+) -> Effects {
+    let mut next_ranked_streams = rank_streams(streams, settings);
+    if !settings.stream_health_check {
+        return eq_update(ranked_streams, next_ranked_streams);
+    }
+    for ranked_stream in next_ranked_streams.iter_mut() {
+        ranked_stream.health = stream_probes.health(&ranked_stream.stream);
+    }
+    next_ranked_streams
+        .sort_by_key(|ranked_stream| ranked_stream.health == Some(StreamHealth::Dead));
+    let probes = next_ranked_streams
+        .iter()
+        .take(STREAM_PROBE_COUNT)
+        .flat_map(|ranked_stream| {
+            let headers = ranked_stream
+                .stream
+                .behavior_hints
+                .proxy_headers
+                .as_ref()
+                .map(|proxy_headers| proxy_headers.request.to_owned())
+                .unwrap_or_default();
+            stream_probe_urls(&ranked_stream.stream)
+                .into_iter()
+                .map(move |url| (url.to_owned(), headers.to_owned()))
+        })
+        .collect::<Vec<_>>();
+    let probe_effects = if probes.is_empty() {
+        Effects::none().unchanged()
+    } else {
+        Effects::msg(Msg::Internal(Internal::ProbeStreams(probes))).unchanged()
+    };
+    eq_update(ranked_streams, next_ranked_streams).join(probe_effects)
+}
+
 /// Find a stream from addon responses, which should be played if binge watching or continuing to watch.
 /// We've already loaded the next Video id and we need to find a proper stream for the binge watching.
 ///
@@ -2,6 +2,7 @@ pub mod common;
 pub mod ctx;
 
 pub mod addon_details;
+pub mod addon_health;
 pub mod calendar;
 pub mod catalog_with_filters;
 pub mod catalogs_with_extra;
@@ -9,9 +10,14 @@ pub mod continue_watching_preview;
 pub mod data_export;
 pub mod installed_addons_with_filters;
 pub mod library_by_type;
+pub mod library_transfer;
 pub mod library_with_filters;
 pub mod link;
 pub mod local_search;
+pub mod local_subtitles;
 pub mod meta_details;
 pub mod player;
 pub mod streaming_server;
+pub mod torrent_info;
+pub mod watch_party;
+pub mod watch_stats;
@@ -2,7 +2,8 @@ use std::marker::PhantomData;
 use std::ops::Div;
 
 use base64::Engine;
-use futures::{future, FutureExt, TryFutureExt};
+use futures::{future, Future, FutureExt, TryFutureExt};
+use http::Request;
 use num::rational::Ratio;
 use url::Url;
 
@@ -12,27 +13,31 @@ use crate::constants::{
     VIDEO_HASH_EXTRA_PROP, VIDEO_SIZE_EXTRA_PROP, WATCHED_THRESHOLD_COEF,
 };
 use crate::models::common::{
-    eq_update, resource_update, resource_update_with_vector_content,
-    resources_update_with_vector_content, Loadable, ResourceAction, ResourceLoadable,
-    ResourcesAction,
+    default_subtitles, eq_update, rank_subtitles, resource_update,
+    resource_update_with_vector_content, resources_cancel_effects,
+    resources_update_with_vector_content, DefaultSubtitles, Loadable, RankedSubtitles,
+    ResourceAction, ResourceLoadable, ResourcesAction,
 };
 use crate::models::ctx::{Ctx, CtxError};
 use crate::runtime::msg::{Action, ActionLoad, ActionPlayer, Event, Internal, Msg};
 use crate::runtime::{Effect, EffectFuture, Effects, Env, EnvError, EnvFutureExt, UpdateWithCtx};
-use crate::types::addon::{AggrRequest, Descriptor, ExtraExt, ResourcePath, ResourceRequest};
+use crate::types::addon::{
+    AggrRequest, Descriptor, ExtraExt, ResourcePath, ResourceRequest, ResourceResponse,
+};
 use crate::types::api::{
     fetch_api, APIRequest, APIResult, SeekLog, SeekLogRequest, SkipGapsRequest, SkipGapsResponse,
     SuccessResponse,
 };
 use crate::types::library::{LibraryBucket, LibraryItem};
 use crate::types::player::{IntroData, IntroOutro};
-use crate::types::profile::Profile;
+use crate::types::profile::{AutoPlayPolicy, Profile, Settings};
 use crate::types::resource::{
-    MetaItem, SeriesInfo, Stream, StreamSource, StreamUrls, Subtitles, Video,
+    MetaItem, SeriesInfo, Stream, StreamInfo, StreamSource, StreamUrls, Subtitles, Video,
 };
 use crate::types::streams::{
-    ConvertedStreamSource, StreamItemState, StreamsBucket, StreamsItemKey,
+    ConvertedStreamSource, StreamItemState, StreamsBucket, StreamsItemKey, SubtitleTrack,
 };
+use crate::types::subtitles::{SubtitlesCue, SubtitlesError, SubtitlesSync, SubtitlesTrack};
 
 use stremio_watched_bitfield::WatchedBitField;
 
@@ -90,6 +95,35 @@ pub struct Selected {
     pub subtitles_path: Option<ResourcePath>,
 }
 
+/// The track to which the addon subtitles are aligned, see [`ActionPlayer::SyncSubtitles`].
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+#[serde(tag = "type", content = "content")]
+pub enum SubtitlesReference {
+    /// The cue times of an embedded track, reported by the player
+    Embedded(Vec<SubtitlesCue>),
+    /// The addon subtitles in another language, which must be loaded for the same [`VideoParams::hash`]
+    Subtitles(Subtitles),
+}
+
+/// The proposed alignment of the addon subtitles, see [`ActionPlayer::ApplySubtitlesSync`].
+#[derive(Clone, PartialEq, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SubtitlesSyncProposal {
+    pub subtitles: Subtitles,
+    pub sync: Loadable<SubtitlesSync, SubtitlesError>,
+}
+
+/// The reason for selecting the stream of the next video, see [`AutoPlayPolicy`].
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Debug)]
+pub enum NextStreamMatch {
+    /// The stream is from the same binge group
+    BingeGroup,
+    /// The first stream of the same addon
+    SameAddon,
+    /// The stream of the same addon with the closest quality and filename
+    BestMatch,
+}
+
 #[derive(Clone, Derivative, Serialize, Debug)]
 #[derivative(Default)]
 #[serde(rename_all = "camelCase")]
@@ -98,13 +132,21 @@ pub struct Player {
     pub video_params: Option<VideoParams>,
     pub meta_item: Option<ResourceLoadable<MetaItem>>,
     pub subtitles: Vec<ResourceLoadable<Vec<Subtitles>>>,
+    /// The loaded `subtitles` of all addons ranked against the subtitles languages in the [`Settings`]
+    pub ranked_subtitles: Vec<RankedSubtitles>,
+    /// The subtitles track which should be selected when the playback starts and why,
+    /// chosen once for the loaded stream
+    pub default_subtitles: Option<DefaultSubtitles>,
     pub next_video: Option<Video>,
     pub next_streams: Option<ResourceLoadable<Vec<Stream>>>,
     pub next_stream: Option<Stream>,
+    /// Why the `next_stream` was selected
+    pub next_stream_match: Option<NextStreamMatch>,
     pub stream: Option<Loadable<(StreamUrls, Stream<ConvertedStreamSource>), EnvError>>,
     pub series_info: Option<SeriesInfo>,
     pub library_item: Option<LibraryItem>,
     pub stream_state: Option<StreamItemState>,
+    pub subtitles_sync: Option<SubtitlesSyncProposal>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub intro_outro: Option<IntroOutro>,
     #[serde(skip_serializing)]
@@ -136,6 +178,37 @@ pub struct Player {
 impl<E: Env + 'static> UpdateWithCtx<E> for Player {
     fn update(&mut self, msg: &Msg, ctx: &Ctx) -> Effects {
         match msg {
+            Msg::Action(Action::Load(ActionLoad::Player(selected)))
+                if selected.meta_request.as_ref().is_some_and(|meta_request| {
+                    let parental_controls = &ctx.profile.parental_controls;
+                    parental_controls.is_blocked(&meta_request.path)
+                        || ctx
+                            .library
+                            .items
+                            .get(&meta_request.path.id)
+                            .is_some_and(|library_item| parental_controls.is_blocked(library_item))
+                }) =>
+            {
+                let id = selected
+                    .meta_request
+                    .as_ref()
+                    .map(|meta_request| meta_request.path.id.to_owned());
+                blocked_update::<E>(self, id, ctx)
+            }
+            Msg::Internal(Internal::ResourceRequestResult(request, result))
+                if request.path.resource == META_RESOURCE_NAME
+                    && self
+                        .meta_item
+                        .as_ref()
+                        .is_some_and(|meta_item| meta_item.request == *request)
+                    && matches!(
+                        result.as_ref(),
+                        Ok(ResourceResponse::Meta { meta })
+                            if ctx.profile.parental_controls.is_blocked(meta)
+                    ) =>
+            {
+                blocked_update::<E>(self, Some(request.path.id.to_owned()), ctx)
+            }
             Msg::Action(Action::Load(ActionLoad::Player(selected))) => {
                 let item_state_update_effects = if self
                     .selected
@@ -177,7 +250,8 @@ impl<E: Env + 'static> UpdateWithCtx<E> for Player {
                     },
                     _ => eq_update(&mut self.meta_item, None),
                 };
-                let stream_state_effects = eq_update(&mut self.stream_state, None);
+                let stream_state_effects = eq_update(&mut self.stream_state, None)
+                    .join(eq_update(&mut self.subtitles_sync, None));
                 let video_params_effects = eq_update(&mut self.video_params, None);
 
                 let stream_effects = stream_update(
@@ -192,6 +266,14 @@ impl<E: Env + 'static> UpdateWithCtx<E> for Player {
                     &self.video_params,
                     &ctx.profile.addons,
                 );
+                let ranked_subtitles_effects =
+                    eq_update(&mut self.default_subtitles, None).join(ranked_subtitles_update(
+                        &mut self.ranked_subtitles,
+                        &mut self.default_subtitles,
+                        &self.subtitles,
+                        &self.stream_state,
+                        &ctx.profile.settings,
+                    ));
                 let next_video_effects = next_video_update(
                     &mut self.next_video,
                     &self.next_stream,
@@ -203,8 +285,13 @@ impl<E: Env + 'static> UpdateWithCtx<E> for Player {
                     &self.next_video,
                     &self.selected,
                 );
-                let next_stream_effects =
-                    next_stream_update(&mut self.next_stream, &self.next_streams, &self.selected);
+                let next_stream_effects = next_stream_update(
+                    &mut self.next_stream,
+                    &mut self.next_stream_match,
+                    &self.next_streams,
+                    &self.selected,
+                    &ctx.profile.settings,
+                );
                 // Make sure to update the steams and in term the StreamsBucket
                 // once the player loads the newly selected item
                 let update_streams_effects = match (&self.selected, &self.meta_item) {
@@ -290,6 +377,7 @@ impl<E: Env + 'static> UpdateWithCtx<E> for Player {
                     .join(video_params_effects)
                     .join(stream_effects)
                     .join(subtitles_effects)
+                    .join(ranked_subtitles_effects)
                     .join(next_video_effects)
                     .join(next_streams_effects)
                     .join(next_stream_effects)
@@ -331,15 +419,22 @@ impl<E: Env + 'static> UpdateWithCtx<E> for Player {
                     .unchanged(),
                     _ => Effects::none().unchanged(),
                 };
+                let cancel_effects = resources_cancel_effects(&self.meta_item)
+                    .join(resources_cancel_effects(&self.subtitles))
+                    .join(resources_cancel_effects(&self.next_streams));
                 let selected_effects = eq_update(&mut self.selected, None);
                 let video_params_effects = eq_update(&mut self.video_params, None);
                 let meta_item_effects = eq_update(&mut self.meta_item, None);
-                let stream_state_effects = eq_update(&mut self.stream_state, None);
+                let stream_state_effects = eq_update(&mut self.stream_state, None)
+                    .join(eq_update(&mut self.subtitles_sync, None));
                 let stream_effects = eq_update(&mut self.stream, None);
-                let subtitles_effects = eq_update(&mut self.subtitles, vec![]);
+                let subtitles_effects = eq_update(&mut self.subtitles, vec![])
+                    .join(eq_update(&mut self.ranked_subtitles, vec![]))
+                    .join(eq_update(&mut self.default_subtitles, None));
                 let next_video_effects = eq_update(&mut self.next_video, None);
                 let next_streams_effects = eq_update(&mut self.next_streams, None);
-                let next_stream_effects = eq_update(&mut self.next_stream, None);
+                let next_stream_effects = eq_update(&mut self.next_stream, None)
+                    .join(eq_update(&mut self.next_stream_match, None));
                 let series_info_effects = eq_update(&mut self.series_info, None);
                 let library_item_effects = eq_update(&mut self.library_item, None);
                 let watched_effects = eq_update(&mut self.watched, None);
@@ -367,6 +462,7 @@ impl<E: Env + 'static> UpdateWithCtx<E> for Player {
                     .join(watched_effects)
                     .join(skip_gaps_effects)
                     .join(ended_effects)
+                    .join(cancel_effects)
             }
             Msg::Action(Action::Player(ActionPlayer::VideoParamsChanged { video_params })) => {
                 let video_params_effects =
@@ -377,7 +473,14 @@ impl<E: Env + 'static> UpdateWithCtx<E> for Player {
                     &self.selected,
                     &self.video_params,
                     &ctx.profile.addons,
-                );
+                )
+                .join(ranked_subtitles_update(
+                    &mut self.ranked_subtitles,
+                    &mut self.default_subtitles,
+                    &self.subtitles,
+                    &self.stream_state,
+                    &ctx.profile.settings,
+                ));
                 let skip_gaps_effects = skip_gaps_update::<E>(
                     &ctx.profile,
                     self.selected.as_ref(),
@@ -405,6 +508,80 @@ impl<E: Env + 'static> UpdateWithCtx<E> for Player {
                 }))
                 .unchanged()
             }
+            Msg::Action(Action::Player(ActionPlayer::SyncSubtitles {
+                subtitles,
+                reference,
+            })) if self.selected.is_some() => {
+                let reference_matches = match reference {
+                    SubtitlesReference::Embedded(_) => true,
+                    SubtitlesReference::Subtitles(reference) => {
+                        is_video_hash_subtitles(&self.subtitles, &self.video_params, reference)
+                    }
+                };
+                let (sync_effects, sync) = if reference_matches {
+                    (
+                        Effects::one(sync_subtitles::<E>(subtitles, reference)),
+                        Loadable::Loading,
+                    )
+                } else {
+                    (
+                        Effects::none(),
+                        Loadable::Err(SubtitlesError::VideoHashMismatch),
+                    )
+                };
+                let subtitles_sync_effects = eq_update(
+                    &mut self.subtitles_sync,
+                    Some(SubtitlesSyncProposal {
+                        subtitles: subtitles.to_owned(),
+                        sync,
+                    }),
+                );
+                sync_effects.join(subtitles_sync_effects)
+            }
+            Msg::Action(Action::Player(ActionPlayer::ApplySubtitlesSync)) => {
+                match (&self.selected, &self.subtitles_sync) {
+                    (
+                        Some(selected),
+                        Some(SubtitlesSyncProposal {
+                            subtitles,
+                            sync: Loadable::Ready(sync),
+                        }),
+                    ) => {
+                        // the delay and the framerate belong to the aligned subtitles
+                        let state = StreamItemState {
+                            subtitle_track: Some(SubtitleTrack {
+                                id: subtitles.id.to_owned(),
+                                embedded: false,
+                                language: Some(subtitles.lang.to_owned()),
+                            }),
+                            subtitle_delay: Some(sync.offset),
+                            subtitle_framerate: sync.framerate(),
+                            ..self.stream_state.to_owned().unwrap_or_default()
+                        };
+                        Effects::msg(Msg::Internal(Internal::StreamStateChanged {
+                            state,
+                            stream_request: selected.stream_request.to_owned(),
+                            meta_request: selected.meta_request.to_owned(),
+                        }))
+                        .unchanged()
+                    }
+                    _ => Effects::none().unchanged(),
+                }
+            }
+            Msg::Internal(Internal::SubtitlesSyncResult(url, result)) => {
+                match &mut self.subtitles_sync {
+                    Some(proposal)
+                        if proposal.subtitles.url == *url && proposal.sync.is_loading() =>
+                    {
+                        proposal.sync = match result {
+                            Ok(sync) => Loadable::Ready(sync.to_owned()),
+                            Err(error) => Loadable::Err(error.to_owned()),
+                        };
+                        Effects::none()
+                    }
+                    _ => Effects::none().unchanged(),
+                }
+            }
             Msg::Action(Action::Player(ActionPlayer::Seek {
                 time,
                 duration,
@@ -744,7 +921,16 @@ impl<E: Env + 'static> UpdateWithCtx<E> for Player {
                     .join(watched_effects)
             }
             Msg::Internal(Internal::StreamsChanged(_)) => {
-                stream_state_update(&mut self.stream_state, &self.selected, &ctx.streams)
+                let stream_state_effects =
+                    stream_state_update(&mut self.stream_state, &self.selected, &ctx.streams);
+                let ranked_subtitles_effects = ranked_subtitles_update(
+                    &mut self.ranked_subtitles,
+                    &mut self.default_subtitles,
+                    &self.subtitles,
+                    &self.stream_state,
+                    &ctx.profile.settings,
+                );
+                stream_state_effects.join(ranked_subtitles_effects)
             }
             Msg::Internal(Internal::ResourceRequestResult(request, result))
                 if self.selected.is_some() =>
@@ -778,6 +964,13 @@ impl<E: Env + 'static> UpdateWithCtx<E> for Player {
                         &mut self.subtitles,
                         ResourcesAction::ResourceRequestResult { request, result },
                     )
+                    .join(ranked_subtitles_update(
+                        &mut self.ranked_subtitles,
+                        &mut self.default_subtitles,
+                        &self.subtitles,
+                        &self.stream_state,
+                        &ctx.profile.settings,
+                    ))
                 } else {
                     Effects::none().unchanged()
                 };
@@ -805,8 +998,13 @@ impl<E: Env + 'static> UpdateWithCtx<E> for Player {
                     &self.selected,
                 ));
 
-                let next_stream_effects =
-                    next_stream_update(&mut self.next_stream, &self.next_streams, &self.selected);
+                let next_stream_effects = next_stream_update(
+                    &mut self.next_stream,
+                    &mut self.next_stream_match,
+                    &self.next_streams,
+                    &self.selected,
+                    &ctx.profile.settings,
+                );
 
                 let series_info_effects =
                     series_info_update(&mut self.series_info, &self.selected, &self.meta_item);
@@ -896,7 +1094,13 @@ impl<E: Env + 'static> UpdateWithCtx<E> for Player {
                 if let Some(analytics_context) = &mut self.analytics_context {
                     analytics_context.has_trakt = ctx.profile.has_trakt::<E>();
                 };
-                Effects::none().unchanged()
+                ranked_subtitles_update(
+                    &mut self.ranked_subtitles,
+                    &mut self.default_subtitles,
+                    &self.subtitles,
+                    &self.stream_state,
+                    &ctx.profile.settings,
+                )
             }
             _ => Effects::none().unchanged(),
         }
@@ -1027,6 +1231,12 @@ fn next_video_update(
     eq_update(video, next_video)
 }
 
+/// Unloads the player when the content is blocked by the parental controls.
+fn blocked_update<E: Env + 'static>(player: &mut Player, id: Option<String>, ctx: &Ctx) -> Effects {
+    let unload_effects = UpdateWithCtx::<E>::update(player, &Msg::Action(Action::Unload), ctx);
+    unload_effects.join(Effects::msg(Msg::Event(Event::PlayerBlocked { id })).unchanged())
+}
+
 fn next_streams_update<E>(
     next_streams: &mut Option<ResourceLoadable<Vec<Stream>>>,
     next_video: &Option<Video>,
@@ -1077,8 +1287,10 @@ where
 
 fn next_stream_update(
     stream: &mut Option<Stream>,
+    stream_match: &mut Option<NextStreamMatch>,
     next_streams: &Option<ResourceLoadable<Vec<Stream>>>,
     selected: &Option<Selected>,
+    settings: &Settings,
 ) -> Effects {
     let next_stream = match (selected, next_streams) {
         (
@@ -1087,14 +1299,75 @@ fn next_stream_update(
                 content: Some(Loadable::Ready(streams)),
                 ..
             }),
-        ) => streams
+        ) if settings.auto_play_policy != AutoPlayPolicy::Off => streams
             .iter()
             .find(|next_stream| next_stream.is_binge_match(stream))
-            .cloned(),
+            .map(|next_stream| (next_stream, NextStreamMatch::BingeGroup))
+            .or_else(|| match settings.auto_play_policy {
+                AutoPlayPolicy::SameAddon => streams
+                    .first()
+                    .map(|next_stream| (next_stream, NextStreamMatch::SameAddon)),
+                AutoPlayPolicy::BestMatch => streams
+                    .iter()
+                    // the first of the equally similar streams is preferred
+                    .rev()
+                    .max_by_key(|next_stream| stream_similarity(stream, next_stream))
+                    .map(|next_stream| (next_stream, NextStreamMatch::BestMatch)),
+                _ => None,
+            }),
         _ => None,
     };
+    let (next_stream, next_stream_match) = match next_stream {
+        Some((next_stream, next_stream_match)) => {
+            (Some(next_stream.to_owned()), Some(next_stream_match))
+        }
+        None => (None, None),
+    };
 
-    eq_update(stream, next_stream)
+    eq_update(stream, next_stream).join(eq_update(stream_match, next_stream_match))
+}
+
+/// How similar are the quality and the naming of two streams.
+///
+/// Every matching detail of the [`StreamInfo`] weights more than a common word
+/// in the `name`, `description` and `filename`.
+fn stream_similarity(stream: &Stream, other_stream: &Stream) -> usize {
+    const INFO_WEIGHT: usize = 10;
+    let info = StreamInfo::from(stream);
+    let other_info = StreamInfo::from(other_stream);
+    let info_similarity = [
+        info.resolution.is_some() && info.resolution == other_info.resolution,
+        info.hdr == other_info.hdr,
+        info.codec.is_some() && info.codec == other_info.codec,
+        info.audio_channels.is_some() && info.audio_channels == other_info.audio_channels,
+        !info.languages.is_empty() && info.languages == other_info.languages,
+    ]
+    .into_iter()
+    .filter(|is_match| *is_match)
+    .count();
+    let words = stream_words(stream);
+    let words_similarity = stream_words(other_stream)
+        .iter()
+        .filter(|word| words.contains(word))
+        .count();
+    INFO_WEIGHT * info_similarity + words_similarity
+}
+
+/// The distinct words of the stream without the numbers, which usually
+/// change between the episodes, e.g. `S01E02`.
+fn stream_words(stream: &Stream) -> Vec<String> {
+    [
+        stream.name.as_deref(),
+        stream.description.as_deref(),
+        stream.behavior_hints.filename.as_deref(),
+    ]
+    .into_iter()
+    .flatten()
+    .flat_map(|text| text.split(|character: char| !character.is_alphanumeric()))
+    .filter(|word| !word.is_empty() && !word.chars().any(|character| character.is_ascii_digit()))
+    .map(|word| word.to_lowercase())
+    .unique()
+    .collect()
 }
 
 fn series_info_update(
@@ -1229,6 +1502,97 @@ fn stream_update(
     }
 }
 
+/// Fetches the addon subtitles and the reference subtitles, then aligns them
+fn sync_subtitles<E: Env + 'static>(
+    subtitles: &Subtitles,
+    reference: &SubtitlesReference,
+) -> Effect {
+    fn fetch_track<E: Env + 'static>(
+        subtitles: Subtitles,
+    ) -> impl Future<Output = Result<SubtitlesTrack, SubtitlesError>> {
+        let request = Request::get(subtitles.url.as_str())
+            .body(())
+            .expect("request builder failed");
+        E::fetch_bytes(request).map(move |result| {
+            result
+                .map_err(SubtitlesError::from)
+                .and_then(|bytes| SubtitlesTrack::parse(&bytes, Some(&subtitles.lang)))
+        })
+    }
+
+    let url = subtitles.url.to_owned();
+    let reference = match reference {
+        SubtitlesReference::Embedded(cues) => future::ok(cues.to_owned()).boxed_env(),
+        SubtitlesReference::Subtitles(subtitles) => fetch_track::<E>(subtitles.to_owned())
+            .map_ok(|track| track.cues)
+            .boxed_env(),
+    };
+    EffectFuture::Concurrent(
+        future::try_join(fetch_track::<E>(subtitles.to_owned()), reference)
+            .map(move |result| {
+                let result = result
+                    .and_then(|(track, reference)| SubtitlesSync::new(&track.cues, &reference));
+                Msg::Internal(Internal::SubtitlesSyncResult(url, result))
+            })
+            .boxed_env(),
+    )
+    .into()
+}
+
+/// Whether the subtitles were returned by an addon for the hash of the played video
+fn is_video_hash_subtitles(
+    subtitles: &[ResourceLoadable<Vec<Subtitles>>],
+    video_params: &Option<VideoParams>,
+    reference: &Subtitles,
+) -> bool {
+    let hash = match video_params
+        .as_ref()
+        .and_then(|params| params.hash.as_ref())
+    {
+        Some(hash) => hash,
+        None => return false,
+    };
+    subtitles.iter().any(|resource| {
+        resource
+            .request
+            .path
+            .get_extra_first_value(&VIDEO_HASH_EXTRA_PROP.name)
+            == Some(hash)
+            && matches!(
+                &resource.content,
+                Some(Loadable::Ready(subtitles)) if subtitles.contains(reference)
+            )
+    })
+}
+
+fn ranked_subtitles_update(
+    ranked_subtitles: &mut Vec<RankedSubtitles>,
+    default: &mut Option<DefaultSubtitles>,
+    subtitles: &[ResourceLoadable<Vec<Subtitles>>],
+    stream_state: &Option<StreamItemState>,
+    settings: &Settings,
+) -> Effects {
+    let previous_track = stream_state
+        .as_ref()
+        .and_then(|state| state.subtitle_track.as_ref());
+    let next_ranked_subtitles = rank_subtitles(subtitles, settings, previous_track);
+    // the default is chosen once for the loaded stream, after the subtitles of all addons are loaded,
+    // so selecting another track during the playback does not change it
+    let default_effects = if default.is_none()
+        && subtitles
+            .iter()
+            .all(|resource| !matches!(resource.content, Some(Loadable::Loading)))
+    {
+        eq_update(
+            default,
+            default_subtitles(&next_ranked_subtitles, previous_track),
+        )
+    } else {
+        Effects::none().unchanged()
+    };
+    eq_update(ranked_subtitles, next_ranked_subtitles).join(default_effects)
+}
+
 fn subtitles_update<E: Env + 'static>(
     subtitles: &mut Vec<ResourceLoadable<Vec<Subtitles>>>,
     selected: &Option<Selected>,
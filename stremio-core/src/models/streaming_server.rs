@@ -1,7 +1,6 @@
 use enclose::enclose;
 use futures::{FutureExt, TryFutureExt};
 use http::request::Request;
-use magnet_url::{Magnet, MagnetError};
 use serde::{Deserialize, Serialize};
 use sha1::{Digest, Sha1};
 use url::Url;
@@ -21,7 +20,7 @@ use crate::types::streaming_server::{
     CreateMagnetRequest, CreateTorrentBlobRequest, DeviceInfo, GetHTTPSResponse, NetworkInfo,
     Settings, SettingsResponse, Statistics, StatisticsRequest, TorrentStatisticsRequest,
 };
-use crate::types::torrent::InfoHash;
+use crate::types::torrent::{InfoHash, MagnetLink};
 
 #[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
@@ -118,8 +117,12 @@ impl<E: Env + 'static> UpdateWithCtx<E> for StreamingServer {
             }
             Msg::Action(Action::StreamingServer(ActionStreamingServer::CreateTorrent(
                 CreateTorrentArgs::Magnet(magnet),
-            ))) => match parse_magnet(magnet) {
-                Ok((info_hash, announce)) => {
+            ))) => match magnet.as_str().parse::<MagnetLink>() {
+                Ok(MagnetLink {
+                    info_hash,
+                    announce,
+                    ..
+                }) => {
                     let torrent_effects =
                         eq_update(&mut self.torrent, Some((info_hash, Loadable::Loading)));
                     Effects::many(vec![
@@ -569,17 +572,8 @@ pub fn create_torrent_request<E: Env + 'static>(
     .into()
 }
 
-fn parse_magnet(magnet: &Url) -> Result<(InfoHash, Vec<String>), MagnetError> {
-    let magnet = Magnet::new(magnet.as_str())?;
-    let info_hash = magnet.xt.ok_or(MagnetError::NotAMagnetURL)?;
-    let info_hash = info_hash
-        .parse()
-        .map_err(|_err| MagnetError::NotAMagnetURL)?;
-
-    let announce = magnet.tr;
-    Ok((info_hash, announce))
-}
-
+/// Unlike [`TorrentMetainfo`](crate::types::torrent::TorrentMetainfo) only the info hash
+/// is required, so the streaming server can create any torrent, e.g. a v2-only one.
 fn parse_torrent(torrent: &[u8]) -> Result<(InfoHash, Vec<String>), serde_bencode::Error> {
     #[derive(Deserialize)]
     struct TorrentFile {
@@ -0,0 +1,147 @@
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::models::common::{eq_update, Loadable};
+use crate::models::ctx::Ctx;
+use crate::runtime::msg::{Action, ActionLoad, ActionTorrentInfo, Msg};
+use crate::runtime::{Effects, Env, UpdateWithCtx};
+use crate::types::resource::StreamSource;
+use crate::types::torrent::{MagnetLink, TorrentError, TorrentFile, TorrentMetainfo};
+
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+#[serde(untagged)]
+pub enum TorrentSource {
+    /// The content of a `.torrent` file
+    File(Vec<u8>),
+    Magnet(Url),
+}
+
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Selected {
+    pub source: TorrentSource,
+    /// The `file_must_include` of the torrent stream
+    #[serde(default)]
+    pub file_must_include: Vec<String>,
+}
+
+#[derive(Clone, PartialEq, Eq, Serialize, Debug)]
+#[serde(tag = "type", content = "content")]
+pub enum TorrentContent {
+    Torrent(TorrentMetainfo),
+    /// The files of a magnet link are not known without the streaming server
+    Magnet(MagnetLink),
+}
+
+#[derive(Clone, PartialEq, Eq, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TorrentInfoFile {
+    #[serde(flatten)]
+    pub file: TorrentFile,
+    pub is_video: bool,
+    /// Whether the file matches the `file_must_include` of the selected torrent
+    pub matches: bool,
+}
+
+/// Inspects the files of a torrent before playing it, without the streaming server.
+#[derive(Default, Clone, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TorrentInfo {
+    pub selected: Option<Selected>,
+    pub content: Option<Loadable<TorrentContent, TorrentError>>,
+    pub files: Vec<TorrentInfoFile>,
+    /// The file to play, by default the one the streaming server would pick
+    pub file_idx: Option<u16>,
+}
+
+impl TorrentInfo {
+    /// The source of the torrent stream playing the selected file.
+    pub fn stream_source(&self) -> Option<StreamSource> {
+        let file_must_include = self
+            .selected
+            .as_ref()
+            .map(|selected| selected.file_must_include.to_owned())
+            .unwrap_or_default();
+        let (info_hash, announce) = match &self.content {
+            Some(Loadable::Ready(TorrentContent::Torrent(metainfo))) => {
+                (metainfo.info_hash, &metainfo.announce)
+            }
+            Some(Loadable::Ready(TorrentContent::Magnet(magnet_link))) => {
+                (magnet_link.info_hash, &magnet_link.announce)
+            }
+            _ => return None,
+        };
+        Some(StreamSource::Torrent {
+            info_hash: info_hash.as_array(),
+            file_idx: self.file_idx,
+            announce: announce.to_owned(),
+            file_must_include,
+        })
+    }
+}
+
+impl<E: Env + 'static> UpdateWithCtx<E> for TorrentInfo {
+    fn update(&mut self, msg: &Msg, _: &Ctx) -> Effects {
+        match msg {
+            Msg::Action(Action::Load(ActionLoad::TorrentInfo(selected))) => {
+                let selected_effects = eq_update(&mut self.selected, Some(selected.to_owned()));
+                let content = match &selected.source {
+                    TorrentSource::File(torrent) => {
+                        TorrentMetainfo::from_bytes(torrent).map(TorrentContent::Torrent)
+                    }
+                    TorrentSource::Magnet(magnet) => magnet
+                        .as_str()
+                        .parse::<MagnetLink>()
+                        .map(TorrentContent::Magnet),
+                };
+                let files = match &content {
+                    Ok(TorrentContent::Torrent(metainfo)) => metainfo
+                        .files
+                        .iter()
+                        .map(|file| TorrentInfoFile {
+                            file: file.to_owned(),
+                            is_video: file.is_video(),
+                            matches: file.matches(&selected.file_must_include),
+                        })
+                        .collect(),
+                    _ => vec![],
+                };
+                let file_idx = match &content {
+                    Ok(TorrentContent::Torrent(metainfo)) => metainfo
+                        .default_file(&selected.file_must_include)
+                        .map(|file| file.idx),
+                    _ => None,
+                };
+                let content_effects = eq_update(
+                    &mut self.content,
+                    Some(match content {
+                        Ok(content) => Loadable::Ready(content),
+                        Err(error) => Loadable::Err(error),
+                    }),
+                );
+                let files_effects = eq_update(&mut self.files, files);
+                let file_idx_effects = eq_update(&mut self.file_idx, file_idx);
+                selected_effects
+                    .join(content_effects)
+                    .join(files_effects)
+                    .join(file_idx_effects)
+            }
+            Msg::Action(Action::TorrentInfo(ActionTorrentInfo::SelectFile(file_idx)))
+                if self.files.iter().any(|file| file.file.idx == *file_idx) =>
+            {
+                eq_update(&mut self.file_idx, Some(*file_idx))
+            }
+            Msg::Action(Action::Unload) => {
+                let selected_effects = eq_update(&mut self.selected, None);
+                let content_effects = eq_update(&mut self.content, None);
+                let files_effects = eq_update(&mut self.files, vec![]);
+                let file_idx_effects = eq_update(&mut self.file_idx, None);
+                selected_effects
+                    .join(content_effects)
+                    .join(files_effects)
+                    .join(file_idx_effects)
+            }
+            _ => Effects::none().unchanged(),
+        }
+    }
+}
@@ -0,0 +1,487 @@
+use chrono::{DateTime, Utc};
+use futures::FutureExt;
+use serde::{Deserialize, Serialize};
+
+use crate::constants::{
+    WATCH_PARTY_DRIFT_TOLERANCE, WATCH_PARTY_PLAYBACK_RATE_ADJUSTMENT, WATCH_PARTY_SEEK_THRESHOLD,
+    WATCH_PARTY_STATE_INTERVAL,
+};
+use crate::models::common::eq_update;
+use crate::models::ctx::{Ctx, CtxError};
+use crate::models::player::Selected as PlayerSelected;
+use crate::runtime::msg::{Action, ActionLoad, ActionWatchParty, Event, Internal, Msg};
+use crate::runtime::{Effect, EffectFuture, Effects, Env, EnvFutureExt, UpdateWithCtx};
+use crate::types::watch_party::{WatchPartyMemberId, WatchPartyMessage, WatchPartyPayload};
+
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Selected {
+    pub party_id: String,
+    pub member_id: WatchPartyMemberId,
+    /// The other members follow the playback of the host
+    pub host: bool,
+}
+
+#[derive(Clone, PartialEq, Eq, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchPartyMember {
+    pub id: WatchPartyMemberId,
+    /// The difference between the clock of the member and the local clock in milliseconds,
+    /// `None` until it is estimated with a ping
+    pub clock_offset: Option<i64>,
+    pub last_seen: DateTime<Utc>,
+}
+
+/// The playback of the local player.
+#[derive(Clone, PartialEq, Eq, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchPartyPlayback {
+    pub paused: bool,
+    /// In milliseconds
+    pub time: u64,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl WatchPartyPlayback {
+    /// The expected position of the player at the given moment.
+    pub fn time_at(&self, now: DateTime<Utc>) -> u64 {
+        if self.paused {
+            self.time
+        } else {
+            self.time + elapsed(self.updated_at, now)
+        }
+    }
+}
+
+/// What the local player should do to follow the party.
+///
+/// The correction is cleared once the player reports the corrected playback.
+#[derive(Clone, PartialEq, Serialize, Debug)]
+#[serde(tag = "type", content = "content")]
+pub enum WatchPartyCorrection {
+    Play {
+        time: u64,
+    },
+    Pause {
+        time: u64,
+    },
+    Seek {
+        time: u64,
+    },
+    /// Speed up or slow down the playback until the drift is within the tolerance
+    PlaybackRate {
+        rate: f64,
+    },
+}
+
+#[derive(Default, Clone, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchParty {
+    pub selected: Option<Selected>,
+    /// The other members of the party
+    pub members: Vec<WatchPartyMember>,
+    pub playback: Option<WatchPartyPlayback>,
+    pub correction: Option<WatchPartyCorrection>,
+    /// The stream the party is watching
+    pub stream: Option<PlayerSelected>,
+    /// When the host sent its playback state for the last time
+    #[serde(skip)]
+    pub state_sent_at: Option<DateTime<Utc>>,
+}
+
+impl<E: Env + 'static> UpdateWithCtx<E> for WatchParty {
+    fn update(&mut self, msg: &Msg, _: &Ctx) -> Effects {
+        match msg {
+            Msg::Action(Action::Load(ActionLoad::WatchParty(selected)))
+                if self.selected.as_ref() != Some(selected) =>
+            {
+                let leave_effects = match &self.selected {
+                    Some(selected) => {
+                        Effects::one(send::<E>(selected, WatchPartyPayload::Leave)).unchanged()
+                    }
+                    None => Effects::none().unchanged(),
+                };
+                *self = WatchParty {
+                    selected: Some(selected.to_owned()),
+                    ..Default::default()
+                };
+                leave_effects
+                    .join(Effects::one(send::<E>(selected, WatchPartyPayload::Join)).unchanged())
+                    .join(Effects::one(send::<E>(selected, WatchPartyPayload::Ping)))
+            }
+            Msg::Action(Action::Unload) => match &self.selected {
+                Some(selected) => {
+                    let leave_effects =
+                        Effects::one(send::<E>(selected, WatchPartyPayload::Leave)).unchanged();
+                    *self = WatchParty::default();
+                    leave_effects
+                }
+                None => Effects::none().unchanged(),
+            },
+            Msg::Action(Action::WatchParty(action)) => match &self.selected {
+                Some(selected) => {
+                    let selected = selected.to_owned();
+                    self.action_update::<E>(&selected, action)
+                }
+                None => Effects::none().unchanged(),
+            },
+            Msg::Internal(Internal::WatchPartyReceiveResult(party_id, result)) => {
+                match (&self.selected, result) {
+                    (Some(selected), Ok(messages)) if selected.party_id == *party_id => {
+                        let selected = selected.to_owned();
+                        messages
+                            .iter()
+                            .filter(|message| {
+                                message.party_id == selected.party_id
+                                    && message.member_id != selected.member_id
+                            })
+                            .fold(
+                                Effects::msg(Msg::Event(Event::WatchPartyMessagesReceived {
+                                    party_id: party_id.to_owned(),
+                                }))
+                                .unchanged(),
+                                |effects, message| {
+                                    effects.join(self.message_update::<E>(&selected, message))
+                                },
+                            )
+                    }
+                    (Some(selected), Err(error)) if selected.party_id == *party_id => {
+                        Effects::msg(Msg::Event(Event::Error {
+                            error: CtxError::from(error.to_owned()),
+                            source: Box::new(Event::WatchPartyMessagesReceived {
+                                party_id: party_id.to_owned(),
+                            }),
+                        }))
+                        .unchanged()
+                    }
+                    _ => Effects::none().unchanged(),
+                }
+            }
+            _ => Effects::none().unchanged(),
+        }
+    }
+}
+
+impl WatchParty {
+    fn action_update<E: Env + 'static>(
+        &mut self,
+        selected: &Selected,
+        action: &ActionWatchParty,
+    ) -> Effects {
+        match action {
+            ActionWatchParty::PausedChanged { paused, time } => {
+                let playback_effects = self.playback_update::<E>(*paused, *time);
+                let followed_correction = matches!(
+                    (&self.correction, paused),
+                    (Some(WatchPartyCorrection::Play { .. }), false)
+                        | (Some(WatchPartyCorrection::Pause { .. }), true)
+                );
+                if followed_correction {
+                    // the player followed the party, do not echo it back
+                    playback_effects.join(eq_update(&mut self.correction, None))
+                } else {
+                    let payload = if *paused {
+                        WatchPartyPayload::Pause { time: *time }
+                    } else {
+                        WatchPartyPayload::Play { time: *time }
+                    };
+                    playback_effects.join(Effects::one(send::<E>(selected, payload)).unchanged())
+                }
+            }
+            ActionWatchParty::Seek { time } => {
+                let paused = self
+                    .playback
+                    .as_ref()
+                    .map(|playback| playback.paused)
+                    .unwrap_or_default();
+                let playback_effects = self.playback_update::<E>(paused, *time);
+                match self.correction {
+                    Some(WatchPartyCorrection::Seek { time: target })
+                        if time.abs_diff(target) <= WATCH_PARTY_DRIFT_TOLERANCE =>
+                    {
+                        playback_effects.join(eq_update(&mut self.correction, None))
+                    }
+                    _ => playback_effects.join(
+                        Effects::one(send::<E>(selected, WatchPartyPayload::Seek { time: *time }))
+                            .unchanged(),
+                    ),
+                }
+            }
+            ActionWatchParty::TimeChanged { time } => {
+                let paused = self
+                    .playback
+                    .as_ref()
+                    .map(|playback| playback.paused)
+                    .unwrap_or_default();
+                let playback_effects = self.playback_update::<E>(paused, *time);
+                let now = E::now();
+                let state_due = self
+                    .state_sent_at
+                    .map(|state_sent_at| now - state_sent_at >= WATCH_PARTY_STATE_INTERVAL)
+                    .unwrap_or(true);
+                let state_effects = if selected.host && state_due {
+                    self.state_sent_at = Some(now);
+                    Effects::one(send::<E>(
+                        selected,
+                        WatchPartyPayload::State {
+                            paused,
+                            time: *time,
+                        },
+                    ))
+                    .unchanged()
+                } else {
+                    Effects::none().unchanged()
+                };
+                playback_effects
+                    .join(state_effects)
+                    .join(Effects::one(receive::<E>(selected)).unchanged())
+            }
+            ActionWatchParty::SelectStream(stream) => {
+                let stream_effects = eq_update(&mut self.stream, Some(*stream.to_owned()));
+                let correction_effects = eq_update(&mut self.correction, None);
+                let playback_effects = eq_update(&mut self.playback, None);
+                stream_effects
+                    .join(correction_effects)
+                    .join(playback_effects)
+                    .join(
+                        Effects::one(send::<E>(
+                            selected,
+                            WatchPartyPayload::SelectStream {
+                                stream: stream.stream.to_owned(),
+                                stream_request: stream.stream_request.to_owned(),
+                                meta_request: stream.meta_request.to_owned(),
+                            },
+                        ))
+                        .unchanged(),
+                    )
+            }
+            ActionWatchParty::Poll => Effects::one(receive::<E>(selected)).unchanged(),
+            ActionWatchParty::SyncClock => {
+                Effects::one(send::<E>(selected, WatchPartyPayload::Ping)).unchanged()
+            }
+        }
+    }
+    fn message_update<E: Env + 'static>(
+        &mut self,
+        selected: &Selected,
+        message: &WatchPartyMessage,
+    ) -> Effects {
+        let now = E::now();
+        let members_effects = match &message.payload {
+            WatchPartyPayload::Leave => {
+                let members_count = self.members.len();
+                self.members.retain(|member| member.id != message.member_id);
+                if self.members.len() != members_count {
+                    Effects::none()
+                } else {
+                    Effects::none().unchanged()
+                }
+            }
+            _ => {
+                match self
+                    .members
+                    .iter_mut()
+                    .find(|member| member.id == message.member_id)
+                {
+                    Some(member) => member.last_seen = now,
+                    None => self.members.push(WatchPartyMember {
+                        id: message.member_id.to_owned(),
+                        clock_offset: None,
+                        last_seen: now,
+                    }),
+                };
+                Effects::none()
+            }
+        };
+        // the position of the sender's player right now
+        let elapsed = elapsed(message.sent_at, now + self.clock_offset(&message.member_id));
+        let message_effects = match &message.payload {
+            WatchPartyPayload::Join if selected.host => {
+                // the stream is sent first as selecting it resets the playback
+                let stream_effects = match &self.stream {
+                    Some(stream) => Effects::one(send::<E>(
+                        selected,
+                        WatchPartyPayload::SelectStream {
+                            stream: stream.stream.to_owned(),
+                            stream_request: stream.stream_request.to_owned(),
+                            meta_request: stream.meta_request.to_owned(),
+                        },
+                    ))
+                    .unchanged(),
+                    None => Effects::none().unchanged(),
+                };
+                let state_effects = match &self.playback {
+                    Some(playback) => Effects::one(send::<E>(
+                        selected,
+                        WatchPartyPayload::State {
+                            paused: playback.paused,
+                            time: playback.time_at(now),
+                        },
+                    ))
+                    .unchanged(),
+                    None => Effects::none().unchanged(),
+                };
+                stream_effects.join(state_effects)
+            }
+            WatchPartyPayload::Ping => Effects::one(send::<E>(
+                selected,
+                WatchPartyPayload::Pong {
+                    to: message.member_id.to_owned(),
+                    ping_sent_at: message.sent_at,
+                },
+            ))
+            .unchanged(),
+            WatchPartyPayload::Pong { to, ping_sent_at } if *to == selected.member_id => {
+                // assuming the same latency in both directions
+                let round_trip = (now - *ping_sent_at).num_milliseconds();
+                let clock_offset =
+                    (message.sent_at - *ping_sent_at).num_milliseconds() - round_trip / 2;
+                match self
+                    .members
+                    .iter_mut()
+                    .find(|member| member.id == message.member_id)
+                {
+                    Some(member) => {
+                        member.clock_offset = Some(clock_offset);
+                        Effects::none()
+                    }
+                    None => Effects::none().unchanged(),
+                }
+            }
+            WatchPartyPayload::Play { time } => eq_update(
+                &mut self.correction,
+                Some(WatchPartyCorrection::Play {
+                    time: time + elapsed,
+                }),
+            ),
+            WatchPartyPayload::Pause { time } => eq_update(
+                &mut self.correction,
+                Some(WatchPartyCorrection::Pause { time: *time }),
+            ),
+            WatchPartyPayload::Seek { time } => {
+                let paused = self
+                    .playback
+                    .as_ref()
+                    .map(|playback| playback.paused)
+                    .unwrap_or_default();
+                let time = if paused { *time } else { time + elapsed };
+                eq_update(
+                    &mut self.correction,
+                    Some(WatchPartyCorrection::Seek { time }),
+                )
+            }
+            WatchPartyPayload::State { paused, time } => {
+                let time = if *paused { *time } else { time + elapsed };
+                let correction = match &self.playback {
+                    Some(playback) if playback.paused == *paused => {
+                        drift_correction(playback.time_at(now), time, *paused)
+                    }
+                    _ if *paused => Some(WatchPartyCorrection::Pause { time }),
+                    _ => Some(WatchPartyCorrection::Play { time }),
+                };
+                eq_update(&mut self.correction, correction)
+            }
+            WatchPartyPayload::SelectStream {
+                stream,
+                stream_request,
+                meta_request,
+            } => {
+                let stream_effects = eq_update(
+                    &mut self.stream,
+                    Some(PlayerSelected {
+                        stream: stream.to_owned(),
+                        stream_request: stream_request.to_owned(),
+                        meta_request: meta_request.to_owned(),
+                        subtitles_path: None,
+                    }),
+                );
+                let correction_effects = eq_update(&mut self.correction, None);
+                let playback_effects = eq_update(&mut self.playback, None);
+                stream_effects
+                    .join(correction_effects)
+                    .join(playback_effects)
+            }
+            _ => Effects::none().unchanged(),
+        };
+        members_effects.join(message_effects)
+    }
+    fn playback_update<E: Env + 'static>(&mut self, paused: bool, time: u64) -> Effects {
+        eq_update(
+            &mut self.playback,
+            Some(WatchPartyPlayback {
+                paused,
+                time,
+                updated_at: E::now(),
+            }),
+        )
+    }
+    fn clock_offset(&self, member_id: &str) -> chrono::Duration {
+        self.members
+            .iter()
+            .find(|member| member.id == member_id)
+            .and_then(|member| member.clock_offset)
+            .map(chrono::Duration::milliseconds)
+            .unwrap_or_else(chrono::Duration::zero)
+    }
+}
+
+/// Small drifts are corrected by changing the playback rate
+/// and the larger ones by seeking to the position of the party.
+fn drift_correction(
+    local_time: u64,
+    party_time: u64,
+    paused: bool,
+) -> Option<WatchPartyCorrection> {
+    let drift = local_time.abs_diff(party_time);
+    if drift <= WATCH_PARTY_DRIFT_TOLERANCE {
+        None
+    } else if drift <= WATCH_PARTY_SEEK_THRESHOLD && !paused {
+        let rate = if local_time > party_time {
+            1.0 - WATCH_PARTY_PLAYBACK_RATE_ADJUSTMENT
+        } else {
+            1.0 + WATCH_PARTY_PLAYBACK_RATE_ADJUSTMENT
+        };
+        Some(WatchPartyCorrection::PlaybackRate { rate })
+    } else {
+        Some(WatchPartyCorrection::Seek { time: party_time })
+    }
+}
+
+/// The milliseconds elapsed between the two moments, zero if `to` is before `from`.
+fn elapsed(from: DateTime<Utc>, to: DateTime<Utc>) -> u64 {
+    (to - from).num_milliseconds().max(0) as u64
+}
+
+fn send<E: Env + 'static>(selected: &Selected, payload: WatchPartyPayload) -> Effect {
+    let party_id = selected.party_id.to_owned();
+    let message = WatchPartyMessage {
+        party_id: party_id.to_owned(),
+        member_id: selected.member_id.to_owned(),
+        sent_at: E::now(),
+        payload,
+    };
+    EffectFuture::Concurrent(
+        E::watch_party_transport(&selected.party_id, &selected.member_id)
+            .send(&message)
+            .map(move |result| match result {
+                Ok(_) => Msg::Event(Event::WatchPartyMessageSent { party_id }),
+                Err(error) => Msg::Event(Event::Error {
+                    error: CtxError::from(error),
+                    source: Box::new(Event::WatchPartyMessageSent { party_id }),
+                }),
+            })
+            .boxed_env(),
+    )
+    .into()
+}
+
+fn receive<E: Env + 'static>(selected: &Selected) -> Effect {
+    let party_id = selected.party_id.to_owned();
+    EffectFuture::Concurrent(
+        E::watch_party_transport(&selected.party_id, &selected.member_id)
+            .receive()
+            .map(move |result| Msg::Internal(Internal::WatchPartyReceiveResult(party_id, result)))
+            .boxed_env(),
+    )
+    .into()
+}
@@ -0,0 +1,436 @@
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use chrono::{DateTime, Datelike, NaiveDate, Utc, Weekday};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::constants::{META_RESOURCE_NAME, WATCH_STATS_REWATCHED_COUNT, WATCH_STATS_SERIES_COUNT};
+use crate::models::calendar::{Month, Year, YearMonthDate};
+use crate::models::common::{
+    eq_update, resource_update, resources_cancel_effects, Loadable, ResourceAction,
+    ResourceLoadable,
+};
+use crate::models::ctx::Ctx;
+use crate::runtime::msg::{Action, ActionLoad, Internal, Msg};
+use crate::runtime::{Effects, Env, UpdateWithCtx};
+use crate::types::addon::{AggrRequest, ResourcePath};
+use crate::types::library::{LibraryBucket, LibraryItem, LibraryItemId};
+use crate::types::resource::{MetaItem, PosterShape};
+use crate::types::streams::StreamsBucket;
+use crate::types::taste::TasteBucket;
+
+/// The period of the statistics, by the last time the items were watched.
+///
+/// The library keeps only the lifetime totals of the items, so an item is counted
+/// in the period of its last watch with all of its totals, see [`WatchTotals`].
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+pub enum StatsPeriod {
+    AllTime,
+    /// The year in review
+    Year(Year),
+    Month(YearMonthDate),
+}
+
+impl StatsPeriod {
+    fn contains(&self, date: &DateTime<Utc>) -> bool {
+        match self {
+            StatsPeriod::AllTime => true,
+            StatsPeriod::Year(year) => date.year() == *year,
+            StatsPeriod::Month(YearMonthDate { month, year }) => {
+                date.year() == *year && date.month() == *month
+            }
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+pub struct Selected {
+    pub period: StatsPeriod,
+}
+
+/// The totals of the watched items.
+///
+/// The times and the time watched are the lifetime totals of the items,
+/// not only the watches within the [`StatsPeriod`].
+#[derive(Default, Clone, PartialEq, Eq, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchTotals {
+    /// The watched items
+    pub items: usize,
+    /// The watched videos of the series and the watches of the other items, of all time
+    pub times_watched: u64,
+    /// In milliseconds, of all time
+    pub time_watched: u64,
+}
+
+impl WatchTotals {
+    fn add(&mut self, library_item: &LibraryItem) {
+        self.items += 1;
+        self.times_watched += library_item.state.times_watched as u64;
+        self.time_watched += library_item.state.overall_time_watched;
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct WeekdayTotals {
+    pub weekday: Weekday,
+    pub totals: WatchTotals,
+}
+
+#[derive(Clone, PartialEq, Eq, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct MonthTotals {
+    pub year: Year,
+    pub month: Month,
+    pub totals: WatchTotals,
+}
+
+/// The consecutive days with something watched
+#[derive(Default, Clone, PartialEq, Eq, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchStreaks {
+    /// Ending today, or yesterday when nothing is watched today yet
+    pub current: u32,
+    pub longest: u32,
+    /// The last day of the longest streak
+    pub longest_end: Option<NaiveDate>,
+}
+
+#[derive(Clone, PartialEq, Eq, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RewatchedItem {
+    pub id: LibraryItemId,
+    pub name: String,
+    pub r#type: String,
+    pub poster: Option<Url>,
+    pub poster_shape: PosterShape,
+    /// The watches after the first one, for a series the watches of the already watched videos
+    pub rewatches: u32,
+}
+
+#[derive(Clone, PartialEq, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SeriesCompletion {
+    pub id: LibraryItemId,
+    pub name: String,
+    pub poster: Option<Url>,
+    /// The watched episodes, the specials are not counted
+    pub watched: usize,
+    pub total: usize,
+    /// From 0 to 100
+    pub percent: f64,
+}
+
+#[derive(Default, Clone, PartialEq, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchStatsReport {
+    pub totals: WatchTotals,
+    pub by_type: BTreeMap<String, WatchTotals>,
+    /// The genres are known for the items watched or rated since the recommendations are kept
+    pub by_genre: BTreeMap<String, WatchTotals>,
+    /// By the weekday of the last watch of the items, starting from Monday
+    pub by_weekday: Vec<WeekdayTotals>,
+    /// By the month of the last watch of the items, in chronological order,
+    /// only the months with something watched
+    pub by_month: Vec<MonthTotals>,
+    pub streaks: WatchStreaks,
+    pub most_rewatched: Vec<RewatchedItem>,
+    /// Known once the videos of the series are loaded, only for the most recently
+    /// watched series, the most completed first
+    pub series_completion: Vec<SeriesCompletion>,
+}
+
+/// Aggregates the watch history of the library for a statistics screen
+#[derive(Default, Clone, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchStats {
+    pub selected: Option<Selected>,
+    /// The most recently watched series, whose videos are needed for their completion
+    pub meta_items: Vec<ResourceLoadable<MetaItem>>,
+    pub report: Option<WatchStatsReport>,
+}
+
+impl<E: Env + 'static> UpdateWithCtx<E> for WatchStats {
+    fn update(&mut self, msg: &Msg, ctx: &Ctx) -> Effects {
+        match msg {
+            Msg::Action(Action::Load(ActionLoad::WatchStats(selected))) => {
+                let cancel_effects = resources_cancel_effects(&self.meta_items);
+                let selected_effects = eq_update(&mut self.selected, Some(selected.to_owned()));
+                let meta_items_effects =
+                    meta_items_update::<E>(&mut self.meta_items, &selected.period, ctx);
+                let report_effects = report_update::<E>(self, ctx);
+                cancel_effects
+                    .join(selected_effects)
+                    .join(meta_items_effects)
+                    .join(report_effects)
+            }
+            Msg::Action(Action::Unload) => {
+                let cancel_effects = resources_cancel_effects(&self.meta_items);
+                let selected_effects = eq_update(&mut self.selected, None);
+                let meta_items_effects = eq_update(&mut self.meta_items, vec![]);
+                let report_effects = eq_update(&mut self.report, None);
+                cancel_effects
+                    .join(selected_effects)
+                    .join(meta_items_effects)
+                    .join(report_effects)
+            }
+            Msg::Internal(Internal::ResourceRequestResult(request, result)) => {
+                match self
+                    .meta_items
+                    .iter_mut()
+                    .find(|meta_item| meta_item.request == *request)
+                {
+                    Some(meta_item) => resource_update::<E, _>(
+                        meta_item,
+                        ResourceAction::ResourceRequestResult { request, result },
+                    )
+                    .join(report_update::<E>(self, ctx)),
+                    None => Effects::none().unchanged(),
+                }
+            }
+            Msg::Internal(Internal::LibraryChanged(_))
+            | Msg::Internal(Internal::StreamsChanged(_))
+            | Msg::Internal(Internal::TasteChanged) => report_update::<E>(self, ctx),
+            _ => Effects::none().unchanged(),
+        }
+    }
+}
+
+/// Requests the meta item of the most recently watched series from the first addon which provides it
+fn meta_items_update<E: Env + 'static>(
+    meta_items: &mut Vec<ResourceLoadable<MetaItem>>,
+    period: &StatsPeriod,
+    ctx: &Ctx,
+) -> Effects {
+    let mut watched_series = watched_items(&ctx.library, period)
+        .filter(|library_item| {
+            library_item.r#type == "series" && library_item.state.watched.is_some()
+        })
+        .collect::<Vec<_>>();
+    watched_series.sort_by_key(|library_item| Reverse(library_item.state.last_watched));
+    let mut next_meta_items = watched_series
+        .into_iter()
+        .filter_map(|library_item| {
+            AggrRequest::AllOfResource(ResourcePath::without_extra(
+                META_RESOURCE_NAME,
+                &library_item.r#type,
+                &library_item.id,
+            ))
+            .plan(&ctx.profile.addons)
+            .into_iter()
+            .map(|(_, request)| ResourceLoadable {
+                request,
+                content: None,
+            })
+            .next()
+        })
+        .take(WATCH_STATS_SERIES_COUNT)
+        .collect::<Vec<_>>();
+    let request_effects = next_meta_items
+        .iter_mut()
+        .map(|meta_item| {
+            let request = meta_item.request.to_owned();
+            resource_update::<E, _>(
+                meta_item,
+                ResourceAction::ResourceRequested { request: &request },
+            )
+        })
+        .fold(Effects::none().unchanged(), Effects::join)
+        .unchanged();
+    request_effects.join(eq_update(meta_items, next_meta_items))
+}
+
+fn report_update<E: Env + 'static>(watch_stats: &mut WatchStats, ctx: &Ctx) -> Effects {
+    let next_report = watch_stats.selected.as_ref().map(|selected| {
+        report(
+            &selected.period,
+            &ctx.library,
+            &ctx.streams,
+            &ctx.taste,
+            &watch_stats.meta_items,
+            E::now().date_naive(),
+        )
+    });
+    eq_update(&mut watch_stats.report, next_report)
+}
+
+/// The items watched in the period, the removed ones included as they are still part of the history
+fn watched_items<'a>(
+    library: &'a LibraryBucket,
+    period: &'a StatsPeriod,
+) -> impl Iterator<Item = &'a LibraryItem> + 'a {
+    library.items.values().filter(move |library_item| {
+        library_item.r#type != "other"
+            && (library_item.watched() || library_item.state.overall_time_watched > 0)
+            && match library_item.state.last_watched {
+                Some(last_watched) => period.contains(&last_watched),
+                None => *period == StatsPeriod::AllTime,
+            }
+    })
+}
+
+fn report(
+    period: &StatsPeriod,
+    library: &LibraryBucket,
+    streams: &StreamsBucket,
+    taste: &TasteBucket,
+    meta_items: &[ResourceLoadable<MetaItem>],
+    today: NaiveDate,
+) -> WatchStatsReport {
+    let mut report = WatchStatsReport {
+        by_weekday: [
+            Weekday::Mon,
+            Weekday::Tue,
+            Weekday::Wed,
+            Weekday::Thu,
+            Weekday::Fri,
+            Weekday::Sat,
+            Weekday::Sun,
+        ]
+        .into_iter()
+        .map(|weekday| WeekdayTotals {
+            weekday,
+            totals: WatchTotals::default(),
+        })
+        .collect(),
+        ..Default::default()
+    };
+    let mut by_month = BTreeMap::<(Year, Month), WatchTotals>::new();
+    let mut watch_days = BTreeSet::new();
+    let mut rewatched = vec![];
+    let meta_items = meta_items
+        .iter()
+        .filter_map(|meta_item| match &meta_item.content {
+            Some(Loadable::Ready(meta_item)) => Some((meta_item.preview.id.as_str(), meta_item)),
+            _ => None,
+        })
+        .collect::<HashMap<_, _>>();
+    for library_item in watched_items(library, period) {
+        report.totals.add(library_item);
+        report
+            .by_type
+            .entry(library_item.r#type.to_owned())
+            .or_default()
+            .add(library_item);
+        if let Some(taste_item) = taste.items.get(&library_item.id) {
+            for genre in &taste_item.genres {
+                report
+                    .by_genre
+                    .entry(genre.to_owned())
+                    .or_default()
+                    .add(library_item);
+            }
+        }
+        if let Some(last_watched) = library_item.state.last_watched {
+            let weekday = last_watched.weekday().num_days_from_monday() as usize;
+            report.by_weekday[weekday].totals.add(library_item);
+            by_month
+                .entry((last_watched.year(), last_watched.month()))
+                .or_default()
+                .add(library_item);
+            watch_days.insert(last_watched.date_naive());
+        }
+        let completion = meta_items
+            .get(library_item.id.as_str())
+            .and_then(|meta_item| series_completion(library_item, meta_item));
+        let rewatches = match (&completion, library_item.r#type.as_str()) {
+            (Some(completion), _) => library_item
+                .state
+                .times_watched
+                .saturating_sub(completion.watched as u32),
+            (None, "series") => 0,
+            (None, _) => library_item.state.times_watched.saturating_sub(1),
+        };
+        if rewatches > 0 {
+            rewatched.push(RewatchedItem {
+                id: library_item.id.to_owned(),
+                name: library_item.name.to_owned(),
+                r#type: library_item.r#type.to_owned(),
+                poster: library_item.poster.to_owned(),
+                poster_shape: library_item.poster_shape.to_owned(),
+                rewatches,
+            });
+        }
+        report.series_completion.extend(completion);
+    }
+    // the streams are kept for every played video, so they fill in the days between the last watches
+    watch_days.extend(
+        streams
+            .items
+            .values()
+            .filter(|stream_item| period.contains(&stream_item.mtime))
+            .map(|stream_item| stream_item.mtime.date_naive()),
+    );
+    report.by_month = by_month
+        .into_iter()
+        .map(|((year, month), totals)| MonthTotals {
+            year,
+            month,
+            totals,
+        })
+        .collect();
+    report.streaks = streaks(&watch_days, today);
+    rewatched.sort_by(|a, b| b.rewatches.cmp(&a.rewatches).then(a.name.cmp(&b.name)));
+    rewatched.truncate(WATCH_STATS_REWATCHED_COUNT);
+    report.most_rewatched = rewatched;
+    report.series_completion.sort_by(|a, b| {
+        b.percent
+            .total_cmp(&a.percent)
+            .then(b.watched.cmp(&a.watched))
+            .then(a.name.cmp(&b.name))
+    });
+    report
+}
+
+fn series_completion(library_item: &LibraryItem, meta_item: &MetaItem) -> Option<SeriesCompletion> {
+    let episodes = meta_item
+        .videos
+        .iter()
+        .filter(|video| {
+            video
+                .series_info
+                .as_ref()
+                .is_some_and(|series_info| series_info.season != 0)
+        })
+        .collect::<Vec<_>>();
+    if episodes.is_empty() {
+        return None;
+    }
+    let watched_bitfield = library_item.state.watched_bitfield(&meta_item.videos);
+    let watched = episodes
+        .iter()
+        .filter(|video| watched_bitfield.get_video(&video.id))
+        .count();
+    Some(SeriesCompletion {
+        id: library_item.id.to_owned(),
+        name: library_item.name.to_owned(),
+        poster: library_item.poster.to_owned(),
+        watched,
+        total: episodes.len(),
+        percent: watched as f64 * 100.0 / episodes.len() as f64,
+    })
+}
+
+fn streaks(watch_days: &BTreeSet<NaiveDate>, today: NaiveDate) -> WatchStreaks {
+    let mut streaks = WatchStreaks::default();
+    let mut streak = 0;
+    let mut previous_day: Option<NaiveDate> = None;
+    for day in watch_days {
+        streak = match previous_day {
+            Some(previous_day) if previous_day.succ_opt() == Some(*day) => streak + 1,
+            _ => 1,
+        };
+        if streak > streaks.longest {
+            streaks.longest = streak;
+            streaks.longest_end = Some(*day);
+        }
+        previous_day = Some(*day);
+    }
+    streaks.current = match previous_day {
+        Some(last_day) if last_day == today || last_day.succ_opt() == Some(today) => streak,
+        _ => 0,
+    };
+    streaks
+}
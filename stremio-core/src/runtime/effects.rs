@@ -1,4 +1,5 @@
 use crate::runtime::msg::Msg;
+use crate::types::addon_health::AddonRequest;
 use derive_more::{From, IntoIterator};
 
 use super::EnvFuture;
@@ -8,12 +9,24 @@ type Future = EnvFuture<'static, Msg>;
 pub enum EffectFuture {
     Concurrent(Future),
     Sequential(Future),
+    /// A concurrent addon request which can be cancelled with [`Effect::Cancel`].
+    ///
+    /// A cancelled request resolves with [`Internal::AddonRequestCancelled`].
+    ///
+    /// [`Internal::AddonRequestCancelled`]: crate::runtime::msg::Internal::AddonRequestCancelled
+    Cancellable(AddonRequest, Future),
 }
 
 #[derive(From)]
 pub enum Effect {
     Msg(Box<Msg>),
     Future(EffectFuture),
+    /// Cancels an in-flight [`EffectFuture::Cancellable`] addon request started by the model,
+    /// it's aborted only when all the models which started it have cancelled it.
+    ///
+    /// Does nothing if the request is already completed.
+    #[from(ignore)]
+    Cancel(AddonRequest),
 }
 
 /// # Examples
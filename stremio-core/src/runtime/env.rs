@@ -1,16 +1,20 @@
-use crate::addon_transport::{AddonHTTPTransport, AddonTransport, UnsupportedTransport};
+use crate::addon_transport::{AddonRequestPolicy, AddonTransport, AddonTransportRegistry};
 use crate::constants::{
-    DISMISSED_EVENTS_STORAGE_KEY, LIBRARY_RECENT_STORAGE_KEY, LIBRARY_STORAGE_KEY,
+    DEFAULT_LOCAL_PROFILE_ID, DEFAULT_LOCAL_PROFILE_NAME, DISMISSED_EVENTS_STORAGE_KEY,
+    LIBRARY_RECENT_STORAGE_KEY, LIBRARY_STORAGE_KEY, LOCAL_PROFILES_STORAGE_KEY,
     PROFILE_STORAGE_KEY, SCHEMA_VERSION, SCHEMA_VERSION_STORAGE_KEY, SEARCH_HISTORY_STORAGE_KEY,
     STREAMING_SERVER_URLS_STORAGE_KEY, STREAMS_STORAGE_KEY,
 };
 use crate::models::ctx::Ctx;
 use crate::models::streaming_server::StreamingServer;
+use crate::types::addon::ResourcePath;
+use crate::watch_party_transport::{UnsupportedWatchPartyTransport, WatchPartyTransport};
 use chrono::{DateTime, Utc};
 use futures::{future, Future, TryFutureExt};
 use http::Request;
 use serde::ser::SerializeStruct;
 use serde::{Deserialize, Serialize, Serializer};
+use std::collections::HashMap;
 use std::fmt;
 use url::Url;
 
@@ -28,6 +32,11 @@ pub enum EnvError {
     StorageSchemaVersionUpgrade(Box<EnvError>),
     StorageReadError(String),
     StorageWriteError(String),
+    WatchPartyTransport(String),
+    /// The request was not completed in the given milliseconds
+    Timeout(u64),
+    /// The feature is not implemented by the [`Env`]
+    Unsupported(String),
     Other(String),
 }
 
@@ -47,6 +56,11 @@ impl EnvError {
             ),
             EnvError::StorageReadError(message) => format!("Storage read error: {message}"),
             EnvError::StorageWriteError(message) => format!("Storage write error: {message}"),
+            EnvError::WatchPartyTransport(message) => {
+                format!("Watch party transport error: {message}")
+            }
+            EnvError::Timeout(timeout) => format!("Request timed out after {timeout}ms"),
+            EnvError::Unsupported(feature) => format!("{feature} is not supported"),
             EnvError::Other(message) => format!("Other error: {message}"),
         }
     }
@@ -60,6 +74,9 @@ impl EnvError {
             EnvError::StorageSchemaVersionUpgrade(_) => 6,
             EnvError::StorageReadError(_) => 7,
             EnvError::StorageWriteError(_) => 8,
+            EnvError::WatchPartyTransport(_) => 9,
+            EnvError::Timeout(_) => 10,
+            EnvError::Unsupported(_) => 11,
             EnvError::Other(_) => 1001,
         }
     }
@@ -89,6 +106,23 @@ impl From<serde_json::Error> for EnvError {
     }
 }
 
+/// The status and the headers of a response, see [`Env::fetch_head`].
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+pub struct ResponseHead {
+    pub status: u16,
+    /// The header names are in lowercase
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+}
+
+impl ResponseHead {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .get(&name.to_lowercase())
+            .map(|value| value.as_str())
+    }
+}
+
 #[cfg(not(feature = "env-future-send"))]
 /// Only for wasm or when `env-future-send` is not enabled
 mod conditional_types {
@@ -159,14 +193,86 @@ pub trait Env {
     ) -> serde_json::Value;
     #[cfg(debug_assertions)]
     fn log(message: String);
+    /// The addon transports supported by the environment, by URL scheme.
+    ///
+    /// Defaults to the `http` and `https` schemes, override it to support
+    /// other transports, e.g. addons compiled into the app.
+    /// The registry is requested for every addon request,
+    /// keep it in a static if it's expensive to build.
+    fn addon_transports() -> AddonTransportRegistry
+    where
+        Self: Sized + 'static,
+    {
+        AddonTransportRegistry::new::<Self>()
+    }
     fn addon_transport(transport_url: &Url) -> Box<dyn AddonTransport>
     where
         Self: Sized + 'static,
     {
-        match transport_url.scheme() {
-            "http" | "https" => Box::new(AddonHTTPTransport::<Self>::new(transport_url.to_owned())),
-            _ => Box::new(UnsupportedTransport::new(transport_url.to_owned())),
-        }
+        Self::addon_transports().transport(transport_url)
+    }
+    /// The timeout and the hedging of the resource requests to the addons.
+    ///
+    /// Defaults to [`AddonRequestPolicy::new`], override it to configure them per resource.
+    fn addon_request_policy(path: &ResourcePath) -> AddonRequestPolicy
+    where
+        Self: Sized + 'static,
+    {
+        AddonRequestPolicy::new(path)
+    }
+    /// A future resolved after the given duration.
+    ///
+    /// Timers are not supported by default and the future fails with [`EnvError::Unsupported`],
+    /// override it for enforcing the [`AddonRequestPolicy`].
+    fn sleep(_duration: chrono::Duration) -> TryEnvFuture<()>
+    where
+        Self: Sized + 'static,
+    {
+        future::err(EnvError::Unsupported("Sleeping".to_owned())).boxed_env()
+    }
+    /// The transport used by the [`WatchParty`] to exchange messages
+    /// with the other members of the party.
+    ///
+    /// Watch parties are not supported by default, override it to use a relay.
+    ///
+    /// [`WatchParty`]: crate::models::watch_party::WatchParty
+    fn watch_party_transport(party_id: &str, member_id: &str) -> Box<dyn WatchPartyTransport>
+    where
+        Self: Sized + 'static,
+    {
+        Box::new(UnsupportedWatchPartyTransport::new(party_id, member_id))
+    }
+    /// Requests only the status and the headers of the response, without reading the body,
+    /// e.g. a `HEAD` request or a `GET` request with a `Range` header.
+    ///
+    /// Not supported by default, the stream probes fail with [`EnvError::Unsupported`].
+    fn fetch_head(_request: Request<()>) -> TryEnvFuture<ResponseHead>
+    where
+        Self: Sized + 'static,
+    {
+        future::err(EnvError::Unsupported(
+            "Fetching the response head".to_owned(),
+        ))
+        .boxed_env()
+    }
+    /// Requests the raw body of the response, e.g. a subtitles file.
+    ///
+    /// Not supported by default as [`Env::fetch`] deserializes the body as JSON,
+    /// the requests fail with [`EnvError::Unsupported`].
+    fn fetch_bytes(_request: Request<()>) -> TryEnvFuture<Vec<u8>>
+    where
+        Self: Sized + 'static,
+    {
+        future::err(EnvError::Unsupported(
+            "Fetching the response body".to_owned(),
+        ))
+        .boxed_env()
+    }
+    /// Cryptographically secure random bytes, e.g. for the ids and the salts.
+    ///
+    /// Uses the random number generator of the OS by default.
+    fn random_buffer(len: usize) -> Vec<u8> {
+        crate::types::random_buffer(len)
     }
     fn migrate_storage_schema() -> TryEnvFuture<()>
     where
@@ -301,6 +407,30 @@ pub trait Env {
                         .await?;
                     schema_version = 20;
                 }
+                if schema_version == 20 {
+                    migrate_storage_schema_to_v21::<Self>()
+                        .map_err(|error| EnvError::StorageSchemaVersionUpgrade(Box::new(error)))
+                        .await?;
+                    schema_version = 21;
+                }
+                if schema_version == 21 {
+                    migrate_storage_schema_to_v22::<Self>()
+                        .map_err(|error| EnvError::StorageSchemaVersionUpgrade(Box::new(error)))
+                        .await?;
+                    schema_version = 22;
+                }
+                if schema_version == 22 {
+                    migrate_storage_schema_to_v23::<Self>()
+                        .map_err(|error| EnvError::StorageSchemaVersionUpgrade(Box::new(error)))
+                        .await?;
+                    schema_version = 23;
+                }
+                if schema_version == 23 {
+                    migrate_storage_schema_to_v24::<Self>()
+                        .map_err(|error| EnvError::StorageSchemaVersionUpgrade(Box::new(error)))
+                        .await?;
+                    schema_version = 24;
+                }
                 if schema_version != SCHEMA_VERSION {
                     panic!(
                         "Storage schema version must be upgraded from {} to {}",
@@ -740,13 +870,146 @@ fn migrate_storage_schema_to_v20<E: Env>() -> TryEnvFuture<()> {
         .boxed_env()
 }
 
+fn migrate_storage_schema_to_v21<E: Env>() -> TryEnvFuture<()> {
+    E::get_storage::<serde_json::Value>(PROFILE_STORAGE_KEY)
+        .and_then(|mut profile| {
+            match profile
+                .as_mut()
+                .and_then(|profile| profile.as_object_mut())
+                .and_then(|profile| profile.get_mut("settings"))
+                .and_then(|settings| settings.as_object_mut())
+            {
+                Some(settings) => {
+                    settings.insert(
+                        "streamPreferences".to_owned(),
+                        serde_json::json!({
+                            "maxResolution": null,
+                            "preferHdr": false,
+                            "preferredCodecs": [],
+                            "maxSize": null,
+                            "preferredLanguages": [],
+                        }),
+                    );
+                    E::set_storage(PROFILE_STORAGE_KEY, Some(&profile))
+                }
+                _ => E::set_storage::<()>(PROFILE_STORAGE_KEY, None),
+            }
+        })
+        .and_then(|_| E::set_storage(SCHEMA_VERSION_STORAGE_KEY, Some(&21)))
+        .boxed_env()
+}
+
+fn migrate_storage_schema_to_v22<E: Env>() -> TryEnvFuture<()> {
+    E::get_storage::<serde_json::Value>(PROFILE_STORAGE_KEY)
+        .and_then(|mut profile| {
+            match profile
+                .as_mut()
+                .and_then(|profile| profile.as_object_mut())
+                .and_then(|profile| profile.get_mut("settings"))
+                .and_then(|settings| settings.as_object_mut())
+            {
+                Some(settings) => {
+                    settings.insert(
+                        "autoPlayPolicy".to_owned(),
+                        serde_json::Value::String("StrictBingeGroup".to_owned()),
+                    );
+                    E::set_storage(PROFILE_STORAGE_KEY, Some(&profile))
+                }
+                _ => E::set_storage::<()>(PROFILE_STORAGE_KEY, None),
+            }
+        })
+        .and_then(|_| E::set_storage(SCHEMA_VERSION_STORAGE_KEY, Some(&22)))
+        .boxed_env()
+}
+
+/// Creates the default local profile with the settings of the profile.
+///
+/// The default local profile keeps using the existing storage keys.
+fn migrate_storage_schema_to_v23<E: Env>() -> TryEnvFuture<()> {
+    E::get_storage::<serde_json::Value>(PROFILE_STORAGE_KEY)
+        .and_then(
+            |profile| match profile.as_ref().and_then(|profile| profile.as_object()) {
+                Some(profile) => {
+                    let uid = profile
+                        .get("auth")
+                        .and_then(|auth| auth.get("user"))
+                        .and_then(|user| user.get("_id"))
+                        .cloned()
+                        .unwrap_or_default();
+                    let settings = profile.get("settings").cloned().unwrap_or_default();
+                    let local_profiles = serde_json::json!({
+                        "uid": uid,
+                        "selected": DEFAULT_LOCAL_PROFILE_ID,
+                        "profiles": [
+                            {
+                                "id": DEFAULT_LOCAL_PROFILE_ID,
+                                "name": DEFAULT_LOCAL_PROFILE_NAME,
+                                "settings": settings,
+                            }
+                        ],
+                    });
+                    E::set_storage(LOCAL_PROFILES_STORAGE_KEY, Some(&local_profiles))
+                }
+                _ => E::set_storage::<()>(LOCAL_PROFILES_STORAGE_KEY, None),
+            },
+        )
+        .and_then(|_| E::set_storage(SCHEMA_VERSION_STORAGE_KEY, Some(&23)))
+        .boxed_env()
+}
+
+fn migrate_storage_schema_to_v24<E: Env>() -> TryEnvFuture<()> {
+    fn add_stream_health_check(settings: &mut serde_json::Value) {
+        if let Some(settings) = settings.as_object_mut() {
+            settings.insert(
+                "streamHealthCheck".to_owned(),
+                serde_json::Value::Bool(false),
+            );
+        }
+    }
+    E::get_storage::<serde_json::Value>(PROFILE_STORAGE_KEY)
+        .and_then(|mut profile| {
+            match profile
+                .as_mut()
+                .and_then(|profile| profile.as_object_mut())
+                .and_then(|profile| profile.get_mut("settings"))
+            {
+                Some(settings) => {
+                    add_stream_health_check(settings);
+                    E::set_storage(PROFILE_STORAGE_KEY, Some(&profile))
+                }
+                _ => E::set_storage::<()>(PROFILE_STORAGE_KEY, None),
+            }
+        })
+        .and_then(|_| E::get_storage::<serde_json::Value>(LOCAL_PROFILES_STORAGE_KEY))
+        .and_then(|mut local_profiles| {
+            match local_profiles
+                .as_mut()
+                .and_then(|local_profiles| local_profiles.as_object_mut())
+                .and_then(|local_profiles| local_profiles.get_mut("profiles"))
+                .and_then(|profiles| profiles.as_array_mut())
+            {
+                Some(profiles) => {
+                    profiles
+                        .iter_mut()
+                        .filter_map(|profile| profile.get_mut("settings"))
+                        .for_each(add_stream_health_check);
+                    E::set_storage(LOCAL_PROFILES_STORAGE_KEY, Some(&local_profiles))
+                }
+                _ => E::set_storage::<()>(LOCAL_PROFILES_STORAGE_KEY, None),
+            }
+        })
+        .and_then(|_| E::set_storage(SCHEMA_VERSION_STORAGE_KEY, Some(&24)))
+        .boxed_env()
+}
+
 #[cfg(test)]
 mod test {
     use serde_json::{json, Value};
 
     use crate::{
         constants::{
-            PROFILE_STORAGE_KEY, SCHEMA_VERSION, SCHEMA_VERSION_STORAGE_KEY, STREAMS_STORAGE_KEY,
+            LOCAL_PROFILES_STORAGE_KEY, PROFILE_STORAGE_KEY, SCHEMA_VERSION,
+            SCHEMA_VERSION_STORAGE_KEY, STREAMS_STORAGE_KEY,
         },
         runtime::{
             env::{
@@ -755,7 +1018,9 @@ mod test {
                 migrate_storage_schema_to_v14, migrate_storage_schema_to_v15,
                 migrate_storage_schema_to_v16, migrate_storage_schema_to_v17,
                 migrate_storage_schema_to_v18, migrate_storage_schema_to_v19,
-                migrate_storage_schema_to_v20, migrate_storage_schema_to_v6,
+                migrate_storage_schema_to_v20, migrate_storage_schema_to_v21,
+                migrate_storage_schema_to_v22, migrate_storage_schema_to_v23,
+                migrate_storage_schema_to_v24, migrate_storage_schema_to_v6,
                 migrate_storage_schema_to_v7, migrate_storage_schema_to_v8,
                 migrate_storage_schema_to_v9,
             },
@@ -1490,4 +1755,232 @@ mod test {
             );
         }
     }
+
+    #[tokio::test]
+    async fn test_migration_from_20_to_21() {
+        {
+            let _test_env_guard = TestEnv::reset().expect("Should lock TestEnv");
+            let profile_before = json!({
+                "settings": {}
+            });
+
+            let migrated_profile = json!({
+                "settings": {
+                    "streamPreferences": {
+                        "maxResolution": null,
+                        "preferHdr": false,
+                        "preferredCodecs": [],
+                        "maxSize": null,
+                        "preferredLanguages": [],
+                    },
+                }
+            });
+
+            // setup storage for migration
+            set_profile_and_schema_version(&profile_before, 20);
+
+            // migrate storage
+            migrate_storage_schema_to_v21::<TestEnv>()
+                .await
+                .expect("Should migrate");
+
+            let storage = STORAGE.read().expect("Should lock");
+
+            assert_eq!(
+                &21.to_string(),
+                storage
+                    .get(SCHEMA_VERSION_STORAGE_KEY)
+                    .expect("Should have the schema set"),
+                "Scheme version should now be updated"
+            );
+            assert_eq!(
+                &migrated_profile.to_string(),
+                storage
+                    .get(PROFILE_STORAGE_KEY)
+                    .expect("Should have the profile set"),
+                "Profile should match"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_migration_from_21_to_22() {
+        {
+            let _test_env_guard = TestEnv::reset().expect("Should lock TestEnv");
+            let profile_before = json!({
+                "settings": {}
+            });
+
+            let migrated_profile = json!({
+                "settings": {
+                    "autoPlayPolicy": "StrictBingeGroup",
+                }
+            });
+
+            // setup storage for migration
+            set_profile_and_schema_version(&profile_before, 21);
+
+            // migrate storage
+            migrate_storage_schema_to_v22::<TestEnv>()
+                .await
+                .expect("Should migrate");
+
+            let storage = STORAGE.read().expect("Should lock");
+
+            assert_eq!(
+                &22.to_string(),
+                storage
+                    .get(SCHEMA_VERSION_STORAGE_KEY)
+                    .expect("Should have the schema set"),
+                "Scheme version should now be updated"
+            );
+            assert_eq!(
+                &migrated_profile.to_string(),
+                storage
+                    .get(PROFILE_STORAGE_KEY)
+                    .expect("Should have the profile set"),
+                "Profile should match"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_migration_from_22_to_23() {
+        {
+            let _test_env_guard = TestEnv::reset().expect("Should lock TestEnv");
+            let profile_before = json!({
+                "auth": {
+                    "key": "auth_key",
+                    "user": {
+                        "_id": "user_id",
+                    }
+                },
+                "settings": {
+                    "autoPlayPolicy": "StrictBingeGroup",
+                }
+            });
+
+            let local_profiles = json!({
+                "uid": "user_id",
+                "selected": "default",
+                "profiles": [
+                    {
+                        "id": "default",
+                        "name": "Default",
+                        "settings": {
+                            "autoPlayPolicy": "StrictBingeGroup",
+                        }
+                    }
+                ]
+            });
+
+            // setup storage for migration
+            set_profile_and_schema_version(&profile_before, 22);
+
+            // migrate storage
+            migrate_storage_schema_to_v23::<TestEnv>()
+                .await
+                .expect("Should migrate");
+
+            let storage = STORAGE.read().expect("Should lock");
+
+            assert_eq!(
+                &23.to_string(),
+                storage
+                    .get(SCHEMA_VERSION_STORAGE_KEY)
+                    .expect("Should have the schema set"),
+                "Scheme version should now be updated"
+            );
+            assert_eq!(
+                &profile_before.to_string(),
+                storage
+                    .get(PROFILE_STORAGE_KEY)
+                    .expect("Should have the profile set"),
+                "Profile should not be changed"
+            );
+            assert_eq!(
+                &local_profiles.to_string(),
+                storage
+                    .get(LOCAL_PROFILES_STORAGE_KEY)
+                    .expect("Should have the local profiles set"),
+                "Local profiles should match"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_migration_from_23_to_24() {
+        {
+            let _test_env_guard = TestEnv::reset().expect("Should lock TestEnv");
+            let profile_before = json!({
+                "settings": {}
+            });
+            let local_profiles_before = json!({
+                "uid": null,
+                "selected": "default",
+                "profiles": [
+                    {
+                        "id": "default",
+                        "name": "Default",
+                        "settings": {}
+                    }
+                ]
+            });
+
+            let migrated_profile = json!({
+                "settings": {
+                    "streamHealthCheck": false,
+                }
+            });
+            let migrated_local_profiles = json!({
+                "uid": null,
+                "selected": "default",
+                "profiles": [
+                    {
+                        "id": "default",
+                        "name": "Default",
+                        "settings": {
+                            "streamHealthCheck": false,
+                        }
+                    }
+                ]
+            });
+
+            // setup storage for migration
+            set_profile_and_schema_version(&profile_before, 23);
+            STORAGE.write().expect("Should lock").insert(
+                LOCAL_PROFILES_STORAGE_KEY.to_owned(),
+                local_profiles_before.to_string(),
+            );
+
+            // migrate storage
+            migrate_storage_schema_to_v24::<TestEnv>()
+                .await
+                .expect("Should migrate");
+
+            let storage = STORAGE.read().expect("Should lock");
+
+            assert_eq!(
+                &24.to_string(),
+                storage
+                    .get(SCHEMA_VERSION_STORAGE_KEY)
+                    .expect("Should have the schema set"),
+                "Scheme version should now be updated"
+            );
+            assert_eq!(
+                &migrated_profile.to_string(),
+                storage
+                    .get(PROFILE_STORAGE_KEY)
+                    .expect("Should have the profile set"),
+                "Profile should match"
+            );
+            assert_eq!(
+                &migrated_local_profiles.to_string(),
+                storage
+                    .get(LOCAL_PROFILES_STORAGE_KEY)
+                    .expect("Should have the local profiles set"),
+                "Local profiles should match"
+            );
+        }
+    }
 }
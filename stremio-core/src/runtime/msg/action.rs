@@ -6,27 +6,36 @@ use url::Url;
 use crate::{
     models::{
         addon_details::Selected as AddonDetailsSelected,
-        calendar::Selected as CalendarSelected,
+        calendar::{FullDate, Selected as CalendarSelected},
         catalog_with_filters::Selected as CatalogWithFiltersSelected,
         catalogs_with_extra::Selected as CatalogsWithExtraSelected,
         installed_addons_with_filters::Selected as InstalledAddonsWithFiltersSelected,
         library_by_type::Selected as LibraryByTypeSelected,
         library_with_filters::Selected as LibraryWithFiltersSelected,
+        local_search::SearchOptions,
+        local_subtitles::Selected as LocalSubtitlesSelected,
         meta_details::Selected as MetaDetailsSelected,
-        player::{Selected as PlayerSelected, VideoParams},
+        player::{Selected as PlayerSelected, SubtitlesReference, VideoParams},
+        torrent_info::Selected as TorrentInfoSelected,
+        watch_party::Selected as WatchPartySelected,
+        watch_stats::Selected as WatchStatsSelected,
     },
     types::{
         addon::Descriptor,
         api::AuthRequest,
+        backup::{Backup, RestoreMode},
         library::LibraryItemId,
-        profile::{AuthKey, Password, Settings as ProfileSettings},
+        lists::{ListId, SharedList},
+        local_profiles::LocalProfileId,
+        profile::{AuthKey, ParentalRestrictions, Password, Settings as ProfileSettings},
         rating::Rating,
-        resource::{MetaItemId, MetaItemPreview, Video},
+        resource::{MetaItemId, MetaItemPreview, Subtitles, Video},
         streaming_server::{
             Settings as StreamingServerSettings,
             StatisticsRequest as StreamingServerStatisticsRequest,
         },
         streams::StreamItemState,
+        tracker::TrackerFormat,
     },
 };
 
@@ -83,6 +92,15 @@ pub enum ActionCtx {
     PushAddonsToAPI,
     PullAddonsFromAPI,
     SyncLibraryWithAPI,
+    /// Pull the lists of the user and push the lists which are newer locally
+    PullListsFromAPI,
+    /// Dismiss the sync conflicts of the [`LibraryItem`] with the given id.
+    ///
+    /// [`LibraryItem`]: crate::types::library::LibraryItem
+    DismissLibraryItemConflicts(LibraryItemId),
+    /// Push all the pending changes in the outbox to the API,
+    /// dispatch it when the app is loaded or back online.
+    FlushOutbox,
     /// Pull notifications for all [`LibraryItem`]s that we should pull notifications for.
     ///
     /// **Warning:** The action will **always** trigger requests to the addons.
@@ -99,6 +117,79 @@ pub enum ActionCtx {
     AddServerUrl(Url),
     /// Delete a server URL from the list of available streaming servers
     DeleteServerUrl(Url),
+    /// Enable the parental controls or change their PIN and restrictions.
+    ///
+    /// The controls must be unlocked if they are already enabled.
+    /// They are locked right after the update.
+    SetParentalControls {
+        pin: String,
+        restrictions: ParentalRestrictions,
+    },
+    /// Lift the parental restrictions until the controls are locked again
+    UnlockParentalControls {
+        pin: String,
+    },
+    LockParentalControls,
+    DisableParentalControls {
+        pin: String,
+    },
+    /// Create an empty list, placed after the other lists
+    CreateList {
+        name: String,
+    },
+    RenameList {
+        id: ListId,
+        name: String,
+    },
+    RemoveList(ListId),
+    /// Add the item to the end of the list, the item is added to the library as well
+    AddToList {
+        id: ListId,
+        meta_item: MetaItemPreview,
+    },
+    RemoveFromList {
+        id: ListId,
+        item_id: MetaItemId,
+    },
+    /// Move the item to the given position in the list
+    MoveListItem {
+        id: ListId,
+        item_id: MetaItemId,
+        position: usize,
+    },
+    /// Move the list to the given position among the other lists
+    MoveList {
+        id: ListId,
+        position: usize,
+    },
+    /// Create a list from a list shared by another user,
+    /// its items are added to the library as well
+    ImportList(SharedList),
+    /// Add a local profile with its own library, streams, search history and settings.
+    ///
+    /// The name is trimmed and can't be empty.
+    AddLocalProfile {
+        name: String,
+    },
+    /// Remove the local profile and its data from the storage.
+    ///
+    /// Neither the default nor the selected local profile can be removed.
+    RemoveLocalProfile(LocalProfileId),
+    /// Load the library, streams, search history and settings of the local profile
+    SwitchLocalProfile(LocalProfileId),
+    /// Create a backup of the buckets of all the local profiles, see [`Event::BackupCreated`]
+    ///
+    /// [`Event::BackupCreated`]: crate::runtime::msg::Event::BackupCreated
+    CreateBackup,
+    /// Restore the buckets of the backup, migrating them first if the backup is older.
+    ///
+    /// The other local profiles of the backup are added, or replace the current ones
+    /// when the `mode` is [`RestoreMode::Replace`].
+    /// The auth and the parental controls of the profile are never restored.
+    RestoreBackup {
+        backup: Box<Backup>,
+        mode: RestoreMode,
+    },
 }
 
 #[derive(Clone, Deserialize, Debug)]
@@ -229,6 +320,15 @@ pub enum ActionPlayer {
     MarkVideoAsWatched(Video, bool),
     /// Mark all videos from given season as watched
     MarkSeasonAsWatched(u32, bool),
+    /// Aligns the addon subtitles to the reference track and proposes a delay
+    /// and a framerate correction for them
+    SyncSubtitles {
+        subtitles: Subtitles,
+        reference: SubtitlesReference,
+    },
+    /// Persists the proposed delay and framerate correction of the aligned subtitles
+    /// in the stream state
+    ApplySubtitlesSync,
 }
 
 #[derive(Clone, Deserialize, Debug)]
@@ -246,9 +346,64 @@ pub enum ActionLoad {
     Calendar(Option<CalendarSelected>),
     /// Loads the data required for Local search
     LocalSearch,
+    /// Fetches and parses the subtitles file, only retimes the cues if it's already loaded
+    LocalSubtitles(LocalSubtitlesSelected),
     MetaDetails(MetaDetailsSelected),
     Player(Box<PlayerSelected>),
     Link,
+    /// Parses the torrent file or the magnet link
+    TorrentInfo(TorrentInfoSelected),
+    /// Joins the watch party, leaving the previous one
+    WatchParty(WatchPartySelected),
+    /// Computes the watch statistics of the period, loading the videos of the watched series
+    WatchStats(WatchStatsSelected),
+}
+
+/// The changes of the local player which are sent to the other members of the watch party.
+///
+/// The times are the playback positions in milliseconds.
+#[derive(Clone, Deserialize, Debug)]
+#[serde(tag = "action", content = "args")]
+pub enum ActionWatchParty {
+    PausedChanged {
+        paused: bool,
+        time: u64,
+    },
+    Seek {
+        time: u64,
+    },
+    /// A normal playback by the local player, it also receives the new messages
+    TimeChanged {
+        time: u64,
+    },
+    SelectStream(Box<PlayerSelected>),
+    /// Receive the new messages of the other members
+    Poll,
+    /// Estimate the clock offsets of the other members
+    SyncClock,
+}
+
+#[derive(Clone, Deserialize, Debug)]
+#[serde(tag = "action", content = "args")]
+pub enum ActionTorrentInfo {
+    /// Selects the file to play by its index in the torrent
+    SelectFile(u16),
+}
+
+#[derive(Clone, Deserialize, Debug)]
+#[serde(tag = "action", content = "args")]
+pub enum ActionCalendar {
+    /// Exports the videos released between the two dates, both inclusive, as an iCalendar file
+    ExportICalendar { from: FullDate, to: FullDate },
+}
+
+#[derive(Clone, Deserialize, Debug)]
+#[serde(tag = "action", content = "args")]
+pub enum ActionLibraryTransfer {
+    /// Exports the watched and rated items of the library to the file of the tracker
+    Export(TrackerFormat),
+    /// Marks the titles of the tracker file as watched in the library
+    Import { format: TrackerFormat, data: String },
 }
 
 #[derive(Clone, Deserialize, Debug)]
@@ -259,7 +414,11 @@ pub enum ActionSearch {
     Search {
         search_query: String,
         max_results: usize,
+        /// Only the items of this type
+        #[serde(default)]
+        r#type: Option<String>,
     },
+    UpdateOptions(SearchOptions),
 }
 
 /// Action messages
@@ -275,9 +434,13 @@ pub enum Action {
     CatalogsWithExtra(ActionCatalogsWithExtra),
     LibraryByType(ActionLibraryByType),
     LibraryWithFilters(ActionLibraryWithFilters),
+    LibraryTransfer(ActionLibraryTransfer),
     MetaDetails(ActionMetaDetails),
     StreamingServer(ActionStreamingServer),
     Player(ActionPlayer),
+    WatchParty(ActionWatchParty),
+    TorrentInfo(ActionTorrentInfo),
+    Calendar(ActionCalendar),
     Load(ActionLoad),
     Search(ActionSearch),
     Unload,
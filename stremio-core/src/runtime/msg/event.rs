@@ -1,8 +1,11 @@
 use crate::models::ctx::CtxError;
 use crate::models::player::AnalyticsContext as PlayerAnalyticsContext;
 use crate::types::api::AuthRequest;
+use crate::types::backup::Backup;
 use crate::types::library::LibraryItemId;
-use crate::types::profile::{AuthKey, Settings, UID};
+use crate::types::lists::ListId;
+use crate::types::local_profiles::LocalProfileId;
+use crate::types::profile::{AuthKey, ParentalControls, Settings, UID};
 use crate::types::resource::MetaItemId;
 use serde::Serialize;
 use url::Url;
@@ -49,6 +52,15 @@ pub enum Event {
     SearchHistoryPushedToStorage {
         uid: UID,
     },
+    TastePushedToStorage {
+        uid: UID,
+    },
+    ListsPushedToStorage {
+        uid: UID,
+    },
+    AddonHealthPushedToStorage {
+        transport_urls: Vec<Url>,
+    },
     NotificationsPushedToStorage {
         ids: Vec<String>,
     },
@@ -77,6 +89,56 @@ pub enum Event {
     LibraryItemsPulledFromAPI {
         ids: Vec<String>,
     },
+    ListsPushedToAPI {
+        ids: Vec<ListId>,
+    },
+    ListsPulledFromAPI {
+        ids: Vec<ListId>,
+    },
+    ListCreated {
+        id: ListId,
+    },
+    ListUpdated {
+        id: ListId,
+    },
+    /// The local and the remote changes of the LibraryItems with the given ids were merged with conflicts
+    LibraryItemsConflicted {
+        ids: Vec<String>,
+    },
+    LibrarySyncPushedToStorage {
+        uid: UID,
+    },
+    OutboxPushedToStorage {
+        uid: UID,
+    },
+    /// The number of changes waiting to be pushed to the API
+    OutboxSizeChanged {
+        size: usize,
+    },
+    LocalProfilesPushedToStorage {
+        uid: UID,
+    },
+    LocalProfileAdded {
+        id: LocalProfileId,
+    },
+    /// The local profile and its data were removed from the storage
+    LocalProfileRemoved {
+        id: LocalProfileId,
+    },
+    LocalProfileSwitched {
+        id: LocalProfileId,
+    },
+    /// The backup of the buckets of all the local profiles.
+    ///
+    /// As the source of an error, it has the buckets of the selected local profile only,
+    /// as the buckets of the other local profiles couldn't be read from the storage.
+    BackupCreated {
+        backup: Box<Backup>,
+    },
+    /// The buckets of the backup were restored, after migrating them from its schema version
+    BackupRestored {
+        schema_version: u32,
+    },
     UserAuthenticated {
         auth_request: AuthRequest,
     },
@@ -116,6 +178,19 @@ pub enum Event {
     SettingsUpdated {
         settings: Settings,
     },
+    ParentalControlsUpdated {
+        parental_controls: ParentalControls,
+    },
+    /// The player was not loaded because the content is blocked by the parental controls
+    PlayerBlocked {
+        id: Option<MetaItemId>,
+    },
+    WatchPartyMessageSent {
+        party_id: String,
+    },
+    WatchPartyMessagesReceived {
+        party_id: String,
+    },
     LibraryItemAdded {
         id: LibraryItemId,
     },
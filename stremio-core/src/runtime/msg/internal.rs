@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use url::Url;
 
 use crate::models::common::ResourceLoadable;
@@ -5,21 +7,33 @@ use crate::models::ctx::CtxError;
 use crate::models::link::LinkError;
 use crate::models::local_search::Searchable;
 use crate::models::streaming_server::PlaybackDevice;
-use crate::runtime::EnvError;
+use crate::runtime::{EnvError, ResponseHead};
 use crate::types::addon::{Descriptor, Manifest, ResourceRequest, ResourceResponse};
+use crate::types::addon_health::AddonRequest;
 use crate::types::api::{
     APIRequest, AuthRequest, DataExportResponse, DatastoreRequest, GetModalResponse,
     GetNotificationResponse, LinkCodeResponse, LinkDataResponse, SeekLogRequest, SkipGapsRequest,
     SkipGapsResponse, SuccessResponse,
 };
+use crate::types::backup::RestoreMode;
+use crate::types::events::DismissedEventsBucket;
 use crate::types::library::{LibraryBucket, LibraryItem, LibraryItemId};
-use crate::types::profile::{Auth, AuthKey, Profile, User};
-use crate::types::rating::{RatingGetStatusResponse, RatingSendResponse};
-use crate::types::resource::MetaItemId;
+use crate::types::lists::{List, ListsBucket};
+use crate::types::local_profiles::{LocalProfileId, LocalProfilesBucket};
+use crate::types::notifications::NotificationsBucket;
+use crate::types::outbox::OutboxRequest;
+use crate::types::profile::{Auth, AuthKey, Profile, Settings, User};
+use crate::types::rating::{Rating, RatingGetStatusResponse, RatingSendResponse};
+use crate::types::resource::{MetaItemId, MetaItemPreview};
+use crate::types::search_history::SearchHistoryBucket;
+use crate::types::server_urls::ServerUrlsBucket;
 use crate::types::streaming_server::{
     DeviceInfo, GetHTTPSResponse, NetworkInfo, SettingsResponse, Statistics, StatisticsRequest,
 };
-use crate::types::streams::StreamItemState;
+use crate::types::streams::{StreamItemState, StreamsBucket};
+use crate::types::subtitles::{SubtitlesError, SubtitlesSync, SubtitlesTrack};
+use crate::types::taste::TasteBucket;
+use crate::types::watch_party::WatchPartyMessage;
 use crate::types::{
     resource::{MetaItem, Stream},
     torrent::InfoHash,
@@ -38,6 +52,35 @@ pub struct CtxAuthResponse {
     pub library_items_result: Result<Vec<LibraryItem>, CtxError>,
 }
 
+#[derive(Debug)]
+pub struct LocalProfileStorageResponse {
+    pub settings: Settings,
+    pub library: LibraryBucket,
+    pub streams: StreamsBucket,
+    pub search_history: SearchHistoryBucket,
+    pub lists: ListsBucket,
+}
+
+/// The buckets of a backup, migrated to the current storage schema.
+///
+/// The library, the streams, the search history and the lists
+/// are the buckets of the selected local profile.
+#[derive(Debug)]
+pub struct BackupStorageResponse {
+    /// The schema version of the backup before the migrations
+    pub schema_version: u32,
+    pub profile: Option<Profile>,
+    pub library: Option<LibraryBucket>,
+    pub streams: Option<StreamsBucket>,
+    pub streaming_server_urls: Option<ServerUrlsBucket>,
+    pub notifications: Option<NotificationsBucket>,
+    pub search_history: Option<SearchHistoryBucket>,
+    pub dismissed_events: Option<DismissedEventsBucket>,
+    pub taste: Option<TasteBucket>,
+    pub lists: Option<ListsBucket>,
+    pub local_profiles: Option<LocalProfilesBucket>,
+}
+
 pub type LibraryPlanResponse = (Vec<String>, Vec<String>);
 
 //
@@ -64,6 +107,8 @@ pub enum Internal {
     LibrarySyncPlanResult(DatastoreRequest, Result<LibraryPlanResponse, CtxError>),
     /// Result for pull library items from API.
     LibraryPullResult(DatastoreRequest, Result<Vec<LibraryItem>, CtxError>),
+    /// Result for pull lists from API.
+    ListsPullResult(DatastoreRequest, Result<Vec<List>, CtxError>),
     /// Dispatched when the user session needs to be cleared with a flag if the session was already deleted server-side
     Logout(bool),
     /// Internal event dispatched on user action or login
@@ -96,6 +141,25 @@ pub enum Internal {
     ProfileChanged,
     /// Dispatched when library changes with a flag if its already persisted.
     LibraryChanged(bool),
+    /// Library sync bucket has changed.
+    LibrarySyncChanged,
+    /// Push the change to the API through the outbox.
+    EnqueueOutboxRequest(OutboxRequest),
+    /// Result for pushing an outbox request to the API.
+    OutboxRequestResult(OutboxRequest, Result<(), CtxError>),
+    /// The backoff of a failed outbox request has elapsed.
+    OutboxRetryDue,
+    /// Outbox has changed.
+    OutboxChanged,
+    /// Result for loading the data of the local profile from the storage.
+    LocalProfileStorageResult(
+        LocalProfileId,
+        Result<LocalProfileStorageResponse, CtxError>,
+    ),
+    /// Local profiles bucket has changed.
+    LocalProfilesChanged,
+    /// Result for migrating the buckets of the backup which are restored with the given mode.
+    BackupRestoreResult(RestoreMode, Result<Box<BackupStorageResponse>, CtxError>),
     /// Dispatched when streams bucket changes with a flag if its already persisted.
     StreamsChanged(bool),
     /// Search history has changed.
@@ -141,6 +205,12 @@ pub enum Internal {
         (Url, StatisticsRequest),
         Result<Option<Statistics>, EnvError>,
     ),
+    /// Dispatched right before a request to an addon is sent.
+    AddonRequestStarted(AddonRequest),
+    /// Dispatched when an in-flight request to an addon is cancelled.
+    AddonRequestCancelled(AddonRequest),
+    /// Addon health bucket has changed.
+    AddonHealthChanged,
     /// Result for fetching resource from addons.
     ResourceRequestResult(ResourceRequest, Box<Result<ResourceResponse, EnvError>>),
     /// Result for fetching manifest from addon.
@@ -157,6 +227,10 @@ pub enum Internal {
     SkipGapsResult(SkipGapsRequest, Result<SkipGapsResponse, CtxError>),
     /// The result of querying the data for LocalSearch
     LoadLocalSearchResult(Url, Result<Vec<Searchable>, EnvError>),
+    /// The result of fetching and parsing the subtitles file for LocalSubtitles
+    LocalSubtitlesResult(Url, Result<SubtitlesTrack, SubtitlesError>),
+    /// The result of aligning the addon subtitles of the Player to the reference track
+    SubtitlesSyncResult(Url, Result<SubtitlesSync, SubtitlesError>),
     /// Result for getModal request
     GetModalResult(APIRequest, Result<Option<GetModalResponse>, CtxError>),
     /// Result for getNotification request
@@ -168,4 +242,24 @@ pub enum Internal {
     DismissedEventsChanged,
     RatingGetStatusResult(MetaItemId, Result<RatingGetStatusResponse, EnvError>),
     RatingSendResult(MetaItemId, Result<RatingSendResponse, EnvError>),
+    /// Probe the stream URLs which are not probed recently,
+    /// with the request headers of their streams.
+    ProbeStreams(Vec<(Url, HashMap<String, String>)>),
+    /// Result for the pre-flight request of a stream URL.
+    StreamProbeResult(Url, Result<ResponseHead, EnvError>),
+    /// Stream probes bucket has changed.
+    StreamProbesChanged,
+    /// Dispatched when the rating of a meta item is loaded or changed by the user.
+    MetaItemRatingChanged {
+        meta_item: MetaItemPreview,
+        status: Option<Rating>,
+    },
+    /// Taste bucket has changed.
+    TasteChanged,
+    /// Dispatched when a list is created, changed or removed by the user.
+    UpdateList(List),
+    /// Lists bucket has changed.
+    ListsChanged,
+    /// Result for receiving the messages of the other members of the watch party.
+    WatchPartyReceiveResult(String, Result<Vec<WatchPartyMessage>, EnvError>),
 }
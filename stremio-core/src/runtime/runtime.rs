@@ -1,14 +1,17 @@
-use crate::runtime::msg::{Action, Event, Msg};
+use crate::runtime::msg::{Action, Event, Internal, Msg};
 use crate::runtime::{Effect, EffectFuture, Env, Model};
+use crate::types::addon_health::AddonRequest;
 use derivative::Derivative;
 use enclose::enclose;
 use futures::channel::mpsc::{channel, Receiver, Sender};
+use futures::future::{abortable, AbortHandle};
 use futures::FutureExt;
 #[cfg(test)]
 use futures::SinkExt;
 use serde::Serialize;
+use std::collections::HashMap;
 use std::marker::PhantomData;
-use std::sync::{Arc, LockResult, RwLock, RwLockReadGuard};
+use std::sync::{Arc, LockResult, Mutex, RwLock, RwLockReadGuard};
 
 #[derive(Serialize, Debug, PartialEq, Eq)]
 #[serde(tag = "name", content = "args")]
@@ -28,9 +31,63 @@ pub struct RuntimeAction<E: Env, M: Model<E>> {
 pub struct Runtime<E: Env, M: Model<E>> {
     model: Arc<RwLock<M>>,
     tx: Sender<RuntimeEvent<E, M>>,
+    cancellable: Arc<Mutex<CancellableFutures>>,
     env: PhantomData<E>,
 }
 
+/// The abort handles of the in-flight [`EffectFuture::Cancellable`] futures.
+///
+/// The same request can be started by many models and its response is delivered to all of them,
+/// so the futures of the request are aborted only when every model which started it has cancelled it.
+#[derive(Default)]
+struct CancellableFutures {
+    next_id: usize,
+    requests: HashMap<AddonRequest, CancellableRequest>,
+}
+
+#[derive(Default)]
+struct CancellableRequest {
+    /// The number of started futures which are neither completed nor cancelled.
+    owners: usize,
+    handles: Vec<(usize, AbortHandle)>,
+}
+
+impl CancellableFutures {
+    fn insert(&mut self, request: AddonRequest, handle: AbortHandle) -> usize {
+        let id = self.next_id;
+        self.next_id += 1;
+        let cancellable = self.requests.entry(request).or_default();
+        cancellable.owners += 1;
+        cancellable.handles.push((id, handle));
+        id
+    }
+    fn remove(&mut self, request: &AddonRequest, id: usize) {
+        if let Some(cancellable) = self.requests.get_mut(request) {
+            cancellable
+                .handles
+                .retain(|(handle_id, _)| *handle_id != id);
+            cancellable.owners = cancellable.owners.min(cancellable.handles.len());
+            if cancellable.handles.is_empty() {
+                self.requests.remove(request);
+            }
+        }
+    }
+    /// Aborts the futures of the request once it's cancelled by all of its owners.
+    fn cancel(&mut self, request: &AddonRequest) {
+        if let Some(cancellable) = self.requests.get_mut(request) {
+            cancellable.owners = cancellable.owners.saturating_sub(1);
+            if cancellable.owners == 0 {
+                if let Some(cancellable) = self.requests.remove(request) {
+                    cancellable
+                        .handles
+                        .into_iter()
+                        .for_each(|(_, handle)| handle.abort());
+                }
+            }
+        }
+    }
+}
+
 impl<E, M> Runtime<E, M>
 where
     E: Env + Send + 'static,
@@ -46,6 +103,7 @@ where
         let runtime = Runtime {
             model,
             tx,
+            cancellable: Default::default(),
             env: PhantomData,
         };
         runtime.handle_effects(effects, vec![]);
@@ -103,6 +161,32 @@ where
                             runtime.handle_effect_output(msg);
                         })))
                     }
+                    Effect::Future(EffectFuture::Cancellable(request, future)) => {
+                        let (future, handle) = abortable(future);
+                        let id = runtime
+                            .cancellable
+                            .lock()
+                            .expect("cancellable lock failed")
+                            .insert(request.to_owned(), handle);
+                        E::exec_concurrent(future.then(enclose!((runtime) move |result| async move {
+                            runtime
+                                .cancellable
+                                .lock()
+                                .expect("cancellable lock failed")
+                                .remove(&request, id);
+                            let msg = result.unwrap_or_else(|_| {
+                                Msg::Internal(Internal::AddonRequestCancelled(request))
+                            });
+                            runtime.handle_effect_output(msg);
+                        })))
+                    }
+                    Effect::Cancel(request) => {
+                        runtime
+                            .cancellable
+                            .lock()
+                            .expect("cancellable lock failed")
+                            .cancel(&request);
+                    }
                 }
             }));
     }
@@ -259,7 +259,7 @@ impl From<&str> for ManifestResource {
 
 impl ManifestResource {
     #[inline]
-    fn name(&self) -> &str {
+    pub fn name(&self) -> &str {
         match self {
             ManifestResource::Short(name) => name,
             ManifestResource::Full { name, .. } => name,
@@ -9,3 +9,6 @@ pub use request::*;
 
 mod response;
 pub use response::*;
+
+mod resource_cache;
+pub use resource_cache::*;
@@ -7,7 +7,7 @@ use crate::{
     types::addon::{Descriptor, ExtraProp, ManifestResource},
 };
 
-#[derive(Clone, From, Into, PartialEq, Eq, Serialize, Deserialize, Debug)]
+#[derive(Clone, From, Into, PartialEq, Eq, Hash, Serialize, Deserialize, Debug)]
 #[serde(from = "(String, String)", into = "(String, String)")]
 pub struct ExtraValue {
     pub name: String,
@@ -59,7 +59,7 @@ impl ExtraExt for Vec<ExtraValue> {
 /// The url paths look as follows:
 /// - Without extra values: `{resource}/{type}/{id}.json`
 /// - With extra values: `{resource}/{type}/{id}/{extra}.json`
-#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+#[derive(Clone, PartialEq, Eq, Hash, Serialize, Deserialize, Debug)]
 #[cfg_attr(test, derive(Default))]
 pub struct ResourcePath {
     /// The resource we want to fetch from the addon.
@@ -120,7 +120,7 @@ impl ResourcePath {
     }
 }
 
-#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+#[derive(Clone, PartialEq, Eq, Hash, Serialize, Deserialize, Debug)]
 pub struct ResourceRequest {
     pub base: Url,
     pub path: ResourcePath,
@@ -0,0 +1,193 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::constants::{RESOURCE_CACHE_MAX_ENTRIES, RESOURCE_CACHE_TTL};
+use crate::types::addon::ResourceResponseCache;
+
+/// The state of a [`ResourceCacheEntry`] at a given point in time.
+///
+/// Follows the `Cache-Control` semantics of the cache hints returned by addons,
+/// see [`ResourceResponseCache`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ResourceCacheFreshness {
+    /// The entry is younger than `cacheMaxAge` and can be served without a request.
+    Fresh,
+    /// The entry has expired less than `staleRevalidate` ago,
+    /// it can be served while it's being revalidated in the background.
+    StaleRevalidate,
+    /// The entry has expired less than `staleError` ago,
+    /// it can be served only when the request to the addon fails.
+    StaleError,
+    /// The entry should not be served anymore.
+    Expired,
+}
+
+/// A cached addon resource response, persisted in the storage.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceCacheEntry {
+    pub cached_at: DateTime<Utc>,
+    pub response: ResourceResponseCache,
+}
+
+impl ResourceCacheEntry {
+    pub fn new(response: ResourceResponseCache, cached_at: DateTime<Utc>) -> Self {
+        ResourceCacheEntry {
+            cached_at,
+            response,
+        }
+    }
+    pub fn freshness(&self, now: DateTime<Utc>) -> ResourceCacheFreshness {
+        let age = u64::try_from((now - self.cached_at).num_seconds()).unwrap_or_default();
+        let max_age = self.response.cache_max_age.unwrap_or_default();
+        let stale_revalidate = self.response.stale_revalidate.unwrap_or_default();
+        let stale_error = self.response.stale_error.unwrap_or_default();
+        if age < max_age {
+            ResourceCacheFreshness::Fresh
+        } else if age < max_age.saturating_add(stale_revalidate) {
+            ResourceCacheFreshness::StaleRevalidate
+        } else if age < max_age.saturating_add(stale_error) {
+            ResourceCacheFreshness::StaleError
+        } else {
+            ResourceCacheFreshness::Expired
+        }
+    }
+}
+
+/// The storage keys of the persisted [`ResourceCacheEntry`]s and the time they were stored at.
+///
+/// Every entry has its own storage key, so the index is what bounds the size of the cache.
+#[derive(Default, Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceCacheIndex {
+    pub entries: HashMap<String, DateTime<Utc>>,
+}
+
+impl ResourceCacheIndex {
+    /// Records the storage key and returns the storage keys which should be removed,
+    /// the ones older than [`RESOURCE_CACHE_TTL`] and the oldest ones above [`RESOURCE_CACHE_MAX_ENTRIES`].
+    pub fn insert(&mut self, storage_key: String, stored_at: DateTime<Utc>) -> Vec<String> {
+        self.entries.insert(storage_key, stored_at);
+        let mut evicted = self
+            .entries
+            .iter()
+            .filter(|(_, entry_stored_at)| stored_at - **entry_stored_at > RESOURCE_CACHE_TTL)
+            .map(|(storage_key, _)| storage_key.to_owned())
+            .collect::<Vec<_>>();
+        self.remove_all(&evicted);
+        if self.entries.len() > RESOURCE_CACHE_MAX_ENTRIES {
+            let mut entries = self
+                .entries
+                .iter()
+                .map(|(storage_key, stored_at)| (*stored_at, storage_key.to_owned()))
+                .collect::<Vec<_>>();
+            entries.sort();
+            let oldest = entries
+                .into_iter()
+                .take(self.entries.len() - RESOURCE_CACHE_MAX_ENTRIES)
+                .map(|(_, storage_key)| storage_key)
+                .collect::<Vec<_>>();
+            self.remove_all(&oldest);
+            evicted.extend(oldest);
+        }
+        evicted
+    }
+    pub fn remove(&mut self, storage_key: &str) {
+        self.entries.remove(storage_key);
+    }
+    fn remove_all(&mut self, storage_keys: &[String]) {
+        for storage_key in storage_keys {
+            self.remove(storage_key);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, TimeZone, Utc};
+
+    use crate::constants::{RESOURCE_CACHE_MAX_ENTRIES, RESOURCE_CACHE_TTL};
+    use crate::types::addon::{ResourceResponse, ResourceResponseCache};
+
+    use super::{ResourceCacheEntry, ResourceCacheFreshness, ResourceCacheIndex};
+
+    #[test]
+    fn test_resource_cache_entry_freshness() {
+        let cached_at = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let entry = ResourceCacheEntry::new(
+            ResourceResponseCache {
+                cache_max_age: Some(60),
+                stale_revalidate: Some(60),
+                stale_error: Some(600),
+                resource: ResourceResponse::Metas { metas: vec![] },
+            },
+            cached_at,
+        );
+
+        assert_eq!(
+            entry.freshness(cached_at - Duration::seconds(10)),
+            ResourceCacheFreshness::Fresh,
+            "Entry from the future should be fresh"
+        );
+        assert_eq!(
+            entry.freshness(cached_at + Duration::seconds(59)),
+            ResourceCacheFreshness::Fresh
+        );
+        assert_eq!(
+            entry.freshness(cached_at + Duration::seconds(60)),
+            ResourceCacheFreshness::StaleRevalidate
+        );
+        assert_eq!(
+            entry.freshness(cached_at + Duration::seconds(120)),
+            ResourceCacheFreshness::StaleError
+        );
+        assert_eq!(
+            entry.freshness(cached_at + Duration::seconds(660)),
+            ResourceCacheFreshness::Expired
+        );
+    }
+
+    #[test]
+    fn test_resource_cache_entry_freshness_without_hints() {
+        let cached_at = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let entry =
+            ResourceCacheEntry::new(ResourceResponse::Metas { metas: vec![] }.into(), cached_at);
+
+        assert_eq!(entry.freshness(cached_at), ResourceCacheFreshness::Expired);
+    }
+
+    #[test]
+    fn test_resource_cache_index_eviction() {
+        let stored_at = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let mut index = ResourceCacheIndex::default();
+        for position in 0..RESOURCE_CACHE_MAX_ENTRIES {
+            let evicted = index.insert(
+                format!("entry_{position}"),
+                stored_at + Duration::seconds(position as i64),
+            );
+            assert!(evicted.is_empty());
+        }
+
+        assert_eq!(
+            index.insert("entry_new".to_owned(), stored_at + Duration::hours(1)),
+            vec!["entry_0".to_owned()],
+            "The oldest entry should be evicted above the maximum entries"
+        );
+        assert_eq!(index.entries.len(), RESOURCE_CACHE_MAX_ENTRIES);
+
+        let mut evicted = index.insert(
+            "entry_latest".to_owned(),
+            stored_at + RESOURCE_CACHE_TTL + Duration::minutes(30),
+        );
+        evicted.sort();
+        assert_eq!(
+            evicted.len(),
+            RESOURCE_CACHE_MAX_ENTRIES - 1,
+            "Entries older than the TTL should be evicted"
+        );
+        assert!(!evicted.contains(&"entry_new".to_owned()));
+        assert_eq!(index.entries.len(), 2);
+    }
+}
@@ -78,6 +78,26 @@ pub struct ResourceResponseCache {
     pub resource: ResourceResponse,
 }
 
+impl ResourceResponseCache {
+    /// Whether the addon has allowed caching of the response with any of the cache hints.
+    pub fn is_cacheable(&self) -> bool {
+        [self.cache_max_age, self.stale_revalidate, self.stale_error]
+            .iter()
+            .any(|hint| hint.unwrap_or_default() > 0)
+    }
+}
+
+impl From<ResourceResponse> for ResourceResponseCache {
+    fn from(resource: ResourceResponse) -> Self {
+        ResourceResponseCache {
+            cache_max_age: None,
+            stale_revalidate: None,
+            stale_error: None,
+            resource,
+        }
+    }
+}
+
 /// Resource Response from an addon.
 ///
 /// Deserializing the struct from json will skip any invalid Vec items
@@ -0,0 +1,95 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::runtime::EnvError;
+use crate::types::addon::ResourceRequest;
+use crate::types::addon_health::{AddonRequestSample, AddonStats};
+
+/// A request to an addon which is waiting for its response.
+#[derive(Clone, PartialEq, Eq, Hash, Serialize, Deserialize, Debug)]
+pub enum AddonRequest {
+    Manifest(Url),
+    Resource(ResourceRequest),
+}
+
+impl AddonRequest {
+    pub fn transport_url(&self) -> &Url {
+        match self {
+            AddonRequest::Manifest(transport_url) => transport_url,
+            AddonRequest::Resource(request) => &request.base,
+        }
+    }
+}
+
+/// The request stats of the addons, by transport url.
+#[derive(Default, Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+pub struct AddonHealthBucket {
+    pub items: HashMap<Url, AddonStats>,
+    /// The requests which are waiting for a response and when they were started
+    #[serde(skip)]
+    pub pending: Vec<(AddonRequest, DateTime<Utc>)>,
+    /// When the stats were last written to the storage
+    #[serde(skip)]
+    pub pushed_at: Option<DateTime<Utc>>,
+}
+
+impl AddonHealthBucket {
+    /// Orders the addons from the least to the most healthy,
+    /// the slower one first for the same status and the addons without recent requests last.
+    pub fn compare_health(&self, a: &Url, b: &Url) -> Ordering {
+        let health = |transport_url| {
+            self.items
+                .get(transport_url)
+                .map(|stats| (stats.status(), stats.latency()))
+        };
+        health(b).cmp(&health(a))
+    }
+    /// Records the sample for the earliest started pending request.
+    ///
+    /// Returns `false` if the request was not started or it was cancelled.
+    pub fn finish(
+        &mut self,
+        request: &AddonRequest,
+        finished_at: DateTime<Utc>,
+        error: Option<&EnvError>,
+    ) -> bool {
+        let position = self
+            .pending
+            .iter()
+            .position(|(pending_request, _)| pending_request == request);
+        match position {
+            Some(position) => {
+                let (request, started_at) = self.pending.remove(position);
+                let latency = (finished_at - started_at).num_milliseconds().max(0) as u64;
+                self.items
+                    .entry(request.transport_url().to_owned())
+                    .or_default()
+                    .record(AddonRequestSample {
+                        finished_at,
+                        latency,
+                        error: error.map(Into::into),
+                    });
+                true
+            }
+            None => false,
+        }
+    }
+    /// Forgets the latest started pending request without recording a sample.
+    pub fn cancel(&mut self, request: &AddonRequest) -> bool {
+        let position = self
+            .pending
+            .iter()
+            .rposition(|(pending_request, _)| pending_request == request);
+        match position {
+            Some(position) => {
+                self.pending.remove(position);
+                true
+            }
+            None => false,
+        }
+    }
+}
@@ -0,0 +1,118 @@
+use std::collections::{HashMap, VecDeque};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::constants::{ADDON_FAILING_ERROR_RATE, ADDON_HEALTH_SAMPLES_COUNT, ADDON_SLOW_LATENCY};
+use crate::runtime::EnvError;
+
+/// The kind of error of a failed addon request
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Debug)]
+pub enum AddonErrorKind {
+    /// The addon is unreachable or responded with an error status
+    Fetch,
+    /// The response of the addon is not a valid addon protocol response
+    Protocol,
+    /// The addon did not respond in time
+    Timeout,
+    Other,
+}
+
+impl From<&EnvError> for AddonErrorKind {
+    fn from(error: &EnvError) -> Self {
+        match error {
+            EnvError::Fetch(_) => AddonErrorKind::Fetch,
+            EnvError::AddonTransport(_) | EnvError::Serde(_) => AddonErrorKind::Protocol,
+            EnvError::Timeout(_) => AddonErrorKind::Timeout,
+            _ => AddonErrorKind::Other,
+        }
+    }
+}
+
+/// Ordered from the most to the least healthy.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, Debug)]
+pub enum AddonHealthStatus {
+    Healthy,
+    Slow,
+    Failing,
+}
+
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AddonRequestSample {
+    pub finished_at: DateTime<Utc>,
+    /// In milliseconds
+    pub latency: u64,
+    pub error: Option<AddonErrorKind>,
+}
+
+/// The rolling stats of the requests to an addon.
+#[derive(Default, Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AddonStats {
+    /// The most recent requests, oldest first,
+    /// at most [`ADDON_HEALTH_SAMPLES_COUNT`] of them
+    pub samples: VecDeque<AddonRequestSample>,
+    pub last_success: Option<DateTime<Utc>>,
+    pub last_error: Option<DateTime<Utc>>,
+}
+
+impl AddonStats {
+    pub fn record(&mut self, sample: AddonRequestSample) {
+        match sample.error {
+            Some(_) => self.last_error = Some(sample.finished_at),
+            None => self.last_success = Some(sample.finished_at),
+        };
+        self.samples.push_back(sample);
+        while self.samples.len() > ADDON_HEALTH_SAMPLES_COUNT {
+            self.samples.pop_front();
+        }
+    }
+    pub fn errors_count(&self) -> usize {
+        self.samples
+            .iter()
+            .filter(|sample| sample.error.is_some())
+            .count()
+    }
+    /// The share of the recent requests which failed, from 0 to 1
+    pub fn error_rate(&self) -> f64 {
+        if self.samples.is_empty() {
+            0.0
+        } else {
+            self.errors_count() as f64 / self.samples.len() as f64
+        }
+    }
+    /// The average latency of the recent requests, in milliseconds
+    pub fn latency(&self) -> Option<u64> {
+        if self.samples.is_empty() {
+            None
+        } else {
+            let total = self
+                .samples
+                .iter()
+                .map(|sample| sample.latency)
+                .sum::<u64>();
+            Some(total / self.samples.len() as u64)
+        }
+    }
+    pub fn error_kinds(&self) -> HashMap<AddonErrorKind, usize> {
+        self.samples.iter().filter_map(|sample| sample.error).fold(
+            HashMap::new(),
+            |mut error_kinds, error| {
+                *error_kinds.entry(error).or_default() += 1;
+                error_kinds
+            },
+        )
+    }
+    /// `None` if there are no recent requests to the addon.
+    pub fn status(&self) -> Option<AddonHealthStatus> {
+        let latency = self.latency()?;
+        if self.error_rate() >= ADDON_FAILING_ERROR_RATE {
+            Some(AddonHealthStatus::Failing)
+        } else if latency >= ADDON_SLOW_LATENCY {
+            Some(AddonHealthStatus::Slow)
+        } else {
+            Some(AddonHealthStatus::Healthy)
+        }
+    }
+}
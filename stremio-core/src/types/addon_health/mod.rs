@@ -0,0 +1,5 @@
+mod addon_health_bucket;
+pub use addon_health_bucket::*;
+
+mod addon_stats;
+pub use addon_stats::*;
@@ -3,6 +3,7 @@ use core::fmt;
 use crate::constants::{API_URL, LINK_API_URL};
 use crate::types::addon::Descriptor;
 use crate::types::library::LibraryItem;
+use crate::types::lists::List;
 use crate::types::profile::{AuthKey, GDPRConsent, Password, User};
 use crate::types::resource::SeriesInfo;
 use chrono::{DateTime, Local};
@@ -314,7 +315,9 @@ impl FetchRequestParams<DatastoreRequest> for DatastoreRequest {
         match &self.command {
             DatastoreCommand::Meta => "datastoreMeta".to_owned(),
             DatastoreCommand::Get { .. } => "datastoreGet".to_owned(),
-            DatastoreCommand::Put { .. } => "datastorePut".to_owned(),
+            DatastoreCommand::Put { .. } | DatastoreCommand::PutLists { .. } => {
+                "datastorePut".to_owned()
+            }
         }
     }
     fn query(&self) -> Option<String> {
@@ -341,6 +344,13 @@ pub enum DatastoreCommand {
         #[serde(default)]
         changes: Vec<LibraryItem>,
     },
+    /// Put the changes of the [`LISTS_COLLECTION_NAME`] collection
+    ///
+    /// [`LISTS_COLLECTION_NAME`]: crate::constants::LISTS_COLLECTION_NAME
+    PutLists {
+        #[serde(default)]
+        changes: Vec<List>,
+    },
 }
 
 #[cfg(test)]
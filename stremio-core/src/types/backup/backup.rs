@@ -0,0 +1,121 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::constants::{BACKUP_VERSION, SCHEMA_VERSION};
+
+/// A snapshot of the buckets which are persisted in the storage.
+///
+/// The buckets are kept as they are stored, so a backup of an older [`SCHEMA_VERSION`]
+/// is migrated with the storage schema migrations before it's restored.
+///
+/// # Examples
+///
+/// ```
+/// use chrono::Utc;
+/// use stremio_core::types::backup::Backup;
+///
+/// let buckets = [("search_history".to_owned(), serde_json::json!({ "uid": null, "items": {} }))];
+/// let mut backup = Backup::new(buckets.into(), Utc::now());
+/// assert!(backup.is_valid_checksum());
+///
+/// backup.buckets.clear();
+/// assert!(!backup.is_valid_checksum());
+/// ```
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Backup {
+    /// The version of the backup format
+    pub version: u32,
+    /// The storage schema version of the buckets
+    pub schema_version: u32,
+    pub created: DateTime<Utc>,
+    /// Hex encoded SHA-256 of the buckets, with the keys of their objects sorted
+    pub checksum: String,
+    /// The buckets by their storage key
+    pub buckets: BTreeMap<String, serde_json::Value>,
+}
+
+impl Backup {
+    pub fn new(buckets: BTreeMap<String, serde_json::Value>, created: DateTime<Utc>) -> Self {
+        Self {
+            version: BACKUP_VERSION,
+            schema_version: SCHEMA_VERSION,
+            created,
+            checksum: checksum(&buckets),
+            buckets,
+        }
+    }
+    /// Whether the buckets were not modified or truncated since the backup was created
+    pub fn is_valid_checksum(&self) -> bool {
+        self.checksum == checksum(&self.buckets)
+    }
+}
+
+/// How the restored buckets are combined with the current ones
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Debug)]
+pub enum RestoreMode {
+    /// Keeps the most recent items of both
+    Merge,
+    /// Replaces the current items with the restored ones
+    Replace,
+}
+
+/// The order of the object keys depends on the `preserve_order` feature of `serde_json`,
+/// so they are sorted before the buckets are hashed.
+fn checksum(buckets: &BTreeMap<String, Value>) -> String {
+    let buckets = buckets
+        .iter()
+        .map(|(key, value)| (key, canonicalize(value)))
+        .collect::<BTreeMap<_, _>>();
+    let buckets = serde_json::to_string(&buckets).expect("Failed to serialize backup buckets");
+    hex::encode(Sha256::digest(buckets.as_bytes()))
+}
+
+fn canonicalize(value: &Value) -> Value {
+    match value {
+        Value::Object(object) => Value::Object(
+            object
+                .iter()
+                .sorted_by(|(a, _), (b, _)| a.cmp(b))
+                .map(|(key, value)| (key.to_owned(), canonicalize(value)))
+                .collect(),
+        ),
+        Value::Array(values) => Value::Array(values.iter().map(canonicalize).collect()),
+        value => value.to_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use serde_json::{json, Map, Value};
+
+    use super::checksum;
+
+    #[test]
+    fn checksum_ignores_key_order() {
+        let object = |keys: &[&str]| {
+            Value::Object(
+                keys.iter()
+                    .map(|key| {
+                        (
+                            key.to_string(),
+                            json!({ "b": [{ "d": 1, "c": 2 }], "a": key }),
+                        )
+                    })
+                    .collect::<Map<_, _>>(),
+            )
+        };
+        let buckets = |keys: &[&str]| BTreeMap::from([("library".to_owned(), object(keys))]);
+        assert_eq!(
+            checksum(&buckets(&["tt1", "tt2"])),
+            checksum(&buckets(&["tt2", "tt1"]))
+        );
+    }
+}
@@ -0,0 +1,2 @@
+mod backup;
+pub use backup::*;
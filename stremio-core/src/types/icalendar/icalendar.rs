@@ -0,0 +1,163 @@
+use std::fmt::{self, Write};
+
+use chrono::{DateTime, NaiveDate, Utc};
+
+use crate::constants::ICALENDAR_PRODUCT_ID;
+
+/// The maximum length of a content line in octets, excluding the line break
+const LINE_LENGTH: usize = 75;
+
+/// An iCalendar ([RFC 5545](https://www.rfc-editor.org/rfc/rfc5545)) object
+/// with all-day events.
+///
+/// # Examples
+///
+/// ```
+/// use chrono::{NaiveDate, TimeZone, Utc};
+/// use stremio_core::types::icalendar::{ICalendar, ICalendarEvent};
+///
+/// let icalendar = ICalendar {
+///     name: "Stremio".to_owned(),
+///     events: vec![ICalendarEvent {
+///         uid: "tt1:1:2@stremio".to_owned(),
+///         stamp: Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap(),
+///         date: NaiveDate::from_ymd_opt(2024, 1, 2).unwrap(),
+///         summary: "Series; S01E02".to_owned(),
+///         description: None,
+///         url: None,
+///     }],
+/// };
+/// let ics = icalendar.to_string();
+/// assert!(ics.contains("DTSTART;VALUE=DATE:20240102\r\n"));
+/// assert!(ics.contains("SUMMARY:Series\\; S01E02\r\n"));
+/// ```
+#[derive(Default, Clone, PartialEq, Eq, Debug)]
+pub struct ICalendar {
+    pub name: String,
+    pub events: Vec<ICalendarEvent>,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ICalendarEvent {
+    /// Stays the same between the exports, so the subscribed calendars update the event
+    pub uid: String,
+    /// The time at which the event was exported
+    pub stamp: DateTime<Utc>,
+    pub date: NaiveDate,
+    pub summary: String,
+    pub description: Option<String>,
+    pub url: Option<String>,
+}
+
+impl fmt::Display for ICalendar {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_line(f, "BEGIN", "VCALENDAR")?;
+        write_line(f, "VERSION", "2.0")?;
+        write_line(f, "PRODID", ICALENDAR_PRODUCT_ID)?;
+        write_line(f, "CALSCALE", "GREGORIAN")?;
+        write_line(f, "METHOD", "PUBLISH")?;
+        write_line(f, "X-WR-CALNAME", &escape(&self.name))?;
+        for event in &self.events {
+            write!(f, "{event}")?;
+        }
+        write_line(f, "END", "VCALENDAR")
+    }
+}
+
+impl fmt::Display for ICalendarEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_line(f, "BEGIN", "VEVENT")?;
+        write_line(f, "UID", &escape(&self.uid))?;
+        write_line(
+            f,
+            "DTSTAMP",
+            &self.stamp.format("%Y%m%dT%H%M%SZ").to_string(),
+        )?;
+        write_line(f, "DTSTART;VALUE=DATE", &format_date(self.date))?;
+        if let Some(end) = self.date.succ_opt() {
+            write_line(f, "DTEND;VALUE=DATE", &format_date(end))?;
+        }
+        write_line(f, "SUMMARY", &escape(&self.summary))?;
+        if let Some(description) = &self.description {
+            write_line(f, "DESCRIPTION", &escape(description))?;
+        }
+        if let Some(url) = &self.url {
+            write_line(f, "URL", url)?;
+        }
+        write_line(f, "TRANSP", "TRANSPARENT")?;
+        write_line(f, "END", "VEVENT")
+    }
+}
+
+fn format_date(date: NaiveDate) -> String {
+    date.format("%Y%m%d").to_string()
+}
+
+/// Escapes the special characters of a TEXT value
+fn escape(text: &str) -> String {
+    text.chars()
+        .fold(String::with_capacity(text.len()), |mut result, char| {
+            match char {
+                '\\' | ';' | ',' => {
+                    result.push('\\');
+                    result.push(char);
+                }
+                '\n' => result.push_str("\\n"),
+                '\r' => {}
+                _ => result.push(char),
+            };
+            result
+        })
+}
+
+/// Writes a content line, folded into lines of at most [`LINE_LENGTH`] octets
+/// without splitting the UTF-8 characters.
+fn write_line(f: &mut fmt::Formatter<'_>, name: &str, value: &str) -> fmt::Result {
+    let line = format!("{name}:{value}");
+    let mut length = 0;
+    for char in line.chars() {
+        if length + char.len_utf8() > LINE_LENGTH {
+            f.write_str("\r\n ")?;
+            length = 1;
+        }
+        f.write_char(char)?;
+        length += char.len_utf8();
+    }
+    f.write_str("\r\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDate, TimeZone, Utc};
+
+    use super::{ICalendar, ICalendarEvent};
+
+    #[test]
+    fn icalendar_folds_long_lines() {
+        let icalendar = ICalendar {
+            name: "Stremio".to_owned(),
+            events: vec![ICalendarEvent {
+                uid: "tt1:1:2@stremio".to_owned(),
+                stamp: Utc.with_ymd_and_hms(2024, 1, 1, 12, 30, 0).unwrap(),
+                date: NaiveDate::from_ymd_opt(2024, 12, 31).unwrap(),
+                summary: "Сериал".repeat(10),
+                description: Some("First line\nSecond, line".to_owned()),
+                url: None,
+            }],
+        };
+        let ics = icalendar.to_string();
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+        assert!(ics.contains("DTSTAMP:20240101T123000Z\r\n"));
+        assert!(ics.contains("DTEND;VALUE=DATE:20250101\r\n"));
+        assert!(ics.contains("DESCRIPTION:First line\\nSecond\\, line\r\n"));
+        assert!(ics.split("\r\n").all(|line| line.len() <= 75));
+        assert_eq!(
+            ics.replace("\r\n ", "")
+                .lines()
+                .find(|line| line.starts_with("SUMMARY:")),
+            Some(format!("SUMMARY:{}", "Сериал".repeat(10)).as_str()),
+            "Folded lines are unfolded to the same value"
+        );
+    }
+}
@@ -0,0 +1,2 @@
+mod icalendar;
+pub use icalendar::*;
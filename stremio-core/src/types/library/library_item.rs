@@ -9,7 +9,11 @@ use url::Url;
 
 use crate::{
     runtime::Env,
-    types::resource::{MetaItemBehaviorHints, MetaItemPreview, PosterShape, Video},
+    types::{
+        lists::ListItem,
+        resource::{MetaItemBehaviorHints, MetaItemPreview, PosterShape, Video},
+        tracker::TrackerEntry,
+    },
 };
 
 pub type LibraryItemId = String;
@@ -182,6 +186,45 @@ impl<E: Env + 'static> From<(&MetaItemPreview, PhantomData<E>)> for LibraryItem
     }
 }
 
+impl<E: Env + 'static> From<(&ListItem, PhantomData<E>)> for LibraryItem {
+    fn from((list_item, _): (&ListItem, PhantomData<E>)) -> Self {
+        LibraryItem {
+            id: list_item.id.to_owned(),
+            removed: true,
+            temp: true,
+            ctime: Some(E::now()),
+            mtime: E::now(),
+            state: LibraryItemState {
+                last_watched: Some(E::now()),
+                ..LibraryItemState::default()
+            },
+            name: list_item.name.to_owned(),
+            r#type: list_item.r#type.to_owned(),
+            poster: list_item.poster.to_owned(),
+            poster_shape: list_item.poster_shape.to_owned(),
+            behavior_hints: MetaItemBehaviorHints::default(),
+        }
+    }
+}
+
+impl<E: Env + 'static> From<(&TrackerEntry, PhantomData<E>)> for LibraryItem {
+    fn from((entry, _): (&TrackerEntry, PhantomData<E>)) -> Self {
+        LibraryItem {
+            id: entry.imdb_id.to_owned(),
+            removed: true,
+            temp: true,
+            ctime: Some(E::now()),
+            mtime: E::now(),
+            state: LibraryItemState::default(),
+            name: entry.name.to_owned(),
+            r#type: entry.r#type.to_owned(),
+            poster: None,
+            poster_shape: PosterShape::default(),
+            behavior_hints: MetaItemBehaviorHints::default(),
+        }
+    }
+}
+
 impl From<(&MetaItemPreview, &LibraryItem)> for LibraryItem {
     fn from((meta_item, library_item): (&MetaItemPreview, &LibraryItem)) -> Self {
         LibraryItem {
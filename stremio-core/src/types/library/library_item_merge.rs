@@ -0,0 +1,210 @@
+use std::cmp;
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::library::{LibraryItem, LibraryItemState};
+
+/// A change of a [`LibraryItem`] made on both devices which could not be merged
+/// without discarding one of the sides.
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Debug)]
+pub enum LibraryItemConflict {
+    /// The item was removed on one device while it was watched on the other.
+    ///
+    /// The item is removed and the watch progress of both devices is kept.
+    RemovedWhileWatched,
+    /// The item was played on both devices up to a different position.
+    ///
+    /// The position of the most recently watched side is kept.
+    PlaybackPosition,
+    /// The item was flagged as watched on one device and unflagged on the other.
+    ///
+    /// The flag of the most recently modified side is kept.
+    FlaggedWatched,
+}
+
+/// The result of a three-way merge of a [`LibraryItem`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct LibraryItemMerge {
+    pub item: LibraryItem,
+    pub conflicts: Vec<LibraryItemConflict>,
+}
+
+impl LibraryItem {
+    /// Merges the local and the remote changes made to the item since their last synced `base`.
+    ///
+    /// - The playback position (`video_id`, `time_offset` and `duration`) is taken from
+    ///   the side which changed it, or from the most recently watched side if both did.
+    /// - The counters (`time_watched`, `overall_time_watched` and `times_watched`)
+    ///   add up the increments of both sides.
+    /// - The watched videos are the union of the watched videos of both sides.
+    /// - The meta details (`name`, `poster`, etc.) are taken from the most recently modified side.
+    /// - Any other field is taken from the side which changed it,
+    ///   or from the most recently modified side if both did.
+    ///
+    /// Without a `base` both sides are treated as changed.
+    pub fn merge(
+        base: Option<&LibraryItem>,
+        local: &LibraryItem,
+        remote: &LibraryItem,
+    ) -> LibraryItemMerge {
+        let prefer_local = local.mtime > remote.mtime;
+        let mut conflicts = vec![];
+        let (local_state, remote_state) = (&local.state, &remote.state);
+        let base_state = base.map(|base| &base.state);
+
+        let position = |state: &LibraryItemState| {
+            (state.video_id.to_owned(), state.time_offset, state.duration)
+        };
+        let prefer_local_position = match (&local_state.last_watched, &remote_state.last_watched) {
+            (Some(local), Some(remote)) if local != remote => local > remote,
+            _ => prefer_local,
+        };
+        let (video_id, time_offset, duration) = merge_field(
+            base_state.map(position),
+            position(local_state),
+            position(remote_state),
+            prefer_local_position,
+        )
+        .record(&mut conflicts, LibraryItemConflict::PlaybackPosition);
+
+        let counter = |field: fn(&LibraryItemState) -> u64| {
+            merge_counter(
+                base_state.map(field),
+                field(local_state),
+                field(remote_state),
+            )
+        };
+        let flagged_watched = merge_field(
+            base_state.map(|state| state.flagged_watched),
+            local_state.flagged_watched,
+            remote_state.flagged_watched,
+            prefer_local,
+        )
+        .record(&mut conflicts, LibraryItemConflict::FlaggedWatched);
+        let watched = match (&local_state.watched, &remote_state.watched) {
+            (Some(local), Some(remote)) => Some(local.union(remote)),
+            (local, remote) => local.as_ref().or(remote.as_ref()).cloned(),
+        };
+        let state = LibraryItemState {
+            last_watched: cmp::max(local_state.last_watched, remote_state.last_watched),
+            time_watched: counter(|state| state.time_watched),
+            time_offset,
+            overall_time_watched: counter(|state| state.overall_time_watched),
+            times_watched: counter(|state| state.times_watched.into())
+                .try_into()
+                .unwrap_or(u32::MAX),
+            flagged_watched,
+            duration,
+            video_id,
+            watched,
+            no_notif: merge_field(
+                base_state.map(|state| state.no_notif),
+                local_state.no_notif,
+                remote_state.no_notif,
+                prefer_local,
+            )
+            .value,
+        };
+
+        let removed = merge_field(
+            base.map(|base| base.removed),
+            local.removed,
+            remote.removed,
+            prefer_local,
+        )
+        .value;
+        let removed_since_base =
+            |item: &LibraryItem| item.removed && base.map_or(true, |base| !base.removed);
+        let watched_since_base = |item: &LibraryItem| {
+            !item.removed
+                && base.map_or(true, |base| {
+                    item.state.last_watched > base.state.last_watched
+                })
+        };
+        if removed
+            && (removed_since_base(local) && watched_since_base(remote)
+                || removed_since_base(remote) && watched_since_base(local))
+        {
+            conflicts.push(LibraryItemConflict::RemovedWhileWatched);
+        }
+
+        let newer = if prefer_local { local } else { remote };
+        let item = LibraryItem {
+            id: local.id.to_owned(),
+            name: newer.name.to_owned(),
+            r#type: newer.r#type.to_owned(),
+            poster: newer.poster.to_owned(),
+            poster_shape: newer.poster_shape.to_owned(),
+            removed,
+            temp: merge_field(
+                base.map(|base| base.temp),
+                local.temp,
+                remote.temp,
+                prefer_local,
+            )
+            .value,
+            ctime: match (local.ctime, remote.ctime) {
+                (Some(local), Some(remote)) => Some(cmp::min(local, remote)),
+                (local, remote) => local.or(remote),
+            },
+            mtime: cmp::max(local.mtime, remote.mtime),
+            state,
+            behavior_hints: newer.behavior_hints.to_owned(),
+        };
+        LibraryItemMerge { item, conflicts }
+    }
+}
+
+struct MergedField<T> {
+    value: T,
+    conflict: bool,
+}
+
+impl<T> MergedField<T> {
+    fn record(self, conflicts: &mut Vec<LibraryItemConflict>, conflict: LibraryItemConflict) -> T {
+        if self.conflict {
+            conflicts.push(conflict);
+        }
+        self.value
+    }
+}
+
+/// Takes the side which changed since the `base`, or the preferred one if both did.
+fn merge_field<T: PartialEq>(
+    base: Option<T>,
+    local: T,
+    remote: T,
+    prefer_local: bool,
+) -> MergedField<T> {
+    if local == remote || base.as_ref() == Some(&remote) {
+        MergedField {
+            value: local,
+            conflict: false,
+        }
+    } else if base.as_ref() == Some(&local) {
+        MergedField {
+            value: remote,
+            conflict: false,
+        }
+    } else {
+        MergedField {
+            value: if prefer_local { local } else { remote },
+            conflict: base.is_some(),
+        }
+    }
+}
+
+/// Adds up the increments of both sides since the `base`.
+///
+/// A side which was reset below the `base` becomes the new starting point.
+fn merge_counter(base: Option<u64>, local: u64, remote: u64) -> u64 {
+    match base {
+        Some(base) => {
+            let start = cmp::min(base, cmp::min(local, remote));
+            start
+                .saturating_add(local.saturating_sub(base))
+                .saturating_add(remote.saturating_sub(base))
+        }
+        None => cmp::max(local, remote),
+    }
+}
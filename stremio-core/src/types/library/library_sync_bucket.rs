@@ -0,0 +1,65 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::types::library::{LibraryItem, LibraryItemConflict, LibraryItemId};
+use crate::types::profile::UID;
+
+/// A conflict found while merging the local and the remote changes of a [`LibraryItem`].
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct LibrarySyncConflict {
+    pub id: LibraryItemId,
+    pub conflict: LibraryItemConflict,
+    /// The local item before the merge
+    pub local: LibraryItem,
+    /// The remote item before the merge
+    pub remote: LibraryItem,
+    pub date: DateTime<Utc>,
+}
+
+/// The state of the library as it was last synced with the API.
+#[derive(Default, Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+pub struct LibrarySyncBucket {
+    /// User ID
+    pub uid: UID,
+    /// The last synced [`LibraryItem`]s, used as the base
+    /// when merging the local and the remote changes.
+    ///
+    /// [`HashMap`] Key is the [`LibraryItem`]`.id`.
+    pub bases: HashMap<LibraryItemId, LibraryItem>,
+    /// The unresolved conflicts, the latest one last.
+    pub conflicts: Vec<LibrarySyncConflict>,
+}
+
+impl LibrarySyncBucket {
+    pub fn new(uid: UID, bases: Vec<LibraryItem>) -> Self {
+        LibrarySyncBucket {
+            uid,
+            bases: bases
+                .into_iter()
+                .map(|item| (item.id.to_owned(), item))
+                .collect(),
+            conflicts: vec![],
+        }
+    }
+    pub fn update_bases(&mut self, items: Vec<LibraryItem>) {
+        self.bases
+            .extend(items.into_iter().map(|item| (item.id.to_owned(), item)));
+    }
+    /// Whether the item was changed locally since it was last synced.
+    pub fn is_changed(&self, item: &LibraryItem) -> bool {
+        self.bases.get(&item.id).map_or(true, |base| {
+            base.mtime != item.mtime || !base.eq_no_mtime(item)
+        })
+    }
+    pub fn conflicts_for<'a>(
+        &'a self,
+        id: &'a str,
+    ) -> impl Iterator<Item = &'a LibrarySyncConflict> + 'a {
+        self.conflicts
+            .iter()
+            .filter(move |conflict| conflict.id == id)
+    }
+}
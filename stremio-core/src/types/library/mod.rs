@@ -3,3 +3,9 @@ pub use library_bucket::*;
 
 mod library_item;
 pub use library_item::*;
+
+mod library_item_merge;
+pub use library_item_merge::*;
+
+mod library_sync_bucket;
+pub use library_sync_bucket::*;
@@ -0,0 +1,92 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::types::resource::{MetaItemId, MetaItemPreview, PosterShape};
+
+pub type ListId = String;
+
+/// An item of a [`List`], with the details needed to add it to the library
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ListItem {
+    pub id: MetaItemId,
+    pub name: String,
+    pub r#type: String,
+    #[serde(default)]
+    pub poster: Option<Url>,
+    #[serde(default)]
+    pub poster_shape: PosterShape,
+}
+
+impl From<&MetaItemPreview> for ListItem {
+    fn from(meta_item: &MetaItemPreview) -> Self {
+        Self {
+            id: meta_item.id.to_owned(),
+            name: meta_item.name.to_owned(),
+            r#type: meta_item.r#type.to_owned(),
+            poster: meta_item.poster.to_owned(),
+            poster_shape: meta_item.poster_shape.to_owned(),
+        }
+    }
+}
+
+/// A collection of items curated by the user, e.g. "Watch with kids".
+///
+/// The lists are stored in their own datastore collection,
+/// the removed lists are kept so that the removal is synced as well.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct List {
+    #[serde(rename = "_id")]
+    pub id: ListId,
+    pub name: String,
+    /// The items in the order chosen by the user
+    pub items: Vec<ListItem>,
+    /// The position of the list among the other lists of the user
+    pub position: usize,
+    pub removed: bool,
+    /// Creation time
+    #[serde(rename = "_ctime")]
+    pub ctime: DateTime<Utc>,
+    /// Modification time
+    #[serde(rename = "_mtime")]
+    pub mtime: DateTime<Utc>,
+}
+
+impl List {
+    pub fn contains(&self, id: &str) -> bool {
+        self.items.iter().any(|item| item.id == id)
+    }
+}
+
+/// The list as it is shared with other users, without the ids and the times of the owner.
+///
+/// # Examples
+///
+/// ```
+/// use stremio_core::types::lists::SharedList;
+///
+/// let shared_list = serde_json::from_value::<SharedList>(serde_json::json!({
+///     "name": "Halloween",
+///     "items": [{ "id": "tt0077651", "name": "Halloween", "type": "movie" }],
+/// }))
+/// .expect("Should be a valid shared list");
+///
+/// assert_eq!(shared_list.items[0].poster, None);
+/// ```
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SharedList {
+    pub name: String,
+    pub items: Vec<ListItem>,
+}
+
+impl From<&List> for SharedList {
+    fn from(list: &List) -> Self {
+        Self {
+            name: list.name.to_owned(),
+            items: list.items.to_owned(),
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+use crate::types::lists::{List, ListId};
+use crate::types::profile::UID;
+
+#[derive(Default, Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+pub struct ListsBucket {
+    /// User ID
+    pub uid: UID,
+    pub items: HashMap<ListId, List>,
+}
+
+impl ListsBucket {
+    pub fn new(uid: UID) -> Self {
+        ListsBucket {
+            uid,
+            items: HashMap::new(),
+        }
+    }
+    /// The lists which are not removed, in the order chosen by the user
+    pub fn ordered(&self) -> Vec<&List> {
+        self.items
+            .values()
+            .filter(|list| !list.removed)
+            .sorted_by(|a, b| a.position.cmp(&b.position).then(a.ctime.cmp(&b.ctime)))
+            .collect()
+    }
+    /// Merges the lists pulled from the API, keeping the most recently modified list.
+    ///
+    /// Returns the local lists which are newer or missing remotely and should be pushed.
+    pub fn merge(&mut self, remote_lists: Vec<List>) -> Vec<List> {
+        let mut push_lists = self
+            .items
+            .values()
+            .filter(|list| !remote_lists.iter().any(|remote| remote.id == list.id))
+            .cloned()
+            .collect::<Vec<_>>();
+        for remote_list in remote_lists {
+            match self.items.get(&remote_list.id) {
+                Some(list) if list.mtime > remote_list.mtime => push_lists.push(list.to_owned()),
+                Some(list) if list.mtime == remote_list.mtime => {}
+                _ => {
+                    self.items.insert(remote_list.id.to_owned(), remote_list);
+                }
+            }
+        }
+        push_lists
+    }
+}
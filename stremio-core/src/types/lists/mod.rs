@@ -0,0 +1,5 @@
+mod list;
+pub use list::*;
+
+mod lists_bucket;
+pub use lists_bucket::*;
@@ -0,0 +1,81 @@
+use serde::{Deserialize, Serialize};
+
+use crate::constants::{DEFAULT_LOCAL_PROFILE_ID, DEFAULT_LOCAL_PROFILE_NAME};
+use crate::types::profile::{Settings, UID};
+
+pub type LocalProfileId = String;
+
+/// A profile of someone sharing the account on this device.
+///
+/// Each local profile has its own library, streams, search history, lists and settings.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalProfile {
+    pub id: LocalProfileId,
+    pub name: String,
+    pub settings: Settings,
+}
+
+impl LocalProfile {
+    pub fn default_profile(settings: Settings) -> Self {
+        LocalProfile {
+            id: DEFAULT_LOCAL_PROFILE_ID.to_owned(),
+            name: DEFAULT_LOCAL_PROFILE_NAME.to_owned(),
+            settings,
+        }
+    }
+    #[inline]
+    pub fn is_default(&self) -> bool {
+        self.id == DEFAULT_LOCAL_PROFILE_ID
+    }
+}
+
+/// The local profiles of the account and the one which is currently selected.
+///
+/// Only the library of the default profile is synced with the API.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalProfilesBucket {
+    /// User ID
+    pub uid: UID,
+    pub selected: LocalProfileId,
+    pub profiles: Vec<LocalProfile>,
+}
+
+impl LocalProfilesBucket {
+    pub fn new(uid: UID, settings: Settings) -> Self {
+        LocalProfilesBucket {
+            uid,
+            selected: DEFAULT_LOCAL_PROFILE_ID.to_owned(),
+            profiles: vec![LocalProfile::default_profile(settings)],
+        }
+    }
+    pub fn get(&self, id: &str) -> Option<&LocalProfile> {
+        self.profiles.iter().find(|profile| profile.id == id)
+    }
+    #[inline]
+    pub fn is_default_selected(&self) -> bool {
+        self.selected == DEFAULT_LOCAL_PROFILE_ID
+    }
+    /// The storage key of the selected profile.
+    pub fn storage_key(&self, key: &str) -> String {
+        local_profile_storage_key(key, &self.selected)
+    }
+}
+
+impl Default for LocalProfilesBucket {
+    fn default() -> Self {
+        LocalProfilesBucket::new(None, Settings::default())
+    }
+}
+
+/// Namespaces the storage key with the id of the local profile.
+///
+/// The default profile keeps the storage keys it had before the local profiles were introduced.
+pub fn local_profile_storage_key(key: &str, id: &str) -> String {
+    if id == DEFAULT_LOCAL_PROFILE_ID {
+        key.to_owned()
+    } else {
+        format!("{key}:{id}")
+    }
+}
@@ -0,0 +1,2 @@
+mod local_profiles_bucket;
+pub use local_profiles_bucket::*;
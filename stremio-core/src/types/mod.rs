@@ -1,17 +1,28 @@
 pub mod addon;
+pub mod addon_health;
 pub mod api;
+pub mod backup;
 pub mod events;
+pub mod icalendar;
 pub mod library;
+pub mod lists;
+pub mod local_profiles;
 pub mod notifications;
+pub mod outbox;
 pub mod player;
 pub mod profile;
 pub mod rating;
 pub mod resource;
 pub mod search_history;
 pub mod server_urls;
+pub mod stream_probes;
 pub mod streaming_server;
 pub mod streams;
+pub mod subtitles;
+pub mod taste;
 pub mod torrent;
+pub mod tracker;
+pub mod watch_party;
 
 // Re-export of stremio_watched_bitfield crate
 pub mod watched_bitfield {
@@ -25,6 +36,9 @@ pub use query_params_encode::*;
 mod serde_as_ext;
 pub use serde_as_ext::*;
 
+mod random;
+pub use random::*;
+
 mod r#true;
 pub use r#true::*;
 
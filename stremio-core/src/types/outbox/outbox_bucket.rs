@@ -0,0 +1,120 @@
+use std::cmp;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::constants::{OUTBOX_RETRY_BASE_DELAY, OUTBOX_RETRY_MAX_DELAY};
+use crate::types::outbox::OutboxRequest;
+use crate::types::profile::{AuthKey, UID};
+
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct OutboxItem {
+    pub request: OutboxRequest,
+    /// The number of failed attempts to push the request,
+    /// `0` if it is being pushed for the first time.
+    pub attempts: u32,
+    /// The request will not be retried automatically before this date.
+    pub retry_at: DateTime<Utc>,
+    /// The request is being pushed, it's not pushed again until the result is received.
+    #[serde(skip)]
+    pub in_flight: bool,
+}
+
+/// The changes which are not yet pushed to the API, e.g. while offline.
+///
+/// There is at most one [`OutboxItem`] for each kind of [`OutboxRequest`],
+/// newer changes are coalesced into it.
+#[derive(Default, Clone, PartialEq, Serialize, Deserialize, Debug)]
+pub struct OutboxBucket {
+    /// User ID
+    pub uid: UID,
+    pub items: Vec<OutboxItem>,
+}
+
+impl OutboxBucket {
+    pub fn new(uid: UID) -> Self {
+        OutboxBucket { uid, items: vec![] }
+    }
+    /// Adds the request to the outbox, coalescing it with any pending request of the same kind.
+    ///
+    /// The coalesced request keeps the backoff of the pending one,
+    /// see [`OutboxBucket::take_due_requests`] for the requests which should be pushed.
+    pub fn enqueue(&mut self, request: OutboxRequest, now: DateTime<Utc>) {
+        match self
+            .items
+            .iter_mut()
+            .find(|item| item.request.is_same_kind(&request))
+        {
+            Some(item) => item.request.coalesce(request),
+            None => self.items.push(OutboxItem {
+                request,
+                attempts: 0,
+                retry_at: now,
+                in_flight: false,
+            }),
+        }
+    }
+    /// Removes the changes which were pushed successfully.
+    ///
+    /// The changes coalesced while the request was in flight are due to be pushed right away.
+    pub fn acknowledge(&mut self, pushed: &OutboxRequest) {
+        self.items.retain_mut(|item| {
+            if !item.request.is_same_kind(pushed) {
+                return true;
+            }
+            item.in_flight = false;
+            item.attempts = 0;
+            !item.request.acknowledge(pushed)
+        });
+    }
+    /// Schedules the next attempt of a failed request with an exponential backoff.
+    ///
+    /// Returns the failed item.
+    pub fn retry_later(
+        &mut self,
+        failed: &OutboxRequest,
+        now: DateTime<Utc>,
+    ) -> Option<&OutboxItem> {
+        let item = self
+            .items
+            .iter_mut()
+            .find(|item| item.request.is_same_kind(failed))?;
+        let delay = OUTBOX_RETRY_BASE_DELAY
+            .checked_mul(2_i32.saturating_pow(item.attempts))
+            .unwrap_or(OUTBOX_RETRY_MAX_DELAY);
+        item.in_flight = false;
+        item.attempts = item.attempts.saturating_add(1);
+        item.retry_at = now + cmp::min(delay, OUTBOX_RETRY_MAX_DELAY);
+        Some(item)
+    }
+    /// Removes the request of the same kind, e.g. when it was rejected by the API.
+    pub fn discard(&mut self, rejected: &OutboxRequest) {
+        self.items
+            .retain(|item| !item.request.is_same_kind(rejected));
+    }
+    /// Marks the requests of the user which are due to be pushed as in flight and returns them.
+    pub fn take_due_requests(
+        &mut self,
+        now: DateTime<Utc>,
+        auth_key: &AuthKey,
+    ) -> Vec<OutboxRequest> {
+        self.items
+            .iter_mut()
+            .filter(|item| {
+                !item.in_flight && item.retry_at <= now && item.request.auth_key() == auth_key
+            })
+            .map(|item| {
+                item.in_flight = true;
+                item.request.to_owned()
+            })
+            .collect()
+    }
+    /// The earliest retry of the failed requests which are not in flight.
+    pub fn next_retry(&self) -> Option<&OutboxItem> {
+        self.items
+            .iter()
+            .filter(|item| !item.in_flight && item.attempts > 0)
+            .min_by_key(|item| item.retry_at)
+    }
+}
@@ -0,0 +1,120 @@
+use serde::{Deserialize, Serialize};
+
+use crate::types::addon::Descriptor;
+use crate::types::library::LibraryItem;
+use crate::types::lists::List;
+use crate::types::profile::{AuthKey, User};
+
+/// A change which has to be pushed to the API.
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum OutboxRequest {
+    /// Pushed with the `datastorePut` [`DatastoreRequest`](crate::types::api::DatastoreRequest)
+    #[serde(rename_all = "camelCase")]
+    LibraryItems {
+        auth_key: AuthKey,
+        items: Vec<LibraryItem>,
+    },
+    /// Pushed with the `datastorePut` [`DatastoreRequest`](crate::types::api::DatastoreRequest)
+    #[serde(rename_all = "camelCase")]
+    Lists { auth_key: AuthKey, lists: Vec<List> },
+    /// Pushed with the [`APIRequest::AddonCollectionSet`](crate::types::api::APIRequest::AddonCollectionSet)
+    #[serde(rename_all = "camelCase")]
+    Addons {
+        auth_key: AuthKey,
+        addons: Vec<Descriptor>,
+    },
+    /// Pushed with the [`APIRequest::SaveUser`](crate::types::api::APIRequest::SaveUser)
+    #[serde(rename_all = "camelCase")]
+    User { auth_key: AuthKey, user: User },
+}
+
+impl OutboxRequest {
+    pub fn auth_key(&self) -> &AuthKey {
+        match self {
+            OutboxRequest::LibraryItems { auth_key, .. }
+            | OutboxRequest::Lists { auth_key, .. }
+            | OutboxRequest::Addons { auth_key, .. }
+            | OutboxRequest::User { auth_key, .. } => auth_key,
+        }
+    }
+    /// Whether both requests push the same kind of change for the same session
+    /// and can be coalesced into a single request.
+    pub fn is_same_kind(&self, other: &OutboxRequest) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other)
+            && self.auth_key() == other.auth_key()
+    }
+    /// Coalesces a newer request of the same kind into this one.
+    ///
+    /// The library items and the lists are merged by id, keeping the most recently modified one.
+    /// The addons and the user are replaced, as they are always pushed in full.
+    pub fn coalesce(&mut self, next: OutboxRequest) {
+        match (self, next) {
+            (
+                OutboxRequest::LibraryItems { items, .. },
+                OutboxRequest::LibraryItems {
+                    items: next_items, ..
+                },
+            ) => {
+                for next_item in next_items {
+                    match items.iter_mut().find(|item| item.id == next_item.id) {
+                        Some(item) if item.mtime <= next_item.mtime => *item = next_item,
+                        Some(_) => {}
+                        None => items.push(next_item),
+                    }
+                }
+            }
+            (
+                OutboxRequest::Lists { lists, .. },
+                OutboxRequest::Lists {
+                    lists: next_lists, ..
+                },
+            ) => {
+                for next_list in next_lists {
+                    match lists.iter_mut().find(|list| list.id == next_list.id) {
+                        Some(list) if list.mtime <= next_list.mtime => *list = next_list,
+                        Some(_) => {}
+                        None => lists.push(next_list),
+                    }
+                }
+            }
+            (request, next) => *request = next,
+        }
+    }
+    /// Removes the changes which were pushed with the given request.
+    ///
+    /// Returns `true` if nothing is left to be pushed.
+    pub fn acknowledge(&mut self, pushed: &OutboxRequest) -> bool {
+        match (self, pushed) {
+            (
+                OutboxRequest::LibraryItems { items, .. },
+                OutboxRequest::LibraryItems {
+                    items: pushed_items,
+                    ..
+                },
+            ) => {
+                items.retain(|item| {
+                    !pushed_items.iter().any(|pushed_item| {
+                        pushed_item.id == item.id && pushed_item.mtime >= item.mtime
+                    })
+                });
+                items.is_empty()
+            }
+            (
+                OutboxRequest::Lists { lists, .. },
+                OutboxRequest::Lists {
+                    lists: pushed_lists,
+                    ..
+                },
+            ) => {
+                lists.retain(|list| {
+                    !pushed_lists.iter().any(|pushed_list| {
+                        pushed_list.id == list.id && pushed_list.mtime >= list.mtime
+                    })
+                });
+                lists.is_empty()
+            }
+            (request, pushed) => request == pushed,
+        }
+    }
+}
@@ -1,6 +1,9 @@
 mod auth;
 pub use auth::*;
 
+mod parental_controls;
+pub use parental_controls::*;
+
 mod profile;
 pub use profile::*;
 
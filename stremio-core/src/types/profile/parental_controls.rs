@@ -0,0 +1,290 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::constants::{
+    GENRES_LINK_CATEGORY, PARENTAL_CONTROLS_PIN_ATTEMPTS, PARENTAL_CONTROLS_PIN_BASE_DELAY,
+    PARENTAL_CONTROLS_PIN_HASH_ROUNDS, PARENTAL_CONTROLS_PIN_MAX_DELAY,
+};
+use crate::runtime::Env;
+use crate::types::addon::ResourcePath;
+use crate::types::library::LibraryItem;
+use crate::types::resource::{MetaItem, MetaItemPreview};
+
+/// Content which can be restricted by the [`ParentalControls`].
+pub trait Restrictable {
+    fn content_type(&self) -> &str;
+    /// The certification of the content, e.g. `PG-13`, `TV-MA` or `16`
+    fn certification(&self) -> Option<&str> {
+        None
+    }
+    fn genres(&self) -> Vec<&str> {
+        vec![]
+    }
+}
+
+impl Restrictable for MetaItemPreview {
+    fn content_type(&self) -> &str {
+        &self.r#type
+    }
+    fn certification(&self) -> Option<&str> {
+        self.certification.as_deref()
+    }
+    fn genres(&self) -> Vec<&str> {
+        self.links
+            .iter()
+            .filter(|link| link.category == GENRES_LINK_CATEGORY)
+            .map(|link| link.name.as_str())
+            .collect()
+    }
+}
+
+impl Restrictable for MetaItem {
+    fn content_type(&self) -> &str {
+        self.preview.content_type()
+    }
+    fn certification(&self) -> Option<&str> {
+        self.preview.certification()
+    }
+    fn genres(&self) -> Vec<&str> {
+        self.preview.genres()
+    }
+}
+
+/// The library doesn't keep the certification and the genres,
+/// so only the type restrictions apply to it.
+impl Restrictable for LibraryItem {
+    fn content_type(&self) -> &str {
+        &self.r#type
+    }
+}
+
+/// Only the type restrictions apply to the requested resources,
+/// e.g. before loading the [`MetaItem`] of the player.
+impl Restrictable for ResourcePath {
+    fn content_type(&self) -> &str {
+        &self.r#type
+    }
+}
+
+#[derive(Default, Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ParentalRestrictions {
+    /// Content certified for an older audience is blocked
+    pub max_age: Option<u8>,
+    pub blocked_genres: Vec<String>,
+    pub blocked_types: Vec<String>,
+}
+
+/// Salted PBKDF2-HMAC-SHA256 hash of the parental controls PIN.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PinHash {
+    /// Hex encoded random salt, generated for every new PIN
+    pub salt: String,
+    pub rounds: u32,
+    /// Hex encoded derived key
+    pub hash: String,
+}
+
+impl PinHash {
+    pub fn new<E: Env>(pin: &str) -> Self {
+        let salt = E::random_buffer(PIN_SALT_LEN);
+        PinHash {
+            salt: hex::encode(&salt),
+            rounds: PARENTAL_CONTROLS_PIN_HASH_ROUNDS,
+            hash: hex::encode(derive_key(pin, &salt, PARENTAL_CONTROLS_PIN_HASH_ROUNDS)),
+        }
+    }
+    pub fn verify(&self, pin: &str) -> bool {
+        match (hex::decode(&self.salt), hex::decode(&self.hash)) {
+            (Ok(salt), Ok(hash)) => derive_key(pin, &salt, self.rounds).as_slice() == hash,
+            _ => false,
+        }
+    }
+}
+
+/// Restricts the content of a profile shared with children.
+///
+/// The restrictions apply while the controls are enabled, i.e. there is a PIN, and locked,
+/// unlocking them requires the PIN.
+#[derive(Default, Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ParentalControls {
+    /// `None` when the controls are disabled.
+    ///
+    /// Never serialized for the UI, the profile is written to the storage
+    /// with [`Profile::to_storage_value`](crate::types::profile::Profile::to_storage_value).
+    #[serde(skip_serializing)]
+    pub pin_hash: Option<PinHash>,
+    pub locked: bool,
+    /// The consecutive failed PIN attempts
+    #[serde(default)]
+    pub failed_pin_attempts: u32,
+    /// The PIN can't be verified before this time after too many failed attempts
+    #[serde(default)]
+    pub pin_retry_at: Option<DateTime<Utc>>,
+    pub restrictions: ParentalRestrictions,
+}
+
+impl ParentalControls {
+    pub fn new<E: Env>(pin: &str, restrictions: ParentalRestrictions) -> Self {
+        ParentalControls {
+            pin_hash: Some(PinHash::new::<E>(pin)),
+            locked: true,
+            failed_pin_attempts: 0,
+            pin_retry_at: None,
+            restrictions,
+        }
+    }
+    #[inline]
+    pub fn is_enabled(&self) -> bool {
+        self.pin_hash.is_some()
+    }
+    #[inline]
+    pub fn is_active(&self) -> bool {
+        self.is_enabled() && self.locked
+    }
+    /// Whether the PIN attempts are delayed after too many failed ones.
+    pub fn is_pin_throttled(&self, now: DateTime<Utc>) -> bool {
+        self.pin_retry_at.is_some_and(|retry_at| retry_at > now)
+    }
+    /// Verifies the PIN and counts the failed attempts.
+    ///
+    /// Once the failed attempts exceed [`PARENTAL_CONTROLS_PIN_ATTEMPTS`] the next attempt
+    /// is delayed by [`PARENTAL_CONTROLS_PIN_BASE_DELAY`], doubled after every failed one.
+    /// Returns `false` without verifying the PIN while the attempts are delayed.
+    pub fn verify_pin(&mut self, pin: &str, now: DateTime<Utc>) -> bool {
+        if self.is_pin_throttled(now) {
+            return false;
+        }
+
+        let verified = self
+            .pin_hash
+            .as_ref()
+            .is_some_and(|pin_hash| pin_hash.verify(pin));
+        if verified {
+            self.failed_pin_attempts = 0;
+            self.pin_retry_at = None;
+        } else {
+            self.failed_pin_attempts = self.failed_pin_attempts.saturating_add(1);
+            self.pin_retry_at = self
+                .failed_pin_attempts
+                .checked_sub(PARENTAL_CONTROLS_PIN_ATTEMPTS + 1)
+                .map(|exceeded_attempts| {
+                    let delay = PARENTAL_CONTROLS_PIN_BASE_DELAY
+                        .checked_mul(2_i32.saturating_pow(exceeded_attempts.min(16)))
+                        .unwrap_or(PARENTAL_CONTROLS_PIN_MAX_DELAY)
+                        .min(PARENTAL_CONTROLS_PIN_MAX_DELAY);
+                    now + delay
+                });
+        }
+        verified
+    }
+    /// Whether the content is blocked by the active restrictions.
+    ///
+    /// Content without a certification is not blocked by the `max_age`.
+    pub fn is_blocked<T: Restrictable + ?Sized>(&self, content: &T) -> bool {
+        if !self.is_active() {
+            return false;
+        }
+
+        let restrictions = &self.restrictions;
+        let blocked_type = restrictions
+            .blocked_types
+            .iter()
+            .any(|blocked_type| blocked_type == content.content_type());
+        let blocked_genre = content.genres().iter().any(|genre| {
+            restrictions
+                .blocked_genres
+                .iter()
+                .any(|blocked_genre| blocked_genre.eq_ignore_ascii_case(genre))
+        });
+        let blocked_age = restrictions
+            .max_age
+            .zip(content.certification().and_then(certification_age))
+            .is_some_and(|(max_age, age)| age > max_age);
+        blocked_type || blocked_genre || blocked_age
+    }
+}
+
+const PIN_SALT_LEN: usize = 16;
+
+const SHA256_BLOCK_LEN: usize = 64;
+
+/// PBKDF2-HMAC-SHA256 (RFC 8018) with a 32 bytes long key, i.e. a single block
+fn derive_key(pin: &str, salt: &[u8], rounds: u32) -> [u8; 32] {
+    let hmac = Hmac::new(pin.as_bytes());
+    let mut block = hmac.digest(&[salt, &1_u32.to_be_bytes()].concat());
+    let mut key = block;
+    for _ in 1..rounds {
+        block = hmac.digest(&block);
+        key.iter_mut()
+            .zip(block)
+            .for_each(|(key_byte, block_byte)| *key_byte ^= block_byte);
+    }
+    key
+}
+
+/// HMAC-SHA256 (RFC 2104) with the padded key hashed once for all the messages
+struct Hmac {
+    inner: Sha256,
+    outer: Sha256,
+}
+
+impl Hmac {
+    fn new(key: &[u8]) -> Self {
+        let mut block_key = [0_u8; SHA256_BLOCK_LEN];
+        if key.len() > SHA256_BLOCK_LEN {
+            block_key[..32].copy_from_slice(&Sha256::digest(key));
+        } else {
+            block_key[..key.len()].copy_from_slice(key);
+        }
+        Hmac {
+            inner: Sha256::new().chain_update(block_key.map(|byte| byte ^ 0x36)),
+            outer: Sha256::new().chain_update(block_key.map(|byte| byte ^ 0x5c)),
+        }
+    }
+    fn digest(&self, message: &[u8]) -> [u8; 32] {
+        let inner = self.inner.clone().chain_update(message).finalize();
+        self.outer.clone().chain_update(inner).finalize().into()
+    }
+}
+
+/// The minimum age of the audience for the given certification.
+///
+/// Supports the US movie and TV ratings and the numeric ratings used by most
+/// of the other countries, e.g. `12A`, `FSK 16` or `18+`.
+pub fn certification_age(certification: &str) -> Option<u8> {
+    match certification.trim().to_uppercase().as_str() {
+        "G" | "U" | "TV-Y" | "TV-G" => Some(0),
+        "TV-Y7" => Some(7),
+        "PG" | "TV-PG" => Some(10),
+        "PG-13" => Some(13),
+        "TV-14" => Some(14),
+        "R" | "TV-MA" => Some(17),
+        "NC-17" | "X" => Some(18),
+        certification => certification
+            .trim_matches(|character: char| !character.is_ascii_digit())
+            .parse()
+            .ok(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::derive_key;
+
+    #[test]
+    fn derive_key_pbkdf2_hmac_sha256() {
+        // the test vectors of RFC 7914
+        assert_eq!(
+            hex::encode(derive_key("passwd", b"salt", 1)),
+            "55ac046e56e3089fec1691c22544b605f94185216dde0465e68b9d57c20dacbc"
+        );
+        assert_eq!(
+            hex::encode(derive_key("password", b"salt", 4096)),
+            "c5e478d59288c841aa530db6845c4c8d962893a001ce4e11a4963873aa98134a"
+        );
+    }
+}
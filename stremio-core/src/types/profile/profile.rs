@@ -1,7 +1,7 @@
 use crate::constants::OFFICIAL_ADDONS;
 use crate::runtime::Env;
 use crate::types::addon::Descriptor;
-use crate::types::profile::{Auth, AuthKey, Settings};
+use crate::types::profile::{Auth, AuthKey, ParentalControls, Settings};
 use crate::types::{UniqueVec, UniqueVecAdapter};
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
@@ -28,6 +28,9 @@ pub struct Profile {
     #[serde(default)]
     pub addons_locked: bool,
     pub settings: Settings,
+    /// Kept on this device through logins and logouts
+    #[serde(default)]
+    pub parental_controls: ParentalControls,
 }
 
 impl Default for Profile {
@@ -37,6 +40,7 @@ impl Default for Profile {
             addons: OFFICIAL_ADDONS.to_owned(),
             addons_locked: false,
             settings: Settings::default(),
+            parental_controls: ParentalControls::default(),
         }
     }
 }
@@ -58,6 +62,24 @@ impl Profile {
             .map(|trakt| E::now() < trakt.created_at + trakt.expires_in)
             .unwrap_or_default()
     }
+
+    /// The profile as written to the storage, including the
+    /// [`ParentalControls::pin_hash`] which is never serialized for the UI.
+    pub fn to_storage_value(&self) -> serde_json::Value {
+        let mut value = serde_json::to_value(self).expect("Profile serialization failed");
+        if let (Some(pin_hash), Some(parental_controls)) = (
+            &self.parental_controls.pin_hash,
+            value
+                .get_mut("parentalControls")
+                .and_then(|parental_controls| parental_controls.as_object_mut()),
+        ) {
+            parental_controls.insert(
+                "pinHash".to_owned(),
+                serde_json::to_value(pin_hash).expect("PinHash serialization failed"),
+            );
+        }
+        value
+    }
 }
 
 struct DescriptorUniqueVecAdapter;
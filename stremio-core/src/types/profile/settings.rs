@@ -1,4 +1,5 @@
 use crate::constants::STREAMING_SERVER_URL;
+use crate::types::resource::{StreamResolution, VideoCodec};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use url::Url;
@@ -45,6 +46,46 @@ pub struct Settings {
     pub streaming_server_warning_dismissed: Option<DateTime<Utc>>,
     pub server_in_foreground: bool,
     pub send_crash_reports: bool,
+    /// Used for ranking the streams of all addons
+    pub stream_preferences: StreamPreferences,
+    /// How the stream of the next video is selected when binge watching
+    pub auto_play_policy: AutoPlayPolicy,
+    /// Probe the best ranked streams before playing them for hiding the dead links
+    pub stream_health_check: bool,
+}
+
+/// The preferences used to rank the streams, see [`StreamInfo`].
+///
+/// [`StreamInfo`]: crate::types::resource::StreamInfo
+#[derive(Default, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamPreferences {
+    /// Streams with a higher resolution are ranked below the ones with unknown resolution
+    pub max_resolution: Option<StreamResolution>,
+    pub prefer_hdr: bool,
+    /// The most preferred codec first
+    pub preferred_codecs: Vec<VideoCodec>,
+    /// Streams bigger than this size (in bytes) are ranked last
+    pub max_size: Option<u64>,
+    /// ISO 639-2 codes of the preferred audio languages, the most preferred first.
+    ///
+    /// If empty the `audio_language` and `secondary_audio_language` are used.
+    pub preferred_languages: Vec<String>,
+}
+
+/// The policy for selecting the stream of the next video.
+///
+/// Every policy, except [`AutoPlayPolicy::Off`], prefers the stream from the same binge group.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AutoPlayPolicy {
+    /// Play only a stream from the same binge group
+    StrictBingeGroup,
+    /// Fallback to the first stream of the same addon
+    SameAddon,
+    /// Fallback to the stream of the same addon with the closest quality and filename
+    BestMatch,
+    /// Never select the stream of the next video
+    Off,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -90,6 +131,9 @@ impl Default for Settings {
             streaming_server_warning_dismissed: None,
             server_in_foreground: false,
             send_crash_reports: true,
+            stream_preferences: StreamPreferences::default(),
+            auto_play_policy: AutoPlayPolicy::StrictBingeGroup,
+            stream_health_check: false,
         }
     }
 }
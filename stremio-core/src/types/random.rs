@@ -0,0 +1,13 @@
+use crate::runtime::Env;
+
+/// Cryptographically secure random bytes, e.g. for salts and ids.
+pub fn random_buffer(len: usize) -> Vec<u8> {
+    let mut buffer = vec![0u8; len];
+    getrandom::getrandom(buffer.as_mut_slice()).expect("generate random buffer failed");
+    buffer
+}
+
+/// A random 128 bit id encoded as hex, unique across devices.
+pub fn random_id<E: Env>() -> String {
+    hex::encode(E::random_buffer(16))
+}
@@ -101,6 +101,8 @@ struct MetaItemPreviewLegacy {
     #[serde(default)]
     #[serde_as(deserialize_as = "DefaultOnNull")]
     behavior_hints: MetaItemBehaviorHints,
+    #[serde(default)]
+    certification: Option<String>,
 }
 
 ///
@@ -122,6 +124,7 @@ struct MetaItemPreviewLegacy {
 ///     links: vec![],
 ///     trailer_streams: vec![],
 ///     behavior_hints: MetaItemBehaviorHints::default(),
+///     certification: None,
 /// };
 ///
 /// let null_fields = serde_json::json!({
@@ -164,6 +167,9 @@ pub struct MetaItemPreview {
     pub links: Vec<Link>,
     pub trailer_streams: Vec<Stream>,
     pub behavior_hints: MetaItemBehaviorHints,
+    /// The content rating, e.g. `PG-13`, used by the parental controls
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub certification: Option<String>,
 }
 
 impl From<MetaItemPreviewLegacy> for MetaItemPreview {
@@ -237,6 +243,7 @@ impl From<MetaItemPreviewLegacy> for MetaItemPreview {
             links,
             trailer_streams,
             behavior_hints: legacy_item.behavior_hints,
+            certification: legacy_item.certification,
         }
     }
 }
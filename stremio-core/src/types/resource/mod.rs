@@ -4,5 +4,8 @@ pub use meta_item::*;
 mod stream;
 pub use stream::*;
 
+mod stream_info;
+pub use stream_info::*;
+
 mod subtitles;
 pub use subtitles::*;
@@ -0,0 +1,215 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::types::resource::Stream;
+
+/// The video resolution of a [`Stream`], ordered from the lowest to the highest.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, Debug)]
+pub enum StreamResolution {
+    #[serde(rename = "480p")]
+    SD,
+    #[serde(rename = "720p")]
+    HD,
+    #[serde(rename = "1080p")]
+    FullHD,
+    #[serde(rename = "1440p")]
+    QHD,
+    #[serde(rename = "2160p")]
+    UHD,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Debug)]
+pub enum VideoCodec {
+    H264,
+    H265,
+    AV1,
+    VP9,
+}
+
+/// The details of a [`Stream`] which addons usually put in its `name`, `description`
+/// and [`StreamBehaviorHints`](crate::types::resource::StreamBehaviorHints).
+#[derive(Default, Clone, PartialEq, Eq, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamInfo {
+    pub resolution: Option<StreamResolution>,
+    pub hdr: bool,
+    pub codec: Option<VideoCodec>,
+    pub audio_channels: Option<u8>,
+    /// Size of the video in bytes
+    pub size: Option<u64>,
+    pub seeders: Option<u32>,
+    /// ISO 639-2 codes of the audio languages
+    pub languages: Vec<String>,
+}
+
+static RESOLUTIONS: Lazy<Vec<(StreamResolution, Regex)>> = Lazy::new(|| {
+    vec![
+        (
+            StreamResolution::UHD,
+            Regex::new(r"(?i)\b(2160p|4k|uhd)\b").unwrap(),
+        ),
+        (StreamResolution::QHD, Regex::new(r"(?i)\b1440p\b").unwrap()),
+        (
+            StreamResolution::FullHD,
+            Regex::new(r"(?i)\b(1080p|fhd)\b").unwrap(),
+        ),
+        (StreamResolution::HD, Regex::new(r"(?i)\b720p\b").unwrap()),
+        (
+            StreamResolution::SD,
+            Regex::new(r"(?i)\b(480p|576p|dvdrip)\b").unwrap(),
+        ),
+    ]
+});
+static CODECS: Lazy<Vec<(VideoCodec, Regex)>> = Lazy::new(|| {
+    vec![
+        (
+            VideoCodec::H265,
+            Regex::new(r"(?i)\b(x265|h\.?265|hevc)\b").unwrap(),
+        ),
+        (
+            VideoCodec::H264,
+            Regex::new(r"(?i)\b(x264|h\.?264|avc)\b").unwrap(),
+        ),
+        (VideoCodec::AV1, Regex::new(r"(?i)\bav1\b").unwrap()),
+        (VideoCodec::VP9, Regex::new(r"(?i)\bvp9\b").unwrap()),
+    ]
+});
+static HDR_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)\b(hdr(10)?|dv|dovi|dolby[ .]?vision)\b").unwrap());
+static SIZE_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)\b(\d+(?:[.,]\d+)?)\s*(tb|gb|mb|kb)\b").unwrap());
+static AUDIO_CHANNELS_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\b([1-9])[.]([01])(?:ch)?\b").unwrap());
+static ATMOS_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?i)\batmos\b").unwrap());
+static SEEDERS_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)(?:👤|\bseed(?:er)?s?\b:?)\s*(\d+)").unwrap());
+
+/// The ISO 639-2 code, the names and the flags of the languages recognized in streams.
+const LANGUAGES: &[(&str, &[&str], &[&str])] = &[
+    ("eng", &["english", "eng"], &["🇬🇧", "🇺🇸"]),
+    ("ita", &["italian", "ita"], &["🇮🇹"]),
+    ("fre", &["french", "fre", "fra", "vff"], &["🇫🇷"]),
+    (
+        "spa",
+        &["spanish", "spa", "esp", "castellano"],
+        &["🇪🇸", "🇲🇽"],
+    ),
+    ("ger", &["german", "ger", "deu"], &["🇩🇪"]),
+    ("por", &["portuguese", "por"], &["🇵🇹", "🇧🇷"]),
+    ("rus", &["russian", "rus"], &["🇷🇺"]),
+    ("pol", &["polish", "pol"], &["🇵🇱"]),
+    ("dut", &["dutch", "dut", "nld"], &["🇳🇱"]),
+    ("tur", &["turkish", "tur"], &["🇹🇷"]),
+    ("bul", &["bulgarian", "bul"], &["🇧🇬"]),
+    ("ara", &["arabic", "ara"], &["🇸🇦"]),
+    ("hin", &["hindi", "hin"], &["🇮🇳"]),
+    ("jpn", &["japanese", "jpn"], &["🇯🇵"]),
+    ("kor", &["korean", "kor"], &["🇰🇷"]),
+    ("chi", &["chinese", "chi", "zho"], &["🇨🇳", "🇹🇼"]),
+];
+
+impl From<&Stream> for StreamInfo {
+    /// Extracts the details from the `name`, the `description` and the `filename` of the stream.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stremio_core::types::resource::{
+    ///     Stream, StreamBehaviorHints, StreamInfo, StreamResolution, StreamSource, VideoCodec,
+    /// };
+    ///
+    /// let stream = Stream {
+    ///     source: StreamSource::Url {
+    ///         url: "https://example.com/video.mkv".parse().unwrap(),
+    ///     },
+    ///     name: Some("Example\n4k HDR".to_owned()),
+    ///     description: Some("Movie.2160p.x265.DDP.5.1\n👤 42 💾 12.5 GB\n🇬🇧 / 🇮🇹".to_owned()),
+    ///     thumbnail: None,
+    ///     subtitles: vec![],
+    ///     behavior_hints: StreamBehaviorHints::default(),
+    /// };
+    ///
+    /// let info = StreamInfo::from(&stream);
+    /// assert_eq!(info.resolution, Some(StreamResolution::UHD));
+    /// assert!(info.hdr);
+    /// assert_eq!(info.codec, Some(VideoCodec::H265));
+    /// assert_eq!(info.audio_channels, Some(6));
+    /// assert_eq!(info.size, Some(12_500_000_000));
+    /// assert_eq!(info.seeders, Some(42));
+    /// assert_eq!(info.languages, vec!["eng".to_owned(), "ita".to_owned()]);
+    /// ```
+    fn from(stream: &Stream) -> Self {
+        let text = [
+            stream.name.as_deref(),
+            stream.description.as_deref(),
+            stream.behavior_hints.filename.as_deref(),
+        ]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join("\n");
+        let size = stream.behavior_hints.video_size.or_else(|| {
+            SIZE_REGEX.captures(&text).and_then(|captures| {
+                let value = captures[1].replace(',', ".").parse::<f64>().ok()?;
+                let unit = match captures[2].to_lowercase().as_str() {
+                    "tb" => 1e12,
+                    "gb" => 1e9,
+                    "mb" => 1e6,
+                    _ => 1e3,
+                };
+                Some((value * unit).round() as u64)
+            })
+        });
+        // the sizes are removed so that `1.0 GB` is not mistaken for audio channels
+        let text_without_sizes = SIZE_REGEX.replace_all(&text, "");
+        let audio_channels = AUDIO_CHANNELS_REGEX
+            .captures(&text_without_sizes)
+            .and_then(|captures| {
+                let main = captures[1].parse::<u8>().ok()?;
+                let lfe = captures[2].parse::<u8>().ok()?;
+                Some(main + lfe)
+            })
+            .or_else(|| ATMOS_REGEX.is_match(&text).then_some(8));
+        let words = text
+            .split(|character: char| !character.is_alphanumeric())
+            .map(|word| word.to_lowercase())
+            .collect::<Vec<_>>();
+        let languages = LANGUAGES
+            .iter()
+            .filter(|(_, names, flags)| {
+                flags.iter().any(|flag| text.contains(flag))
+                    || names
+                        .iter()
+                        .any(|name| words.iter().any(|word| word == name))
+            })
+            .map(|(code, _, _)| (*code).to_owned())
+            .collect();
+        StreamInfo {
+            resolution: RESOLUTIONS
+                .iter()
+                .find(|(_, regex)| regex.is_match(&text))
+                .map(|(resolution, _)| *resolution),
+            hdr: HDR_REGEX.is_match(&text),
+            codec: CODECS
+                .iter()
+                .find(|(_, regex)| regex.is_match(&text))
+                .map(|(codec, _)| *codec),
+            audio_channels,
+            size,
+            seeders: SEEDERS_REGEX
+                .captures(&text)
+                .and_then(|captures| captures[1].parse().ok()),
+            languages,
+        }
+    }
+}
+
+/// Returns the ISO 639-2 code of a language recognized in streams by its name or code.
+pub(crate) fn language_code(name: &str) -> Option<&'static str> {
+    let name = name.to_lowercase();
+    LANGUAGES
+        .iter()
+        .find(|(code, names, _)| *code == name || names.contains(&name.as_str()))
+        .map(|(code, _, _)| *code)
+}
@@ -0,0 +1,5 @@
+mod stream_probe;
+pub use stream_probe::*;
+
+mod stream_probes_bucket;
+pub use stream_probes_bucket::*;
@@ -0,0 +1,77 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use url::Url;
+
+use crate::constants::STREAM_PROBE_TTL;
+use crate::types::resource::{Stream, StreamSource};
+
+/// The result of a pre-flight request for a stream URL.
+#[derive(Clone, PartialEq, Eq, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamProbe {
+    /// The HTTP status of the response, `None` if the request failed or was blocked
+    pub status: Option<u16>,
+    pub content_type: Option<String>,
+    /// The size of the content in bytes
+    pub content_length: Option<u64>,
+    /// Why the request failed, e.g. the host is not reachable
+    pub error: Option<String>,
+    pub checked_at: DateTime<Utc>,
+}
+
+impl StreamProbe {
+    /// A successful response which is not an HTML page is alive,
+    /// as file hosts usually reply to dead links with an error page.
+    /// Only the responses for missing content (`404`, `410`) are dead otherwise.
+    ///
+    /// The health is unknown when the request failed, as the browsers block
+    /// the cross-origin requests to the hosts which don't allow them (CORS),
+    /// and when the host refused the request for now, e.g. it requires
+    /// authorization (`401`, `403`), rate limits (`429`) or fails (`5xx`).
+    pub fn health(&self) -> StreamHealth {
+        let is_html = self
+            .content_type
+            .as_ref()
+            .is_some_and(|content_type| content_type.starts_with("text/html"));
+        match self.status {
+            Some(200..=399) if is_html => StreamHealth::Dead,
+            Some(200..=399) => StreamHealth::Alive,
+            Some(404 | 410) => StreamHealth::Dead,
+            _ => StreamHealth::Unknown,
+        }
+    }
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        now - self.checked_at >= STREAM_PROBE_TTL
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Debug)]
+pub enum StreamHealth {
+    /// Some of the URLs of the stream are not probed yet
+    Checking,
+    Alive,
+    /// Any of the URLs of the stream is not reachable
+    Dead,
+    /// Some of the URLs of the stream couldn't be probed,
+    /// e.g. the request was blocked or probing is not supported
+    Unknown,
+}
+
+/// The URLs which are requested when playing the stream:
+/// the URL of [`StreamSource::Url`] and the URLs of the archive sources.
+///
+/// Streams which are not played over HTTP, e.g. torrents, have no URLs to probe.
+pub fn stream_probe_urls(stream: &Stream) -> Vec<&Url> {
+    let urls = match &stream.source {
+        StreamSource::Url { url } => vec![url],
+        StreamSource::Rar { urls, .. }
+        | StreamSource::Zip { urls, .. }
+        | StreamSource::Zip7 { urls, .. }
+        | StreamSource::Tgz { urls, .. }
+        | StreamSource::Tar { urls, .. } => urls.iter().map(|url| &url.url).collect(),
+        _ => vec![],
+    };
+    urls.into_iter()
+        .filter(|url| matches!(url.scheme(), "http" | "https"))
+        .collect()
+}
@@ -0,0 +1,78 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, Utc};
+use itertools::Itertools;
+use url::Url;
+
+use crate::constants::STREAM_PROBES_MAX_COUNT;
+use crate::types::resource::Stream;
+use crate::types::stream_probes::{stream_probe_urls, StreamHealth, StreamProbe};
+
+/// The probes of the stream URLs, kept in memory until they expire.
+#[derive(Default, Clone, PartialEq, Eq, Debug)]
+pub struct StreamProbesBucket {
+    pub items: HashMap<Url, StreamProbe>,
+    /// The URLs which are being probed
+    pub pending: HashSet<Url>,
+}
+
+impl StreamProbesBucket {
+    /// Keeps the probe, evicting the expired probes
+    /// and the oldest ones above [`STREAM_PROBES_MAX_COUNT`].
+    pub fn insert(&mut self, url: Url, probe: StreamProbe) {
+        let now = probe.checked_at;
+        self.items.insert(url, probe);
+        self.items.retain(|_, probe| !probe.is_expired(now));
+        if self.items.len() > STREAM_PROBES_MAX_COUNT {
+            let oldest_urls = self
+                .items
+                .iter()
+                .sorted_by_key(|(_, probe)| probe.checked_at)
+                .take(self.items.len() - STREAM_PROBES_MAX_COUNT)
+                .map(|(url, _)| url.to_owned())
+                .collect::<Vec<_>>();
+            for url in oldest_urls {
+                self.items.remove(&url);
+            }
+        }
+    }
+    /// Whether the URL is neither being probed nor has a probe which is not expired.
+    pub fn needs_probe(&self, url: &Url, now: DateTime<Utc>) -> bool {
+        !self.pending.contains(url)
+            && self
+                .items
+                .get(url)
+                .map_or(true, |probe| probe.is_expired(now))
+    }
+    /// `None` if the stream has no URLs to probe or they are not requested yet.
+    ///
+    /// The health is kept while an expired probe is being renewed.
+    pub fn health(&self, stream: &Stream) -> Option<StreamHealth> {
+        let urls = stream_probe_urls(stream);
+        let probes = urls
+            .iter()
+            .filter_map(|url| self.items.get(*url))
+            .collect::<Vec<_>>();
+        if urls.is_empty() {
+            None
+        } else if probes
+            .iter()
+            .any(|probe| probe.health() == StreamHealth::Dead)
+        {
+            Some(StreamHealth::Dead)
+        } else if probes.len() == urls.len() {
+            if probes
+                .iter()
+                .all(|probe| probe.health() == StreamHealth::Alive)
+            {
+                Some(StreamHealth::Alive)
+            } else {
+                Some(StreamHealth::Unknown)
+            }
+        } else if urls.iter().any(|url| self.pending.contains(*url)) {
+            Some(StreamHealth::Checking)
+        } else {
+            None
+        }
+    }
+}
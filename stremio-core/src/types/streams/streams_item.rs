@@ -4,6 +4,7 @@ use serde_with::serde_as;
 use url::Url;
 
 use crate::types::resource::Stream;
+use crate::types::subtitles::FramerateCorrection;
 
 #[serde_as]
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -32,6 +33,9 @@ pub struct StreamItemState {
     /// In milliseconds
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub subtitle_delay: Option<i64>,
+    /// Applied before the `subtitle_delay`, e.g. proposed by the subtitles sync
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subtitle_framerate: Option<FramerateCorrection>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     /// Subtitles size, platform dependent units
     pub subtitle_size: Option<f32>,
@@ -87,7 +91,7 @@ impl StreamsItem {
     /// Retrieve adjusted stream state based on given stream:
     ///     If stream source is the same we want to retain the same state;
     ///     If stream binge group matches we want to retain same state except
-    ///         for subtitle and audio delay, as these are not relevant when playing
+    ///         for subtitle and audio delay and subtitle framerate, as these are not relevant when playing
     ///         a stream for next binge video as audio/subtitle sync might be different,
     ///         but we want to retain track ids as next binge group might have
     ///         the same embedded tracks with same ids;
@@ -105,6 +109,7 @@ impl StreamsItem {
                 return StreamItemState {
                     subtitle_track: state.subtitle_track.filter(|track| track.embedded),
                     subtitle_delay: None,
+                    subtitle_framerate: None,
                     audio_delay: None,
                     ..state
                 };
@@ -0,0 +1,17 @@
+mod subtitles_cue;
+pub use subtitles_cue::*;
+
+mod subtitles_encoding;
+pub use subtitles_encoding::*;
+
+mod subtitles_error;
+pub use subtitles_error::*;
+
+mod subtitles_format;
+pub use subtitles_format::*;
+
+mod subtitles_track;
+pub use subtitles_track::*;
+
+mod subtitles_sync;
+pub use subtitles_sync::*;
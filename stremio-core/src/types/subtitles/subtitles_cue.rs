@@ -0,0 +1,77 @@
+use serde::{Deserialize, Serialize};
+
+/// A subtitles cue with its times in milliseconds
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SubtitlesCue {
+    pub start: u64,
+    pub end: u64,
+    /// The text of the cue, the lines are separated with `\n`.
+    ///
+    /// Only the `<b>`, `<i>` and `<u>` tags are kept and the WebVTT entities are decoded.
+    /// It's not needed for the cue times of a reference track.
+    #[serde(default)]
+    pub text: String,
+}
+
+impl SubtitlesCue {
+    /// Parses the `HH:MM:SS.mmm` timestamps of the SRT, WebVTT and ASS formats.
+    ///
+    /// The hours are optional and the fraction can be separated with `,`,
+    /// ASS uses centiseconds and some SRT files have less than 3 fraction digits.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stremio_core::types::subtitles::SubtitlesCue;
+    ///
+    /// assert_eq!(SubtitlesCue::parse_timestamp("01:02:03,456"), Some(3_723_456));
+    /// assert_eq!(SubtitlesCue::parse_timestamp("02:03.456"), Some(123_456));
+    /// assert_eq!(SubtitlesCue::parse_timestamp("0:00:01.50"), Some(1_500));
+    /// assert_eq!(SubtitlesCue::parse_timestamp("00:61.000"), None);
+    /// assert_eq!(SubtitlesCue::parse_timestamp("18446744073709551615:00:00.000"), None);
+    /// ```
+    pub fn parse_timestamp(timestamp: &str) -> Option<u64> {
+        let (time, fraction) = timestamp
+            .trim()
+            .split_once(['.', ','])
+            .unwrap_or((timestamp.trim(), "0"));
+        let parts = time
+            .split(':')
+            .map(|part| part.parse::<u64>().ok())
+            .collect::<Option<Vec<_>>>()?;
+        let (hours, minutes, seconds) = match parts[..] {
+            [hours, minutes, seconds] => (hours, minutes, seconds),
+            [minutes, seconds] => (0, minutes, seconds),
+            _ => return None,
+        };
+        if minutes > 59 || seconds > 59 || fraction.is_empty() {
+            return None;
+        }
+        let millis = fraction
+            .chars()
+            .chain(std::iter::repeat('0'))
+            .take(3)
+            .collect::<String>()
+            .parse::<u64>()
+            .ok()?;
+        hours
+            .checked_mul(60)?
+            .checked_add(minutes)?
+            .checked_mul(60)?
+            .checked_add(seconds)?
+            .checked_mul(1000)?
+            .checked_add(millis)
+    }
+
+    /// Formats the time as a WebVTT `HH:MM:SS.mmm` timestamp
+    pub fn format_timestamp(time: u64) -> String {
+        format!(
+            "{:02}:{:02}:{:02}.{:03}",
+            time / 3_600_000,
+            time / 60_000 % 60,
+            time / 1000 % 60,
+            time % 1000
+        )
+    }
+}
@@ -0,0 +1,76 @@
+use chardetng::EncodingDetector;
+use encoding_rs::{Encoding, UTF_8};
+
+/// The top-level domains hinting the legacy encodings of the languages,
+/// by their ISO 639-2 codes, which are used by the subtitles addons.
+const LANGUAGE_TLDS: &[(&str, &str)] = &[
+    ("ara", "sa"),
+    ("bul", "bg"),
+    ("ces", "cz"),
+    ("chi", "cn"),
+    ("cze", "cz"),
+    ("ell", "gr"),
+    ("fas", "ir"),
+    ("gre", "gr"),
+    ("heb", "il"),
+    ("hrv", "hr"),
+    ("hun", "hu"),
+    ("jpn", "jp"),
+    ("kor", "kr"),
+    ("mkd", "mk"),
+    ("per", "ir"),
+    ("pol", "pl"),
+    ("ron", "ro"),
+    ("rum", "ro"),
+    ("rus", "ru"),
+    ("slk", "sk"),
+    ("slo", "sk"),
+    ("slv", "si"),
+    ("srp", "rs"),
+    ("tha", "th"),
+    ("tur", "tr"),
+    ("ukr", "ua"),
+    ("vie", "vn"),
+    ("zho", "cn"),
+];
+
+/// Decodes the subtitles file to UTF-8, returns the text and the name of the detected encoding.
+///
+/// The encoding is detected from the byte order mark, then the file is decoded as UTF-8
+/// if it's valid, otherwise the legacy encoding is guessed with the language as a hint.
+///
+/// # Examples
+///
+/// ```
+/// use stremio_core::types::subtitles::decode_subtitles;
+///
+/// // "Здравей, свят!" in windows-1251
+/// let bytes = b"\xc7\xe4\xf0\xe0\xe2\xe5\xe9, \xf1\xe2\xff\xf2!";
+/// assert_eq!(
+///     decode_subtitles(bytes, Some("bul")),
+///     ("Здравей, свят!".to_owned(), "windows-1251")
+/// );
+/// assert_eq!(
+///     decode_subtitles("\u{feff}Hello".as_bytes(), None),
+///     ("Hello".to_owned(), "UTF-8")
+/// );
+/// ```
+pub fn decode_subtitles(bytes: &[u8], language: Option<&str>) -> (String, &'static str) {
+    let encoding = match Encoding::for_bom(bytes) {
+        Some((encoding, _)) => encoding,
+        None if std::str::from_utf8(bytes).is_ok() => UTF_8,
+        None => {
+            let tld = language.and_then(|language| {
+                LANGUAGE_TLDS
+                    .iter()
+                    .find(|(code, _)| code.eq_ignore_ascii_case(language))
+                    .map(|(_, tld)| tld.as_bytes())
+            });
+            let mut detector = EncodingDetector::new();
+            detector.feed(bytes, true);
+            detector.guess(tld, false)
+        }
+    };
+    let (text, encoding, _) = encoding.decode(bytes);
+    (text.into_owned(), encoding.name())
+}
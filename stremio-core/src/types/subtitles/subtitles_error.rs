@@ -0,0 +1,35 @@
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::runtime::EnvError;
+
+#[derive(Clone, PartialEq, Eq, Serialize, Debug)]
+#[serde(tag = "type", content = "content")]
+pub enum SubtitlesError {
+    /// The subtitles file failed to be fetched
+    Env(EnvError),
+    /// The file is neither SRT, WebVTT nor ASS
+    UnsupportedFormat,
+    /// The file has no valid cues
+    NoCues,
+    /// The reference subtitles were not fetched for the hash of the played video
+    VideoHashMismatch,
+}
+
+impl fmt::Display for SubtitlesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self {
+            SubtitlesError::Env(error) => write!(f, "Env: {}", error.message()),
+            SubtitlesError::UnsupportedFormat => write!(f, "UnsupportedFormat"),
+            SubtitlesError::NoCues => write!(f, "NoCues"),
+            SubtitlesError::VideoHashMismatch => write!(f, "VideoHashMismatch"),
+        }
+    }
+}
+
+impl From<EnvError> for SubtitlesError {
+    fn from(error: EnvError) -> Self {
+        SubtitlesError::Env(error)
+    }
+}
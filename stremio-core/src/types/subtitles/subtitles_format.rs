@@ -0,0 +1,189 @@
+use itertools::Itertools;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::types::subtitles::{SubtitlesCue, SubtitlesError};
+
+/// The ASS override blocks, e.g. `{\an8}`, which are also found in some SRT files
+static OVERRIDE_BLOCK: Lazy<Regex> = Lazy::new(|| Regex::new(r"\{[^}]*\}").unwrap());
+/// Any tag except `<b>`, `<i>` and `<u>`
+static UNSUPPORTED_TAG: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"</?(?:[^biu/>][^>]*|[biu][^>]+)>").unwrap());
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Debug)]
+pub enum SubtitlesFormat {
+    Srt,
+    WebVtt,
+    /// Only the dialogue lines of the Advanced SubStation Alpha files, without styling
+    Ass,
+}
+
+impl SubtitlesFormat {
+    /// Detects the format by the content of the file
+    pub fn detect(text: &str) -> Option<Self> {
+        let text = text.trim_start_matches('\u{feff}').trim_start();
+        if text.starts_with("WEBVTT") {
+            Some(SubtitlesFormat::WebVtt)
+        } else if text.starts_with("[Script Info]") || text.contains("[Events]") {
+            Some(SubtitlesFormat::Ass)
+        } else if text.contains("-->") {
+            Some(SubtitlesFormat::Srt)
+        } else {
+            None
+        }
+    }
+
+    /// Parses the cues sorted by their start, the invalid cues are skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stremio_core::types::subtitles::{SubtitlesCue, SubtitlesFormat};
+    ///
+    /// let srt = "1\r\n00:00:01,000 --> 00:00:02,500\r\n<font color=\"red\">Hello</font>\r\n<i>world</i>\r\n";
+    /// assert_eq!(
+    ///     SubtitlesFormat::Srt.parse(srt),
+    ///     Ok(vec![SubtitlesCue {
+    ///         start: 1000,
+    ///         end: 2500,
+    ///         text: "Hello\n<i>world</i>".to_owned(),
+    ///     }])
+    /// );
+    /// ```
+    pub fn parse(&self, text: &str) -> Result<Vec<SubtitlesCue>, SubtitlesError> {
+        let text = text.trim_start_matches('\u{feff}').replace("\r\n", "\n");
+        let cues = match self {
+            SubtitlesFormat::Srt => parse_blocks(&text),
+            SubtitlesFormat::WebVtt => parse_blocks(&text)
+                .into_iter()
+                .map(|cue| SubtitlesCue {
+                    text: decode_webvtt_entities(&cue.text),
+                    ..cue
+                })
+                .collect(),
+            SubtitlesFormat::Ass => parse_ass(&text),
+        };
+        if cues.is_empty() {
+            return Err(SubtitlesError::NoCues);
+        }
+        Ok(cues
+            .into_iter()
+            .sorted_by_key(|cue| (cue.start, cue.end))
+            .collect())
+    }
+}
+
+/// Parses the cues of the SRT and WebVTT files, which are blocks separated by empty lines.
+///
+/// The numbers and the identifiers of the cues, the WebVTT header, notes, styles
+/// and cue settings are skipped.
+fn parse_blocks(text: &str) -> Vec<SubtitlesCue> {
+    text.split("\n\n")
+        .filter_map(|block| {
+            let mut lines = block.lines().skip_while(|line| !line.contains("-->"));
+            let (start, end) = lines.next()?.split_once("-->")?;
+            // the cue settings follow the end timestamp in WebVTT
+            let end = end.split_whitespace().next()?;
+            let start = SubtitlesCue::parse_timestamp(start)?;
+            let end = SubtitlesCue::parse_timestamp(end)?;
+            cue(start, end, &lines.join("\n"))
+        })
+        .collect()
+}
+
+fn parse_ass(text: &str) -> Vec<SubtitlesCue> {
+    let mut columns = None;
+    let mut in_events = false;
+    text.lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            if line.starts_with('[') {
+                in_events = line.eq_ignore_ascii_case("[Events]");
+                return None;
+            }
+            if !in_events {
+                return None;
+            }
+            let (kind, fields) = line.split_once(':')?;
+            match kind {
+                "Format" => {
+                    columns = Some(
+                        fields
+                            .split(',')
+                            .map(|column| column.trim().to_owned())
+                            .collect_vec(),
+                    );
+                    None
+                }
+                "Dialogue" => {
+                    let columns = columns.as_ref()?;
+                    // the text is the last column and it may contain commas
+                    let fields = fields.splitn(columns.len(), ',').collect_vec();
+                    let field = |name: &str| {
+                        columns
+                            .iter()
+                            .position(|column| column == name)
+                            .and_then(|index| fields.get(index))
+                    };
+                    let start = SubtitlesCue::parse_timestamp(field("Start")?)?;
+                    let end = SubtitlesCue::parse_timestamp(field("End")?)?;
+                    let text = field("Text")?
+                        .replace("\\N", "\n")
+                        .replace("\\n", "\n")
+                        .replace("\\h", "\u{a0}");
+                    cue(start, end, &text)
+                }
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// Decodes the character references of the WebVTT cues,
+/// they are escaped again when the cues are serialized with [`to_webvtt`].
+///
+/// [`to_webvtt`]: crate::types::subtitles::to_webvtt
+fn decode_webvtt_entities(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&nbsp;", "\u{a0}")
+        .replace("&lrm;", "\u{200e}")
+        .replace("&rlm;", "\u{200f}")
+        .replace("&amp;", "&")
+}
+
+/// A cue with cleaned up text, `None` if it's empty or the times are invalid
+fn cue(start: u64, end: u64, text: &str) -> Option<SubtitlesCue> {
+    let text = OVERRIDE_BLOCK.replace_all(text, "");
+    let text = UNSUPPORTED_TAG.replace_all(&text, "");
+    let text = text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .join("\n");
+    (end > start && !text.is_empty()).then_some(SubtitlesCue { start, end, text })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SubtitlesFormat;
+    use crate::types::subtitles::SubtitlesCue;
+
+    #[test]
+    fn subtitles_format_webvtt() {
+        let webvtt = "WEBVTT - Title\n\nNOTE a comment\n\nSTYLE\n::cue { color: red }\n\nintro\n00:01.000 --> 00:02.000 align:start line:0\n<v Roger><b>Hi</b> &amp; bye\n\n00:00:03.000 --> 00:00:02.000\nInvalid\n";
+        assert_eq!(
+            SubtitlesFormat::detect(webvtt),
+            Some(SubtitlesFormat::WebVtt)
+        );
+        assert_eq!(
+            SubtitlesFormat::WebVtt.parse(webvtt),
+            Ok(vec![SubtitlesCue {
+                start: 1000,
+                end: 2000,
+                text: "<b>Hi</b> & bye".to_owned(),
+            }])
+        );
+    }
+}
@@ -0,0 +1,246 @@
+use std::cmp::Reverse;
+
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+use crate::constants::SUBTITLES_SYNC_MAX_OFFSET;
+use crate::types::subtitles::{FramerateCorrection, SubtitlesCue, SubtitlesError};
+
+/// The step in milliseconds of the first search of the delay
+const COARSE_STEP: i64 = 100;
+/// The step in milliseconds of the search around the best coarse delay
+const FINE_STEP: i64 = 10;
+/// The drifts of the subtitles made for a video with another common framerate
+const FRAMERATE_DRIFTS: [f64; 7] = [
+    1.0,
+    25.0 / 23.976,
+    23.976 / 25.0,
+    25.0 / 24.0,
+    24.0 / 25.0,
+    24.0 / 23.976,
+    23.976 / 24.0,
+];
+/// The least matched cues for fitting the drift and the offset
+const MIN_MATCHED_CUES: usize = 10;
+
+/// The alignment of the subtitles to a reference track.
+///
+/// The time of a cue on the reference is `time * drift + offset`.
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SubtitlesSync {
+    /// In milliseconds, the best `subtitle_delay` when the drift is not corrected
+    pub delay: i64,
+    pub drift: f64,
+    /// In milliseconds, the `subtitle_delay` which aligns the subtitles with the drift corrected
+    pub offset: i64,
+    /// The share of the duration of the cues overlapping the reference after the alignment,
+    /// from `0.0` to `1.0`
+    pub score: f64,
+}
+
+impl SubtitlesSync {
+    /// Aligns the cues to the reference by correlating the intervals of the cues.
+    ///
+    /// The offset is searched within [`SUBTITLES_SYNC_MAX_OFFSET`] for the drift of each
+    /// common framerate conversion, coarsely and then around the best coarse offset.
+    /// Then the drift and the offset are fitted to the starts of the matched cues.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stremio_core::types::subtitles::{FramerateCorrection, SubtitlesCue, SubtitlesSync};
+    ///
+    /// let cue = |start: u64, end: u64| SubtitlesCue { start, end, text: String::new() };
+    /// let reference = (0..20)
+    ///     .map(|index| cue(10_000 + index * 3_700, 11_500 + index * 3_700 + index % 3 * 400))
+    ///     .collect::<Vec<_>>();
+    /// let cues = reference
+    ///     .iter()
+    ///     .map(|reference| cue(reference.start - 2_500, reference.end - 2_500))
+    ///     .collect::<Vec<_>>();
+    /// let sync = SubtitlesSync::new(&cues, &reference).unwrap();
+    /// assert_eq!((sync.delay, sync.drift, sync.offset), (2_500, 1.0, 2_500));
+    /// assert_eq!(sync.score, 1.0);
+    /// assert_eq!(sync.framerate(), None);
+    ///
+    /// // subtitles made for the 25 fps release of a 23.976 fps video
+    /// let cues = reference
+    ///     .iter()
+    ///     .map(|reference| cue(reference.start * 23_976 / 25_000, reference.end * 23_976 / 25_000))
+    ///     .collect::<Vec<_>>();
+    /// let sync = SubtitlesSync::new(&cues, &reference).unwrap();
+    /// assert!((sync.drift - 25.0 / 23.976).abs() < 0.0001);
+    /// assert!(sync.offset.abs() <= 10);
+    /// assert!(sync.score > 0.99);
+    /// assert_eq!(sync.framerate(), Some(FramerateCorrection { from: sync.drift, to: 1.0 }));
+    /// ```
+    pub fn new(
+        cues: &[SubtitlesCue],
+        reference: &[SubtitlesCue],
+    ) -> Result<SubtitlesSync, SubtitlesError> {
+        let cues = merge_intervals(intervals(cues));
+        let reference = merge_intervals(intervals(reference));
+        let duration = cues.iter().map(|(start, end)| end - start).sum::<i64>();
+        if duration == 0 || reference.is_empty() {
+            return Err(SubtitlesError::NoCues);
+        }
+
+        let offsets = FRAMERATE_DRIFTS
+            .iter()
+            .map(|drift| best_delay(&retime(&cues, *drift, 0), &reference))
+            .collect::<Vec<_>>();
+        let (delay, _) = offsets[0];
+        let (drift, offset) = FRAMERATE_DRIFTS
+            .iter()
+            .zip(offsets)
+            .enumerate()
+            // the first drift wins a tie
+            .max_by_key(|(index, (_, (_, overlap)))| (*overlap, Reverse(*index)))
+            .map(|(_, (drift, (offset, _)))| fit(&cues, &reference, *drift, offset))
+            .expect("drifts are not empty");
+        let overlap = overlap(&retime(&cues, drift, offset), &reference, 0);
+
+        Ok(SubtitlesSync {
+            delay,
+            drift,
+            offset,
+            score: overlap as f64 / duration as f64,
+        })
+    }
+
+    /// The drift as the correction of the framerate which is applied with the `offset`,
+    /// `None` if the subtitles don't drift.
+    pub fn framerate(&self) -> Option<FramerateCorrection> {
+        (self.drift != 1.0).then_some(FramerateCorrection {
+            from: self.drift,
+            to: 1.0,
+        })
+    }
+}
+
+type Interval = (i64, i64);
+
+fn intervals(cues: &[SubtitlesCue]) -> Vec<Interval> {
+    cues.iter()
+        .filter(|cue| cue.end > cue.start)
+        .map(|cue| (cue.start as i64, cue.end as i64))
+        .collect()
+}
+
+/// Merges the overlapping intervals, so the overlap is not counted twice
+fn merge_intervals(intervals: Vec<Interval>) -> Vec<Interval> {
+    intervals
+        .into_iter()
+        .sorted()
+        .coalesce(|(start, end), (next_start, next_end)| {
+            if next_start <= end {
+                Ok((start, end.max(next_end)))
+            } else {
+                Err(((start, end), (next_start, next_end)))
+            }
+        })
+        .collect()
+}
+
+/// Scales the times by the drift, like [`FramerateCorrection`], and then delays them by the offset
+fn retime(intervals: &[Interval], drift: f64, offset: i64) -> Vec<Interval> {
+    merge_intervals(
+        intervals
+            .iter()
+            .map(|interval| retime_interval(interval, drift, offset))
+            .collect(),
+    )
+}
+
+fn retime_interval((start, end): &Interval, drift: f64, offset: i64) -> Interval {
+    let retime = |time: i64| (time as f64 * drift).round() as i64 + offset;
+    (retime(*start), retime(*end))
+}
+
+/// The total duration of the overlaps of the two sorted lists of disjoint intervals,
+/// when the first list is delayed by `delay`
+fn overlap(intervals: &[Interval], reference: &[Interval], delay: i64) -> i64 {
+    let (mut i, mut j, mut overlap) = (0, 0, 0);
+    while let (Some((start, end)), Some((reference_start, reference_end))) =
+        (intervals.get(i), reference.get(j))
+    {
+        let (start, end) = (start + delay, end + delay);
+        overlap += (end.min(*reference_end) - start.max(*reference_start)).max(0);
+        if end < *reference_end {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    overlap
+}
+
+/// The delay with the largest overlap, searched coarsely and then finely
+fn best_delay(cues: &[Interval], reference: &[Interval]) -> (i64, i64) {
+    let (coarse, _) = search(
+        cues,
+        reference,
+        (-SUBTITLES_SYNC_MAX_OFFSET..=SUBTITLES_SYNC_MAX_OFFSET).step_by(COARSE_STEP as usize),
+    );
+    search(
+        cues,
+        reference,
+        (coarse - COARSE_STEP..=coarse + COARSE_STEP).step_by(FINE_STEP as usize),
+    )
+}
+
+fn search(
+    cues: &[Interval],
+    reference: &[Interval],
+    delays: impl Iterator<Item = i64>,
+) -> (i64, i64) {
+    delays
+        .map(|delay| (delay, overlap(cues, reference, delay)))
+        // the smallest delay wins a tie
+        .max_by_key(|(delay, overlap)| (*overlap, -delay.abs()))
+        .expect("delays are not empty")
+}
+
+/// Fits the drift and the offset with least squares to the starts of the cues
+/// and the reference cues which overlap them the most.
+///
+/// Keeps the searched drift and offset if too few cues are matched
+/// or the fit is not better than them.
+fn fit(cues: &[Interval], reference: &[Interval], drift: f64, offset: i64) -> (f64, i64) {
+    let pairs = cues
+        .iter()
+        .filter_map(|interval| {
+            let retimed = retime_interval(interval, drift, offset);
+            reference
+                .iter()
+                .map(|reference| (reference, overlap(&[retimed], &[*reference], 0)))
+                .filter(|(_, overlap)| *overlap > 0)
+                .max_by_key(|(_, overlap)| *overlap)
+                .map(|((reference_start, _), _)| (interval.0 as f64, *reference_start as f64))
+        })
+        .collect::<Vec<_>>();
+    if pairs.len() < MIN_MATCHED_CUES {
+        return (drift, offset);
+    }
+
+    let count = pairs.len() as f64;
+    let mean_x = pairs.iter().map(|(x, _)| x).sum::<f64>() / count;
+    let mean_y = pairs.iter().map(|(_, y)| y).sum::<f64>() / count;
+    let covariance = pairs
+        .iter()
+        .map(|(x, y)| (x - mean_x) * (y - mean_y))
+        .sum::<f64>();
+    let variance = pairs.iter().map(|(x, _)| (x - mean_x).powi(2)).sum::<f64>();
+    if variance == 0.0 {
+        return (drift, offset);
+    }
+    let fitted_drift = covariance / variance;
+    let fitted_offset = (mean_y - fitted_drift * mean_x).round() as i64;
+    let fitted_overlap = overlap(&retime(cues, fitted_drift, fitted_offset), reference, 0);
+    if fitted_overlap > overlap(&retime(cues, drift, offset), reference, 0) {
+        (fitted_drift, fitted_offset)
+    } else {
+        (drift, offset)
+    }
+}
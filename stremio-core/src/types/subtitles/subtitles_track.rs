@@ -0,0 +1,142 @@
+use serde::{Deserialize, Serialize};
+
+use crate::types::subtitles::{decode_subtitles, SubtitlesCue, SubtitlesError, SubtitlesFormat};
+
+/// Corrects the times of subtitles made for a video with another framerate,
+/// e.g. PAL subtitles at 25 fps for a 23.976 fps video.
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct FramerateCorrection {
+    /// The framerate of the video for which the subtitles were made
+    pub from: f64,
+    /// The framerate of the played video
+    pub to: f64,
+}
+
+/// The parsed cues of a subtitles file
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SubtitlesTrack {
+    pub format: SubtitlesFormat,
+    /// The name of the encoding from which the file was decoded
+    pub encoding: String,
+    pub cues: Vec<SubtitlesCue>,
+}
+
+impl SubtitlesTrack {
+    /// Decodes and parses the subtitles file, `language` is a hint for the legacy encodings
+    pub fn parse(bytes: &[u8], language: Option<&str>) -> Result<Self, SubtitlesError> {
+        let (text, encoding) = decode_subtitles(bytes, language);
+        let format = SubtitlesFormat::detect(&text).ok_or(SubtitlesError::UnsupportedFormat)?;
+        let cues = format.parse(&text)?;
+        Ok(SubtitlesTrack {
+            format,
+            encoding: encoding.to_owned(),
+            cues,
+        })
+    }
+
+    /// Applies the framerate correction and then the delay in milliseconds.
+    ///
+    /// The cues which end before the start of the video are dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stremio_core::types::subtitles::{FramerateCorrection, SubtitlesCue, SubtitlesFormat, SubtitlesTrack};
+    ///
+    /// let track = SubtitlesTrack {
+    ///     format: SubtitlesFormat::Srt,
+    ///     encoding: "UTF-8".to_owned(),
+    ///     cues: vec![
+    ///         SubtitlesCue { start: 0, end: 1000, text: "First".to_owned() },
+    ///         SubtitlesCue { start: 25_000, end: 26_000, text: "Second".to_owned() },
+    ///     ],
+    /// };
+    /// let framerate = FramerateCorrection { from: 25.0, to: 24.0 };
+    /// let cues = track.retimed(-500, Some(framerate));
+    /// assert_eq!((cues[0].start, cues[0].end), (0, 542));
+    /// assert_eq!((cues[1].start, cues[1].end), (25_542, 26_583));
+    /// ```
+    pub fn retimed(&self, delay: i64, framerate: Option<FramerateCorrection>) -> Vec<SubtitlesCue> {
+        let ratio = framerate
+            .filter(|framerate| framerate.from > 0.0 && framerate.to > 0.0)
+            .map(|framerate| framerate.from / framerate.to)
+            .unwrap_or(1.0);
+        let retime = |time: u64| ((time as f64 * ratio).round() as i64).saturating_add(delay);
+        self.cues
+            .iter()
+            .filter_map(|cue| {
+                let end = retime(cue.end);
+                (end > 0).then(|| SubtitlesCue {
+                    start: retime(cue.start).max(0) as u64,
+                    end: end as u64,
+                    text: cue.text.to_owned(),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Serializes the cues as a WebVTT file, escaping the text except the `<b>`, `<i>` and `<u>` tags
+///
+/// # Examples
+///
+/// ```
+/// use stremio_core::types::subtitles::{to_webvtt, SubtitlesCue};
+///
+/// let cues = [
+///     SubtitlesCue { start: 1000, end: 3_723_456, text: "Hello\nworld".to_owned() },
+///     SubtitlesCue { start: 4_000_000, end: 4_001_000, text: "<i>Tom & Jerry</i> <3".to_owned() },
+/// ];
+/// assert_eq!(
+///     to_webvtt(&cues),
+///     "WEBVTT\n\n1\n00:00:01.000 --> 01:02:03.456\nHello\nworld\n\n\
+///      2\n01:06:40.000 --> 01:06:41.000\n<i>Tom &amp; Jerry</i> &lt;3\n\n"
+/// );
+/// ```
+pub fn to_webvtt(cues: &[SubtitlesCue]) -> String {
+    cues.iter()
+        .enumerate()
+        .fold("WEBVTT\n\n".to_owned(), |mut webvtt, (index, cue)| {
+            webvtt.push_str(&format!(
+                "{}\n{} --> {}\n{}\n\n",
+                index + 1,
+                SubtitlesCue::format_timestamp(cue.start),
+                SubtitlesCue::format_timestamp(cue.end),
+                escape_webvtt_text(&cue.text)
+            ));
+            webvtt
+        })
+}
+
+/// Escapes the text of a WebVTT cue, keeping the `<b>`, `<i>` and `<u>` tags
+fn escape_webvtt_text(text: &str) -> String {
+    const KEPT_TAGS: [&str; 6] = ["<b>", "</b>", "<i>", "</i>", "<u>", "</u>"];
+    let mut escaped = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(char) = rest.chars().next() {
+        let len = match char {
+            '&' => {
+                escaped.push_str("&amp;");
+                1
+            }
+            '<' => match KEPT_TAGS.iter().find(|tag| rest.starts_with(**tag)) {
+                Some(tag) => {
+                    escaped.push_str(tag);
+                    tag.len()
+                }
+                None => {
+                    escaped.push_str("&lt;");
+                    1
+                }
+            },
+            char => {
+                escaped.push(char);
+                char.len_utf8()
+            }
+        };
+        rest = &rest[len..];
+    }
+    escaped.replace("-->", "--&gt;")
+}
@@ -0,0 +1,5 @@
+mod taste_bucket;
+pub use taste_bucket::*;
+
+mod taste_profile;
+pub use taste_profile::*;
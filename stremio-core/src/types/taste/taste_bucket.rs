@@ -0,0 +1,175 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::constants::{CAST_LINK_CATEGORY, GENRES_LINK_CATEGORY, TASTE_ITEMS_COUNT};
+use crate::types::library::{LibraryBucket, LibraryItem};
+use crate::types::profile::UID;
+use crate::types::rating::Rating;
+use crate::types::resource::{MetaItemId, MetaItemPreview};
+
+/// An item which the user watched or rated
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TasteItem {
+    pub id: MetaItemId,
+    pub name: String,
+    pub r#type: String,
+    pub genres: Vec<String>,
+    pub cast: Vec<String>,
+    /// A stream of the item was played
+    pub watched: bool,
+    pub rating: Option<Rating>,
+    pub mtime: DateTime<Utc>,
+}
+
+impl TasteItem {
+    pub fn new(meta_item: &MetaItemPreview, mtime: DateTime<Utc>) -> Self {
+        let links = |category: &str| {
+            meta_item
+                .links
+                .iter()
+                .filter(|link| link.category == category)
+                .map(|link| link.name.to_owned())
+                .collect()
+        };
+        Self {
+            id: meta_item.id.to_owned(),
+            name: meta_item.name.to_owned(),
+            r#type: meta_item.r#type.to_owned(),
+            genres: links(GENRES_LINK_CATEGORY),
+            cast: links(CAST_LINK_CATEGORY),
+            watched: false,
+            rating: None,
+            mtime,
+        }
+    }
+    /// The item of a watched library item, without genres and cast
+    /// until the meta item is watched or rated again.
+    pub fn from_library_item(library_item: &LibraryItem) -> Self {
+        Self {
+            id: library_item.id.to_owned(),
+            name: library_item.name.to_owned(),
+            r#type: library_item.r#type.to_owned(),
+            genres: vec![],
+            cast: vec![],
+            watched: true,
+            rating: None,
+            mtime: library_item
+                .state
+                .last_watched
+                .unwrap_or(library_item.mtime),
+        }
+    }
+    /// How much the item defines the taste of the user
+    pub fn weight(&self) -> f64 {
+        match self.rating {
+            Some(Rating::Loved) => 3.0,
+            Some(Rating::Liked) => 2.0,
+            Some(Rating::Watched) => 1.0,
+            None if self.watched => 1.0,
+            None => 0.0,
+        }
+    }
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TasteBucket {
+    pub uid: UID,
+    pub items: HashMap<MetaItemId, TasteItem>,
+}
+
+impl TasteBucket {
+    pub fn new(uid: UID) -> Self {
+        Self {
+            uid,
+            items: HashMap::new(),
+        }
+    }
+    /// Updates the item of the meta item, returns whether the bucket changed.
+    ///
+    /// The items without weight are removed and only the [`TASTE_ITEMS_COUNT`]
+    /// most recent items are kept.
+    pub fn update(
+        &mut self,
+        meta_item: &MetaItemPreview,
+        now: DateTime<Utc>,
+        update: impl FnOnce(&mut TasteItem),
+    ) -> bool {
+        let prev_item = self.items.get(&meta_item.id);
+        let mut next_item = prev_item
+            .cloned()
+            .unwrap_or_else(|| TasteItem::new(meta_item, now));
+        if prev_item.is_some() {
+            // the items seeded from the library have no genres and cast
+            let meta_taste_item = TasteItem::new(meta_item, now);
+            if !meta_taste_item.genres.is_empty() || !meta_taste_item.cast.is_empty() {
+                next_item.genres = meta_taste_item.genres;
+                next_item.cast = meta_taste_item.cast;
+            }
+        }
+        update(&mut next_item);
+        let changed = match prev_item {
+            Some(prev_item) => {
+                (
+                    prev_item.watched,
+                    &prev_item.rating,
+                    &prev_item.genres,
+                    &prev_item.cast,
+                ) != (
+                    next_item.watched,
+                    &next_item.rating,
+                    &next_item.genres,
+                    &next_item.cast,
+                )
+            }
+            None => next_item.weight() > 0.0,
+        };
+        if !changed {
+            return false;
+        }
+        if next_item.weight() > 0.0 {
+            next_item.mtime = now;
+            self.items.insert(meta_item.id.to_owned(), next_item);
+        } else {
+            self.items.remove(&meta_item.id);
+        }
+        if self.items.len() > TASTE_ITEMS_COUNT {
+            if let Some(oldest_id) = self
+                .items
+                .values()
+                .min_by_key(|item| item.mtime)
+                .map(|item| item.id.to_owned())
+            {
+                self.items.remove(&oldest_id);
+            }
+        }
+        true
+    }
+    /// Seeds the bucket with the watched items of the library, returns whether the bucket changed.
+    ///
+    /// Only the [`TASTE_ITEMS_COUNT`] most recently watched items are added
+    /// and the existing items are kept.
+    pub fn seed(&mut self, library: &LibraryBucket) -> bool {
+        let mut seed_items = library
+            .items
+            .values()
+            .filter(|library_item| {
+                library_item.r#type != "other"
+                    && library_item.watched()
+                    && !self.items.contains_key(&library_item.id)
+            })
+            .map(TasteItem::from_library_item)
+            .collect::<Vec<_>>();
+        seed_items.sort_by(|a, b| b.mtime.cmp(&a.mtime));
+        seed_items.truncate(TASTE_ITEMS_COUNT.saturating_sub(self.items.len()));
+        let changed = !seed_items.is_empty();
+        self.items.extend(
+            seed_items
+                .into_iter()
+                .map(|taste_item| (taste_item.id.to_owned(), taste_item)),
+        );
+        changed
+    }
+}
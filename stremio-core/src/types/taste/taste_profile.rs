@@ -0,0 +1,158 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use crate::constants::{CAST_LINK_CATEGORY, GENRES_LINK_CATEGORY};
+use crate::types::addon::ResourceRequest;
+use crate::types::rating::Rating;
+use crate::types::resource::{MetaItemId, MetaItemPreview};
+use crate::types::taste::{TasteBucket, TasteItem};
+
+/// The weight of the matching genres in the score of a recommendation
+const GENRES_WEIGHT: f64 = 1.0;
+/// The weight of the matching cast in the score of a recommendation
+const CAST_WEIGHT: f64 = 2.0;
+
+/// The item of the taste profile which is the most similar to the recommended one,
+/// e.g. "Because you loved X"
+#[derive(Clone, PartialEq, Eq, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RecommendationReason {
+    pub id: MetaItemId,
+    pub name: String,
+    /// The item was watched but not rated if it's `None`
+    pub rating: Option<Rating>,
+    /// The genres in common
+    pub genres: Vec<String>,
+    /// The cast in common
+    pub cast: Vec<String>,
+}
+
+#[derive(Clone, PartialEq, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Recommendation {
+    pub meta_item: MetaItemPreview,
+    /// The request of the catalog in which the item was found
+    pub request: ResourceRequest,
+    pub score: f64,
+    pub reason: RecommendationReason,
+}
+
+/// The genres and the cast of the items which the user watched or rated,
+/// weighted by the rating of the items
+pub struct TasteProfile<'a> {
+    items: Vec<&'a TasteItem>,
+    genres: HashMap<&'a str, f64>,
+    cast: HashMap<&'a str, f64>,
+    total_weight: f64,
+}
+
+impl<'a> TasteProfile<'a> {
+    pub fn new(taste: &'a TasteBucket) -> Self {
+        let items = taste.items.values().collect::<Vec<_>>();
+        let mut genres = HashMap::<&str, f64>::new();
+        let mut cast = HashMap::<&str, f64>::new();
+        for item in &items {
+            for genre in &item.genres {
+                *genres.entry(genre).or_default() += item.weight();
+            }
+            for name in &item.cast {
+                *cast.entry(name).or_default() += item.weight();
+            }
+        }
+        let total_weight = items.iter().map(|item| item.weight()).sum();
+        Self {
+            items,
+            genres,
+            cast,
+            total_weight,
+        }
+    }
+    pub fn is_empty(&self) -> bool {
+        self.total_weight <= 0.0
+    }
+    /// Scores the meta item against the profile, `None` if it has nothing in common with it.
+    pub fn recommend(
+        &self,
+        meta_item: &MetaItemPreview,
+        request: &ResourceRequest,
+    ) -> Option<Recommendation> {
+        if self.is_empty() {
+            return None;
+        }
+        let links = |category: &str| {
+            meta_item
+                .links
+                .iter()
+                .filter(|link| link.category == category)
+                .map(|link| link.name.as_str())
+                .collect::<Vec<_>>()
+        };
+        let genres = links(GENRES_LINK_CATEGORY);
+        let cast = links(CAST_LINK_CATEGORY);
+        // the share of the taste matching the genres, on average
+        let genres_score = match genres.len() {
+            0 => 0.0,
+            len => {
+                genres
+                    .iter()
+                    .filter_map(|genre| self.genres.get(genre))
+                    .sum::<f64>()
+                    / len as f64
+                    / self.total_weight
+            }
+        };
+        // every actor in common counts
+        let cast_score = cast
+            .iter()
+            .filter_map(|name| self.cast.get(name))
+            .sum::<f64>()
+            / self.total_weight;
+        let score = GENRES_WEIGHT * genres_score + CAST_WEIGHT * cast_score;
+        if score <= 0.0 {
+            return None;
+        }
+        let reason = self
+            .items
+            .iter()
+            .map(|item| {
+                let common_genres = item
+                    .genres
+                    .iter()
+                    .filter(|genre| genres.contains(&genre.as_str()))
+                    .cloned()
+                    .collect::<Vec<_>>();
+                let common_cast = item
+                    .cast
+                    .iter()
+                    .filter(|name| cast.contains(&name.as_str()))
+                    .cloned()
+                    .collect::<Vec<_>>();
+                let similarity = item.weight()
+                    * (GENRES_WEIGHT * common_genres.len() as f64
+                        + CAST_WEIGHT * common_cast.len() as f64);
+                (item, similarity, common_genres, common_cast)
+            })
+            .filter(|(_, similarity, ..)| *similarity > 0.0)
+            .max_by(|(item_a, similarity_a, ..), (item_b, similarity_b, ..)| {
+                similarity_a
+                    .partial_cmp(similarity_b)
+                    .unwrap_or(Ordering::Equal)
+                    .then(item_a.mtime.cmp(&item_b.mtime))
+            })
+            .map(|(item, _, genres, cast)| RecommendationReason {
+                id: item.id.to_owned(),
+                name: item.name.to_owned(),
+                rating: item.rating.to_owned(),
+                genres,
+                cast,
+            })?;
+        Some(Recommendation {
+            meta_item: meta_item.to_owned(),
+            request: request.to_owned(),
+            score,
+            reason,
+        })
+    }
+}
@@ -0,0 +1,129 @@
+use std::str::FromStr;
+
+use magnet_url::Magnet;
+use percent_encoding::percent_decode_str;
+use serde::Serialize;
+
+use crate::types::torrent::{InfoHash, TorrentError};
+
+/// The alphabet of the base32 encoded info hashes, see RFC 4648
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// A BitTorrent v1 magnet link.
+///
+/// The files of the torrent are not part of the link,
+/// they are only known after fetching the metadata from the peers.
+///
+/// # Examples
+///
+/// ```
+/// use stremio_core::types::torrent::MagnetLink;
+///
+/// let magnet_link = "magnet:?xt=urn:btih:df389295484b3059a4726dc6d8a57f71bb5f4c81&dn=Big%20Buck%20Bunny&tr=udp%3A%2F%2Ftracker%3A1337"
+///     .parse::<MagnetLink>()
+///     .unwrap();
+///
+/// assert_eq!(
+///     magnet_link.info_hash.to_string(),
+///     "df389295484b3059a4726dc6d8a57f71bb5f4c81"
+/// );
+/// assert_eq!(magnet_link.name.as_deref(), Some("Big Buck Bunny"));
+/// assert_eq!(magnet_link.announce, vec!["udp://tracker:1337".to_owned()]);
+/// ```
+#[derive(Clone, PartialEq, Eq, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct MagnetLink {
+    pub info_hash: InfoHash,
+    /// The display name (`dn`)
+    pub name: Option<String>,
+    /// The trackers (`tr`)
+    pub announce: Vec<String>,
+    /// The exact length of the content in bytes (`xl`)
+    pub length: Option<u64>,
+}
+
+impl FromStr for MagnetLink {
+    type Err = TorrentError;
+
+    fn from_str(magnet_link: &str) -> Result<Self, Self::Err> {
+        let magnet = Magnet::new(magnet_link)
+            .map_err(|error| TorrentError::InvalidMagnet(error.to_string()))?;
+        if magnet
+            .hash_type
+            .as_ref()
+            .is_some_and(|hash_type| !hash_type.eq_ignore_ascii_case("btih"))
+        {
+            return Err(TorrentError::InvalidMagnet(
+                "only BitTorrent v1 info hashes are supported".to_owned(),
+            ));
+        }
+        let info_hash = magnet
+            .xt
+            .as_deref()
+            .and_then(parse_info_hash)
+            .ok_or_else(|| TorrentError::InvalidMagnet("invalid info hash".to_owned()))?;
+        let mut announce = Vec::<String>::new();
+        magnet
+            .tr
+            .iter()
+            .map(|tracker| decode(tracker))
+            .for_each(|tracker| {
+                if !announce.contains(&tracker) {
+                    announce.push(tracker)
+                }
+            });
+        Ok(MagnetLink {
+            info_hash,
+            name: magnet.dn.as_deref().map(decode),
+            announce,
+            length: magnet.xl,
+        })
+    }
+}
+
+/// The info hash is either hex (40 characters) or base32 (32 characters) encoded.
+fn parse_info_hash(info_hash: &str) -> Option<InfoHash> {
+    match info_hash.len() {
+        40 => info_hash.parse().ok(),
+        32 => {
+            let mut bytes = [0_u8; 20];
+            let mut buffer = 0_u64;
+            let mut bits = 0;
+            let mut idx = 0;
+            for character in info_hash.bytes() {
+                let value = BASE32_ALPHABET.iter().position(|alphabet_character| {
+                    *alphabet_character == character.to_ascii_uppercase()
+                })?;
+                buffer = (buffer << 5) | value as u64;
+                bits += 5;
+                if bits >= 8 {
+                    bits -= 8;
+                    bytes[idx] = (buffer >> bits) as u8;
+                    idx += 1;
+                }
+            }
+            Some(InfoHash::new(bytes))
+        }
+        _ => None,
+    }
+}
+
+fn decode(value: &str) -> String {
+    percent_decode_str(&value.replace('+', " "))
+        .decode_utf8_lossy()
+        .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base32_info_hash() {
+        let hex = parse_info_hash("df389295484b3059a4726dc6d8a57f71bb5f4c81");
+        let base32 = parse_info_hash("344JFFKIJMYFTJDSNXDNRJL7OG5V6TEB");
+        assert!(hex.is_some());
+        assert_eq!(hex, base32);
+        assert_eq!(parse_info_hash("344JFFKIJMYFTJDSNXDNRJL7OG5V6TE1"), None);
+    }
+}
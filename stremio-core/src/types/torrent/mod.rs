@@ -0,0 +1,11 @@
+mod info_hash;
+pub use info_hash::*;
+
+mod magnet_link;
+pub use magnet_link::*;
+
+mod torrent_error;
+pub use torrent_error::*;
+
+mod torrent_metainfo;
+pub use torrent_metainfo::*;
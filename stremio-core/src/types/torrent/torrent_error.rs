@@ -0,0 +1,29 @@
+use std::fmt;
+
+use serde::Serialize;
+
+#[derive(Clone, PartialEq, Eq, Serialize, Debug)]
+#[serde(tag = "type", content = "content")]
+pub enum TorrentError {
+    /// The `.torrent` file is not valid bencode
+    Bencode(String),
+    /// The bencode is valid but the info dictionary is not
+    InvalidMetainfo(String),
+    InvalidMagnet(String),
+}
+
+impl fmt::Display for TorrentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self {
+            TorrentError::Bencode(message) => write!(f, "Bencode: {message}"),
+            TorrentError::InvalidMetainfo(message) => write!(f, "InvalidMetainfo: {message}"),
+            TorrentError::InvalidMagnet(message) => write!(f, "InvalidMagnet: {message}"),
+        }
+    }
+}
+
+impl From<serde_bencode::Error> for TorrentError {
+    fn from(error: serde_bencode::Error) -> Self {
+        TorrentError::Bencode(error.to_string())
+    }
+}
@@ -0,0 +1,257 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use serde::Serialize;
+use serde_bencode::value::Value;
+use sha1::{Digest, Sha1};
+
+use crate::constants::VIDEO_FILE_EXTENSIONS;
+use crate::types::torrent::{InfoHash, TorrentError};
+
+type Dict = HashMap<Vec<u8>, Value>;
+
+/// The length of a SHA-1 hash in the `pieces` of the info dictionary
+const PIECE_HASH_LENGTH: usize = 20;
+
+/// The content of a `.torrent` file (BitTorrent v1).
+///
+/// # Examples
+///
+/// ```
+/// use stremio_core::types::torrent::TorrentMetainfo;
+///
+/// let torrent = b"d8:announce18:udp://tracker:13374:infod6:lengthi1024e4:name9:movie.mkv12:piece lengthi16384e6:pieces20:aaaaaaaaaaaaaaaaaaaaee";
+/// let metainfo = TorrentMetainfo::from_bytes(torrent).unwrap();
+///
+/// assert_eq!(metainfo.name, "movie.mkv");
+/// assert_eq!(metainfo.files.len(), 1);
+/// assert!(metainfo.files[0].is_video());
+/// assert_eq!(metainfo.announce, vec!["udp://tracker:1337".to_owned()]);
+/// ```
+#[derive(Clone, PartialEq, Eq, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TorrentMetainfo {
+    /// The SHA-1 hash of the bencoded info dictionary
+    pub info_hash: InfoHash,
+    pub name: String,
+    /// The trackers of `announce` and `announce-list`, without duplicates
+    pub announce: Vec<String>,
+    /// In bytes
+    pub piece_length: u64,
+    pub pieces_count: usize,
+    pub private: bool,
+    /// The files in the order of the info dictionary,
+    /// a single file named after the torrent for single-file torrents
+    pub files: Vec<TorrentFile>,
+}
+
+impl TorrentMetainfo {
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, TorrentError> {
+        let torrent = match serde_bencode::from_bytes::<Value>(bytes)? {
+            Value::Dict(torrent) => torrent,
+            _ => return Err(invalid("torrent is not a dictionary")),
+        };
+        let info_value = torrent
+            .get(b"info".as_slice())
+            .ok_or_else(|| invalid("missing info"))?;
+        let info = match info_value {
+            Value::Dict(info) => info,
+            _ => return Err(invalid("info is not a dictionary")),
+        };
+        let mut hasher = Sha1::new();
+        hasher.update(serde_bencode::to_bytes(info_value)?);
+        let info_hash = InfoHash::new(hasher.finalize().into());
+
+        let name = utf8_string(info, "name").ok_or_else(|| invalid("missing name"))?;
+        let piece_length = get_u64(info, "piece length")
+            .filter(|piece_length| *piece_length > 0)
+            .ok_or_else(|| invalid("missing piece length"))?;
+        let pieces_count = match info.get(b"pieces".as_slice()) {
+            Some(Value::Bytes(pieces)) if pieces.len() % PIECE_HASH_LENGTH == 0 => {
+                pieces.len() / PIECE_HASH_LENGTH
+            }
+            Some(_) => return Err(invalid("pieces are not a list of SHA-1 hashes")),
+            None => return Err(invalid("missing pieces")),
+        };
+        let private = get_u64(info, "private") == Some(1);
+        let files = match (get_u64(info, "length"), info.get(b"files".as_slice())) {
+            (Some(length), None) => vec![TorrentFile {
+                idx: 0,
+                path: name.to_owned(),
+                length,
+                offset: 0,
+            }],
+            (None, Some(Value::List(files))) => parse_files(files)?,
+            _ => return Err(invalid("either length or files is required")),
+        };
+        let total_length = files
+            .last()
+            .map_or(Some(0), |file| file.offset.checked_add(file.length))
+            .ok_or_else(|| invalid("files are too long"))?;
+        let pieces_length = piece_length
+            .checked_mul(pieces_count as u64)
+            .ok_or_else(|| invalid("pieces are too long"))?;
+        if total_length > pieces_length {
+            return Err(invalid("files are longer than the pieces"));
+        }
+
+        let mut announce = get_string(&torrent, "announce")
+            .into_iter()
+            .collect::<Vec<_>>();
+        if let Some(Value::List(tiers)) = torrent.get(b"announce-list".as_slice()) {
+            tiers
+                .iter()
+                .filter_map(|tier| match tier {
+                    Value::List(trackers) => Some(trackers),
+                    _ => None,
+                })
+                .flatten()
+                .filter_map(as_string)
+                .for_each(|tracker| {
+                    if !announce.contains(&tracker) {
+                        announce.push(tracker)
+                    }
+                });
+        };
+
+        Ok(TorrentMetainfo {
+            info_hash,
+            name,
+            announce,
+            piece_length,
+            pieces_count,
+            private,
+            files,
+        })
+    }
+    /// In bytes
+    pub fn total_length(&self) -> u64 {
+        self.files.iter().map(|file| file.length).sum()
+    }
+    /// The file played when a torrent stream has no `file_idx`:
+    /// the largest video file which matches the `file_must_include` of the stream.
+    pub fn default_file(&self, file_must_include: &[String]) -> Option<&TorrentFile> {
+        self.files
+            .iter()
+            .filter(|file| file.is_video() && file.matches(file_must_include))
+            .max_by_key(|file| file.length)
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TorrentFile {
+    /// The index of the file used as `file_idx` of torrent streams
+    pub idx: u16,
+    /// The components of the path joined with `/`
+    pub path: String,
+    /// In bytes
+    pub length: u64,
+    /// The position of the first byte of the file in the torrent, in bytes
+    pub offset: u64,
+}
+
+impl TorrentFile {
+    pub fn name(&self) -> &str {
+        self.path.rsplit('/').next().unwrap_or(&self.path)
+    }
+    pub fn is_video(&self) -> bool {
+        Path::new(self.name())
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(|extension| VIDEO_FILE_EXTENSIONS.contains(&extension.to_lowercase().as_str()))
+            .unwrap_or_default()
+    }
+    /// Whether the path contains any of the `file_must_include` of a torrent stream,
+    /// ignoring the case. Every file matches an empty `file_must_include`.
+    pub fn matches(&self, file_must_include: &[String]) -> bool {
+        let path = self.path.to_lowercase();
+        file_must_include.is_empty()
+            || file_must_include
+                .iter()
+                .any(|pattern| path.contains(&pattern.to_lowercase()))
+    }
+}
+
+fn parse_files(files: &[Value]) -> Result<Vec<TorrentFile>, TorrentError> {
+    let mut offset = 0;
+    files
+        .iter()
+        .enumerate()
+        .map(|(idx, file)| {
+            let file = match file {
+                Value::Dict(file) => file,
+                _ => return Err(invalid("file is not a dictionary")),
+            };
+            let idx = u16::try_from(idx).map_err(|_| invalid("too many files"))?;
+            let length = get_u64(file, "length").ok_or_else(|| invalid("missing file length"))?;
+            let path = match file
+                .get(b"path.utf-8".as_slice())
+                .or_else(|| file.get(b"path".as_slice()))
+            {
+                Some(Value::List(components)) if !components.is_empty() => components
+                    .iter()
+                    .map(|component| as_string(component).ok_or_else(|| invalid("invalid path")))
+                    .collect::<Result<Vec<_>, _>>()?
+                    .join("/"),
+                _ => return Err(invalid("missing file path")),
+            };
+            let torrent_file = TorrentFile {
+                idx,
+                path,
+                length,
+                offset,
+            };
+            offset = offset
+                .checked_add(length)
+                .ok_or_else(|| invalid("files are too long"))?;
+            Ok(torrent_file)
+        })
+        .collect()
+}
+
+fn invalid(message: &str) -> TorrentError {
+    TorrentError::InvalidMetainfo(message.to_owned())
+}
+
+fn as_string(value: &Value) -> Option<String> {
+    match value {
+        Value::Bytes(bytes) => Some(String::from_utf8_lossy(bytes).into_owned()),
+        _ => None,
+    }
+}
+
+fn get_string(dict: &Dict, key: &str) -> Option<String> {
+    dict.get(key.as_bytes()).and_then(as_string)
+}
+
+/// Prefers the `.utf-8` variant of the key set by some clients for non UTF-8 torrents.
+fn utf8_string(dict: &Dict, key: &str) -> Option<String> {
+    get_string(dict, &format!("{key}.utf-8")).or_else(|| get_string(dict, key))
+}
+
+fn get_u64(dict: &Dict, key: &str) -> Option<u64> {
+    match dict.get(key.as_bytes()) {
+        Some(Value::Int(value)) => u64::try_from(*value).ok(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overflowing_lengths() {
+        let pieces_overflow = b"d4:infod6:lengthi1e4:name5:Movie12:piece lengthi9223372036854775807e6:pieces60:aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaee";
+        assert_eq!(
+            TorrentMetainfo::from_bytes(pieces_overflow),
+            Err(invalid("pieces are too long"))
+        );
+        let files_overflow = b"d4:infod5:filesld6:lengthi9223372036854775807e4:pathl1:aeed6:lengthi9223372036854775807e4:pathl1:beed6:lengthi9223372036854775807e4:pathl1:ceee4:name5:Movie12:piece lengthi16384e6:pieces20:aaaaaaaaaaaaaaaaaaaaee";
+        assert_eq!(
+            TorrentMetainfo::from_bytes(files_overflow),
+            Err(invalid("files are too long"))
+        );
+    }
+}
@@ -0,0 +1,53 @@
+//! A minimal reader and writer of the RFC 4180 CSV used by the trackers.
+
+/// Reads the records of the CSV, the quoted fields can contain commas, quotes and line breaks.
+pub fn read(data: &str) -> Vec<Vec<String>> {
+    let mut records = vec![];
+    let mut record = vec![];
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = data.trim_start_matches('\u{feff}').chars().peekable();
+    while let Some(char) = chars.next() {
+        match (quoted, char) {
+            (true, '"') if chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            (true, '"') => quoted = false,
+            (true, char) => field.push(char),
+            (false, '"') => quoted = true,
+            (false, ',') => record.push(std::mem::take(&mut field)),
+            (false, '\r') => {}
+            (false, '\n') => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            (false, char) => field.push(char),
+        }
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    records
+        .into_iter()
+        .filter(|record| record.iter().any(|field| !field.is_empty()))
+        .collect()
+}
+
+/// Writes the record as a CSV line, quoting the fields only when needed.
+pub fn write<T: AsRef<str>>(record: &[T]) -> String {
+    let line = record
+        .iter()
+        .map(|field| {
+            let field = field.as_ref();
+            if field.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field.to_owned()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",");
+    format!("{line}\n")
+}
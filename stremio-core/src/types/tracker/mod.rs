@@ -0,0 +1,10 @@
+mod csv;
+
+mod tracker_entry;
+pub use tracker_entry::*;
+
+mod tracker_error;
+pub use tracker_error::*;
+
+mod tracker_format;
+pub use tracker_format::*;
@@ -0,0 +1,132 @@
+use std::marker::PhantomData;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::runtime::Env;
+use crate::types::library::LibraryItem;
+use crate::types::rating::Rating;
+use crate::types::resource::{MetaItemId, SeriesInfo, Video};
+
+/// A title watched or rated on a tracker, matched with the library by its IMDb id (`tt…`)
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TrackerEntry {
+    pub imdb_id: MetaItemId,
+    pub name: String,
+    /// Either `movie` or `series`
+    pub r#type: String,
+    pub year: Option<i32>,
+    pub watched_at: Option<DateTime<Utc>>,
+    /// From 1 to 10
+    pub rating: Option<u8>,
+    /// The watched episodes of a series
+    pub episodes: Vec<SeriesInfo>,
+}
+
+impl TrackerEntry {
+    /// The watched history of the item, `None` when it's not an IMDb title or it was neither watched nor rated.
+    ///
+    /// The watched episodes of a series are taken from its watched bitfield,
+    /// so they are known only when the `videos` of the series are given.
+    pub fn from_library_item(
+        library_item: &LibraryItem,
+        videos: &[Video],
+        rating: Option<&Rating>,
+    ) -> Option<Self> {
+        if !is_imdb_id(&library_item.id)
+            || !matches!(library_item.r#type.as_str(), "movie" | "series")
+        {
+            return None;
+        }
+        let episodes = match (&library_item.state.watched, videos.is_empty()) {
+            (Some(_), false) => {
+                let watched = library_item.state.watched_bitfield(videos);
+                videos
+                    .iter()
+                    .filter(|video| watched.get_video(&video.id))
+                    .filter_map(|video| video.series_info.to_owned())
+                    .collect()
+            }
+            _ => vec![],
+        };
+        let rating = match rating {
+            Some(Rating::Loved) => Some(10),
+            Some(Rating::Liked) => Some(8),
+            _ => None,
+        };
+        if !library_item.watched() && episodes.is_empty() && rating.is_none() {
+            return None;
+        }
+        Some(TrackerEntry {
+            imdb_id: library_item.id.to_owned(),
+            name: library_item.name.to_owned(),
+            r#type: library_item.r#type.to_owned(),
+            year: None,
+            watched_at: library_item.state.last_watched,
+            rating,
+            episodes,
+        })
+    }
+    /// Marks the title as watched in the library item, creating it when it's not in the library yet.
+    ///
+    /// Returns the updated item and the episodes which were not found in the `videos` of the series.
+    pub fn to_library_item<E: Env + 'static>(
+        &self,
+        library_item: Option<&LibraryItem>,
+        videos: &[Video],
+    ) -> (LibraryItem, Vec<SeriesInfo>) {
+        let mut library_item = library_item
+            .cloned()
+            .unwrap_or_else(|| LibraryItem::from((self, PhantomData::<E>)));
+        library_item.removed = false;
+        library_item.temp = false;
+        let mut unknown_episodes = vec![];
+        if !self.episodes.is_empty() && !videos.is_empty() {
+            let mut watched = library_item.state.watched_bitfield(videos);
+            for episode in &self.episodes {
+                match videos
+                    .iter()
+                    .find(|video| video.series_info.as_ref() == Some(episode))
+                {
+                    Some(video) if !watched.get_video(&video.id) => {
+                        watched.set_video(&video.id, true);
+                        library_item.state.times_watched =
+                            library_item.state.times_watched.saturating_add(1);
+                    }
+                    Some(_) => {}
+                    None => unknown_episodes.push(episode.to_owned()),
+                }
+            }
+            library_item.state.watched = Some(watched.into());
+        } else {
+            unknown_episodes.extend(self.episodes.iter().cloned());
+        }
+        if !library_item.watched() {
+            library_item.state.times_watched = 1;
+            if library_item.r#type == "movie" {
+                library_item.state.flagged_watched = 1;
+            }
+        }
+        library_item.state.last_watched = match (library_item.state.last_watched, self.watched_at) {
+            (Some(last_watched), Some(watched_at)) => Some(last_watched.max(watched_at)),
+            (last_watched, watched_at) => last_watched.or(watched_at).or_else(|| Some(E::now())),
+        };
+        (library_item, unknown_episodes)
+    }
+    /// Merges the other entry of the same title, e.g. another watched episode of the series
+    pub(super) fn merge(&mut self, other: TrackerEntry) {
+        self.watched_at = self.watched_at.max(other.watched_at);
+        self.rating = other.rating.or(self.rating);
+        self.year = self.year.or(other.year);
+        for episode in other.episodes {
+            if !self.episodes.contains(&episode) {
+                self.episodes.push(episode);
+            }
+        }
+    }
+}
+
+pub(super) fn is_imdb_id(id: &str) -> bool {
+    id.len() > 2 && id.starts_with("tt") && id[2..].chars().all(|char| char.is_ascii_digit())
+}
@@ -0,0 +1,27 @@
+use std::fmt;
+
+use serde::Serialize;
+
+#[derive(Clone, PartialEq, Eq, Serialize, Debug)]
+#[serde(tag = "type", content = "content")]
+pub enum TrackerError {
+    /// The Trakt file is not a JSON array
+    InvalidJson(String),
+    /// The CSV file has no column with the given name
+    MissingColumn(String),
+}
+
+impl fmt::Display for TrackerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self {
+            TrackerError::InvalidJson(message) => write!(f, "InvalidJson: {message}"),
+            TrackerError::MissingColumn(column) => write!(f, "MissingColumn: {column}"),
+        }
+    }
+}
+
+impl From<serde_json::Error> for TrackerError {
+    fn from(error: serde_json::Error) -> Self {
+        TrackerError::InvalidJson(error.to_string())
+    }
+}
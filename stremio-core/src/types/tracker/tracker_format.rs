@@ -0,0 +1,430 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::types::resource::SeriesInfo;
+use crate::types::tracker::tracker_entry::is_imdb_id;
+use crate::types::tracker::{csv, TrackerEntry, TrackerError};
+
+const LETTERBOXD_HEADER: [&str; 5] = ["Title", "Year", "imdbID", "WatchedDate", "Rating10"];
+const IMDB_HEADER: [&str; 7] = [
+    "Const",
+    "Your Rating",
+    "Date Rated",
+    "Title",
+    "URL",
+    "Title Type",
+    "Year",
+];
+
+/// The file formats of the trackers which the library can be exported to and imported from
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub enum TrackerFormat {
+    /// Trakt JSON, an array of the history, watched and ratings items of the Trakt API
+    Trakt,
+    /// Letterboxd import CSV with an `imdbID` column, movies only
+    Letterboxd,
+    /// IMDb ratings CSV, the unrated titles are exported with an empty rating
+    Imdb,
+}
+
+/// A row of the imported file which could not be matched with a title
+#[derive(Clone, PartialEq, Eq, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct UnmatchedRow {
+    /// The position of the row in the file, starting from 1 and not counting the CSV header
+    pub row: usize,
+    pub title: Option<String>,
+    pub reason: UnmatchedReason,
+}
+
+#[derive(Clone, PartialEq, Eq, Serialize, Debug)]
+#[serde(tag = "type", content = "content")]
+pub enum UnmatchedReason {
+    /// The row has no IMDb id (`tt…`)
+    MissingImdbId,
+    /// The row is neither a movie nor a series, e.g. a rated episode
+    UnsupportedType(String),
+    /// The row is not a valid item of the format
+    InvalidRow(String),
+    /// The watched episodes are not among the videos of the series
+    UnknownEpisodes(Vec<SeriesInfo>),
+}
+
+/// The titles read from the file of a tracker, one entry per IMDb id
+#[derive(Default, Clone, PartialEq, Eq, Debug)]
+pub struct TrackerImport {
+    pub entries: Vec<TrackerEntry>,
+    pub unmatched: Vec<UnmatchedRow>,
+}
+
+impl TrackerImport {
+    fn push(&mut self, entry: TrackerEntry) {
+        match self
+            .entries
+            .iter_mut()
+            .find(|imported| imported.imdb_id == entry.imdb_id)
+        {
+            Some(imported) => imported.merge(entry),
+            None => self.entries.push(entry),
+        }
+    }
+    fn unmatched(&mut self, row: usize, title: Option<&str>, reason: UnmatchedReason) {
+        self.unmatched.push(UnmatchedRow {
+            row,
+            title: title.filter(|title| !title.is_empty()).map(str::to_owned),
+            reason,
+        });
+    }
+}
+
+impl TrackerFormat {
+    pub fn export(&self, entries: &[TrackerEntry]) -> String {
+        match self {
+            TrackerFormat::Trakt => {
+                let items = entries.iter().flat_map(TraktItem::from_entry).collect();
+                serde_json::to_string_pretty::<Vec<TraktItem>>(&items)
+                    .expect("Failed to serialize Trakt items")
+            }
+            TrackerFormat::Letterboxd => std::iter::once(csv::write(&LETTERBOXD_HEADER))
+                .chain(
+                    entries
+                        .iter()
+                        .filter(|entry| entry.r#type == "movie")
+                        .map(|entry| {
+                            csv::write(&[
+                                entry.name.to_owned(),
+                                entry.year.map(|year| year.to_string()).unwrap_or_default(),
+                                entry.imdb_id.to_owned(),
+                                format_date(entry.watched_at),
+                                entry
+                                    .rating
+                                    .map(|rating| rating.to_string())
+                                    .unwrap_or_default(),
+                            ])
+                        }),
+                )
+                .collect(),
+            TrackerFormat::Imdb => std::iter::once(csv::write(&IMDB_HEADER))
+                .chain(entries.iter().map(|entry| {
+                    csv::write(&[
+                        entry.imdb_id.to_owned(),
+                        entry
+                            .rating
+                            .map(|rating| rating.to_string())
+                            .unwrap_or_default(),
+                        format_date(entry.watched_at),
+                        entry.name.to_owned(),
+                        format!("https://www.imdb.com/title/{}/", entry.imdb_id),
+                        match entry.r#type.as_str() {
+                            "series" => "TV Series".to_owned(),
+                            _ => "Movie".to_owned(),
+                        },
+                        entry.year.map(|year| year.to_string()).unwrap_or_default(),
+                    ])
+                }))
+                .collect(),
+        }
+    }
+    pub fn import(&self, data: &str) -> Result<TrackerImport, TrackerError> {
+        match self {
+            TrackerFormat::Trakt => import_trakt(data),
+            TrackerFormat::Letterboxd => import_csv(data, |row, columns, import| {
+                let title = columns.get(row, &["Title", "Name"]);
+                match columns.get(row, &["imdbID", "imdb_id", "Const"]) {
+                    Some(imdb_id) if is_imdb_id(imdb_id) => {
+                        let rating = match columns.get(row, &["Rating10"]) {
+                            Some(rating) => parse_rating(rating, 1.0),
+                            // Letterboxd rates from 0.5 to 5 stars
+                            None => columns
+                                .get(row, &["Rating"])
+                                .and_then(|rating| parse_rating(rating, 2.0)),
+                        };
+                        Ok(TrackerEntry {
+                            imdb_id: imdb_id.to_owned(),
+                            name: title.unwrap_or_default().to_owned(),
+                            r#type: "movie".to_owned(),
+                            year: columns
+                                .get(row, &["Year"])
+                                .and_then(|year| year.parse().ok()),
+                            watched_at: columns
+                                .get(row, &["WatchedDate", "Watched Date", "Date"])
+                                .and_then(parse_date),
+                            rating,
+                            episodes: vec![],
+                        })
+                    }
+                    _ => Err((title, UnmatchedReason::MissingImdbId)),
+                }
+                .map(|entry| import.push(entry))
+            }),
+            TrackerFormat::Imdb => import_csv(data, |row, columns, import| {
+                let title = columns.get(row, &["Title"]);
+                let title_type = columns.get(row, &["Title Type"]).unwrap_or("Movie");
+                let r#type = match title_type {
+                    "Movie" | "movie" | "TV Movie" | "tvMovie" | "Short" | "short" | "Video"
+                    | "video" => "movie",
+                    "TV Series" | "tvSeries" | "TV Mini Series" | "tvMiniSeries" => "series",
+                    _ => {
+                        return Err((
+                            title,
+                            UnmatchedReason::UnsupportedType(title_type.to_owned()),
+                        ))
+                    }
+                };
+                match columns.get(row, &["Const"]) {
+                    Some(imdb_id) if is_imdb_id(imdb_id) => {
+                        import.push(TrackerEntry {
+                            imdb_id: imdb_id.to_owned(),
+                            name: title.unwrap_or_default().to_owned(),
+                            r#type: r#type.to_owned(),
+                            year: columns
+                                .get(row, &["Year"])
+                                .and_then(|year| year.parse().ok()),
+                            watched_at: columns.get(row, &["Date Rated"]).and_then(parse_date),
+                            rating: columns
+                                .get(row, &["Your Rating"])
+                                .and_then(|rating| parse_rating(rating, 1.0)),
+                            episodes: vec![],
+                        });
+                        Ok(())
+                    }
+                    _ => Err((title, UnmatchedReason::MissingImdbId)),
+                }
+            }),
+        }
+    }
+}
+
+/// The indices of the columns of the CSV header
+struct Columns(HashMap<String, usize>);
+
+impl Columns {
+    fn get<'a>(&self, row: &'a [String], names: &[&str]) -> Option<&'a str> {
+        names
+            .iter()
+            .filter_map(|name| self.0.get(*name))
+            .filter_map(|index| row.get(*index))
+            .map(|field| field.trim())
+            .find(|field| !field.is_empty())
+    }
+    fn contains(&self, names: &[&str]) -> bool {
+        names.iter().any(|name| self.0.contains_key(*name))
+    }
+}
+
+fn import_csv<F>(data: &str, import_row: F) -> Result<TrackerImport, TrackerError>
+where
+    F: for<'a> Fn(
+        &'a [String],
+        &Columns,
+        &mut TrackerImport,
+    ) -> Result<(), (Option<&'a str>, UnmatchedReason)>,
+{
+    let mut records = csv::read(data).into_iter();
+    let columns = Columns(
+        records
+            .next()
+            .unwrap_or_default()
+            .into_iter()
+            .enumerate()
+            .map(|(index, name)| (name.trim().to_owned(), index))
+            .collect(),
+    );
+    if !columns.contains(&["Title", "Name"]) {
+        return Err(TrackerError::MissingColumn("Title".to_owned()));
+    }
+    let mut import = TrackerImport::default();
+    for (index, record) in records.enumerate() {
+        if let Err((title, reason)) = import_row(&record, &columns, &mut import) {
+            let title = title.map(str::to_owned);
+            import.unmatched(index + 1, title.as_deref(), reason);
+        }
+    }
+    Ok(import)
+}
+
+fn import_trakt(data: &str) -> Result<TrackerImport, TrackerError> {
+    let values = serde_json::from_str::<Vec<serde_json::Value>>(data)?;
+    let mut import = TrackerImport::default();
+    for (index, value) in values.into_iter().enumerate() {
+        let row = index + 1;
+        let item = match serde_json::from_value::<TraktItem>(value) {
+            Ok(item) => item,
+            Err(error) => {
+                import.unmatched(row, None, UnmatchedReason::InvalidRow(error.to_string()));
+                continue;
+            }
+        };
+        let (media, r#type) = match (&item.movie, &item.show) {
+            (Some(movie), _) => (movie, "movie"),
+            (None, Some(show)) => (show, "series"),
+            (None, None) => {
+                let r#type = item.r#type.to_owned().unwrap_or_default();
+                import.unmatched(row, None, UnmatchedReason::UnsupportedType(r#type));
+                continue;
+            }
+        };
+        let imdb_id = match &media.ids.imdb {
+            Some(imdb_id) if is_imdb_id(imdb_id) => imdb_id.to_owned(),
+            _ => {
+                import.unmatched(row, Some(&media.title), UnmatchedReason::MissingImdbId);
+                continue;
+            }
+        };
+        let episodes = item
+            .episode
+            .iter()
+            .map(|episode| SeriesInfo {
+                season: episode.season,
+                episode: episode.number,
+            })
+            .chain(item.seasons.iter().flat_map(|season| {
+                season.episodes.iter().map(|episode| SeriesInfo {
+                    season: season.number,
+                    episode: episode.number,
+                })
+            }))
+            .collect();
+        import.push(TrackerEntry {
+            imdb_id,
+            name: media.title.to_owned(),
+            r#type: r#type.to_owned(),
+            year: media.year,
+            watched_at: item.watched_at.or(item.last_watched_at),
+            rating: item.rating,
+            episodes,
+        });
+    }
+    Ok(import)
+}
+
+fn parse_rating(rating: &str, scale: f64) -> Option<u8> {
+    rating
+        .parse::<f64>()
+        .ok()
+        .map(|rating| (rating * scale).round())
+        .filter(|rating| (1.0..=10.0).contains(rating))
+        .map(|rating| rating as u8)
+}
+
+fn parse_date(date: &str) -> Option<DateTime<Utc>> {
+    match NaiveDate::parse_from_str(date, "%Y-%m-%d") {
+        Ok(date) => date.and_hms_opt(0, 0, 0).map(|date| date.and_utc()),
+        Err(_) => DateTime::parse_from_rfc3339(date)
+            .ok()
+            .map(|date| date.with_timezone(&Utc)),
+    }
+}
+
+fn format_date(date: Option<DateTime<Utc>>) -> String {
+    date.map(|date| date.format("%Y-%m-%d").to_string())
+        .unwrap_or_default()
+}
+
+/// An item of the history (`watched_at`), watched (`last_watched_at`, `seasons`) or ratings (`rating`) of the Trakt API
+#[derive(Default, Serialize, Deserialize)]
+struct TraktItem {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    watched_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing)]
+    last_watched_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    rated_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    rating: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    r#type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    movie: Option<TraktMedia>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    show: Option<TraktMedia>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    episode: Option<TraktEpisode>,
+    #[serde(default, skip_serializing)]
+    seasons: Vec<TraktSeason>,
+}
+
+impl TraktItem {
+    /// The history items of the watched movie or episodes and the ratings item of the rated title
+    fn from_entry(entry: &TrackerEntry) -> Vec<TraktItem> {
+        let media = TraktMedia {
+            title: entry.name.to_owned(),
+            year: entry.year,
+            ids: TraktIds {
+                imdb: Some(entry.imdb_id.to_owned()),
+            },
+        };
+        let (media_type, movie, show) = match entry.r#type.as_str() {
+            "series" => ("show", None, Some(media)),
+            _ => ("movie", Some(media), None),
+        };
+        let history = match (media_type, entry.episodes.is_empty()) {
+            ("show", false) => entry
+                .episodes
+                .iter()
+                .map(|episode| TraktItem {
+                    watched_at: entry.watched_at,
+                    r#type: Some("episode".to_owned()),
+                    show: show.to_owned(),
+                    episode: Some(TraktEpisode {
+                        season: episode.season,
+                        number: episode.episode,
+                    }),
+                    ..Default::default()
+                })
+                .collect(),
+            _ if entry.watched_at.is_some() => vec![TraktItem {
+                watched_at: entry.watched_at,
+                r#type: Some(media_type.to_owned()),
+                movie: movie.to_owned(),
+                show: show.to_owned(),
+                ..Default::default()
+            }],
+            _ => vec![],
+        };
+        let ratings = entry.rating.map(|rating| TraktItem {
+            rated_at: entry.watched_at,
+            rating: Some(rating),
+            r#type: Some(media_type.to_owned()),
+            movie,
+            show,
+            ..Default::default()
+        });
+        history.into_iter().chain(ratings).collect()
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct TraktMedia {
+    title: String,
+    #[serde(default)]
+    year: Option<i32>,
+    ids: TraktIds,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct TraktIds {
+    #[serde(default)]
+    imdb: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TraktEpisode {
+    season: u32,
+    number: u32,
+}
+
+#[derive(Deserialize)]
+struct TraktSeason {
+    number: u32,
+    #[serde(default)]
+    episodes: Vec<TraktSeasonEpisode>,
+}
+
+#[derive(Deserialize)]
+struct TraktSeasonEpisode {
+    number: u32,
+}
@@ -0,0 +1,2 @@
+mod watch_party_message;
+pub use watch_party_message::*;
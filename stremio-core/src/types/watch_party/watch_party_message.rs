@@ -0,0 +1,60 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::types::addon::ResourceRequest;
+use crate::types::resource::Stream;
+
+pub type WatchPartyMemberId = String;
+
+/// A message of the watch party sync protocol,
+/// exchanged between the members through a [`WatchPartyTransport`].
+///
+/// [`WatchPartyTransport`]: crate::watch_party_transport::WatchPartyTransport
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchPartyMessage {
+    pub party_id: String,
+    /// The member who sent the message
+    pub member_id: WatchPartyMemberId,
+    /// The clock of the sender when the message was sent
+    pub sent_at: DateTime<Utc>,
+    pub payload: WatchPartyPayload,
+}
+
+/// The times are the playback positions in milliseconds
+/// at the moment the message was sent.
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum WatchPartyPayload {
+    Join,
+    Leave,
+    /// Request for estimating the clock offset between the members
+    Ping,
+    /// Reply to the [`WatchPartyPayload::Ping`] of the given member,
+    /// sent right after the ping is received
+    #[serde(rename_all = "camelCase")]
+    Pong {
+        to: WatchPartyMemberId,
+        ping_sent_at: DateTime<Utc>,
+    },
+    Play {
+        time: u64,
+    },
+    Pause {
+        time: u64,
+    },
+    Seek {
+        time: u64,
+    },
+    /// The playback state of the host, sent regularly for correcting the drift of the other members
+    State {
+        paused: bool,
+        time: u64,
+    },
+    #[serde(rename_all = "camelCase")]
+    SelectStream {
+        stream: Stream,
+        stream_request: Option<ResourceRequest>,
+        meta_request: Option<ResourceRequest>,
+    },
+}
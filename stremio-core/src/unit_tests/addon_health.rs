@@ -0,0 +1,205 @@
+use semver::Version;
+use stremio_derive::Model;
+use url::Url;
+
+use crate::addon_transport::AddonBuilder;
+use crate::constants::{
+    ADDON_HEALTH_STORAGE_INTERVAL, ADDON_HEALTH_STORAGE_KEY, META_RESOURCE_NAME,
+    STREAM_RESOURCE_NAME,
+};
+use crate::models::addon_health::AddonHealth;
+use crate::models::ctx::Ctx;
+use crate::models::installed_addons_with_filters::{
+    InstalledAddonsRequest, InstalledAddonsSort, InstalledAddonsWithFilters,
+    Selected as InstalledAddonsSelected,
+};
+use crate::models::meta_details::{MetaDetails, Selected as MetaDetailsSelected};
+use crate::runtime::msg::{Action, ActionLoad};
+use crate::runtime::{Env, EnvError, EnvFutureExt, Runtime, RuntimeAction};
+use crate::types::addon::{Descriptor, Manifest, ResourcePath, ResourceResponse};
+use crate::types::addon_health::{AddonErrorKind, AddonHealthBucket, AddonHealthStatus};
+use crate::types::profile::Profile;
+use crate::unit_tests::{TestEnv, ADDON_TRANSPORTS, NOW, STORAGE};
+
+#[derive(Model, Default, Clone, Debug)]
+#[model(TestEnv)]
+struct TestModel {
+    ctx: Ctx,
+    meta_details: MetaDetails,
+    installed_addons: InstalledAddonsWithFilters,
+    addon_health: AddonHealth,
+}
+
+fn manifest(id: &str) -> Manifest {
+    Manifest {
+        id: id.to_owned(),
+        version: Version::new(1, 0, 0),
+        name: id.to_owned(),
+        contact_email: None,
+        description: None,
+        logo: None,
+        background: None,
+        types: vec!["movie".to_owned()],
+        resources: vec![STREAM_RESOURCE_NAME.into()],
+        id_prefixes: Some(vec!["tt".to_owned()]),
+        catalogs: vec![],
+        addon_catalogs: vec![],
+        behavior_hints: Default::default(),
+    }
+}
+
+fn descriptor(id: &str) -> Descriptor {
+    Descriptor {
+        manifest: manifest(id),
+        transport_url: Url::parse(&format!("{id}://addon/manifest.json")).unwrap(),
+        flags: Default::default(),
+    }
+}
+
+/// Every request to the addon takes the given time and fails if `error` is set.
+fn register_addon(id: &str, latency: i64, error: bool) {
+    let addon = AddonBuilder::new(manifest(id))
+        .define_stream_handler(move |_| {
+            async move {
+                *NOW.write().unwrap() = TestEnv::now() + chrono::Duration::milliseconds(latency);
+                if error {
+                    Err(EnvError::Fetch("Connection refused".to_owned()))
+                } else {
+                    Ok(ResourceResponse::Streams { streams: vec![] })
+                }
+            }
+            .boxed_env()
+        })
+        .build()
+        .unwrap();
+    let registry = ADDON_TRANSPORTS.read().unwrap().to_owned();
+    *ADDON_TRANSPORTS.write().unwrap() = registry.register(id, move |_| Box::new(addon.to_owned()));
+}
+
+#[test]
+fn addon_health() {
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    register_addon("fast", 100, false);
+    register_addon("slow", 5_000, false);
+    register_addon("broken", 200, true);
+    let profile = Profile {
+        addons: vec![descriptor("fast"), descriptor("slow"), descriptor("broken")],
+        ..Default::default()
+    };
+    let (addon_health, _) = AddonHealth::new(&profile, &AddonHealthBucket::default());
+    let (runtime, _rx) = Runtime::<TestEnv, _>::new(
+        TestModel {
+            ctx: Ctx {
+                profile,
+                ..Default::default()
+            },
+            addon_health,
+            ..Default::default()
+        },
+        vec![],
+        1000,
+    );
+    let load_streams = |id: &str| {
+        TestEnv::run(|| {
+            runtime.dispatch(RuntimeAction {
+                field: None,
+                action: Action::Load(ActionLoad::MetaDetails(MetaDetailsSelected {
+                    meta_path: ResourcePath::without_extra(META_RESOURCE_NAME, "movie", id),
+                    stream_path: Some(ResourcePath::without_extra(
+                        STREAM_RESOURCE_NAME,
+                        "movie",
+                        id,
+                    )),
+                    guess_stream: false,
+                })),
+            })
+        })
+    };
+    let stored_addon_health = || {
+        STORAGE
+            .read()
+            .unwrap()
+            .get(ADDON_HEALTH_STORAGE_KEY)
+            .map(|data| serde_json::from_str::<AddonHealthBucket>(data).unwrap())
+            .expect("Addon health should be persisted")
+    };
+    load_streams("tt1");
+    {
+        // the requests are handled one after another,
+        // so the latency of every addon includes the latency of the previous ones
+        let model = runtime.model().unwrap();
+        assert_eq!(
+            model
+                .addon_health
+                .items
+                .iter()
+                .map(|item| (item.name.as_str(), item.status, item.latency))
+                .collect::<Vec<_>>(),
+            vec![
+                ("broken", Some(AddonHealthStatus::Failing), Some(5_300)),
+                ("slow", Some(AddonHealthStatus::Slow), Some(5_100)),
+                ("fast", Some(AddonHealthStatus::Healthy), Some(100)),
+            ],
+            "failing and slow addons first"
+        );
+        let broken = &model.addon_health.items[0];
+        assert_eq!(broken.error_rate, 1.0);
+        assert_eq!(broken.error_kinds.get(&AddonErrorKind::Fetch), Some(&1));
+        assert!(broken.last_success.is_none() && broken.last_error.is_some());
+        assert!(model.ctx.addon_health.pending.is_empty());
+        assert_eq!(
+            stored_addon_health().items,
+            model.ctx.addon_health.items,
+            "addon health persisted when the status of the addons changes"
+        );
+    }
+    load_streams("tt2");
+    {
+        let model = runtime.model().unwrap();
+        assert!(
+            model
+                .ctx
+                .addon_health
+                .items
+                .values()
+                .all(|stats| stats.samples.len() == 2),
+            "requests recorded"
+        );
+        assert!(
+            stored_addon_health()
+                .items
+                .values()
+                .all(|stats| stats.samples.len() == 1),
+            "addon health is not persisted on every request"
+        );
+    }
+    *NOW.write().unwrap() = TestEnv::now() + ADDON_HEALTH_STORAGE_INTERVAL;
+    load_streams("tt3");
+    assert_eq!(
+        stored_addon_health()
+            .items
+            .get(&descriptor("fast").transport_url)
+            .map(|stats| stats.samples.len()),
+        Some(3),
+        "addon health persisted once per interval"
+    );
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Load(ActionLoad::InstalledAddonsWithFilters(
+                InstalledAddonsSelected {
+                    request: InstalledAddonsRequest {
+                        r#type: None,
+                        sort: InstalledAddonsSort::Health,
+                        health: Some(AddonHealthStatus::Slow),
+                    },
+                },
+            )),
+        })
+    });
+    assert_eq!(
+        runtime.model().unwrap().installed_addons.catalog,
+        vec![descriptor("slow")],
+        "installed addons filtered by health"
+    );
+}
@@ -0,0 +1,247 @@
+use futures::channel::mpsc::Receiver;
+use futures::future;
+use semver::Version;
+use stremio_derive::Model;
+use url::Url;
+
+use crate::addon_transport::AddonBuilder;
+use crate::constants::{ADDON_STREAM_REQUEST_TIMEOUT, META_RESOURCE_NAME, STREAM_RESOURCE_NAME};
+use crate::models::common::{Loadable, ResourceError};
+use crate::models::ctx::Ctx;
+use crate::models::meta_details::{MetaDetails, Selected as MetaDetailsSelected};
+use crate::runtime::msg::{Action, ActionLoad};
+use crate::runtime::{Env, EnvError, EnvFutureExt, Runtime, RuntimeAction, RuntimeEvent};
+use crate::types::addon::{Descriptor, Manifest, ResourcePath, ResourceResponse};
+use crate::types::addon_health::AddonErrorKind;
+use crate::types::profile::Profile;
+use crate::unit_tests::{TestEnv, ADDON_TRANSPORTS, NOW};
+
+#[derive(Model, Default, Clone, Debug)]
+#[model(TestEnv)]
+struct TestModel {
+    ctx: Ctx,
+    meta_details: MetaDetails,
+}
+
+fn manifest() -> Manifest {
+    Manifest {
+        id: "unresponsive".to_owned(),
+        version: Version::new(1, 0, 0),
+        name: "unresponsive".to_owned(),
+        contact_email: None,
+        description: None,
+        logo: None,
+        background: None,
+        types: vec!["movie".to_owned()],
+        resources: vec![STREAM_RESOURCE_NAME.into()],
+        id_prefixes: Some(vec!["tt".to_owned()]),
+        catalogs: vec![],
+        addon_catalogs: vec![],
+        behavior_hints: Default::default(),
+    }
+}
+
+fn transport_url() -> Url {
+    Url::parse("unresponsive://addon/manifest.json").unwrap()
+}
+
+/// The addon never responds to the stream requests.
+fn register_addon() {
+    let addon = AddonBuilder::new(manifest())
+        .define_stream_handler(|_| {
+            future::pending::<Result<ResourceResponse, EnvError>>().boxed_env()
+        })
+        .build()
+        .unwrap();
+    let registry = ADDON_TRANSPORTS.read().unwrap().to_owned();
+    *ADDON_TRANSPORTS.write().unwrap() =
+        registry.register("unresponsive", move |_| Box::new(addon.to_owned()));
+}
+
+fn ctx() -> Ctx {
+    Ctx {
+        profile: Profile {
+            addons: vec![Descriptor {
+                manifest: manifest(),
+                transport_url: transport_url(),
+                flags: Default::default(),
+            }],
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+fn runtime() -> (
+    Runtime<TestEnv, TestModel>,
+    Receiver<RuntimeEvent<TestEnv, TestModel>>,
+) {
+    Runtime::<TestEnv, _>::new(
+        TestModel {
+            ctx: ctx(),
+            ..Default::default()
+        },
+        vec![],
+        1000,
+    )
+}
+
+fn load_action() -> Action {
+    Action::Load(ActionLoad::MetaDetails(MetaDetailsSelected {
+        meta_path: ResourcePath::without_extra(META_RESOURCE_NAME, "movie", "tt1"),
+        stream_path: Some(ResourcePath::without_extra(
+            STREAM_RESOURCE_NAME,
+            "movie",
+            "tt1",
+        )),
+        guess_stream: false,
+    }))
+}
+
+#[test]
+fn addon_request_timeout() {
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    register_addon();
+    let (runtime, _rx) = runtime();
+    let started_at = TestEnv::now();
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: load_action(),
+        })
+    });
+    let model = runtime.model().unwrap();
+    assert_eq!(model.meta_details.streams.len(), 1);
+    assert!(
+        matches!(
+            &model.meta_details.streams[0].content,
+            Some(Loadable::Err(ResourceError::Env(EnvError::Timeout(timeout))))
+                if *timeout == ADDON_STREAM_REQUEST_TIMEOUT.num_milliseconds() as u64
+        ),
+        "stream request timed out"
+    );
+    assert_eq!(
+        *NOW.read().unwrap(),
+        started_at + ADDON_STREAM_REQUEST_TIMEOUT,
+        "waited for the stream request timeout"
+    );
+    let stats = model
+        .ctx
+        .addon_health
+        .items
+        .get(&transport_url())
+        .expect("Addon stats");
+    assert_eq!(stats.error_kinds().get(&AddonErrorKind::Timeout), Some(&1));
+    assert!(model.ctx.addon_health.pending.is_empty());
+}
+
+#[test]
+fn addon_request_cancel_on_unload() {
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    register_addon();
+    let (runtime, _rx) = runtime();
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: load_action(),
+        });
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Unload,
+        });
+    });
+    let model = runtime.model().unwrap();
+    assert!(model.meta_details.streams.is_empty());
+    assert!(
+        model.ctx.addon_health.pending.is_empty(),
+        "cancelled request is no longer pending"
+    );
+    assert!(
+        model.ctx.addon_health.items.is_empty(),
+        "cancelled request has not timed out"
+    );
+}
+
+#[test]
+fn addon_request_cancel_on_reload() {
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    register_addon();
+    let (runtime, _rx) = runtime();
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: load_action(),
+        });
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Load(ActionLoad::MetaDetails(MetaDetailsSelected {
+                meta_path: ResourcePath::without_extra(META_RESOURCE_NAME, "movie", "tt2"),
+                stream_path: Some(ResourcePath::without_extra(
+                    STREAM_RESOURCE_NAME,
+                    "movie",
+                    "tt2",
+                )),
+                guess_stream: false,
+            })),
+        });
+    });
+    let model = runtime.model().unwrap();
+    let stats = model
+        .ctx
+        .addon_health
+        .items
+        .get(&transport_url())
+        .expect("Addon stats");
+    assert_eq!(
+        stats.samples.len(),
+        1,
+        "only the request of the selected meta item is recorded"
+    );
+    assert!(model.ctx.addon_health.pending.is_empty());
+    assert_eq!(
+        model.meta_details.streams[0].request.path.id, "tt2",
+        "streams of the selected meta item"
+    );
+}
+
+#[derive(Model, Default, Clone, Debug)]
+#[model(TestEnv)]
+struct SharedRequestTestModel {
+    ctx: Ctx,
+    meta_details: MetaDetails,
+    other_meta_details: MetaDetails,
+}
+
+#[test]
+fn addon_request_cancel_shared() {
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    register_addon();
+    let (runtime, _rx) = Runtime::<TestEnv, _>::new(
+        SharedRequestTestModel {
+            ctx: ctx(),
+            ..Default::default()
+        },
+        vec![],
+        1000,
+    );
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: load_action(),
+        });
+        runtime.dispatch(RuntimeAction {
+            field: Some(SharedRequestTestModelField::MetaDetails),
+            action: Action::Unload,
+        });
+    });
+    let model = runtime.model().unwrap();
+    assert!(model.meta_details.streams.is_empty());
+    assert!(
+        matches!(
+            &model.other_meta_details.streams[0].content,
+            Some(Loadable::Err(ResourceError::Env(EnvError::Timeout(_))))
+        ),
+        "request is not cancelled for the other model"
+    );
+    assert!(model.ctx.addon_health.pending.is_empty());
+}
@@ -0,0 +1,223 @@
+use futures::future;
+use semver::Version;
+use serde_json::json;
+use stremio_derive::Model;
+use url::Url;
+
+use crate::{
+    addon_transport::{AddonBuilder, AddonBuilderError, AddonTransport, LocalAddon},
+    models::{
+        catalog_with_filters::{CatalogWithFilters, Selected},
+        common::{Loadable, ResourceLoadable},
+        ctx::Ctx,
+    },
+    runtime::{
+        msg::{Action, ActionLoad},
+        Env, EnvError, EnvFutureExt, Runtime, RuntimeAction,
+    },
+    types::{
+        addon::{
+            Descriptor, Manifest, ManifestCatalog, ManifestExtra, ResourcePath, ResourceRequest,
+            ResourceResponse,
+        },
+        profile::Profile,
+        resource::MetaItemPreview,
+    },
+    unit_tests::{TestEnv, ADDON_TRANSPORTS, REQUESTS},
+};
+
+const TRANSPORT_URL: &str = "local://example/manifest.json";
+
+fn manifest() -> Manifest {
+    Manifest {
+        id: "org.example.local".to_owned(),
+        version: Version::new(1, 0, 0),
+        name: "Local".to_owned(),
+        contact_email: None,
+        description: None,
+        logo: None,
+        background: None,
+        types: vec!["movie".to_owned()],
+        resources: vec!["stream".into()],
+        id_prefixes: Some(vec!["tt".to_owned()]),
+        catalogs: vec![ManifestCatalog {
+            id: "top".to_owned(),
+            r#type: "movie".to_owned(),
+            name: None,
+            extra: ManifestExtra::default(),
+        }],
+        addon_catalogs: vec![],
+        behavior_hints: Default::default(),
+    }
+}
+
+fn addon() -> LocalAddon {
+    AddonBuilder::new(manifest())
+        .define_catalog_handler(|path| {
+            future::ok(ResourceResponse::Metas {
+                metas: vec![MetaItemPreview {
+                    id: format!(
+                        "tt{}",
+                        path.get_extra_first_value("skip").map_or("0", |skip| skip)
+                    ),
+                    r#type: path.r#type.to_owned(),
+                    ..Default::default()
+                }],
+            })
+            .boxed_env()
+        })
+        .define_stream_handler(|_path| {
+            future::ok(ResourceResponse::Streams { streams: vec![] }).boxed_env()
+        })
+        .build()
+        .expect("Addon should be built")
+}
+
+#[test]
+fn local_addon_builder() {
+    assert_eq!(
+        AddonBuilder::new(manifest())
+            .define_catalog_handler(
+                |_| future::ok(ResourceResponse::Metas { metas: vec![] }).boxed_env()
+            )
+            .build()
+            .err(),
+        Some(AddonBuilderError::MissingHandler("stream".to_owned()))
+    );
+    assert_eq!(
+        AddonBuilder::new(manifest())
+            .define_catalog_handler(
+                |_| future::ok(ResourceResponse::Metas { metas: vec![] }).boxed_env()
+            )
+            .define_stream_handler(
+                |_| future::ok(ResourceResponse::Streams { streams: vec![] }).boxed_env()
+            )
+            .define_meta_handler(
+                |_| future::ok(ResourceResponse::Metas { metas: vec![] }).boxed_env()
+            )
+            .build()
+            .err(),
+        Some(AddonBuilderError::UndeclaredResource("meta".to_owned()))
+    );
+}
+
+#[test]
+fn local_addon_serve() {
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    let addon = addon();
+    assert!(
+        addon.serve("/meta/movie/tt1.json").is_none(),
+        "Undeclared resource is not served"
+    );
+    assert!(
+        addon.serve("/stream/movie/kitsu:1.json").is_none(),
+        "Unsupported id prefix is not served"
+    );
+    assert!(addon.serve("/stream/movie.json").is_none());
+    TestEnv::run(|| {
+        TestEnv::exec_concurrent(async move {
+            assert_eq!(
+                addon.serve("/manifest.json").unwrap().await,
+                Ok(serde_json::to_value(manifest()).unwrap())
+            );
+            assert_eq!(
+                addon
+                    .serve("/catalog/movie/top.json")
+                    .unwrap()
+                    .await
+                    .map(|body| serde_json::from_value::<ResourceResponse>(body).unwrap()),
+                Ok(ResourceResponse::Metas {
+                    metas: vec![MetaItemPreview {
+                        id: "tt0".to_owned(),
+                        r#type: "movie".to_owned(),
+                        ..Default::default()
+                    }]
+                })
+            );
+            assert_eq!(
+                addon.serve("/stream/movie/tt1.json?query").unwrap().await,
+                Ok(json!({ "streams": [] }))
+            );
+        })
+    });
+}
+
+#[test]
+fn local_addon_transport() {
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    let addon = addon();
+    TestEnv::run(|| {
+        TestEnv::exec_concurrent(async move {
+            assert_eq!(AddonTransport::manifest(&addon).await, Ok(manifest()));
+            assert_eq!(
+                addon
+                    .resource(&ResourcePath::without_extra("meta", "movie", "tt1"))
+                    .await,
+                Err(EnvError::AddonTransport(
+                    "Resource not supported by org.example.local: meta/movie/tt1".to_owned()
+                ))
+            );
+        })
+    });
+}
+
+#[test]
+fn local_addon_catalog_with_filters() {
+    #[derive(Model, Clone, Debug)]
+    #[model(TestEnv)]
+    struct TestModel {
+        ctx: Ctx,
+        discover: CatalogWithFilters<MetaItemPreview>,
+    }
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    let addon = addon();
+    let registry = ADDON_TRANSPORTS.read().unwrap().to_owned();
+    *ADDON_TRANSPORTS.write().unwrap() =
+        registry.register("local", move |_| Box::new(addon.to_owned()));
+    let transport_url = Url::parse(TRANSPORT_URL).unwrap();
+    let ctx = Ctx {
+        profile: Profile {
+            addons: vec![Descriptor {
+                manifest: manifest(),
+                transport_url: transport_url.to_owned(),
+                flags: Default::default(),
+            }],
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let (discover, effects) = CatalogWithFilters::<MetaItemPreview>::new(&ctx.profile);
+    let (runtime, _rx) = Runtime::<TestEnv, _>::new(
+        TestModel { ctx, discover },
+        effects.into_iter().collect::<Vec<_>>(),
+        1000,
+    );
+    let request = ResourceRequest {
+        base: transport_url,
+        path: ResourcePath::without_extra("catalog", "movie", "top"),
+    };
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Load(ActionLoad::CatalogWithFilters(Some(Selected {
+                request: request.to_owned(),
+            }))),
+        })
+    });
+    assert_eq!(
+        runtime.model().unwrap().discover.catalog,
+        vec![ResourceLoadable {
+            request,
+            content: Some(Loadable::Ready(vec![MetaItemPreview {
+                id: "tt0".to_owned(),
+                r#type: "movie".to_owned(),
+                ..Default::default()
+            }])),
+        }],
+        "Catalog loaded from the local addon"
+    );
+    assert!(
+        REQUESTS.read().unwrap().is_empty(),
+        "No fetch requests made"
+    );
+}
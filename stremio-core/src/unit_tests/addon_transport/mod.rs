@@ -0,0 +1,3 @@
+mod local_transport;
+mod resource_cache;
+mod transport_registry;
@@ -0,0 +1,216 @@
+use std::{any::Any, cell::RefCell, rc::Rc};
+
+use chrono::{Duration, TimeZone, Utc};
+use enclose::enclose;
+use futures::{future, FutureExt};
+use url::Url;
+
+use crate::{
+    addon_transport::{AddonHTTPTransport, AddonTransport},
+    constants::{RESOURCE_CACHE_INDEX_STORAGE_KEY, RESOURCE_CACHE_TTL},
+    runtime::{Env, EnvError, EnvFutureExt},
+    types::{
+        addon::{ResourceCacheIndex, ResourcePath, ResourceResponse, ResourceResponseCache},
+        resource::MetaItemPreview,
+    },
+    unit_tests::{default_fetch_handler, Request, TestEnv, FETCH_HANDLER, NOW, REQUESTS, STORAGE},
+};
+
+const CATALOG_URL: &str = "https://addon.com/catalog/movie/top.json";
+
+fn metas(id: &str) -> ResourceResponse {
+    ResourceResponse::Metas {
+        metas: vec![MetaItemPreview {
+            id: id.to_owned(),
+            r#type: "movie".to_owned(),
+            ..Default::default()
+        }],
+    }
+}
+
+/// Responds to the catalog request with the given response or fails the request if `None`.
+fn set_catalog_response(response: Option<ResourceResponseCache>) {
+    *FETCH_HANDLER.write().unwrap() = Box::new(move |request: Request| match &response {
+        Some(response) if request.url == CATALOG_URL => {
+            future::ok(Box::new(response.to_owned()) as Box<dyn Any + Send>).boxed_env()
+        }
+        Some(_) => default_fetch_handler(request),
+        None => future::err(EnvError::Fetch("Network error".to_owned())).boxed_env(),
+    });
+}
+
+fn request_catalog() -> Result<ResourceResponse, EnvError> {
+    let result = Rc::new(RefCell::new(None));
+    TestEnv::run(enclose!(
+        (result) || {
+            TestEnv::exec_concurrent(
+                AddonHTTPTransport::<TestEnv>::new(
+                    Url::parse("https://addon.com/manifest.json").unwrap(),
+                )
+                .resource(&ResourcePath::without_extra("catalog", "movie", "top"))
+                .map(move |response| *result.borrow_mut() = Some(response)),
+            )
+        }
+    ));
+    let result = result.borrow_mut().take();
+    result.expect("Request should have finished")
+}
+
+#[test]
+fn resource_cache_fresh() {
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    *NOW.write().unwrap() = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+    set_catalog_response(Some(ResourceResponseCache {
+        cache_max_age: Some(60),
+        ..metas("tt1").into()
+    }));
+
+    assert_eq!(request_catalog(), Ok(metas("tt1")));
+    assert_eq!(REQUESTS.read().unwrap().len(), 1);
+
+    *NOW.write().unwrap() = TestEnv::now() + Duration::seconds(30);
+    assert_eq!(
+        request_catalog(),
+        Ok(metas("tt1")),
+        "Fresh entry should be served"
+    );
+    assert_eq!(
+        REQUESTS.read().unwrap().len(),
+        1,
+        "No request should be made for a fresh entry"
+    );
+
+    *NOW.write().unwrap() = TestEnv::now() + Duration::seconds(30);
+    set_catalog_response(Some(metas("tt2").into()));
+    assert_eq!(
+        request_catalog(),
+        Ok(metas("tt2")),
+        "Expired entry should be refetched"
+    );
+    assert_eq!(REQUESTS.read().unwrap().len(), 2);
+}
+
+#[test]
+fn resource_cache_without_hints() {
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    set_catalog_response(Some(metas("tt1").into()));
+
+    assert_eq!(request_catalog(), Ok(metas("tt1")));
+    assert_eq!(request_catalog(), Ok(metas("tt1")));
+    assert_eq!(
+        REQUESTS.read().unwrap().len(),
+        2,
+        "Responses without cache hints should not be cached"
+    );
+}
+
+#[test]
+fn resource_cache_stale_while_revalidate() {
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    *NOW.write().unwrap() = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+    set_catalog_response(Some(ResourceResponseCache {
+        cache_max_age: Some(60),
+        stale_revalidate: Some(600),
+        ..metas("tt1").into()
+    }));
+    assert_eq!(request_catalog(), Ok(metas("tt1")));
+
+    *NOW.write().unwrap() = TestEnv::now() + Duration::seconds(120);
+    set_catalog_response(Some(ResourceResponseCache {
+        cache_max_age: Some(60),
+        ..metas("tt2").into()
+    }));
+    assert_eq!(
+        request_catalog(),
+        Ok(metas("tt1")),
+        "Stale entry should be served while revalidating"
+    );
+    assert_eq!(
+        REQUESTS.read().unwrap().len(),
+        2,
+        "Stale entry should be revalidated in the background"
+    );
+    assert_eq!(
+        request_catalog(),
+        Ok(metas("tt2")),
+        "Revalidated entry should be served"
+    );
+    assert_eq!(REQUESTS.read().unwrap().len(), 2);
+}
+
+#[test]
+fn resource_cache_stale_if_error() {
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    *NOW.write().unwrap() = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+    set_catalog_response(Some(ResourceResponseCache {
+        cache_max_age: Some(60),
+        stale_error: Some(600),
+        ..metas("tt1").into()
+    }));
+    assert_eq!(request_catalog(), Ok(metas("tt1")));
+
+    set_catalog_response(None);
+    *NOW.write().unwrap() = TestEnv::now() + Duration::seconds(120);
+    assert_eq!(
+        request_catalog(),
+        Ok(metas("tt1")),
+        "Stale entry should be served when the request fails"
+    );
+
+    *NOW.write().unwrap() = TestEnv::now() + Duration::seconds(600);
+    assert_eq!(
+        request_catalog(),
+        Err(EnvError::Fetch("Network error".to_owned())),
+        "Expired entry should not be served when the request fails"
+    );
+    assert_eq!(REQUESTS.read().unwrap().len(), 3);
+}
+
+#[test]
+fn resource_cache_eviction() {
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    *NOW.write().unwrap() = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+    let cache_index = || {
+        STORAGE
+            .read()
+            .unwrap()
+            .get(RESOURCE_CACHE_INDEX_STORAGE_KEY)
+            .map(|index| serde_json::from_str::<ResourceCacheIndex>(index).unwrap())
+            .unwrap_or_default()
+    };
+    set_catalog_response(Some(ResourceResponseCache {
+        cache_max_age: Some(60),
+        ..metas("tt1").into()
+    }));
+    assert_eq!(request_catalog(), Ok(metas("tt1")));
+    let storage_keys = cache_index().entries.into_keys().collect::<Vec<_>>();
+    assert_eq!(storage_keys.len(), 1, "The entry should be indexed");
+    assert!(STORAGE.read().unwrap().contains_key(&storage_keys[0]));
+
+    *NOW.write().unwrap() = TestEnv::now() + RESOURCE_CACHE_TTL + Duration::days(1);
+    *FETCH_HANDLER.write().unwrap() = Box::new(|request: Request| {
+        if request.url == "https://addon.com/catalog/movie/other.json" {
+            future::ok(Box::new(ResourceResponseCache {
+                cache_max_age: Some(60),
+                ..metas("tt2").into()
+            }) as Box<dyn Any + Send>)
+            .boxed_env()
+        } else {
+            default_fetch_handler(request)
+        }
+    });
+    TestEnv::run(|| {
+        TestEnv::exec_concurrent(
+            AddonHTTPTransport::<TestEnv>::new(
+                Url::parse("https://addon.com/manifest.json").unwrap(),
+            )
+            .resource(&ResourcePath::without_extra("catalog", "movie", "other"))
+            .map(|_| ()),
+        )
+    });
+    assert!(
+        !STORAGE.read().unwrap().contains_key(&storage_keys[0]),
+        "The entry older than the TTL should be removed from the storage"
+    );
+    assert_eq!(cache_index().entries.len(), 1);
+}
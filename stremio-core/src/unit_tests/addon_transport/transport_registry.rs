@@ -0,0 +1,135 @@
+use futures::future;
+use semver::Version;
+use stremio_derive::Model;
+use url::Url;
+
+use crate::{
+    addon_transport::AddonTransport,
+    models::{
+        addon_details::{AddonDetails, Selected},
+        common::{DescriptorLoadable, Loadable},
+        ctx::Ctx,
+    },
+    runtime::{
+        msg::{Action, ActionLoad},
+        Env, EnvError, EnvFutureExt, Runtime, RuntimeAction, TryEnvFuture,
+    },
+    types::addon::{Descriptor, Manifest, ResourcePath, ResourceResponse},
+    unit_tests::{TestEnv, ADDON_TRANSPORTS, REQUESTS},
+};
+
+struct LocalTransport {
+    transport_url: Url,
+}
+
+impl AddonTransport for LocalTransport {
+    fn resource(&self, _path: &ResourcePath) -> TryEnvFuture<ResourceResponse> {
+        future::ok(ResourceResponse::Metas { metas: vec![] }).boxed_env()
+    }
+    fn manifest(&self) -> TryEnvFuture<Manifest> {
+        future::ok(manifest(self.transport_url.host_str().unwrap_or_default())).boxed_env()
+    }
+}
+
+fn manifest(id: &str) -> Manifest {
+    Manifest {
+        id: id.to_owned(),
+        version: Version::new(0, 0, 1),
+        name: "Local".to_owned(),
+        contact_email: None,
+        description: None,
+        logo: None,
+        background: None,
+        types: vec![],
+        resources: vec![],
+        id_prefixes: None,
+        catalogs: vec![],
+        addon_catalogs: vec![],
+        behavior_hints: Default::default(),
+    }
+}
+
+fn register_local_transport() {
+    let registry = ADDON_TRANSPORTS.read().unwrap().to_owned();
+    *ADDON_TRANSPORTS.write().unwrap() = registry.register("local", |transport_url| {
+        Box::new(LocalTransport {
+            transport_url: transport_url.to_owned(),
+        })
+    });
+}
+
+#[test]
+fn addon_transport_registry() {
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    let local_url = Url::parse("local://addon/manifest.json").unwrap();
+    let unknown_url = Url::parse("ipfs://addon/manifest.json").unwrap();
+    let http_url = Url::parse("https://addon.com/manifest.json").unwrap();
+
+    let registry = TestEnv::addon_transports();
+    assert!(registry.is_supported(&http_url));
+    assert!(!registry.is_supported(&local_url));
+
+    register_local_transport();
+    let registry = TestEnv::addon_transports();
+    assert!(registry.is_supported(&local_url), "local scheme registered");
+    assert!(!registry.is_supported(&unknown_url));
+
+    TestEnv::run(|| {
+        TestEnv::exec_concurrent(async move {
+            assert_eq!(
+                TestEnv::addon_transport(&local_url).manifest().await,
+                Ok(manifest("addon")),
+                "Manifest served by the local transport"
+            );
+            assert_eq!(
+                TestEnv::addon_transport(&unknown_url).manifest().await,
+                Err(EnvError::AddonTransport(
+                    "Unsupported addon transport: ipfs".to_owned()
+                )),
+                "Unregistered scheme is unsupported"
+            );
+        })
+    });
+    assert!(
+        REQUESTS.read().unwrap().is_empty(),
+        "No fetch requests made"
+    );
+}
+
+#[test]
+fn addon_transport_registry_addon_details() {
+    #[derive(Model, Clone, Default)]
+    #[model(TestEnv)]
+    struct TestModel {
+        ctx: Ctx,
+        addon_details: AddonDetails,
+    }
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    register_local_transport();
+    let transport_url = Url::parse("local://addon/manifest.json").unwrap();
+    let (runtime, _rx) = Runtime::<TestEnv, _>::new(TestModel::default(), vec![], 1000);
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Load(ActionLoad::AddonDetails(Selected {
+                transport_url: transport_url.to_owned(),
+            })),
+        })
+    });
+    assert_eq!(
+        runtime.model().unwrap().addon_details.remote_addon,
+        Some(DescriptorLoadable {
+            transport_url: transport_url.to_owned(),
+            content: Loadable::Ready(Descriptor {
+                manifest: manifest("addon"),
+                transport_url,
+                flags: Default::default(),
+            }),
+        }),
+        "Addon loaded through the local transport"
+    );
+    assert!(
+        REQUESTS.read().unwrap().is_empty(),
+        "No fetch requests made"
+    );
+}
@@ -0,0 +1,157 @@
+use std::any::Any;
+
+use chrono::{TimeZone, Utc};
+use futures::future;
+use stremio_derive::Model;
+use url::Url;
+
+use crate::{
+    constants::{CALENDAR_IDS_EXTRA_PROP, CATALOG_RESOURCE_NAME},
+    models::{
+        calendar::{Calendar, FullDate},
+        ctx::Ctx,
+    },
+    runtime::{
+        msg::{Action, ActionCalendar},
+        Env, EnvFutureExt, Runtime, RuntimeAction, TryEnvFuture,
+    },
+    types::{
+        addon::{
+            Descriptor, Manifest, ManifestCatalog, ManifestExtra, ResourceResponse,
+            ResourceResponseCache,
+        },
+        library::{LibraryBucket, LibraryItem},
+        profile::Profile,
+        resource::{MetaItem, MetaItemPreview, SeriesInfo, Video},
+    },
+    unit_tests::{default_fetch_handler, Request, TestEnv, FETCH_HANDLER, NOW},
+};
+
+fn video(episode: u32, day: u32) -> Video {
+    Video {
+        id: format!("tt1:1:{episode}"),
+        title: format!("Episode, {episode}"),
+        released: Some(Utc.with_ymd_and_hms(2024, 1, day, 20, 0, 0).unwrap()),
+        series_info: Some(SeriesInfo { season: 1, episode }),
+        ..Video::default()
+    }
+}
+
+#[test]
+fn export_icalendar() {
+    #[derive(Model, Clone, Debug)]
+    #[model(TestEnv)]
+    struct TestModel {
+        ctx: Ctx,
+        calendar: Calendar,
+    }
+
+    fn fetch_handler(request: Request) -> TryEnvFuture<Box<dyn Any + Send>> {
+        match request {
+            Request { url, method, .. }
+                if url
+                    == "https://addon/catalog/series/calendarVideosIds/calendarVideosIds=tt1.json"
+                    && method == "GET" =>
+            {
+                future::ok(Box::new(ResourceResponseCache::from(
+                    ResourceResponse::MetasDetailed {
+                        metas_detailed: vec![MetaItem {
+                            preview: MetaItemPreview {
+                                id: "tt1".to_owned(),
+                                r#type: "series".to_owned(),
+                                name: "Series".to_owned(),
+                                ..MetaItemPreview::default()
+                            },
+                            videos: vec![video(3, 31), video(1, 1), video(2, 15)],
+                        }],
+                    },
+                )) as Box<dyn Any + Send>)
+                .boxed_env()
+            }
+            _ => default_fetch_handler(request),
+        }
+    }
+
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    *FETCH_HANDLER.write().unwrap() = Box::new(fetch_handler);
+    *NOW.write().unwrap() = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+
+    let addon = Descriptor {
+        transport_url: Url::parse("https://addon/manifest.json").unwrap(),
+        flags: Default::default(),
+        manifest: Manifest {
+            id: "addon".to_owned(),
+            types: vec!["series".into()],
+            resources: vec![CATALOG_RESOURCE_NAME.into()],
+            catalogs: vec![ManifestCatalog {
+                id: "calendarVideosIds".to_owned(),
+                r#type: "series".to_owned(),
+                name: Some("calendar-videos".to_string()),
+                extra: ManifestExtra::Full {
+                    props: vec![CALENDAR_IDS_EXTRA_PROP.to_owned()],
+                },
+            }],
+            ..Default::default()
+        },
+    };
+    let library_item = LibraryItem {
+        id: "tt1".to_owned(),
+        r#type: "series".to_owned(),
+        removed: false,
+        temp: false,
+        name: "Series".to_owned(),
+        poster: Default::default(),
+        poster_shape: Default::default(),
+        ctime: Some(TestEnv::now()),
+        mtime: TestEnv::now(),
+        state: Default::default(),
+        behavior_hints: Default::default(),
+    };
+    let (runtime, _rx) = Runtime::<TestEnv, _>::new(
+        TestModel {
+            ctx: Ctx {
+                profile: Profile {
+                    addons: vec![addon],
+                    ..Default::default()
+                },
+                library: LibraryBucket::new(None, vec![library_item]),
+                ..Default::default()
+            },
+            calendar: Default::default(),
+        },
+        vec![],
+        1000,
+    );
+
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Calendar(ActionCalendar::ExportICalendar {
+                from: FullDate::new(2024, 1, 1),
+                to: FullDate::new(2024, 1, 15),
+            }),
+        });
+    });
+
+    let model = runtime.model().unwrap();
+    let ics = model
+        .calendar
+        .icalendar
+        .as_ref()
+        .and_then(|icalendar| icalendar.ics.as_ref())
+        .expect("iCalendar is exported once the meta items are loaded");
+    let events = ics
+        .split("BEGIN:VEVENT\r\n")
+        .skip(1)
+        .map(|event| event.replace("\r\n ", ""))
+        .collect::<Vec<_>>();
+    assert_eq!(events.len(), 2, "Videos outside the range are skipped");
+    assert!(events[0].contains("UID:tt1/tt1:1:1@stremio\r\n"));
+    assert!(events[0].contains("DTSTART;VALUE=DATE:20240101\r\n"));
+    assert!(events[0].contains("SUMMARY:Series - S01E01 - Episode\\, 1\r\n"));
+    assert!(events[0].contains("URL:stremio:///detail/series/tt1/tt1%3A1%3A1\r\n"));
+    assert!(
+        events[1].contains("DTSTART;VALUE=DATE:20240115\r\n"),
+        "Events are sorted by date"
+    );
+}
@@ -13,7 +13,10 @@ use crate::{
         Env, EnvFutureExt, Runtime, RuntimeAction, TryEnvFuture,
     },
     types::{
-        addon::{Descriptor, Manifest, ManifestCatalog, ManifestExtra, ResourceResponse},
+        addon::{
+            Descriptor, Manifest, ManifestCatalog, ManifestExtra, ResourceResponse,
+            ResourceResponseCache,
+        },
         library::{LibraryBucket, LibraryItem},
         profile::Profile,
         resource::{MetaItem, MetaItemPreview, SeriesInfo, Video},
@@ -91,9 +94,11 @@ fn calendar() {
                     == "https://addon/catalog/series/calendarVideosIds/calendarVideosIds=tt1.json"
                     && method == "GET" =>
             {
-                future::ok(Box::new(ResourceResponse::MetasDetailed {
-                    metas_detailed: vec![meta_item("tt1", "series")],
-                }) as Box<dyn Any + Send>)
+                future::ok(Box::new(ResourceResponseCache::from(
+                    ResourceResponse::MetasDetailed {
+                        metas_detailed: vec![meta_item("tt1", "series")],
+                    },
+                )) as Box<dyn Any + Send>)
                 .boxed_env()
             }
             _ => default_fetch_handler(request),
@@ -1 +1,3 @@
+mod export_icalendar;
 mod load_action;
+mod views;
@@ -0,0 +1,314 @@
+use std::any::Any;
+
+use chrono::{Duration, TimeZone, Utc};
+use futures::future;
+use stremio_derive::Model;
+use url::Url;
+
+use crate::{
+    constants::{
+        CALENDAR_IDS_EXTRA_PROP, CALENDAR_ITEMS_COUNT, CALENDAR_MAX_DAYS, CATALOG_RESOURCE_NAME,
+    },
+    models::{
+        calendar::{Calendar, CalendarFilters, CalendarView, FullDate, Selected, YearMonthDate},
+        ctx::Ctx,
+    },
+    runtime::{
+        msg::{Action, ActionLoad},
+        Env, EnvFutureExt, Runtime, RuntimeAction, TryEnvFuture,
+    },
+    types::{
+        addon::{
+            Descriptor, ExtraProp, Manifest, ManifestCatalog, ManifestExtra, OptionsLimit,
+            ResourceResponse, ResourceResponseCache,
+        },
+        library::{LibraryBucket, LibraryItem, LibraryItemState},
+        profile::Profile,
+        resource::{MetaItem, MetaItemPreview, SeriesInfo, Video},
+    },
+    unit_tests::{default_fetch_handler, Request, TestEnv, FETCH_HANDLER, NOW, REQUESTS},
+};
+
+#[derive(Model, Clone, Debug)]
+#[model(TestEnv)]
+struct TestModel {
+    ctx: Ctx,
+    calendar: Calendar,
+}
+
+fn meta_item(id: &str, days: &[u32]) -> MetaItem {
+    MetaItem {
+        preview: MetaItemPreview {
+            id: id.to_owned(),
+            r#type: "series".to_owned(),
+            ..MetaItemPreview::default()
+        },
+        videos: days
+            .iter()
+            .zip(1..)
+            .map(|(day, episode)| Video {
+                id: format!("{id}:1:{episode}"),
+                released: Some(Utc.with_ymd_and_hms(2024, 1, *day, 0, 0, 0).unwrap()),
+                series_info: Some(SeriesInfo { season: 1, episode }),
+                ..Video::default()
+            })
+            .collect(),
+    }
+}
+
+fn fetch_handler(request: Request) -> TryEnvFuture<Box<dyn Any + Send>> {
+    match request {
+        Request { url, method, .. }
+            if url.starts_with("https://addon/catalog/series/calendarVideosIds/")
+                && method == "GET" =>
+        {
+            // the last library item is requested with the second page
+            let meta_item = if url.contains("tt150") {
+                meta_item("tt150", &[5])
+            } else {
+                meta_item("tt1", &[3, 20])
+            };
+            future::ok(Box::new(ResourceResponseCache::from(
+                ResourceResponse::MetasDetailed {
+                    metas_detailed: vec![meta_item],
+                },
+            )) as Box<dyn Any + Send>)
+            .boxed_env()
+        }
+        _ => default_fetch_handler(request),
+    }
+}
+
+fn test_model() -> TestModel {
+    let addon = Descriptor {
+        transport_url: Url::parse("https://addon/manifest.json").unwrap(),
+        flags: Default::default(),
+        manifest: Manifest {
+            id: "addon".to_owned(),
+            types: vec!["series".into()],
+            resources: vec![CATALOG_RESOURCE_NAME.into()],
+            catalogs: vec![ManifestCatalog {
+                id: "calendarVideosIds".to_owned(),
+                r#type: "series".to_owned(),
+                name: Some("calendar-videos".to_string()),
+                extra: ManifestExtra::Full {
+                    props: vec![ExtraProp {
+                        options_limit: OptionsLimit(CALENDAR_ITEMS_COUNT),
+                        ..CALENDAR_IDS_EXTRA_PROP.to_owned()
+                    }],
+                },
+            }],
+            ..Default::default()
+        },
+    };
+    let library_items = (1..=150)
+        .map(|index| LibraryItem {
+            id: format!("tt{index}"),
+            r#type: "series".to_owned(),
+            removed: false,
+            temp: false,
+            name: Default::default(),
+            poster: Default::default(),
+            poster_shape: Default::default(),
+            ctime: Some(TestEnv::now()),
+            mtime: TestEnv::now() - Duration::minutes(index),
+            state: LibraryItemState {
+                no_notif: index == 1,
+                ..Default::default()
+            },
+            behavior_hints: Default::default(),
+        })
+        .collect();
+    TestModel {
+        ctx: Ctx {
+            profile: Profile {
+                addons: vec![addon],
+                ..Default::default()
+            },
+            library: LibraryBucket::new(None, library_items),
+            ..Default::default()
+        },
+        calendar: Default::default(),
+    }
+}
+
+fn load(runtime: &Runtime<TestEnv, TestModel>, selected: Selected) {
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Load(ActionLoad::Calendar(Some(selected))),
+        });
+    });
+}
+
+fn days_with_items(calendar: &Calendar) -> Vec<FullDate> {
+    calendar
+        .items
+        .iter()
+        .filter(|item| !item.items.is_empty())
+        .map(|item| item.date.to_owned())
+        .collect()
+}
+
+#[test]
+fn calendar_week_view() {
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    *FETCH_HANDLER.write().unwrap() = Box::new(fetch_handler);
+    *NOW.write().unwrap() = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+    let (runtime, _rx) = Runtime::<TestEnv, _>::new(test_model(), vec![], 1000);
+    load(
+        &runtime,
+        Selected {
+            date: YearMonthDate {
+                year: 2024,
+                month: 1,
+            },
+            day: Some(4),
+            view: CalendarView::Week,
+            ..Default::default()
+        },
+    );
+    assert_eq!(
+        REQUESTS.read().unwrap().len(),
+        2,
+        "The library items are requested in pages"
+    );
+    let calendar = &runtime.model().unwrap().calendar;
+    assert_eq!(calendar.items.len(), 7);
+    assert_eq!(calendar.items[0].date, FullDate::new(2024, 1, 1));
+    assert_eq!(
+        days_with_items(calendar),
+        vec![FullDate::new(2024, 1, 3), FullDate::new(2024, 1, 5)]
+    );
+    assert_eq!(
+        (
+            calendar.selectable.prev.date.year,
+            calendar.selectable.prev.day
+        ),
+        (2023, Some(25))
+    );
+    assert_eq!(calendar.selectable.next.day, Some(8));
+}
+
+#[test]
+fn calendar_agenda_view() {
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    *FETCH_HANDLER.write().unwrap() = Box::new(fetch_handler);
+    *NOW.write().unwrap() = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+    let (runtime, _rx) = Runtime::<TestEnv, _>::new(test_model(), vec![], 1000);
+    let selected = Selected {
+        date: YearMonthDate {
+            year: 2024,
+            month: 1,
+        },
+        day: Some(2),
+        view: CalendarView::Agenda { days: 30 },
+        ..Default::default()
+    };
+    load(&runtime, selected.to_owned());
+    {
+        let calendar = &runtime.model().unwrap().calendar;
+        assert_eq!(
+            days_with_items(calendar),
+            vec![
+                FullDate::new(2024, 1, 3),
+                FullDate::new(2024, 1, 5),
+                FullDate::new(2024, 1, 20)
+            ]
+        );
+        assert_eq!(
+            calendar.items.len(),
+            3,
+            "Only the days with releases are in the agenda"
+        );
+    }
+    load(
+        &runtime,
+        Selected {
+            filters: CalendarFilters {
+                notifications_only: true,
+                ..Default::default()
+            },
+            ..selected
+        },
+    );
+    assert_eq!(
+        days_with_items(&runtime.model().unwrap().calendar),
+        vec![FullDate::new(2024, 1, 5)],
+        "Library items with disabled notifications are skipped"
+    );
+}
+
+#[test]
+fn calendar_selected_month_deserialize() {
+    let selected = serde_json::from_str::<Selected>(r#"{ "year": 2024, "month": 2 }"#).unwrap();
+    assert_eq!(
+        selected.range(),
+        Some((
+            chrono::NaiveDate::from_ymd_opt(2024, 2, 1).unwrap(),
+            chrono::NaiveDate::from_ymd_opt(2024, 2, 29).unwrap()
+        ))
+    );
+}
+
+#[test]
+fn calendar_selected_range_overflow() {
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    let agenda = Selected {
+        date: YearMonthDate {
+            year: 2024,
+            month: 2,
+        },
+        day: Some(1),
+        view: CalendarView::Agenda { days: u32::MAX },
+        filters: CalendarFilters::default(),
+    };
+    let start = chrono::NaiveDate::from_ymd_opt(2024, 2, 1).unwrap();
+    assert_eq!(
+        agenda.range(),
+        Some((start, start + Duration::days(CALENDAR_MAX_DAYS - 1))),
+        "Agenda is cut to the max days"
+    );
+    let last_week = Selected {
+        date: YearMonthDate::from(chrono::NaiveDate::MAX),
+        day: Some(chrono::Datelike::day(&chrono::NaiveDate::MAX)),
+        view: CalendarView::Week,
+        ..agenda.to_owned()
+    };
+    assert_eq!(last_week.range(), None, "Week past the last date");
+    let last_agenda = Selected {
+        view: CalendarView::Agenda { days: u32::MAX },
+        ..last_week
+    };
+    assert_eq!(last_agenda.range(), None, "Agenda past the last date");
+    *FETCH_HANDLER.write().unwrap() = Box::new(fetch_handler);
+    let (runtime, _rx) = Runtime::<TestEnv, _>::new(test_model(), vec![], 1000);
+    load(&runtime, last_agenda);
+    load(&runtime, agenda.to_owned());
+    assert_eq!(
+        runtime.model().unwrap().calendar.selectable.next.day,
+        Some(chrono::Datelike::day(
+            &(start + Duration::days(CALENDAR_MAX_DAYS))
+        ),),
+        "Next page of the agenda starts after the max days"
+    );
+    let months = Selected {
+        day: None,
+        view: CalendarView::Months { count: 24 },
+        ..agenda
+    };
+    assert_eq!(
+        months.range(),
+        Some((start, chrono::NaiveDate::from_ymd_opt(2025, 1, 31).unwrap())),
+        "Months are cut to the max months"
+    );
+    load(&runtime, months);
+    assert_eq!(
+        runtime.model().unwrap().calendar.selectable.next.date,
+        YearMonthDate {
+            year: 2025,
+            month: 2,
+        },
+        "Next page of the months starts after the max months"
+    );
+}
@@ -1,17 +1,24 @@
 use crate::models::catalog_with_filters::{CatalogWithFilters, Selected};
 use crate::models::common::{Loadable, ResourceLoadable};
-use crate::models::ctx::Ctx;
+use crate::models::ctx::{Ctx, CtxBuckets};
 use crate::runtime::msg::{Action, ActionLoad};
 use crate::runtime::{EnvFutureExt, Runtime, RuntimeAction, RuntimeEvent, TryEnvFuture};
-use crate::types::addon::{ExtraValue, ResourcePath, ResourceRequest, ResourceResponse};
+use crate::types::addon::{
+    ExtraValue, ResourcePath, ResourceRequest, ResourceResponse, ResourceResponseCache,
+};
+use crate::types::addon_health::AddonHealthBucket;
 use crate::types::events::DismissedEventsBucket;
-use crate::types::library::LibraryBucket;
+use crate::types::library::{LibraryBucket, LibrarySyncBucket};
+use crate::types::lists::ListsBucket;
+use crate::types::local_profiles::LocalProfilesBucket;
 use crate::types::notifications::NotificationsBucket;
+use crate::types::outbox::OutboxBucket;
 use crate::types::profile::Profile;
 use crate::types::resource::MetaItemPreview;
 use crate::types::search_history::SearchHistoryBucket;
 use crate::types::server_urls::ServerUrlsBucket;
 use crate::types::streams::StreamsBucket;
+use crate::types::taste::TasteBucket;
 use crate::unit_tests::{
     default_fetch_handler, Request, TestEnv, EVENTS, FETCH_HANDLER, REQUESTS, STATES,
 };
@@ -37,9 +44,11 @@ fn default_catalog() {
                 if url == "https://v3-cinemeta.strem.io/catalog/movie/top.json"
                     && method == "GET" =>
             {
-                future::ok(Box::new(ResourceResponse::Metas {
-                    metas: vec![MetaItemPreview::default()],
-                }) as Box<dyn Any + Send>)
+                future::ok(
+                    Box::new(ResourceResponseCache::from(ResourceResponse::Metas {
+                        metas: vec![MetaItemPreview::default()],
+                    })) as Box<dyn Any + Send>,
+                )
                 .boxed_env()
             }
             _ => default_fetch_handler(request),
@@ -49,12 +58,20 @@ fn default_catalog() {
     *FETCH_HANDLER.write().unwrap() = Box::new(fetch_handler);
     let ctx = Ctx::new(
         Profile::default(),
-        LibraryBucket::default(),
-        StreamsBucket::default(),
-        ServerUrlsBucket::new::<TestEnv>(None),
-        NotificationsBucket::new::<TestEnv>(None, vec![]),
-        SearchHistoryBucket::default(),
-        DismissedEventsBucket::default(),
+        CtxBuckets {
+            library: LibraryBucket::default(),
+            streams: StreamsBucket::default(),
+            streaming_server_urls: ServerUrlsBucket::new::<TestEnv>(None),
+            notifications: NotificationsBucket::new::<TestEnv>(None, vec![]),
+            search_history: SearchHistoryBucket::default(),
+            dismissed_events: DismissedEventsBucket::default(),
+            library_sync: LibrarySyncBucket::default(),
+            outbox: OutboxBucket::default(),
+            local_profiles: LocalProfilesBucket::default(),
+            addon_health: AddonHealthBucket::default(),
+            taste: TasteBucket::default(),
+            lists: ListsBucket::default(),
+        },
     );
     let (discover, effects) = CatalogWithFilters::<MetaItemPreview>::new(&ctx.profile);
     let (runtime, rx) = Runtime::<TestEnv, _>::new(
@@ -139,9 +156,11 @@ fn search_catalog() {
                     == "https://v3-cinemeta.strem.io/catalog/movie/top/search=Harry%20Potter.json"
                     && method == "GET" =>
             {
-                future::ok(Box::new(ResourceResponse::Metas {
-                    metas: vec![MetaItemPreview::default()],
-                }) as Box<dyn Any + Send>)
+                future::ok(
+                    Box::new(ResourceResponseCache::from(ResourceResponse::Metas {
+                        metas: vec![MetaItemPreview::default()],
+                    })) as Box<dyn Any + Send>,
+                )
                 .boxed_env()
             }
             _ => default_fetch_handler(request),
@@ -151,12 +170,20 @@ fn search_catalog() {
     *FETCH_HANDLER.write().unwrap() = Box::new(fetch_handler);
     let ctx = Ctx::new(
         Profile::default(),
-        LibraryBucket::default(),
-        StreamsBucket::default(),
-        ServerUrlsBucket::new::<TestEnv>(None),
-        NotificationsBucket::new::<TestEnv>(None, vec![]),
-        SearchHistoryBucket::default(),
-        DismissedEventsBucket::default(),
+        CtxBuckets {
+            library: LibraryBucket::default(),
+            streams: StreamsBucket::default(),
+            streaming_server_urls: ServerUrlsBucket::new::<TestEnv>(None),
+            notifications: NotificationsBucket::new::<TestEnv>(None, vec![]),
+            search_history: SearchHistoryBucket::default(),
+            dismissed_events: DismissedEventsBucket::default(),
+            library_sync: LibrarySyncBucket::default(),
+            outbox: OutboxBucket::default(),
+            local_profiles: LocalProfilesBucket::default(),
+            addon_health: AddonHealthBucket::default(),
+            taste: TasteBucket::default(),
+            lists: ListsBucket::default(),
+        },
     );
     let (discover, effects) = CatalogWithFilters::<MetaItemPreview>::new(&ctx.profile);
     let (runtime, rx) = Runtime::<TestEnv, _>::new(
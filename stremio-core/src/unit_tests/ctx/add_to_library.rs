@@ -1,16 +1,21 @@
 use crate::constants::{LIBRARY_RECENT_STORAGE_KEY, LIBRARY_STORAGE_KEY};
-use crate::models::ctx::Ctx;
+use crate::models::ctx::{Ctx, CtxBuckets};
 use crate::runtime::msg::{Action, ActionCtx};
 use crate::runtime::{Env, EnvFutureExt, Runtime, RuntimeAction, TryEnvFuture};
+use crate::types::addon_health::AddonHealthBucket;
 use crate::types::api::{APIResult, SuccessResponse};
 use crate::types::events::DismissedEventsBucket;
-use crate::types::library::{LibraryBucket, LibraryItem, LibraryItemState};
+use crate::types::library::{LibraryBucket, LibraryItem, LibraryItemState, LibrarySyncBucket};
+use crate::types::lists::ListsBucket;
+use crate::types::local_profiles::LocalProfilesBucket;
 use crate::types::notifications::NotificationsBucket;
+use crate::types::outbox::OutboxBucket;
 use crate::types::profile::{Auth, AuthKey, GDPRConsent, Profile, User};
 use crate::types::resource::{MetaItemBehaviorHints, MetaItemPreview, PosterShape};
 use crate::types::search_history::SearchHistoryBucket;
 use crate::types::server_urls::ServerUrlsBucket;
 use crate::types::streams::StreamsBucket;
+use crate::types::taste::TasteBucket;
 use crate::types::True;
 use crate::unit_tests::{
     default_fetch_handler, Request, TestEnv, FETCH_HANDLER, NOW, REQUESTS, STORAGE,
@@ -57,6 +62,7 @@ fn actionctx_addtolibrary() {
         links: vec![],
         trailer_streams: vec![],
         behavior_hints: Default::default(),
+        certification: None,
     };
     let library_item = LibraryItem {
         id: "id".into(),
@@ -104,15 +110,23 @@ fn actionctx_addtolibrary() {
                     }),
                     ..Default::default()
                 },
-                LibraryBucket {
-                    uid: Some("id".into()),
-                    ..Default::default()
+                CtxBuckets {
+                    library: LibraryBucket {
+                        uid: Some("id".into()),
+                        ..Default::default()
+                    },
+                    streams: StreamsBucket::default(),
+                    streaming_server_urls: ServerUrlsBucket::new::<TestEnv>(None),
+                    notifications: NotificationsBucket::new::<TestEnv>(None, vec![]),
+                    search_history: SearchHistoryBucket::default(),
+                    dismissed_events: DismissedEventsBucket::default(),
+                    library_sync: LibrarySyncBucket::default(),
+                    outbox: OutboxBucket::default(),
+                    local_profiles: LocalProfilesBucket::default(),
+                    addon_health: AddonHealthBucket::default(),
+                    taste: TasteBucket::default(),
+                    lists: ListsBucket::default(),
                 },
-                StreamsBucket::default(),
-                ServerUrlsBucket::new::<TestEnv>(None),
-                NotificationsBucket::new::<TestEnv>(None, vec![]),
-                SearchHistoryBucket::default(),
-                DismissedEventsBucket::default(),
             ),
         },
         vec![],
@@ -194,6 +208,7 @@ fn actionctx_addtolibrary_already_added() {
             has_scheduled_videos: false,
             other: Default::default(),
         },
+        certification: None,
     };
     let library_item = LibraryItem {
         id: "id".into(),
@@ -222,35 +237,43 @@ fn actionctx_addtolibrary_already_added() {
         TestModel {
             ctx: Ctx::new(
                 Profile::default(),
-                LibraryBucket {
-                    uid: None,
-                    items: vec![(
-                        "id".to_owned(),
-                        LibraryItem {
-                            id: "id".into(),
-                            r#type: "typename_".to_owned(),
-                            name: "name_".to_owned(),
-                            poster: None,
-                            poster_shape: PosterShape::Poster,
-                            removed: true,
-                            temp: true,
-                            ctime: Some(Utc.with_ymd_and_hms(2020, 1, 1, 0, 0, 0).unwrap()),
-                            mtime: Utc.with_ymd_and_hms(2020, 1, 1, 0, 0, 0).unwrap(),
-                            state: LibraryItemState {
-                                video_id: Some("video_id".to_owned()),
-                                ..LibraryItemState::default()
+                CtxBuckets {
+                    library: LibraryBucket {
+                        uid: None,
+                        items: vec![(
+                            "id".to_owned(),
+                            LibraryItem {
+                                id: "id".into(),
+                                r#type: "typename_".to_owned(),
+                                name: "name_".to_owned(),
+                                poster: None,
+                                poster_shape: PosterShape::Poster,
+                                removed: true,
+                                temp: true,
+                                ctime: Some(Utc.with_ymd_and_hms(2020, 1, 1, 0, 0, 0).unwrap()),
+                                mtime: Utc.with_ymd_and_hms(2020, 1, 1, 0, 0, 0).unwrap(),
+                                state: LibraryItemState {
+                                    video_id: Some("video_id".to_owned()),
+                                    ..LibraryItemState::default()
+                                },
+                                behavior_hints: Default::default(),
                             },
-                            behavior_hints: Default::default(),
-                        },
-                    )]
-                    .into_iter()
-                    .collect(),
+                        )]
+                        .into_iter()
+                        .collect(),
+                    },
+                    streams: StreamsBucket::default(),
+                    streaming_server_urls: ServerUrlsBucket::new::<TestEnv>(None),
+                    notifications: NotificationsBucket::new::<TestEnv>(None, vec![]),
+                    search_history: SearchHistoryBucket::default(),
+                    dismissed_events: DismissedEventsBucket::default(),
+                    library_sync: LibrarySyncBucket::default(),
+                    outbox: OutboxBucket::default(),
+                    local_profiles: LocalProfilesBucket::default(),
+                    addon_health: AddonHealthBucket::default(),
+                    taste: TasteBucket::default(),
+                    lists: ListsBucket::default(),
                 },
-                StreamsBucket::default(),
-                ServerUrlsBucket::new::<TestEnv>(None),
-                NotificationsBucket::new::<TestEnv>(None, vec![]),
-                SearchHistoryBucket::default(),
-                DismissedEventsBucket::default(),
             ),
         },
         vec![],
@@ -1,18 +1,23 @@
+use crate::types::addon_health::AddonHealthBucket;
 use crate::types::events::DismissedEventsBucket;
+use crate::types::lists::{List, ListsBucket};
+use crate::types::local_profiles::LocalProfilesBucket;
 use crate::types::notifications::NotificationsBucket;
+use crate::types::outbox::OutboxBucket;
 use crate::types::search_history::SearchHistoryBucket;
 use crate::types::server_urls::ServerUrlsBucket;
 use crate::types::streams::StreamsBucket;
+use crate::types::taste::TasteBucket;
 use crate::{
     constants::{LIBRARY_RECENT_STORAGE_KEY, LIBRARY_STORAGE_KEY, PROFILE_STORAGE_KEY},
-    models::ctx::Ctx,
+    models::ctx::{Ctx, CtxBuckets},
     runtime::{
         msg::{Action, ActionCtx},
         Env, EnvFutureExt, Runtime, RuntimeAction, TryEnvFuture,
     },
     types::{
         api::{APIResult, AuthRequest, AuthResponse, CollectionResponse, LibraryItemsResponse},
-        library::LibraryBucket,
+        library::{LibraryBucket, LibrarySyncBucket},
         profile::{Auth, AuthKey, GDPRConsent, Profile, User},
     },
     unit_tests::{default_fetch_handler, Request, TestEnv, FETCH_HANDLER, REQUESTS, STORAGE},
@@ -101,6 +106,14 @@ fn actionctx_authenticate_login() {
                     LibraryItemsResponse::new(),
                 )) as Box<dyn Any + Send>).boxed_env()
             }
+            Request {
+                url, method, body, ..
+            } if url == "https://api.strem.io/api/datastoreGet"
+                && method == "POST"
+                && body == "{\"authKey\":\"auth_key\",\"collection\":\"userList\",\"ids\":[],\"all\":true}" =>
+            {
+                future::ok(Box::new(APIResult::Ok(Vec::<List>::new())) as Box<dyn Any + Send>).boxed_env()
+            }
             _ => default_fetch_handler(request),
         }
     }
@@ -108,12 +121,20 @@ fn actionctx_authenticate_login() {
     *FETCH_HANDLER.write().unwrap() = Box::new(fetch_handler);
     let ctx = Ctx::new(
         Profile::default(),
-        LibraryBucket::default(),
-        StreamsBucket::default(),
-        ServerUrlsBucket::new::<TestEnv>(None),
-        NotificationsBucket::new::<TestEnv>(None, vec![]),
-        SearchHistoryBucket::default(),
-        DismissedEventsBucket::default(),
+        CtxBuckets {
+            library: LibraryBucket::default(),
+            streams: StreamsBucket::default(),
+            streaming_server_urls: ServerUrlsBucket::new::<TestEnv>(None),
+            notifications: NotificationsBucket::new::<TestEnv>(None, vec![]),
+            search_history: SearchHistoryBucket::default(),
+            dismissed_events: DismissedEventsBucket::default(),
+            library_sync: LibrarySyncBucket::default(),
+            outbox: OutboxBucket::default(),
+            local_profiles: LocalProfilesBucket::default(),
+            addon_health: AddonHealthBucket::default(),
+            taste: TasteBucket::default(),
+            lists: ListsBucket::default(),
+        },
     );
     let (runtime, _rx) = Runtime::<TestEnv, _>::new(TestModel { ctx }, vec![], 1000);
     TestEnv::run(|| {
@@ -167,8 +188,8 @@ fn actionctx_authenticate_login() {
     );
     assert_eq!(
         REQUESTS.read().unwrap().len(),
-        3,
-        "Three requests have been sent"
+        4,
+        "Four requests have been sent"
     );
     assert_eq!(
         REQUESTS.read().unwrap().first().unwrap().to_owned(),
@@ -203,6 +224,17 @@ fn actionctx_authenticate_login() {
         },
         "DatastoreGet request has been sent"
     );
+    assert_eq!(
+        REQUESTS.read().unwrap().get(3).unwrap().to_owned(),
+        Request {
+            url: "https://api.strem.io/api/datastoreGet".to_owned(),
+            method: "POST".to_owned(),
+            body: "{\"authKey\":\"auth_key\",\"collection\":\"userList\",\"ids\":[],\"all\":true}"
+                .to_owned(),
+            ..Default::default()
+        },
+        "Lists have been pulled"
+    );
 }
 
 #[test]
@@ -243,6 +275,14 @@ fn actionctx_authenticate_login_with_token() {
             {
                 future::ok(Box::new(APIResult::Ok(LibraryItemsResponse::new(),)) as Box<dyn Any + Send>).boxed_env()
             }
+            Request {
+                url, method, body, ..
+            } if url == "https://api.strem.io/api/datastoreGet"
+                && method == "POST"
+                && body == "{\"authKey\":\"auth_key\",\"collection\":\"userList\",\"ids\":[],\"all\":true}" =>
+            {
+                future::ok(Box::new(APIResult::Ok(Vec::<List>::new())) as Box<dyn Any + Send>).boxed_env()
+            }
             _ => default_fetch_handler(request),
         }
     }
@@ -250,12 +290,20 @@ fn actionctx_authenticate_login_with_token() {
     *FETCH_HANDLER.write().unwrap() = Box::new(fetch_handler);
     let ctx = Ctx::new(
         Profile::default(),
-        LibraryBucket::default(),
-        StreamsBucket::default(),
-        ServerUrlsBucket::new::<TestEnv>(None),
-        NotificationsBucket::new::<TestEnv>(None, vec![]),
-        SearchHistoryBucket::default(),
-        DismissedEventsBucket::default(),
+        CtxBuckets {
+            library: LibraryBucket::default(),
+            streams: StreamsBucket::default(),
+            streaming_server_urls: ServerUrlsBucket::new::<TestEnv>(None),
+            notifications: NotificationsBucket::new::<TestEnv>(None, vec![]),
+            search_history: SearchHistoryBucket::default(),
+            dismissed_events: DismissedEventsBucket::default(),
+            library_sync: LibrarySyncBucket::default(),
+            outbox: OutboxBucket::default(),
+            local_profiles: LocalProfilesBucket::default(),
+            addon_health: AddonHealthBucket::default(),
+            taste: TasteBucket::default(),
+            lists: ListsBucket::default(),
+        },
     );
     let (runtime, _rx) = Runtime::<TestEnv, _>::new(TestModel { ctx }, vec![], 1000);
     TestEnv::run(|| {
@@ -307,8 +355,8 @@ fn actionctx_authenticate_login_with_token() {
     );
     assert_eq!(
         REQUESTS.read().unwrap().len(),
-        3,
-        "Three requests have been sent"
+        4,
+        "Four requests have been sent"
     );
     assert_eq!(
         REQUESTS.read().unwrap().first().unwrap().to_owned(),
@@ -344,6 +392,17 @@ fn actionctx_authenticate_login_with_token() {
         },
         "DatastoreGet request has been sent"
     );
+    assert_eq!(
+        REQUESTS.read().unwrap().get(3).unwrap().to_owned(),
+        Request {
+            url: "https://api.strem.io/api/datastoreGet".to_owned(),
+            method: "POST".to_owned(),
+            body: "{\"authKey\":\"auth_key\",\"collection\":\"userList\",\"ids\":[],\"all\":true}"
+                .to_owned(),
+            ..Default::default()
+        },
+        "Lists have been pulled"
+    );
 }
 
 #[test]
@@ -384,6 +443,14 @@ fn actionctx_authenticate_facebook() {
             {
                 future::ok(Box::new(APIResult::Ok(LibraryItemsResponse::new(),)) as Box<dyn Any + Send>).boxed_env()
             }
+            Request {
+                url, method, body, ..
+            } if url == "https://api.strem.io/api/datastoreGet"
+                && method == "POST"
+                && body == "{\"authKey\":\"auth_key\",\"collection\":\"userList\",\"ids\":[],\"all\":true}" =>
+            {
+                future::ok(Box::new(APIResult::Ok(Vec::<List>::new())) as Box<dyn Any + Send>).boxed_env()
+            }
             _ => default_fetch_handler(request),
         }
     }
@@ -391,12 +458,20 @@ fn actionctx_authenticate_facebook() {
     *FETCH_HANDLER.write().unwrap() = Box::new(fetch_handler);
     let ctx = Ctx::new(
         Profile::default(),
-        LibraryBucket::default(),
-        StreamsBucket::default(),
-        ServerUrlsBucket::new::<TestEnv>(None),
-        NotificationsBucket::new::<TestEnv>(None, vec![]),
-        SearchHistoryBucket::default(),
-        DismissedEventsBucket::default(),
+        CtxBuckets {
+            library: LibraryBucket::default(),
+            streams: StreamsBucket::default(),
+            streaming_server_urls: ServerUrlsBucket::new::<TestEnv>(None),
+            notifications: NotificationsBucket::new::<TestEnv>(None, vec![]),
+            search_history: SearchHistoryBucket::default(),
+            dismissed_events: DismissedEventsBucket::default(),
+            library_sync: LibrarySyncBucket::default(),
+            outbox: OutboxBucket::default(),
+            local_profiles: LocalProfilesBucket::default(),
+            addon_health: AddonHealthBucket::default(),
+            taste: TasteBucket::default(),
+            lists: ListsBucket::default(),
+        },
     );
     let (runtime, _rx) = Runtime::<TestEnv, _>::new(TestModel { ctx }, vec![], 1000);
     TestEnv::run(|| {
@@ -448,8 +523,8 @@ fn actionctx_authenticate_facebook() {
     );
     assert_eq!(
         REQUESTS.read().unwrap().len(),
-        3,
-        "Three requests have been sent"
+        4,
+        "Four requests have been sent"
     );
     assert_eq!(
         REQUESTS.read().unwrap().first().unwrap().to_owned(),
@@ -484,6 +559,17 @@ fn actionctx_authenticate_facebook() {
         },
         "DatastoreGet request has been sent"
     );
+    assert_eq!(
+        REQUESTS.read().unwrap().get(3).unwrap().to_owned(),
+        Request {
+            url: "https://api.strem.io/api/datastoreGet".to_owned(),
+            method: "POST".to_owned(),
+            body: "{\"authKey\":\"auth_key\",\"collection\":\"userList\",\"ids\":[],\"all\":true}"
+                .to_owned(),
+            ..Default::default()
+        },
+        "Lists have been pulled"
+    );
 }
 
 #[test]
@@ -524,6 +610,14 @@ fn actionctx_authenticate_apple() {
             {
                 future::ok(Box::new(APIResult::Ok(LibraryItemsResponse::new(),)) as Box<dyn Any + Send>).boxed_env()
             }
+            Request {
+                url, method, body, ..
+            } if url == "https://api.strem.io/api/datastoreGet"
+                && method == "POST"
+                && body == "{\"authKey\":\"auth_key\",\"collection\":\"userList\",\"ids\":[],\"all\":true}" =>
+            {
+                future::ok(Box::new(APIResult::Ok(Vec::<List>::new())) as Box<dyn Any + Send>).boxed_env()
+            }
             _ => default_fetch_handler(request),
         }
     }
@@ -531,12 +625,20 @@ fn actionctx_authenticate_apple() {
     *FETCH_HANDLER.write().unwrap() = Box::new(fetch_handler);
     let ctx = Ctx::new(
         Profile::default(),
-        LibraryBucket::default(),
-        StreamsBucket::default(),
-        ServerUrlsBucket::new::<TestEnv>(None),
-        NotificationsBucket::new::<TestEnv>(None, vec![]),
-        SearchHistoryBucket::default(),
-        DismissedEventsBucket::default(),
+        CtxBuckets {
+            library: LibraryBucket::default(),
+            streams: StreamsBucket::default(),
+            streaming_server_urls: ServerUrlsBucket::new::<TestEnv>(None),
+            notifications: NotificationsBucket::new::<TestEnv>(None, vec![]),
+            search_history: SearchHistoryBucket::default(),
+            dismissed_events: DismissedEventsBucket::default(),
+            library_sync: LibrarySyncBucket::default(),
+            outbox: OutboxBucket::default(),
+            local_profiles: LocalProfilesBucket::default(),
+            addon_health: AddonHealthBucket::default(),
+            taste: TasteBucket::default(),
+            lists: ListsBucket::default(),
+        },
     );
     let (runtime, _rx) = Runtime::<TestEnv, _>::new(TestModel { ctx }, vec![], 1000);
     TestEnv::run(|| {
@@ -591,8 +693,8 @@ fn actionctx_authenticate_apple() {
     );
     assert_eq!(
         REQUESTS.read().unwrap().len(),
-        3,
-        "Three requests have been sent"
+        4,
+        "Four requests have been sent"
     );
     assert_eq!(
         REQUESTS.read().unwrap().first().unwrap().to_owned(),
@@ -627,6 +729,17 @@ fn actionctx_authenticate_apple() {
         },
         "DatastoreGet request has been sent"
     );
+    assert_eq!(
+        REQUESTS.read().unwrap().get(3).unwrap().to_owned(),
+        Request {
+            url: "https://api.strem.io/api/datastoreGet".to_owned(),
+            method: "POST".to_owned(),
+            body: "{\"authKey\":\"auth_key\",\"collection\":\"userList\",\"ids\":[],\"all\":true}"
+                .to_owned(),
+            ..Default::default()
+        },
+        "Lists have been pulled"
+    );
 }
 
 #[test]
@@ -668,6 +781,14 @@ fn actionctx_authenticate_register() {
                 future::ok(Box::new(APIResult::Ok(LibraryItemsResponse::new(),
                 )) as Box<dyn Any + Send>).boxed_env()
             }
+            Request {
+                url, method, body, ..
+            } if url == "https://api.strem.io/api/datastoreGet"
+                && method == "POST"
+                && body == "{\"authKey\":\"auth_key\",\"collection\":\"userList\",\"ids\":[],\"all\":true}" =>
+            {
+                future::ok(Box::new(APIResult::Ok(Vec::<List>::new())) as Box<dyn Any + Send>).boxed_env()
+            }
             _ => default_fetch_handler(request),
         }
     }
@@ -675,12 +796,20 @@ fn actionctx_authenticate_register() {
     *FETCH_HANDLER.write().unwrap() = Box::new(fetch_handler);
     let ctx = Ctx::new(
         Profile::default(),
-        LibraryBucket::default(),
-        StreamsBucket::default(),
-        ServerUrlsBucket::new::<TestEnv>(None),
-        NotificationsBucket::new::<TestEnv>(None, vec![]),
-        SearchHistoryBucket::default(),
-        DismissedEventsBucket::default(),
+        CtxBuckets {
+            library: LibraryBucket::default(),
+            streams: StreamsBucket::default(),
+            streaming_server_urls: ServerUrlsBucket::new::<TestEnv>(None),
+            notifications: NotificationsBucket::new::<TestEnv>(None, vec![]),
+            search_history: SearchHistoryBucket::default(),
+            dismissed_events: DismissedEventsBucket::default(),
+            library_sync: LibrarySyncBucket::default(),
+            outbox: OutboxBucket::default(),
+            local_profiles: LocalProfilesBucket::default(),
+            addon_health: AddonHealthBucket::default(),
+            taste: TasteBucket::default(),
+            lists: ListsBucket::default(),
+        },
     );
     let (runtime, _rx) = Runtime::<TestEnv, _>::new(TestModel { ctx }, vec![], 1000);
     TestEnv::run(|| {
@@ -739,8 +868,8 @@ fn actionctx_authenticate_register() {
     );
     assert_eq!(
         REQUESTS.read().unwrap().len(),
-        3,
-        "Three requests have been sent"
+        4,
+        "Four requests have been sent"
     );
     assert_eq!(
         REQUESTS.read().unwrap().first().unwrap().to_owned(),
@@ -775,4 +904,15 @@ fn actionctx_authenticate_register() {
         },
         "DatastoreGet request has been sent"
     );
+    assert_eq!(
+        REQUESTS.read().unwrap().get(3).unwrap().to_owned(),
+        Request {
+            url: "https://api.strem.io/api/datastoreGet".to_owned(),
+            method: "POST".to_owned(),
+            body: "{\"authKey\":\"auth_key\",\"collection\":\"userList\",\"ids\":[],\"all\":true}"
+                .to_owned(),
+            ..Default::default()
+        },
+        "Lists have been pulled"
+    );
 }
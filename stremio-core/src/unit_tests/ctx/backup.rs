@@ -0,0 +1,353 @@
+use std::sync::{Arc, RwLock};
+
+use chrono::{Duration, TimeZone, Utc};
+use enclose::enclose;
+use stremio_derive::Model;
+
+use crate::constants::{
+    LIBRARY_RECENT_STORAGE_KEY, LOCAL_PROFILES_STORAGE_KEY, PROFILE_STORAGE_KEY, SCHEMA_VERSION,
+};
+use crate::models::ctx::{Ctx, CtxError, OtherError};
+use crate::runtime::msg::{Action, ActionCtx, Event};
+use crate::runtime::{Env, Runtime, RuntimeAction, RuntimeEvent};
+use crate::types::backup::{Backup, RestoreMode};
+use crate::types::library::{LibraryBucket, LibraryItem};
+use crate::types::local_profiles::{local_profile_storage_key, LocalProfile, LocalProfilesBucket};
+use crate::types::profile::{Auth, AuthKey, ParentalControls, Profile, Settings};
+use crate::types::search_history::SearchHistoryBucket;
+use crate::unit_tests::{TestEnv, EVENTS, NOW, STORAGE};
+
+#[derive(Model, Default, Clone, Debug)]
+#[model(TestEnv)]
+struct TestModel {
+    ctx: Ctx,
+}
+
+fn library_item(id: &str, mtime: chrono::DateTime<Utc>) -> LibraryItem {
+    LibraryItem {
+        id: id.to_owned(),
+        r#type: "movie".to_owned(),
+        name: id.to_owned(),
+        poster: None,
+        poster_shape: Default::default(),
+        removed: false,
+        temp: false,
+        ctime: Some(mtime),
+        mtime,
+        state: Default::default(),
+        behavior_hints: Default::default(),
+    }
+}
+
+fn search_history(queries: &[&str]) -> SearchHistoryBucket {
+    SearchHistoryBucket {
+        uid: None,
+        items: queries
+            .iter()
+            .map(|query| (query.to_string(), TestEnv::now()))
+            .collect(),
+    }
+}
+
+fn restore(runtime: &Runtime<TestEnv, TestModel>, backup: Backup, mode: RestoreMode) {
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Ctx(ActionCtx::RestoreBackup {
+                backup: Box::new(backup),
+                mode,
+            }),
+        })
+    });
+}
+
+#[test]
+fn actionctx_restore_backup_replace() {
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    let backup = Ctx {
+        profile: Profile {
+            auth: Some(Auth {
+                key: AuthKey("auth_key".to_owned()),
+                ..Default::default()
+            }),
+            settings: Settings {
+                interface_language: "bul".to_owned(),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        library: LibraryBucket::new(None, vec![library_item("tt1", TestEnv::now())]),
+        search_history: search_history(&["halloween"]),
+        ..Default::default()
+    }
+    .backup::<TestEnv>();
+    assert_eq!(backup.schema_version, SCHEMA_VERSION);
+    assert!(
+        !backup.buckets[PROFILE_STORAGE_KEY]
+            .to_string()
+            .contains("auth_key"),
+        "Auth is not backed up"
+    );
+    let parental_controls = ParentalControls {
+        locked: true,
+        ..Default::default()
+    };
+    let (runtime, _rx) = Runtime::<TestEnv, _>::new(
+        TestModel {
+            ctx: Ctx {
+                profile: Profile {
+                    parental_controls: parental_controls.to_owned(),
+                    ..Default::default()
+                },
+                library: LibraryBucket::new(None, vec![library_item("tt2", TestEnv::now())]),
+                search_history: search_history(&["scream"]),
+                ..Default::default()
+            },
+        },
+        vec![],
+        1000,
+    );
+    *NOW.write().unwrap() = TestEnv::now() + Duration::days(1);
+    restore(&runtime, backup, RestoreMode::Replace);
+    let model = runtime.model().unwrap();
+    assert_eq!(model.ctx.profile.settings.interface_language, "bul");
+    assert_eq!(
+        model.ctx.profile.parental_controls, parental_controls,
+        "Parental controls are not restored"
+    );
+    assert!(!model.ctx.library.items["tt1"].removed);
+    assert_eq!(
+        model.ctx.library.items["tt1"].mtime,
+        TestEnv::now(),
+        "Restored items are modified now"
+    );
+    assert!(
+        model.ctx.library.items["tt2"].removed,
+        "Items missing in the backup are removed"
+    );
+    assert_eq!(
+        model.ctx.search_history.items.keys().collect::<Vec<_>>(),
+        vec!["halloween"]
+    );
+    assert!(
+        STORAGE
+            .read()
+            .unwrap()
+            .get(LIBRARY_RECENT_STORAGE_KEY)
+            .is_some_and(|library| library.contains("tt1")),
+        "Restored library is pushed to storage"
+    );
+}
+
+#[test]
+fn actionctx_restore_backup_merge() {
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    let older = TestEnv::now();
+    let newer = older + Duration::days(1);
+    let backup = Ctx {
+        library: LibraryBucket::new(
+            None,
+            vec![library_item("tt1", newer), library_item("tt2", older)],
+        ),
+        search_history: search_history(&["halloween"]),
+        ..Default::default()
+    }
+    .backup::<TestEnv>();
+    let current_item = LibraryItem {
+        name: "Current".to_owned(),
+        ..library_item("tt2", newer)
+    };
+    let (runtime, _rx) = Runtime::<TestEnv, _>::new(
+        TestModel {
+            ctx: Ctx {
+                library: LibraryBucket::new(
+                    None,
+                    vec![library_item("tt3", older), current_item.to_owned()],
+                ),
+                search_history: search_history(&["scream"]),
+                ..Default::default()
+            },
+        },
+        vec![],
+        1000,
+    );
+    restore(&runtime, backup, RestoreMode::Merge);
+    let model = runtime.model().unwrap();
+    assert_eq!(model.ctx.library.items.len(), 3);
+    assert_eq!(
+        model.ctx.library.items["tt2"], current_item,
+        "Newer current item is kept"
+    );
+    assert!(model.ctx.library.items.values().all(|item| !item.removed));
+    assert_eq!(model.ctx.search_history.items.len(), 2);
+}
+
+#[test]
+fn actionctx_restore_backup_migrate() {
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    let mut settings = serde_json::to_value(Settings::default()).unwrap();
+    settings
+        .as_object_mut()
+        .unwrap()
+        .remove("streamHealthCheck");
+    settings["interfaceLanguage"] = serde_json::Value::from("bul");
+    let profile = serde_json::json!({
+        "auth": null,
+        "addons": [],
+        "settings": settings,
+    });
+    let mut backup = Backup::new(
+        [(PROFILE_STORAGE_KEY.to_owned(), profile)].into(),
+        Utc.with_ymd_and_hms(2023, 10, 1, 0, 0, 0).unwrap(),
+    );
+    backup.schema_version = 23;
+    let (runtime, _rx) = Runtime::<TestEnv, _>::new(TestModel::default(), vec![], 1000);
+    restore(&runtime, backup, RestoreMode::Replace);
+    let model = runtime.model().unwrap();
+    assert_eq!(
+        model.ctx.profile.settings.interface_language, "bul",
+        "Profile of an older schema version is migrated and restored"
+    );
+    assert!(!model.ctx.profile.settings.stream_health_check);
+    assert!(
+        STORAGE
+            .read()
+            .unwrap()
+            .get(PROFILE_STORAGE_KEY)
+            .is_some_and(|profile| profile.contains("bul")),
+        "Migrated profile is pushed to storage"
+    );
+}
+
+#[test]
+fn actionctx_restore_backup_invalid() {
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    let mut backup = Ctx {
+        search_history: search_history(&["halloween"]),
+        ..Default::default()
+    }
+    .backup::<TestEnv>();
+    backup.buckets.remove(PROFILE_STORAGE_KEY);
+    let (runtime, rx) = Runtime::<TestEnv, _>::new(TestModel::default(), vec![], 1000);
+    let runtime = Arc::new(RwLock::new(runtime));
+    TestEnv::run_with_runtime(
+        rx,
+        runtime.clone(),
+        enclose!((runtime) move || {
+            let runtime = runtime.read().unwrap();
+            runtime.dispatch(RuntimeAction {
+                field: None,
+                action: Action::Ctx(ActionCtx::RestoreBackup {
+                    backup: Box::new(backup),
+                    mode: RestoreMode::Replace,
+                }),
+            })
+        }),
+    );
+    assert!(
+        EVENTS.read().unwrap().iter().any(|event| matches!(
+            event.downcast_ref::<RuntimeEvent<TestEnv, TestModel>>(),
+            Some(RuntimeEvent::CoreEvent(Event::Error {
+                error: CtxError::Other(OtherError::InvalidBackupChecksum),
+                ..
+            }))
+        )),
+        "Modified backup is rejected"
+    );
+    assert!(
+        runtime
+            .read()
+            .unwrap()
+            .model()
+            .unwrap()
+            .ctx
+            .search_history
+            .items
+            .is_empty(),
+        "Nothing is restored"
+    );
+}
+
+#[test]
+fn actionctx_create_backup_local_profiles() {
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    let kids_library_key = local_profile_storage_key(LIBRARY_RECENT_STORAGE_KEY, "kids");
+    STORAGE.write().unwrap().insert(
+        kids_library_key.to_owned(),
+        serde_json::to_string(&LibraryBucket::new(
+            None,
+            vec![library_item("tt2", TestEnv::now())],
+        ))
+        .unwrap(),
+    );
+    let mut local_profiles = LocalProfilesBucket::default();
+    local_profiles.profiles.push(LocalProfile {
+        id: "kids".to_owned(),
+        name: "Kids".to_owned(),
+        settings: Settings::default(),
+    });
+    let (runtime, rx) = Runtime::<TestEnv, _>::new(
+        TestModel {
+            ctx: Ctx {
+                library: LibraryBucket::new(None, vec![library_item("tt1", TestEnv::now())]),
+                local_profiles,
+                ..Default::default()
+            },
+        },
+        vec![],
+        1000,
+    );
+    let runtime = Arc::new(RwLock::new(runtime));
+    TestEnv::run_with_runtime(
+        rx,
+        runtime.clone(),
+        enclose!((runtime) move || {
+            let runtime = runtime.read().unwrap();
+            runtime.dispatch(RuntimeAction {
+                field: None,
+                action: Action::Ctx(ActionCtx::CreateBackup),
+            })
+        }),
+    );
+    let backup = EVENTS
+        .read()
+        .unwrap()
+        .iter()
+        .find_map(
+            |event| match event.downcast_ref::<RuntimeEvent<TestEnv, TestModel>>() {
+                Some(RuntimeEvent::CoreEvent(Event::BackupCreated { backup })) => {
+                    Some(backup.to_owned())
+                }
+                _ => None,
+            },
+        )
+        .expect("Backup is created");
+    assert!(backup.is_valid_checksum());
+    assert!(
+        backup.buckets.contains_key(LOCAL_PROFILES_STORAGE_KEY),
+        "Local profiles are backed up"
+    );
+    assert!(
+        backup.buckets[&kids_library_key]
+            .to_string()
+            .contains("tt2"),
+        "Buckets of the other local profiles are backed up from the storage"
+    );
+    STORAGE.write().unwrap().clear();
+    let (runtime, _rx) = Runtime::<TestEnv, _>::new(TestModel::default(), vec![], 1000);
+    restore(&runtime, *backup, RestoreMode::Merge);
+    let model = runtime.model().unwrap();
+    assert!(model.ctx.library.items.contains_key("tt1"));
+    assert!(
+        model.ctx.local_profiles.get("kids").is_some(),
+        "Local profiles are restored"
+    );
+    assert!(
+        STORAGE
+            .read()
+            .unwrap()
+            .get(&kids_library_key)
+            .is_some_and(|library| library.contains("tt2")),
+        "Buckets of the other local profiles are restored in the storage"
+    );
+}
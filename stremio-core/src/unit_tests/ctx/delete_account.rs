@@ -1,6 +1,11 @@
+use crate::types::addon_health::AddonHealthBucket;
+use crate::types::lists::ListsBucket;
+use crate::types::local_profiles::LocalProfilesBucket;
+use crate::types::outbox::OutboxBucket;
+use crate::types::taste::TasteBucket;
 use crate::{
     constants::PROFILE_STORAGE_KEY,
-    models::ctx::Ctx,
+    models::ctx::{Ctx, CtxBuckets},
     runtime::{
         msg::{Action, ActionCtx},
         Env, EnvFutureExt, Runtime, RuntimeAction, TryEnvFuture,
@@ -8,7 +13,7 @@ use crate::{
     types::{
         api::{APIResult, SuccessResponse},
         events::DismissedEventsBucket,
-        library::LibraryBucket,
+        library::{LibraryBucket, LibrarySyncBucket},
         notifications::NotificationsBucket,
         profile::{Auth, AuthKey, GDPRConsent, Password, Profile, User},
         search_history::SearchHistoryBucket,
@@ -85,12 +90,20 @@ fn actionctx_delete_account() {
         TestModel {
             ctx: Ctx::new(
                 profile,
-                LibraryBucket::default(),
-                StreamsBucket::default(),
-                ServerUrlsBucket::new::<TestEnv>(None),
-                NotificationsBucket::new::<TestEnv>(None, vec![]),
-                SearchHistoryBucket::default(),
-                DismissedEventsBucket::default(),
+                CtxBuckets {
+                    library: LibraryBucket::default(),
+                    streams: StreamsBucket::default(),
+                    streaming_server_urls: ServerUrlsBucket::new::<TestEnv>(None),
+                    notifications: NotificationsBucket::new::<TestEnv>(None, vec![]),
+                    search_history: SearchHistoryBucket::default(),
+                    dismissed_events: DismissedEventsBucket::default(),
+                    library_sync: LibrarySyncBucket::default(),
+                    outbox: OutboxBucket::default(),
+                    local_profiles: LocalProfilesBucket::default(),
+                    addon_health: AddonHealthBucket::default(),
+                    taste: TasteBucket::default(),
+                    lists: ListsBucket::default(),
+                },
             ),
         },
         vec![],
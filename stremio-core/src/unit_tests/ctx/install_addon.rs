@@ -1,16 +1,21 @@
 use crate::constants::PROFILE_STORAGE_KEY;
-use crate::models::ctx::Ctx;
+use crate::models::ctx::{Ctx, CtxBuckets};
 use crate::runtime::msg::{Action, ActionCtx};
 use crate::runtime::{Env, EnvFutureExt, Runtime, RuntimeAction, TryEnvFuture};
 use crate::types::addon::{Descriptor, Manifest};
+use crate::types::addon_health::AddonHealthBucket;
 use crate::types::api::{APIResult, SuccessResponse};
 use crate::types::events::DismissedEventsBucket;
-use crate::types::library::LibraryBucket;
+use crate::types::library::{LibraryBucket, LibrarySyncBucket};
+use crate::types::lists::ListsBucket;
+use crate::types::local_profiles::LocalProfilesBucket;
 use crate::types::notifications::NotificationsBucket;
+use crate::types::outbox::OutboxBucket;
 use crate::types::profile::{Auth, AuthKey, GDPRConsent, Profile, User};
 use crate::types::search_history::SearchHistoryBucket;
 use crate::types::server_urls::ServerUrlsBucket;
 use crate::types::streams::StreamsBucket;
+use crate::types::taste::TasteBucket;
 use crate::types::True;
 use crate::unit_tests::{
     default_fetch_handler, Request, TestEnv, FETCH_HANDLER, REQUESTS, STORAGE,
@@ -55,12 +60,20 @@ fn actionctx_installaddon_install() {
                     addons: vec![],
                     ..Default::default()
                 },
-                LibraryBucket::default(),
-                StreamsBucket::default(),
-                ServerUrlsBucket::new::<TestEnv>(None),
-                NotificationsBucket::new::<TestEnv>(None, vec![]),
-                SearchHistoryBucket::default(),
-                DismissedEventsBucket::default(),
+                CtxBuckets {
+                    library: LibraryBucket::default(),
+                    streams: StreamsBucket::default(),
+                    streaming_server_urls: ServerUrlsBucket::new::<TestEnv>(None),
+                    notifications: NotificationsBucket::new::<TestEnv>(None, vec![]),
+                    search_history: SearchHistoryBucket::default(),
+                    dismissed_events: DismissedEventsBucket::default(),
+                    library_sync: LibrarySyncBucket::default(),
+                    outbox: OutboxBucket::default(),
+                    local_profiles: LocalProfilesBucket::default(),
+                    addon_health: AddonHealthBucket::default(),
+                    taste: TasteBucket::default(),
+                    lists: ListsBucket::default(),
+                },
             ),
         },
         vec![],
@@ -163,12 +176,20 @@ fn actionctx_installaddon_install_with_user() {
                     addons: vec![],
                     ..Default::default()
                 },
-                LibraryBucket::default(),
-                StreamsBucket::default(),
-                ServerUrlsBucket::new::<TestEnv>(None),
-                NotificationsBucket::new::<TestEnv>(None, vec![]),
-                SearchHistoryBucket::default(),
-                DismissedEventsBucket::default(),
+                CtxBuckets {
+                    library: LibraryBucket::default(),
+                    streams: StreamsBucket::default(),
+                    streaming_server_urls: ServerUrlsBucket::new::<TestEnv>(None),
+                    notifications: NotificationsBucket::new::<TestEnv>(None, vec![]),
+                    search_history: SearchHistoryBucket::default(),
+                    dismissed_events: DismissedEventsBucket::default(),
+                    library_sync: LibrarySyncBucket::default(),
+                    outbox: OutboxBucket::default(),
+                    local_profiles: LocalProfilesBucket::default(),
+                    addon_health: AddonHealthBucket::default(),
+                    taste: TasteBucket::default(),
+                    lists: ListsBucket::default(),
+                },
             ),
         },
         vec![],
@@ -287,12 +308,20 @@ fn actionctx_installaddon_update() {
                     ],
                     ..Default::default()
                 },
-                LibraryBucket::default(),
-                StreamsBucket::default(),
-                ServerUrlsBucket::new::<TestEnv>(None),
-                NotificationsBucket::new::<TestEnv>(None, vec![]),
-                SearchHistoryBucket::default(),
-                DismissedEventsBucket::default(),
+                CtxBuckets {
+                    library: LibraryBucket::default(),
+                    streams: StreamsBucket::default(),
+                    streaming_server_urls: ServerUrlsBucket::new::<TestEnv>(None),
+                    notifications: NotificationsBucket::new::<TestEnv>(None, vec![]),
+                    search_history: SearchHistoryBucket::default(),
+                    dismissed_events: DismissedEventsBucket::default(),
+                    library_sync: LibrarySyncBucket::default(),
+                    outbox: OutboxBucket::default(),
+                    local_profiles: LocalProfilesBucket::default(),
+                    addon_health: AddonHealthBucket::default(),
+                    taste: TasteBucket::default(),
+                    lists: ListsBucket::default(),
+                },
             ),
         },
         vec![],
@@ -365,12 +394,20 @@ fn actionctx_installaddon_already_installed() {
         TestModel {
             ctx: Ctx::new(
                 profile,
-                LibraryBucket::default(),
-                StreamsBucket::default(),
-                ServerUrlsBucket::new::<TestEnv>(None),
-                NotificationsBucket::new::<TestEnv>(None, vec![]),
-                SearchHistoryBucket::default(),
-                DismissedEventsBucket::default(),
+                CtxBuckets {
+                    library: LibraryBucket::default(),
+                    streams: StreamsBucket::default(),
+                    streaming_server_urls: ServerUrlsBucket::new::<TestEnv>(None),
+                    notifications: NotificationsBucket::new::<TestEnv>(None, vec![]),
+                    search_history: SearchHistoryBucket::default(),
+                    dismissed_events: DismissedEventsBucket::default(),
+                    library_sync: LibrarySyncBucket::default(),
+                    outbox: OutboxBucket::default(),
+                    local_profiles: LocalProfilesBucket::default(),
+                    addon_health: AddonHealthBucket::default(),
+                    taste: TasteBucket::default(),
+                    lists: ListsBucket::default(),
+                },
             ),
         },
         vec![],
@@ -0,0 +1,253 @@
+use crate::constants::{
+    DEFAULT_LOCAL_PROFILE_ID, LIBRARY_RECENT_STORAGE_KEY, LISTS_STORAGE_KEY,
+    LOCAL_PROFILES_STORAGE_KEY, SEARCH_HISTORY_STORAGE_KEY,
+};
+use crate::models::ctx::Ctx;
+use crate::runtime::msg::{Action, ActionCtx};
+use crate::runtime::{Env, Runtime, RuntimeAction};
+use crate::types::library::{LibraryBucket, LibraryItem};
+use crate::types::lists::ListsBucket;
+use crate::types::local_profiles::{local_profile_storage_key, LocalProfilesBucket};
+use crate::types::profile::{Profile, Settings};
+use crate::types::resource::MetaItemPreview;
+use crate::unit_tests::{TestEnv, STORAGE};
+use stremio_derive::Model;
+
+fn library_item(id: &str) -> LibraryItem {
+    LibraryItem {
+        id: id.to_owned(),
+        r#type: "movie".to_owned(),
+        name: id.to_owned(),
+        poster: None,
+        poster_shape: Default::default(),
+        removed: false,
+        temp: false,
+        ctime: Some(TestEnv::now()),
+        mtime: TestEnv::now(),
+        state: Default::default(),
+        behavior_hints: Default::default(),
+    }
+}
+
+#[test]
+fn actionctx_local_profiles() {
+    #[derive(Model, Default, Clone, Debug)]
+    #[model(TestEnv)]
+    struct TestModel {
+        ctx: Ctx,
+    }
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    let default_library = LibraryBucket::new(None, vec![library_item("tt1")]);
+    STORAGE.write().unwrap().insert(
+        LIBRARY_RECENT_STORAGE_KEY.to_owned(),
+        serde_json::to_string(&default_library).unwrap(),
+    );
+    let (runtime, _rx) = Runtime::<TestEnv, _>::new(
+        TestModel {
+            ctx: Ctx {
+                profile: Profile::default(),
+                library: default_library.to_owned(),
+                local_profiles: LocalProfilesBucket::default(),
+                ..Default::default()
+            },
+        },
+        vec![],
+        1000,
+    );
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Ctx(ActionCtx::AddLocalProfile {
+                name: "  ".to_owned(),
+            }),
+        })
+    });
+    assert_eq!(
+        runtime.model().unwrap().ctx.local_profiles.profiles.len(),
+        1,
+        "Local profile with an empty name is not added"
+    );
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Ctx(ActionCtx::AddLocalProfile {
+                name: " Kids ".to_owned(),
+            }),
+        })
+    });
+    let local_profiles = runtime.model().unwrap().ctx.local_profiles.to_owned();
+    assert_eq!(local_profiles.profiles.len(), 2, "Local profile added");
+    assert_eq!(
+        local_profiles.profiles[1].name, "Kids",
+        "Local profile name is trimmed"
+    );
+    assert_eq!(
+        local_profiles.selected, DEFAULT_LOCAL_PROFILE_ID,
+        "Default local profile is still selected"
+    );
+    assert_eq!(
+        serde_json::from_str::<LocalProfilesBucket>(
+            STORAGE
+                .read()
+                .unwrap()
+                .get(LOCAL_PROFILES_STORAGE_KEY)
+                .unwrap()
+        )
+        .unwrap(),
+        local_profiles,
+        "Local profiles updated in storage"
+    );
+    let kids_id = local_profiles.profiles[1].id.to_owned();
+    assert_eq!(
+        kids_id, "00000000000000000000000000000002",
+        "Id of the kids local profile is generated by the env after the id of the rejected profile"
+    );
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Ctx(ActionCtx::SwitchLocalProfile(kids_id.to_owned())),
+        })
+    });
+    assert_eq!(
+        runtime.model().unwrap().ctx.local_profiles.selected,
+        kids_id,
+        "Kids local profile is selected"
+    );
+    assert!(
+        runtime.model().unwrap().ctx.library.items.is_empty(),
+        "Library of the kids local profile is empty"
+    );
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Ctx(ActionCtx::AddToLibrary(MetaItemPreview {
+                id: "tt2".to_owned(),
+                r#type: "movie".to_owned(),
+                name: "tt2".to_owned(),
+                ..Default::default()
+            })),
+        })
+    });
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Ctx(ActionCtx::CreateList {
+                name: "Cartoons".to_owned(),
+            }),
+        })
+    });
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Ctx(ActionCtx::UpdateSettings(Settings {
+                interface_language: "bul".to_owned(),
+                ..Default::default()
+            })),
+        })
+    });
+    {
+        let storage = STORAGE.read().unwrap();
+        let kids_library = serde_json::from_str::<LibraryBucket>(
+            storage
+                .get(&local_profile_storage_key(
+                    LIBRARY_RECENT_STORAGE_KEY,
+                    &kids_id,
+                ))
+                .unwrap(),
+        )
+        .unwrap();
+        assert!(
+            kids_library.items.contains_key("tt2"),
+            "Library of the kids local profile updated in storage"
+        );
+        assert_eq!(
+            serde_json::from_str::<LibraryBucket>(storage.get(LIBRARY_RECENT_STORAGE_KEY).unwrap())
+                .unwrap(),
+            default_library,
+            "Library of the default local profile is not changed in storage"
+        );
+        let kids_lists = serde_json::from_str::<ListsBucket>(
+            storage
+                .get(&local_profile_storage_key(LISTS_STORAGE_KEY, &kids_id))
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            kids_lists.ordered().len(),
+            1,
+            "Lists of the kids local profile updated in storage"
+        );
+        assert!(
+            storage.get(LISTS_STORAGE_KEY).is_none(),
+            "Lists of the default local profile are not changed in storage"
+        );
+    }
+    assert_eq!(
+        runtime.model().unwrap().ctx.local_profiles.profiles[1]
+            .settings
+            .interface_language,
+        "bul",
+        "Settings of the kids local profile updated"
+    );
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Ctx(ActionCtx::RemoveLocalProfile(kids_id.to_owned())),
+        })
+    });
+    assert_eq!(
+        runtime.model().unwrap().ctx.local_profiles.profiles.len(),
+        2,
+        "Selected local profile is not removed"
+    );
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Ctx(ActionCtx::SwitchLocalProfile(
+                DEFAULT_LOCAL_PROFILE_ID.to_owned(),
+            )),
+        })
+    });
+    assert_eq!(
+        runtime.model().unwrap().ctx.library,
+        default_library,
+        "Library of the default local profile is loaded"
+    );
+    assert!(
+        runtime.model().unwrap().ctx.lists.items.is_empty(),
+        "Lists of the default local profile are loaded"
+    );
+    assert_eq!(
+        runtime.model().unwrap().ctx.profile.settings,
+        Settings::default(),
+        "Settings of the default local profile are loaded"
+    );
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Ctx(ActionCtx::RemoveLocalProfile(kids_id.to_owned())),
+        })
+    });
+    assert_eq!(
+        runtime.model().unwrap().ctx.local_profiles.profiles.len(),
+        1,
+        "Kids local profile removed"
+    );
+    {
+        let storage = STORAGE.read().unwrap();
+        assert!(
+            !storage.contains_key(&local_profile_storage_key(
+                LIBRARY_RECENT_STORAGE_KEY,
+                &kids_id
+            )),
+            "Library of the kids local profile removed from storage"
+        );
+        assert!(
+            !storage.contains_key(&local_profile_storage_key(
+                SEARCH_HISTORY_STORAGE_KEY,
+                &kids_id
+            )),
+            "Search history of the kids local profile removed from storage"
+        );
+    }
+}
@@ -1,15 +1,20 @@
 use crate::constants::{LIBRARY_RECENT_STORAGE_KEY, LIBRARY_STORAGE_KEY, PROFILE_STORAGE_KEY};
-use crate::models::ctx::Ctx;
+use crate::models::ctx::{Ctx, CtxBuckets};
 use crate::runtime::msg::{Action, ActionCtx};
 use crate::runtime::{Env, EnvFutureExt, Runtime, RuntimeAction, TryEnvFuture};
+use crate::types::addon_health::AddonHealthBucket;
 use crate::types::api::{APIResult, SuccessResponse};
 use crate::types::events::DismissedEventsBucket;
-use crate::types::library::LibraryBucket;
+use crate::types::library::{LibraryBucket, LibrarySyncBucket};
+use crate::types::lists::ListsBucket;
+use crate::types::local_profiles::LocalProfilesBucket;
 use crate::types::notifications::NotificationsBucket;
+use crate::types::outbox::OutboxBucket;
 use crate::types::profile::{Auth, AuthKey, GDPRConsent, Profile, User};
 use crate::types::search_history::SearchHistoryBucket;
 use crate::types::server_urls::ServerUrlsBucket;
 use crate::types::streams::StreamsBucket;
+use crate::types::taste::TasteBucket;
 use crate::types::True;
 use crate::unit_tests::{
     default_fetch_handler, Request, TestEnv, FETCH_HANDLER, REQUESTS, STORAGE,
@@ -88,12 +93,20 @@ fn actionctx_logout() {
         TestModel {
             ctx: Ctx::new(
                 profile,
-                library,
-                StreamsBucket::default(),
-                ServerUrlsBucket::new::<TestEnv>(None),
-                NotificationsBucket::new::<TestEnv>(None, vec![]),
-                SearchHistoryBucket::default(),
-                DismissedEventsBucket::default(),
+                CtxBuckets {
+                    library: library,
+                    streams: StreamsBucket::default(),
+                    streaming_server_urls: ServerUrlsBucket::new::<TestEnv>(None),
+                    notifications: NotificationsBucket::new::<TestEnv>(None, vec![]),
+                    search_history: SearchHistoryBucket::default(),
+                    dismissed_events: DismissedEventsBucket::default(),
+                    library_sync: LibrarySyncBucket::default(),
+                    outbox: OutboxBucket::default(),
+                    local_profiles: LocalProfilesBucket::default(),
+                    addon_health: AddonHealthBucket::default(),
+                    taste: TasteBucket::default(),
+                    lists: ListsBucket::default(),
+                },
             ),
         },
         vec![],
@@ -1,8 +1,12 @@
 mod add_to_library;
 mod authenticate;
+mod backup;
 mod delete_account;
 mod install_addon;
+mod local_profiles;
 mod logout;
+mod outbox;
+mod parental_controls;
 mod update_events;
 mod notifications {
     mod update_notifications;
@@ -4,6 +4,11 @@ use std::{
     sync::{Arc, RwLock},
 };
 
+use crate::types::addon_health::AddonHealthBucket;
+use crate::types::lists::ListsBucket;
+use crate::types::local_profiles::LocalProfilesBucket;
+use crate::types::outbox::OutboxBucket;
+use crate::types::taste::TasteBucket;
 use assert_matches::assert_matches;
 use chrono::{TimeZone, Utc};
 use enclose::enclose;
@@ -18,7 +23,7 @@ use stremio_derive::Model;
 use crate::{
     constants::{CATALOG_RESOURCE_NAME, LAST_VIDEOS_IDS_EXTRA_PROP},
     models::{
-        ctx::Ctx,
+        ctx::{Ctx, CtxBuckets},
         player::{Player, Selected as PlayerSelected},
     },
     runtime::{
@@ -28,10 +33,10 @@ use crate::{
     types::{
         addon::{
             Descriptor, Manifest, ManifestCatalog, ManifestExtra, ResourcePath, ResourceRequest,
-            ResourceResponse,
+            ResourceResponse, ResourceResponseCache,
         },
         events::DismissedEventsBucket,
-        library::{LibraryBucket, LibraryItem, LibraryItemState},
+        library::{LibraryBucket, LibraryItem, LibraryItemState, LibrarySyncBucket},
         notifications::{NotificationItem, NotificationsBucket},
         profile::Profile,
         resource::{
@@ -51,7 +56,7 @@ pub const PULL_NOTIFICATIONS_TEST_DATA: &[u8] = include_bytes!("./pull_notificat
 
 #[derive(Deserialize)]
 struct TestData {
-    network_requests: HashMap<String, ResourceResponse>,
+    network_requests: HashMap<String, ResourceResponseCache>,
     addons: Vec<Descriptor>,
     library_items: Vec<LibraryItem>,
     notification_items: Vec<NotificationItem>,
@@ -112,6 +117,7 @@ fn test_pull_notifications_and_play_in_player() {
             links: vec![],
             trailer_streams: vec![],
             behavior_hints: Default::default(),
+            certification: None,
         },
         videos: vec![
             Video {
@@ -176,16 +182,20 @@ fn test_pull_notifications_and_play_in_player() {
                 if url == "https://addon_1.com/catalog/series/lastVideosIds/lastVideosIds=tt1.json"
                     && method == "GET" =>
             {
-                future::ok(Box::new(ResourceResponse::MetasDetailed {
-                    metas_detailed: vec![meta_item],
-                }) as Box<dyn Any + Send>)
+                future::ok(Box::new(ResourceResponseCache::from(
+                    ResourceResponse::MetasDetailed {
+                        metas_detailed: vec![meta_item],
+                    },
+                )) as Box<dyn Any + Send>)
                 .boxed_env()
             }
             Request { url, method, .. }
                 if url == "https://addon_1.com/meta/series/tt1.json" && method == "GET" =>
             {
                 future::ok(
-                    Box::new(ResourceResponse::Meta { meta: meta_item }) as Box<dyn Any + Send>
+                    Box::new(ResourceResponseCache::from(ResourceResponse::Meta {
+                        meta: meta_item,
+                    })) as Box<dyn Any + Send>,
                 )
                 .boxed_env()
             }
@@ -194,7 +204,9 @@ fn test_pull_notifications_and_play_in_player() {
                     && method == "GET" =>
             {
                 future::ok(
-                    Box::new(ResourceResponse::Streams { streams: vec![] }) as Box<dyn Any + Send>
+                    Box::new(ResourceResponseCache::from(ResourceResponse::Streams {
+                        streams: vec![],
+                    })) as Box<dyn Any + Send>,
                 )
                 .boxed_env()
             }
@@ -211,39 +223,49 @@ fn test_pull_notifications_and_play_in_player() {
                     addons: vec![ADDON_1.clone()],
                     ..Default::default()
                 },
-                LibraryBucket::new(
-                    None,
-                    vec![LibraryItem {
-                        id: "tt1".to_string(),
-                        name: "name".to_string(),
-                        r#type: "series".to_string(),
-                        poster: None,
-                        poster_shape: PosterShape::Poster,
-                        removed: false,
-                        temp: false,
-                        ctime: Some(TestEnv::now()),
-                        mtime: TestEnv::now(),
-                        state: LibraryItemState {
-                            watched: None,
-                            time_watched: 1000,
-                            overall_time_watched: 15 * 60 * 1000 + 1,
-                            // Episode 5 is released on this date and we've watched it the later that day
-                            last_watched: Some(Utc.with_ymd_and_hms(2020, 1, 1, 20, 0, 0).unwrap()),
-                            times_watched: 5,
-                            flagged_watched: 1,
-                            time_offset: 100,
-                            duration: 101,
-                            video_id: Some("tt1:1:5".to_string()),
-                            no_notif: false,
-                        },
-                        behavior_hints: Default::default(),
-                    }],
-                ),
-                StreamsBucket::default(),
-                ServerUrlsBucket::new::<TestEnv>(None),
-                NotificationsBucket::new::<TestEnv>(None, vec![]),
-                SearchHistoryBucket::default(),
-                DismissedEventsBucket::default(),
+                CtxBuckets {
+                    library: LibraryBucket::new(
+                        None,
+                        vec![LibraryItem {
+                            id: "tt1".to_string(),
+                            name: "name".to_string(),
+                            r#type: "series".to_string(),
+                            poster: None,
+                            poster_shape: PosterShape::Poster,
+                            removed: false,
+                            temp: false,
+                            ctime: Some(TestEnv::now()),
+                            mtime: TestEnv::now(),
+                            state: LibraryItemState {
+                                watched: None,
+                                time_watched: 1000,
+                                overall_time_watched: 15 * 60 * 1000 + 1,
+                                // Episode 5 is released on this date and we've watched it the later that day
+                                last_watched: Some(
+                                    Utc.with_ymd_and_hms(2020, 1, 1, 20, 0, 0).unwrap(),
+                                ),
+                                times_watched: 5,
+                                flagged_watched: 1,
+                                time_offset: 100,
+                                duration: 101,
+                                video_id: Some("tt1:1:5".to_string()),
+                                no_notif: false,
+                            },
+                            behavior_hints: Default::default(),
+                        }],
+                    ),
+                    streams: StreamsBucket::default(),
+                    streaming_server_urls: ServerUrlsBucket::new::<TestEnv>(None),
+                    notifications: NotificationsBucket::new::<TestEnv>(None, vec![]),
+                    search_history: SearchHistoryBucket::default(),
+                    dismissed_events: DismissedEventsBucket::default(),
+                    library_sync: LibrarySyncBucket::default(),
+                    outbox: OutboxBucket::default(),
+                    local_profiles: LocalProfilesBucket::default(),
+                    addon_health: AddonHealthBucket::default(),
+                    taste: TasteBucket::default(),
+                    lists: ListsBucket::default(),
+                },
             ),
             player: Default::default(),
         },
@@ -388,12 +410,23 @@ fn test_pull_notifications_test_cases() {
                         addons: test.addons,
                         ..Default::default()
                     },
-                    LibraryBucket::new(None, test.library_items),
-                    StreamsBucket::default(),
-                    ServerUrlsBucket::new::<TestEnv>(None),
-                    NotificationsBucket::new::<TestEnv>(None, test.notification_items),
-                    SearchHistoryBucket::default(),
-                    DismissedEventsBucket::default(),
+                    CtxBuckets {
+                        library: LibraryBucket::new(None, test.library_items),
+                        streams: StreamsBucket::default(),
+                        streaming_server_urls: ServerUrlsBucket::new::<TestEnv>(None),
+                        notifications: NotificationsBucket::new::<TestEnv>(
+                            None,
+                            test.notification_items,
+                        ),
+                        search_history: SearchHistoryBucket::default(),
+                        dismissed_events: DismissedEventsBucket::default(),
+                        library_sync: LibrarySyncBucket::default(),
+                        outbox: OutboxBucket::default(),
+                        local_profiles: LocalProfilesBucket::default(),
+                        addon_health: AddonHealthBucket::default(),
+                        taste: TasteBucket::default(),
+                        lists: ListsBucket::default(),
+                    },
                 ),
             },
             vec![],
@@ -430,82 +463,90 @@ fn test_dismiss_notification() {
                 Profile {
                     ..Default::default()
                 },
-                LibraryBucket::new(
-                    None,
-                    vec![
-                        LibraryItem {
-                            id: "tt1".to_string(),
-                            name: "Item 1".to_string(),
-                            r#type: "series".to_string(),
-                            poster: None,
-                            poster_shape: PosterShape::Poster,
-                            removed: false,
-                            temp: false,
-                            ctime: Some(Utc.with_ymd_and_hms(2022, 6, 20, 0, 0, 0).unwrap()),
-                            mtime: Utc.with_ymd_and_hms(2022, 6, 20, 0, 0, 0).unwrap(),
-                            state: LibraryItemState {
-                                last_watched: Some(
-                                    Utc.with_ymd_and_hms(2022, 6, 20, 0, 0, 0).unwrap(),
-                                ),
-                                time_watched: 40 * 60 * 60 * 1000,
-                                time_offset: 15,
-                                overall_time_watched: 140 * 60 * 60 * 1000,
-                                times_watched: 2,
-                                flagged_watched: 1,
-                                duration: 55 * 60 * 60 * 1000,
-                                video_id: Some("tt1:1".into()),
-                                watched: None,
-                                no_notif: false,
+                CtxBuckets {
+                    library: LibraryBucket::new(
+                        None,
+                        vec![
+                            LibraryItem {
+                                id: "tt1".to_string(),
+                                name: "Item 1".to_string(),
+                                r#type: "series".to_string(),
+                                poster: None,
+                                poster_shape: PosterShape::Poster,
+                                removed: false,
+                                temp: false,
+                                ctime: Some(Utc.with_ymd_and_hms(2022, 6, 20, 0, 0, 0).unwrap()),
+                                mtime: Utc.with_ymd_and_hms(2022, 6, 20, 0, 0, 0).unwrap(),
+                                state: LibraryItemState {
+                                    last_watched: Some(
+                                        Utc.with_ymd_and_hms(2022, 6, 20, 0, 0, 0).unwrap(),
+                                    ),
+                                    time_watched: 40 * 60 * 60 * 1000,
+                                    time_offset: 15,
+                                    overall_time_watched: 140 * 60 * 60 * 1000,
+                                    times_watched: 2,
+                                    flagged_watched: 1,
+                                    duration: 55 * 60 * 60 * 1000,
+                                    video_id: Some("tt1:1".into()),
+                                    watched: None,
+                                    no_notif: false,
+                                },
+                                behavior_hints: Default::default(),
                             },
-                            behavior_hints: Default::default(),
-                        },
-                        LibraryItem {
-                            id: "tt2".to_string(),
-                            name: "Item 2".to_string(),
-                            r#type: "series".to_string(),
-                            poster: None,
-                            poster_shape: PosterShape::Poster,
-                            removed: false,
-                            temp: false,
-                            ctime: Some(Utc.with_ymd_and_hms(2022, 6, 20, 0, 0, 0).unwrap()),
-                            mtime: Utc.with_ymd_and_hms(2022, 6, 20, 0, 0, 0).unwrap(),
-                            state: LibraryItemState {
-                                last_watched: Some(
-                                    Utc.with_ymd_and_hms(2022, 6, 20, 0, 0, 0).unwrap(),
-                                ),
-                                time_watched: 40 * 60 * 60 * 1000,
-                                time_offset: 15,
-                                overall_time_watched: 140 * 60 * 60 * 1000,
-                                times_watched: 2,
-                                flagged_watched: 1,
-                                duration: 55 * 60 * 60 * 1000,
-                                video_id: Some("tt1:1".into()),
-                                watched: None,
-                                no_notif: false,
+                            LibraryItem {
+                                id: "tt2".to_string(),
+                                name: "Item 2".to_string(),
+                                r#type: "series".to_string(),
+                                poster: None,
+                                poster_shape: PosterShape::Poster,
+                                removed: false,
+                                temp: false,
+                                ctime: Some(Utc.with_ymd_and_hms(2022, 6, 20, 0, 0, 0).unwrap()),
+                                mtime: Utc.with_ymd_and_hms(2022, 6, 20, 0, 0, 0).unwrap(),
+                                state: LibraryItemState {
+                                    last_watched: Some(
+                                        Utc.with_ymd_and_hms(2022, 6, 20, 0, 0, 0).unwrap(),
+                                    ),
+                                    time_watched: 40 * 60 * 60 * 1000,
+                                    time_offset: 15,
+                                    overall_time_watched: 140 * 60 * 60 * 1000,
+                                    times_watched: 2,
+                                    flagged_watched: 1,
+                                    duration: 55 * 60 * 60 * 1000,
+                                    video_id: Some("tt1:1".into()),
+                                    watched: None,
+                                    no_notif: false,
+                                },
+                                behavior_hints: Default::default(),
                             },
-                            behavior_hints: Default::default(),
-                        },
-                    ],
-                ),
-                StreamsBucket::default(),
-                ServerUrlsBucket::new::<TestEnv>(None),
-                NotificationsBucket::new::<TestEnv>(
-                    None,
-                    vec![
-                        NotificationItem {
-                            meta_id: "tt1".to_string(),
-                            video_id: "tt1:2".to_string(),
-                            video_released: Utc.with_ymd_and_hms(2023, 7, 10, 0, 0, 0).unwrap(),
-                        },
-                        NotificationItem {
-                            meta_id: "tt2".to_string(),
-                            video_id: "tt2:10".to_string(),
-                            video_released: Utc.with_ymd_and_hms(2023, 8, 14, 0, 0, 0).unwrap(),
-                        },
-                    ],
-                ),
-                SearchHistoryBucket::default(),
-                DismissedEventsBucket::default(),
+                        ],
+                    ),
+                    streams: StreamsBucket::default(),
+                    streaming_server_urls: ServerUrlsBucket::new::<TestEnv>(None),
+                    notifications: NotificationsBucket::new::<TestEnv>(
+                        None,
+                        vec![
+                            NotificationItem {
+                                meta_id: "tt1".to_string(),
+                                video_id: "tt1:2".to_string(),
+                                video_released: Utc.with_ymd_and_hms(2023, 7, 10, 0, 0, 0).unwrap(),
+                            },
+                            NotificationItem {
+                                meta_id: "tt2".to_string(),
+                                video_id: "tt2:10".to_string(),
+                                video_released: Utc.with_ymd_and_hms(2023, 8, 14, 0, 0, 0).unwrap(),
+                            },
+                        ],
+                    ),
+                    search_history: SearchHistoryBucket::default(),
+                    dismissed_events: DismissedEventsBucket::default(),
+                    library_sync: LibrarySyncBucket::default(),
+                    outbox: OutboxBucket::default(),
+                    local_profiles: LocalProfilesBucket::default(),
+                    addon_health: AddonHealthBucket::default(),
+                    taste: TasteBucket::default(),
+                    lists: ListsBucket::default(),
+                },
             ),
         },
         vec![],
@@ -528,7 +569,7 @@ fn test_dismiss_notification() {
         }),
     );
     let events = EVENTS.read().unwrap();
-    assert_eq!(events.len(), 5);
+    assert_eq!(events.len(), 7);
 
     let events = events
         .iter()
@@ -552,28 +593,38 @@ fn test_dismiss_notification() {
 
     assert_matches!(
         events[2],
+        RuntimeEvent::NewState(fields, model) if fields.len() == 1 && *fields.first().unwrap() == TestModelField::Ctx && model.ctx.taste.items.len() == 2,
+        "The taste should be seeded with the watched LibraryItems"
+    );
+    assert_matches!(
+        events[3],
         RuntimeEvent::CoreEvent(crate::runtime::msg::Event::NotificationsDismissed {
             id
         }) if id == "tt1"
     );
     assert_matches!(
-        events[3],
+        events[4],
         RuntimeEvent::CoreEvent(crate::runtime::msg::Event::NotificationsPushedToStorage {
             ids
         }) if ids == &["tt2".to_string()]
     );
     assert_matches!(
-        events[4],
+        events[5],
         RuntimeEvent::CoreEvent(Event::LibraryItemsPushedToStorage { ids }) if ids == &["tt1".to_string()],
         "LibraryItem should be pushed to storage"
     );
+    assert_matches!(
+        events[6],
+        RuntimeEvent::CoreEvent(Event::TastePushedToStorage { uid: None }),
+        "Taste should be pushed to storage"
+    );
 
     let states = STATES.read().unwrap();
     let states = states
         .iter()
         .map(|state| state.downcast_ref::<TestModel>().unwrap())
         .collect::<Vec<_>>();
-    assert_eq!(3, states.len());
+    assert_eq!(4, states.len());
 
     // state 0 - we have both notifs.
     {
@@ -0,0 +1,210 @@
+use std::any::Any;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use chrono::{TimeZone, Utc};
+use futures::future;
+use serde::Deserialize;
+use stremio_derive::Model;
+
+use crate::constants::{OUTBOX_RETRY_TIMER_ATTEMPTS, OUTBOX_STORAGE_KEY};
+use crate::models::ctx::{Ctx, CtxBuckets};
+use crate::runtime::msg::{Action, ActionCtx};
+use crate::runtime::{Env, EnvError, EnvFutureExt, Runtime, RuntimeAction, TryEnvFuture};
+use crate::types::addon_health::AddonHealthBucket;
+use crate::types::api::{APIResult, SuccessResponse};
+use crate::types::events::DismissedEventsBucket;
+use crate::types::library::{LibraryBucket, LibraryItem, LibrarySyncBucket};
+use crate::types::lists::ListsBucket;
+use crate::types::local_profiles::LocalProfilesBucket;
+use crate::types::notifications::NotificationsBucket;
+use crate::types::outbox::{OutboxBucket, OutboxItem, OutboxRequest};
+use crate::types::profile::{Auth, AuthKey, Profile, User};
+use crate::types::resource::MetaItemPreview;
+use crate::types::search_history::SearchHistoryBucket;
+use crate::types::server_urls::ServerUrlsBucket;
+use crate::types::streams::StreamsBucket;
+use crate::types::taste::TasteBucket;
+use crate::types::True;
+use crate::unit_tests::{
+    default_fetch_handler, Request, TestEnv, FETCH_HANDLER, NOW, REQUESTS, STORAGE,
+};
+
+#[test]
+fn actionctx_flushoutbox() {
+    #[derive(Model, Clone, Default)]
+    #[model(TestEnv)]
+    struct TestModel {
+        ctx: Ctx,
+    }
+    static ONLINE: AtomicBool = AtomicBool::new(false);
+    fn fetch_handler(request: Request) -> TryEnvFuture<Box<dyn Any + Send>> {
+        match &request {
+            Request { url, method, .. }
+                if url == "https://api.strem.io/api/datastorePut" && method == "POST" =>
+            {
+                if ONLINE.load(Ordering::SeqCst) {
+                    future::ok(
+                        Box::new(APIResult::Ok(SuccessResponse { success: True {} }))
+                            as Box<dyn Any + Send>,
+                    )
+                    .boxed_env()
+                } else {
+                    future::err(EnvError::Fetch("Network unreachable".to_owned())).boxed_env()
+                }
+            }
+            _ => default_fetch_handler(request),
+        }
+    }
+    let meta_preview = |id: &str| MetaItemPreview {
+        id: id.to_owned(),
+        r#type: "movie".to_owned(),
+        name: "name".to_owned(),
+        poster: None,
+        background: None,
+        logo: None,
+        description: None,
+        release_info: None,
+        runtime: None,
+        released: None,
+        poster_shape: Default::default(),
+        links: vec![],
+        trailer_streams: vec![],
+        behavior_hints: Default::default(),
+        certification: None,
+    };
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    ONLINE.store(false, Ordering::SeqCst);
+    *FETCH_HANDLER.write().unwrap() = Box::new(fetch_handler);
+    let now = Utc.with_ymd_and_hms(2020, 1, 1, 0, 0, 0).unwrap();
+    *NOW.write().unwrap() = now;
+    let (runtime, _rx) = Runtime::<TestEnv, _>::new(
+        TestModel {
+            ctx: Ctx::new(
+                Profile {
+                    auth: Some(Auth {
+                        key: AuthKey("auth_key".to_owned()),
+                        user: User {
+                            id: "user_id".into(),
+                            ..Default::default()
+                        },
+                    }),
+                    ..Default::default()
+                },
+                CtxBuckets {
+                    library: LibraryBucket::new(Some("user_id".into()), vec![]),
+                    streams: StreamsBucket::default(),
+                    streaming_server_urls: ServerUrlsBucket::new::<TestEnv>(None),
+                    notifications: NotificationsBucket::new::<TestEnv>(None, vec![]),
+                    search_history: SearchHistoryBucket::default(),
+                    dismissed_events: DismissedEventsBucket::default(),
+                    library_sync: LibrarySyncBucket::new(Some("user_id".into()), vec![]),
+                    outbox: OutboxBucket::new(Some("user_id".into())),
+                    local_profiles: LocalProfilesBucket::default(),
+                    addon_health: AddonHealthBucket::default(),
+                    taste: TasteBucket::default(),
+                    lists: ListsBucket::default(),
+                },
+            ),
+        },
+        vec![],
+        1000,
+    );
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Ctx(ActionCtx::AddToLibrary(meta_preview("tt1"))),
+        })
+    });
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Ctx(ActionCtx::AddToLibrary(meta_preview("tt2"))),
+        })
+    });
+    let library_items = |ids: &[&str]| {
+        let library = &runtime.model().unwrap().ctx.library;
+        ids.iter()
+            .map(|id| library.items.get(*id).unwrap().to_owned())
+            .collect::<Vec<_>>()
+    };
+    let item = runtime.model().unwrap().ctx.outbox.items[0].to_owned();
+    assert_eq!(
+        runtime.model().unwrap().ctx.outbox.items,
+        vec![OutboxItem {
+            request: OutboxRequest::LibraryItems {
+                auth_key: AuthKey("auth_key".to_owned()),
+                items: library_items(&["tt1", "tt2"]),
+            },
+            attempts: OUTBOX_RETRY_TIMER_ATTEMPTS + 1,
+            retry_at: item.retry_at,
+            in_flight: false,
+        }],
+        "Both offline changes are coalesced into a single request"
+    );
+    assert!(item.retry_at > TestEnv::now());
+    assert_eq!(
+        STORAGE
+            .read()
+            .unwrap()
+            .get(OUTBOX_STORAGE_KEY)
+            .map(|data| serde_json::from_str::<OutboxBucket>(data).unwrap()),
+        Some(runtime.model().unwrap().ctx.outbox.to_owned()),
+        "Outbox updated successfully in storage"
+    );
+    assert_eq!(
+        REQUESTS.read().unwrap().len(),
+        OUTBOX_RETRY_TIMER_ATTEMPTS as usize + 1,
+        "The failed request has been retried on a timer, \
+        the second change is not pushed before the backoff has elapsed"
+    );
+
+    ONLINE.store(true, Ordering::SeqCst);
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Ctx(ActionCtx::FlushOutbox),
+        })
+    });
+    assert_eq!(
+        REQUESTS.read().unwrap().len(),
+        OUTBOX_RETRY_TIMER_ATTEMPTS as usize + 1,
+        "The request is not flushed before it's due"
+    );
+
+    *NOW.write().unwrap() = item.retry_at;
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Ctx(ActionCtx::FlushOutbox),
+        })
+    });
+    assert!(
+        runtime.model().unwrap().ctx.outbox.items.is_empty(),
+        "Outbox is empty"
+    );
+    #[derive(Deserialize)]
+    struct Body {
+        changes: Vec<LibraryItem>,
+    }
+    assert_eq!(
+        REQUESTS.read().unwrap().len(),
+        OUTBOX_RETRY_TIMER_ATTEMPTS as usize + 2,
+        "One request has been replayed"
+    );
+    assert_eq!(
+        serde_json::from_str::<Body>(&REQUESTS.read().unwrap().last().unwrap().body)
+            .unwrap()
+            .changes,
+        library_items(&["tt1", "tt2"]),
+        "Both changes have been pushed"
+    );
+    assert_eq!(
+        STORAGE
+            .read()
+            .unwrap()
+            .get(OUTBOX_STORAGE_KEY)
+            .map(|data| serde_json::from_str::<OutboxBucket>(data).unwrap()),
+        Some(OutboxBucket::new(Some("user_id".into()))),
+        "Outbox updated successfully in storage"
+    );
+}
@@ -0,0 +1,256 @@
+use crate::constants::{
+    GENRES_LINK_CATEGORY, META_RESOURCE_NAME, PARENTAL_CONTROLS_PIN_ATTEMPTS,
+    PARENTAL_CONTROLS_PIN_BASE_DELAY, PROFILE_STORAGE_KEY,
+};
+use crate::models::catalog_with_filters::{CatalogWithFilters, Selected};
+use crate::models::common::Loadable;
+use crate::models::ctx::Ctx;
+use crate::models::player::{Player, Selected as PlayerSelected};
+use crate::runtime::msg::{Action, ActionCtx, ActionLoad};
+use crate::runtime::{EnvFutureExt, Runtime, RuntimeAction, TryEnvFuture};
+use crate::types::addon::{ResourcePath, ResourceRequest, ResourceResponse, ResourceResponseCache};
+use crate::types::profile::{ParentalRestrictions, Profile};
+use crate::types::resource::{Link, MetaItemPreview, Stream, StreamSource};
+use crate::unit_tests::{default_fetch_handler, Request, TestEnv, FETCH_HANDLER, NOW, STORAGE};
+use futures::future;
+use std::any::Any;
+use stremio_derive::Model;
+
+fn meta_item(id: &str, certification: Option<&str>, genre: Option<&str>) -> MetaItemPreview {
+    MetaItemPreview {
+        id: id.to_owned(),
+        r#type: "movie".to_owned(),
+        certification: certification.map(|certification| certification.to_owned()),
+        links: genre
+            .map(|genre| Link {
+                name: genre.to_owned(),
+                category: GENRES_LINK_CATEGORY.to_owned(),
+                url: "stremio:///discover".parse().unwrap(),
+            })
+            .into_iter()
+            .collect(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn actionctx_parental_controls() {
+    #[derive(Model, Default, Clone, Debug)]
+    #[model(TestEnv)]
+    struct TestModel {
+        ctx: Ctx,
+        discover: CatalogWithFilters<MetaItemPreview>,
+        player: Player,
+    }
+    fn fetch_handler(request: Request) -> TryEnvFuture<Box<dyn Any + Send>> {
+        match request {
+            Request { url, .. } if url == "https://v3-cinemeta.strem.io/catalog/movie/top.json" => {
+                future::ok(
+                    Box::new(ResourceResponseCache::from(ResourceResponse::Metas {
+                        metas: vec![
+                            meta_item("tt1", Some("PG"), None),
+                            meta_item("tt2", Some("R"), None),
+                            meta_item("tt3", Some("FSK 12"), Some("Horror")),
+                            meta_item("tt4", None, None),
+                        ],
+                    })) as Box<dyn Any + Send>,
+                )
+                .boxed_env()
+            }
+            _ => default_fetch_handler(request),
+        }
+    }
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    *FETCH_HANDLER.write().unwrap() = Box::new(fetch_handler);
+    let (runtime, _rx) = Runtime::<TestEnv, _>::new(
+        TestModel {
+            ctx: Ctx {
+                profile: Profile::default(),
+                ..Default::default()
+            },
+            discover: Default::default(),
+            player: Default::default(),
+        },
+        vec![],
+        1000,
+    );
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Ctx(ActionCtx::SetParentalControls {
+                pin: "1234".to_owned(),
+                restrictions: ParentalRestrictions {
+                    max_age: Some(12),
+                    blocked_genres: vec!["horror".to_owned()],
+                    blocked_types: vec!["series".to_owned()],
+                },
+            }),
+        })
+    });
+    let parental_controls = runtime
+        .model()
+        .unwrap()
+        .ctx
+        .profile
+        .parental_controls
+        .to_owned();
+    assert!(
+        parental_controls.is_active(),
+        "Parental controls are locked once set"
+    );
+    assert!(
+        serde_json::to_value(&parental_controls)
+            .unwrap()
+            .get("pinHash")
+            .is_none(),
+        "PIN hash is not serialized for the UI"
+    );
+    assert!(
+        STORAGE
+            .read()
+            .unwrap()
+            .get(PROFILE_STORAGE_KEY)
+            .is_some_and(|data| {
+                serde_json::from_str::<Profile>(data)
+                    .unwrap()
+                    .parental_controls
+                    == parental_controls
+            }),
+        "Parental controls updated successfully in storage"
+    );
+
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Load(ActionLoad::CatalogWithFilters(Some(Selected {
+                request: ResourceRequest {
+                    base: "https://v3-cinemeta.strem.io/manifest.json"
+                        .parse()
+                        .unwrap(),
+                    path: ResourcePath::without_extra("catalog", "movie", "top"),
+                },
+            }))),
+        })
+    });
+    let catalog_ids = || {
+        let model = runtime.model().unwrap();
+        match &model.discover.catalog.first().unwrap().content {
+            Some(Loadable::Ready(meta_items)) => meta_items
+                .iter()
+                .map(|meta_item| meta_item.id.to_owned())
+                .collect::<Vec<_>>(),
+            content => panic!("Unexpected catalog content: {content:?}"),
+        }
+    };
+    assert_eq!(
+        catalog_ids(),
+        vec!["tt1".to_owned(), "tt4".to_owned()],
+        "Items for older audience and blocked genres are filtered out of the catalog"
+    );
+    assert!(
+        matches!(
+            &runtime.model().unwrap().discover.pages.first().unwrap().content,
+            Some(Loadable::Ready(meta_items)) if meta_items.len() == 4
+        ),
+        "The loaded page is kept intact for the pagination"
+    );
+
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Load(ActionLoad::Player(Box::new(PlayerSelected {
+                stream: Stream {
+                    source: StreamSource::Url {
+                        url: "https://example.com/video.mp4".parse().unwrap(),
+                    },
+                    name: None,
+                    description: None,
+                    thumbnail: None,
+                    subtitles: vec![],
+                    behavior_hints: Default::default(),
+                },
+                stream_request: None,
+                meta_request: Some(ResourceRequest {
+                    base: "https://v3-cinemeta.strem.io/manifest.json"
+                        .parse()
+                        .unwrap(),
+                    path: ResourcePath::without_extra(META_RESOURCE_NAME, "series", "tt5"),
+                }),
+                subtitles_path: None,
+            }))),
+        })
+    });
+    assert!(
+        runtime.model().unwrap().player.selected.is_none(),
+        "Player is not loaded for blocked types"
+    );
+
+    let unlock = |pin: &str| {
+        TestEnv::run(|| {
+            runtime.dispatch(RuntimeAction {
+                field: None,
+                action: Action::Ctx(ActionCtx::UnlockParentalControls {
+                    pin: pin.to_owned(),
+                }),
+            })
+        })
+    };
+    let parental_controls = || {
+        runtime
+            .model()
+            .unwrap()
+            .ctx
+            .profile
+            .parental_controls
+            .to_owned()
+    };
+    for _ in 0..PARENTAL_CONTROLS_PIN_ATTEMPTS {
+        unlock("0000");
+    }
+    assert!(
+        parental_controls().locked,
+        "Parental controls are not unlocked with an invalid PIN"
+    );
+    assert_eq!(
+        parental_controls().pin_retry_at,
+        None,
+        "PIN attempts are not delayed before the allowed failed attempts are exceeded"
+    );
+
+    unlock("0000");
+    let pin_retry_at = parental_controls()
+        .pin_retry_at
+        .expect("PIN attempts should be delayed");
+    assert_eq!(
+        pin_retry_at,
+        *NOW.read().unwrap() + PARENTAL_CONTROLS_PIN_BASE_DELAY,
+        "PIN attempts are delayed after too many failed attempts"
+    );
+    unlock("1234");
+    assert!(
+        parental_controls().locked,
+        "Parental controls are not unlocked while the PIN attempts are delayed"
+    );
+
+    *NOW.write().unwrap() = pin_retry_at;
+    unlock("1234");
+    assert!(
+        !parental_controls().locked,
+        "Parental controls are unlocked with the PIN"
+    );
+    assert_eq!(
+        parental_controls().failed_pin_attempts,
+        0,
+        "Failed PIN attempts are reset"
+    );
+    assert_eq!(
+        catalog_ids(),
+        vec![
+            "tt1".to_owned(),
+            "tt2".to_owned(),
+            "tt3".to_owned(),
+            "tt4".to_owned()
+        ],
+        "Blocked items are shown again once unlocked"
+    );
+}
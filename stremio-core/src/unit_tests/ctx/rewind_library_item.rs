@@ -1,15 +1,20 @@
 use crate::constants::{LIBRARY_RECENT_STORAGE_KEY, LIBRARY_STORAGE_KEY};
-use crate::models::ctx::Ctx;
+use crate::models::ctx::{Ctx, CtxBuckets};
 use crate::runtime::msg::{Action, ActionCtx};
 use crate::runtime::{Env, EnvFutureExt, Runtime, RuntimeAction, TryEnvFuture};
+use crate::types::addon_health::AddonHealthBucket;
 use crate::types::api::{APIResult, SuccessResponse};
 use crate::types::events::DismissedEventsBucket;
-use crate::types::library::{LibraryBucket, LibraryItem, LibraryItemState};
+use crate::types::library::{LibraryBucket, LibraryItem, LibraryItemState, LibrarySyncBucket};
+use crate::types::lists::ListsBucket;
+use crate::types::local_profiles::LocalProfilesBucket;
 use crate::types::notifications::NotificationsBucket;
+use crate::types::outbox::OutboxBucket;
 use crate::types::profile::{Auth, AuthKey, GDPRConsent, Profile, User};
 use crate::types::search_history::SearchHistoryBucket;
 use crate::types::server_urls::ServerUrlsBucket;
 use crate::types::streams::StreamsBucket;
+use crate::types::taste::TasteBucket;
 use crate::types::True;
 use crate::unit_tests::{
     default_fetch_handler, Request, TestEnv, FETCH_HANDLER, NOW, REQUESTS, STORAGE,
@@ -105,17 +110,25 @@ fn actionctx_rewindlibraryitem() {
                     }),
                     ..Default::default()
                 },
-                LibraryBucket {
-                    uid: Some("id".into()),
-                    items: vec![("id".to_owned(), library_item.to_owned())]
-                        .into_iter()
-                        .collect(),
+                CtxBuckets {
+                    library: LibraryBucket {
+                        uid: Some("id".into()),
+                        items: vec![("id".to_owned(), library_item.to_owned())]
+                            .into_iter()
+                            .collect(),
+                    },
+                    streams: StreamsBucket::default(),
+                    streaming_server_urls: ServerUrlsBucket::new::<TestEnv>(None),
+                    notifications: NotificationsBucket::new::<TestEnv>(None, vec![]),
+                    search_history: SearchHistoryBucket::default(),
+                    dismissed_events: DismissedEventsBucket::default(),
+                    library_sync: LibrarySyncBucket::default(),
+                    outbox: OutboxBucket::default(),
+                    local_profiles: LocalProfilesBucket::default(),
+                    addon_health: AddonHealthBucket::default(),
+                    taste: TasteBucket::default(),
+                    lists: ListsBucket::default(),
                 },
-                StreamsBucket::default(),
-                ServerUrlsBucket::new::<TestEnv>(None),
-                NotificationsBucket::new::<TestEnv>(None, vec![]),
-                SearchHistoryBucket::default(),
-                DismissedEventsBucket::default(),
             ),
         },
         vec![],
@@ -197,17 +210,25 @@ fn actionctx_rewindlibraryitem_not_added() {
         TestModel {
             ctx: Ctx::new(
                 Profile::default(),
-                LibraryBucket {
-                    uid: None,
-                    items: vec![("id".to_owned(), library_item.to_owned())]
-                        .into_iter()
-                        .collect(),
+                CtxBuckets {
+                    library: LibraryBucket {
+                        uid: None,
+                        items: vec![("id".to_owned(), library_item.to_owned())]
+                            .into_iter()
+                            .collect(),
+                    },
+                    streams: StreamsBucket::default(),
+                    streaming_server_urls: ServerUrlsBucket::new::<TestEnv>(None),
+                    notifications: NotificationsBucket::new::<TestEnv>(None, vec![]),
+                    search_history: SearchHistoryBucket::default(),
+                    dismissed_events: DismissedEventsBucket::default(),
+                    library_sync: LibrarySyncBucket::default(),
+                    outbox: OutboxBucket::default(),
+                    local_profiles: LocalProfilesBucket::default(),
+                    addon_health: AddonHealthBucket::default(),
+                    taste: TasteBucket::default(),
+                    lists: ListsBucket::default(),
                 },
-                StreamsBucket::default(),
-                ServerUrlsBucket::new::<TestEnv>(None),
-                NotificationsBucket::new::<TestEnv>(None, vec![]),
-                SearchHistoryBucket::default(),
-                DismissedEventsBucket::default(),
             ),
         },
         vec![],
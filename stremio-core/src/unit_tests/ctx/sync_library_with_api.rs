@@ -1,20 +1,28 @@
 use std::any::Any;
 
-use crate::constants::LIBRARY_RECENT_STORAGE_KEY;
-use crate::models::ctx::Ctx;
+use crate::constants::{LIBRARY_RECENT_STORAGE_KEY, LIBRARY_SYNC_STORAGE_KEY};
+use crate::models::ctx::{Ctx, CtxBuckets};
 use crate::runtime::msg::{Action, ActionCtx};
 use crate::runtime::{Env, EnvFutureExt, Runtime, RuntimeAction, TryEnvFuture};
+use crate::types::addon_health::AddonHealthBucket;
 use crate::types::api::{APIResult, LibraryItemModified, LibraryItemsResponse, SuccessResponse};
 use crate::types::events::DismissedEventsBucket;
-use crate::types::library::{LibraryBucket, LibraryItem};
+use crate::types::library::{
+    LibraryBucket, LibraryItem, LibraryItemConflict, LibraryItemState, LibrarySyncBucket,
+    LibrarySyncConflict,
+};
+use crate::types::lists::ListsBucket;
+use crate::types::local_profiles::LocalProfilesBucket;
 use crate::types::notifications::NotificationsBucket;
+use crate::types::outbox::OutboxBucket;
 use crate::types::profile::{Auth, AuthKey, GDPRConsent, Profile, User};
 use crate::types::search_history::SearchHistoryBucket;
 use crate::types::server_urls::ServerUrlsBucket;
 use crate::types::streams::StreamsBucket;
+use crate::types::taste::TasteBucket;
 use crate::types::True;
 use crate::unit_tests::{
-    default_fetch_handler, Request, TestEnv, FETCH_HANDLER, REQUESTS, STORAGE,
+    default_fetch_handler, Request, TestEnv, FETCH_HANDLER, NOW, REQUESTS, STORAGE,
 };
 
 use chrono::prelude::TimeZone;
@@ -34,12 +42,20 @@ fn actionctx_synclibrarywithapi() {
     let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
     let ctx = Ctx::new(
         Profile::default(),
-        LibraryBucket::default(),
-        StreamsBucket::default(),
-        ServerUrlsBucket::new::<TestEnv>(None),
-        NotificationsBucket::new::<TestEnv>(None, vec![]),
-        SearchHistoryBucket::default(),
-        DismissedEventsBucket::default(),
+        CtxBuckets {
+            library: LibraryBucket::default(),
+            streams: StreamsBucket::default(),
+            streaming_server_urls: ServerUrlsBucket::new::<TestEnv>(None),
+            notifications: NotificationsBucket::new::<TestEnv>(None, vec![]),
+            search_history: SearchHistoryBucket::default(),
+            dismissed_events: DismissedEventsBucket::default(),
+            library_sync: LibrarySyncBucket::default(),
+            outbox: OutboxBucket::default(),
+            local_profiles: LocalProfilesBucket::default(),
+            addon_health: AddonHealthBucket::default(),
+            taste: TasteBucket::default(),
+            lists: ListsBucket::default(),
+        },
     );
     let (runtime, _rx) = Runtime::<TestEnv, _>::new(TestModel { ctx }, vec![], 1000);
     TestEnv::run(|| {
@@ -266,39 +282,47 @@ fn actionctx_synclibrarywithapi_with_user() {
                     }),
                     ..Default::default()
                 },
-                LibraryBucket {
-                    uid: Some("user_id".into()),
-                    items: vec![
-                        (LOCAL_ONLY_ITEM.id.to_owned(), LOCAL_ONLY_ITEM.to_owned()),
-                        (LOCAL_NEWER_ITEM.id.to_owned(), LOCAL_NEWER_ITEM.to_owned()),
-                        (
-                            REMOTE_NEWER_ITEM.id.to_owned(),
-                            LibraryItem {
-                                mtime: REMOTE_NEWER_ITEM.mtime - Duration::days(1),
-                                ..REMOTE_NEWER_ITEM.to_owned()
-                            },
-                        ),
-                        (
-                            LOCAL_OLD_REMOVED_ITEM.id.to_owned(),
-                            LOCAL_OLD_REMOVED_ITEM.to_owned(),
-                        ),
-                        (
-                            LOCAL_NEW_REMOVED_ITEM.id.to_owned(),
-                            LOCAL_NEW_REMOVED_ITEM.to_owned(),
-                        ),
-                        (
-                            LOCAL_OTHER_TYPE_ITEM.id.to_owned(),
-                            LOCAL_OTHER_TYPE_ITEM.to_owned(),
-                        ),
-                    ]
-                    .into_iter()
-                    .collect(),
+                CtxBuckets {
+                    library: LibraryBucket {
+                        uid: Some("user_id".into()),
+                        items: vec![
+                            (LOCAL_ONLY_ITEM.id.to_owned(), LOCAL_ONLY_ITEM.to_owned()),
+                            (LOCAL_NEWER_ITEM.id.to_owned(), LOCAL_NEWER_ITEM.to_owned()),
+                            (
+                                REMOTE_NEWER_ITEM.id.to_owned(),
+                                LibraryItem {
+                                    mtime: REMOTE_NEWER_ITEM.mtime - Duration::days(1),
+                                    ..REMOTE_NEWER_ITEM.to_owned()
+                                },
+                            ),
+                            (
+                                LOCAL_OLD_REMOVED_ITEM.id.to_owned(),
+                                LOCAL_OLD_REMOVED_ITEM.to_owned(),
+                            ),
+                            (
+                                LOCAL_NEW_REMOVED_ITEM.id.to_owned(),
+                                LOCAL_NEW_REMOVED_ITEM.to_owned(),
+                            ),
+                            (
+                                LOCAL_OTHER_TYPE_ITEM.id.to_owned(),
+                                LOCAL_OTHER_TYPE_ITEM.to_owned(),
+                            ),
+                        ]
+                        .into_iter()
+                        .collect(),
+                    },
+                    streams: StreamsBucket::default(),
+                    streaming_server_urls: ServerUrlsBucket::new::<TestEnv>(None),
+                    notifications: NotificationsBucket::new::<TestEnv>(None, vec![]),
+                    search_history: SearchHistoryBucket::default(),
+                    dismissed_events: DismissedEventsBucket::default(),
+                    library_sync: LibrarySyncBucket::default(),
+                    outbox: OutboxBucket::default(),
+                    local_profiles: LocalProfilesBucket::default(),
+                    addon_health: AddonHealthBucket::default(),
+                    taste: TasteBucket::default(),
+                    lists: ListsBucket::default(),
                 },
-                StreamsBucket::default(),
-                ServerUrlsBucket::new::<TestEnv>(None),
-                NotificationsBucket::new::<TestEnv>(None, vec![]),
-                SearchHistoryBucket::default(),
-                DismissedEventsBucket::default(),
             ),
         },
         vec![],
@@ -372,13 +396,13 @@ fn actionctx_synclibrarywithapi_with_user() {
     );
     assert_eq!(
         REQUESTS.read().unwrap().get(1).unwrap().url,
-        "https://api.strem.io/api/datastorePut".to_owned(),
-        "datastorePut request has been sent"
+        "https://api.strem.io/api/datastoreGet".to_owned(),
+        "datastoreGet request has been sent"
     );
     assert_eq!(
         REQUESTS.read().unwrap().get(2).unwrap().url,
-        "https://api.strem.io/api/datastoreGet".to_owned(),
-        "datastoreGet request has been sent"
+        "https://api.strem.io/api/datastorePut".to_owned(),
+        "datastorePut request has been sent through the outbox"
     );
 }
 
@@ -433,12 +457,20 @@ fn actionctx_synclibrarywithapi_with_user_empty_library() {
                     }),
                     ..Default::default()
                 },
-                LibraryBucket::default(),
-                StreamsBucket::default(),
-                ServerUrlsBucket::new::<TestEnv>(None),
-                NotificationsBucket::new::<TestEnv>(None, vec![]),
-                SearchHistoryBucket::default(),
-                DismissedEventsBucket::default(),
+                CtxBuckets {
+                    library: LibraryBucket::default(),
+                    streams: StreamsBucket::default(),
+                    streaming_server_urls: ServerUrlsBucket::new::<TestEnv>(None),
+                    notifications: NotificationsBucket::new::<TestEnv>(None, vec![]),
+                    search_history: SearchHistoryBucket::default(),
+                    dismissed_events: DismissedEventsBucket::default(),
+                    library_sync: LibrarySyncBucket::default(),
+                    outbox: OutboxBucket::default(),
+                    local_profiles: LocalProfilesBucket::default(),
+                    addon_health: AddonHealthBucket::default(),
+                    taste: TasteBucket::default(),
+                    lists: ListsBucket::default(),
+                },
             ),
         },
         vec![],
@@ -461,3 +493,205 @@ fn actionctx_synclibrarywithapi_with_user_empty_library() {
         "datastoreMeta request has been sent"
     );
 }
+
+#[test]
+fn actionctx_synclibrarywithapi_with_user_merge_conflict() {
+    #[derive(Model, Clone, Default)]
+    #[model(TestEnv)]
+    struct TestModel {
+        ctx: Ctx,
+    }
+    // last synced state of the item
+    static BASE_ITEM: Lazy<LibraryItem> = Lazy::new(|| LibraryItem {
+        id: "id".to_owned(),
+        r#type: "series".to_owned(),
+        name: "name".to_owned(),
+        poster: None,
+        poster_shape: Default::default(),
+        removed: false,
+        temp: false,
+        ctime: Some(Utc.with_ymd_and_hms(2020, 1, 1, 0, 0, 0).unwrap()),
+        mtime: Utc.with_ymd_and_hms(2020, 1, 1, 0, 0, 0).unwrap(),
+        state: LibraryItemState {
+            last_watched: Some(Utc.with_ymd_and_hms(2020, 1, 1, 0, 0, 0).unwrap()),
+            time_offset: 10,
+            time_watched: 10,
+            overall_time_watched: 10,
+            video_id: Some("id:1:1".to_owned()),
+            ..Default::default()
+        },
+        behavior_hints: Default::default(),
+    });
+    // removed on this device
+    static LOCAL_ITEM: Lazy<LibraryItem> = Lazy::new(|| LibraryItem {
+        removed: true,
+        mtime: Utc.with_ymd_and_hms(2020, 1, 3, 0, 0, 0).unwrap(),
+        ..BASE_ITEM.to_owned()
+    });
+    // watched on another device
+    static REMOTE_ITEM: Lazy<LibraryItem> = Lazy::new(|| LibraryItem {
+        mtime: Utc.with_ymd_and_hms(2020, 1, 2, 0, 0, 0).unwrap(),
+        state: LibraryItemState {
+            last_watched: Some(Utc.with_ymd_and_hms(2020, 1, 2, 0, 0, 0).unwrap()),
+            time_offset: 500,
+            time_watched: 500,
+            overall_time_watched: 500,
+            times_watched: 1,
+            video_id: Some("id:1:2".to_owned()),
+            ..BASE_ITEM.state.to_owned()
+        },
+        ..BASE_ITEM.to_owned()
+    });
+    static MERGED_ITEM: Lazy<LibraryItem> = Lazy::new(|| LibraryItem {
+        removed: true,
+        mtime: Utc.with_ymd_and_hms(2020, 1, 4, 0, 0, 0).unwrap(),
+        ..REMOTE_ITEM.to_owned()
+    });
+    fn fetch_handler(request: Request) -> TryEnvFuture<Box<dyn Any + Send>> {
+        match &request {
+            Request { url, method, .. }
+                if url == "https://api.strem.io/api/datastoreMeta" && method == "POST" =>
+            {
+                future::ok(Box::new(APIResult::Ok(vec![LibraryItemModified(
+                    REMOTE_ITEM.id.to_owned(),
+                    REMOTE_ITEM.mtime.to_owned(),
+                )])) as Box<dyn Any + Send>)
+                .boxed_env()
+            }
+            Request { url, method, .. }
+                if url == "https://api.strem.io/api/datastoreGet" && method == "POST" =>
+            {
+                future::ok(Box::new(APIResult::Ok(LibraryItemsResponse(vec![
+                    REMOTE_ITEM.to_owned()
+                ]))) as Box<dyn Any + Send>)
+                .boxed_env()
+            }
+            Request {
+                url, method, body, ..
+            } if url == "https://api.strem.io/api/datastorePut" && method == "POST" => {
+                #[derive(Deserialize)]
+                struct Body {
+                    changes: Vec<LibraryItem>,
+                }
+                match serde_json::from_str::<Body>(body) {
+                    Result::Ok(body) if body.changes == vec![MERGED_ITEM.to_owned()] => future::ok(
+                        Box::new(APIResult::Ok(SuccessResponse { success: True {} }))
+                            as Box<dyn Any + Send>,
+                    )
+                    .boxed_env(),
+                    _ => default_fetch_handler(request),
+                }
+            }
+            _ => default_fetch_handler(request),
+        }
+    }
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    *FETCH_HANDLER.write().unwrap() = Box::new(fetch_handler);
+    *NOW.write().unwrap() = MERGED_ITEM.mtime;
+    let (runtime, _rx) = Runtime::<TestEnv, _>::new(
+        TestModel {
+            ctx: Ctx::new(
+                Profile {
+                    auth: Some(Auth {
+                        key: AuthKey("auth_key".to_owned()),
+                        user: User {
+                            id: "user_id".into(),
+                            ..Default::default()
+                        },
+                    }),
+                    ..Default::default()
+                },
+                CtxBuckets {
+                    library: LibraryBucket::new(
+                        Some("user_id".into()),
+                        vec![LOCAL_ITEM.to_owned()],
+                    ),
+                    streams: StreamsBucket::default(),
+                    streaming_server_urls: ServerUrlsBucket::new::<TestEnv>(None),
+                    notifications: NotificationsBucket::new::<TestEnv>(None, vec![]),
+                    search_history: SearchHistoryBucket::default(),
+                    dismissed_events: DismissedEventsBucket::default(),
+                    library_sync: LibrarySyncBucket::new(
+                        Some("user_id".into()),
+                        vec![BASE_ITEM.to_owned()],
+                    ),
+                    outbox: OutboxBucket::default(),
+                    local_profiles: LocalProfilesBucket::default(),
+                    addon_health: AddonHealthBucket::default(),
+                    taste: TasteBucket::default(),
+                    lists: ListsBucket::default(),
+                },
+            ),
+        },
+        vec![],
+        1000,
+    );
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Ctx(ActionCtx::SyncLibraryWithAPI),
+        })
+    });
+    let ctx = runtime.model().unwrap().ctx.to_owned();
+    assert_eq!(
+        ctx.library.items.get("id"),
+        Some(&*MERGED_ITEM),
+        "Removal and the watch progress are both kept"
+    );
+    assert_eq!(
+        ctx.library_sync.bases.get("id"),
+        Some(&*MERGED_ITEM),
+        "Base updated to the pushed item"
+    );
+    assert_eq!(
+        ctx.library_sync.conflicts,
+        vec![LibrarySyncConflict {
+            id: "id".to_owned(),
+            conflict: LibraryItemConflict::RemovedWhileWatched,
+            local: LOCAL_ITEM.to_owned(),
+            remote: REMOTE_ITEM.to_owned(),
+            date: MERGED_ITEM.mtime,
+        }],
+        "Conflict recorded"
+    );
+    assert_eq!(
+        STORAGE
+            .read()
+            .unwrap()
+            .get(LIBRARY_SYNC_STORAGE_KEY)
+            .map(|data| serde_json::from_str::<LibrarySyncBucket>(data).unwrap()),
+        Some(ctx.library_sync.to_owned()),
+        "Library sync bucket updated successfully in storage"
+    );
+    assert_eq!(
+        REQUESTS
+            .read()
+            .unwrap()
+            .iter()
+            .map(|request| request.url.to_owned())
+            .collect::<Vec<_>>(),
+        vec![
+            "https://api.strem.io/api/datastoreMeta".to_owned(),
+            "https://api.strem.io/api/datastoreGet".to_owned(),
+            "https://api.strem.io/api/datastorePut".to_owned(),
+        ],
+        "Item pulled and the merged item pushed"
+    );
+
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Ctx(ActionCtx::DismissLibraryItemConflicts("id".to_owned())),
+        })
+    });
+    assert!(
+        runtime
+            .model()
+            .unwrap()
+            .ctx
+            .library_sync
+            .conflicts
+            .is_empty(),
+        "Conflicts dismissed"
+    );
+}
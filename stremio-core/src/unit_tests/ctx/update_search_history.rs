@@ -1,19 +1,29 @@
 use stremio_derive::Model;
 
+use crate::types::addon_health::AddonHealthBucket;
+use crate::types::lists::ListsBucket;
+use crate::types::local_profiles::LocalProfilesBucket;
+use crate::types::outbox::OutboxBucket;
+use crate::types::taste::TasteBucket;
 use crate::{
     constants::SEARCH_HISTORY_STORAGE_KEY,
     models::{
         catalogs_with_extra::{CatalogsWithExtra, Selected},
-        ctx::Ctx,
+        ctx::{Ctx, CtxBuckets},
     },
     runtime::{
         msg::{Action, ActionCtx, ActionLoad},
         Env, Runtime, RuntimeAction,
     },
     types::{
-        addon::ExtraValue, events::DismissedEventsBucket, library::LibraryBucket,
-        notifications::NotificationsBucket, profile::Profile, search_history::SearchHistoryBucket,
-        server_urls::ServerUrlsBucket, streams::StreamsBucket,
+        addon::ExtraValue,
+        events::DismissedEventsBucket,
+        library::{LibraryBucket, LibrarySyncBucket},
+        notifications::NotificationsBucket,
+        profile::Profile,
+        search_history::SearchHistoryBucket,
+        server_urls::ServerUrlsBucket,
+        streams::StreamsBucket,
     },
     unit_tests::{TestEnv, STORAGE},
 };
@@ -31,12 +41,20 @@ fn test_search_history_update() {
 
     let ctx = Ctx::new(
         Profile::default(),
-        LibraryBucket::default(),
-        StreamsBucket::default(),
-        ServerUrlsBucket::new::<TestEnv>(None),
-        NotificationsBucket::new::<TestEnv>(None, vec![]),
-        SearchHistoryBucket::default(),
-        DismissedEventsBucket::default(),
+        CtxBuckets {
+            library: LibraryBucket::default(),
+            streams: StreamsBucket::default(),
+            streaming_server_urls: ServerUrlsBucket::new::<TestEnv>(None),
+            notifications: NotificationsBucket::new::<TestEnv>(None, vec![]),
+            search_history: SearchHistoryBucket::default(),
+            dismissed_events: DismissedEventsBucket::default(),
+            library_sync: LibrarySyncBucket::default(),
+            outbox: OutboxBucket::default(),
+            local_profiles: LocalProfilesBucket::default(),
+            addon_health: AddonHealthBucket::default(),
+            taste: TasteBucket::default(),
+            lists: ListsBucket::default(),
+        },
     );
 
     let catalogs_with_extra = CatalogsWithExtra::default();
@@ -105,12 +123,20 @@ fn test_search_history_clear_items() {
 
     let ctx = Ctx::new(
         Profile::default(),
-        LibraryBucket::default(),
-        StreamsBucket::default(),
-        ServerUrlsBucket::new::<TestEnv>(None),
-        NotificationsBucket::new::<TestEnv>(None, vec![]),
-        SearchHistoryBucket::default(),
-        DismissedEventsBucket::default(),
+        CtxBuckets {
+            library: LibraryBucket::default(),
+            streams: StreamsBucket::default(),
+            streaming_server_urls: ServerUrlsBucket::new::<TestEnv>(None),
+            notifications: NotificationsBucket::new::<TestEnv>(None, vec![]),
+            search_history: SearchHistoryBucket::default(),
+            dismissed_events: DismissedEventsBucket::default(),
+            library_sync: LibrarySyncBucket::default(),
+            outbox: OutboxBucket::default(),
+            local_profiles: LocalProfilesBucket::default(),
+            addon_health: AddonHealthBucket::default(),
+            taste: TasteBucket::default(),
+            lists: ListsBucket::default(),
+        },
     );
 
     let catalogs_with_extra = CatalogsWithExtra::default();
@@ -1,14 +1,19 @@
 use crate::constants::PROFILE_STORAGE_KEY;
-use crate::models::ctx::Ctx;
+use crate::models::ctx::{Ctx, CtxBuckets};
 use crate::runtime::msg::{Action, ActionCtx};
 use crate::runtime::{Runtime, RuntimeAction};
+use crate::types::addon_health::AddonHealthBucket;
 use crate::types::events::DismissedEventsBucket;
-use crate::types::library::LibraryBucket;
+use crate::types::library::{LibraryBucket, LibrarySyncBucket};
+use crate::types::lists::ListsBucket;
+use crate::types::local_profiles::LocalProfilesBucket;
 use crate::types::notifications::NotificationsBucket;
+use crate::types::outbox::OutboxBucket;
 use crate::types::profile::{Profile, Settings};
 use crate::types::search_history::SearchHistoryBucket;
 use crate::types::server_urls::ServerUrlsBucket;
 use crate::types::streams::StreamsBucket;
+use crate::types::taste::TasteBucket;
 use crate::unit_tests::{TestEnv, REQUESTS, STORAGE};
 use stremio_derive::Model;
 
@@ -27,12 +32,20 @@ fn actionctx_updatesettings() {
     let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
     let ctx = Ctx::new(
         Profile::default(),
-        LibraryBucket::default(),
-        StreamsBucket::default(),
-        ServerUrlsBucket::new::<TestEnv>(None),
-        NotificationsBucket::new::<TestEnv>(None, vec![]),
-        SearchHistoryBucket::default(),
-        DismissedEventsBucket::default(),
+        CtxBuckets {
+            library: LibraryBucket::default(),
+            streams: StreamsBucket::default(),
+            streaming_server_urls: ServerUrlsBucket::new::<TestEnv>(None),
+            notifications: NotificationsBucket::new::<TestEnv>(None, vec![]),
+            search_history: SearchHistoryBucket::default(),
+            dismissed_events: DismissedEventsBucket::default(),
+            library_sync: LibrarySyncBucket::default(),
+            outbox: OutboxBucket::default(),
+            local_profiles: LocalProfilesBucket::default(),
+            addon_health: AddonHealthBucket::default(),
+            taste: TasteBucket::default(),
+            lists: ListsBucket::default(),
+        },
     );
     let (runtime, _rx) = Runtime::<TestEnv, _>::new(TestModel { ctx }, vec![], 1000);
     TestEnv::run(|| {
@@ -87,12 +100,20 @@ fn actionctx_updatesettings_not_changed() {
         TestModel {
             ctx: Ctx::new(
                 profile,
-                LibraryBucket::default(),
-                StreamsBucket::default(),
-                ServerUrlsBucket::new::<TestEnv>(None),
-                NotificationsBucket::new::<TestEnv>(None, vec![]),
-                SearchHistoryBucket::default(),
-                DismissedEventsBucket::default(),
+                CtxBuckets {
+                    library: LibraryBucket::default(),
+                    streams: StreamsBucket::default(),
+                    streaming_server_urls: ServerUrlsBucket::new::<TestEnv>(None),
+                    notifications: NotificationsBucket::new::<TestEnv>(None, vec![]),
+                    search_history: SearchHistoryBucket::default(),
+                    dismissed_events: DismissedEventsBucket::default(),
+                    library_sync: LibrarySyncBucket::default(),
+                    outbox: OutboxBucket::default(),
+                    local_profiles: LocalProfilesBucket::default(),
+                    addon_health: AddonHealthBucket::default(),
+                    taste: TasteBucket::default(),
+                    lists: ListsBucket::default(),
+                },
             ),
         },
         vec![],
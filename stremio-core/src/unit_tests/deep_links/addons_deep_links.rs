@@ -6,7 +6,11 @@ use url::Url;
 
 #[test]
 fn addons_deep_links_installed_addons_request_no_type() {
-    let request = InstalledAddonsRequest { r#type: None };
+    let request = InstalledAddonsRequest {
+        r#type: None,
+        sort: Default::default(),
+        health: None,
+    };
     let adl = AddonsDeepLinks::from(&request);
     assert_eq!(adl.addons, "stremio:///addons".to_string());
 }
@@ -15,6 +19,8 @@ fn addons_deep_links_installed_addons_request_no_type() {
 fn addons_deep_links_installed_addons_request_type() {
     let request = InstalledAddonsRequest {
         r#type: Some("movie".to_string()),
+        sort: Default::default(),
+        health: None,
     };
     let adl = AddonsDeepLinks::from(&request);
     assert_eq!(adl.addons, "stremio:///addons/movie".to_string());
@@ -13,6 +13,7 @@ fn library_deep_links_request_type() {
     let root = "library".to_string();
     let request = LibraryRequest {
         r#type: Some("movie".to_string()),
+        list: None,
         sort: Sort::LastWatched,
         page: Default::default(),
     };
@@ -28,6 +29,7 @@ fn library_deep_links_request_no_type() {
     let root = "library".to_string();
     let request = LibraryRequest {
         r#type: None,
+        list: None,
         sort: Sort::LastWatched,
         page: Default::default(),
     };
@@ -37,3 +39,19 @@ fn library_deep_links_request_no_type() {
         "stremio:///library?sort=lastwatched".to_string()
     );
 }
+
+#[test]
+fn library_deep_links_request_list() {
+    let root = "library".to_string();
+    let request = LibraryRequest {
+        r#type: None,
+        list: Some("1700000000000".to_string()),
+        sort: Sort::LastWatched,
+        page: Default::default(),
+    };
+    let ldl = LibraryDeepLinks::from((&root, &request));
+    assert_eq!(
+        ldl.library,
+        "stremio:///library?sort=lastwatched&list=1700000000000".to_string()
+    );
+}
@@ -21,6 +21,7 @@ fn meta_item_deep_links() {
         links: vec![],
         trailer_streams: vec![],
         behavior_hints: MetaItemBehaviorHints::default(),
+        certification: None,
     };
     let item = MetaItem {
         preview: preview.clone(),
@@ -64,6 +65,7 @@ fn meta_item_deep_links_behavior_hints() {
             has_scheduled_videos: false,
             other: Default::default(),
         },
+        certification: None,
     };
     let item = MetaItem {
         preview: preview.clone(),
@@ -107,6 +109,7 @@ fn meta_item_deep_links_behavior_hints_yt_id() {
             has_scheduled_videos: false,
             other: Default::default(),
         },
+        certification: None,
     };
     let item = MetaItem {
         preview: preview.clone(),
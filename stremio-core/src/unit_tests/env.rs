@@ -12,8 +12,12 @@ use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
 
 use crate::{
+    addon_transport::AddonTransportRegistry,
     models::{ctx::Ctx, streaming_server::StreamingServer},
-    runtime::{Env, EnvFuture, EnvFutureExt, Model, Runtime, RuntimeEvent, TryEnvFuture},
+    runtime::{
+        Env, EnvFuture, EnvFutureExt, Model, ResponseHead, Runtime, RuntimeEvent, TryEnvFuture,
+    },
+    watch_party_transport::{LoopbackRelay, WatchPartyTransport},
 };
 
 pub static FETCH_HANDLER: Lazy<RwLock<FetchHandler>> =
@@ -25,6 +29,11 @@ pub static EVENTS: Lazy<RwLock<Vec<Box<dyn Any + Send + Sync + 'static>>>> =
 pub static STATES: Lazy<RwLock<Vec<Box<dyn Any + Send + Sync + 'static>>>> =
     Lazy::new(Default::default);
 pub static NOW: Lazy<RwLock<DateTime<Utc>>> = Lazy::new(|| RwLock::new(Utc::now()));
+pub static ADDON_TRANSPORTS: Lazy<RwLock<AddonTransportRegistry>> =
+    Lazy::new(|| RwLock::new(AddonTransportRegistry::new::<TestEnv>()));
+pub static WATCH_PARTY_RELAY: Lazy<RwLock<LoopbackRelay>> = Lazy::new(Default::default);
+/// The number of random buffers generated since the last reset
+pub static RANDOM_BUFFERS: Lazy<RwLock<u64>> = Lazy::new(Default::default);
 pub static ENV_MUTEX: Mutex<()> = Mutex::new(());
 
 pub type FetchHandler =
@@ -66,6 +75,9 @@ impl TestEnv {
         *EVENTS.write().unwrap() = vec![];
         *STATES.write().unwrap() = vec![];
         *NOW.write().unwrap() = Utc::now();
+        *ADDON_TRANSPORTS.write().unwrap() = AddonTransportRegistry::new::<TestEnv>();
+        *WATCH_PARTY_RELAY.write().unwrap() = LoopbackRelay::default();
+        *RANDOM_BUFFERS.write().unwrap() = 0;
         env_mutex
     }
     pub fn run<F: FnOnce()>(runnable: F) {
@@ -119,6 +131,12 @@ impl Env for TestEnv {
             })
             .boxed_env()
     }
+    fn fetch_head(request: http::Request<()>) -> TryEnvFuture<ResponseHead> {
+        Self::fetch(request)
+    }
+    fn fetch_bytes(request: http::Request<()>) -> TryEnvFuture<Vec<u8>> {
+        Self::fetch(request)
+    }
     fn get_storage<T: for<'de> Deserialize<'de> + 'static>(key: &str) -> TryEnvFuture<Option<T>> {
         future::ok(
             STORAGE
@@ -146,6 +164,37 @@ impl Env for TestEnv {
     fn now() -> DateTime<Utc> {
         *NOW.read().unwrap()
     }
+    /// The buffers end with the big endian number of the buffer, e.g. the first id
+    /// is `00000000000000000000000000000001`, so the generated ids are predictable.
+    fn random_buffer(len: usize) -> Vec<u8> {
+        let mut random_buffers = RANDOM_BUFFERS.write().unwrap();
+        *random_buffers += 1;
+        let number = random_buffers.to_be_bytes();
+        let number = &number[number.len().saturating_sub(len)..];
+        let mut buffer = vec![0; len - number.len()];
+        buffer.extend_from_slice(number);
+        buffer
+    }
+    fn addon_transports() -> AddonTransportRegistry {
+        ADDON_TRANSPORTS.read().unwrap().to_owned()
+    }
+    /// Timers fire as soon as they are polled, advancing the clock by the duration.
+    fn sleep(duration: chrono::Duration) -> TryEnvFuture<()> {
+        async move {
+            let now = *NOW.read().unwrap() + duration;
+            *NOW.write().unwrap() = now;
+            Ok(())
+        }
+        .boxed_env()
+    }
+    fn watch_party_transport(party_id: &str, member_id: &str) -> Box<dyn WatchPartyTransport> {
+        Box::new(
+            WATCH_PARTY_RELAY
+                .read()
+                .unwrap()
+                .transport(party_id, member_id),
+        )
+    }
     fn flush_analytics() -> EnvFuture<'static, ()> {
         future::ready(()).boxed_env()
     }
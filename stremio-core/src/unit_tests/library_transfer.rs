@@ -0,0 +1,344 @@
+use chrono::{TimeZone, Utc};
+use futures::future;
+use semver::Version;
+use stremio_derive::Model;
+use url::Url;
+
+use crate::addon_transport::AddonBuilder;
+use crate::constants::META_RESOURCE_NAME;
+use crate::models::common::Loadable;
+use crate::models::ctx::Ctx;
+use crate::models::library_transfer::LibraryTransfer;
+use crate::runtime::msg::{Action, ActionLibraryTransfer};
+use crate::runtime::{Env, EnvFutureExt, Runtime, RuntimeAction};
+use crate::types::addon::{Descriptor, Manifest, ResourceResponse};
+use crate::types::library::{LibraryBucket, LibraryItem, LibraryItemState};
+use crate::types::profile::Profile;
+use crate::types::rating::Rating;
+use crate::types::resource::{MetaItem, MetaItemPreview, SeriesInfo, Video};
+use crate::types::taste::{TasteBucket, TasteItem};
+use crate::types::tracker::{TrackerFormat, UnmatchedReason};
+use crate::types::watched_bitfield::WatchedBitField;
+use crate::unit_tests::{TestEnv, ADDON_TRANSPORTS};
+
+const SERIES_ID: &str = "tt0903747";
+const MOVIE_ID: &str = "tt0111161";
+
+#[derive(Model, Default, Clone, Debug)]
+#[model(TestEnv)]
+struct TestModel {
+    ctx: Ctx,
+    library_transfer: LibraryTransfer,
+}
+
+fn manifest() -> Manifest {
+    Manifest {
+        id: "series".to_owned(),
+        version: Version::new(1, 0, 0),
+        name: "series".to_owned(),
+        contact_email: None,
+        description: None,
+        logo: None,
+        background: None,
+        types: vec!["series".to_owned()],
+        resources: vec![META_RESOURCE_NAME.into()],
+        id_prefixes: Some(vec!["tt".to_owned()]),
+        catalogs: vec![],
+        addon_catalogs: vec![],
+        behavior_hints: Default::default(),
+    }
+}
+
+fn videos() -> Vec<Video> {
+    (1..=3)
+        .map(|episode| Video {
+            id: format!("{SERIES_ID}:1:{episode}"),
+            series_info: Some(SeriesInfo { season: 1, episode }),
+            ..Default::default()
+        })
+        .collect()
+}
+
+fn register_addon() {
+    let addon = AddonBuilder::new(manifest())
+        .define_meta_handler(|_| {
+            future::ok(ResourceResponse::Meta {
+                meta: MetaItem {
+                    preview: MetaItemPreview {
+                        id: SERIES_ID.to_owned(),
+                        r#type: "series".to_owned(),
+                        name: "Breaking Bad".to_owned(),
+                        ..Default::default()
+                    },
+                    videos: videos(),
+                },
+            })
+            .boxed_env()
+        })
+        .build()
+        .unwrap();
+    let registry = ADDON_TRANSPORTS.read().unwrap().to_owned();
+    *ADDON_TRANSPORTS.write().unwrap() =
+        registry.register("series", move |_| Box::new(addon.to_owned()));
+}
+
+fn profile() -> Profile {
+    Profile {
+        addons: vec![Descriptor {
+            manifest: manifest(),
+            transport_url: Url::parse("series://addon/manifest.json").unwrap(),
+            flags: Default::default(),
+        }],
+        ..Default::default()
+    }
+}
+
+fn library_item(id: &str, name: &str, r#type: &str, state: LibraryItemState) -> LibraryItem {
+    LibraryItem {
+        id: id.to_owned(),
+        name: name.to_owned(),
+        r#type: r#type.to_owned(),
+        poster: None,
+        poster_shape: Default::default(),
+        removed: false,
+        temp: false,
+        ctime: None,
+        mtime: TestEnv::now(),
+        state,
+        behavior_hints: Default::default(),
+    }
+}
+
+fn watched_at() -> chrono::DateTime<Utc> {
+    Utc.with_ymd_and_hms(2023, 10, 1, 20, 0, 0).unwrap()
+}
+
+fn dispatch(runtime: &Runtime<TestEnv, TestModel>, action: ActionLibraryTransfer) {
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::LibraryTransfer(action),
+        })
+    });
+}
+
+#[test]
+fn library_transfer_export_trakt() {
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    register_addon();
+    let video_ids = videos().into_iter().map(|video| video.id).collect();
+    let watched = WatchedBitField::construct_from_array(vec![true, false, true], video_ids);
+    let (runtime, _rx) = Runtime::<TestEnv, _>::new(
+        TestModel {
+            ctx: Ctx {
+                profile: profile(),
+                library: LibraryBucket::new(
+                    None,
+                    vec![
+                        library_item(
+                            MOVIE_ID,
+                            "The Shawshank Redemption",
+                            "movie",
+                            LibraryItemState {
+                                times_watched: 1,
+                                last_watched: Some(watched_at()),
+                                ..Default::default()
+                            },
+                        ),
+                        library_item(
+                            SERIES_ID,
+                            "Breaking Bad",
+                            "series",
+                            LibraryItemState {
+                                times_watched: 2,
+                                last_watched: Some(watched_at()),
+                                watched: Some(watched.into()),
+                                ..Default::default()
+                            },
+                        ),
+                        library_item("tt0068646", "The Godfather", "movie", Default::default()),
+                    ],
+                ),
+                taste: TasteBucket {
+                    uid: None,
+                    items: [(
+                        MOVIE_ID.to_owned(),
+                        TasteItem {
+                            id: MOVIE_ID.to_owned(),
+                            name: "The Shawshank Redemption".to_owned(),
+                            r#type: "movie".to_owned(),
+                            genres: vec![],
+                            cast: vec![],
+                            watched: true,
+                            rating: Some(Rating::Loved),
+                            mtime: TestEnv::now(),
+                        },
+                    )]
+                    .into(),
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        vec![],
+        1000,
+    );
+    dispatch(
+        &runtime,
+        ActionLibraryTransfer::Export(TrackerFormat::Trakt),
+    );
+    let model = runtime.model().unwrap();
+    let export = match &model.library_transfer.export {
+        Some(Loadable::Ready(export)) => export,
+        export => panic!("Export is not ready: {export:?}"),
+    };
+    let items = serde_json::from_str::<Vec<serde_json::Value>>(export).unwrap();
+    let history = |r#type: &str| {
+        items
+            .iter()
+            .filter(|item| item["type"] == r#type && item.get("watched_at").is_some())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(history("movie").len(), 1);
+    assert_eq!(history("movie")[0]["movie"]["ids"]["imdb"], MOVIE_ID);
+    assert_eq!(
+        history("episode")
+            .iter()
+            .map(|item| (
+                item["show"]["ids"]["imdb"].as_str().unwrap(),
+                item["episode"]["season"].as_u64().unwrap(),
+                item["episode"]["number"].as_u64().unwrap()
+            ))
+            .collect::<Vec<_>>(),
+        vec![(SERIES_ID, 1, 1), (SERIES_ID, 1, 3)],
+        "Watched episodes are taken from the watched bitfield"
+    );
+    assert!(
+        items
+            .iter()
+            .any(|item| item["rating"] == 10 && item["movie"]["ids"]["imdb"] == MOVIE_ID),
+        "Loved movie is rated"
+    );
+    assert!(
+        !export.contains("tt0068646"),
+        "Unwatched items are not exported"
+    );
+}
+
+#[test]
+fn library_transfer_import_imdb() {
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    let (runtime, _rx) = Runtime::<TestEnv, _>::new(TestModel::default(), vec![], 1000);
+    let data = "\u{feff}Const,Your Rating,Date Rated,Title,URL,Title Type,Year\r\n\
+        tt0111161,10,2023-10-01,\"Shawshank Redemption, The\",https://www.imdb.com/title/tt0111161/,Movie,1994\r\n\
+        tt0959621,9,2023-10-02,Pilot,https://www.imdb.com/title/tt0959621/,TV Episode,2008\r\n\
+        ,8,2023-10-03,Unknown,,Movie,2000\r\n";
+    dispatch(
+        &runtime,
+        ActionLibraryTransfer::Import {
+            format: TrackerFormat::Imdb,
+            data: data.to_owned(),
+        },
+    );
+    let model = runtime.model().unwrap();
+    let report = match &model.library_transfer.import {
+        Some(Loadable::Ready(report)) => report,
+        import => panic!("Import is not ready: {import:?}"),
+    };
+    assert_eq!(report.imported, vec![MOVIE_ID.to_owned()]);
+    assert_eq!(
+        report
+            .unmatched
+            .iter()
+            .map(|row| (row.row, row.reason.to_owned()))
+            .collect::<Vec<_>>(),
+        vec![
+            (2, UnmatchedReason::UnsupportedType("TV Episode".to_owned())),
+            (3, UnmatchedReason::MissingImdbId),
+        ]
+    );
+    let library_item = model.ctx.library.items.get(MOVIE_ID).expect("Imported");
+    assert_eq!(library_item.name, "Shawshank Redemption, The");
+    assert!(!library_item.removed && library_item.watched());
+    assert_eq!(
+        library_item.state.last_watched,
+        Some(Utc.with_ymd_and_hms(2023, 10, 1, 0, 0, 0).unwrap())
+    );
+}
+
+#[test]
+fn library_transfer_import_trakt_episodes() {
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    register_addon();
+    let (runtime, _rx) = Runtime::<TestEnv, _>::new(
+        TestModel {
+            ctx: Ctx {
+                profile: profile(),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        vec![],
+        1000,
+    );
+    let data = serde_json::json!([
+        {
+            "last_watched_at": "2023-10-01T20:00:00.000Z",
+            "show": { "title": "Breaking Bad", "year": 2008, "ids": { "imdb": SERIES_ID } },
+            "seasons": [{ "number": 1, "episodes": [{ "number": 2 }, { "number": 3 }] }]
+        },
+        {
+            "watched_at": "2023-10-02T20:00:00.000Z",
+            "type": "episode",
+            "episode": { "season": 2, "number": 1 },
+            "show": { "title": "Breaking Bad", "year": 2008, "ids": { "imdb": SERIES_ID } }
+        },
+        {
+            "watched_at": "2023-10-03T20:00:00.000Z",
+            "type": "movie",
+            "movie": { "title": "Unknown", "ids": { "trakt": 1 } }
+        }
+    ]);
+    dispatch(
+        &runtime,
+        ActionLibraryTransfer::Import {
+            format: TrackerFormat::Trakt,
+            data: data.to_string(),
+        },
+    );
+    let model = runtime.model().unwrap();
+    let report = match &model.library_transfer.import {
+        Some(Loadable::Ready(report)) => report,
+        import => panic!("Import is not ready: {import:?}"),
+    };
+    assert_eq!(report.imported, vec![SERIES_ID.to_owned()]);
+    assert_eq!(
+        report
+            .unmatched
+            .iter()
+            .map(|row| row.reason.to_owned())
+            .collect::<Vec<_>>(),
+        vec![
+            UnmatchedReason::MissingImdbId,
+            UnmatchedReason::UnknownEpisodes(vec![SeriesInfo {
+                season: 2,
+                episode: 1
+            }]),
+        ]
+    );
+    let library_item = model.ctx.library.items.get(SERIES_ID).expect("Imported");
+    let watched = library_item.state.watched_bitfield(&videos());
+    assert_eq!(
+        videos()
+            .iter()
+            .map(|video| watched.get_video(&video.id))
+            .collect::<Vec<_>>(),
+        vec![false, true, true],
+        "Watched episodes are set in the watched bitfield"
+    );
+    assert_eq!(library_item.state.times_watched, 2);
+    assert_eq!(
+        library_item.state.last_watched,
+        Some(Utc.with_ymd_and_hms(2023, 10, 2, 20, 0, 0).unwrap())
+    );
+}
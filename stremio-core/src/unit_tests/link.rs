@@ -1,16 +1,21 @@
 use crate::models::common::Loadable;
-use crate::models::ctx::Ctx;
+use crate::models::ctx::{Ctx, CtxBuckets};
 use crate::models::link::Link;
 use crate::runtime::msg::{Action, ActionLink, ActionLoad};
 use crate::runtime::{EnvFutureExt, Runtime, RuntimeAction, TryEnvFuture};
+use crate::types::addon_health::AddonHealthBucket;
 use crate::types::api::{APIResult, LinkAuthKey, LinkCodeResponse, LinkDataResponse};
 use crate::types::events::DismissedEventsBucket;
-use crate::types::library::LibraryBucket;
+use crate::types::library::{LibraryBucket, LibrarySyncBucket};
+use crate::types::lists::ListsBucket;
+use crate::types::local_profiles::LocalProfilesBucket;
 use crate::types::notifications::NotificationsBucket;
+use crate::types::outbox::OutboxBucket;
 use crate::types::profile::Profile;
 use crate::types::search_history::SearchHistoryBucket;
 use crate::types::server_urls::ServerUrlsBucket;
 use crate::types::streams::StreamsBucket;
+use crate::types::taste::TasteBucket;
 use crate::unit_tests::{default_fetch_handler, Request, TestEnv, FETCH_HANDLER, REQUESTS};
 use futures::future;
 use std::any::Any;
@@ -60,12 +65,20 @@ fn create_link_code() {
     let model = TestModel {
         ctx: Ctx::new(
             Profile::default(),
-            LibraryBucket::default(),
-            StreamsBucket::default(),
-            ServerUrlsBucket::new::<TestEnv>(None),
-            NotificationsBucket::new::<TestEnv>(None, vec![]),
-            SearchHistoryBucket::default(),
-            DismissedEventsBucket::default(),
+            CtxBuckets {
+                library: LibraryBucket::default(),
+                streams: StreamsBucket::default(),
+                streaming_server_urls: ServerUrlsBucket::new::<TestEnv>(None),
+                notifications: NotificationsBucket::new::<TestEnv>(None, vec![]),
+                search_history: SearchHistoryBucket::default(),
+                dismissed_events: DismissedEventsBucket::default(),
+                library_sync: LibrarySyncBucket::default(),
+                outbox: OutboxBucket::default(),
+                local_profiles: LocalProfilesBucket::default(),
+                addon_health: AddonHealthBucket::default(),
+                taste: TasteBucket::default(),
+                lists: ListsBucket::default(),
+            },
         ),
         link: Link::default(),
     };
@@ -0,0 +1,363 @@
+use std::any::Any;
+
+use chrono::{Duration, TimeZone, Utc};
+use futures::future;
+use serde::Deserialize;
+use stremio_derive::Model;
+
+use crate::constants::LISTS_STORAGE_KEY;
+use crate::models::ctx::Ctx;
+use crate::models::library_with_filters::{
+    LibraryRequest, LibraryWithFilters, NotRemovedFilter, Selected as LibraryWithFiltersSelected,
+};
+use crate::runtime::msg::{Action, ActionCtx, ActionLoad};
+use crate::runtime::{Env, EnvFutureExt, Runtime, RuntimeAction, TryEnvFuture};
+use crate::types::api::{APIResult, SuccessResponse};
+use crate::types::lists::{List, ListItem, ListsBucket, SharedList};
+use crate::types::profile::{Auth, AuthKey, Profile};
+use crate::types::resource::MetaItemPreview;
+use crate::types::True;
+use crate::unit_tests::{
+    default_fetch_handler, Request, TestEnv, FETCH_HANDLER, NOW, REQUESTS, STORAGE,
+};
+
+#[derive(Model, Default, Clone, Debug)]
+#[model(TestEnv)]
+struct TestModel {
+    ctx: Ctx,
+    library: LibraryWithFilters<NotRemovedFilter>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DatastoreBody {
+    collection: String,
+}
+
+fn fetch_handler(request: Request) -> TryEnvFuture<Box<dyn Any + Send>> {
+    match &request {
+        Request {
+            url, method, body, ..
+        } if url == "https://api.strem.io/api/datastorePut" && method == "POST" => {
+            match serde_json::from_str::<DatastoreBody>(body) {
+                // the items added to the lists are pushed to the library as well
+                Ok(body) if body.collection == "userList" || body.collection == "libraryItem" => {
+                    future::ok(
+                        Box::new(APIResult::Ok(SuccessResponse { success: True {} }))
+                            as Box<dyn Any + Send>,
+                    )
+                    .boxed_env()
+                }
+                _ => default_fetch_handler(request),
+            }
+        }
+        Request {
+            url, method, body, ..
+        } if url == "https://api.strem.io/api/datastoreGet" && method == "POST" => {
+            match serde_json::from_str::<DatastoreBody>(body) {
+                Ok(body) if body.collection == "userList" => {
+                    future::ok(Box::new(APIResult::Ok(vec![
+                        list("1", "Halloween", 0, remote_mtime()),
+                        list("2", "Watch with kids", 1, remote_mtime()),
+                    ])) as Box<dyn Any + Send>)
+                    .boxed_env()
+                }
+                _ => default_fetch_handler(request),
+            }
+        }
+        _ => default_fetch_handler(request),
+    }
+}
+
+fn remote_mtime() -> chrono::DateTime<Utc> {
+    Utc.with_ymd_and_hms(2023, 10, 1, 0, 0, 0).unwrap()
+}
+
+fn list(id: &str, name: &str, position: usize, mtime: chrono::DateTime<Utc>) -> List {
+    List {
+        id: id.to_owned(),
+        name: name.to_owned(),
+        items: vec![],
+        position,
+        removed: false,
+        ctime: remote_mtime(),
+        mtime,
+    }
+}
+
+fn meta_item(id: &str, name: &str) -> MetaItemPreview {
+    MetaItemPreview {
+        id: id.to_owned(),
+        r#type: "movie".to_owned(),
+        name: name.to_owned(),
+        ..Default::default()
+    }
+}
+
+fn profile() -> Profile {
+    Profile {
+        auth: Some(Auth {
+            key: AuthKey("auth_key".to_owned()),
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+}
+
+fn list_id(runtime: &Runtime<TestEnv, TestModel>, name: &str) -> String {
+    runtime
+        .model()
+        .unwrap()
+        .ctx
+        .lists
+        .items
+        .values()
+        .find(|list| list.name == name)
+        .map(|list| list.id.to_owned())
+        .expect("List was created")
+}
+
+fn load_list(id: &str) -> Action {
+    Action::Load(ActionLoad::LibraryWithFilters(LibraryWithFiltersSelected {
+        request: LibraryRequest {
+            r#type: None,
+            list: Some(id.to_owned()),
+            sort: Default::default(),
+            page: Default::default(),
+        },
+    }))
+}
+
+#[test]
+fn lists_curate_library() {
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    *FETCH_HANDLER.write().unwrap() = Box::new(fetch_handler);
+    let (runtime, _rx) = Runtime::<TestEnv, _>::new(
+        TestModel {
+            ctx: Ctx {
+                profile: profile(),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        vec![],
+        1000,
+    );
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Ctx(ActionCtx::CreateList {
+                name: "Halloween".to_owned(),
+            }),
+        })
+    });
+    let id = list_id(&runtime, "Halloween");
+    assert_eq!(
+        id, "00000000000000000000000000000001",
+        "Id of the list is generated by the env"
+    );
+    for meta_item in [
+        meta_item("tt0077651", "Halloween"),
+        meta_item("tt0117571", "Scream"),
+    ] {
+        TestEnv::run(|| {
+            runtime.dispatch(RuntimeAction {
+                field: None,
+                action: Action::Ctx(ActionCtx::AddToList {
+                    id: id.to_owned(),
+                    meta_item,
+                }),
+            })
+        });
+    }
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Ctx(ActionCtx::MoveListItem {
+                id: id.to_owned(),
+                item_id: "tt0117571".to_owned(),
+                position: 0,
+            }),
+        })
+    });
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: load_list(&id),
+        })
+    });
+    let model = runtime.model().unwrap();
+    let list = model.ctx.lists.items.get(&id).expect("List was created");
+    assert_eq!(list.name, "Halloween");
+    assert_eq!(
+        list.items
+            .iter()
+            .map(|item| item.id.as_str())
+            .collect::<Vec<_>>(),
+        vec!["tt0117571", "tt0077651"],
+        "Items are in the order chosen by the user"
+    );
+    assert!(
+        ["tt0077651", "tt0117571"].iter().all(|id| model
+            .ctx
+            .library
+            .items
+            .get(*id)
+            .is_some_and(|library_item| !library_item.removed)),
+        "Items of the list were added to the library"
+    );
+    assert_eq!(
+        model
+            .library
+            .catalog
+            .iter()
+            .map(|library_item| library_item.id.as_str())
+            .collect::<Vec<_>>(),
+        vec!["tt0117571", "tt0077651"],
+        "Library is filtered by the list, in the order of the list"
+    );
+    assert!(model.library.selectable.sorts.is_empty());
+    assert_eq!(model.library.selectable.lists.len(), 2);
+    assert!(model.library.selectable.lists[1].selected);
+    assert!(
+        model.ctx.outbox.items.is_empty(),
+        "List changes were pushed to the API"
+    );
+    assert_eq!(
+        STORAGE
+            .read()
+            .unwrap()
+            .get(LISTS_STORAGE_KEY)
+            .map(|data| serde_json::from_str::<ListsBucket>(data).unwrap()),
+        Some(model.ctx.lists.to_owned()),
+        "Lists were updated in storage"
+    );
+}
+
+#[test]
+fn lists_pull_from_api() {
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    *FETCH_HANDLER.write().unwrap() = Box::new(fetch_handler);
+    let local_list = List {
+        items: vec![ListItem::from(&meta_item("tt0077651", "Halloween"))],
+        ..list("1", "Halloween", 0, remote_mtime() + Duration::days(1))
+    };
+    let (runtime, _rx) = Runtime::<TestEnv, _>::new(
+        TestModel {
+            ctx: Ctx {
+                profile: profile(),
+                lists: ListsBucket {
+                    uid: None,
+                    items: [("1".to_owned(), local_list.to_owned())].into(),
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        vec![],
+        1000,
+    );
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Ctx(ActionCtx::PullListsFromAPI),
+        })
+    });
+    let model = runtime.model().unwrap();
+    assert_eq!(
+        model
+            .ctx
+            .lists
+            .ordered()
+            .into_iter()
+            .map(|list| list.name.as_str())
+            .collect::<Vec<_>>(),
+        vec!["Halloween", "Watch with kids"],
+        "Remote list was pulled"
+    );
+    assert_eq!(
+        model.ctx.lists.items.get("1"),
+        Some(&local_list),
+        "Newer local list was kept"
+    );
+    assert!(
+        REQUESTS.read().unwrap().iter().any(|request| request.url
+            == "https://api.strem.io/api/datastorePut"
+            && request.body.contains("\"collection\":\"userList\"")),
+        "Newer local list was pushed"
+    );
+}
+
+#[test]
+fn lists_import_and_remove() {
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    let (runtime, _rx) = Runtime::<TestEnv, _>::new(TestModel::default(), vec![], 1000);
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Ctx(ActionCtx::ImportList(SharedList {
+                name: "Watch with kids".to_owned(),
+                items: vec![ListItem::from(&meta_item("tt0266543", "Finding Nemo"))],
+            })),
+        })
+    });
+    let id = list_id(&runtime, "Watch with kids");
+    *NOW.write().unwrap() = TestEnv::now() + Duration::seconds(1);
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Ctx(ActionCtx::CreateList {
+                name: "Halloween".to_owned(),
+            }),
+        })
+    });
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: load_list(&id),
+        })
+    });
+    {
+        let model = runtime.model().unwrap();
+        assert_eq!(
+            SharedList::from(*model.ctx.lists.ordered().first().unwrap()).name,
+            "Watch with kids"
+        );
+        assert_eq!(model.library.catalog.len(), 1, "Imported items are shown");
+    }
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Ctx(ActionCtx::MoveList {
+                id: id.to_owned(),
+                position: 1,
+            }),
+        })
+    });
+    assert_eq!(
+        runtime
+            .model()
+            .unwrap()
+            .ctx
+            .lists
+            .ordered()
+            .into_iter()
+            .map(|list| list.name.as_str())
+            .collect::<Vec<_>>(),
+        vec!["Halloween", "Watch with kids"],
+        "List was moved"
+    );
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Ctx(ActionCtx::RemoveList(id.to_owned())),
+        })
+    });
+    let model = runtime.model().unwrap();
+    assert!(model.ctx.lists.items.get(&id).unwrap().removed);
+    assert_eq!(model.ctx.lists.ordered().len(), 1);
+    assert!(
+        model.library.catalog.is_empty(),
+        "Removed list has no items"
+    );
+}
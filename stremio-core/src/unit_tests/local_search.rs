@@ -0,0 +1,181 @@
+use std::any::Any;
+
+use chrono::{TimeZone, Utc};
+use futures::future;
+use stremio_derive::Model;
+
+use crate::models::ctx::Ctx;
+use crate::models::local_search::{
+    FieldWeights, LocalSearch, SearchOptions, Searchable, SearchableItemsResponse, SearchableSource,
+};
+use crate::runtime::msg::{Action, ActionLoad, ActionSearch};
+use crate::runtime::{EnvFutureExt, Runtime, RuntimeAction, TryEnvFuture};
+use crate::types::library::{LibraryBucket, LibraryItem, LibraryItemState};
+use crate::types::search_history::SearchHistoryBucket;
+use crate::unit_tests::{default_fetch_handler, Request, TestEnv, FETCH_HANDLER};
+
+#[derive(Model, Default, Clone, Debug)]
+#[model(TestEnv)]
+struct TestModel {
+    ctx: Ctx,
+    local_search: LocalSearch,
+}
+
+fn fetch_handler(request: Request) -> TryEnvFuture<Box<dyn Any + Send>> {
+    match request {
+        Request { url, method, .. }
+            if url == "https://cinemeta-catalogs.strem.io/feed.json" && method == "GET" =>
+        {
+            future::ok(Box::new(SearchableItemsResponse(vec![
+                searchable("tt0903747", "Breaking Bad", "series", vec![]),
+                searchable(
+                    "tt0468569",
+                    "The Dark Knight",
+                    "movie",
+                    vec!["Christian Bale"],
+                ),
+            ])) as Box<dyn Any + Send>)
+            .boxed_env()
+        }
+        _ => default_fetch_handler(request),
+    }
+}
+
+fn searchable(id: &str, name: &str, r#type: &str, cast: Vec<&str>) -> Searchable {
+    Searchable {
+        id: id.to_owned(),
+        name: name.to_owned(),
+        r#type: r#type.to_owned(),
+        poster: None,
+        imdb_rating: None,
+        popularity: None,
+        release_info: Some("2008".to_owned()),
+        cast: cast.into_iter().map(ToOwned::to_owned).collect(),
+        genres: vec![],
+        source: SearchableSource::Feed,
+    }
+}
+
+fn library_item(id: &str, name: &str, removed: bool) -> LibraryItem {
+    LibraryItem {
+        id: id.to_owned(),
+        name: name.to_owned(),
+        r#type: "series".to_owned(),
+        poster: None,
+        poster_shape: Default::default(),
+        removed,
+        temp: false,
+        ctime: None,
+        mtime: Utc.with_ymd_and_hms(2020, 1, 1, 0, 0, 0).unwrap(),
+        state: LibraryItemState::default(),
+        behavior_hints: Default::default(),
+    }
+}
+
+fn search(
+    runtime: &Runtime<TestEnv, TestModel>,
+    search_query: &str,
+    r#type: Option<&str>,
+) -> Vec<(String, SearchableSource)> {
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Search(ActionSearch::Search {
+                search_query: search_query.to_owned(),
+                max_results: 10,
+                r#type: r#type.map(ToOwned::to_owned),
+            }),
+        })
+    });
+    runtime
+        .model()
+        .unwrap()
+        .local_search
+        .search_results
+        .iter()
+        .map(|searchable| (searchable.name.to_owned(), searchable.source))
+        .collect()
+}
+
+#[test]
+fn local_search() {
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    *FETCH_HANDLER.write().unwrap() = Box::new(fetch_handler);
+    let mut search_history = SearchHistoryBucket::default();
+    search_history.items.insert(
+        "dark knight".to_owned(),
+        Utc.with_ymd_and_hms(2020, 1, 1, 0, 0, 0).unwrap(),
+    );
+    let (runtime, _rx) = Runtime::<TestEnv, _>::new(
+        TestModel {
+            ctx: Ctx {
+                library: LibraryBucket::new(
+                    None,
+                    vec![
+                        library_item("tt0903747", "Breaking Bad", false),
+                        library_item("tt3032476", "Better Call Saul", true),
+                    ],
+                ),
+                search_history,
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        vec![],
+        1000,
+    );
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Load(ActionLoad::LocalSearch),
+        })
+    });
+    assert_eq!(
+        search(&runtime, "breaking", None),
+        vec![("Breaking Bad".to_owned(), SearchableSource::Library)],
+        "library item instead of the same item of the feed"
+    );
+    assert!(
+        search(&runtime, "saul", None).is_empty(),
+        "removed library items are not searched"
+    );
+    assert_eq!(
+        search(&runtime, "dark", None),
+        vec![
+            ("dark knight".to_owned(), SearchableSource::SearchHistory),
+            ("The Dark Knight".to_owned(), SearchableSource::Feed),
+        ],
+        "search history before the feed"
+    );
+    assert_eq!(
+        search(&runtime, "dark", Some("movie")),
+        vec![("The Dark Knight".to_owned(), SearchableSource::Feed)],
+        "filtered by type"
+    );
+    assert_eq!(
+        search(&runtime, "bale", None),
+        vec![("The Dark Knight".to_owned(), SearchableSource::Feed)],
+        "matched by the cast"
+    );
+    assert_eq!(
+        search(&runtime, "2008", None).len(),
+        2,
+        "matched by the year"
+    );
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Search(ActionSearch::UpdateOptions(SearchOptions {
+                field_weights: FieldWeights {
+                    cast: 0.0,
+                    ..Default::default()
+                },
+                ..Default::default()
+            })),
+        })
+    });
+    assert!(
+        search(&runtime, "bale", None).is_empty(),
+        "cast is not searched without weight"
+    );
+}
@@ -0,0 +1,107 @@
+use std::any::Any;
+
+use futures::future;
+use stremio_derive::Model;
+use url::Url;
+
+use crate::models::common::Loadable;
+use crate::models::ctx::Ctx;
+use crate::models::local_subtitles::{LocalSubtitles, Selected};
+use crate::runtime::msg::{Action, ActionLoad};
+use crate::runtime::{EnvFutureExt, Runtime, RuntimeAction, TryEnvFuture};
+use crate::types::subtitles::{FramerateCorrection, SubtitlesFormat};
+use crate::unit_tests::{default_fetch_handler, Request, TestEnv, FETCH_HANDLER, REQUESTS};
+
+const ASS: &str = "[Script Info]
+Title: Test
+ScriptType: v4.00+
+
+[V4+ Styles]
+Format: Name, Fontname, Fontsize
+Style: Default,Arial,20
+
+[Events]
+Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text
+Dialogue: 0,0:00:02.50,0:00:04.00,Default,,0,0,0,,{\\an8}Здравей,\\Nсвят!
+Dialogue: 0,0:00:01.00,0:00:02.00,Default,,0,0,0,,Първи
+";
+
+#[derive(Model, Default, Clone, Debug)]
+#[model(TestEnv)]
+struct TestModel {
+    ctx: Ctx,
+    local_subtitles: LocalSubtitles,
+}
+
+fn fetch_handler(request: Request) -> TryEnvFuture<Box<dyn Any + Send>> {
+    match request {
+        Request { url, method, .. } if url == "https://subtitles/bul.ass" && method == "GET" => {
+            // windows-1251, as the legacy subtitles usually are
+            let (bytes, _, _) = encoding_rs::WINDOWS_1251.encode(ASS);
+            future::ok(Box::new(bytes.into_owned()) as Box<dyn Any + Send>).boxed_env()
+        }
+        _ => default_fetch_handler(request),
+    }
+}
+
+fn load(runtime: &Runtime<TestEnv, TestModel>, selected: Selected) {
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Load(ActionLoad::LocalSubtitles(selected)),
+        })
+    });
+}
+
+#[test]
+fn local_subtitles() {
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    *FETCH_HANDLER.write().unwrap() = Box::new(fetch_handler);
+    let (runtime, _rx) = Runtime::<TestEnv, _>::new(TestModel::default(), vec![], 1000);
+    let selected = Selected {
+        url: Url::parse("https://subtitles/bul.ass").unwrap(),
+        lang: Some("bul".to_owned()),
+        delay: 0,
+        framerate: None,
+    };
+    load(&runtime, selected.to_owned());
+    {
+        let model = runtime.model().unwrap();
+        let track = match &model.local_subtitles.track {
+            Some(Loadable::Ready(track)) => track,
+            track => panic!("Unexpected track: {track:?}"),
+        };
+        assert_eq!(track.format, SubtitlesFormat::Ass);
+        assert_eq!(track.encoding, "windows-1251");
+        assert_eq!(
+            model.local_subtitles.webvtt.as_deref(),
+            Some(
+                "WEBVTT\n\n\
+                1\n00:00:01.000 --> 00:00:02.000\nПърви\n\n\
+                2\n00:00:02.500 --> 00:00:04.000\nЗдравей,\nсвят!\n\n"
+            ),
+            "Cues are sorted and the override blocks are removed"
+        );
+    }
+    load(
+        &runtime,
+        Selected {
+            delay: -2000,
+            framerate: Some(FramerateCorrection {
+                from: 25.0,
+                to: 25.0,
+            }),
+            ..selected
+        },
+    );
+    assert_eq!(
+        REQUESTS.read().unwrap().len(),
+        1,
+        "The file is not fetched again when only the timing changes"
+    );
+    assert_eq!(
+        runtime.model().unwrap().local_subtitles.webvtt.as_deref(),
+        Some("WEBVTT\n\n1\n00:00:00.500 --> 00:00:02.000\nЗдравей,\nсвят!\n\n"),
+        "Cues ending before the start are dropped"
+    );
+}
@@ -1 +1,3 @@
 mod override_selected;
+mod ranked_streams;
+mod stream_probes;
@@ -3,7 +3,7 @@ use crate::models::ctx::Ctx;
 use crate::models::meta_details::{MetaDetails, Selected};
 use crate::runtime::msg::{Action, ActionLoad};
 use crate::runtime::{EnvFutureExt, Runtime, RuntimeAction, TryEnvFuture};
-use crate::types::addon::{ResourcePath, ResourceResponse};
+use crate::types::addon::{ResourcePath, ResourceResponse, ResourceResponseCache};
 use crate::types::profile::Profile;
 use crate::types::resource::{MetaItem, MetaItemBehaviorHints, MetaItemPreview};
 use crate::unit_tests::{default_fetch_handler, Request, TestEnv, FETCH_HANDLER, STATES};
@@ -25,20 +25,22 @@ fn override_selected_default_video_id() {
     fn fetch_handler(request: Request) -> TryEnvFuture<Box<dyn Any + Send>> {
         match request {
             Request { url, .. } if url == "https://v3-cinemeta.strem.io/meta/movie/tt1.json" => {
-                future::ok(Box::new(ResourceResponse::Meta {
-                    meta: MetaItem {
-                        preview: MetaItemPreview {
-                            id: "tt1".to_owned(),
-                            r#type: "movie".to_owned(),
-                            behavior_hints: MetaItemBehaviorHints {
-                                default_video_id: Some("_tt1".to_owned()),
+                future::ok(
+                    Box::new(ResourceResponseCache::from(ResourceResponse::Meta {
+                        meta: MetaItem {
+                            preview: MetaItemPreview {
+                                id: "tt1".to_owned(),
+                                r#type: "movie".to_owned(),
+                                behavior_hints: MetaItemBehaviorHints {
+                                    default_video_id: Some("_tt1".to_owned()),
+                                    ..Default::default()
+                                },
                                 ..Default::default()
                             },
-                            ..Default::default()
+                            videos: vec![],
                         },
-                        videos: vec![],
-                    },
-                }) as Box<dyn Any + Send>)
+                    })) as Box<dyn Any + Send>,
+                )
                 .boxed_env()
             }
             _ => default_fetch_handler(request),
@@ -124,16 +126,18 @@ fn override_selected_meta_id() {
     fn fetch_handler(request: Request) -> TryEnvFuture<Box<dyn Any + Send>> {
         match request {
             Request { url, .. } if url == "https://v3-cinemeta.strem.io/meta/movie/tt1.json" => {
-                future::ok(Box::new(ResourceResponse::Meta {
-                    meta: MetaItem {
-                        preview: MetaItemPreview {
-                            id: "tt1".to_owned(),
-                            r#type: "movie".to_owned(),
-                            ..Default::default()
+                future::ok(
+                    Box::new(ResourceResponseCache::from(ResourceResponse::Meta {
+                        meta: MetaItem {
+                            preview: MetaItemPreview {
+                                id: "tt1".to_owned(),
+                                r#type: "movie".to_owned(),
+                                ..Default::default()
+                            },
+                            videos: vec![],
                         },
-                        videos: vec![],
-                    },
-                }) as Box<dyn Any + Send>)
+                    })) as Box<dyn Any + Send>,
+                )
                 .boxed_env()
             }
             _ => default_fetch_handler(request),
@@ -0,0 +1,171 @@
+use futures::future;
+use semver::Version;
+use stremio_derive::Model;
+use url::Url;
+
+use crate::addon_transport::AddonBuilder;
+use crate::constants::{META_RESOURCE_NAME, STREAM_RESOURCE_NAME};
+use crate::models::ctx::Ctx;
+use crate::models::meta_details::{MetaDetails, Selected};
+use crate::runtime::msg::{Action, ActionCtx, ActionLoad};
+use crate::runtime::{EnvFutureExt, Runtime, RuntimeAction};
+use crate::types::addon::{Descriptor, Manifest, ResourcePath, ResourceResponse};
+use crate::types::profile::{Profile, Settings, StreamPreferences};
+use crate::types::resource::{Stream, StreamResolution, StreamSource};
+use crate::unit_tests::{TestEnv, ADDON_TRANSPORTS};
+
+fn stream(url: &str, description: &str) -> Stream {
+    Stream {
+        source: StreamSource::Url {
+            url: url.parse().unwrap(),
+        },
+        name: None,
+        description: Some(description.to_owned()),
+        thumbnail: None,
+        subtitles: vec![],
+        behavior_hints: Default::default(),
+    }
+}
+
+fn descriptor(id: &str) -> Descriptor {
+    Descriptor {
+        manifest: Manifest {
+            id: id.to_owned(),
+            version: Version::new(1, 0, 0),
+            name: id.to_owned(),
+            contact_email: None,
+            description: None,
+            logo: None,
+            background: None,
+            types: vec!["movie".to_owned()],
+            resources: vec![STREAM_RESOURCE_NAME.into()],
+            id_prefixes: Some(vec!["tt".to_owned()]),
+            catalogs: vec![],
+            addon_catalogs: vec![],
+            behavior_hints: Default::default(),
+        },
+        transport_url: Url::parse(&format!("local://{id}/manifest.json")).unwrap(),
+        flags: Default::default(),
+    }
+}
+
+#[test]
+fn ranked_streams() {
+    #[derive(Model, Default, Clone, Debug)]
+    #[model(TestEnv)]
+    struct TestModel {
+        ctx: Ctx,
+        meta_details: MetaDetails,
+    }
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    let addons = vec![descriptor("first"), descriptor("second")];
+    let first_addon = AddonBuilder::new(addons[0].manifest.to_owned())
+        .define_stream_handler(|_| {
+            future::ok(ResourceResponse::Streams {
+                streams: vec![
+                    stream("https://first/720p", "Movie 720p x264 English"),
+                    stream("https://second/2160p", "Movie 720p"),
+                ],
+            })
+            .boxed_env()
+        })
+        .build()
+        .unwrap();
+    let second_addon = AddonBuilder::new(addons[1].manifest.to_owned())
+        .define_stream_handler(|_| {
+            future::ok(ResourceResponse::Streams {
+                streams: vec![
+                    stream("https://second/1080p", "Movie.1080p.ITA"),
+                    stream("https://second/2160p", "Movie 2160p HDR 🇬🇧"),
+                ],
+            })
+            .boxed_env()
+        })
+        .build()
+        .unwrap();
+    let registry = ADDON_TRANSPORTS.read().unwrap().to_owned();
+    *ADDON_TRANSPORTS.write().unwrap() = registry.register("local", move |transport_url| {
+        match transport_url.host_str() {
+            Some("first") => Box::new(first_addon.to_owned()),
+            _ => Box::new(second_addon.to_owned()),
+        }
+    });
+    let (runtime, _rx) = Runtime::<TestEnv, _>::new(
+        TestModel {
+            ctx: Ctx {
+                profile: Profile {
+                    addons: addons.to_owned(),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            meta_details: Default::default(),
+        },
+        vec![],
+        1000,
+    );
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Load(ActionLoad::MetaDetails(Selected {
+                meta_path: ResourcePath::without_extra(META_RESOURCE_NAME, "movie", "tt1"),
+                stream_path: Some(ResourcePath::without_extra(
+                    STREAM_RESOURCE_NAME,
+                    "movie",
+                    "tt1",
+                )),
+                guess_stream: false,
+            })),
+        })
+    });
+    let ranked_urls = || {
+        runtime
+            .model()
+            .unwrap()
+            .meta_details
+            .ranked_streams
+            .iter()
+            .map(|ranked_stream| {
+                let url = match &ranked_stream.stream.source {
+                    StreamSource::Url { url } => url.to_string(),
+                    _ => panic!("Unexpected stream source"),
+                };
+                (
+                    ranked_stream.request.base.host_str().unwrap().to_owned(),
+                    url,
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        ranked_urls(),
+        vec![
+            ("second".to_owned(), "https://second/2160p".to_owned()),
+            ("first".to_owned(), "https://first/720p".to_owned()),
+            ("second".to_owned(), "https://second/1080p".to_owned()),
+        ],
+        "Streams of both addons are ranked by language and resolution without duplicates"
+    );
+
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Ctx(ActionCtx::UpdateSettings(Settings {
+                stream_preferences: StreamPreferences {
+                    max_resolution: Some(StreamResolution::FullHD),
+                    ..Default::default()
+                },
+                ..Default::default()
+            })),
+        })
+    });
+    assert_eq!(
+        ranked_urls(),
+        vec![
+            ("first".to_owned(), "https://first/720p".to_owned()),
+            ("second".to_owned(), "https://second/2160p".to_owned()),
+            ("second".to_owned(), "https://second/1080p".to_owned()),
+        ],
+        "Streams are ranked again when the preferences change"
+    );
+}
@@ -0,0 +1,265 @@
+use std::any::Any;
+use std::collections::HashMap;
+
+use chrono::{Duration, Utc};
+use futures::future;
+use semver::Version;
+use stremio_derive::Model;
+use url::Url;
+
+use crate::addon_transport::AddonBuilder;
+use crate::constants::{
+    META_RESOURCE_NAME, STREAM_PROBES_MAX_COUNT, STREAM_PROBE_TTL, STREAM_RESOURCE_NAME,
+};
+use crate::models::ctx::Ctx;
+use crate::models::meta_details::{MetaDetails, Selected};
+use crate::runtime::msg::{Action, ActionLoad};
+use crate::runtime::{EnvError, EnvFutureExt, ResponseHead, Runtime, RuntimeAction, TryEnvFuture};
+use crate::types::addon::{Descriptor, Manifest, ResourcePath, ResourceResponse};
+use crate::types::profile::{Profile, Settings};
+use crate::types::resource::{Stream, StreamProxyHeaders, StreamSource};
+use crate::types::stream_probes::{StreamHealth, StreamProbe, StreamProbesBucket};
+use crate::unit_tests::{
+    default_fetch_handler, Request, TestEnv, ADDON_TRANSPORTS, FETCH_HANDLER, REQUESTS,
+};
+
+fn stream(url: &str) -> Stream {
+    Stream {
+        source: StreamSource::Url {
+            url: url.parse().unwrap(),
+        },
+        name: None,
+        description: None,
+        thumbnail: None,
+        subtitles: vec![],
+        behavior_hints: Default::default(),
+    }
+}
+
+fn private_stream(url: &str) -> Stream {
+    let mut stream = stream(url);
+    stream.behavior_hints.proxy_headers = Some(StreamProxyHeaders {
+        request: HashMap::from([("Authorization".to_owned(), "Bearer token".to_owned())]),
+        response: HashMap::new(),
+    });
+    stream
+}
+
+fn response_head(status: u16, headers: &[(&str, &str)]) -> TryEnvFuture<Box<dyn Any + Send>> {
+    future::ok(Box::new(ResponseHead {
+        status,
+        headers: headers
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect::<HashMap<_, _>>(),
+    }) as Box<dyn Any + Send>)
+    .boxed_env()
+}
+
+fn fetch_handler(request: Request) -> TryEnvFuture<Box<dyn Any + Send>> {
+    match (request.method.as_str(), request.url.as_str()) {
+        ("HEAD", "https://host/alive.mp4") => response_head(
+            200,
+            &[("content-type", "video/mp4"), ("content-length", "1000")],
+        ),
+        ("HEAD", "https://host/not_found.mp4") => response_head(404, &[]),
+        ("HEAD", "https://host/error_page.mp4") => {
+            response_head(200, &[("content-type", "text/html")])
+        }
+        ("HEAD", "https://host/no_head.mp4") => response_head(405, &[]),
+        ("HEAD", "https://host/forbidden.mp4") => response_head(403, &[]),
+        ("HEAD", "https://host/private.mp4")
+            if request.headers.get("authorization").map(String::as_str) == Some("Bearer token") =>
+        {
+            response_head(200, &[("content-type", "video/mp4")])
+        }
+        ("HEAD", "https://host/private.mp4") => response_head(401, &[]),
+        ("GET", "https://host/no_head.mp4")
+            if request.headers.get("range").map(String::as_str) == Some("bytes=0-0") =>
+        {
+            response_head(
+                206,
+                &[
+                    ("content-type", "video/mp4"),
+                    ("content-range", "bytes 0-0/2000"),
+                ],
+            )
+        }
+        ("HEAD", "https://unreachable/video.mp4") => {
+            future::err(EnvError::Fetch("Connection refused".to_owned())).boxed_env()
+        }
+        _ => default_fetch_handler(request),
+    }
+}
+
+#[test]
+fn stream_probes() {
+    #[derive(Model, Default, Clone, Debug)]
+    #[model(TestEnv)]
+    struct TestModel {
+        ctx: Ctx,
+        meta_details: MetaDetails,
+    }
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    *FETCH_HANDLER.write().unwrap() = Box::new(fetch_handler);
+    let manifest = Manifest {
+        id: "addon".to_owned(),
+        version: Version::new(1, 0, 0),
+        name: "addon".to_owned(),
+        contact_email: None,
+        description: None,
+        logo: None,
+        background: None,
+        types: vec!["movie".to_owned()],
+        resources: vec![STREAM_RESOURCE_NAME.into()],
+        id_prefixes: Some(vec!["tt".to_owned()]),
+        catalogs: vec![],
+        addon_catalogs: vec![],
+        behavior_hints: Default::default(),
+    };
+    let addon = AddonBuilder::new(manifest.to_owned())
+        .define_stream_handler(|_| {
+            future::ok(ResourceResponse::Streams {
+                streams: vec![
+                    stream("https://host/not_found.mp4"),
+                    stream("https://host/alive.mp4"),
+                    stream("https://unreachable/video.mp4"),
+                    stream("https://host/error_page.mp4"),
+                    stream("https://host/no_head.mp4"),
+                    stream("https://host/forbidden.mp4"),
+                    private_stream("https://host/private.mp4"),
+                ],
+            })
+            .boxed_env()
+        })
+        .build()
+        .unwrap();
+    let registry = ADDON_TRANSPORTS.read().unwrap().to_owned();
+    *ADDON_TRANSPORTS.write().unwrap() =
+        registry.register("local", move |_| Box::new(addon.to_owned()));
+    let (runtime, _rx) = Runtime::<TestEnv, _>::new(
+        TestModel {
+            ctx: Ctx {
+                profile: Profile {
+                    addons: vec![Descriptor {
+                        manifest,
+                        transport_url: Url::parse("local://addon/manifest.json").unwrap(),
+                        flags: Default::default(),
+                    }],
+                    settings: Settings {
+                        stream_health_check: true,
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            meta_details: Default::default(),
+        },
+        vec![],
+        1000,
+    );
+    let load = || {
+        TestEnv::run(|| {
+            runtime.dispatch(RuntimeAction {
+                field: None,
+                action: Action::Load(ActionLoad::MetaDetails(Selected {
+                    meta_path: ResourcePath::without_extra(META_RESOURCE_NAME, "movie", "tt1"),
+                    stream_path: Some(ResourcePath::without_extra(
+                        STREAM_RESOURCE_NAME,
+                        "movie",
+                        "tt1",
+                    )),
+                    guess_stream: false,
+                })),
+            })
+        })
+    };
+    load();
+    assert_eq!(
+        runtime
+            .model()
+            .unwrap()
+            .meta_details
+            .ranked_streams
+            .iter()
+            .map(|ranked_stream| (ranked_stream.stream.to_owned(), ranked_stream.health))
+            .collect::<Vec<_>>(),
+        vec![
+            (stream("https://host/alive.mp4"), Some(StreamHealth::Alive)),
+            (
+                stream("https://unreachable/video.mp4"),
+                Some(StreamHealth::Unknown)
+            ),
+            (
+                stream("https://host/no_head.mp4"),
+                Some(StreamHealth::Alive)
+            ),
+            (
+                stream("https://host/forbidden.mp4"),
+                Some(StreamHealth::Unknown)
+            ),
+            (
+                private_stream("https://host/private.mp4"),
+                Some(StreamHealth::Alive)
+            ),
+            (
+                stream("https://host/not_found.mp4"),
+                Some(StreamHealth::Dead)
+            ),
+            (
+                stream("https://host/error_page.mp4"),
+                Some(StreamHealth::Dead)
+            ),
+        ],
+        "dead streams demoted, failed and refused probes are unknown, probes sent with the request headers"
+    );
+    {
+        let ctx = &runtime.model().unwrap().ctx;
+        let no_head_probe = ctx
+            .stream_probes
+            .items
+            .get(&"https://host/no_head.mp4".parse::<Url>().unwrap())
+            .expect("Should be probed");
+        assert_eq!(no_head_probe.status, Some(206), "range request fallback");
+        assert_eq!(no_head_probe.content_length, Some(2000));
+        assert!(ctx.stream_probes.pending.is_empty());
+    }
+    let requests_count = REQUESTS.read().unwrap().len();
+    load();
+    assert_eq!(
+        REQUESTS.read().unwrap().len(),
+        requests_count,
+        "probes cached"
+    );
+}
+
+#[test]
+fn stream_probes_eviction() {
+    let now = Utc::now();
+    let probe = |checked_at| StreamProbe {
+        status: Some(200),
+        content_type: None,
+        content_length: None,
+        error: None,
+        checked_at,
+    };
+    let url = |index: usize| format!("https://host/{index}.mp4").parse::<Url>().unwrap();
+    let mut stream_probes = StreamProbesBucket::default();
+    stream_probes.insert(url(0), probe(now - STREAM_PROBE_TTL));
+    for index in 1..=STREAM_PROBES_MAX_COUNT + 1 {
+        stream_probes.insert(url(index), probe(now + Duration::seconds(index as i64)));
+    }
+    assert_eq!(stream_probes.items.len(), STREAM_PROBES_MAX_COUNT);
+    assert!(
+        !stream_probes.items.contains_key(&url(0)),
+        "expired probe evicted"
+    );
+    assert!(
+        !stream_probes.items.contains_key(&url(1)),
+        "oldest probe evicted"
+    );
+    assert!(stream_probes
+        .items
+        .contains_key(&url(STREAM_PROBES_MAX_COUNT + 1)));
+}
@@ -1,13 +1,24 @@
 mod env;
 pub use env::*;
 
+mod addon_health;
+mod addon_request_policy;
+mod addon_transport;
 mod calendar;
 mod catalog_with_filters;
 mod ctx;
 mod data_export;
 mod deep_links;
+mod library_transfer;
 mod link;
+mod lists;
+mod local_search;
+mod local_subtitles;
 mod meta_details;
 mod player;
+mod recommendations;
 mod serde;
 mod streaming_server;
+mod torrent_info;
+mod watch_party;
+mod watch_stats;
@@ -1 +1,3 @@
 mod next_stream;
+mod ranked_subtitles;
+mod subtitles_sync;
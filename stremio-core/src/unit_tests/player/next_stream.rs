@@ -2,15 +2,15 @@ use crate::{
     constants::{META_RESOURCE_NAME, STREAM_RESOURCE_NAME},
     models::{
         ctx::Ctx,
-        player::{Player, Selected},
+        player::{NextStreamMatch, Player, Selected},
     },
     runtime::{
         msg::{Action, ActionLoad, ActionPlayer},
         EnvFutureExt, Runtime, RuntimeAction, TryEnvFuture,
     },
     types::{
-        addon::{ResourcePath, ResourceRequest, ResourceResponse},
-        profile::{Profile, Settings},
+        addon::{ResourcePath, ResourceRequest, ResourceResponse, ResourceResponseCache},
+        profile::{AutoPlayPolicy, Profile, Settings},
         resource::{
             MetaItem, MetaItemPreview, SeriesInfo, Stream, StreamBehaviorHints, StreamSource, Video,
         },
@@ -62,32 +62,38 @@ fn next_stream() {
     fn fetch_handler(request: Request) -> TryEnvFuture<Box<dyn Any + Send>> {
         match request {
             Request { url, .. } if url == "https://transport_url/meta/series/tt123456.json" => {
-                future::ok(Box::new(ResourceResponse::Meta {
-                    meta: MetaItem {
-                        preview: MetaItemPreview {
-                            id: "tt123456".to_owned(),
-                            r#type: "series".to_owned(),
-                            ..Default::default()
+                future::ok(
+                    Box::new(ResourceResponseCache::from(ResourceResponse::Meta {
+                        meta: MetaItem {
+                            preview: MetaItemPreview {
+                                id: "tt123456".to_owned(),
+                                r#type: "series".to_owned(),
+                                ..Default::default()
+                            },
+                            videos: vec![create_video(1, 1), create_video(1, 2)],
                         },
-                        videos: vec![create_video(1, 1), create_video(1, 2)],
-                    },
-                }) as Box<dyn Any + Send>)
+                    })) as Box<dyn Any + Send>,
+                )
                 .boxed_env()
             }
             Request { url, .. }
                 if url == "https://transport_url/stream/series/tt123456%3A1%3A2.json" =>
             {
-                future::ok(Box::new(ResourceResponse::Streams {
-                    streams: vec![create_stream("binge_group"), create_stream("binge_group_1")],
-                }) as Box<dyn Any + Send>)
+                future::ok(
+                    Box::new(ResourceResponseCache::from(ResourceResponse::Streams {
+                        streams: vec![create_stream("binge_group"), create_stream("binge_group_1")],
+                    })) as Box<dyn Any + Send>,
+                )
                 .boxed_env()
             }
             Request { url, .. }
                 if url == "https://transport_url/stream/series/tt123456%3A1%3A3.json" =>
             {
-                future::ok(Box::new(ResourceResponse::Streams {
-                    streams: vec![create_stream("binge_group"), create_stream("binge_group_1")],
-                }) as Box<dyn Any + Send>)
+                future::ok(
+                    Box::new(ResourceResponseCache::from(ResourceResponse::Streams {
+                        streams: vec![create_stream("binge_group"), create_stream("binge_group_1")],
+                    })) as Box<dyn Any + Send>,
+                )
                 .boxed_env()
             }
             _ => default_fetch_handler(request),
@@ -208,3 +214,155 @@ fn next_stream() {
         "library item time_offset was reset to 1 to keep it in CW as there's a next video"
     );
 }
+
+fn create_named_stream(url: &str, binge_group: &str, filename: &str) -> Stream {
+    Stream {
+        source: StreamSource::Url {
+            url: url.parse().unwrap(),
+        },
+        name: None,
+        description: Some(filename.to_owned()),
+        thumbnail: None,
+        subtitles: vec![],
+        behavior_hints: StreamBehaviorHints {
+            binge_group: Some(binge_group.to_owned()),
+            filename: Some(filename.to_owned()),
+            ..Default::default()
+        },
+    }
+}
+
+#[test]
+fn next_stream_auto_play_policy() {
+    #[derive(Model, Default, Clone, Debug)]
+    #[model(TestEnv)]
+    struct TestModel {
+        ctx: Ctx,
+        player: Player,
+    }
+
+    fn fetch_handler(request: Request) -> TryEnvFuture<Box<dyn Any + Send>> {
+        match request {
+            Request { url, .. } if url == "https://transport_url/meta/series/tt123456.json" => {
+                future::ok(
+                    Box::new(ResourceResponseCache::from(ResourceResponse::Meta {
+                        meta: MetaItem {
+                            preview: MetaItemPreview {
+                                id: "tt123456".to_owned(),
+                                r#type: "series".to_owned(),
+                                ..Default::default()
+                            },
+                            videos: vec![create_video(1, 1), create_video(1, 2)],
+                        },
+                    })) as Box<dyn Any + Send>,
+                )
+                .boxed_env()
+            }
+            Request { url, .. }
+                if url == "https://transport_url/stream/series/tt123456%3A1%3A2.json" =>
+            {
+                future::ok(
+                    Box::new(ResourceResponseCache::from(ResourceResponse::Streams {
+                        streams: vec![
+                            create_named_stream(
+                                "https://source_url/720p",
+                                "binge_group_720p",
+                                "Show.S01E02.720p.x264-OTHER.mkv",
+                            ),
+                            create_named_stream(
+                                "https://source_url/1080p",
+                                "binge_group_1080p",
+                                "Show.S01E02.1080p.x265-GROUP.mkv",
+                            ),
+                        ],
+                    })) as Box<dyn Any + Send>,
+                )
+                .boxed_env()
+            }
+            _ => default_fetch_handler(request),
+        }
+    }
+
+    let stream = create_named_stream(
+        "https://source_url/1080p",
+        "binge_group",
+        "Show.S01E01.1080p.x265-GROUP.mkv",
+    );
+    let meta_request = ResourceRequest {
+        base: "https://transport_url/manifest.json".parse().unwrap(),
+        path: ResourcePath {
+            resource: META_RESOURCE_NAME.to_owned(),
+            r#type: "series".to_owned(),
+            id: "tt123456".to_owned(),
+            extra: vec![],
+        },
+    };
+    let stream_request = ResourceRequest {
+        base: "https://transport_url/manifest.json".parse().unwrap(),
+        path: ResourcePath {
+            resource: STREAM_RESOURCE_NAME.to_owned(),
+            r#type: "series".to_owned(),
+            id: "tt123456:1:1".to_owned(),
+            extra: vec![],
+        },
+    };
+
+    for (auto_play_policy, expected_url, expected_match) in [
+        (AutoPlayPolicy::StrictBingeGroup, None, None),
+        (
+            AutoPlayPolicy::SameAddon,
+            Some("https://source_url/720p"),
+            Some(NextStreamMatch::SameAddon),
+        ),
+        (
+            AutoPlayPolicy::BestMatch,
+            Some("https://source_url/1080p"),
+            Some(NextStreamMatch::BestMatch),
+        ),
+        (AutoPlayPolicy::Off, None, None),
+    ] {
+        let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+        *FETCH_HANDLER.write().unwrap() = Box::new(fetch_handler);
+        let (runtime, _rx) = Runtime::<TestEnv, _>::new(
+            TestModel {
+                ctx: Ctx {
+                    profile: Profile {
+                        settings: Settings {
+                            auto_play_policy,
+                            ..Default::default()
+                        },
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                player: Player::default(),
+            },
+            vec![],
+            1000,
+        );
+        TestEnv::run(|| {
+            runtime.dispatch(RuntimeAction {
+                field: None,
+                action: Action::Load(ActionLoad::Player(Box::new(Selected {
+                    stream: stream.clone(),
+                    stream_request: Some(stream_request.clone()),
+                    meta_request: Some(meta_request.clone()),
+                    subtitles_path: None,
+                }))),
+            });
+        });
+
+        let player = runtime.model().unwrap().player.to_owned();
+        assert_eq!(
+            player.next_stream.map(|next_stream| next_stream.source),
+            expected_url.map(|url| StreamSource::Url {
+                url: url.parse().unwrap()
+            }),
+            "next stream selected by {auto_play_policy:?}"
+        );
+        assert_eq!(
+            player.next_stream_match, expected_match,
+            "next stream match of {auto_play_policy:?}"
+        );
+    }
+}
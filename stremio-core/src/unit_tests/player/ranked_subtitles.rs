@@ -0,0 +1,281 @@
+use futures::future;
+use semver::Version;
+use stremio_derive::Model;
+use url::Url;
+
+use crate::addon_transport::AddonBuilder;
+use crate::constants::{META_RESOURCE_NAME, STREAM_RESOURCE_NAME, SUBTITLES_RESOURCE_NAME};
+use crate::models::common::SubtitlesMatch;
+use crate::models::ctx::Ctx;
+use crate::models::player::{Player, Selected, VideoParams};
+use crate::runtime::msg::{Action, ActionLoad, ActionPlayer};
+use crate::runtime::{EnvFutureExt, Runtime, RuntimeAction};
+use crate::types::addon::{Descriptor, Manifest, ResourcePath, ResourceRequest, ResourceResponse};
+use crate::types::profile::{Profile, Settings};
+use crate::types::resource::{MetaItem, MetaItemPreview, Stream, StreamSource, Subtitles};
+use crate::types::streams::{StreamItemState, SubtitleTrack};
+use crate::unit_tests::{TestEnv, ADDON_TRANSPORTS};
+
+fn subtitles(addon: &str, id: &str, lang: &str, file: &str) -> Subtitles {
+    Subtitles {
+        id: id.to_owned(),
+        lang: lang.to_owned(),
+        url: Url::parse(&format!("https://{addon}/{file}")).unwrap(),
+    }
+}
+
+fn descriptor(id: &str) -> Descriptor {
+    Descriptor {
+        manifest: Manifest {
+            id: id.to_owned(),
+            version: Version::new(1, 0, 0),
+            name: id.to_owned(),
+            contact_email: None,
+            description: None,
+            logo: None,
+            background: None,
+            types: vec!["movie".to_owned()],
+            resources: vec![META_RESOURCE_NAME.into(), SUBTITLES_RESOURCE_NAME.into()],
+            id_prefixes: Some(vec!["tt".to_owned()]),
+            catalogs: vec![],
+            addon_catalogs: vec![],
+            behavior_hints: Default::default(),
+        },
+        transport_url: Url::parse(&format!("local://{id}/manifest.json")).unwrap(),
+        flags: Default::default(),
+    }
+}
+
+#[test]
+fn ranked_subtitles() {
+    #[derive(Model, Default, Clone, Debug)]
+    #[model(TestEnv)]
+    struct TestModel {
+        ctx: Ctx,
+        player: Player,
+    }
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    let addons = vec![descriptor("first"), descriptor("second")];
+    let build_addon = |descriptor: &Descriptor, subtitles: Vec<Subtitles>| {
+        AddonBuilder::new(descriptor.manifest.to_owned())
+            .define_meta_handler(|_| {
+                future::ok(ResourceResponse::Meta {
+                    meta: MetaItem {
+                        preview: MetaItemPreview {
+                            id: "tt1".to_owned(),
+                            r#type: "movie".to_owned(),
+                            ..Default::default()
+                        },
+                        videos: vec![],
+                    },
+                })
+                .boxed_env()
+            })
+            .define_subtitles_handler(move |_| {
+                future::ok(ResourceResponse::Subtitles {
+                    subtitles: subtitles.to_owned(),
+                })
+                .boxed_env()
+            })
+            .build()
+            .unwrap()
+    };
+    let first_addon = build_addon(
+        &addons[0],
+        vec![
+            subtitles("first", "1", "spa", "movie.spa.srt"),
+            subtitles("first", "2", "eng", "movie.eng.forced.srt"),
+            subtitles("first", "3", "bul", "movie.bul.srt"),
+            subtitles("first", "7", "hi", "movie.hin.srt"),
+        ],
+    );
+    let second_addon = build_addon(
+        &addons[1],
+        vec![
+            subtitles("second", "4", "English", "movie.srt"),
+            subtitles("second", "5", "eng", "movie.eng.sdh.srt"),
+            subtitles("second", "6", "eng", "movie.eng.forced.srt"),
+            subtitles("second", "8", "en", "movie.en.srt"),
+            subtitles("second", "9", "pob", "movie.pob.srt"),
+        ],
+    );
+    let registry = ADDON_TRANSPORTS.read().unwrap().to_owned();
+    *ADDON_TRANSPORTS.write().unwrap() = registry.register("local", move |transport_url| {
+        match transport_url.host_str() {
+            Some("first") => Box::new(first_addon.to_owned()),
+            _ => Box::new(second_addon.to_owned()),
+        }
+    });
+    let (runtime, _rx) = Runtime::<TestEnv, _>::new(
+        TestModel {
+            ctx: Ctx {
+                profile: Profile {
+                    addons: addons.to_owned(),
+                    settings: Settings {
+                        subtitles_language: Some("eng".to_owned()),
+                        secondary_subtitles_language: Some("bul".to_owned()),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            player: Default::default(),
+        },
+        vec![],
+        1000,
+    );
+    let load = || {
+        TestEnv::run(|| {
+            runtime.dispatch(RuntimeAction {
+                field: None,
+                action: Action::Load(ActionLoad::Player(Box::new(Selected {
+                    stream: Stream {
+                        source: StreamSource::Url {
+                            url: "https://source_url".parse().unwrap(),
+                        },
+                        name: None,
+                        description: None,
+                        thumbnail: None,
+                        subtitles: vec![],
+                        behavior_hints: Default::default(),
+                    },
+                    stream_request: Some(ResourceRequest {
+                        base: addons[0].transport_url.to_owned(),
+                        path: ResourcePath::without_extra(STREAM_RESOURCE_NAME, "movie", "tt1"),
+                    }),
+                    meta_request: Some(ResourceRequest {
+                        base: addons[0].transport_url.to_owned(),
+                        path: ResourcePath::without_extra(META_RESOURCE_NAME, "movie", "tt1"),
+                    }),
+                    subtitles_path: Some(ResourcePath::without_extra(
+                        SUBTITLES_RESOURCE_NAME,
+                        "movie",
+                        "tt1",
+                    )),
+                }))),
+            });
+            runtime.dispatch(RuntimeAction {
+                field: None,
+                action: Action::Player(ActionPlayer::VideoParamsChanged {
+                    video_params: Some(VideoParams {
+                        hash: None,
+                        size: None,
+                        filename: None,
+                    }),
+                }),
+            });
+        })
+    };
+    load();
+    let ranked = || {
+        let model = runtime.model().unwrap();
+        let ranked_ids = model
+            .player
+            .ranked_subtitles
+            .iter()
+            .map(|ranked| ranked.subtitles.id.to_owned())
+            .collect::<Vec<_>>();
+        let default = model
+            .player
+            .default_subtitles
+            .as_ref()
+            .map(|default| (default.track.id.to_owned(), default.subtitles_match));
+        (ranked_ids, default)
+    };
+    let state_changed = |subtitle_track: SubtitleTrack| {
+        TestEnv::run(|| {
+            runtime.dispatch(RuntimeAction {
+                field: None,
+                action: Action::Player(ActionPlayer::StreamStateChanged {
+                    state: StreamItemState {
+                        subtitle_track: Some(subtitle_track),
+                        ..Default::default()
+                    },
+                }),
+            })
+        })
+    };
+
+    assert_eq!(
+        ranked(),
+        (
+            vec!["4", "8", "5", "2", "6", "3", "1", "7", "9"]
+                .into_iter()
+                .map(ToOwned::to_owned)
+                .collect(),
+            Some(("4".to_owned(), SubtitlesMatch::PreferredLanguage))
+        ),
+        "Forced and hearing impaired subtitles are ranked below the regular ones of the language, \
+        equal scores keep the order of the addons"
+    );
+    {
+        let model = runtime.model().unwrap();
+        let english = &model.player.ranked_subtitles[0];
+        assert_eq!(english.language, "eng");
+        assert_eq!(english.request.base, addons[1].transport_url);
+        assert_eq!(model.player.ranked_subtitles[1].language, "eng");
+        assert!(model.player.ranked_subtitles[2].hearing_impaired);
+        assert!(model.player.ranked_subtitles[3].forced);
+        let hindi = &model.player.ranked_subtitles[7];
+        assert_eq!(hindi.language, "hin");
+        assert!(
+            !hindi.hearing_impaired,
+            "The language code is not a hearing impaired marker"
+        );
+        assert_eq!(model.player.ranked_subtitles[8].language, "por");
+    }
+
+    state_changed(SubtitleTrack {
+        id: "10".to_owned(),
+        embedded: false,
+        language: Some("bul".to_owned()),
+    });
+    {
+        let model = runtime.model().unwrap();
+        assert_eq!(
+            model.player.ranked_subtitles[0].subtitles_match,
+            Some(SubtitlesMatch::PreviousLanguage)
+        );
+        assert_eq!(
+            model
+                .player
+                .default_subtitles
+                .as_ref()
+                .map(|default| default.track.id.as_str()),
+            Some("4"),
+            "The default subtitles are not changed during the playback"
+        );
+    }
+
+    load();
+    assert_eq!(
+        ranked().1,
+        Some(("3".to_owned(), SubtitlesMatch::PreviousLanguage)),
+        "The language of the previous subtitles is preferred when they are not available"
+    );
+
+    state_changed(SubtitleTrack {
+        id: "6".to_owned(),
+        embedded: false,
+        language: Some("eng".to_owned()),
+    });
+    load();
+    assert_eq!(
+        ranked().1,
+        Some(("6".to_owned(), SubtitlesMatch::PreviousTrack)),
+        "The previous forced subtitles are chosen again"
+    );
+
+    state_changed(SubtitleTrack {
+        id: "embedded_1".to_owned(),
+        embedded: true,
+        language: Some("eng".to_owned()),
+    });
+    load();
+    assert_eq!(
+        ranked().1,
+        Some(("embedded_1".to_owned(), SubtitlesMatch::PreviousTrack)),
+        "The previous embedded track is kept"
+    );
+}
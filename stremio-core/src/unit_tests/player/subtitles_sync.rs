@@ -0,0 +1,238 @@
+use crate::{
+    constants::{META_RESOURCE_NAME, STREAM_RESOURCE_NAME},
+    models::{
+        common::Loadable,
+        ctx::Ctx,
+        player::{Player, Selected, SubtitlesReference},
+    },
+    runtime::{
+        msg::{Action, ActionLoad, ActionPlayer},
+        EnvFutureExt, Runtime, RuntimeAction, TryEnvFuture,
+    },
+    types::{
+        addon::{ResourcePath, ResourceRequest, ResourceResponse, ResourceResponseCache},
+        resource::{
+            MetaItem, MetaItemPreview, Stream, StreamBehaviorHints, StreamSource, Subtitles, Video,
+        },
+        subtitles::{FramerateCorrection, SubtitlesCue, SubtitlesError},
+    },
+    unit_tests::{default_fetch_handler, Request, TestEnv, FETCH_HANDLER},
+};
+use futures::future;
+use std::any::Any;
+use stremio_derive::Model;
+
+/// Irregularly spaced cues, so that only one offset aligns them
+fn cues() -> Vec<SubtitlesCue> {
+    (0..12)
+        .map(|index: u64| {
+            let start = 10_000 + index * 4_000 + index * index * 137 % 1_000;
+            SubtitlesCue {
+                start,
+                end: start + 1_000 + index * 311 % 800,
+                text: format!("Line {index}"),
+            }
+        })
+        .collect()
+}
+
+fn srt(cues: &[SubtitlesCue]) -> String {
+    cues.iter()
+        .enumerate()
+        .map(|(index, cue)| {
+            format!(
+                "{}\n{} --> {}\n{}\n\n",
+                index + 1,
+                SubtitlesCue::format_timestamp(cue.start).replace('.', ","),
+                SubtitlesCue::format_timestamp(cue.end).replace('.', ","),
+                cue.text
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn subtitles_sync() {
+    #[derive(Model, Default, Clone, Debug)]
+    #[model(TestEnv)]
+    struct TestModel {
+        ctx: Ctx,
+        player: Player,
+    }
+
+    fn fetch_handler(request: Request) -> TryEnvFuture<Box<dyn Any + Send>> {
+        match request {
+            Request { url, .. } if url == "https://transport_url/meta/movie/tt123456.json" => {
+                future::ok(
+                    Box::new(ResourceResponseCache::from(ResourceResponse::Meta {
+                        meta: MetaItem {
+                            preview: MetaItemPreview {
+                                id: "tt123456".to_owned(),
+                                r#type: "movie".to_owned(),
+                                ..Default::default()
+                            },
+                            videos: vec![Video {
+                                id: "tt123456".to_owned(),
+                                ..Default::default()
+                            }],
+                        },
+                    })) as Box<dyn Any + Send>,
+                )
+                .boxed_env()
+            }
+            Request { url, .. } if url == "https://subtitles/eng.srt" => {
+                future::ok(Box::new(srt(&cues()).into_bytes()) as Box<dyn Any + Send>).boxed_env()
+            }
+            _ => default_fetch_handler(request),
+        }
+    }
+
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    *FETCH_HANDLER.write().unwrap() = Box::new(fetch_handler);
+    let (runtime, _rx) = Runtime::<TestEnv, _>::new(TestModel::default(), vec![], 1000);
+
+    let meta_request = ResourceRequest {
+        base: "https://transport_url/manifest.json".parse().unwrap(),
+        path: ResourcePath {
+            resource: META_RESOURCE_NAME.to_owned(),
+            r#type: "movie".to_owned(),
+            id: "tt123456".to_owned(),
+            extra: vec![],
+        },
+    };
+    let stream_request = ResourceRequest {
+        base: "https://transport_url/manifest.json".parse().unwrap(),
+        path: ResourcePath {
+            resource: STREAM_RESOURCE_NAME.to_owned(),
+            r#type: "movie".to_owned(),
+            id: "tt123456".to_owned(),
+            extra: vec![],
+        },
+    };
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Load(ActionLoad::Player(Box::new(Selected {
+                stream: Stream {
+                    source: StreamSource::Url {
+                        url: "https://source_url".parse().unwrap(),
+                    },
+                    name: None,
+                    description: None,
+                    thumbnail: None,
+                    subtitles: vec![],
+                    behavior_hints: StreamBehaviorHints::default(),
+                },
+                stream_request: Some(stream_request),
+                meta_request: Some(meta_request),
+                subtitles_path: None,
+            }))),
+        });
+    });
+
+    let subtitles = Subtitles {
+        id: "eng".to_owned(),
+        lang: "eng".to_owned(),
+        url: "https://subtitles/eng.srt".parse().unwrap(),
+    };
+    // the embedded track is 1.5 seconds late and the subtitles are made for a 25 fps video
+    let drift = 25.0 / 23.976;
+    let reference = cues()
+        .into_iter()
+        .map(|cue| SubtitlesCue {
+            start: (cue.start as f64 * drift).round() as u64 + 1_500,
+            end: (cue.end as f64 * drift).round() as u64 + 1_500,
+            ..cue
+        })
+        .collect();
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Player(ActionPlayer::SyncSubtitles {
+                subtitles: subtitles.to_owned(),
+                reference: SubtitlesReference::Embedded(reference),
+            }),
+        });
+    });
+    {
+        let model = runtime.model().unwrap();
+        let proposal = model
+            .player
+            .subtitles_sync
+            .as_ref()
+            .expect("A proposal for the subtitles");
+        assert_eq!(proposal.subtitles, subtitles);
+        match &proposal.sync {
+            Loadable::Ready(sync) => {
+                assert!((sync.drift - drift).abs() < 0.0001);
+                assert!((sync.offset - 1_500).abs() <= 10);
+                assert!(sync.score > 0.99);
+            }
+            sync => panic!("Unexpected sync: {sync:?}"),
+        };
+    }
+
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Player(ActionPlayer::ApplySubtitlesSync),
+        });
+    });
+    {
+        let model = runtime.model().unwrap();
+        let state = model
+            .player
+            .stream_state
+            .as_ref()
+            .expect("The stream state is stored");
+        let sync = match model
+            .player
+            .subtitles_sync
+            .as_ref()
+            .map(|proposal| &proposal.sync)
+        {
+            Some(Loadable::Ready(sync)) => sync,
+            sync => panic!("Unexpected sync: {sync:?}"),
+        };
+        assert_eq!(state.subtitle_delay, Some(sync.offset));
+        assert_eq!(
+            state.subtitle_framerate,
+            Some(FramerateCorrection {
+                from: sync.drift,
+                to: 1.0
+            }),
+            "The drift is corrected with the offset"
+        );
+        assert_eq!(
+            state.subtitle_track.as_ref().map(|track| &track.id),
+            Some(&subtitles.id),
+            "The delay belongs to the aligned subtitles"
+        );
+    }
+
+    // no subtitles were loaded for the hash of the video
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Player(ActionPlayer::SyncSubtitles {
+                subtitles: subtitles.to_owned(),
+                reference: SubtitlesReference::Subtitles(Subtitles {
+                    id: "spa".to_owned(),
+                    lang: "spa".to_owned(),
+                    url: "https://subtitles/spa.srt".parse().unwrap(),
+                }),
+            }),
+        });
+    });
+    assert_eq!(
+        runtime
+            .model()
+            .unwrap()
+            .player
+            .subtitles_sync
+            .as_ref()
+            .map(|proposal| &proposal.sync),
+        Some(&Loadable::Err(SubtitlesError::VideoHashMismatch)),
+        "The reference subtitles are not synced to"
+    );
+}
@@ -0,0 +1,312 @@
+use std::any::Any;
+
+use futures::future;
+use semver::Version;
+use stremio_derive::Model;
+use url::Url;
+
+use crate::addon_transport::AddonBuilder;
+use crate::constants::{
+    CAST_LINK_CATEGORY, CATALOG_RESOURCE_NAME, GENRES_LINK_CATEGORY, META_RESOURCE_NAME,
+    TASTE_STORAGE_KEY,
+};
+use crate::models::catalogs_with_extra::{
+    CatalogsWithExtra, Selected as CatalogsWithExtraSelected,
+};
+use crate::models::ctx::Ctx;
+use crate::models::meta_details::{MetaDetails, Selected as MetaDetailsSelected};
+use crate::runtime::msg::{Action, ActionCatalogsWithExtra, ActionCtx, ActionLoad};
+use crate::runtime::{Env, EnvFutureExt, Runtime, RuntimeAction, TryEnvFuture};
+use crate::types::addon::{Descriptor, Manifest, ManifestCatalog, ResourcePath, ResourceResponse};
+use crate::types::library::{LibraryBucket, LibraryItem, LibraryItemState};
+use crate::types::profile::{Auth, Profile};
+use crate::types::rating::{Rating, RatingGetStatusResponse};
+use crate::types::resource::{Link, MetaItem, MetaItemPreview};
+use crate::types::taste::{RecommendationReason, TasteBucket};
+use crate::unit_tests::{
+    default_fetch_handler, Request, TestEnv, ADDON_TRANSPORTS, FETCH_HANDLER, STORAGE,
+};
+
+#[derive(Model, Default, Clone, Debug)]
+#[model(TestEnv)]
+struct TestModel {
+    ctx: Ctx,
+    meta_details: MetaDetails,
+    board: CatalogsWithExtra,
+}
+
+fn fetch_handler(request: Request) -> TryEnvFuture<Box<dyn Any + Send>> {
+    match request {
+        Request { url, method, .. }
+            if url.starts_with("https://likes.stremio.com/api/get_status") && method == "GET" =>
+        {
+            future::ok(Box::new(RatingGetStatusResponse {
+                status: Some(Rating::Loved),
+            }) as Box<dyn Any + Send>)
+            .boxed_env()
+        }
+        _ => default_fetch_handler(request),
+    }
+}
+
+fn meta_item(id: &str, name: &str, genres: &[&str], cast: &[&str]) -> MetaItemPreview {
+    let link = |category: &str, name: &str| Link {
+        name: name.to_owned(),
+        category: category.to_owned(),
+        url: Url::parse("stremio:///search").unwrap(),
+    };
+    MetaItemPreview {
+        id: id.to_owned(),
+        r#type: "movie".to_owned(),
+        name: name.to_owned(),
+        links: genres
+            .iter()
+            .map(|genre| link(GENRES_LINK_CATEGORY, genre))
+            .chain(cast.iter().map(|name| link(CAST_LINK_CATEGORY, name)))
+            .collect(),
+        ..Default::default()
+    }
+}
+
+fn inception() -> MetaItemPreview {
+    meta_item(
+        "tt1375666",
+        "Inception",
+        &["Sci-Fi", "Thriller"],
+        &["Leonardo DiCaprio"],
+    )
+}
+
+fn manifest() -> Manifest {
+    Manifest {
+        id: "movies".to_owned(),
+        version: Version::new(1, 0, 0),
+        name: "movies".to_owned(),
+        contact_email: None,
+        description: None,
+        logo: None,
+        background: None,
+        types: vec!["movie".to_owned()],
+        resources: vec![CATALOG_RESOURCE_NAME.into(), META_RESOURCE_NAME.into()],
+        id_prefixes: Some(vec!["tt".to_owned()]),
+        catalogs: vec![ManifestCatalog {
+            id: "top".to_owned(),
+            r#type: "movie".to_owned(),
+            name: None,
+            extra: Default::default(),
+        }],
+        addon_catalogs: vec![],
+        behavior_hints: Default::default(),
+    }
+}
+
+fn register_addon() {
+    let addon = AddonBuilder::new(manifest())
+        .define_catalog_handler(|_| {
+            future::ok(ResourceResponse::Metas {
+                metas: vec![
+                    inception(),
+                    meta_item("tt0816692", "Interstellar", &["Sci-Fi"], &[]),
+                    meta_item(
+                        "tt1663202",
+                        "The Revenant",
+                        &["Drama"],
+                        &["Leonardo DiCaprio"],
+                    ),
+                    meta_item("tt2543164", "Arrival", &["Sci-Fi", "Drama"], &[]),
+                    meta_item("tt0317219", "Cars", &["Animation"], &[]),
+                ],
+            })
+            .boxed_env()
+        })
+        .define_meta_handler(|_| {
+            future::ok(ResourceResponse::Meta {
+                meta: MetaItem {
+                    preview: inception(),
+                    videos: vec![],
+                },
+            })
+            .boxed_env()
+        })
+        .build()
+        .unwrap();
+    let registry = ADDON_TRANSPORTS.read().unwrap().to_owned();
+    *ADDON_TRANSPORTS.write().unwrap() =
+        registry.register("movies", move |_| Box::new(addon.to_owned()));
+}
+
+#[test]
+fn recommendations() {
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    *FETCH_HANDLER.write().unwrap() = Box::new(fetch_handler);
+    register_addon();
+    let interstellar = LibraryItem {
+        id: "tt0816692".to_owned(),
+        name: "Interstellar".to_owned(),
+        r#type: "movie".to_owned(),
+        poster: None,
+        poster_shape: Default::default(),
+        removed: true,
+        temp: true,
+        ctime: None,
+        mtime: TestEnv::now(),
+        state: LibraryItemState {
+            times_watched: 1,
+            ..Default::default()
+        },
+        behavior_hints: Default::default(),
+    };
+    let (runtime, _rx) = Runtime::<TestEnv, _>::new(
+        TestModel {
+            ctx: Ctx {
+                profile: Profile {
+                    auth: Some(Auth::default()),
+                    addons: vec![Descriptor {
+                        manifest: manifest(),
+                        transport_url: Url::parse("movies://addon/manifest.json").unwrap(),
+                        flags: Default::default(),
+                    }],
+                    ..Default::default()
+                },
+                library: LibraryBucket::new(None, vec![interstellar]),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        vec![],
+        1000,
+    );
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Load(ActionLoad::MetaDetails(MetaDetailsSelected {
+                meta_path: ResourcePath::without_extra(META_RESOURCE_NAME, "movie", "tt1375666"),
+                stream_path: None,
+                guess_stream: false,
+            })),
+        })
+    });
+    {
+        let model = runtime.model().unwrap();
+        let item = model.ctx.taste.items.get("tt1375666").expect("Taste item");
+        assert_eq!(
+            item.rating,
+            Some(Rating::Loved),
+            "rating of the loaded meta item"
+        );
+        assert_eq!(item.cast, vec!["Leonardo DiCaprio".to_owned()]);
+        assert_eq!(
+            STORAGE
+                .read()
+                .unwrap()
+                .get(TASTE_STORAGE_KEY)
+                .map(|data| serde_json::from_str::<TasteBucket>(data).unwrap()),
+            Some(model.ctx.taste.to_owned()),
+            "taste persisted"
+        );
+    }
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Load(ActionLoad::CatalogsWithExtra(CatalogsWithExtraSelected {
+                r#type: None,
+                extra: vec![],
+            })),
+        });
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::CatalogsWithExtra(ActionCatalogsWithExtra::LoadRange(0..1)),
+        });
+    });
+    let model = runtime.model().unwrap();
+    assert_eq!(
+        model
+            .board
+            .recommendations
+            .iter()
+            .map(|recommendation| (
+                recommendation.meta_item.name.as_str(),
+                &recommendation.reason
+            ))
+            .collect::<Vec<_>>(),
+        vec![
+            (
+                "The Revenant",
+                &RecommendationReason {
+                    id: "tt1375666".to_owned(),
+                    name: "Inception".to_owned(),
+                    rating: Some(Rating::Loved),
+                    genres: vec![],
+                    cast: vec!["Leonardo DiCaprio".to_owned()],
+                }
+            ),
+            (
+                "Arrival",
+                &RecommendationReason {
+                    id: "tt1375666".to_owned(),
+                    name: "Inception".to_owned(),
+                    rating: Some(Rating::Loved),
+                    genres: vec!["Sci-Fi".to_owned()],
+                    cast: vec![],
+                }
+            ),
+        ],
+        "the watched and rated items and the ones without anything in common are not recommended"
+    );
+}
+
+#[test]
+fn taste_seeded_from_library() {
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    let library_item = |id: &str, name: &str, times_watched: u32| LibraryItem {
+        id: id.to_owned(),
+        name: name.to_owned(),
+        r#type: "movie".to_owned(),
+        poster: None,
+        poster_shape: Default::default(),
+        removed: false,
+        temp: false,
+        ctime: None,
+        mtime: TestEnv::now(),
+        state: LibraryItemState {
+            times_watched,
+            ..Default::default()
+        },
+        behavior_hints: Default::default(),
+    };
+    let (runtime, _rx) = Runtime::<TestEnv, _>::new(
+        TestModel {
+            ctx: Ctx {
+                library: LibraryBucket::new(
+                    None,
+                    vec![
+                        library_item("tt0816692", "Interstellar", 1),
+                        library_item("tt1663202", "The Revenant", 0),
+                    ],
+                ),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        vec![],
+        1000,
+    );
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Ctx(ActionCtx::AddToLibrary(meta_item(
+                "tt2543164",
+                "Arrival",
+                &["Sci-Fi", "Drama"],
+                &[],
+            ))),
+        })
+    });
+    let model = runtime.model().unwrap();
+    assert_eq!(
+        model.ctx.taste.items.keys().collect::<Vec<_>>(),
+        vec!["tt0816692"],
+        "only the watched library items are seeded"
+    );
+    assert!(model.ctx.taste.items["tt0816692"].watched);
+}
@@ -3,7 +3,10 @@ use crate::types::addon::{
 };
 use crate::types::api::{APIError, AuthRequest};
 use crate::types::library::LibraryItemState;
-use crate::types::profile::{Auth, AuthKey, GDPRConsent, Settings, User};
+use crate::types::profile::{
+    Auth, AuthKey, GDPRConsent, ParentalControls, ParentalRestrictions, Settings,
+    StreamPreferences, User,
+};
 use crate::types::resource::{
     MetaItem, MetaItemBehaviorHints, PosterShape, SeriesInfo, StreamBehaviorHints, StreamSource,
     Subtitles,
@@ -376,7 +379,7 @@ impl DefaultTokens for Settings {
         vec![
             Token::Struct {
                 name: "Settings",
-                len: 33,
+                len: 36,
             },
             Token::Str("interfaceLanguage"),
             Token::Str("eng"),
@@ -449,6 +452,86 @@ impl DefaultTokens for Settings {
             Token::Bool(false),
             Token::Str("sendCrashReports"),
             Token::Bool(true),
+            Token::Str("streamPreferences"),
+        ]
+        .into_iter()
+        .chain(StreamPreferences::default_tokens())
+        .chain([
+            Token::Str("autoPlayPolicy"),
+            Token::UnitVariant {
+                name: "AutoPlayPolicy",
+                variant: "StrictBingeGroup",
+            },
+            Token::Str("streamHealthCheck"),
+            Token::Bool(false),
+            Token::StructEnd,
+        ])
+        .collect()
+    }
+}
+
+impl DefaultTokens for StreamPreferences {
+    fn default_tokens() -> Vec<Token> {
+        vec![
+            Token::Struct {
+                name: "StreamPreferences",
+                len: 5,
+            },
+            Token::Str("maxResolution"),
+            Token::None,
+            Token::Str("preferHdr"),
+            Token::Bool(false),
+            Token::Str("preferredCodecs"),
+            Token::Seq { len: Some(0) },
+            Token::SeqEnd,
+            Token::Str("maxSize"),
+            Token::None,
+            Token::Str("preferredLanguages"),
+            Token::Seq { len: Some(0) },
+            Token::SeqEnd,
+            Token::StructEnd,
+        ]
+    }
+}
+
+impl DefaultTokens for ParentalControls {
+    fn default_tokens() -> Vec<Token> {
+        [
+            vec![
+                Token::Struct {
+                    name: "ParentalControls",
+                    len: 4,
+                },
+                Token::Str("locked"),
+                Token::Bool(false),
+                Token::Str("failedPinAttempts"),
+                Token::U32(0),
+                Token::Str("pinRetryAt"),
+                Token::None,
+                Token::Str("restrictions"),
+            ],
+            ParentalRestrictions::default_tokens(),
+            vec![Token::StructEnd],
+        ]
+        .concat()
+    }
+}
+
+impl DefaultTokens for ParentalRestrictions {
+    fn default_tokens() -> Vec<Token> {
+        vec![
+            Token::Struct {
+                name: "ParentalRestrictions",
+                len: 3,
+            },
+            Token::Str("maxAge"),
+            Token::None,
+            Token::Str("blockedGenres"),
+            Token::Seq { len: Some(0) },
+            Token::SeqEnd,
+            Token::Str("blockedTypes"),
+            Token::Seq { len: Some(0) },
+            Token::SeqEnd,
             Token::StructEnd,
         ]
     }
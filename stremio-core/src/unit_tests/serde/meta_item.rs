@@ -24,6 +24,7 @@ fn meta_item() {
                     links: vec![],
                     trailer_streams: vec![],
                     behavior_hints: MetaItemBehaviorHints::default(),
+                    certification: None,
                 },
                 videos: vec![],
             },
@@ -43,6 +44,7 @@ fn meta_item() {
                     links: vec![],
                     trailer_streams: vec![],
                     behavior_hints: MetaItemBehaviorHints::default(),
+                    certification: None,
                 },
                 videos: vec![],
             },
@@ -168,6 +170,7 @@ fn meta_item() {
                     links: vec![],
                     trailer_streams: vec![],
                     behavior_hints: MetaItemBehaviorHints::default(),
+                    certification: None,
                 },
                 videos: vec![],
             },
@@ -187,6 +190,7 @@ fn meta_item() {
                     links: vec![],
                     trailer_streams: vec![],
                     behavior_hints: MetaItemBehaviorHints::default(),
+                    certification: None,
                 },
                 videos: vec![],
             },
@@ -248,6 +252,7 @@ fn meta_item_de_urls_none_when_empty() {
                 links: vec![],
                 trailer_streams: vec![],
                 behavior_hints: MetaItemBehaviorHints::default(),
+                certification: None,
             },
             videos: vec![],
         },
@@ -26,6 +26,7 @@ fn meta_item_preview_serialize() {
                 links: vec![],
                 trailer_streams: vec![],
                 behavior_hints: MetaItemBehaviorHints::default(),
+                certification: None,
             },
             MetaItemPreview {
                 id: "id".into(),
@@ -42,6 +43,7 @@ fn meta_item_preview_serialize() {
                 links: vec![],
                 trailer_streams: vec![],
                 behavior_hints: MetaItemBehaviorHints::default(),
+                certification: None,
             },
         ]
         .readable(),
@@ -156,6 +158,7 @@ fn meta_item_preview_de() {
                 links: vec![],
                 trailer_streams: vec![],
                 behavior_hints: MetaItemBehaviorHints::default(),
+                certification: None,
             },
             MetaItemPreview {
                 id: "id".into(),
@@ -172,6 +175,7 @@ fn meta_item_preview_de() {
                 links: vec![],
                 trailer_streams: vec![],
                 behavior_hints: MetaItemBehaviorHints::default(),
+                certification: None,
             },
         ]
         .readable(),
@@ -280,6 +284,7 @@ fn meta_item_preview_de_minimal() {
             links: vec![],
             trailer_streams: vec![],
             behavior_hints: MetaItemBehaviorHints::default(),
+            certification: None,
         }
         .readable(),
         &[
@@ -314,6 +319,7 @@ fn meta_item_preview_de_null() {
             links: vec![],
             trailer_streams: vec![],
             behavior_hints: MetaItemBehaviorHints::default(),
+            certification: None,
         }
         .readable(),
         &[
@@ -372,6 +378,7 @@ fn meta_item_preview_de_numeric_imdb() {
             }],
             trailer_streams: vec![],
             behavior_hints: MetaItemBehaviorHints::default(),
+            certification: None,
         }
         .readable(),
         &[
@@ -440,6 +447,7 @@ fn meta_item_preview_de_legacy_links() {
                 }
             ],
             behavior_hints: MetaItemBehaviorHints::default(),
+            certification: None,
         }.readable(),
         &[
             Token::Struct {
@@ -519,6 +527,7 @@ fn meta_item_preview_de_ignore_legacy_when_links() {
                 behavior_hints: StreamBehaviorHints::default(),
             }],
             behavior_hints: MetaItemBehaviorHints::default(),
+            certification: None,
         }.readable(),
         &[
             Token::Struct {
@@ -1,4 +1,8 @@
-use crate::types::profile::{FrameRateMatchingStrategy, Settings};
+use crate::types::profile::{
+    AutoPlayPolicy, FrameRateMatchingStrategy, Settings, StreamPreferences,
+};
+use crate::types::resource::{StreamResolution, VideoCodec};
+use crate::unit_tests::serde::default_tokens_ext::DefaultTokens;
 use chrono::{TimeZone, Utc};
 use serde_test::{assert_de_tokens, assert_tokens, Token};
 use url::Url;
@@ -42,11 +46,20 @@ fn settings() {
             ),
             server_in_foreground: false,
             send_crash_reports: true,
+            stream_preferences: StreamPreferences {
+                max_resolution: Some(StreamResolution::FullHD),
+                prefer_hdr: true,
+                preferred_codecs: vec![VideoCodec::H265],
+                max_size: Some(1),
+                preferred_languages: vec!["eng".to_owned()],
+            },
+            auto_play_policy: AutoPlayPolicy::BestMatch,
+            stream_health_check: true,
         },
         &[
             Token::Struct {
                 name: "Settings",
-                len: 33,
+                len: 36,
             },
             Token::Str("interfaceLanguage"),
             Token::Str("interface_language"),
@@ -124,6 +137,41 @@ fn settings() {
             Token::Bool(false),
             Token::Str("sendCrashReports"),
             Token::Bool(true),
+            Token::Str("streamPreferences"),
+            Token::Struct {
+                name: "StreamPreferences",
+                len: 5,
+            },
+            Token::Str("maxResolution"),
+            Token::Some,
+            Token::UnitVariant {
+                name: "StreamResolution",
+                variant: "1080p",
+            },
+            Token::Str("preferHdr"),
+            Token::Bool(true),
+            Token::Str("preferredCodecs"),
+            Token::Seq { len: Some(1) },
+            Token::UnitVariant {
+                name: "VideoCodec",
+                variant: "H265",
+            },
+            Token::SeqEnd,
+            Token::Str("maxSize"),
+            Token::Some,
+            Token::U64(1),
+            Token::Str("preferredLanguages"),
+            Token::Seq { len: Some(1) },
+            Token::Str("eng"),
+            Token::SeqEnd,
+            Token::StructEnd,
+            Token::Str("autoPlayPolicy"),
+            Token::UnitVariant {
+                name: "AutoPlayPolicy",
+                variant: "BestMatch",
+            },
+            Token::Str("streamHealthCheck"),
+            Token::Bool(true),
             Token::StructEnd,
         ],
     );
@@ -136,7 +184,7 @@ fn settings_de() {
         &[
             Token::Struct {
                 name: "Settings",
-                len: 33,
+                len: 36,
             },
             Token::Str("interfaceLanguage"),
             Token::Str("eng"),
@@ -205,7 +253,20 @@ fn settings_de() {
             Token::Bool(false),
             Token::Str("sendCrashReports"),
             Token::Bool(true),
+            Token::Str("streamPreferences"),
+        ]
+        .into_iter()
+        .chain(StreamPreferences::default_tokens())
+        .chain([
+            Token::Str("autoPlayPolicy"),
+            Token::UnitVariant {
+                name: "AutoPlayPolicy",
+                variant: "StrictBingeGroup",
+            },
+            Token::Str("streamHealthCheck"),
+            Token::Bool(false),
             Token::StructEnd,
-        ],
+        ])
+        .collect::<Vec<_>>(),
     );
 }
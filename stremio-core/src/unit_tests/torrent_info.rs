@@ -0,0 +1,164 @@
+use crate::models::common::Loadable;
+use crate::models::ctx::Ctx;
+use crate::models::torrent_info::{Selected, TorrentContent, TorrentInfo, TorrentSource};
+use crate::runtime::msg::{Action, ActionLoad, ActionTorrentInfo};
+use crate::runtime::{Runtime, RuntimeAction};
+use crate::types::resource::StreamSource;
+use crate::types::torrent::TorrentError;
+use crate::unit_tests::TestEnv;
+use stremio_derive::Model;
+
+const TORRENT: &[u8] = b"d8:announce18:udp://tracker:13374:infod5:filesld6:lengthi100e4:pathl6:Sample10:sample.mkveed6:lengthi5000e4:pathl9:movie.mkveed6:lengthi10e4:pathl8:info.txteee4:name5:Movie12:piece lengthi16384e6:pieces20:aaaaaaaaaaaaaaaaaaaaee";
+
+#[derive(Model, Default, Clone, Debug)]
+#[model(TestEnv)]
+struct TestModel {
+    ctx: Ctx,
+    torrent_info: TorrentInfo,
+}
+
+fn dispatch(runtime: &Runtime<TestEnv, TestModel>, action: Action) {
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action,
+        })
+    });
+}
+
+#[test]
+fn torrent_info_files() {
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    let (runtime, _rx) = Runtime::<TestEnv, _>::new(TestModel::default(), vec![], 1000);
+    dispatch(
+        &runtime,
+        Action::Load(ActionLoad::TorrentInfo(Selected {
+            source: TorrentSource::File(TORRENT.to_vec()),
+            file_must_include: vec![],
+        })),
+    );
+    {
+        let torrent_info = runtime.model().unwrap().torrent_info.to_owned();
+        let metainfo = match torrent_info.content {
+            Some(Loadable::Ready(TorrentContent::Torrent(metainfo))) => metainfo,
+            content => panic!("torrent not parsed: {content:?}"),
+        };
+        assert_eq!(
+            metainfo.info_hash.to_string(),
+            "17ae280a582ac6ee56d30abef1eec896c5f000ff",
+            "info hash of the info dictionary"
+        );
+        assert_eq!(metainfo.name, "Movie");
+        assert_eq!(metainfo.announce, vec!["udp://tracker:1337".to_owned()]);
+        assert_eq!(metainfo.pieces_count, 1);
+        assert_eq!(metainfo.total_length(), 5110);
+        assert_eq!(
+            torrent_info
+                .files
+                .iter()
+                .map(|file| (file.file.path.as_str(), file.file.offset, file.is_video))
+                .collect::<Vec<_>>(),
+            vec![
+                ("Sample/sample.mkv", 0, true),
+                ("movie.mkv", 100, true),
+                ("info.txt", 5100, false),
+            ],
+            "files parsed"
+        );
+        assert_eq!(torrent_info.file_idx, Some(1), "largest video selected");
+    }
+    dispatch(
+        &runtime,
+        Action::TorrentInfo(ActionTorrentInfo::SelectFile(5)),
+    );
+    assert_eq!(
+        runtime.model().unwrap().torrent_info.file_idx,
+        Some(1),
+        "unknown file not selected"
+    );
+    dispatch(
+        &runtime,
+        Action::Load(ActionLoad::TorrentInfo(Selected {
+            source: TorrentSource::File(TORRENT.to_vec()),
+            file_must_include: vec!["SAMPLE".to_owned()],
+        })),
+    );
+    {
+        let torrent_info = runtime.model().unwrap().torrent_info.to_owned();
+        assert_eq!(
+            torrent_info
+                .files
+                .iter()
+                .map(|file| file.matches)
+                .collect::<Vec<_>>(),
+            vec![true, false, false],
+            "files matching file_must_include"
+        );
+        assert_eq!(torrent_info.file_idx, Some(0), "matching video selected");
+    }
+    dispatch(
+        &runtime,
+        Action::TorrentInfo(ActionTorrentInfo::SelectFile(2)),
+    );
+    let stream_source = runtime.model().unwrap().torrent_info.stream_source();
+    match stream_source {
+        Some(StreamSource::Torrent {
+            file_idx,
+            announce,
+            file_must_include,
+            ..
+        }) => {
+            assert_eq!(file_idx, Some(2), "selected file played");
+            assert_eq!(announce, vec!["udp://tracker:1337".to_owned()]);
+            assert_eq!(file_must_include, vec!["SAMPLE".to_owned()]);
+        }
+        source => panic!("unexpected stream source: {source:?}"),
+    }
+}
+
+#[test]
+fn torrent_info_magnet() {
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    let (runtime, _rx) = Runtime::<TestEnv, _>::new(TestModel::default(), vec![], 1000);
+    dispatch(
+        &runtime,
+        Action::Load(ActionLoad::TorrentInfo(Selected {
+            source: TorrentSource::Magnet(
+                "magnet:?xt=urn:btih:344JFFKIJMYFTJDSNXDNRJL7OG5V6TEB&dn=Movie"
+                    .parse()
+                    .unwrap(),
+            ),
+            file_must_include: vec![],
+        })),
+    );
+    {
+        let torrent_info = runtime.model().unwrap().torrent_info.to_owned();
+        match torrent_info.content {
+            Some(Loadable::Ready(TorrentContent::Magnet(magnet_link))) => {
+                assert_eq!(
+                    magnet_link.info_hash.to_string(),
+                    "df389295484b3059a4726dc6d8a57f71bb5f4c81",
+                    "base32 info hash decoded"
+                );
+                assert_eq!(magnet_link.name.as_deref(), Some("Movie"));
+            }
+            content => panic!("magnet not parsed: {content:?}"),
+        };
+        assert!(torrent_info.files.is_empty(), "files of magnets unknown");
+        assert_eq!(torrent_info.file_idx, None);
+    }
+    dispatch(
+        &runtime,
+        Action::Load(ActionLoad::TorrentInfo(Selected {
+            source: TorrentSource::File(b"d4:infod4:name5:Movieee".to_vec()),
+            file_must_include: vec![],
+        })),
+    );
+    assert!(
+        matches!(
+            runtime.model().unwrap().torrent_info.content,
+            Some(Loadable::Err(TorrentError::InvalidMetainfo(_)))
+        ),
+        "invalid info dictionary"
+    );
+}
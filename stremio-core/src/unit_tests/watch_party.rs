@@ -0,0 +1,181 @@
+use crate::models::ctx::Ctx;
+use crate::models::player::Selected as PlayerSelected;
+use crate::models::watch_party::{Selected, WatchParty, WatchPartyCorrection};
+use crate::runtime::msg::{Action, ActionLoad, ActionWatchParty};
+use crate::runtime::{Env, Runtime, RuntimeAction};
+use crate::types::resource::{Stream, StreamSource};
+use crate::unit_tests::{TestEnv, NOW};
+use stremio_derive::Model;
+
+#[derive(Model, Default, Clone, Debug)]
+#[model(TestEnv)]
+struct TestModel {
+    ctx: Ctx,
+    host: WatchParty,
+    guest: WatchParty,
+}
+
+fn dispatch(runtime: &Runtime<TestEnv, TestModel>, field: TestModelField, action: Action) {
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: Some(field),
+            action,
+        })
+    });
+}
+
+fn advance(milliseconds: i64) {
+    let now = TestEnv::now() + chrono::Duration::milliseconds(milliseconds);
+    *NOW.write().unwrap() = now;
+}
+
+fn selected(member_id: &str, host: bool) -> Selected {
+    Selected {
+        party_id: "party".to_owned(),
+        member_id: member_id.to_owned(),
+        host,
+    }
+}
+
+#[test]
+fn watch_party_sync() {
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    let (runtime, _rx) = Runtime::<TestEnv, _>::new(TestModel::default(), vec![], 1000);
+    let stream = Stream {
+        source: StreamSource::Url {
+            url: "https://source_url".parse().unwrap(),
+        },
+        name: None,
+        description: None,
+        thumbnail: None,
+        subtitles: vec![],
+        behavior_hints: Default::default(),
+    };
+    dispatch(
+        &runtime,
+        TestModelField::Host,
+        Action::Load(ActionLoad::WatchParty(selected("host", true))),
+    );
+    dispatch(
+        &runtime,
+        TestModelField::Host,
+        Action::WatchParty(ActionWatchParty::SelectStream(Box::new(PlayerSelected {
+            stream: stream.to_owned(),
+            stream_request: None,
+            meta_request: None,
+            subtitles_path: None,
+        }))),
+    );
+    dispatch(
+        &runtime,
+        TestModelField::Host,
+        Action::WatchParty(ActionWatchParty::PausedChanged {
+            paused: false,
+            time: 10_000,
+        }),
+    );
+    dispatch(
+        &runtime,
+        TestModelField::Guest,
+        Action::Load(ActionLoad::WatchParty(selected("guest", false))),
+    );
+    dispatch(
+        &runtime,
+        TestModelField::Host,
+        Action::WatchParty(ActionWatchParty::Poll),
+    );
+    dispatch(
+        &runtime,
+        TestModelField::Guest,
+        Action::WatchParty(ActionWatchParty::Poll),
+    );
+    {
+        let model = runtime.model().unwrap();
+        assert_eq!(model.host.members.len(), 1, "host sees the guest");
+        assert_eq!(model.guest.members.len(), 1, "guest sees the host");
+        assert_eq!(
+            model.guest.stream.as_ref().map(|stream| &stream.stream),
+            Some(&stream),
+            "guest follows the stream of the host"
+        );
+        assert_eq!(
+            model.guest.correction,
+            Some(WatchPartyCorrection::Play { time: 10_000 }),
+            "guest starts playing at the time of the host"
+        );
+        assert_eq!(
+            model.guest.members[0].clock_offset,
+            Some(0),
+            "clock offset estimated"
+        );
+    }
+    dispatch(
+        &runtime,
+        TestModelField::Guest,
+        Action::WatchParty(ActionWatchParty::PausedChanged {
+            paused: false,
+            time: 10_000,
+        }),
+    );
+    assert_eq!(
+        runtime.model().unwrap().guest.correction,
+        None,
+        "correction followed"
+    );
+    dispatch(
+        &runtime,
+        TestModelField::Host,
+        Action::WatchParty(ActionWatchParty::Poll),
+    );
+    assert_eq!(
+        runtime.model().unwrap().host.correction,
+        None,
+        "followed correction not echoed back to the host"
+    );
+    advance(6_000);
+    dispatch(
+        &runtime,
+        TestModelField::Guest,
+        Action::WatchParty(ActionWatchParty::TimeChanged { time: 15_000 }),
+    );
+    dispatch(
+        &runtime,
+        TestModelField::Host,
+        Action::WatchParty(ActionWatchParty::TimeChanged { time: 16_000 }),
+    );
+    dispatch(
+        &runtime,
+        TestModelField::Guest,
+        Action::WatchParty(ActionWatchParty::Poll),
+    );
+    assert_eq!(
+        runtime.model().unwrap().guest.correction,
+        Some(WatchPartyCorrection::PlaybackRate { rate: 1.05 }),
+        "small drift corrected with the playback rate"
+    );
+    dispatch(
+        &runtime,
+        TestModelField::Host,
+        Action::WatchParty(ActionWatchParty::Seek { time: 60_000 }),
+    );
+    dispatch(
+        &runtime,
+        TestModelField::Guest,
+        Action::WatchParty(ActionWatchParty::Poll),
+    );
+    assert_eq!(
+        runtime.model().unwrap().guest.correction,
+        Some(WatchPartyCorrection::Seek { time: 60_000 }),
+        "guest seeks with the host"
+    );
+    dispatch(&runtime, TestModelField::Guest, Action::Unload);
+    dispatch(
+        &runtime,
+        TestModelField::Host,
+        Action::WatchParty(ActionWatchParty::Poll),
+    );
+    assert!(
+        runtime.model().unwrap().host.members.is_empty(),
+        "guest left the party"
+    );
+}
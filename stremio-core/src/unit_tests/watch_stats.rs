@@ -0,0 +1,335 @@
+use chrono::{Duration, TimeZone, Utc, Weekday};
+use futures::future;
+use semver::Version;
+use stremio_derive::Model;
+use url::Url;
+
+use crate::addon_transport::AddonBuilder;
+use crate::constants::{META_RESOURCE_NAME, WATCH_STATS_SERIES_COUNT};
+use crate::models::ctx::Ctx;
+use crate::models::watch_stats::{Selected, StatsPeriod, WatchStats};
+use crate::runtime::msg::{Action, ActionLoad};
+use crate::runtime::{EnvFutureExt, Runtime, RuntimeAction};
+use crate::types::addon::{Descriptor, Manifest, ResourceResponse};
+use crate::types::library::{LibraryBucket, LibraryItem, LibraryItemState};
+use crate::types::profile::Profile;
+use crate::types::resource::{MetaItem, MetaItemPreview, SeriesInfo, Stream, Video};
+use crate::types::streams::{StreamsBucket, StreamsItem, StreamsItemKey};
+use crate::types::taste::{TasteBucket, TasteItem};
+use crate::types::watched_bitfield::WatchedBitField;
+use crate::unit_tests::{TestEnv, ADDON_TRANSPORTS, NOW};
+
+const SERIES_ID: &str = "tt0903747";
+const MOVIE_ID: &str = "tt0111161";
+
+#[derive(Model, Default, Clone, Debug)]
+#[model(TestEnv)]
+struct TestModel {
+    ctx: Ctx,
+    watch_stats: WatchStats,
+}
+
+fn manifest() -> Manifest {
+    Manifest {
+        id: "series".to_owned(),
+        version: Version::new(1, 0, 0),
+        name: "series".to_owned(),
+        contact_email: None,
+        description: None,
+        logo: None,
+        background: None,
+        types: vec!["series".to_owned()],
+        resources: vec![META_RESOURCE_NAME.into()],
+        id_prefixes: Some(vec!["tt".to_owned()]),
+        catalogs: vec![],
+        addon_catalogs: vec![],
+        behavior_hints: Default::default(),
+    }
+}
+
+/// A special and 4 episodes
+fn videos() -> Vec<Video> {
+    [(0, 1), (1, 1), (1, 2), (1, 3), (1, 4)]
+        .into_iter()
+        .map(|(season, episode)| Video {
+            id: format!("{SERIES_ID}:{season}:{episode}"),
+            series_info: Some(SeriesInfo { season, episode }),
+            ..Default::default()
+        })
+        .collect()
+}
+
+fn register_addon() {
+    let addon = AddonBuilder::new(manifest())
+        .define_meta_handler(|_| {
+            future::ok(ResourceResponse::Meta {
+                meta: MetaItem {
+                    preview: MetaItemPreview {
+                        id: SERIES_ID.to_owned(),
+                        r#type: "series".to_owned(),
+                        name: "Breaking Bad".to_owned(),
+                        ..Default::default()
+                    },
+                    videos: videos(),
+                },
+            })
+            .boxed_env()
+        })
+        .build()
+        .unwrap();
+    let registry = ADDON_TRANSPORTS.read().unwrap().to_owned();
+    *ADDON_TRANSPORTS.write().unwrap() =
+        registry.register("series", move |_| Box::new(addon.to_owned()));
+}
+
+fn library_item(
+    id: &str,
+    name: &str,
+    r#type: &str,
+    last_watched: chrono::DateTime<Utc>,
+    state: LibraryItemState,
+) -> LibraryItem {
+    LibraryItem {
+        id: id.to_owned(),
+        name: name.to_owned(),
+        r#type: r#type.to_owned(),
+        poster: None,
+        poster_shape: Default::default(),
+        removed: false,
+        temp: false,
+        ctime: None,
+        mtime: last_watched,
+        state: LibraryItemState {
+            last_watched: Some(last_watched),
+            ..state
+        },
+        behavior_hints: Default::default(),
+    }
+}
+
+fn streams_item(video: &Video, mtime: chrono::DateTime<Utc>) -> (StreamsItemKey, StreamsItem) {
+    let transport_url = Url::parse("series://addon/manifest.json").unwrap();
+    (
+        StreamsItemKey {
+            meta_id: SERIES_ID.to_owned(),
+            video_id: video.id.to_owned(),
+        },
+        StreamsItem {
+            stream: Stream::youtube("yt_id:channel:video").unwrap(),
+            r#type: "series".to_owned(),
+            meta_id: SERIES_ID.to_owned(),
+            video_id: video.id.to_owned(),
+            meta_transport_url: transport_url.to_owned(),
+            stream_transport_url: transport_url,
+            state: None,
+            mtime,
+        },
+    )
+}
+
+#[test]
+fn watch_stats_year_in_review() {
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    register_addon();
+    // Monday
+    let monday = Utc.with_ymd_and_hms(2023, 10, 2, 20, 0, 0).unwrap();
+    *NOW.write().unwrap() = monday + Duration::days(2);
+    let video_ids = videos().into_iter().map(|video| video.id).collect();
+    let watched =
+        WatchedBitField::construct_from_array(vec![true, true, true, false, false], video_ids);
+    let library = LibraryBucket::new(
+        None,
+        vec![
+            library_item(
+                MOVIE_ID,
+                "The Shawshank Redemption",
+                "movie",
+                monday,
+                LibraryItemState {
+                    times_watched: 3,
+                    overall_time_watched: 3 * 8_520_000,
+                    ..Default::default()
+                },
+            ),
+            library_item(
+                SERIES_ID,
+                "Breaking Bad",
+                "series",
+                monday + Duration::days(2),
+                LibraryItemState {
+                    times_watched: 3,
+                    overall_time_watched: 3 * 2_820_000,
+                    watched: Some(watched.into()),
+                    ..Default::default()
+                },
+            ),
+            library_item(
+                "tt0068646",
+                "The Godfather",
+                "movie",
+                Utc.with_ymd_and_hms(2022, 12, 31, 20, 0, 0).unwrap(),
+                LibraryItemState {
+                    times_watched: 1,
+                    ..Default::default()
+                },
+            ),
+        ],
+    );
+    let streams = StreamsBucket {
+        uid: None,
+        items: [streams_item(&videos()[2], monday + Duration::days(1))].into(),
+    };
+    let taste = TasteBucket {
+        uid: None,
+        items: [(
+            MOVIE_ID.to_owned(),
+            TasteItem {
+                id: MOVIE_ID.to_owned(),
+                name: "The Shawshank Redemption".to_owned(),
+                r#type: "movie".to_owned(),
+                genres: vec!["Drama".to_owned()],
+                cast: vec![],
+                watched: true,
+                rating: None,
+                mtime: monday,
+            },
+        )]
+        .into(),
+    };
+    let (runtime, _rx) = Runtime::<TestEnv, _>::new(
+        TestModel {
+            ctx: Ctx {
+                profile: Profile {
+                    addons: vec![Descriptor {
+                        manifest: manifest(),
+                        transport_url: Url::parse("series://addon/manifest.json").unwrap(),
+                        flags: Default::default(),
+                    }],
+                    ..Default::default()
+                },
+                library,
+                streams,
+                taste,
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        vec![],
+        1000,
+    );
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Load(ActionLoad::WatchStats(Selected {
+                period: StatsPeriod::Year(2023),
+            })),
+        })
+    });
+    let model = runtime.model().unwrap();
+    let report = model.watch_stats.report.as_ref().expect("Report is ready");
+    assert_eq!(
+        report.totals.items, 2,
+        "Items of the other years are skipped"
+    );
+    assert_eq!(report.totals.times_watched, 6);
+    assert_eq!(report.by_type["movie"].time_watched, 3 * 8_520_000);
+    assert_eq!(report.by_genre["Drama"].items, 1);
+    assert_eq!(report.by_weekday[0].weekday, Weekday::Mon);
+    assert_eq!(report.by_weekday[0].totals.items, 1);
+    assert_eq!(report.by_weekday[2].totals.items, 1);
+    assert_eq!(report.by_month.len(), 1);
+    assert_eq!(report.by_month[0].month, 10);
+    assert_eq!(
+        (report.streaks.current, report.streaks.longest),
+        (3, 3),
+        "Played streams fill in the streak"
+    );
+    assert_eq!(
+        report
+            .most_rewatched
+            .iter()
+            .map(|item| (item.id.as_str(), item.rewatches))
+            .collect::<Vec<_>>(),
+        vec![(MOVIE_ID, 2), (SERIES_ID, 1)],
+        "A series is rewatched when it's watched more times than its watched episodes"
+    );
+    assert_eq!(report.series_completion.len(), 1);
+    assert_eq!(
+        (
+            report.series_completion[0].watched,
+            report.series_completion[0].total
+        ),
+        (2, 4),
+        "Specials are not counted"
+    );
+    assert_eq!(report.series_completion[0].percent, 50.0);
+}
+
+#[test]
+fn watch_stats_most_recently_watched_series() {
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    register_addon();
+    let now = Utc.with_ymd_and_hms(2023, 10, 2, 20, 0, 0).unwrap();
+    *NOW.write().unwrap() = now;
+    let video_ids = videos().into_iter().map(|video| video.id).collect();
+    let watched = WatchedBitField::construct_from_array(vec![true], video_ids);
+    let library = LibraryBucket::new(
+        None,
+        (0..=WATCH_STATS_SERIES_COUNT)
+            .map(|index| {
+                library_item(
+                    &format!("tt{index}"),
+                    &format!("Series {index}"),
+                    "series",
+                    now - Duration::days(index as i64),
+                    LibraryItemState {
+                        times_watched: 1,
+                        watched: Some(watched.to_owned().into()),
+                        ..Default::default()
+                    },
+                )
+            })
+            .collect(),
+    );
+    let (runtime, _rx) = Runtime::<TestEnv, _>::new(
+        TestModel {
+            ctx: Ctx {
+                profile: Profile {
+                    addons: vec![Descriptor {
+                        manifest: manifest(),
+                        transport_url: Url::parse("series://addon/manifest.json").unwrap(),
+                        flags: Default::default(),
+                    }],
+                    ..Default::default()
+                },
+                library,
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        vec![],
+        1000,
+    );
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Load(ActionLoad::WatchStats(Selected {
+                period: StatsPeriod::AllTime,
+            })),
+        })
+    });
+    let model = runtime.model().unwrap();
+    assert_eq!(
+        model.watch_stats.meta_items.len(),
+        WATCH_STATS_SERIES_COUNT,
+        "Meta items of the most recently watched series requested"
+    );
+    assert!(
+        model
+            .watch_stats
+            .meta_items
+            .iter()
+            .all(|meta_item| meta_item.request.path.id != format!("tt{WATCH_STATS_SERIES_COUNT}")),
+        "Meta item of the least recently watched series not requested"
+    );
+}
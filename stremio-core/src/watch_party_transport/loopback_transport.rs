@@ -0,0 +1,85 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+
+use futures::future;
+
+use crate::runtime::{EnvError, EnvFutureExt, TryEnvFuture};
+use crate::types::watch_party::{WatchPartyMemberId, WatchPartyMessage};
+use crate::watch_party_transport::WatchPartyTransport;
+
+type Inboxes = HashMap<(String, WatchPartyMemberId), VecDeque<WatchPartyMessage>>;
+
+/// An in-process relay for the members of the parties running in the same app, e.g. in tests.
+///
+/// A member receives the messages sent after its first [`LoopbackTransport`] call.
+///
+/// # Examples
+///
+/// ```
+/// use stremio_core::watch_party_transport::LoopbackRelay;
+///
+/// let relay = LoopbackRelay::default();
+/// let host = relay.transport("party", "host");
+/// let guest = relay.transport("party", "guest");
+/// ```
+#[derive(Clone, Default)]
+pub struct LoopbackRelay {
+    inboxes: Arc<Mutex<Inboxes>>,
+}
+
+impl LoopbackRelay {
+    pub fn transport(&self, party_id: &str, member_id: &str) -> LoopbackTransport {
+        LoopbackTransport {
+            relay: self.to_owned(),
+            party_id: party_id.to_owned(),
+            member_id: member_id.to_owned(),
+        }
+    }
+}
+
+/// The [`WatchPartyTransport`] of a member of the [`LoopbackRelay`].
+///
+/// The messages are delivered right away, before the returned futures are polled.
+#[derive(Clone)]
+pub struct LoopbackTransport {
+    relay: LoopbackRelay,
+    party_id: String,
+    member_id: WatchPartyMemberId,
+}
+
+impl LoopbackTransport {
+    fn with_inboxes<T>(&self, f: impl FnOnce(&mut Inboxes) -> T) -> Result<T, EnvError> {
+        let mut inboxes = self
+            .relay
+            .inboxes
+            .lock()
+            .map_err(|error| EnvError::WatchPartyTransport(error.to_string()))?;
+        inboxes
+            .entry((self.party_id.to_owned(), self.member_id.to_owned()))
+            .or_default();
+        Ok(f(&mut inboxes))
+    }
+}
+
+impl WatchPartyTransport for LoopbackTransport {
+    fn send(&self, message: &WatchPartyMessage) -> TryEnvFuture<()> {
+        let result = self.with_inboxes(|inboxes| {
+            inboxes
+                .iter_mut()
+                .filter(|((party_id, member_id), _)| {
+                    *party_id == self.party_id && *member_id != self.member_id
+                })
+                .for_each(|(_, inbox)| inbox.push_back(message.to_owned()));
+        });
+        future::ready(result).boxed_env()
+    }
+    fn receive(&self) -> TryEnvFuture<Vec<WatchPartyMessage>> {
+        let result = self.with_inboxes(|inboxes| {
+            inboxes
+                .get_mut(&(self.party_id.to_owned(), self.member_id.to_owned()))
+                .map(|inbox| inbox.drain(..).collect())
+                .unwrap_or_default()
+        });
+        future::ready(result).boxed_env()
+    }
+}
@@ -0,0 +1,8 @@
+mod watch_party_transport;
+pub use watch_party_transport::*;
+
+mod loopback_transport;
+pub use loopback_transport::*;
+
+mod unsupported_transport;
+pub use unsupported_transport::*;
@@ -0,0 +1,35 @@
+use futures::future;
+
+use crate::runtime::{ConditionalSend, EnvError, EnvFutureExt, TryEnvFuture};
+use crate::types::watch_party::WatchPartyMessage;
+use crate::watch_party_transport::WatchPartyTransport;
+
+pub struct UnsupportedWatchPartyTransport {
+    party_id: String,
+    member_id: String,
+}
+
+impl UnsupportedWatchPartyTransport {
+    pub fn new(party_id: &str, member_id: &str) -> Self {
+        UnsupportedWatchPartyTransport {
+            party_id: party_id.to_owned(),
+            member_id: member_id.to_owned(),
+        }
+    }
+    fn result<T: Sized + ConditionalSend + 'static>(&self) -> TryEnvFuture<T> {
+        future::err(EnvError::WatchPartyTransport(format!(
+            "Watch parties are not supported: {} in {}",
+            self.member_id, self.party_id
+        )))
+        .boxed_env()
+    }
+}
+
+impl WatchPartyTransport for UnsupportedWatchPartyTransport {
+    fn send(&self, _message: &WatchPartyMessage) -> TryEnvFuture<()> {
+        self.result::<()>()
+    }
+    fn receive(&self) -> TryEnvFuture<Vec<WatchPartyMessage>> {
+        self.result::<Vec<WatchPartyMessage>>()
+    }
+}
@@ -0,0 +1,11 @@
+use crate::runtime::TryEnvFuture;
+use crate::types::watch_party::WatchPartyMessage;
+
+/// Exchanges the [`WatchPartyMessage`]s of a member with the other members of the party,
+/// e.g. through a WebSocket relay.
+pub trait WatchPartyTransport {
+    /// Delivers the message to all the other members of the party.
+    fn send(&self, message: &WatchPartyMessage) -> TryEnvFuture<()>;
+    /// The messages received from the other members since the last call, in the order they were sent.
+    fn receive(&self) -> TryEnvFuture<Vec<WatchPartyMessage>>;
+}
@@ -21,7 +21,7 @@ use stremio_core::{
     models::{ctx::Ctx, streaming_server::StreamingServer},
     runtime::{
         msg::{Action, ActionCtx, Event},
-        Env, EnvError, EnvFuture, EnvFutureExt, TryEnvFuture,
+        Env, EnvError, EnvFuture, EnvFutureExt, ResponseHead, TryEnvFuture,
     },
     types::{api::AuthRequest, resource::StreamSource},
 };
@@ -267,12 +267,6 @@ impl WebEnv {
     pub fn clear_timeout(id: i32) {
         global().clear_timeout_with_handle(id);
     }
-
-    pub fn random_buffer(len: usize) -> Vec<u8> {
-        let mut buffer = vec![0u8; len];
-        getrandom::getrandom(buffer.as_mut_slice()).expect("generate random buffer failed");
-        buffer
-    }
 }
 
 impl Env for WebEnv {
@@ -390,6 +384,92 @@ impl Env for WebEnv {
         }
         .boxed_local()
     }
+    fn fetch_head(request: Request<()>) -> TryEnvFuture<ResponseHead> {
+        let url = request.uri().to_string();
+        let headers = {
+            let mut headers = HashMap::new();
+            for (key, value) in request.headers().iter() {
+                let key = key.as_str().to_owned();
+                let value = String::from_utf8_lossy(value.as_bytes()).into_owned();
+                headers.entry(key).or_insert_with(Vec::new).push(value);
+            }
+            <JsValue as JsValueSerdeExt>::from_serde(&headers)
+                .expect("WebEnv::fetch_head: JsValue from Headers failed to be built")
+        };
+        let mut request_options = web_sys::RequestInit::new();
+        request_options
+            .method(request.method().as_str())
+            .headers(&headers);
+        let request = web_sys::Request::new_with_str_and_init(&url, &request_options)
+            .expect("request builder failed");
+        let promise = global().fetch_with_request(&request);
+        async move {
+            let resp = JsFuture::from(promise).await.map_err(|error| {
+                EnvError::Fetch(
+                    error
+                        .dyn_into::<js_sys::Error>()
+                        .map(|error| String::from(error.message()))
+                        .unwrap_or_else(|_| UNKNOWN_ERROR.to_owned()),
+                )
+            })?;
+            let resp = resp
+                .dyn_into::<web_sys::Response>()
+                .expect("WebEnv::fetch_head: Response into web_sys::Response failed to be built");
+            // only the headers used for probing the streams are read
+            let headers = ["content-type", "content-length", "content-range"]
+                .into_iter()
+                .filter_map(|name| {
+                    resp.headers()
+                        .get(name)
+                        .ok()
+                        .flatten()
+                        .map(|value| (name.to_owned(), value))
+                })
+                .collect();
+            Ok(ResponseHead {
+                status: resp.status(),
+                headers,
+            })
+        }
+        .boxed_local()
+    }
+
+    fn fetch_bytes(request: Request<()>) -> TryEnvFuture<Vec<u8>> {
+        let url = request.uri().to_string();
+        let mut request_options = web_sys::RequestInit::new();
+        request_options.method(request.method().as_str());
+        let request = web_sys::Request::new_with_str_and_init(&url, &request_options)
+            .expect("request builder failed");
+        let promise = global().fetch_with_request(&request);
+        async move {
+            let to_fetch_error = |error: JsValue| {
+                EnvError::Fetch(
+                    error
+                        .dyn_into::<js_sys::Error>()
+                        .map(|error| String::from(error.message()))
+                        .unwrap_or_else(|_| UNKNOWN_ERROR.to_owned()),
+                )
+            };
+            let resp = JsFuture::from(promise).await.map_err(to_fetch_error)?;
+            let resp = resp
+                .dyn_into::<web_sys::Response>()
+                .expect("WebEnv::fetch_bytes: Response into web_sys::Response failed to be built");
+            if resp.status() != 200 {
+                return Err(EnvError::Fetch(format!(
+                    "Unexpected HTTP status code {}",
+                    resp.status(),
+                )));
+            }
+            let buffer = JsFuture::from(
+                resp.array_buffer()
+                    .expect("WebEnv::fetch_bytes: Response array buffer failed to be retrieved"),
+            )
+            .await
+            .map_err(to_fetch_error)?;
+            Ok(js_sys::Uint8Array::new(&buffer).to_vec())
+        }
+        .boxed_local()
+    }
 
     fn get_storage<T>(key: &str) -> TryEnvFuture<Option<T>>
     where
@@ -457,6 +537,28 @@ impl Env for WebEnv {
         spawn_local(future)
     }
 
+    fn sleep(duration: chrono::Duration) -> TryEnvFuture<()> {
+        let promise = js_sys::Promise::new(&mut |resolve, reject| {
+            if let Err(error) = global().set_timeout_with_callback_and_timeout_and_arguments_0(
+                &resolve,
+                i32::try_from(duration.num_milliseconds()).unwrap_or(i32::MAX),
+            ) {
+                reject.call1(&JsValue::undefined(), &error).ok();
+            }
+        });
+        JsFuture::from(promise)
+            .map_ok(|_| ())
+            .map_err(|error| {
+                EnvError::Other(
+                    error
+                        .dyn_into::<js_sys::Error>()
+                        .map(|error| String::from(error.message()))
+                        .unwrap_or_else(|_| "Set timeout failed".to_owned()),
+                )
+            })
+            .boxed_local()
+    }
+
     fn now() -> DateTime<Utc> {
         let msecs = js_sys::Date::now() as i64;
         let (secs, nsecs) = (msecs / 1000, msecs % 1000 * 1_000_000);
@@ -9,26 +9,30 @@ use serde::Serialize;
 use stremio_core::{
     models::{
         addon_details::AddonDetails,
+        addon_health::AddonHealth,
         calendar::Calendar,
         catalog_with_filters::CatalogWithFilters,
         catalogs_with_extra::CatalogsWithExtra,
         continue_watching_preview::ContinueWatchingPreview,
-        ctx::Ctx,
+        ctx::{Ctx, CtxBuckets},
         data_export::DataExport,
         installed_addons_with_filters::InstalledAddonsWithFilters,
+        library_transfer::LibraryTransfer,
         library_with_filters::{ContinueWatchingFilter, LibraryWithFilters, NotRemovedFilter},
         link::Link,
         local_search::LocalSearch,
+        local_subtitles::LocalSubtitles,
         meta_details::MetaDetails,
         player::Player,
         streaming_server::StreamingServer,
+        torrent_info::TorrentInfo,
+        watch_stats::WatchStats,
     },
-    runtime::Effects,
-    types::{
-        addon::Descriptor, api::LinkAuthKey, events::DismissedEventsBucket, library::LibraryBucket,
-        notifications::NotificationsBucket, profile::Profile, resource::MetaItemPreview,
-        search_history::SearchHistoryBucket, server_urls::ServerUrlsBucket, streams::StreamsBucket,
+    runtime::{
+        msg::{Action, ActionCtx, Msg},
+        Effects,
     },
+    types::{addon::Descriptor, api::LinkAuthKey, profile::Profile, resource::MetaItemPreview},
     Model,
 };
 
@@ -47,6 +51,8 @@ pub struct WebModel {
     pub discover: CatalogWithFilters<MetaItemPreview>,
     pub library: LibraryWithFilters<NotRemovedFilter>,
     pub continue_watching: LibraryWithFilters<ContinueWatchingFilter>,
+    pub library_transfer: LibraryTransfer,
+    pub watch_stats: WatchStats,
     pub calendar: Calendar,
     pub search: CatalogsWithExtra,
     /// Pre-loaded results for local search
@@ -55,43 +61,41 @@ pub struct WebModel {
     pub remote_addons: CatalogWithFilters<Descriptor>,
     pub installed_addons: InstalledAddonsWithFilters,
     pub addon_details: AddonDetails,
+    pub addon_health: AddonHealth,
     pub streaming_server: StreamingServer,
     pub player: Player,
+    pub local_subtitles: LocalSubtitles,
+    pub torrent_info: TorrentInfo,
 }
 
 impl WebModel {
-    pub fn new(
-        profile: Profile,
-        library: LibraryBucket,
-        streams: StreamsBucket,
-        server_urls: ServerUrlsBucket,
-        notifications: NotificationsBucket,
-        search_history: SearchHistoryBucket,
-        dismissed_events: DismissedEventsBucket,
-    ) -> (WebModel, Effects) {
+    pub fn new(profile: Profile, buckets: CtxBuckets) -> (WebModel, Effects) {
         let (continue_watching_preview, continue_watching_preview_effects) =
-            ContinueWatchingPreview::new(&library, &notifications);
+            ContinueWatchingPreview::new(&buckets.library, &buckets.notifications);
         let (discover, discover_effects) = CatalogWithFilters::<MetaItemPreview>::new(&profile);
-        let (library_, library_effects) =
-            LibraryWithFilters::<NotRemovedFilter>::new(&library, &notifications);
+        let (library_, library_effects) = LibraryWithFilters::<NotRemovedFilter>::new(
+            &buckets.library,
+            &buckets.lists,
+            &buckets.notifications,
+            &profile.parental_controls,
+        );
         let (continue_watching, continue_watching_effects) =
-            LibraryWithFilters::<ContinueWatchingFilter>::new(&library, &notifications);
+            LibraryWithFilters::<ContinueWatchingFilter>::new(
+                &buckets.library,
+                &buckets.lists,
+                &buckets.notifications,
+                &profile.parental_controls,
+            );
         let (remote_addons, remote_addons_effects) =
             CatalogWithFilters::<Descriptor>::new(&profile);
         let (installed_addons, installed_addons_effects) =
             InstalledAddonsWithFilters::new(&profile);
+        let (addon_health_, addon_health_effects) =
+            AddonHealth::new(&profile, &buckets.addon_health);
         let (streaming_server, streaming_server_effects) = StreamingServer::new::<WebEnv>(&profile);
         let (local_search, local_search_effects) = LocalSearch::new::<WebEnv>();
         let model = WebModel {
-            ctx: Ctx::new(
-                profile,
-                library,
-                streams,
-                server_urls,
-                notifications,
-                search_history,
-                dismissed_events,
-            ),
+            ctx: Ctx::new(profile, buckets),
             auth_link: Default::default(),
             data_export: Default::default(),
             local_search,
@@ -100,17 +104,22 @@ impl WebModel {
             discover,
             library: library_,
             continue_watching,
+            library_transfer: Default::default(),
+            watch_stats: Default::default(),
             calendar: Default::default(),
             search: Default::default(),
             meta_details: Default::default(),
             remote_addons,
             installed_addons,
             addon_details: Default::default(),
+            addon_health: addon_health_,
             streaming_server,
             player: Player {
                 collect_seek_logs: true,
                 ..Default::default()
             },
+            local_subtitles: Default::default(),
+            torrent_info: Default::default(),
         };
         (
             model,
@@ -120,8 +129,11 @@ impl WebModel {
                 .join(continue_watching_effects)
                 .join(remote_addons_effects)
                 .join(installed_addons_effects)
+                .join(addon_health_effects)
                 .join(streaming_server_effects)
-                .join(local_search_effects),
+                .join(local_search_effects)
+                // replay the changes which were not pushed to the API in the previous session
+                .join(Effects::msg(Msg::Action(Action::Ctx(ActionCtx::FlushOutbox))).unchanged()),
         )
     }
     pub fn get_state(&self, field: &WebModelField) -> JsValue {
@@ -161,6 +173,14 @@ impl WebModel {
                 self.streaming_server.base_url.as_ref(),
                 "continuewatching".to_owned(),
             ),
+            WebModelField::LibraryTransfer => {
+                <JsValue as JsValueSerdeExt>::from_serde(&self.library_transfer)
+                    .expect("JsValue from LibraryTransfer")
+            }
+            WebModelField::WatchStats => {
+                <JsValue as JsValueSerdeExt>::from_serde(&self.watch_stats)
+                    .expect("JsValue from WatchStats")
+            }
             WebModelField::Search => {
                 // let old = serialize_catalogs_with_extra(&self.search, &self.ctx)
                 crate::model::serialize_catalogs_with_extra::CatalogsWithExtra::new(
@@ -184,10 +204,22 @@ impl WebModel {
                 <JsValue as JsValueSerdeExt>::from_serde(&self.addon_details)
                     .expect("JsValue from AddonDetails")
             }
+            WebModelField::AddonHealth => {
+                <JsValue as JsValueSerdeExt>::from_serde(&self.addon_health)
+                    .expect("JsValue from AddonHealth")
+            }
             WebModelField::StreamingServer => serialize_streaming_server(&self.streaming_server),
             WebModelField::Player => {
                 serialize_player::<WebEnv>(&self.player, &self.ctx, &self.streaming_server)
             }
+            WebModelField::LocalSubtitles => {
+                <JsValue as JsValueSerdeExt>::from_serde(&self.local_subtitles)
+                    .expect("JsValue from LocalSubtitles")
+            }
+            WebModelField::TorrentInfo => {
+                <JsValue as JsValueSerdeExt>::from_serde(&self.torrent_info)
+                    .expect("JsValue from TorrentInfo")
+            }
         }
     }
 }
@@ -3,7 +3,7 @@ use itertools::Itertools;
 use serde::Serialize;
 use stremio_core::{
     deep_links::{CalendarDeepLinks, CalendarItemDeepLinks},
-    models::calendar::{FullDate, MonthInfo, Selected, YearMonthDate},
+    models::calendar::{FullDate, ICalendarExport, MonthInfo, Selected},
     types::resource::SeriesInfo,
 };
 use url::Url;
@@ -37,7 +37,7 @@ mod model {
     #[serde(rename_all = "camelCase")]
     pub struct SelectableDate<'a> {
         #[serde(flatten)]
-        pub date: &'a YearMonthDate,
+        pub selected: &'a Selected,
         pub deep_links: CalendarDeepLinks,
     }
 
@@ -55,6 +55,7 @@ mod model {
         pub selectable: Selectable<'a>,
         pub month_info: &'a MonthInfo,
         pub items: &'a Vec<CalendarItem<'a>>,
+        pub icalendar: &'a Option<ICalendarExport>,
     }
 }
 
@@ -64,13 +65,13 @@ pub fn serialize_calendar(calendar: &stremio_core::models::calendar::Calendar) -
         selected: &calendar.selected,
         selectable: model::Selectable {
             prev: model::SelectableDate {
-                date: &calendar.selectable.prev,
-                deep_links: CalendarDeepLinks::from(&calendar.selectable.prev)
+                selected: &calendar.selectable.prev,
+                deep_links: CalendarDeepLinks::from(&calendar.selectable.prev.date)
                     .into_web_deep_links(),
             },
             next: model::SelectableDate {
-                date: &calendar.selectable.next,
-                deep_links: CalendarDeepLinks::from(&calendar.selectable.next)
+                selected: &calendar.selectable.next,
+                deep_links: CalendarDeepLinks::from(&calendar.selectable.next.date)
                     .into_web_deep_links(),
             },
         },
@@ -96,6 +97,7 @@ pub fn serialize_calendar(calendar: &stremio_core::models::calendar::Calendar) -
                     .collect_vec(),
             })
             .collect_vec(),
+        icalendar: &calendar.icalendar,
     })
     .expect("JsValue from model::Calendar")
 }
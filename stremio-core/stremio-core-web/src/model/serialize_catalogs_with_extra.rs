@@ -9,7 +9,7 @@ use crate::model::deep_links_ext::DeepLinksExt;
 pub use stremio_core::{
     deep_links::{DiscoverDeepLinks, MetaItemDeepLinks, StreamDeepLinks},
     models::{catalogs_with_extra::Selected, common::Loadable, ctx::Ctx},
-    types::resource::PosterShape,
+    types::{addon::ResourceRequest, resource::PosterShape, taste::RecommendationReason},
 };
 
 #[derive(Serialize)]
@@ -42,6 +42,56 @@ pub struct MetaItemPreview<'a> {
     pub deep_links: MetaItemDeepLinks,
 }
 
+impl<'a> MetaItemPreview<'a> {
+    fn new(
+        meta_item: &'a stremio_core::types::resource::MetaItemPreview,
+        poster_shape: &'a PosterShape,
+        request: &ResourceRequest,
+        ctx: &Ctx,
+        streaming_server: &stremio_core::models::streaming_server::StreamingServer,
+    ) -> Self {
+        Self {
+            meta_item,
+            poster_shape,
+            trailer_streams: meta_item
+                .trailer_streams
+                .iter()
+                .take(1)
+                .map(|stream| Stream {
+                    stream,
+                    deep_links: StreamDeepLinks::from((
+                        stream,
+                        streaming_server.base_url.as_ref(),
+                        &ctx.profile.settings,
+                    ))
+                    .into_web_deep_links(),
+                })
+                .collect::<Vec<_>>(),
+            watched: ctx
+                .library
+                .items
+                .get(&meta_item.id)
+                .map(|library_item| library_item.watched())
+                .unwrap_or_default(),
+            in_library: ctx
+                .library
+                .items
+                .get(&meta_item.id)
+                .map(|library_item| !library_item.removed)
+                .unwrap_or_default(),
+            deep_links: MetaItemDeepLinks::from((meta_item, request)).into_web_deep_links(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Recommendation<'a> {
+    #[serde(flatten)]
+    pub meta_item: MetaItemPreview<'a>,
+    pub reason: &'a RecommendationReason,
+}
+
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ResourceLoadable<'a> {
@@ -58,6 +108,7 @@ pub struct ResourceLoadable<'a> {
 pub struct CatalogsWithExtra<'a> {
     pub selected: &'a Option<Selected>,
     pub catalogs: Vec<ResourceLoadable<'a>>,
+    pub recommendations: Vec<Recommendation<'a>>,
 }
 
 impl<'a> CatalogsWithExtra<'a> {
@@ -112,41 +163,14 @@ impl<'a> CatalogsWithExtra<'a> {
                                     .iter()
                                     .unique_by(|meta_item| &meta_item.id)
                                     .take(10)
-                                    .map(|meta_item| MetaItemPreview {
-                                        meta_item,
-                                        poster_shape: poster_shape
-                                            .unwrap_or(&meta_item.poster_shape),
-                                        trailer_streams: meta_item
-                                            .trailer_streams
-                                            .iter()
-                                            .take(1)
-                                            .map(|stream| Stream {
-                                                stream,
-                                                deep_links: StreamDeepLinks::from((
-                                                    stream,
-                                                    streaming_server.base_url.as_ref(),
-                                                    &ctx.profile.settings,
-                                                ))
-                                                .into_web_deep_links(),
-                                            })
-                                            .collect::<Vec<_>>(),
-                                        watched: ctx
-                                            .library
-                                            .items
-                                            .get(&meta_item.id)
-                                            .map(|library_item| library_item.watched())
-                                            .unwrap_or_default(),
-                                        in_library: ctx
-                                            .library
-                                            .items
-                                            .get(&meta_item.id)
-                                            .map(|library_item| !library_item.removed)
-                                            .unwrap_or_default(),
-                                        deep_links: MetaItemDeepLinks::from((
+                                    .map(|meta_item| {
+                                        MetaItemPreview::new(
                                             meta_item,
+                                            poster_shape.unwrap_or(&meta_item.poster_shape),
                                             &catalog.request,
-                                        ))
-                                        .into_web_deep_links(),
+                                            ctx,
+                                            streaming_server,
+                                        )
                                     })
                                     .collect::<Vec<_>>(),
                             ))
@@ -158,6 +182,20 @@ impl<'a> CatalogsWithExtra<'a> {
                     deep_links: DiscoverDeepLinks::from(&catalog.request).into_web_deep_links(),
                 })
                 .collect::<Vec<_>>(),
+            recommendations: catalogs_with_extra
+                .recommendations
+                .iter()
+                .map(|recommendation| Recommendation {
+                    meta_item: MetaItemPreview::new(
+                        &recommendation.meta_item,
+                        &recommendation.meta_item.poster_shape,
+                        &recommendation.request,
+                        ctx,
+                        streaming_server,
+                    ),
+                    reason: &recommendation.reason,
+                })
+                .collect::<Vec<_>>(),
         }
     }
 }
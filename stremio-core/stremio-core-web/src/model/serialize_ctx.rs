@@ -17,7 +17,12 @@ mod model {
 
     use stremio_core::deep_links::SearchHistoryItemDeepLinks;
     use stremio_core::types::{
-        events::Events, notifications::NotificationItem, resource::MetaItemId,
+        events::Events,
+        library::LibrarySyncConflict,
+        lists::{ListId, ListItem, SharedList},
+        local_profiles::LocalProfileId,
+        notifications::NotificationItem,
+        resource::MetaItemId,
     };
     use url::Url;
 
@@ -31,6 +36,33 @@ mod model {
         pub search_history: Vec<SearchHistoryItem<'a>>,
         pub events: &'a Events,
         pub streaming_server_urls: Vec<StreamingServerUrlItem>,
+        pub library_conflicts: &'a Vec<LibrarySyncConflict>,
+        pub local_profiles: LocalProfiles<'a>,
+        pub lists: Vec<List<'a>>,
+    }
+
+    #[derive(Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct List<'a> {
+        pub id: &'a ListId,
+        pub name: &'a String,
+        pub items: &'a Vec<ListItem>,
+        /// The list as it is shared with other users
+        pub share: SharedList,
+    }
+
+    #[derive(Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct LocalProfiles<'a> {
+        pub selected: &'a LocalProfileId,
+        pub profiles: Vec<LocalProfileItem<'a>>,
+    }
+
+    #[derive(Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct LocalProfileItem<'a> {
+        pub id: &'a LocalProfileId,
+        pub name: &'a String,
     }
 
     #[derive(Serialize)]
@@ -125,6 +157,30 @@ mod model {
                     })
                     .sorted_by(|a, b| Ord::cmp(&a.mtime, &b.mtime))
                     .collect(),
+                library_conflicts: &ctx.library_sync.conflicts,
+                local_profiles: LocalProfiles {
+                    selected: &ctx.local_profiles.selected,
+                    profiles: ctx
+                        .local_profiles
+                        .profiles
+                        .iter()
+                        .map(|local_profile| LocalProfileItem {
+                            id: &local_profile.id,
+                            name: &local_profile.name,
+                        })
+                        .collect(),
+                },
+                lists: ctx
+                    .lists
+                    .ordered()
+                    .into_iter()
+                    .map(|list| List {
+                        id: &list.id,
+                        name: &list.name,
+                        items: &list.items,
+                        share: SharedList::from(list),
+                    })
+                    .collect(),
             }
         }
     }
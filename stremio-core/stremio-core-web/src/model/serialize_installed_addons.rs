@@ -81,6 +81,8 @@ pub fn serialize_installed_addons(
                 deep_links: AddonsDeepLinks::from(
                     &stremio_core::models::installed_addons_with_filters::InstalledAddonsRequest {
                         r#type: None,
+                        sort: Default::default(),
+                        health: None,
                     },
                 )
                 .into_web_deep_links(),
@@ -5,6 +5,7 @@ use serde::Serialize;
 use stremio_core::deep_links::{LibraryDeepLinks, LibraryItemDeepLinks};
 use stremio_core::models::ctx::Ctx;
 use stremio_core::models::library_with_filters::{LibraryWithFilters, Selected, Sort};
+use stremio_core::types::lists::ListId;
 use stremio_core::types::resource::PosterShape;
 use stremio_core::types::streams::StreamsItemKey;
 use url::Url;
@@ -36,6 +37,14 @@ mod model {
     }
     #[derive(Serialize)]
     #[serde(rename_all = "camelCase")]
+    pub struct SelectableList<'a> {
+        pub list: &'a Option<ListId>,
+        pub name: &'a Option<String>,
+        pub selected: &'a bool,
+        pub deep_links: LibraryDeepLinks,
+    }
+    #[derive(Serialize)]
+    #[serde(rename_all = "camelCase")]
     pub struct SelectableSort<'a> {
         pub sort: &'a Sort,
         pub selected: &'a bool,
@@ -50,6 +59,7 @@ mod model {
     #[serde(rename_all = "camelCase")]
     pub struct Selectable<'a> {
         pub types: Vec<SelectableType<'a>>,
+        pub lists: Vec<SelectableList<'a>>,
         pub sorts: Vec<SelectableSort<'a>>,
         pub next_page: bool,
     }
@@ -82,6 +92,18 @@ pub fn serialize_library<F>(
                         .into_web_deep_links(),
                 })
                 .collect(),
+            lists: library
+                .selectable
+                .lists
+                .iter()
+                .map(|selectable_list| model::SelectableList {
+                    list: &selectable_list.list,
+                    name: &selectable_list.name,
+                    selected: &selectable_list.selected,
+                    deep_links: LibraryDeepLinks::from((&root, &selectable_list.request))
+                        .into_web_deep_links(),
+                })
+                .collect(),
             sorts: library
                 .selectable
                 .sorts
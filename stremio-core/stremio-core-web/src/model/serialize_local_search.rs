@@ -6,7 +6,7 @@ use serde::Serialize;
 use wasm_bindgen::JsValue;
 
 use stremio_core::deep_links::LocalSearchItemDeepLinks;
-use stremio_core::models::local_search::{LocalSearch, Searchable};
+use stremio_core::models::local_search::{LocalSearch, Searchable, SearchableSource};
 
 use crate::model::deep_links_ext::DeepLinksExt;
 
@@ -24,6 +24,7 @@ mod model {
     #[serde(rename_all = "camelCase")]
     pub struct LocalSearchItem<'a> {
         pub query: &'a String,
+        pub source: &'a SearchableSource,
         pub deep_links: LocalSearchItemDeepLinks,
     }
 }
@@ -34,8 +35,9 @@ pub fn serialize_local_search(local_search: &LocalSearch) -> JsValue {
             .search_results
             .to_owned()
             .iter()
-            .map(|Searchable { name, .. }| model::LocalSearchItem {
+            .map(|Searchable { name, source, .. }| model::LocalSearchItem {
                 query: name,
+                source,
                 deep_links: LocalSearchItemDeepLinks::from(name).into_web_deep_links(),
             })
             .unique_by(|i| i.query)
@@ -53,6 +53,16 @@ mod model {
     }
     #[derive(Serialize)]
     #[serde(rename_all = "camelCase")]
+    pub struct RankedStream<'a> {
+        #[serde(flatten)]
+        pub stream: Stream<'a>,
+        pub info: &'a stremio_core::types::resource::StreamInfo,
+        pub score: i64,
+        pub health: Option<stremio_core::types::stream_probes::StreamHealth>,
+        pub addon: DescriptorPreview<'a>,
+    }
+    #[derive(Serialize)]
+    #[serde(rename_all = "camelCase")]
     pub struct Video<'a> {
         #[serde(flatten)]
         pub video: &'a stremio_core::types::resource::Video,
@@ -95,6 +105,8 @@ mod model {
         pub meta_item: Option<ResourceLoadable<'a, MetaItem<'a>>>,
         pub library_item: &'a Option<LibraryItem>,
         pub streams: Vec<ResourceLoadable<'a, Vec<Stream<'a>>>>,
+        /// The streams of all addons merged and ranked by the stream preferences
+        pub ranked_streams: Vec<RankedStream<'a>>,
         pub meta_extensions: Vec<MetaExtension<'a>>,
         pub title: Option<String>,
         pub rating_info: &'a Option<Loadable<RatingInfo, EnvError>>,
@@ -317,6 +329,55 @@ pub fn serialize_meta_details<E: Env + 'static>(
                 },
             })
             .collect::<Vec<_>>(),
+        ranked_streams: meta_details
+            .ranked_streams
+            .iter()
+            .filter_map(|ranked_stream| {
+                ctx.profile
+                    .addons
+                    .iter()
+                    .find(|addon| addon.transport_url == ranked_stream.request.base)
+                    .map(|addon| (ranked_stream, addon))
+            })
+            .map(|(ranked_stream, addon)| model::RankedStream {
+                stream: model::Stream {
+                    stream: &ranked_stream.stream,
+                    progress: None,
+                    deep_links: meta_item
+                        .map_or_else(
+                            || {
+                                StreamDeepLinks::from((
+                                    &ranked_stream.stream,
+                                    streaming_server.base_url.as_ref(),
+                                    &ctx.profile.settings,
+                                ))
+                            },
+                            |meta_item| {
+                                StreamDeepLinks::from((
+                                    &ranked_stream.stream,
+                                    &ranked_stream.request,
+                                    &meta_item.request,
+                                    streaming_server.base_url.as_ref(),
+                                    &ctx.profile.settings,
+                                ))
+                            },
+                        )
+                        .into_web_deep_links(),
+                    last_used: None,
+                },
+                info: &ranked_stream.info,
+                score: ranked_stream.score,
+                health: ranked_stream.health,
+                addon: model::DescriptorPreview {
+                    transport_url: &addon.transport_url,
+                    manifest: model::ManifestPreview {
+                        id: &addon.manifest.id,
+                        name: &addon.manifest.name,
+                        logo: &addon.manifest.logo,
+                    },
+                },
+            })
+            .collect::<Vec<_>>(),
         meta_extensions: meta_details
             .meta_items
             .iter()
@@ -5,9 +5,11 @@ use url::Url;
 use {gloo_utils::format::JsValueSerdeExt, wasm_bindgen::JsValue};
 
 use stremio_core::deep_links::{StreamDeepLinks, VideoDeepLinks};
-use stremio_core::models::common::{Loadable, ResourceError, ResourceLoadable};
+use stremio_core::models::common::{
+    DefaultSubtitles, Loadable, RankedSubtitles, ResourceError, ResourceLoadable,
+};
 use stremio_core::models::ctx::Ctx;
-use stremio_core::models::player::Player;
+use stremio_core::models::player::{NextStreamMatch, Player, SubtitlesSyncProposal};
 use stremio_core::models::streaming_server::StreamingServer;
 use stremio_core::types::{
     addon::{ResourcePath, ResourceRequest},
@@ -108,12 +110,16 @@ mod model {
         pub stream: Option<Loadable<Stream<ConvertedStreamSource>, &'a EnvError>>,
         pub meta_item: Option<Loadable<model::MetaItem<'a>, &'a ResourceError>>,
         pub subtitles: Vec<model::Subtitles<'a>>,
+        pub ranked_subtitles: &'a [RankedSubtitles],
+        pub default_subtitles: Option<&'a DefaultSubtitles>,
         pub next_video: Option<Video<'a>>,
+        pub next_stream_match: Option<&'a NextStreamMatch>,
         pub series_info: Option<&'a stremio_core::types::resource::SeriesInfo>,
         pub library_item: Option<LibraryItem<'a>>,
         pub stream_state: Option<&'a StreamItemState>,
         #[serde(skip_serializing_if = "Option::is_none")]
         pub intro_outro: Option<&'a stremio_core::types::player::IntroOutro>,
+        pub subtitles_sync: Option<&'a SubtitlesSyncProposal>,
         pub title: Option<String>,
         pub addon: Option<model::DescriptorPreview<'a>>,
     }
@@ -238,6 +244,8 @@ pub fn serialize_player<E: stremio_core::runtime::Env + 'static>(
                     })
             })
             .collect(),
+        ranked_subtitles: &player.ranked_subtitles,
+        default_subtitles: player.default_subtitles.as_ref(),
         next_video: player
             .selected
             .as_ref()
@@ -314,6 +322,7 @@ pub fn serialize_player<E: stremio_core::runtime::Env + 'static>(
                 ))
                 .into_web_deep_links(),
             }),
+        next_stream_match: player.next_stream_match.as_ref(),
         series_info: player.series_info.as_ref(),
         library_item: player
             .library_item
@@ -327,6 +336,7 @@ pub fn serialize_player<E: stremio_core::runtime::Env + 'static>(
             }),
         stream_state: player.stream_state.as_ref(),
         intro_outro: player.intro_outro.as_ref(),
+        subtitles_sync: player.subtitles_sync.as_ref(),
         title: player.selected.as_ref().and_then(|selected| {
             player
                 .meta_item
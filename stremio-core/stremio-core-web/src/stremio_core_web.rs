@@ -10,21 +10,28 @@ use wasm_bindgen::{prelude::wasm_bindgen, JsValue, UnwrapThrowExt};
 
 use stremio_core::{
     constants::{
-        DISMISSED_EVENTS_STORAGE_KEY, LIBRARY_RECENT_STORAGE_KEY, LIBRARY_STORAGE_KEY,
-        NOTIFICATIONS_STORAGE_KEY, PROFILE_STORAGE_KEY, SEARCH_HISTORY_STORAGE_KEY,
-        STREAMING_SERVER_URLS_STORAGE_KEY, STREAMS_STORAGE_KEY,
+        ADDON_HEALTH_STORAGE_KEY, DISMISSED_EVENTS_STORAGE_KEY, LIBRARY_RECENT_STORAGE_KEY,
+        LIBRARY_STORAGE_KEY, LIBRARY_SYNC_STORAGE_KEY, LOCAL_PROFILES_STORAGE_KEY,
+        NOTIFICATIONS_STORAGE_KEY, OUTBOX_STORAGE_KEY, PROFILE_STORAGE_KEY,
+        SEARCH_HISTORY_STORAGE_KEY, STREAMING_SERVER_URLS_STORAGE_KEY, STREAMS_STORAGE_KEY,
+        TASTE_STORAGE_KEY,
     },
-    models::common::Loadable,
+    models::{common::Loadable, ctx::CtxBuckets},
     runtime::{msg::Action, Env, EnvError, Runtime, RuntimeAction, RuntimeEvent},
     types::{
+        addon_health::AddonHealthBucket,
         events::DismissedEventsBucket,
-        library::LibraryBucket,
+        library::{LibraryBucket, LibrarySyncBucket},
+        lists::ListsBucket,
+        local_profiles::LocalProfilesBucket,
         notifications::NotificationsBucket,
+        outbox::OutboxBucket,
         profile::Profile,
         resource::{Stream, StreamSource},
         search_history::SearchHistoryBucket,
         server_urls::ServerUrlsBucket,
         streams::StreamsBucket,
+        taste::TasteBucket,
     },
 };
 
@@ -68,16 +75,40 @@ pub async fn initialize_runtime(emit_to_ui: js_sys::Function) -> Result<(), JsVa
     let env_init_result = WebEnv::init().await;
     match env_init_result {
         Ok(_) => {
-            let storage_result = try_join!(
-                WebEnv::get_storage::<Profile>(PROFILE_STORAGE_KEY),
-                WebEnv::get_storage::<LibraryBucket>(LIBRARY_RECENT_STORAGE_KEY),
-                WebEnv::get_storage::<LibraryBucket>(LIBRARY_STORAGE_KEY),
-                WebEnv::get_storage::<StreamsBucket>(STREAMS_STORAGE_KEY),
-                WebEnv::get_storage::<ServerUrlsBucket>(STREAMING_SERVER_URLS_STORAGE_KEY),
-                WebEnv::get_storage::<NotificationsBucket>(NOTIFICATIONS_STORAGE_KEY),
-                WebEnv::get_storage::<SearchHistoryBucket>(SEARCH_HISTORY_STORAGE_KEY),
-                WebEnv::get_storage::<DismissedEventsBucket>(DISMISSED_EVENTS_STORAGE_KEY),
-            );
+            // the library, streams, search history and lists are stored per local profile
+            let storage_result = async {
+                let local_profiles =
+                    WebEnv::get_storage::<LocalProfilesBucket>(LOCAL_PROFILES_STORAGE_KEY)
+                        .await?
+                        .unwrap_or_default();
+                try_join!(
+                    WebEnv::get_storage::<Profile>(PROFILE_STORAGE_KEY),
+                    WebEnv::get_storage::<LibraryBucket>(
+                        &local_profiles.storage_key(LIBRARY_RECENT_STORAGE_KEY)
+                    ),
+                    WebEnv::get_storage::<LibraryBucket>(
+                        &local_profiles.storage_key(LIBRARY_STORAGE_KEY)
+                    ),
+                    WebEnv::get_storage::<StreamsBucket>(
+                        &local_profiles.storage_key(STREAMS_STORAGE_KEY)
+                    ),
+                    WebEnv::get_storage::<ServerUrlsBucket>(STREAMING_SERVER_URLS_STORAGE_KEY),
+                    WebEnv::get_storage::<NotificationsBucket>(NOTIFICATIONS_STORAGE_KEY),
+                    WebEnv::get_storage::<SearchHistoryBucket>(
+                        &local_profiles.storage_key(SEARCH_HISTORY_STORAGE_KEY)
+                    ),
+                    WebEnv::get_storage::<DismissedEventsBucket>(DISMISSED_EVENTS_STORAGE_KEY),
+                    WebEnv::get_storage::<LibrarySyncBucket>(LIBRARY_SYNC_STORAGE_KEY),
+                    WebEnv::get_storage::<OutboxBucket>(OUTBOX_STORAGE_KEY),
+                    future::ok::<_, EnvError>(local_profiles),
+                    WebEnv::get_storage::<AddonHealthBucket>(ADDON_HEALTH_STORAGE_KEY),
+                    WebEnv::get_storage::<TasteBucket>(TASTE_STORAGE_KEY),
+                    WebEnv::get_storage::<ListsBucket>(
+                        &local_profiles.storage_key(LISTS_STORAGE_KEY)
+                    ),
+                )
+            }
+            .await;
             match storage_result {
                 Ok((
                     profile,
@@ -88,6 +119,12 @@ pub async fn initialize_runtime(emit_to_ui: js_sys::Function) -> Result<(), JsVa
                     notifications_bucket,
                     search_history_bucket,
                     dismissed_events_bucket,
+                    library_sync_bucket,
+                    outbox_bucket,
+                    local_profiles_bucket,
+                    addon_health_bucket,
+                    taste_bucket,
+                    lists_bucket,
                 )) => {
                     let profile = profile.unwrap_or_default();
                     let mut library = LibraryBucket::new(profile.uid(), vec![]);
@@ -108,14 +145,34 @@ pub async fn initialize_runtime(emit_to_ui: js_sys::Function) -> Result<(), JsVa
                         search_history_bucket.unwrap_or(SearchHistoryBucket::new(profile.uid()));
                     let dismissed_events_bucket = dismissed_events_bucket
                         .unwrap_or(DismissedEventsBucket::new(profile.uid()));
+                    let library_sync_bucket = library_sync_bucket
+                        .filter(|bucket| bucket.uid == profile.uid())
+                        .unwrap_or_else(|| LibrarySyncBucket::new(profile.uid(), vec![]));
+                    let outbox_bucket = outbox_bucket
+                        .filter(|bucket| bucket.uid == profile.uid())
+                        .unwrap_or_else(|| OutboxBucket::new(profile.uid()));
+                    let taste_bucket = taste_bucket
+                        .filter(|bucket| bucket.uid == profile.uid())
+                        .unwrap_or_else(|| TasteBucket::new(profile.uid()));
+                    let lists_bucket = lists_bucket
+                        .filter(|bucket| bucket.uid == profile.uid())
+                        .unwrap_or_else(|| ListsBucket::new(profile.uid()));
                     let (model, effects) = WebModel::new(
                         profile,
-                        library,
-                        streams_bucket,
-                        server_urls_bucket,
-                        notifications_bucket,
-                        search_history_bucket,
-                        dismissed_events_bucket,
+                        CtxBuckets {
+                            library,
+                            streams: streams_bucket,
+                            streaming_server_urls: server_urls_bucket,
+                            notifications: notifications_bucket,
+                            search_history: search_history_bucket,
+                            dismissed_events: dismissed_events_bucket,
+                            library_sync: library_sync_bucket,
+                            outbox: outbox_bucket,
+                            local_profiles: local_profiles_bucket,
+                            addon_health: addon_health_bucket.unwrap_or_default(),
+                            taste: taste_bucket,
+                            lists: lists_bucket,
+                        },
                     );
                     let (runtime, rx) = Runtime::<WebEnv, _>::new(
                         model,
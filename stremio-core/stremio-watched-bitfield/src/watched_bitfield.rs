@@ -47,6 +47,54 @@ impl Display for WatchedField {
     }
 }
 
+impl WatchedField {
+    /// Merges the watched videos of both fields.
+    ///
+    /// The fields are aligned by their anchor video when it's the same,
+    /// otherwise both bitfields are assumed to start from the same video.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stremio_watched_bitfield::WatchedField;
+    ///
+    /// // first and third video watched
+    /// let first = "tt2934286:1:3:3:eJxjBQAABgAG".parse::<WatchedField>().unwrap();
+    /// // second video watched
+    /// let second = "tt2934286:1:2:2:eJxjAgAAAwAD".parse::<WatchedField>().unwrap();
+    ///
+    /// assert_eq!(first.union(&second).to_string(), "tt2934286:1:3:3:eJxjBwAACAAI");
+    /// ```
+    pub fn union(&self, other: &WatchedField) -> WatchedField {
+        let shift = if self.anchor_video == other.anchor_video {
+            self.anchor_length as i64 - other.anchor_length as i64
+        } else {
+            0
+        };
+        let mut bitfield = self.bitfield.clone();
+        for i in 0..other.bitfield.values.len() * 8 {
+            let idx = i as i64 + shift;
+            if other.bitfield.get(i) && idx >= 0 {
+                bitfield.set(idx as usize, true);
+            }
+        }
+        let (anchor_video, anchor_length) =
+            if other.anchor_length as i64 + shift > self.anchor_length as i64 {
+                (
+                    other.anchor_video.to_owned(),
+                    (other.anchor_length as i64 + shift) as usize,
+                )
+            } else {
+                (self.anchor_video.to_owned(), self.anchor_length)
+            };
+        WatchedField {
+            anchor_video,
+            anchor_length,
+            bitfield,
+        }
+    }
+}
+
 impl From<WatchedBitField> for WatchedField {
     fn from(watched_bit_field: WatchedBitField) -> Self {
         let last_id = watched_bit_field.bitfield.last_index_of(true).unwrap_or(0);
@@ -327,6 +375,52 @@ mod tests {
         }
     }
 
+    #[test]
+    fn watched_field_union() {
+        let empty = |videos: &[String]| {
+            WatchedBitField::new(
+                BitField8::new_with_values(vec![], Some(videos.len())),
+                videos.to_vec(),
+            )
+        };
+        let videos = (1..=9)
+            .map(|episode| format!("tt2934286:1:{episode}"))
+            .collect::<Vec<_>>();
+        let mut first = empty(&videos);
+        first.set_video("tt2934286:1:1", true);
+        first.set_video("tt2934286:1:5", true);
+        let mut second = empty(&videos);
+        second.set_video("tt2934286:1:3", true);
+        second.set_video("tt2934286:1:7", true);
+
+        let union = WatchedField::from(first).union(&WatchedField::from(second));
+        let union = WatchedBitField::construct_with_videos(union, videos.to_owned()).unwrap();
+        for (idx, video) in videos.iter().enumerate() {
+            assert_eq!(
+                union.get_video(video),
+                [0, 2, 4, 6].contains(&idx),
+                "{video}"
+            );
+        }
+
+        // a video was prepended to the list on one of the devices
+        let shifted_videos = [vec!["tt2934286:0:1".to_owned()], videos.to_owned()].concat();
+        let mut first = empty(&videos);
+        first.set_video("tt2934286:1:2", true);
+        first.set_video("tt2934286:1:5", true);
+        let mut second = empty(&shifted_videos);
+        second.set_video("tt2934286:1:3", true);
+        second.set_video("tt2934286:1:5", true);
+
+        let union = WatchedField::from(second).union(&WatchedField::from(first));
+        let union = WatchedBitField::construct_with_videos(union, shifted_videos).unwrap();
+        assert!(!union.get_video("tt2934286:0:1"));
+        assert!(union.get_video("tt2934286:1:2"));
+        assert!(union.get_video("tt2934286:1:3"));
+        assert!(union.get_video("tt2934286:1:5"));
+        assert!(!union.get_video("tt2934286:1:4"));
+    }
+
     #[test]
     fn to_string_empty() {
         let watched = WatchedBitField::construct_from_array(vec![], vec![]);